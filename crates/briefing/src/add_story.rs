@@ -0,0 +1,184 @@
+//! `briefing add-story`: fetch one article and add it to a story file.
+//!
+//! For news that breaks after collection ran. The article is fetched and
+//! summarized like a bookmark would be, filed under a topic picked by
+//! number or a new topic's name (asked for unless `--topic` says), and the
+//! org draft is regenerated.
+
+use anyhow::{bail, Result};
+use shared::clustering::StoryDetails;
+use shared::org_template::OrgTemplate;
+use shared::prompts::{Prompt, PromptKind};
+use shared::settings::Settings;
+use shared::{BriefingData, ClaudeSummarizer, ContentExtractor, ExtractionResult, Story, Topic};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+pub struct Args {
+    pub url: String,
+    pub file: Option<PathBuf>,
+    pub topic: Option<String>,
+    pub title: Option<String>,
+    pub star: bool,
+}
+
+pub async fn run(args: Args) -> Result<()> {
+    let path = match args.file {
+        Some(path) => path,
+        None => crate::review::latest_story_file()?,
+    };
+    let mut data = shared::load_stories(&path)?;
+    if let Some(topic) = data
+        .topics
+        .iter()
+        .find(|topic| topic.stories.iter().any(|story| story.url == args.url))
+    {
+        bail!(
+            "{} is already in {} under \"{}\"",
+            args.url,
+            path.display(),
+            topic.title
+        );
+    }
+    let mut settings = Settings::load()?;
+    settings.apply_show(&data.show.slug);
+    settings.briefing.org_template = OrgTemplate::load(&data.show.slug)?;
+
+    println!("🔗 Fetching {}...", args.url);
+    let extractor = ContentExtractor::new()?.with_fetch_settings(settings.fetch.clone());
+    let result = extractor.fetch_article_content(&args.url).await;
+    collect_stories::remember_extractions(&[(args.url.clone(), result.clone())]);
+    let content = match result {
+        ExtractionResult::Success(content) => content,
+        ExtractionResult::Paywalled => bail!("the article is paywalled"),
+        ExtractionResult::Skipped => bail!("a domain rule or robots.txt skips this site"),
+        ExtractionResult::OutOfTime => bail!("the fetch time budget ran out"),
+        ExtractionResult::Failed(reason) => bail!("fetch failed: {}", reason),
+    };
+    let Some(title) = args.title.or_else(|| content.title.clone()) else {
+        bail!("the page has no title; give one with --title");
+    };
+    println!("✓ {}", title);
+
+    let summary = if shared::llm::credentials_available() {
+        let prompt = Prompt::load(PromptKind::Summarize, &data.show, &settings.prompts)?;
+        ClaudeSummarizer::new()?
+            .with_prompt(prompt)
+            .with_length(settings.prompts.summary_length(&data.show))
+            .summarize_story(&title, &content.text)
+            .await?
+    } else {
+        shared::summarizer::extractive_summary(&content.text)
+    };
+
+    let topic = match args.topic {
+        Some(topic) => topic,
+        None => ask_topic(&data)?,
+    };
+    let story = Story {
+        title,
+        url: args.url.clone(),
+        created: content
+            .published_date
+            .clone()
+            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+        summary,
+        details: Some(StoryDetails::from_article(&args.url, &content)),
+        starred: args.star,
+        ..Default::default()
+    };
+    let topic = insert(&mut data, story, &topic)?;
+    println!("✓ Added to \"{}\"", topic);
+    crate::retry::save(&path, &data, &settings)
+}
+
+/// Ask which topic the story goes under: a number from the list or a new
+/// topic's name.
+fn ask_topic(data: &BriefingData) -> Result<String> {
+    println!();
+    for (i, topic) in data.topics.iter().enumerate() {
+        println!("  {}. {} ({})", i + 1, topic.title, topic.stories.len());
+    }
+    print!("Topic (number, or a name for a new one): ");
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    let topic = line.trim();
+    if topic.is_empty() {
+        bail!("no topic chosen; nothing added");
+    }
+    Ok(topic.to_string())
+}
+
+/// Add `story` to the topic numbered `topic` (from 1), the one titled
+/// `topic`, or a new topic of that title at the end. Returns the topic's
+/// title.
+fn insert(data: &mut BriefingData, story: Story, topic: &str) -> Result<String> {
+    let index = match topic.parse::<usize>() {
+        Ok(n) if (1..=data.topics.len()).contains(&n) => n - 1,
+        Ok(n) => bail!("no topic {}; there are {}", n, data.topics.len()),
+        Err(_) => match data
+            .topics
+            .iter()
+            .position(|t| t.title.eq_ignore_ascii_case(topic))
+        {
+            Some(index) => index,
+            None => {
+                data.topics.push(Topic {
+                    title: topic.to_string(),
+                    stories: Vec::new(),
+                    connections: None,
+                    notes: None,
+                    rationale: None,
+                });
+                data.topics.len() - 1
+            }
+        },
+    };
+    let topic = &mut data.topics[index];
+    topic.stories.push(story);
+    Ok(topic.title.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::testing::story;
+    use shared::ShowInfo;
+
+    fn topic(title: &str) -> Topic {
+        Topic {
+            title: title.to_string(),
+            stories: vec![story("first").build()],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_by_number_name_or_new_topic() {
+        let mut data = BriefingData::new(
+            ShowInfo::new("This Week in Tech", "twit", "TWiT"),
+            vec![topic("Apple"), topic("AI")],
+        );
+
+        assert_eq!(insert(&mut data, story("a").build(), "2").unwrap(), "AI");
+        assert_eq!(
+            insert(&mut data, story("b").build(), "apple").unwrap(),
+            "Apple"
+        );
+        assert_eq!(
+            insert(&mut data, story("c").build(), "Space").unwrap(),
+            "Space"
+        );
+        assert!(insert(&mut data, story("d").build(), "4").is_err());
+
+        let titles: Vec<Vec<&str>> = data
+            .topics
+            .iter()
+            .map(|t| t.stories.iter().map(|s| s.title.as_str()).collect())
+            .collect();
+        assert_eq!(titles, [vec!["first", "b"], vec!["first", "a"], vec!["c"]]);
+    }
+}
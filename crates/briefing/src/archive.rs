@@ -0,0 +1,210 @@
+//! `briefing archive`: a browsable index of every past briefing.
+//!
+//! Each upload replaces the show's `index.html`, so the WebDAV folder only
+//! ever holds the latest week. This scans the output directory for
+//! `{slug}-{YYYY-MM-DD}.html` briefings (per-host views are left out),
+//! uploads any dated copies the server doesn't have yet to
+//! `{url}/{slug}/{slug}-{date}.html`, and puts an `index.html` listing them
+//! by show, newest first, at the `[upload]` root.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use shared::dates::DateFormat;
+use shared::settings::Settings;
+use shared::upload::Uploader;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::serve::escape;
+
+/// The index is also saved beside the briefings under this name.
+const LOCAL_INDEX: &str = "briefing-archive.html";
+
+/// A dated briefing in the output directory.
+#[derive(Debug, PartialEq)]
+struct Briefing {
+    slug: String,
+    date: NaiveDate,
+    path: PathBuf,
+}
+
+impl Briefing {
+    fn file_name(&self) -> String {
+        format!("{}-{}.html", self.slug, self.date.format("%Y-%m-%d"))
+    }
+}
+
+pub async fn run(dir: Option<PathBuf>, no_upload: bool) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => shared::output::settings().dir(shared::output::Artifact::Html)?,
+    };
+    let settings = Settings::load()?;
+    let briefings = briefings(&dir)?;
+    if briefings.is_empty() {
+        anyhow::bail!("No briefings in {}", dir.display());
+    }
+
+    let html = index(&briefings, &settings);
+    let local = dir.join(LOCAL_INDEX);
+    fs::write(&local, &html).with_context(|| format!("Failed to write {}", local.display()))?;
+    println!(
+        "✓ Listed {} briefing(s) in {}",
+        briefings.len(),
+        local.display()
+    );
+    if no_upload {
+        println!("  Not uploaded (--no-upload)");
+        return Ok(());
+    }
+
+    let Some(index_url) = settings.upload.root_url("index.html") else {
+        anyhow::bail!("No [upload] url in config.toml");
+    };
+    let dav = Uploader::connect(&settings.upload).await?;
+    let mut uploaded = 0;
+    for briefing in &briefings {
+        let Some(url) = settings
+            .upload
+            .file_url(&briefing.slug, &briefing.file_name())
+        else {
+            continue;
+        };
+        if dav.exists(&url).await? {
+            continue;
+        }
+        let body = fs::read(&briefing.path)
+            .with_context(|| format!("Failed to read {}", briefing.path.display()))?;
+        dav.put(&url, body).await?;
+        println!("  ✓ {}", url);
+        uploaded += 1;
+    }
+    dav.put(&index_url, html.into_bytes()).await?;
+    println!("  ✓ Index → {}", index_url);
+    println!("\n✅ Archive up to date ({} new upload(s))", uploaded);
+    Ok(())
+}
+
+/// The dated briefings in `dir`, newest first within each show.
+fn briefings(dir: &Path) -> Result<Vec<Briefing>> {
+    let mut found: Vec<Briefing> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (slug, date) = parse_name(path.file_name()?.to_str()?)?;
+            Some(Briefing { slug, date, path })
+        })
+        .collect();
+    found.sort_by(|a, b| a.slug.cmp(&b.slug).then_with(|| b.date.cmp(&a.date)));
+    Ok(found)
+}
+
+/// The show slug and date of `{slug}-{YYYY-MM-DD}.html`.
+fn parse_name(name: &str) -> Option<(String, NaiveDate)> {
+    let stem = name.strip_suffix(".html")?;
+    let split = stem.len().checked_sub(10)?;
+    let (slug, date) = (stem.get(..split)?, stem.get(split..)?);
+    let slug = slug.strip_suffix('-').filter(|s| !s.is_empty())?;
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some((slug.to_string(), date))
+}
+
+/// The archive page: configured shows first, in config order, then any
+/// others by slug.
+fn index(briefings: &[Briefing], settings: &Settings) -> String {
+    let mut slugs: Vec<&str> = Vec::new();
+    for show in &settings.shows {
+        if briefings.iter().any(|b| b.slug == show.slug) {
+            slugs.push(&show.slug);
+        }
+    }
+    for briefing in briefings {
+        if !slugs.contains(&briefing.slug.as_str()) {
+            slugs.push(&briefing.slug);
+        }
+    }
+
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n  <meta charset=\"UTF-8\">\n  \
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n  \
+         <title>Briefings</title>\n  <style>\n    \
+         body { font-family: Arial, sans-serif; max-width: 700px; margin: 40px auto; padding: 0 20px; line-height: 1.8; }\n    \
+         a { color: #3498db; text-decoration: none; }\n  \
+         </style>\n</head>\n<body>\n<h1>Briefings</h1>\n",
+    );
+    for slug in slugs {
+        let show = settings.shows.iter().find(|s| s.slug == slug);
+        let name = show.map_or(slug, |s| s.name.as_str());
+        let locale = show
+            .and_then(|s| s.locale)
+            .unwrap_or(settings.briefing.locale);
+        html.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape(name)));
+        for briefing in briefings.iter().filter(|b| b.slug == slug) {
+            html.push_str(&format!(
+                "  <li><a href=\"{}/{}\">{}</a></li>\n",
+                escape(slug),
+                escape(&briefing.file_name()),
+                escape(&locale.format_date(briefing.date, DateFormat::Long))
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        assert_eq!(
+            parse_name("twit-2026-02-01.html"),
+            Some(("twit".to_string(), date))
+        );
+        assert_eq!(
+            parse_name("security-now-2026-02-01.html"),
+            Some(("security-now".to_string(), date))
+        );
+        // Host views, other pages and the index itself aren't briefings
+        assert_eq!(parse_name("twit-2026-02-01-leo.html"), None);
+        assert_eq!(parse_name("twit-2026-02-01.org"), None);
+        assert_eq!(parse_name("2026-02-01.html"), None);
+        assert_eq!(parse_name(LOCAL_INDEX), None);
+    }
+
+    #[test]
+    fn test_index_groups_by_show() {
+        let settings = Settings::from_toml(
+            "[[shows]]\nslug = \"twit\"\nname = \"This Week in Tech\"\nday = \"sunday\"\nends = 18\n\n\
+             [[shows]]\nslug = \"mbw\"\nname = \"MacBreak Weekly\"\nday = \"tuesday\"\nends = 14\n",
+        )
+        .unwrap();
+        let briefing = |slug: &str, day| Briefing {
+            slug: slug.to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 2, day).unwrap(),
+            path: PathBuf::new(),
+        };
+        let html = index(
+            &[
+                briefing("mbw", 3),
+                briefing("old", 1),
+                briefing("twit", 8),
+                briefing("twit", 1),
+            ],
+            &settings,
+        );
+
+        let twit = html.find("<h2>This Week in Tech</h2>").unwrap();
+        let mbw = html.find("<h2>MacBreak Weekly</h2>").unwrap();
+        let old = html.find("<h2>old</h2>").unwrap();
+        assert!(twit < mbw && mbw < old, "{}", html);
+        assert!(html.contains(
+            "<li><a href=\"twit/twit-2026-02-08.html\">Sunday, 8 February 2026</a></li>"
+        ));
+        assert!(html.find("twit-2026-02-08").unwrap() < html.find("twit-2026-02-01").unwrap());
+    }
+}
@@ -0,0 +1,460 @@
+//! `briefing import-legacy`: load old org and HTML briefings into the archive.
+//!
+//! Parsing is best-effort. Org files go through the same parser as
+//! prepare-briefing. HTML files produced by this tool keep their summaries.
+//! Other HTML falls back to headings as topics and links as stories. The show
+//! and date come from the filename (`{slug}-{YYYY-MM-DD}`), then from the
+//! document, then from the file's modification time.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use scraper::{ElementRef, Html, Selector};
+use shared::archive::{parse_filename, Archive};
+use shared::quotes::Quote;
+use shared::settings::Settings;
+use shared::shows::ShowSettings;
+use shared::{BriefingData, ShowInfo, Story, Summary, Topic};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DATE_FORMATS: &[&str] = &[
+    "%a, %d %B %Y",
+    "%A, %d %B %Y",
+    "%a, %d %b %Y",
+    "%Y-%m-%d",
+    "%B %d, %Y",
+];
+
+struct Parsed {
+    show_name: Option<String>,
+    date: Option<NaiveDate>,
+    topics: Vec<Topic>,
+}
+
+pub fn run(dir: &Path, show: Option<String>, force: bool) -> Result<()> {
+    let archive = Archive::open_default();
+    let settings = Settings::load()?;
+    let mut files = find_briefings(dir)?;
+    // Org files carry full summaries, so import them before HTML copies of
+    // the same briefing
+    files.sort_by_key(|p| (!is_org(p), p.clone()));
+
+    println!(
+        "📦 Importing {} file(s) into {}",
+        files.len(),
+        archive.root().display()
+    );
+
+    let (mut imported, mut skipped, mut failed) = (0, 0, 0);
+    for path in &files {
+        match import_file(&archive, &settings.shows, path, show.as_deref(), force) {
+            Ok(Some(stored)) => {
+                imported += 1;
+                println!("  ✓ {} → {}", path.display(), stored.display());
+            }
+            Ok(None) => skipped += 1,
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {}: {:#}", path.display(), e);
+            }
+        }
+    }
+
+    println!(
+        "\n✅ Imported {} briefing(s) ({} already archived, {} failed)",
+        imported, skipped, failed
+    );
+    Ok(())
+}
+
+/// Returns the archive path, or `None` if that show/date is already archived.
+fn import_file(
+    archive: &Archive,
+    shows: &[ShowSettings],
+    path: &Path,
+    show: Option<&str>,
+    force: bool,
+) -> Result<Option<PathBuf>> {
+    let content = fs::read_to_string(path).context("Failed to read file")?;
+    let parsed = if is_org(path) {
+        parse_org(&content)?
+    } else {
+        parse_html(&content)
+    };
+    if parsed.topics.is_empty() {
+        anyhow::bail!("No stories found");
+    }
+
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let (file_slug, file_date) = parse_filename(stem);
+    let slug = show
+        .map(str::to_string)
+        .or(file_slug)
+        .or_else(|| {
+            let name = parsed.show_name.as_deref()?;
+            Some(match configured_show(shows, name) {
+                Some(show) => show.slug.clone(),
+                None => slugify(name),
+            })
+        })
+        .context("Can't tell which show this is (use --show)")?;
+    let date = match file_date.or(parsed.date) {
+        Some(date) => date,
+        None => modified_date(path)?,
+    };
+
+    if archive.contains(&slug, date) && !force {
+        return Ok(None);
+    }
+
+    let show = match shows.iter().find(|s| s.slug == slug) {
+        Some(show) => show.info(),
+        None => {
+            let name = parsed.show_name.unwrap_or_else(|| slug.clone());
+            ShowInfo::new(name, slug.clone(), format!("#{}", slug))
+        }
+    };
+    let mut data = BriefingData::new(show, parsed.topics);
+    data.created_at = date
+        .and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().to_rfc3339())
+        .unwrap_or(data.created_at);
+
+    archive.store(&data, date).map(Some)
+}
+
+fn find_briefings(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("org" | "html" | "htm")
+            ) {
+                found.push(path);
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn is_org(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("org")
+}
+
+fn modified_date(path: &Path) -> Result<NaiveDate> {
+    let modified = fs::metadata(path)?.modified()?;
+    let datetime: chrono::DateTime<chrono::Local> = modified.into();
+    Ok(datetime.date_naive())
+}
+
+/// The `[[shows]]` entry an old title names, by name or slug, ignoring case.
+fn configured_show<'a>(shows: &'a [ShowSettings], name: &str) -> Option<&'a ShowSettings> {
+    shows
+        .iter()
+        .find(|s| s.name.eq_ignore_ascii_case(name) || s.slug.eq_ignore_ascii_case(name))
+}
+
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim().trim_start_matches("For ").trim();
+    DATE_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(text, fmt).ok())
+        // Briefings written for other locales
+        .or_else(|| shared::dates::parse(text).map(|date| date.date()))
+        .or_else(|| {
+            // Hand-edited files sometimes have the wrong weekday; chrono
+            // rejects those, so retry without it
+            let (_, rest) = text.split_once(", ")?;
+            ["%d %B %Y", "%d %b %Y"]
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(rest.trim(), fmt).ok())
+        })
+}
+
+fn parse_org(content: &str) -> Result<Parsed> {
+    let (show_name, topics) = shared::org::parse_org_mode(content)?;
+    let date = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("#+DATE:"))
+        .and_then(parse_date);
+    Ok(Parsed {
+        show_name: (!show_name.is_empty()).then_some(show_name),
+        date,
+        topics,
+    })
+}
+
+fn parse_html(content: &str) -> Parsed {
+    let document = Html::parse_document(content);
+    let select = |s: &str| Selector::parse(s).expect("valid selector");
+    let text = |el: ElementRef| el.text().collect::<String>().trim().to_string();
+
+    let show_name = document
+        .select(&select("meta[name=show]"))
+        .next()
+        .and_then(|el| el.value().attr("content"))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let date = document
+        .select(&select(".date"))
+        .next()
+        .and_then(|el| parse_date(&text(el)));
+
+    // Our own output puts topics in h2 and stories in h3; older documents
+    // may only use h1 for sections and bare links for stories
+    let has_h2 = document.select(&select("h2")).next().is_some();
+    let has_h3 = document.select(&select("h3")).next().is_some();
+    let topic_tag = if has_h2 { "h2" } else { "h1" };
+
+    let mut topics: Vec<Topic> = Vec::new();
+    let mut paragraphs: Vec<String> = Vec::new();
+    let finish_story = |topics: &mut Vec<Topic>, paragraphs: &mut Vec<String>| {
+        if let Some(story) = topics.last_mut().and_then(|t| t.stories.last_mut()) {
+            if !paragraphs.is_empty() {
+                story.summary = summary_from_paragraphs(paragraphs);
+            }
+        }
+        paragraphs.clear();
+    };
+
+    for el in document.select(&select("h1, h2, h3, a[href], details.article p")) {
+        let tag = el.value().name();
+        if tag == topic_tag {
+            finish_story(&mut topics, &mut paragraphs);
+            let title = text(el);
+            let title = strip_numbering(&title);
+            if el.select(&select(".show-name")).next().is_none() && !title.is_empty() {
+                topics.push(Topic {
+                    title: title.to_string(),
+                    stories: Vec::new(),
+                    connections: None,
+                    notes: None,
+                    rationale: None,
+                });
+            }
+        } else if tag == "h3" {
+            finish_story(&mut topics, &mut paragraphs);
+            current_topic(&mut topics)
+                .stories
+                .push(story(&text(el), ""));
+        } else if tag == "a" {
+            let href = el.value().attr("href").unwrap_or("");
+            if !href.starts_with("http") {
+                continue;
+            }
+            let topic = current_topic(&mut topics);
+            match topic.stories.last_mut() {
+                Some(story) if has_h3 && story.url.is_empty() => story.url = href.to_string(),
+                _ if !has_h3 => {
+                    let title = text(el);
+                    let title = if title.is_empty() { href } else { &title };
+                    topic.stories.push(story(title, href));
+                }
+                _ => {}
+            }
+        } else if tag == "p" {
+            paragraphs.push(text(el));
+        }
+    }
+    finish_story(&mut topics, &mut paragraphs);
+
+    topics.retain(|t| !t.stories.is_empty());
+    Parsed {
+        show_name,
+        date,
+        topics,
+    }
+}
+
+fn current_topic(topics: &mut Vec<Topic>) -> &mut Topic {
+    if topics.is_empty() {
+        topics.push(Topic {
+            title: "News".to_string(),
+            stories: Vec::new(),
+            connections: None,
+            notes: None,
+            rationale: None,
+        });
+    }
+    topics.last_mut().expect("just pushed")
+}
+
+fn story(title: &str, url: &str) -> Story {
+    Story {
+        title: title.to_string(),
+        url: url.to_string(),
+        summary: Summary::Insufficient,
+        ..Default::default()
+    }
+}
+
+/// "3. Apple" → "Apple"
+fn strip_numbering(title: &str) -> &str {
+    match title.split_once(". ") {
+        Some((n, rest)) if n.chars().all(|c| c.is_ascii_digit()) && !n.is_empty() => rest.trim(),
+        _ => title,
+    }
+}
+
+/// Rebuild a summary from the paragraphs `BriefingGenerator::generate` writes.
+fn summary_from_paragraphs(paragraphs: &[String]) -> Summary {
+    let field = |label: &str| {
+        paragraphs
+            .iter()
+            .find_map(|p| p.strip_prefix(label))
+            .map(|v| v.trim().to_string())
+    };
+    let is_quote = |p: &&String| p.starts_with('"') || p.starts_with('“');
+    let quotes: Vec<Quote> = paragraphs
+        .iter()
+        .filter(is_quote)
+        .map(|p| Quote::parse(p))
+        .collect();
+
+    if let Some(the_product) = field("The product:") {
+        return Summary::Product {
+            the_product,
+            cost: field("Cost:").unwrap_or_default(),
+            availability: field("Availability:").unwrap_or_default(),
+            platforms: field("Platforms:").unwrap_or_default(),
+            quotes,
+            reaction: None,
+        };
+    }
+
+    let mut rest = paragraphs.iter().filter(|p| !is_quote(p));
+    match (rest.next(), rest.next()) {
+        (Some(lede), Some(nutgraf)) => Summary::Editorial {
+            lede: lede.clone(),
+            nutgraf: nutgraf.clone(),
+            quotes,
+            reaction: None,
+            by_the_numbers: None,
+        },
+        _ => Summary::Insufficient,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_html_round_trips_generated_briefing() {
+        let topics = vec![Topic {
+            title: "Apple".to_string(),
+            stories: vec![Story {
+                title: "iPhone 17 Announced".to_string(),
+                url: "https://example.com/iphone".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Editorial {
+                    lede: "Apple announced the iPhone 17.".to_string(),
+                    nutgraf: "It has a faster chip.".to_string(),
+                    quotes: Vec::new(),
+                    reaction: None,
+                    by_the_numbers: None,
+                },
+                ..Default::default()
+            }],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = shared::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let html = shared::briefing::BriefingGenerator::generate(&topics, "TWiT", date);
+
+        let parsed = parse_html(&html);
+        assert_eq!(parsed.show_name.as_deref(), Some("TWiT"));
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2026, 2, 1));
+        assert_eq!(parsed.topics.len(), 1);
+        assert_eq!(parsed.topics[0].title, "Apple");
+        let story = &parsed.topics[0].stories[0];
+        assert_eq!(story.url, "https://example.com/iphone");
+        match &story.summary {
+            Summary::Editorial { lede, nutgraf, .. } => {
+                assert_eq!(lede, "Apple announced the iPhone 17.");
+                assert_eq!(nutgraf, "It has a faster chip.");
+            }
+            other => panic!("Expected Editorial, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_html_plain_links_under_headings() {
+        let html = r#"<html><head><title>MBW Briefing</title></head><body>
+            <h1>Apple</h1>
+            <ul><li><a href="https://example.com/a">New Macs</a></li>
+                <li><a href="/relative">ignored</a></li></ul>
+            <h1>Google</h1>
+            <p><a href="https://example.com/b">Pixel</a></p>
+            </body></html>"#;
+
+        let parsed = parse_html(html);
+        // The title isn't taken apart for a show name
+        assert_eq!(parsed.show_name, None);
+        assert_eq!(parsed.topics.len(), 2);
+        assert_eq!(parsed.topics[0].stories[0].title, "New Macs");
+        assert_eq!(parsed.topics[0].stories.len(), 1);
+        assert_eq!(parsed.topics[1].stories[0].url, "https://example.com/b");
+    }
+
+    #[test]
+    fn test_parse_org_reads_date_line() {
+        let org = "#+TITLE: TWiT Briefing Book\n#+SHOW: TWiT\n#+DATE: Sun, 2 February 2026\n\n\
+                   * Apple\n\n** Story\n\n*** URL\nhttps://example.com/a\n";
+        let parsed = parse_org(org).unwrap();
+        assert_eq!(parsed.show_name.as_deref(), Some("TWiT"));
+        assert_eq!(parsed.date, NaiveDate::from_ymd_opt(2026, 2, 2));
+
+        // Mismatched weekday (2 February 2026 is a Monday) still parses
+        assert_eq!(
+            parse_date("Sun, 1 February 2026"),
+            NaiveDate::from_ymd_opt(2026, 2, 1)
+        );
+        assert_eq!(parsed.topics[0].stories[0].url, "https://example.com/a");
+    }
+
+    #[test]
+    fn test_strip_numbering_and_slugify() {
+        assert_eq!(strip_numbering("3. Apple"), "Apple");
+        assert_eq!(strip_numbering("Web 3. Or not"), "Web 3. Or not");
+        assert_eq!(slugify("This Week in Tech"), "this-week-in-tech");
+    }
+
+    #[test]
+    fn test_configured_show_by_name_or_slug() {
+        let shows = vec![ShowSettings {
+            slug: "twit".to_string(),
+            name: "This Week in Tech".to_string(),
+            tag: None,
+            day: chrono::Weekday::Sun,
+            ends: 18,
+            rollover: None,
+            locale: None,
+            topic_order: None,
+            timezone: None,
+            exceptions: Vec::new(),
+        }];
+        assert!(configured_show(&shows, "This Week In Tech").is_some());
+        assert!(configured_show(&shows, "TWiT").is_some());
+        assert!(configured_show(&shows, "MBW").is_none());
+    }
+}
@@ -0,0 +1,400 @@
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod add_story;
+mod archive;
+mod import_legacy;
+mod merge;
+mod prune;
+mod reconcile;
+mod retry;
+mod review;
+mod run;
+mod self_update;
+mod serve;
+
+#[derive(Parser)]
+#[command(name = "briefing")]
+#[command(about = "Collect, prepare and publish podcast briefings")]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Print debug logging to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Use this config.toml instead of the one in ~/.config/podcast-briefing
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Collect and summarize the week's bookmarks into an org draft
+    Collect(collect_stories::Args),
+    /// Turn an edited org draft into the HTML briefing and links CSV, and
+    /// upload them
+    Prepare(prepare_briefing::Args),
+    /// Upload a prepared briefing to the [upload] WebDAV folder
+    Publish(prepare_briefing::PublishArgs),
+    /// Collect, edit, prepare and upload in one go, or just some of the
+    /// stages with --from and --to
+    Run(run::Args),
+    /// Show which config.toml is used and check that it's valid
+    Config {
+        /// Only print the file's path, e.g. for `$EDITOR $(briefing config --path)`
+        #[arg(long)]
+        path: bool,
+    },
+    /// Download and install the latest release of the briefing tools
+    SelfUpdate {
+        /// Only report whether an update is available
+        #[arg(long)]
+        check: bool,
+    },
+    /// Write the built-in themes and schema to disk for customization
+    ExportAssets {
+        /// Destination directory (default: ~/.config/podcast-briefing)
+        dir: Option<PathBuf>,
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+    /// Import old org/HTML briefings into the archive
+    ImportLegacy {
+        /// Directory to scan (recursively) for .org and .html files
+        dir: PathBuf,
+        /// Show slug to file them under, if filenames don't say
+        #[arg(long)]
+        show: Option<String>,
+        /// Replace briefings that are already archived
+        #[arg(long)]
+        force: bool,
+    },
+    /// Curate a collected story file and regenerate its org draft
+    Review {
+        /// Story file (default: the most recently collected)
+        file: Option<PathBuf>,
+    },
+    /// Fetch the articles a collection couldn't and patch them into its
+    /// story file
+    RetryFailed {
+        /// Story file (default: the most recently collected)
+        file: Option<PathBuf>,
+    },
+    /// Fetch and summarize one article and add it to a story file
+    AddStory {
+        /// The article's URL
+        url: String,
+        /// Story file (default: the most recently collected)
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Topic number or title; a new title adds a topic (default: ask)
+        #[arg(long)]
+        topic: Option<String>,
+        /// Headline to use instead of the page's
+        #[arg(long)]
+        title: Option<String>,
+        /// Star the story so it leads its topic
+        #[arg(long)]
+        star: bool,
+    },
+    /// Remove stories from a story file by site or headline, or undo that
+    Prune {
+        /// Story file (default: the most recently collected)
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Remove stories from this site or its subdomains (repeatable)
+        #[arg(long = "domain", value_name = "DOMAIN")]
+        domains: Vec<String>,
+        /// Remove stories whose headline contains this, ignoring case
+        /// (repeatable)
+        #[arg(long = "title-contains", value_name = "TEXT")]
+        titles: Vec<String>,
+        /// Ask before removing each story; without patterns, ask about all
+        #[arg(short, long)]
+        interactive: bool,
+        /// Put back the stories the last prune removed
+        #[arg(long, conflicts_with_all = ["domains", "titles", "interactive"])]
+        undo: bool,
+    },
+    /// Combine story files for one show, dropping repeats and re-clustering
+    Merge {
+        /// Story files to merge (default: every file for the latest show date)
+        files: Vec<PathBuf>,
+        /// Where to save the merged stories
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Record which stories of an archived briefing were discussed on air
+    Reconcile {
+        /// Stories discussed: a file (e.g. a timestamps log), `-` for stdin,
+        /// or a comma-separated list of story numbers, URLs or titles
+        #[arg(long)]
+        covered: String,
+        /// Show slug, as in `[[shows]]`
+        #[arg(long)]
+        show: String,
+        /// Show date (YYYY-MM-DD; default: the latest archived briefing)
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+    },
+    /// Search archived briefings for stories matching all words
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+    },
+    /// Check every story link in an org briefing before publishing
+    CheckLinks {
+        /// The org file to check
+        file: PathBuf,
+    },
+    /// Preview briefings in a browser, reloading when they change
+    Serve {
+        /// Directory of briefings (default: ~/Documents)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Address to listen on; 0.0.0.0 lets other devices on the network in
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Upload an index of every past briefing to the [upload] WebDAV root
+    Archive {
+        /// Directory of briefings (default: ~/Documents)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// Only write the index beside the briefings
+        #[arg(long)]
+        no_upload: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match dispatch(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            shared::logging::print_error(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn dispatch(cli: Cli) -> Result<()> {
+    if let Some(path) = cli.config {
+        shared::settings::Settings::use_file(path);
+    }
+    // Collecting and preparing keep the log files they had as separate tools
+    let app = match cli.command {
+        Command::Collect(_) => "collect-stories",
+        Command::Prepare(_) | Command::Publish(_) => "prepare-briefing",
+        _ => "briefing",
+    };
+    shared::logging::init(app, cli.verbose);
+
+    match cli.command {
+        Command::Collect(args) => collect_stories::run(args).await,
+        Command::Prepare(args) => prepare_briefing::run(args).await,
+        Command::Publish(args) => prepare_briefing::publish(args).await,
+        Command::Run(args) => run::run(args).await,
+        Command::Config { path } => config(path),
+        Command::SelfUpdate { check } => self_update::run(check).await,
+        Command::ExportAssets { dir, force } => export_assets(dir, force),
+        Command::ImportLegacy { dir, show, force } => import_legacy::run(&dir, show, force),
+        Command::Review { file } => review::run(file).await,
+        Command::RetryFailed { file } => retry::run(file).await,
+        Command::AddStory {
+            url,
+            file,
+            topic,
+            title,
+            star,
+        } => {
+            add_story::run(add_story::Args {
+                url,
+                file,
+                topic,
+                title,
+                star,
+            })
+            .await
+        }
+        Command::Prune {
+            file,
+            domains,
+            titles,
+            interactive,
+            undo,
+        } => prune::run(prune::Args {
+            file,
+            domains,
+            titles,
+            interactive,
+            undo,
+        }),
+        Command::Merge { files, output } => merge::run(files, output).await,
+        Command::Reconcile {
+            covered,
+            show,
+            date,
+        } => reconcile::run(&covered, &show, date),
+        Command::Search { query } => search(&query.join(" ")),
+        Command::CheckLinks { file } => check_links(&file).await,
+        Command::Serve { dir, host, port } => serve::run(dir, host, port).await,
+        Command::Archive { dir, no_upload } => archive::run(dir, no_upload).await,
+    }
+}
+
+fn config(path_only: bool) -> Result<()> {
+    let path = shared::settings::Settings::path();
+    if path_only {
+        println!("{}", path.display());
+        return Ok(());
+    }
+    if !path.exists() {
+        println!("{} doesn't exist yet", path.display());
+        println!("Create it with at least one [[shows]] table; see the README");
+        return Ok(());
+    }
+    let mut settings = shared::settings::Settings::from_file(&path)?;
+    println!("✓ {} is valid", path.display());
+    if settings.shows.is_empty() {
+        println!("⚠ No [[shows]] tables, so the old built-in shows are used");
+        settings.shows = shared::shows::legacy();
+    }
+    for show in &settings.shows {
+        let schedule = shared::schedule::Schedule::new(show, &settings.schedule)?;
+        println!(
+            "  {:<8} {} (next: {})",
+            show.slug,
+            show.name,
+            schedule.next_date(schedule.now()).format("%A, %-d %B")
+        );
+    }
+    Ok(())
+}
+
+fn export_assets(dir: Option<PathBuf>, force: bool) -> Result<()> {
+    let dir = dir.unwrap_or_else(shared::config::config_dir);
+    let (written, skipped) = shared::assets::export(&dir, force)?;
+
+    for path in &written {
+        println!("  ✓ {}", path.display());
+    }
+    for path in &skipped {
+        println!("  – {} (exists, use --force to overwrite)", path.display());
+    }
+    println!(
+        "\n✅ Exported {} asset(s) to {}",
+        written.len(),
+        dir.display()
+    );
+    Ok(())
+}
+
+/// Fails when a link is dead, so a publish script can stop there.
+async fn check_links(file: &std::path::Path) -> Result<()> {
+    let org = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let (_, topics) = shared::org::parse_org_mode(&org)?;
+    let statuses = shared::links::check_all(&topics).await;
+
+    let problems = shared::links::report(&topics, &statuses);
+    for line in &problems {
+        println!("{}", line);
+    }
+    let dead = shared::links::dead_count(&statuses);
+    println!(
+        "\n{} link(s) checked: {} dead, {} to look at",
+        statuses.len(),
+        dead,
+        problems.len()
+    );
+    if dead > 0 {
+        anyhow::bail!("{} dead link(s) in {}", dead, file.display());
+    }
+    Ok(())
+}
+
+fn search(query: &str) -> Result<()> {
+    let hits = shared::archive::Archive::open_default().search(query)?;
+    if hits.is_empty() {
+        println!("No archived stories match \"{}\"", query);
+        return Ok(());
+    }
+
+    for hit in &hits {
+        println!("{} {:<5} {} › {}", hit.date, hit.slug, hit.topic, hit.title);
+        if !hit.url.is_empty() {
+            println!("                 {}", hit.url);
+        }
+    }
+    println!("\n{} match(es)", hits.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use shared::briefing::CsvColumn;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        // Global flags go before or after the subcommand
+        let cli = Cli::try_parse_from(["briefing", "-v", "collect", "--show", "twit"]).unwrap();
+        assert!(cli.verbose);
+        assert!(
+            matches!(cli.command, Command::Collect(args) if args.show.as_deref() == Some("twit"))
+        );
+        let cli = Cli::try_parse_from(["briefing", "prepare", "--no-upload", "--config", "x.toml"])
+            .unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("x.toml")));
+        assert!(matches!(cli.command, Command::Prepare(args) if args.no_upload));
+        let cli =
+            Cli::try_parse_from(["briefing", "prepare", "--csv-columns", "date,words"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Prepare(args) if args.csv_columns == [CsvColumn::Date, CsvColumn::Words]
+        ));
+        let cli = Cli::try_parse_from(["briefing", "prepare", "--csv-columns", "words,date,words"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Prepare(args)
+                if args.csv_columns == [CsvColumn::Words, CsvColumn::Date, CsvColumn::Words]
+        ));
+        let error = Cli::try_parse_from(["briefing", "prepare", "--csv-columns", "date,isbn"])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("unknown CSV column 'isbn'"), "{}", error);
+        let cli = Cli::try_parse_from([
+            "briefing", "run", "--show", "twit", "--from", "cluster", "--to", "prepare",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Run(args) if args.from == run::Stage::Cluster && args.to == run::Stage::Prepare
+        ));
+        assert!(
+            Cli::try_parse_from(["briefing", "run", "--show", "twit", "--to", "edited"]).is_err()
+        );
+        let cli = Cli::try_parse_from(["briefing", "archive", "--no-upload"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Archive {
+                dir: None,
+                no_upload: true
+            }
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+//! `briefing merge`: combine several story files for one show.
+//!
+//! For weeks with more than one collect-stories run (early bookmarks plus
+//! last-minute additions). The stories of every file are pooled, repeats
+//! are dropped by URL, and the union is clustered again so the topics fit
+//! the whole week. The result is saved as a new story file and the org
+//! draft is regenerated from it.
+
+use anyhow::{bail, Context, Result};
+use shared::archive::parse_filename;
+use shared::briefing::BriefingGenerator;
+use shared::org_template::OrgTemplate;
+use shared::prompts::{Prompt, PromptKind};
+use shared::settings::Settings;
+use shared::{BriefingData, ShowInfo, Story, Topic, TopicClusterer};
+use std::path::{Path, PathBuf};
+
+pub async fn run(files: Vec<PathBuf>, output: Option<PathBuf>) -> Result<()> {
+    let files = if files.is_empty() {
+        same_week_files()?
+    } else {
+        files
+    };
+    if files.len() < 2 {
+        bail!("Need at least two story files to merge");
+    }
+
+    let mut inputs = Vec::with_capacity(files.len());
+    for path in &files {
+        println!("📂 {}", path.display());
+        inputs.push(shared::load_stories(path)?);
+    }
+
+    let missed: Vec<_> = inputs.iter().flat_map(|d| d.missed.clone()).collect();
+    let merged = shared::io::merge_stories(inputs)?;
+    println!(
+        "✓ {} stories from {} files ({} duplicate(s) dropped)",
+        merged.stories.len(),
+        files.len(),
+        merged.duplicates
+    );
+
+    let mut settings = Settings::load()?;
+    settings.apply_show(&merged.show.slug);
+    settings.briefing.org_template = OrgTemplate::load(&merged.show.slug)?;
+    let topics = cluster(merged.stories, &merged.show, &settings).await?;
+
+    let mut data = BriefingData::new(merged.show, topics);
+    // Drop suggestions that another file's bookmarks cover
+    let bookmarked: Vec<(String, String)> = data
+        .topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .map(|s| (s.url.clone(), s.title.clone()))
+        .collect();
+    data.missed = shared::discovery::not_bookmarked(missed, &bookmarked);
+    // The newest input decides the show date
+    let show_date = crate::review::show_date_for(&files[files.len() - 1], &data, &settings)?;
+    let output = match output {
+        Some(path) => path,
+        None => shared::get_default_stories_dir()?.join(format!(
+            "{}-{}-merged.json",
+            data.show.slug,
+            show_date.format("%Y-%m-%d")
+        )),
+    };
+
+    let json = serde_json::to_string_pretty(&data).context("Failed to serialize briefing data")?;
+    shared::io::save_file(&output, json)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!("💾 Merged story data saved to: {}", output.display());
+
+    let mut org = BriefingGenerator::generate_org_mode_with(
+        &data.topics,
+        &data.show.name,
+        show_date,
+        &settings.briefing,
+    );
+    org.push_str(&shared::discovery::org_section(&data.missed));
+    let org_path = BriefingGenerator::save_org_mode(&org, &data.show.slug, show_date)?;
+    println!("✓ Org-mode file saved to: {}", org_path.display());
+    Ok(())
+}
+
+/// Group `stories` into topics with the show's clustering prompt, or by site
+/// when no API key is set.
+pub(crate) async fn cluster(
+    stories: Vec<Story>,
+    show: &ShowInfo,
+    settings: &Settings,
+) -> Result<Vec<Topic>> {
+    let mut topics = if shared::llm::credentials_available() {
+        println!("\n🔗 Clustering stories by topic...");
+        let prompt = Prompt::load(PromptKind::Cluster, show, &settings.prompts)?;
+        let split_prompt = Prompt::load(PromptKind::Split, show, &settings.prompts)?;
+        TopicClusterer::new()
+            .context("Failed to initialize topic clusterer")?
+            .with_prompt(prompt)
+            .with_split_prompt(split_prompt)
+            .with_limits(settings.clustering.clone())
+            .cluster_stories(stories)
+            .await
+            .context("Failed to cluster stories")?
+    } else {
+        println!(
+            "\n🔗 Grouping stories by site ({} is not set)...",
+            shared::llm::key_env_var()
+        );
+        let topics = shared::clustering::cluster_by_domain(stories);
+        shared::clustering::apply_size_limits(topics, &settings.clustering)
+    };
+    println!("✓ Organized into {} topics", topics.len());
+    settings.briefing.topic_order.apply(&mut topics);
+    Ok(topics)
+}
+
+/// Story files for the same show and date as the most recent one, oldest
+/// first.
+fn same_week_files() -> Result<Vec<PathBuf>> {
+    let files: Vec<PathBuf> = shared::list_story_files()?
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| !is_merged(path))
+        .collect();
+    let newest = files
+        .first()
+        .context("No story files found; run collect-stories first")?;
+    let key = week_key(newest);
+
+    let mut same: Vec<PathBuf> = files
+        .iter()
+        .filter(|path| week_key(path) == key)
+        .cloned()
+        .collect();
+    same.reverse();
+    Ok(same)
+}
+
+fn week_key(path: &Path) -> (Option<String>, Option<chrono::NaiveDate>) {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(parse_filename)
+        .unwrap_or_default()
+}
+
+/// Earlier merge results would only duplicate their own inputs.
+fn is_merged(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|s| s.ends_with("-merged"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_runs_share_a_week_key() {
+        let first = Path::new("/stories/twit-2026-02-08.json");
+        let second = Path::new("/stories/twit-2026-02-08-2.json");
+        let merged = Path::new("/stories/twit-2026-02-08-merged.json");
+        assert_eq!(week_key(first), week_key(second));
+        assert_eq!(week_key(first), week_key(merged));
+        assert_ne!(
+            week_key(first),
+            week_key(Path::new("/stories/mbw-2026-02-08.json"))
+        );
+        assert!(is_merged(merged));
+        assert!(!is_merged(second));
+    }
+}
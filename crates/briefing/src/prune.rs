@@ -0,0 +1,345 @@
+//! `briefing prune`: drop stories from a story file by site or headline.
+//!
+//! Every story whose site is one of `--domain` (or a subdomain of one) or
+//! whose headline contains one of `--title-contains` is removed, and the
+//! org draft is regenerated. With `--interactive` each match is confirmed
+//! first, and without patterns every story is offered. Removed stories are
+//! kept under the story file's name in `pruned/` in the data dir, and
+//! `--undo` puts the last prune's stories back where they were (again to
+//! undo the one before).
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use shared::clustering::source_domain;
+use shared::org_template::OrgTemplate;
+use shared::settings::Settings;
+use shared::{BriefingData, Story, Topic};
+use std::path::{Path, PathBuf};
+
+pub struct Args {
+    pub file: Option<PathBuf>,
+    pub domains: Vec<String>,
+    pub titles: Vec<String>,
+    pub interactive: bool,
+    pub undo: bool,
+}
+
+/// A removed story and where it was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Removed {
+    topic: String,
+    /// Of the topic in the file
+    topic_index: usize,
+    /// Of the story in its topic
+    index: usize,
+    story: Story,
+}
+
+/// One run of `briefing prune`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Prune {
+    /// When it ran, e.g. `2026-02-01 09:30`
+    at: String,
+    removed: Vec<Removed>,
+}
+
+/// The prunes of one story file, oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PruneLog {
+    prunes: Vec<Prune>,
+}
+
+impl PruneLog {
+    fn path_for(story_file: &Path) -> PathBuf {
+        shared::config::data_dir()
+            .join("pruned")
+            .join(story_file.file_name().unwrap_or_default())
+    }
+
+    fn load(story_file: &Path) -> Result<Self> {
+        let path = Self::path_for(story_file);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write the log, or delete it when it's empty.
+    fn save(&self, story_file: &Path) -> Result<()> {
+        let path = Self::path_for(story_file);
+        if self.prunes.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize prune log")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Which stories to prune.
+struct Patterns {
+    domains: Vec<String>,
+    titles: Vec<String>,
+}
+
+impl Patterns {
+    fn new(domains: &[String], titles: &[String]) -> Self {
+        Self {
+            domains: domains
+                .iter()
+                .map(|d| d.trim().trim_start_matches("www.").to_lowercase())
+                .collect(),
+            titles: titles.iter().map(|t| t.to_lowercase()).collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.domains.is_empty() && self.titles.is_empty()
+    }
+
+    fn matches(&self, story: &Story) -> bool {
+        let site = source_domain(&story.url).map(|s| s.to_lowercase());
+        let on_domain = site.is_some_and(|site| {
+            self.domains
+                .iter()
+                .any(|d| site == *d || site.ends_with(&format!(".{}", d)))
+        });
+        let title = story.title.to_lowercase();
+        on_domain || self.titles.iter().any(|t| title.contains(t.as_str()))
+    }
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let path = match args.file {
+        Some(path) => path,
+        None => crate::review::latest_story_file()?,
+    };
+    let mut data = shared::load_stories(&path)?;
+    let mut log = PruneLog::load(&path)?;
+    let mut settings = Settings::load()?;
+    settings.apply_show(&data.show.slug);
+    settings.briefing.org_template = OrgTemplate::load(&data.show.slug)?;
+
+    if args.undo {
+        let Some(prune) = log.prunes.pop() else {
+            println!("✓ Nothing pruned from {}", path.display());
+            return Ok(());
+        };
+        let restored = restore(&mut data, prune.removed);
+        println!("↩ Restored {} story(ies) pruned {}", restored, prune.at);
+        crate::retry::save(&path, &data, &settings)?;
+        return log.save(&path);
+    }
+
+    let patterns = Patterns::new(&args.domains, &args.titles);
+    if patterns.is_empty() && !args.interactive {
+        bail!("give --domain or --title-contains patterns, or --interactive");
+    }
+    let mut ask = |story: &Story| -> Result<bool> {
+        if !patterns.is_empty() && !patterns.matches(story) {
+            return Ok(false);
+        }
+        if !args.interactive {
+            return Ok(true);
+        }
+        let site = source_domain(&story.url).unwrap_or_default();
+        crate::review::confirm(&format!("Remove \"{}\" ({})?", story.title, site))
+    };
+    let removed = remove(&mut data, &mut ask)?;
+    if removed.is_empty() {
+        println!("✓ No stories to prune in {}", path.display());
+        return Ok(());
+    }
+    for removed in &removed {
+        println!("  ✗ {} › {}", removed.topic, removed.story.title);
+    }
+    println!(
+        "🗑  Pruned {} story(ies); undo with: briefing prune --undo",
+        removed.len()
+    );
+    log.prunes.push(Prune {
+        at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        removed,
+    });
+    crate::retry::save(&path, &data, &settings)?;
+    log.save(&path)
+}
+
+/// Take out the stories `prune` says to, dropping topics left with no
+/// stories (sections of notes alone stay).
+fn remove(
+    data: &mut BriefingData,
+    prune: &mut impl FnMut(&Story) -> Result<bool>,
+) -> Result<Vec<Removed>> {
+    let mut removed = Vec::new();
+    for (topic_index, topic) in data.topics.iter_mut().enumerate() {
+        let mut kept = Vec::new();
+        for (index, story) in std::mem::take(&mut topic.stories).into_iter().enumerate() {
+            if prune(&story)? {
+                removed.push(Removed {
+                    topic: topic.title.clone(),
+                    topic_index,
+                    index,
+                    story,
+                });
+            } else {
+                kept.push(story);
+            }
+        }
+        topic.stories = kept;
+    }
+    let emptied: Vec<&str> = removed.iter().map(|r| r.topic.as_str()).collect();
+    data.topics.retain(|topic| {
+        !topic.stories.is_empty()
+            || topic.notes.is_some()
+            || !emptied.contains(&topic.title.as_str())
+    });
+    Ok(removed)
+}
+
+/// Put the stories of one prune back at their places, re-creating their
+/// topics if they were dropped. Returns how many.
+fn restore(data: &mut BriefingData, mut removed: Vec<Removed>) -> usize {
+    let count = removed.len();
+    // Topics in order, then stories in order, so each index is right once
+    // the ones before it are back
+    removed.sort_by_key(|r| (r.topic_index, r.index));
+    for r in removed {
+        let position = match data.topics.iter().position(|t| t.title == r.topic) {
+            Some(position) => position,
+            None => {
+                let position = r.topic_index.min(data.topics.len());
+                data.topics.insert(
+                    position,
+                    Topic {
+                        title: r.topic.clone(),
+                        stories: Vec::new(),
+                        connections: None,
+                        notes: None,
+                        rationale: None,
+                    },
+                );
+                position
+            }
+        };
+        let stories = &mut data.topics[position].stories;
+        stories.insert(r.index.min(stories.len()), r.story);
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::testing::story;
+    use shared::ShowInfo;
+
+    fn data() -> BriefingData {
+        let topic = |title: &str, stories| Topic {
+            title: title.to_string(),
+            stories,
+            connections: None,
+            notes: None,
+            rationale: None,
+        };
+        BriefingData::new(
+            ShowInfo::new("This Week in Tech", "twit", "TWiT"),
+            vec![
+                topic(
+                    "Apple",
+                    vec![
+                        story("Apple cuts Vision Pro price")
+                            .with_url("https://www.theverge.com/a")
+                            .build(),
+                        story("Rumor: foldable iPhone")
+                            .with_url("https://macrumors.com/b")
+                            .build(),
+                        story("Apple Watch alerts")
+                            .with_url("https://apple.com/c")
+                            .build(),
+                    ],
+                ),
+                topic(
+                    "Gossip",
+                    vec![story("Who's leaving")
+                        .with_url("https://news.gossip.example/d")
+                        .build()],
+                ),
+                topic(
+                    "AI",
+                    vec![story("New model").with_url("https://example.com/e").build()],
+                ),
+            ],
+        )
+    }
+
+    fn titles(data: &BriefingData) -> Vec<(String, Vec<String>)> {
+        data.topics
+            .iter()
+            .map(|t| {
+                let stories = t.stories.iter().map(|s| s.title.clone()).collect();
+                (t.title.clone(), stories)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_patterns_match_domain_or_title() {
+        let patterns = Patterns::new(&["gossip.example".to_string()], &["RUMOR".to_string()]);
+        assert!(patterns.matches(
+            &story("Who's leaving")
+                .with_url("https://news.gossip.example/d")
+                .build()
+        ));
+        assert!(patterns.matches(
+            &story("Rumor: foldable iPhone")
+                .with_url("https://macrumors.com/b")
+                .build()
+        ));
+        assert!(!patterns.matches(
+            &story("New model")
+                .with_url("https://notgossip.example/e")
+                .build()
+        ));
+        let www = Patterns::new(&["www.theverge.com".to_string()], &[]);
+        assert!(www.matches(
+            &story("Apple cuts Vision Pro price")
+                .with_url("https://theverge.com/a")
+                .build()
+        ));
+    }
+
+    #[test]
+    fn test_remove_then_restore() {
+        let mut data = data();
+        let original = titles(&data);
+        let patterns = Patterns::new(&["gossip.example".to_string()], &["rumor".to_string()]);
+        let removed = remove(&mut data, &mut |s: &Story| Ok(patterns.matches(s))).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(
+            titles(&data),
+            [
+                (
+                    "Apple".to_string(),
+                    vec![
+                        "Apple cuts Vision Pro price".to_string(),
+                        "Apple Watch alerts".to_string()
+                    ]
+                ),
+                ("AI".to_string(), vec!["New model".to_string()]),
+            ]
+        );
+
+        assert_eq!(restore(&mut data, removed), 2);
+        assert_eq!(titles(&data), original);
+    }
+}
@@ -0,0 +1,206 @@
+//! `briefing reconcile`: record which briefed stories made it on air.
+//!
+//! After the episode, pass the stories that were discussed: a file with one
+//! entry per line (a timestamps log from the show works as is), `-` for
+//! stdin, or a comma-separated list. Each entry is a story number like `2.3`
+//! (as in `briefing review`), a URL, or part of a story's title, optionally
+//! after a timestamp such as `00:41:07` or `[1:02:15]`. The discussed URLs
+//! are saved in the archived briefing. Next week's collect-stories offers
+//! the stories that weren't discussed again, in a "Held from last week"
+//! topic.
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use shared::archive::Archive;
+use shared::dedup::canonicalize_url;
+use shared::{BriefingData, Story};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+pub fn run(covered: &str, show: &str, date: Option<NaiveDate>) -> Result<()> {
+    let archive = Archive::open_default();
+    let Some(entry) = archive.find(show, date)? else {
+        match date {
+            Some(date) => bail!("No archived {} briefing for {}", show, date),
+            None => bail!("No archived {} briefings", show),
+        }
+    };
+    let mut data = archive.load(&entry)?;
+
+    let entries = read_entries(covered)?;
+    let (discussed, unmatched) = match_entries(&data, &entries);
+
+    println!(
+        "📋 {} {}: {} of {} stories discussed",
+        data.show.name,
+        entry.date,
+        discussed.len(),
+        data.topics.iter().map(|t| t.stories.len()).sum::<usize>()
+    );
+    for (i, topic) in data.topics.iter().enumerate() {
+        for (j, story) in topic.stories.iter().enumerate() {
+            let mark = if discussed.contains(&canonicalize_url(&story.url)) {
+                "✓"
+            } else {
+                "✗"
+            };
+            println!("  {} {}.{} {}", mark, i + 1, j + 1, story.title);
+        }
+    }
+    for line in &unmatched {
+        println!("  ? No story matches \"{}\"", line);
+    }
+
+    data.discussed = Some(discussed.into_iter().collect());
+    archive.store(&data, entry.date)?;
+    println!("\n✅ Recorded in {}", entry.path.display());
+    Ok(())
+}
+
+fn read_entries(covered: &str) -> Result<Vec<String>> {
+    let text = if covered == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        text
+    } else if Path::new(covered).is_file() {
+        std::fs::read_to_string(covered).with_context(|| format!("Failed to read {}", covered))?
+    } else {
+        covered.replace(',', "\n")
+    };
+    Ok(text
+        .lines()
+        .map(strip_timestamp)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// `"[00:41:07] - Apple earnings"` -> `"Apple earnings"`
+fn strip_timestamp(line: &str) -> &str {
+    let line = line.trim();
+    let rest = line.trim_start_matches('[');
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+        .unwrap_or(rest.len());
+    if !rest[..end].contains(':') {
+        return line;
+    }
+    rest[end..]
+        .trim_start_matches(']')
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '-' || c == '–')
+        .trim_end()
+}
+
+/// The canonical URLs of the stories `entries` refer to, and the entries
+/// that matched no story.
+fn match_entries(data: &BriefingData, entries: &[String]) -> (HashSet<String>, Vec<String>) {
+    let mut discussed = HashSet::new();
+    let mut unmatched = Vec::new();
+    for entry in entries {
+        match find_story(data, entry) {
+            Some(story) => {
+                discussed.insert(canonicalize_url(&story.url));
+            }
+            None => unmatched.push(entry.clone()),
+        }
+    }
+    (discussed, unmatched)
+}
+
+fn find_story<'a>(data: &'a BriefingData, entry: &str) -> Option<&'a Story> {
+    // Story number, e.g. 2.3
+    if let Some((topic, story)) = entry.split_once('.') {
+        if let (Ok(topic), Ok(story)) = (topic.parse::<usize>(), story.parse::<usize>()) {
+            return data
+                .topics
+                .get(topic.checked_sub(1)?)?
+                .stories
+                .get(story.checked_sub(1)?);
+        }
+    }
+
+    let mut stories = data.topics.iter().flat_map(|t| &t.stories);
+    if let Some(url) = entry.split_whitespace().find(|w| w.starts_with("http")) {
+        let url = canonicalize_url(url);
+        return stories.find(|s| canonicalize_url(&s.url) == url);
+    }
+
+    let entry = entry.to_lowercase();
+    stories.find(|s| {
+        let title = s.title.to_lowercase();
+        title.contains(&entry) || (!title.is_empty() && entry.contains(&title))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::testing::story;
+    use shared::{ShowInfo, Topic};
+
+    #[test]
+    fn test_strip_timestamp() {
+        assert_eq!(
+            strip_timestamp("[00:41:07] - Apple earnings"),
+            "Apple earnings"
+        );
+        assert_eq!(strip_timestamp("1:02:15 Pixel 10"), "Pixel 10");
+        assert_eq!(strip_timestamp("2.3"), "2.3");
+        assert_eq!(strip_timestamp("  Vision Pro  "), "Vision Pro");
+    }
+
+    #[test]
+    fn test_match_entries() {
+        let data = BriefingData::new(
+            ShowInfo::new("TWiT", "twit", "#twit"),
+            vec![
+                Topic {
+                    title: "Apple".to_string(),
+                    stories: vec![
+                        story("Apple Q1 earnings beat estimates")
+                            .with_url("https://a.com/earnings")
+                            .build(),
+                        story("Vision Pro 2 delayed")
+                            .with_url("https://a.com/vision")
+                            .build(),
+                    ],
+                    connections: None,
+                    notes: None,
+                    rationale: None,
+                },
+                Topic {
+                    title: "Google".to_string(),
+                    stories: vec![story("Pixel 10 review")
+                        .with_url("https://g.com/pixel")
+                        .build()],
+                    connections: None,
+                    notes: None,
+                    rationale: None,
+                },
+            ],
+        );
+        let entries: Vec<String> = [
+            "q1 earnings",
+            "https://www.g.com/pixel?utm_source=x",
+            "1.2",
+            "9.9",
+            "Something unplanned",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let (discussed, unmatched) = match_entries(&data, &entries);
+        assert_eq!(
+            discussed,
+            [
+                "https://a.com/earnings",
+                "https://g.com/pixel",
+                "https://a.com/vision"
+            ]
+            .map(String::from)
+            .into()
+        );
+        assert_eq!(unmatched, ["9.9", "Something unplanned"]);
+    }
+}
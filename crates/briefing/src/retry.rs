@@ -0,0 +1,153 @@
+//! `briefing retry-failed`: fetch the articles a collection couldn't, and
+//! patch them into its story file.
+//!
+//! collect-stories saves the URLs that failed or hit a paywall with each
+//! story file (see `shared::triage`). This fetches just those again,
+//! bypassing the page cache, summarizes the ones that come back, replaces
+//! their excerpt or "Summary not available" stories in the story file and
+//! regenerates the org draft. Whatever still fails stays in the retry file
+//! for next time.
+
+use anyhow::{Context, Result};
+use shared::briefing::BriefingGenerator;
+use shared::clustering::StoryDetails;
+use shared::org_template::OrgTemplate;
+use shared::prompts::{Prompt, PromptKind};
+use shared::settings::Settings;
+use shared::triage::{FailedFetch, RetryFile};
+use shared::{ArticleContent, ClaudeSummarizer, ContentExtractor, ExtractionResult, Summary};
+use std::path::{Path, PathBuf};
+
+pub async fn run(file: Option<PathBuf>) -> Result<()> {
+    let path = match file {
+        Some(path) => path,
+        None => crate::review::latest_story_file()?,
+    };
+    let Some(mut retry) = RetryFile::load(&path)? else {
+        println!("✓ No failed fetches recorded for {}", path.display());
+        return Ok(());
+    };
+    let mut data = shared::load_stories(&path)?;
+    let mut settings = Settings::load()?;
+    settings.apply_show(&data.show.slug);
+    settings.briefing.org_template = OrgTemplate::load(&data.show.slug)?;
+
+    println!(
+        "🔁 Fetching {} article(s) again for {}...",
+        retry.failures.len(),
+        path.display()
+    );
+    let extractor = ContentExtractor::new()?
+        .with_fetch_settings(settings.fetch.clone())
+        .with_refresh(true);
+    let urls: Vec<String> = retry.failures.iter().map(|f| f.url.clone()).collect();
+    let results = extractor.fetch_articles_parallel(urls).await;
+    collect_stories::remember_extractions(&results);
+
+    let summarizer = if shared::llm::credentials_available() {
+        let prompt = Prompt::load(PromptKind::Summarize, &data.show, &settings.prompts)?;
+        Some(
+            ClaudeSummarizer::new()?
+                .with_prompt(prompt)
+                .with_length(settings.prompts.summary_length(&data.show)),
+        )
+    } else {
+        None
+    };
+
+    let mut still_failing = Vec::new();
+    let mut patched = 0;
+    for failure in std::mem::take(&mut retry.failures) {
+        let result = results
+            .iter()
+            .find(|(url, _)| *url == failure.url)
+            .map(|(_, result)| result);
+        let content = match result {
+            Some(ExtractionResult::Success(content)) => content,
+            _ => {
+                still_failing.push(match extractor.failure(&failure.url) {
+                    Some(now) => FailedFetch::new(&failure.url, &failure.title, now),
+                    None => failure,
+                });
+                continue;
+            }
+        };
+        let summary = match &summarizer {
+            Some(summarizer) => match summarizer
+                .summarize_story(&failure.title, &content.text)
+                .await
+            {
+                Ok(summary) => summary,
+                Err(e) => {
+                    println!("⚠ {}: summary failed: {:#}", failure.title, e);
+                    still_failing.push(failure);
+                    continue;
+                }
+            },
+            None => shared::summarizer::extractive_summary(&content.text),
+        };
+        if patch(&mut data, &failure.url, content, summary) {
+            println!("✓ {}", failure.title);
+            patched += 1;
+        }
+    }
+
+    println!(
+        "✓ Fetched {}/{} article(s)",
+        patched,
+        patched + still_failing.len()
+    );
+    if patched > 0 {
+        save(&path, &data, &settings)?;
+    }
+    retry.failures = still_failing;
+    retry.save()?;
+    if !retry.failures.is_empty() {
+        println!("\n⚠ Still failing:");
+        print!("{}", shared::triage::report(&retry.failures));
+    }
+    Ok(())
+}
+
+/// Give the story for `url` the fetched article's summary and details.
+/// False if the story is no longer in the file.
+fn patch(
+    data: &mut shared::BriefingData,
+    url: &str,
+    content: &ArticleContent,
+    summary: Summary,
+) -> bool {
+    let Some(story) = data
+        .topics
+        .iter_mut()
+        .flat_map(|topic| &mut topic.stories)
+        .find(|story| story.url == url)
+    else {
+        return false;
+    };
+    story.summary = summary;
+    story.details = Some(StoryDetails::from_article(url, content));
+    if let Some(published) = &content.published_date {
+        story.created = published.clone();
+    }
+    true
+}
+
+pub(crate) fn save(path: &Path, data: &shared::BriefingData, settings: &Settings) -> Result<()> {
+    let json = serde_json::to_string_pretty(data).context("Failed to serialize briefing data")?;
+    shared::io::save_file(path, json)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("💾 Updated {}", path.display());
+
+    let show_date = crate::review::show_date_for(path, data, settings)?;
+    let mut org = BriefingGenerator::generate_org_mode_with(
+        &data.topics,
+        &data.show.name,
+        show_date,
+        &settings.briefing,
+    );
+    org.push_str(&shared::discovery::org_section(&data.missed));
+    let org_path = BriefingGenerator::save_org_mode(&org, &data.show.slug, show_date)?;
+    println!("✓ Regenerated {}", org_path.display());
+    Ok(())
+}
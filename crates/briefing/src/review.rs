@@ -0,0 +1,1065 @@
+//! `briefing review`: curate a collected story file before editing the org
+//! draft.
+//!
+//! Loads the `BriefingData` JSON that collect-stories saves next to the org
+//! file and opens it in a terminal UI: the topics and stories on the left,
+//! the selected story's text on the right. Stories can be included or
+//! excluded, starred, reordered, moved between topics, assigned to hosts and
+//! re-summarized. Saving writes the JSON back (without the excluded stories)
+//! and regenerates the org draft from it.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveTime};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use shared::briefing::BriefingGenerator;
+use shared::org_template::OrgTemplate;
+use shared::prompts::{Prompt, PromptKind};
+use shared::schedule::Schedule;
+use shared::settings::Settings;
+use shared::timezone::{Tz, ZoneExt};
+use shared::{
+    BriefingData, ClaudeSummarizer, ContentExtractor, ExtractionResult, Story, Summary, Topic,
+};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const HELP: &str = "\
+↑/↓ or k/j     select a topic or story
+space or x     include / exclude the story
+s              star / unstar it (starred stories lead their topic)
+K/J            move the story, or the topic, up / down
+m              move the story to another topic (number or new title)
+n              rename the topic
+a              assign the story to hosts (empty clears them)
+r              re-fetch and re-summarize the story
+PgUp/PgDn      scroll the preview
+w              save the story file and regenerate the org draft
+q              quit (asks first if there are unsaved changes)
+?              show or hide this help";
+
+/// A story, by topic and place in it, both from 0. Shown from 1, as `2.3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pos {
+    topic: usize,
+    story: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Topic(usize),
+    New(String),
+}
+
+impl Target {
+    /// A topic number, counting from 1, or the title of a topic to move to
+    /// (made if there isn't one).
+    fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            bail!("give a topic number or a new topic title");
+        }
+        Ok(match input.parse::<usize>() {
+            Ok(n) => Target::Topic(n.checked_sub(1).context("numbers start at 1")?),
+            Err(_) => Target::New(input.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReviewStory {
+    story: Story,
+    included: bool,
+}
+
+#[derive(Debug, Clone)]
+struct ReviewTopic {
+    title: String,
+    stories: Vec<ReviewStory>,
+    connections: Option<String>,
+    notes: Option<String>,
+}
+
+/// Editing state for one story file. Positions are validated here so the
+/// prompt loop only has to report errors.
+struct Review {
+    topics: Vec<ReviewTopic>,
+    dirty: bool,
+}
+
+impl Review {
+    fn new(topics: Vec<Topic>) -> Self {
+        Self {
+            topics: topics
+                .into_iter()
+                .map(|t| ReviewTopic {
+                    title: t.title,
+                    stories: t
+                        .stories
+                        .into_iter()
+                        .map(|story| ReviewStory {
+                            story,
+                            included: true,
+                        })
+                        .collect(),
+                    connections: t.connections,
+                    notes: t.notes,
+                })
+                .collect(),
+            dirty: false,
+        }
+    }
+
+    fn story(&self, pos: Pos) -> Result<&ReviewStory> {
+        self.topics
+            .get(pos.topic)
+            .and_then(|t| t.stories.get(pos.story))
+            .with_context(|| format!("no story {}.{}", pos.topic + 1, pos.story + 1))
+    }
+
+    fn story_mut(&mut self, pos: Pos) -> Result<&mut ReviewStory> {
+        self.topics
+            .get_mut(pos.topic)
+            .and_then(|t| t.stories.get_mut(pos.story))
+            .with_context(|| format!("no story {}.{}", pos.topic + 1, pos.story + 1))
+    }
+
+    fn check_topic(&self, topic: usize) -> Result<()> {
+        if topic >= self.topics.len() {
+            bail!("no topic {}", topic + 1);
+        }
+        Ok(())
+    }
+
+    fn toggle(&mut self, pos: Pos) -> Result<bool> {
+        let story = self.story_mut(pos)?;
+        story.included = !story.included;
+        let included = story.included;
+        self.dirty = true;
+        Ok(included)
+    }
+
+    fn star(&mut self, pos: Pos) -> Result<bool> {
+        let story = &mut self.story_mut(pos)?.story;
+        story.starred = !story.starred;
+        let starred = story.starred;
+        self.dirty = true;
+        Ok(starred)
+    }
+
+    /// Swap a story with the one above or below it.
+    fn shift(&mut self, pos: Pos, up: bool) -> Result<()> {
+        self.story(pos)?;
+        let stories = &mut self.topics[pos.topic].stories;
+        let other = if up {
+            pos.story.checked_sub(1)
+        } else {
+            Some(pos.story + 1).filter(|&i| i < stories.len())
+        };
+        if let Some(other) = other {
+            stories.swap(pos.story, other);
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    fn shift_topic(&mut self, topic: usize, up: bool) -> Result<()> {
+        self.check_topic(topic)?;
+        let other = if up {
+            topic.checked_sub(1)
+        } else {
+            Some(topic + 1).filter(|&i| i < self.topics.len())
+        };
+        if let Some(other) = other {
+            self.topics.swap(topic, other);
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Move a story to the end of another topic. Returns the topic's index.
+    fn move_story(&mut self, pos: Pos, target: Target) -> Result<usize> {
+        self.story(pos)?;
+        let dest = match target {
+            Target::Topic(t) => {
+                self.check_topic(t)?;
+                t
+            }
+            Target::New(title) => match self.topics.iter().position(|t| t.title == title) {
+                Some(t) => t,
+                None => {
+                    self.topics.push(ReviewTopic {
+                        title,
+                        stories: Vec::new(),
+                        connections: None,
+                        notes: None,
+                    });
+                    self.topics.len() - 1
+                }
+            },
+        };
+        if dest != pos.topic {
+            let story = self.topics[pos.topic].stories.remove(pos.story);
+            self.topics[dest].stories.push(story);
+            self.dirty = true;
+        }
+        Ok(dest)
+    }
+
+    fn rename(&mut self, topic: usize, title: String) -> Result<()> {
+        self.check_topic(topic)?;
+        self.topics[topic].title = title;
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn assign(&mut self, pos: Pos, hosts: Vec<String>) -> Result<()> {
+        self.story_mut(pos)?.story.hosts = hosts;
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn set_summary(&mut self, pos: Pos, summary: Summary) -> Result<()> {
+        self.story_mut(pos)?.story.summary = summary;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// The curated topics: excluded stories and topics left empty are
+    /// dropped. Sections with notes stay, and starred stories move to the
+    /// top of their topic.
+    fn to_topics(&self) -> Vec<Topic> {
+        let mut topics: Vec<Topic> = self
+            .topics
+            .iter()
+            .map(|t| Topic {
+                title: t.title.clone(),
+                stories: t
+                    .stories
+                    .iter()
+                    .filter(|s| s.included)
+                    .map(|s| s.story.clone())
+                    .collect(),
+                connections: t.connections.clone(),
+                notes: t.notes.clone(),
+                rationale: None,
+            })
+            .filter(|t| !t.stories.is_empty() || t.notes.is_some())
+            .collect();
+        shared::clustering::starred_first(&mut topics);
+        topics
+    }
+
+    /// A line of the story list: a topic heading or one of its stories.
+    fn label(&self, row: Row) -> String {
+        match row {
+            Row::Topic(ti) => format!("{:>2}. {}", ti + 1, self.topics[ti].title),
+            Row::Story(pos) => {
+                let s = &self.topics[pos.topic].stories[pos.story];
+                let mark = if s.included { "x" } else { " " };
+                let star = if s.story.starred { "★ " } else { "" };
+                let note = match s.story.summary {
+                    Summary::Failed(_) => "  (summary failed)",
+                    Summary::Insufficient => "  (no summary)",
+                    _ => "",
+                };
+                let hosts = if s.story.hosts.is_empty() {
+                    String::new()
+                } else {
+                    format!("  @{}", s.story.hosts.join(" @"))
+                };
+                format!(
+                    "    {}.{:<2} [{}] {}{}{}{}",
+                    pos.topic + 1,
+                    pos.story + 1,
+                    mark,
+                    star,
+                    s.story.title,
+                    hosts,
+                    note
+                )
+            }
+        }
+    }
+
+    /// Every topic, each followed by its stories, in list order.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (topic, t) in self.topics.iter().enumerate() {
+            rows.push(Row::Topic(topic));
+            rows.extend((0..t.stories.len()).map(|story| Row::Story(Pos { topic, story })));
+        }
+        rows
+    }
+}
+
+/// A line of the story list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Topic(usize),
+    Story(Pos),
+}
+
+impl Row {
+    fn topic(self) -> usize {
+        match self {
+            Row::Topic(topic) => topic,
+            Row::Story(pos) => pos.topic,
+        }
+    }
+}
+
+/// What the bottom line is asking for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Browse,
+    Help,
+    Input(Field, String),
+    ConfirmQuit,
+}
+
+/// What an input line is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    MoveTo(Pos),
+    Rename(usize),
+    Hosts(Pos),
+}
+
+impl Field {
+    fn prompt(self) -> &'static str {
+        match self {
+            Field::MoveTo(_) => "Move to topic (number or new title): ",
+            Field::Rename(_) => "Rename topic: ",
+            Field::Hosts(_) => "Hosts: ",
+        }
+    }
+}
+
+/// What a key asks [`run`] to do that the UI can't do by itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Resummarize(Pos),
+    Save,
+    Quit,
+}
+
+/// The review screen: the stories being edited, the selected line and what
+/// the bottom line is showing.
+struct App {
+    review: Review,
+    selected: usize,
+    mode: Mode,
+    status: String,
+    scroll: u16,
+}
+
+impl App {
+    fn new(review: Review) -> Self {
+        Self {
+            review,
+            selected: 0,
+            mode: Mode::Browse,
+            status: "? for help".to_string(),
+            scroll: 0,
+        }
+    }
+
+    fn row(&self) -> Option<Row> {
+        self.review.rows().get(self.selected).copied()
+    }
+
+    fn story_pos(&self) -> Result<Pos> {
+        match self.row() {
+            Some(Row::Story(pos)) => Ok(pos),
+            _ => bail!("select a story first"),
+        }
+    }
+
+    /// Select `row`, e.g. a story that was just moved.
+    fn select(&mut self, row: Row) {
+        if let Some(i) = self.review.rows().iter().position(|r| *r == row) {
+            self.selected = i;
+        }
+        self.scroll = 0;
+    }
+
+    /// Handle a key press, returning what's left for the caller to do.
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => match self.browse(key) {
+                Ok(action) => action,
+                Err(e) => {
+                    self.status = format!("{:#}", e);
+                    None
+                }
+            },
+            Mode::Help => None,
+            Mode::ConfirmQuit => match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(Action::Quit),
+                _ => {
+                    self.status = "Not quitting".to_string();
+                    None
+                }
+            },
+            Mode::Input(field, mut text) => {
+                match key.code {
+                    KeyCode::Enter => {
+                        if let Err(e) = self.submit(field, &text) {
+                            self.status = format!("{:#}", e);
+                        }
+                    }
+                    KeyCode::Esc => self.status = "Cancelled".to_string(),
+                    KeyCode::Backspace => {
+                        text.pop();
+                        self.mode = Mode::Input(field, text);
+                    }
+                    KeyCode::Char(c) => {
+                        text.push(c);
+                        self.mode = Mode::Input(field, text);
+                    }
+                    _ => self.mode = Mode::Input(field, text),
+                }
+                None
+            }
+        }
+    }
+
+    fn browse(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let rows = self.review.rows();
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Up if shift => self.shift(true)?,
+            KeyCode::Down if shift => self.shift(false)?,
+            KeyCode::Char('K') => self.shift(true)?,
+            KeyCode::Char('J') => self.shift(false)?,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                self.scroll = 0;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(rows.len().saturating_sub(1));
+                self.scroll = 0;
+            }
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = rows.len().saturating_sub(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::Char(' ') | KeyCode::Char('x') => {
+                let included = self.review.toggle(self.story_pos()?)?;
+                self.status = if included { "Included" } else { "Excluded" }.to_string();
+            }
+            KeyCode::Char('s') => {
+                let starred = self.review.star(self.story_pos()?)?;
+                self.status = if starred { "Starred" } else { "Unstarred" }.to_string();
+            }
+            KeyCode::Char('m') => {
+                let pos = self.story_pos()?;
+                self.mode = Mode::Input(Field::MoveTo(pos), String::new());
+            }
+            KeyCode::Char('n') => {
+                let topic = self.row().context("nothing to rename")?.topic();
+                let title = self.review.topics[topic].title.clone();
+                self.mode = Mode::Input(Field::Rename(topic), title);
+            }
+            KeyCode::Char('a') => {
+                let pos = self.story_pos()?;
+                let hosts = self.review.story(pos)?.story.hosts.join(" ");
+                self.mode = Mode::Input(Field::Hosts(pos), hosts);
+            }
+            KeyCode::Char('r') => return Ok(Some(Action::Resummarize(self.story_pos()?))),
+            KeyCode::Char('w') => return Ok(Some(Action::Save)),
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if !self.review.dirty {
+                    return Ok(Some(Action::Quit));
+                }
+                self.mode = Mode::ConfirmQuit;
+            }
+            KeyCode::Char('?') => self.mode = Mode::Help,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Move the selected story within its topic, or the selected topic
+    /// among the others, keeping it selected.
+    fn shift(&mut self, up: bool) -> Result<()> {
+        match self.row().context("nothing selected")? {
+            Row::Story(pos) => {
+                self.review.shift(pos, up)?;
+                let len = self.review.topics[pos.topic].stories.len();
+                let story = if up {
+                    pos.story.saturating_sub(1)
+                } else {
+                    (pos.story + 1).min(len - 1)
+                };
+                self.select(Row::Story(Pos { story, ..pos }));
+            }
+            Row::Topic(topic) => {
+                self.review.shift_topic(topic, up)?;
+                let topic = if up {
+                    topic.saturating_sub(1)
+                } else {
+                    (topic + 1).min(self.review.topics.len() - 1)
+                };
+                self.select(Row::Topic(topic));
+            }
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self, field: Field, text: &str) -> Result<()> {
+        match field {
+            Field::MoveTo(pos) => {
+                let topic = self.review.move_story(pos, Target::parse(text)?)?;
+                let story = self.review.topics[topic].stories.len() - 1;
+                self.status = format!("Moved to {}", self.review.topics[topic].title);
+                self.select(Row::Story(Pos { topic, story }));
+            }
+            Field::Rename(topic) => {
+                let title = text.trim();
+                if title.is_empty() {
+                    bail!("a topic needs a title");
+                }
+                self.review.rename(topic, title.to_string())?;
+                self.status = "Renamed".to_string();
+            }
+            Field::Hosts(pos) => {
+                let hosts = text.split_whitespace().map(|h| h.to_lowercase()).collect();
+                self.review.assign(pos, hosts)?;
+                self.status = "Hosts set".to_string();
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, bottom] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .areas(main);
+
+        let rows = self.review.rows();
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|&row| {
+                let label = self.review.label(row);
+                let style = match row {
+                    Row::Topic(_) => Style::new().add_modifier(Modifier::BOLD),
+                    Row::Story(pos)
+                        if !self.review.topics[pos.topic].stories[pos.story].included =>
+                    {
+                        Style::new().add_modifier(Modifier::DIM | Modifier::CROSSED_OUT)
+                    }
+                    Row::Story(_) => Style::new(),
+                };
+                ListItem::new(Line::styled(label, style))
+            })
+            .collect();
+        let title = if self.review.dirty {
+            " Stories (unsaved) "
+        } else {
+            " Stories "
+        };
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().reversed());
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, list_area, &mut state);
+
+        let text = match self.row() {
+            Some(Row::Story(pos)) => {
+                preview(&self.review.topics[pos.topic].stories[pos.story].story)
+            }
+            Some(Row::Topic(topic)) => {
+                let t = &self.review.topics[topic];
+                let mut text = format!("{}\n\n{} stories\n", t.title, t.stories.len());
+                if let Some(connections) = &t.connections {
+                    text.push_str(&format!("\n{}\n", connections));
+                }
+                text
+            }
+            None => String::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(Block::bordered().title(" Preview ")),
+            preview_area,
+        );
+
+        let line = match &self.mode {
+            Mode::Input(field, text) => format!("{}{}▏", field.prompt(), text),
+            Mode::ConfirmQuit => "Discard unsaved changes? [y/N]".to_string(),
+            Mode::Browse | Mode::Help => self.status.clone(),
+        };
+        frame.render_widget(Paragraph::new(line), bottom);
+
+        if self.mode == Mode::Help {
+            let area = centered(frame.area(), 64, HELP.lines().count() as u16 + 2);
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(HELP).block(Block::bordered().title(" Keys (any key closes) ")),
+                area,
+            );
+        }
+    }
+}
+
+/// A `width` by `height` box in the middle of `area`, clipped to it.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn preview(story: &Story) -> String {
+    let mut out = format!("{}\n{}\n", story.title, story.url);
+    if !story.created.is_empty() {
+        out.push_str(&format!("{}\n", story.created));
+    }
+    out.push('\n');
+    match &story.summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        } => {
+            for q in quotes {
+                out.push_str(&format!("{}\n\n", q));
+            }
+            out.push_str(&format!("{}\n\n{}\n", lede, nutgraf));
+            if let Some(numbers) = by_the_numbers {
+                out.push_str(&format!("\nBy the numbers: {}\n", numbers));
+            }
+            if let Some(reaction) = reaction {
+                out.push_str(&format!("\nReaction: {}\n", reaction));
+            }
+        }
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        } => {
+            for q in quotes {
+                out.push_str(&format!("{}\n\n", q));
+            }
+            out.push_str(&format!("The product: {}\n", the_product));
+            for (label, value) in [
+                ("Cost", cost),
+                ("Availability", availability),
+                ("Platforms", platforms),
+            ] {
+                if !value.is_empty() {
+                    out.push_str(&format!("{}: {}\n", label, value));
+                }
+            }
+            if let Some(reaction) = reaction {
+                out.push_str(&format!("Reaction: {}\n", reaction));
+            }
+        }
+        Summary::Insufficient => out.push_str("Summary not available\n"),
+        Summary::Failed(reason) => out.push_str(&format!("Summary failed: {}\n", reason)),
+    }
+    out
+}
+
+/// The newest story file, for when no path is given.
+pub(crate) fn latest_story_file() -> Result<PathBuf> {
+    shared::list_story_files()?
+        .into_iter()
+        .next()
+        .map(|(path, _)| path)
+        .context("No story files found; run collect-stories first")
+}
+
+/// Show date encoded in a `{slug}-{YYYY-MM-DD}.json` filename, at
+/// midnight in the show's time zone. Without one, the show's next episode
+/// per `[[shows]]`, or today.
+pub(crate) fn show_date_for(
+    path: &Path,
+    data: &BriefingData,
+    settings: &Settings,
+) -> Result<DateTime<Tz>> {
+    let schedule = match shared::shows::find(&settings.shows, &data.show.slug) {
+        Ok(show) => Some(Schedule::new(show, &settings.schedule)?),
+        Err(_) => None,
+    };
+    let zone = schedule
+        .as_ref()
+        .map_or_else(shared::timezone::local, |s| *s.zone());
+    let date = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .and_then(|s| shared::archive::parse_filename(s).1);
+    Ok(match (date, &schedule) {
+        (Some(date), _) => zone.at(date.and_time(NaiveTime::MIN)),
+        (None, Some(schedule)) => schedule.next_date(schedule.now()),
+        (None, None) => zone.now(),
+    })
+}
+
+pub(crate) fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes"))
+}
+
+async fn resummarize(story: &Story, data: &BriefingData, settings: &Settings) -> Result<Summary> {
+    let extractor = ContentExtractor::new()?.with_fetch_settings(settings.fetch.clone());
+    let text = match extractor.fetch_article_content(&story.url).await {
+        ExtractionResult::Success(content) => content.text,
+        ExtractionResult::Paywalled => bail!("article is paywalled"),
+        ExtractionResult::Skipped => bail!("a domain rule or robots.txt skips this site"),
+        ExtractionResult::OutOfTime => bail!("the fetch time budget ran out"),
+        ExtractionResult::Failed(reason) => bail!("fetch failed: {}", reason),
+    };
+    let prompt = Prompt::load(PromptKind::Summarize, &data.show, &settings.prompts)?;
+    let summarizer = ClaudeSummarizer::new()?
+        .with_prompt(prompt)
+        .with_length(settings.prompts.summary_length(&data.show));
+    summarizer.summarize_story(&story.title, &text).await
+}
+
+/// Write the kept stories back to `path` and regenerate the org draft,
+/// returning what was written for the status line.
+fn save(
+    path: &Path,
+    data: &mut BriefingData,
+    review: &mut Review,
+    settings: &Settings,
+) -> Result<String> {
+    let topics = review.to_topics();
+    if topics.is_empty() {
+        bail!("every story is excluded; include at least one before saving");
+    }
+    data.topics = topics;
+
+    let json = serde_json::to_string_pretty(data).context("Failed to serialize briefing data")?;
+    shared::io::save_file(path, json)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let show_date = show_date_for(path, data, settings)?;
+    let mut org = BriefingGenerator::generate_org_mode_with(
+        &data.topics,
+        &data.show.name,
+        show_date,
+        &settings.briefing,
+    );
+    org.push_str(&shared::discovery::org_section(&data.missed));
+    let org_path = BriefingGenerator::save_org_mode(&org, &data.show.slug, show_date)?;
+
+    review.dirty = false;
+    Ok(format!(
+        "✓ Saved {} and regenerated {}",
+        path.display(),
+        org_path.display()
+    ))
+}
+
+pub async fn run(file: Option<PathBuf>) -> Result<()> {
+    let path = match file {
+        Some(path) => path,
+        None => latest_story_file()?,
+    };
+    let mut data = shared::load_stories(&path)?;
+    let mut settings = Settings::load()?;
+    settings.apply_show(&data.show.slug);
+    settings.briefing.org_template = OrgTemplate::load(&data.show.slug)?;
+    let mut app = App::new(Review::new(std::mem::take(&mut data.topics)));
+    app.status = format!(
+        "Reviewing {} ({}), ? for help",
+        data.show.name,
+        path.display()
+    );
+
+    let mut terminal = ratatui::try_init().context("Failed to start the terminal UI")?;
+    let result = event_loop(&mut terminal, &mut app, &path, &mut data, &settings).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    path: &Path,
+    data: &mut BriefingData,
+    settings: &Settings,
+) -> Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        // Reading blocks, so keep it off the runtime's worker threads
+        let event = tokio::task::spawn_blocking(event::read).await??;
+        let Event::Key(key) = event else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.handle_key(key) {
+            None => {}
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::Save) => {
+                app.status = match save(path, data, &mut app.review, settings) {
+                    Ok(message) => message,
+                    Err(e) => format!("{:#}", e),
+                };
+            }
+            Some(Action::Resummarize(pos)) => {
+                let story = app.review.story(pos)?.story.clone();
+                app.status = format!("🤖 Re-summarizing {}...", story.title);
+                terminal.draw(|frame| app.draw(frame))?;
+                app.status = match resummarize(&story, data, settings).await {
+                    Ok(summary) => {
+                        app.review.set_summary(pos, summary)?;
+                        "✓ Re-summarized".to_string()
+                    }
+                    Err(e) => format!("{:#}", e),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use shared::testing::story;
+
+    fn review() -> Review {
+        Review::new(vec![
+            Topic {
+                title: "Apple".to_string(),
+                stories: vec![story("a1").build(), story("a2").build()],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Google".to_string(),
+                stories: vec![story("g1").build()],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ])
+    }
+
+    fn titles(topics: &[Topic]) -> Vec<(String, Vec<String>)> {
+        topics
+            .iter()
+            .map(|t| {
+                (
+                    t.title.clone(),
+                    t.stories.iter().map(|s| s.title.clone()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn press(app: &mut App, keys: &str) -> Option<Action> {
+        let mut action = None;
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+            action = app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+        action
+    }
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(Target::parse("1").unwrap(), Target::Topic(0));
+        assert_eq!(
+            Target::parse(" AI Policy ").unwrap(),
+            Target::New("AI Policy".to_string())
+        );
+        assert!(Target::parse("0").is_err());
+        assert!(Target::parse("  ").is_err());
+    }
+
+    #[test]
+    fn test_exclude_and_move_drop_empty_topics() {
+        let mut r = review();
+        assert!(!r.toggle(Pos { topic: 0, story: 0 }).unwrap());
+        r.move_story(Pos { topic: 1, story: 0 }, Target::Topic(0))
+            .unwrap();
+        assert!(r.dirty);
+
+        assert_eq!(
+            titles(&r.to_topics()),
+            vec![(
+                "Apple".to_string(),
+                vec!["a2".to_string(), "g1".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_reorder_and_new_topic() {
+        let mut r = review();
+        r.shift(Pos { topic: 0, story: 1 }, true).unwrap();
+        // Already at the bottom: no-op rather than an error
+        r.shift(Pos { topic: 0, story: 1 }, false).unwrap();
+        let dest = r
+            .move_story(Pos { topic: 0, story: 0 }, Target::New("Chips".to_string()))
+            .unwrap();
+        assert_eq!(dest, 2);
+        r.shift_topic(2, true).unwrap();
+        r.rename(0, "Apple Inc".to_string()).unwrap();
+
+        assert_eq!(
+            titles(&r.to_topics()),
+            vec![
+                ("Apple Inc".to_string(), vec!["a1".to_string()]),
+                ("Chips".to_string(), vec!["a2".to_string()]),
+                ("Google".to_string(), vec!["g1".to_string()]),
+            ]
+        );
+        assert!(r.story(Pos { topic: 5, story: 0 }).is_err());
+        assert!(r.shift_topic(9, true).is_err());
+    }
+
+    #[test]
+    fn test_starred_stories_lead_their_topic() {
+        let mut r = review();
+        assert!(r.star(Pos { topic: 0, story: 1 }).unwrap());
+        assert_eq!(
+            r.label(Row::Story(Pos { topic: 0, story: 1 })),
+            "    1.2  [x] ★ a2  (no summary)"
+        );
+        assert_eq!(
+            titles(&r.to_topics())[0],
+            (
+                "Apple".to_string(),
+                vec!["a2".to_string(), "a1".to_string()]
+            )
+        );
+        assert!(!r.star(Pos { topic: 0, story: 1 }).unwrap());
+    }
+
+    #[test]
+    fn test_keys_edit_the_selected_story() {
+        let mut app = App::new(review());
+        // Topic heading, then a1: exclude it, star it and give it hosts
+        assert_eq!(press(&mut app, "jxs"), None);
+        assert_eq!(press(&mut app, "aLeo andy\n"), None);
+        let a1 = &app.review.story(Pos { topic: 0, story: 0 }).unwrap().story;
+        assert_eq!(a1.hosts, vec!["leo", "andy"]);
+        assert!(a1.starred);
+        assert!(
+            !app.review
+                .story(Pos { topic: 0, story: 0 })
+                .unwrap()
+                .included
+        );
+
+        // Move a1 to a new topic; it stays selected there
+        press(&mut app, "mChips\n");
+        assert_eq!(app.row(), Some(Row::Story(Pos { topic: 2, story: 0 })));
+        assert_eq!(app.status, "Moved to Chips");
+        assert_eq!(
+            press(&mut app, "r"),
+            Some(Action::Resummarize(Pos { topic: 2, story: 0 }))
+        );
+        assert_eq!(press(&mut app, "w"), Some(Action::Save));
+    }
+
+    #[test]
+    fn test_keys_reorder_and_rename() {
+        let mut app = App::new(review());
+        // Move a2 above a1, following it
+        press(&mut app, "jjK");
+        assert_eq!(app.row(), Some(Row::Story(Pos { topic: 0, story: 0 })));
+        // Move Google above Apple, then rename it
+        press(&mut app, "jjK");
+        assert_eq!(app.row(), Some(Row::Topic(0)));
+        press(&mut app, "n");
+        for _ in 0..2 {
+            app.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        }
+        press(&mut app, "LE\n");
+        assert_eq!(
+            titles(&app.review.to_topics()),
+            vec![
+                ("GoogLE".to_string(), vec!["g1".to_string()]),
+                (
+                    "Apple".to_string(),
+                    vec!["a2".to_string(), "a1".to_string()]
+                ),
+            ]
+        );
+
+        // Story keys on a topic heading say so instead of acting
+        press(&mut app, "x");
+        assert_eq!(app.status, "select a story first");
+    }
+
+    #[test]
+    fn test_quit_asks_about_unsaved_changes() {
+        let mut app = App::new(review());
+        assert_eq!(press(&mut app, "q"), Some(Action::Quit));
+
+        press(&mut app, "jx");
+        assert_eq!(press(&mut app, "q"), None);
+        assert_eq!(app.mode, Mode::ConfirmQuit);
+        assert_eq!(press(&mut app, "n"), None);
+        assert_eq!(app.mode, Mode::Browse);
+        assert_eq!(press(&mut app, "qy"), Some(Action::Quit));
+    }
+
+    #[test]
+    fn test_draw_lists_stories_and_previews_the_selection() {
+        let mut app = App::new(review());
+        press(&mut app, "jjs");
+        let shown = screen(&app);
+        assert!(shown.contains("Stories (unsaved)"));
+        assert!(shown.contains(" 1. Apple"));
+        assert!(shown.contains("1.2  [x] ★ a2"));
+        assert!(shown.contains(" 2. Google"));
+        assert!(shown.contains("https://example.com/a2"));
+        assert!(shown.contains("Starred"));
+
+        press(&mut app, "?");
+        assert!(screen(&app).contains("Keys (any key closes)"));
+        press(&mut app, " ");
+        assert_eq!(app.mode, Mode::Browse);
+    }
+}
@@ -0,0 +1,300 @@
+//! `briefing run`: the whole week in one command, from bookmarks to the
+//! uploaded briefing.
+//!
+//! The stages run in order and each leaves its results on disk: the
+//! extraction and summary caches, the story file, the org draft, and the
+//! HTML and CSV. `--from` starts at a later stage using what an earlier run
+//! left behind, and `--to` stops early, so a rerun after a bad clustering
+//! or a late edit only repeats what it has to.
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use collect_stories::StopAfter;
+use shared::briefing::BriefingGenerator;
+use shared::org_template::OrgTemplate;
+use shared::settings::Settings;
+use shared::{BriefingData, Topic};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Stage {
+    /// Fetch the show's bookmarks from Raindrop.io
+    Collect,
+    /// Fetch the articles (again, when starting here)
+    Extract,
+    /// Summarize the articles (again, when starting here)
+    Summarize,
+    /// Group the latest stories into topics and save a new story file
+    Cluster,
+    /// Write the org draft from the latest story file
+    Org,
+    /// Open the org draft in $VISUAL or $EDITOR, or wait for Enter
+    Edit,
+    /// Write the HTML briefing and links CSV
+    Prepare,
+    /// Upload the briefing
+    Upload,
+}
+
+impl Stage {
+    fn name(self) -> String {
+        self.to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Where collection stops for a run that ends at `self`.
+    fn stop_after(self) -> Option<StopAfter> {
+        match self {
+            Stage::Collect => Some(StopAfter::Collect),
+            Stage::Extract => Some(StopAfter::Extract),
+            Stage::Summarize => Some(StopAfter::Summarize),
+            Stage::Cluster => Some(StopAfter::Cluster),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Show to run, by its slug in `[[shows]]`
+    #[arg(short, long)]
+    pub show: String,
+
+    /// First stage to run; the stages before it use what the last run saved
+    #[arg(long, value_enum, default_value_t = Stage::Collect)]
+    pub from: Stage,
+
+    /// Last stage to run
+    #[arg(long, value_enum, default_value_t = Stage::Upload)]
+    pub to: Stage,
+
+    /// Go straight from the org draft to the HTML, for unattended runs
+    #[arg(long)]
+    pub skip_edit: bool,
+
+    /// As for `briefing collect`
+    #[arg(long)]
+    pub batch: bool,
+
+    /// As for `briefing collect`
+    #[arg(long)]
+    pub editorial_pass: bool,
+
+    /// As for `briefing prepare`
+    #[arg(long)]
+    pub images: bool,
+
+    /// As for `briefing prepare`
+    #[arg(long)]
+    pub check_links: bool,
+}
+
+pub async fn run(args: Args) -> Result<()> {
+    if args.from > args.to {
+        bail!(
+            "--from {} comes after --to {}",
+            args.from.name(),
+            args.to.name()
+        );
+    }
+    let mut settings = Settings::load()?;
+    shared::shows::find(&settings.shows, &args.show)?;
+    settings.apply_show(&args.show);
+    settings.briefing.org_template = OrgTemplate::load(&args.show)?;
+    let runs = |stage: Stage| args.from <= stage && stage <= args.to;
+
+    let mut org_file = None;
+    if args.from <= Stage::Summarize {
+        let collected = collect_stories::collect(collect_stories::Args {
+            show: Some(args.show.clone()),
+            batch: args.batch,
+            editorial_pass: args.editorial_pass,
+            stop_after: args.to.stop_after(),
+            refetch: args.from == Stage::Extract,
+            resummarize: args.from > Stage::Collect,
+            ..Default::default()
+        })
+        .await?;
+        if args.to <= Stage::Cluster {
+            return Ok(());
+        }
+        match collected {
+            Some(path) => org_file = Some(path),
+            None => {
+                println!("Nothing collected for {}; stopping", args.show);
+                return Ok(());
+            }
+        }
+    } else if args.from <= Stage::Org {
+        let (path, mut data) = latest_stories(&args.show)?;
+        println!("📂 {}", path.display());
+        let show_date = crate::review::show_date_for(&path, &data, &settings)?;
+        if runs(Stage::Cluster) {
+            data.topics = recluster(std::mem::take(&mut data.topics), &data, &settings).await?;
+            let saved = shared::io::new_story_filename(&data.show.slug, show_date.date_naive())
+                .and_then(|filename| shared::save_stories(&data, &filename))?;
+            println!("💾 Story data saved to: {}", saved.display());
+            if args.to == Stage::Cluster {
+                return Ok(());
+            }
+        }
+        let mut org = BriefingGenerator::generate_org_mode_with(
+            &data.topics,
+            &data.show.name,
+            show_date,
+            &settings.briefing,
+        );
+        org.push_str(&shared::discovery::org_section(&data.missed));
+        let path = BriefingGenerator::save_org_mode(&org, &data.show.slug, show_date)?;
+        println!("✓ Org-mode file saved to: {}", path.display());
+        org_file = Some(path);
+    }
+    if args.to == Stage::Org {
+        return Ok(());
+    }
+
+    if args.from <= Stage::Prepare {
+        let org_file = match org_file {
+            Some(path) => path,
+            None => latest_org(&args.show)?,
+        };
+        if runs(Stage::Edit) && !args.skip_edit {
+            pause_for_edit(&org_file)?;
+        }
+        if runs(Stage::Prepare) {
+            println!();
+            prepare_briefing::run(prepare_briefing::Args {
+                file: Some(org_file),
+                images: args.images,
+                check_links: args.check_links,
+                no_upload: args.to < Stage::Upload,
+                ..Default::default()
+            })
+            .await?;
+        }
+    } else {
+        prepare_briefing::publish(prepare_briefing::PublishArgs {
+            show: Some(args.show.clone()),
+            ..Default::default()
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+/// Cluster the stories again, keeping last week's held stories as their own
+/// topic at the end.
+async fn recluster(
+    topics: Vec<Topic>,
+    data: &BriefingData,
+    settings: &Settings,
+) -> Result<Vec<Topic>> {
+    let (held, topics): (Vec<Topic>, Vec<Topic>) = topics
+        .into_iter()
+        .partition(|t| t.title == shared::archive::HELD_TOPIC);
+    let stories = topics.into_iter().flat_map(|t| t.stories).collect();
+    let mut topics = crate::merge::cluster(stories, &data.show, settings).await?;
+    topics.extend(held);
+    Ok(topics)
+}
+
+/// The newest story file for `show`.
+fn latest_stories(show: &str) -> Result<(PathBuf, BriefingData)> {
+    shared::list_story_files()?
+        .into_iter()
+        .find(|(_, data)| data.show.slug == show)
+        .with_context(|| format!("No story files for {}; run from collect first", show))
+}
+
+/// The most recently modified org draft for `show` in the drafts directory.
+fn latest_org(show: &str) -> Result<PathBuf> {
+    let documents_dir = shared::output::settings().dir(shared::output::Artifact::Org)?;
+    std::fs::read_dir(&documents_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_draft_for(path, show))
+        .max_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .with_context(|| {
+            format!(
+                "No {} org draft in {}; run from org first",
+                show,
+                documents_dir.display()
+            )
+        })
+}
+
+/// Whether `path` is a `{show}-{YYYY-MM-DD}.org` draft, not a links export.
+fn is_draft_for(path: &Path, show: &str) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("org")
+        && path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .is_some_and(|stem| {
+                !stem.ends_with("-LINKS")
+                    && shared::archive::parse_filename(stem).0.as_deref() == Some(show)
+            })
+}
+
+/// Open the draft in the user's editor and wait for it to close, or, with
+/// no editor set, wait for Enter.
+fn pause_for_edit(org_file: &Path) -> Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty());
+    match editor {
+        Some(editor) => {
+            println!("\n✏️  Opening {} with {}", org_file.display(), editor);
+            // Through the shell, since editors are often set with arguments
+            // (`code --wait`)
+            let status = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{} \"$1\"", editor))
+                .arg("sh")
+                .arg(org_file)
+                .status()
+                .with_context(|| format!("Failed to run {}", editor))?;
+            if !status.success() {
+                bail!("{} exited with {}; stopping before prepare", editor, status);
+            }
+        }
+        None => {
+            print!(
+                "\n✏️  Edit {}, then press Enter to continue (Ctrl-C to stop) ",
+                org_file.display()
+            );
+            io::stdout().flush()?;
+            if io::stdin().read_line(&mut String::new())? == 0 {
+                bail!("No terminal to wait for edits on; use --skip-edit for unattended runs");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages() {
+        assert!(Stage::Collect < Stage::Extract);
+        assert!(Stage::Edit < Stage::Prepare);
+        assert_eq!(Stage::Summarize.name(), "summarize");
+        assert_eq!(Stage::Cluster.stop_after(), Some(StopAfter::Cluster));
+        assert_eq!(Stage::Org.stop_after(), None);
+    }
+
+    #[test]
+    fn test_is_draft_for() {
+        assert!(is_draft_for(Path::new("/d/twit-2026-02-01.org"), "twit"));
+        assert!(!is_draft_for(Path::new("/d/mbw-2026-02-01.org"), "twit"));
+        assert!(!is_draft_for(
+            Path::new("/d/twit-2026-02-01-LINKS.org"),
+            "twit"
+        ));
+        assert!(!is_draft_for(Path::new("/d/twit-2026-02-01.html"), "twit"));
+    }
+}
@@ -0,0 +1,346 @@
+//! `briefing self-update`: install the latest GitHub release in place.
+//!
+//! The release workflow publishes one asset per binary and platform
+//! (`collect-stories-linux-amd64`, ...) plus a `SHA256SUMS` file. Every tool
+//! installed next to the running `briefing` binary is replaced, and each
+//! download must match its checksum before anything is swapped in.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/leolaporte/briefing/releases/latest";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+const BINARIES: &[&str] = &["briefing", "collect-stories", "prepare-briefing"];
+
+/// Release binaries are ~10 MB; anything far larger is not one of ours.
+const MAX_ASSET_BYTES: usize = 128 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|a| a.name == name)
+            .with_context(|| format!("Release {} has no asset named {}", self.tag_name, name))
+    }
+}
+
+pub async fn run(check_only: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let suffix = platform_suffix()?;
+
+    let client = Client::builder()
+        .user_agent(concat!("briefing/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(120))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    println!("🔎 Checking GitHub for the latest release...");
+    let release: Release = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .context("Failed to reach GitHub releases API")?
+        .error_for_status()
+        .context("GitHub releases API returned an error")?
+        .json()
+        .await
+        .context("Failed to parse GitHub release metadata")?;
+
+    if !is_newer(&release.tag_name, current) {
+        println!("✓ Already up to date (v{})", current);
+        return Ok(());
+    }
+
+    println!("  Update available: v{} → {}", current, release.tag_name);
+    if check_only {
+        return Ok(());
+    }
+
+    let install_dir = std::env::current_exe()
+        .context("Failed to locate the running binary")?
+        .parent()
+        .map(Path::to_path_buf)
+        .context("Running binary has no parent directory")?;
+
+    let checksums = download(&client, release.asset(CHECKSUMS_ASSET)?).await?;
+    let checksums = parse_checksums(&String::from_utf8_lossy(&checksums));
+
+    // Download and verify everything first so a bad asset can't leave the
+    // tools at mixed versions.
+    let mut downloads = Vec::new();
+    for bin in installed_binaries(&install_dir) {
+        let asset_name = format!("{}-{}", bin, suffix);
+        let asset = release.asset(&asset_name)?;
+        println!("⬇️  Downloading {}...", asset_name);
+        let bytes = download(&client, asset).await?;
+
+        let expected = checksums
+            .iter()
+            .find(|(_, name)| *name == asset_name)
+            .map(|(hash, _)| hash.as_str())
+            .with_context(|| format!("{} is not listed in {}", asset_name, CHECKSUMS_ASSET))?;
+        verify_checksum(&bytes, expected)
+            .with_context(|| format!("Refusing to install {}", asset_name))?;
+        downloads.push((bin, bytes));
+    }
+    install(&install_dir, &downloads)?;
+
+    println!("\n✅ Updated to {}", release.tag_name);
+    Ok(())
+}
+
+/// Write each binary beside the one it replaces as `.{bin}.new`, then
+/// swap them all in, moving the old ones aside as `.{bin}.old`. If anything
+/// fails, the binaries already swapped in are put back, so the tools are
+/// never left at mixed versions, and the `.new` files are removed.
+fn install(install_dir: &Path, binaries: &[(&str, Vec<u8>)]) -> Result<()> {
+    // (new, target, old) for each binary
+    let paths: Vec<(PathBuf, PathBuf, PathBuf)> = binaries
+        .iter()
+        .map(|(bin, _)| {
+            (
+                install_dir.join(format!(".{}.new", bin)),
+                install_dir.join(bin),
+                install_dir.join(format!(".{}.old", bin)),
+            )
+        })
+        .collect();
+    // Which binaries have been touched, and whether an old one was moved aside
+    let mut swapped: Vec<(usize, bool)> = Vec::new();
+    let mut swap_in = || {
+        for ((new, _, _), (_, bytes)) in paths.iter().zip(binaries) {
+            std::fs::write(new, bytes)
+                .with_context(|| format!("Failed to write {}", new.display()))?;
+            make_executable(new)?;
+        }
+        for (i, (new, target, old)) in paths.iter().enumerate() {
+            let _ = std::fs::remove_file(old);
+            let backed_up = target.exists();
+            if backed_up {
+                std::fs::rename(target, old)
+                    .with_context(|| format!("Failed to move {} aside", target.display()))?;
+            }
+            swapped.push((i, backed_up));
+            std::fs::rename(new, target)
+                .with_context(|| format!("Failed to replace {}", target.display()))?;
+        }
+        anyhow::Ok(())
+    };
+    let result = swap_in();
+
+    match &result {
+        Ok(()) => {
+            for (_, target, old) in &paths {
+                let _ = std::fs::remove_file(old);
+                println!("  ✓ {}", target.display());
+            }
+        }
+        Err(_) => {
+            for &(i, backed_up) in swapped.iter().rev() {
+                let (_, target, old) = &paths[i];
+                if backed_up {
+                    let _ = std::fs::rename(old, target);
+                } else {
+                    let _ = std::fs::remove_file(target);
+                }
+            }
+            for (new, _, _) in &paths {
+                let _ = std::fs::remove_file(new);
+            }
+        }
+    }
+    result
+}
+
+async fn download(client: &Client, asset: &Asset) -> Result<Vec<u8>> {
+    let response = client
+        .get(&asset.browser_download_url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {}", asset.name))?
+        .error_for_status()
+        .with_context(|| format!("Download of {} failed", asset.name))?;
+    shared::net::read_body_capped(response, MAX_ASSET_BYTES)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", asset.name, e))
+}
+
+/// Release asset suffix for the running platform, matching build.yml.
+fn platform_suffix() -> Result<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Ok("linux-amd64"),
+        ("macos", "x86_64") => Ok("macos-amd64"),
+        ("macos", "aarch64") => Ok("macos-arm64"),
+        (os, arch) => anyhow::bail!("No release binaries are published for {}/{}", os, arch),
+    }
+}
+
+/// The briefing tools present in `dir`. `briefing` itself is always included.
+fn installed_binaries(dir: &Path) -> Vec<&'static str> {
+    BINARIES
+        .iter()
+        .copied()
+        .filter(|bin| *bin == "briefing" || dir.join(bin).exists())
+        .collect()
+}
+
+/// Compare a release tag like `v1.2.0` with the running version. Tags that
+/// don't parse are never considered newer.
+fn is_newer(tag: &str, current: &str) -> bool {
+    fn parse(v: &str) -> Option<Vec<u64>> {
+        v.trim_start_matches('v')
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect()
+    }
+    match (parse(tag), parse(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Parse `sha256sum` output: `<hex>  <filename>` per line.
+fn parse_checksums(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?.to_ascii_lowercase();
+            let name = parts.next()?.trim_start_matches('*');
+            Some((hash, name.to_string()))
+        })
+        .collect()
+}
+
+fn verify_checksum(bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!("checksum mismatch (expected {}, got {})", expected, actual);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to mark {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v1.1.0", "1.0.1"));
+        assert!(is_newer("v2.0.0", "1.9.9"));
+        assert!(!is_newer("v1.0.1", "1.0.1"));
+        assert!(!is_newer("v1.0.0", "1.0.1"));
+        assert!(!is_newer("nightly", "1.0.1"));
+    }
+
+    #[test]
+    fn test_parse_checksums() {
+        let text = "abc123  collect-stories-linux-amd64\nDEF456 *briefing-macos-arm64\n\n";
+        let sums = parse_checksums(text);
+        assert_eq!(sums.len(), 2);
+        assert_eq!(
+            sums[0],
+            ("abc123".into(), "collect-stories-linux-amd64".into())
+        );
+        assert_eq!(sums[1], ("def456".into(), "briefing-macos-arm64".into()));
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        // sha256("hello")
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_checksum(b"hello", hello).is_ok());
+        assert!(verify_checksum(b"hello", &hello.to_uppercase()).is_ok());
+        assert!(verify_checksum(b"tampered", hello).is_err());
+    }
+
+    #[test]
+    fn test_installed_binaries_always_includes_self() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        assert_eq!(installed_binaries(dir), vec!["briefing"]);
+
+        std::fs::write(dir.join("prepare-briefing"), b"").unwrap();
+        assert_eq!(
+            installed_binaries(dir),
+            vec!["briefing", "prepare-briefing"]
+        );
+    }
+
+    #[test]
+    fn test_install_cleans_up_after_a_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        install(dir, &[("briefing", b"new".to_vec())]).unwrap();
+        assert_eq!(std::fs::read(dir.join("briefing")).unwrap(), b"new");
+        assert!(!dir.join(".briefing.new").exists());
+
+        // A folder in the way of the second binary
+        std::fs::create_dir_all(dir.join("prepare-briefing").join("keep")).unwrap();
+        std::fs::create_dir_all(dir.join(".prepare-briefing.old").join("keep")).unwrap();
+        let binaries = [
+            ("collect-stories", b"new".to_vec()),
+            ("prepare-briefing", b"new".to_vec()),
+        ];
+        assert!(install(dir, &binaries).is_err());
+        assert!(!dir.join(".collect-stories.new").exists());
+        assert!(!dir.join(".prepare-briefing.new").exists());
+        assert!(!dir.join("collect-stories").exists());
+    }
+
+    #[test]
+    fn test_install_rolls_back_after_a_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        for bin in ["briefing", "collect-stories", "prepare-briefing"] {
+            std::fs::write(dir.join(bin), b"old").unwrap();
+        }
+        // prepare-briefing can't be moved aside: a folder is in the way
+        std::fs::create_dir_all(dir.join(".prepare-briefing.old").join("keep")).unwrap();
+        let binaries = [
+            ("briefing", b"new".to_vec()),
+            ("collect-stories", b"new".to_vec()),
+            ("prepare-briefing", b"new".to_vec()),
+        ];
+        assert!(install(dir, &binaries).is_err());
+        for bin in ["briefing", "collect-stories", "prepare-briefing"] {
+            assert_eq!(std::fs::read(dir.join(bin)).unwrap(), b"old", "{}", bin);
+            assert!(!dir.join(format!(".{}.new", bin)).exists());
+        }
+        assert!(!dir.join(".briefing.old").exists());
+
+        std::fs::remove_dir_all(dir.join(".prepare-briefing.old")).unwrap();
+        install(dir, &binaries).unwrap();
+        for bin in ["briefing", "collect-stories", "prepare-briefing"] {
+            assert_eq!(std::fs::read(dir.join(bin)).unwrap(), b"new", "{}", bin);
+            assert!(!dir.join(format!(".{}.old", bin)).exists());
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! `briefing serve`: a preview server for proofreading briefings on another
+//! device before they're uploaded.
+//!
+//! `/` lists the HTML files in the output directory, newest first, and each
+//! one is served as it is on disk. Every page polls `/__stamp`, which
+//! changes whenever an HTML file is written, and reloads itself, so a
+//! `briefing prepare --no-upload` shows up on the iPad within seconds.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head read; anything longer is cut off.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Added to every page to reload it when the files change.
+const RELOAD_SCRIPT: &str = r#"<script>
+(function () {
+  var seen = null;
+  setInterval(function () {
+    fetch('/__stamp', { cache: 'no-store' })
+      .then(function (r) { return r.text(); })
+      .then(function (stamp) {
+        if (seen !== null && stamp !== seen) location.reload();
+        seen = stamp;
+      })
+      .catch(function () {});
+  }, 2000);
+})();
+</script>
+"#;
+
+pub async fn run(dir: Option<PathBuf>, host: IpAddr, port: u16) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => shared::output::settings().dir(shared::output::Artifact::Html)?,
+    };
+    if !dir.is_dir() {
+        anyhow::bail!("{} is not a directory", dir.display());
+    }
+    let listener = TcpListener::bind((host, port))
+        .await
+        .with_context(|| format!("Failed to listen on {}:{}", host, port))?;
+    let addr = listener.local_addr()?;
+
+    println!("👀 Serving {}", dir.display());
+    println!("   http://{}/", display_addr(addr));
+    if host.is_unspecified() {
+        if let Some(ip) = lan_address() {
+            println!("   http://{}:{}/ from other devices", ip, addr.port());
+        }
+    }
+    println!("   Pages reload when the files change. Ctrl-C to stop.");
+
+    let dir = Arc::new(dir);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let dir = Arc::clone(&dir);
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, &dir).await {
+                tracing::debug!("Preview request failed: {:#}", e);
+            }
+        });
+    }
+}
+
+/// `localhost:8080` when this machine can use that, so the printed URL is the
+/// one people type.
+fn display_addr(addr: SocketAddr) -> String {
+    if addr.ip().is_loopback() || addr.ip().is_unspecified() {
+        format!("localhost:{}", addr.port())
+    } else {
+        addr.to_string()
+    }
+}
+
+/// This machine's address on the local network: the source address the OS
+/// would pick for an outside host. Connecting a UDP socket sends nothing.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn html(body: String) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: body.into_bytes(),
+        }
+    }
+
+    fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, dir: &Path) -> Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or("/");
+
+    let reply = match method {
+        "GET" | "HEAD" => respond(dir, target),
+        _ => Reply::text(405, "Only GET is supported"),
+    };
+    let header = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        reply.status,
+        reply.reason(),
+        reply.content_type,
+        reply.body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    if method != "HEAD" {
+        stream.write_all(&reply.body).await?;
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn respond(dir: &Path, target: &str) -> Reply {
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+    match path {
+        "/" => match briefings(dir) {
+            Ok(files) => Reply::html(listing(&files)),
+            Err(e) => Reply::text(500, &format!("{:#}", e)),
+        },
+        "/__stamp" => Reply::text(200, &stamp(dir)),
+        _ => {
+            // Browsers percent-encode names with spaces and the like; check
+            // the decoded name, as that's the one opened
+            let name = path
+                .strip_prefix('/')
+                .and_then(|name| urlencoding::decode(name).ok());
+            let Some(name) = name.filter(|name| is_page_name(name)) else {
+                return Reply::text(404, "Not found");
+            };
+            match fs::read_to_string(dir.join(&*name)) {
+                Ok(html) => Reply::html(with_reload(&html)),
+                Err(_) => Reply::text(404, "Not found"),
+            }
+        }
+    }
+}
+
+/// Only plain `.html` names directly in the directory are served, so
+/// nothing outside it (or hidden in it) can be reached.
+fn is_page_name(name: &str) -> bool {
+    name.ends_with(".html")
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
+
+/// The HTML files in `dir` with when they were written, newest first.
+fn briefings(dir: &Path) -> Result<Vec<(String, SystemTime)>> {
+    let mut files: Vec<(String, SystemTime)> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            is_page_name(&name).then_some((name, modified))
+        })
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(files)
+}
+
+/// Changes whenever an HTML file in `dir` is written, added or removed.
+fn stamp(dir: &Path) -> String {
+    let files = briefings(dir).unwrap_or_default();
+    let newest = files
+        .first()
+        .and_then(|(_, modified)| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis());
+    format!("{}-{}", files.len(), newest)
+}
+
+fn listing(files: &[(String, SystemTime)]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n  <meta charset=\"UTF-8\">\n  \
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n  \
+         <title>Briefings</title>\n  <style>\n    \
+         body { font-family: Arial, sans-serif; max-width: 700px; margin: 40px auto; padding: 0 20px; line-height: 1.8; }\n    \
+         a { color: #3498db; text-decoration: none; }\n    \
+         .modified { color: #888; font-size: 0.9em; }\n  \
+         </style>\n</head>\n<body>\n<h1>Briefings</h1>\n",
+    );
+    if files.is_empty() {
+        html.push_str("<p>No briefings yet. Run <code>briefing prepare --no-upload</code>.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for (name, modified) in files {
+            html.push_str(&format!(
+                "  <li><a href=\"/{0}\">{1}</a> <span class=\"modified\">{2}</span></li>\n",
+                escape(&urlencoding::encode(name)),
+                escape(name),
+                chrono::DateTime::<chrono::Local>::from(*modified).format("%a %-d %b, %H:%M")
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    with_reload(&html)
+}
+
+/// `html` with the reload script at the end of its body.
+fn with_reload(html: &str) -> String {
+    match html.rfind("</body>") {
+        Some(at) => format!("{}{}{}", &html[..at], RELOAD_SCRIPT, &html[at..]),
+        None => format!("{}{}", html, RELOAD_SCRIPT),
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respond() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("twit-2026-02-01.html"),
+            "<html><body><h1>TWiT</h1></body></html>",
+        )
+        .unwrap();
+        fs::write(dir.path().join("twit-2026-02-01.org"), "* Draft").unwrap();
+
+        let listing = respond(dir.path(), "/");
+        let listing = String::from_utf8(listing.body).unwrap();
+        assert!(listing.contains("<a href=\"/twit-2026-02-01.html\">"));
+        assert!(!listing.contains(".org"));
+
+        let page = respond(dir.path(), "/twit-2026-02-01.html?x=1");
+        assert_eq!(page.status, 200);
+        let page = String::from_utf8(page.body).unwrap();
+        assert!(page.starts_with("<html><body><h1>TWiT</h1><script>"));
+        assert!(page.ends_with("</script>\n</body></html>"));
+
+        assert_eq!(respond(dir.path(), "/twit-2026-02-01.org").status, 404);
+        assert_eq!(respond(dir.path(), "/../etc/passwd.html").status, 404);
+        assert_eq!(respond(dir.path(), "/missing.html").status, 404);
+        assert_eq!(respond(dir.path(), "").status, 404);
+        assert_eq!(respond(dir.path(), "é.html").status, 404);
+        assert_eq!(
+            respond(dir.path(), "/%2E%2E%2Fetc%2Fpasswd.html").status,
+            404
+        );
+        assert_eq!(respond(dir.path(), "/%FF.html").status, 404);
+    }
+
+    #[test]
+    fn test_respond_decodes_names() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("twit special.html"), "<html></html>").unwrap();
+
+        let listing = String::from_utf8(respond(dir.path(), "/").body).unwrap();
+        assert!(listing.contains("<a href=\"/twit%20special.html\">twit special.html</a>"));
+        assert_eq!(respond(dir.path(), "/twit%20special.html").status, 200);
+    }
+
+    #[test]
+    fn test_stamp_changes_with_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = stamp(dir.path());
+        fs::write(dir.path().join("mbw-2026-02-03.html"), "<html></html>").unwrap();
+        assert_ne!(stamp(dir.path()), empty);
+    }
+}
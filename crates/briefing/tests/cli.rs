@@ -0,0 +1,596 @@
+//! The `briefing` subcommands that wrap the old tools, run as a user would.
+
+use shared::testing::{MockServer, Response, Sandbox};
+use std::fs;
+
+const CONFIG: &str = r#"
+[[shows]]
+slug = "weekly"
+name = "The Weekly Show"
+day = "sunday"
+ends = 18
+"#;
+
+#[test]
+fn test_config_reports_shows() {
+    let sandbox = Sandbox::new(CONFIG);
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .arg("config")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("config.toml is valid"), "{}", stdout);
+    assert!(
+        stdout.contains("weekly   The Weekly Show (next: Sunday"),
+        "{}",
+        stdout
+    );
+
+    // A time zone that can't be found is reported, not ignored
+    let zoned = sandbox.home().join("zoned.toml");
+    fs::write(
+        &zoned,
+        format!("{}timezone = \"Mars/Olympus_Mons\"\n", CONFIG),
+    )
+    .unwrap();
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["config", "--config"])
+        .arg(&zoned)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Unknown time zone 'Mars/Olympus_Mons'"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // --config points every subcommand at another file
+    let other = sandbox.home().join("other.toml");
+    fs::write(&other, "[[shows]]\nslug = ").unwrap();
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["config", "--config"])
+        .arg(&other)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["--config"])
+        .arg(&other)
+        .args(["config", "--path"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        other.display().to_string()
+    );
+}
+
+#[test]
+fn test_prepare_then_publish() {
+    let dav = MockServer::webdav();
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[upload]\nurl = \"{}/Briefings\"\n",
+        CONFIG,
+        dav.url()
+    ));
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    fs::write(
+        &org_file,
+        "#+TITLE: The Weekly Show Briefing Book\n\n* Apple\n\n\
+         ** Apple cuts the price of Vision Pro\n\n\
+         *** URL\nhttps://news.example/vision-pro\n\n\
+         *** Summary\nApple dropped the price.\n",
+    )
+    .unwrap();
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["prepare", "--no-upload", "--file"])
+        .arg(&org_file)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Not uploaded (--no-upload)"), "{}", stdout);
+    assert!(dav.requests().is_empty());
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .arg("publish")
+        .env("WEBDAV_USER", "leo")
+        .env("WEBDAV_PASSWORD", "secret")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let html = fs::read(&sandbox.documents_with("html")[0]).unwrap();
+    let mut puts = dav.requests_for("PUT");
+    puts.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(puts.len(), 2, "{}", stdout);
+    assert_eq!(puts[0].path, "/Briefings/weekly/index.html");
+    assert_eq!(puts[0].body, html);
+    assert_eq!(puts[1].path, "/Briefings/weekly/links.csv");
+
+    // Publishing again sends nothing the server already has
+    let publish = |args: &[&str]| {
+        let output = sandbox
+            .command(env!("CARGO_BIN_EXE_briefing"))
+            .arg("publish")
+            .args(args)
+            .env("WEBDAV_USER", "leo")
+            .env("WEBDAV_PASSWORD", "secret")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let stdout = publish(&[]);
+    assert!(stdout.contains("Already up to date"), "{}", stdout);
+    assert_eq!(dav.requests_for("PUT").len(), 2);
+    let stdout = publish(&["--status"]);
+    assert!(stdout.contains("✓ HTML current"), "{}", stdout);
+    assert!(stdout.contains("✓ CSV  current"), "{}", stdout);
+
+    // Only the edited file goes up again
+    let html_file = sandbox.documents_with("html")[0].clone();
+    fs::write(&html_file, "<html>edited</html>").unwrap();
+    let stdout = publish(&["--status"]);
+    assert!(
+        stdout.contains("⚠ HTML changed since it was published"),
+        "{}",
+        stdout
+    );
+    publish(&[]);
+    let puts = dav.requests_for("PUT");
+    assert_eq!(puts.len(), 3);
+    assert_eq!(puts[2].path, "/Briefings/weekly/index.html");
+    let stdout = publish(&["--status"]);
+    assert!(!stdout.contains("⚠"), "{}", stdout);
+    let html = fs::read(&html_file).unwrap();
+
+    // --reupload pushes the same files again without preparing them anew
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["prepare", "--reupload", "--file"])
+        .arg(&org_file)
+        .env("WEBDAV_USER", "leo")
+        .env("WEBDAV_PASSWORD", "secret")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!stdout.contains("Parsing"), "{}", stdout);
+    let puts = dav.requests_for("PUT");
+    assert_eq!(puts.len(), 5, "{}", stdout);
+    assert!(puts[3..].iter().any(|put| put.body == html));
+}
+
+#[test]
+fn test_publish_records_the_file_that_made_it() {
+    // A server that takes the HTML but refuses the CSV
+    let html = std::sync::Mutex::new(None::<Vec<u8>>);
+    let dav = MockServer::start(move |request| {
+        let mut html = html.lock().unwrap();
+        match (request.method.as_str(), request.path.ends_with(".html")) {
+            ("PUT", true) => {
+                *html = Some(request.body.clone());
+                Response::status(201)
+            }
+            ("PUT", false) => Response::status(403),
+            ("GET" | "HEAD", true) => match &*html {
+                Some(body) => Response::html(&String::from_utf8_lossy(body)),
+                None => Response::status(404),
+            },
+            ("GET" | "HEAD", false) => Response::status(404),
+            _ => Response::status(405),
+        }
+    });
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[upload]\nurl = \"{}/Briefings\"\n",
+        CONFIG,
+        dav.url()
+    ));
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    fs::write(
+        &org_file,
+        "#+TITLE: The Weekly Show Briefing Book\n\n* Apple\n\n\
+         ** Apple cuts the price of Vision Pro\n\n\
+         *** URL\nhttps://news.example/vision-pro\n\n\
+         *** Summary\nApple dropped the price.\n",
+    )
+    .unwrap();
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["prepare", "--no-upload", "--file"])
+        .arg(&org_file)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let publish = |args: &[&str]| {
+        let output = sandbox
+            .command(env!("CARGO_BIN_EXE_briefing"))
+            .arg("publish")
+            .args(args)
+            .env("WEBDAV_USER", "leo")
+            .env("WEBDAV_PASSWORD", "secret")
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let stdout = publish(&[]);
+    assert!(stdout.contains("403"), "{}", stdout);
+    let stdout = publish(&["--status"]);
+    assert!(stdout.contains("✓ HTML current"), "{}", stdout);
+    assert!(stdout.contains("⚠ CSV  not published"), "{}", stdout);
+
+    // Only the CSV is tried again
+    publish(&[]);
+    let puts = dav.requests_for("PUT");
+    let html_puts = puts.iter().filter(|put| put.path.ends_with(".html"));
+    assert_eq!(html_puts.count(), 1);
+}
+
+#[test]
+fn test_publish_needs_a_prepared_briefing() {
+    let sandbox = Sandbox::new(CONFIG);
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["publish", "missing.html"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("run `briefing prepare` first"));
+}
+
+#[test]
+fn test_errors_are_redacted() {
+    let sandbox = Sandbox::new(CONFIG);
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .env("RAINDROP_TOKEN", "rd-0123456789abcdef")
+        .args(["review", "stories-rd-0123456789abcdef.json"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error: Story file not found"));
+    assert!(stderr.contains("stories-[REDACTED].json"));
+    assert!(!stderr.contains("rd-0123456789abcdef"));
+}
+
+#[test]
+fn test_run_from_edit_prepares_and_uploads() {
+    let dav = MockServer::webdav();
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[upload]\nurl = \"{}/Briefings\"\n",
+        CONFIG,
+        dav.url()
+    ));
+    fs::write(
+        sandbox.documents().join("weekly-2026-02-01.org"),
+        "#+TITLE: The Weekly Show Briefing Book\n\n* Apple\n\n\
+         ** Apple cuts the price of Vision Pro\n\n\
+         *** URL\nhttps://news.example/vision-pro\n\n\
+         *** Summary\nApple dropped the price.\n",
+    )
+    .unwrap();
+
+    // Unattended, the edit pause needs --skip-edit
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["run", "--show", "weekly", "--from", "edit"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("use --skip-edit"));
+    assert!(dav.requests().is_empty());
+
+    // The editor's changes make it into the briefing
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["run", "--show", "weekly", "--from", "edit"])
+        .env("EDITOR", "sed -i s/cuts/slashes/")
+        .env("WEBDAV_USER", "leo")
+        .env("WEBDAV_PASSWORD", "secret")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let html = fs::read_to_string(&sandbox.documents_with("html")[0]).unwrap();
+    assert!(html.contains("<h3>Apple slashes the price of Vision Pro</h3>"));
+    let puts = dav.requests_for("PUT");
+    assert_eq!(puts.len(), 2, "{}", stdout);
+    assert!(puts
+        .iter()
+        .any(|put| put.path == "/Briefings/weekly/index.html"));
+
+    // --to stops before the upload
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args([
+            "run", "--show", "weekly", "--from", "edit", "--to", "prepare",
+        ])
+        .arg("--skip-edit")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(dav.requests_for("PUT").len(), 2);
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["run", "--show", "weekly", "--from", "upload", "--to", "org"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--from upload comes after --to org"));
+}
+
+#[test]
+fn test_archive_uploads_missing_briefings_and_index() {
+    // The server already has last week's copy
+    let dav = MockServer::start(|request| match request.method.as_str() {
+        "HEAD" if request.path.ends_with("weekly-2026-01-25.html") => Response::status(200),
+        "HEAD" => Response::status(404),
+        _ => Response::status(201),
+    });
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[upload]\nurl = \"{}/Briefings\"\n",
+        CONFIG,
+        dav.url()
+    ));
+    for name in [
+        "weekly-2026-01-25.html",
+        "weekly-2026-02-01.html",
+        "weekly-2026-02-01-leo.html",
+    ] {
+        fs::write(sandbox.documents().join(name), "<html></html>").unwrap();
+    }
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .arg("archive")
+        .env("WEBDAV_USER", "leo")
+        .env("WEBDAV_PASSWORD", "secret")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    let puts: Vec<_> = dav
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "PUT")
+        .collect();
+    assert_eq!(puts.len(), 2, "{}", stdout);
+    assert_eq!(puts[0].path, "/Briefings/weekly/weekly-2026-02-01.html");
+    assert_eq!(puts[1].path, "/Briefings/index.html");
+    let index = puts[1].body_text();
+    assert!(index.contains("<h2>The Weekly Show</h2>"), "{}", index);
+    assert!(index.contains("href=\"weekly/weekly-2026-01-25.html\""));
+    assert!(!index.contains("leo"));
+}
+
+#[test]
+fn test_retry_failed_patches_the_story_file() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    // The second story's site is down until the retry
+    let down = Arc::new(AtomicBool::new(true));
+    let site_down = down.clone();
+    let site = MockServer::start(move |request| match request.path.as_str() {
+        "/vision-pro" => Response::html(
+            "<p>Apple cut the price of the Vision Pro headset by a thousand dollars on \
+             Tuesday, hoping that a lower price will reach the buyers and developers it \
+             needs.</p>",
+        ),
+        "/watch" if site_down.load(Ordering::SeqCst) => Response::status(503),
+        "/watch" => Response::html(
+            "<p>An update lets the Apple Watch warn its wearer of signs of high blood \
+             pressure without a cuff, after regulators in the US and Europe cleared the \
+             feature.</p>",
+        ),
+        _ => Response::status(404),
+    });
+    let bookmarks = serde_json::json!([
+        {"_id": 1, "title": "Apple cuts the price of Vision Pro", "tags": ["weekly"],
+         "link": format!("{}/vision-pro", site.url()), "created": chrono::Utc::now().to_rfc3339()},
+        {"_id": 2, "title": "Apple Watch gets blood pressure alerts", "tags": ["weekly"],
+         "link": format!("{}/watch", site.url()), "created": chrono::Utc::now().to_rfc3339()},
+    ]);
+    let raindrop = MockServer::start(move |request| {
+        let items = if request.path.contains("page=0") {
+            bookmarks.clone()
+        } else {
+            serde_json::json!([])
+        };
+        Response::json(&serde_json::json!({ "items": items, "count": 2 }))
+    });
+    let sandbox = Sandbox::new(CONFIG);
+    let failed_dir = sandbox
+        .home()
+        .join(".local/share/podcast-briefing/failed-fetches");
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["collect", "--show", "weekly"])
+        .env("RAINDROP_TOKEN", "raindrop-token")
+        .env("RAINDROP_API_URL", raindrop.url())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains(&format!(
+            "✗ 503 · no cookies · {}/watch\n    Server error (503 Service Unavailable)",
+            site.url()
+        )),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("briefing retry-failed"), "{}", stdout);
+    assert_eq!(fs::read_dir(&failed_dir).unwrap().count(), 1);
+    let org = fs::read_to_string(&sandbox.documents_with("org")[0]).unwrap();
+    assert!(!org.contains("high blood pressure"), "{}", org);
+
+    down.store(false, Ordering::SeqCst);
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .arg("retry-failed")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Fetched 1/1 article(s)"), "{}", stdout);
+    let org = fs::read_to_string(&sandbox.documents_with("org")[0]).unwrap();
+    assert!(org.contains("high blood pressure"), "{}", org);
+    assert_eq!(fs::read_dir(&failed_dir).unwrap().count(), 0);
+
+    // Nothing left to retry
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .arg("retry-failed")
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No failed fetches recorded"));
+}
+
+#[test]
+fn test_add_story_files_an_article_under_a_topic() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let site = MockServer::start(|request| match request.path.as_str() {
+        "/vision-pro" => Response::html(
+            "<p>Apple cut the price of the Vision Pro headset by a thousand dollars on \
+             Tuesday, hoping that a lower price will reach the buyers and developers it \
+             needs.</p>",
+        ),
+        "/breaking" => Response::html(
+            "<head><meta property=\"og:title\" content=\"Starship reaches orbit\"></head>\
+             <p>SpaceX's Starship reached orbit on Sunday evening for the first time, and the \
+             company says the booster was caught by the launch tower once again.</p>",
+        ),
+        _ => Response::status(404),
+    });
+    let bookmarks = serde_json::json!([
+        {"_id": 1, "title": "Apple cuts the price of Vision Pro", "tags": ["weekly"],
+         "link": format!("{}/vision-pro", site.url()), "created": chrono::Utc::now().to_rfc3339()},
+    ]);
+    let raindrop = MockServer::start(move |request| {
+        let items = if request.path.contains("page=0") {
+            bookmarks.clone()
+        } else {
+            serde_json::json!([])
+        };
+        Response::json(&serde_json::json!({ "items": items, "count": 1 }))
+    });
+    let sandbox = Sandbox::new(CONFIG);
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["collect", "--show", "weekly"])
+        .env("RAINDROP_TOKEN", "raindrop-token")
+        .env("RAINDROP_API_URL", raindrop.url())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let url = format!("{}/breaking", site.url());
+    let mut child = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["add-story", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"Space\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Topic (number, or a name"), "{}", stdout);
+    assert!(stdout.contains("Added to \"Space\""), "{}", stdout);
+    let org = fs::read_to_string(&sandbox.documents_with("org")[0]).unwrap();
+    assert!(org.contains("Starship reaches orbit"), "{}", org);
+    assert!(org.contains("Vision Pro"), "{}", org);
+
+    // The same URL again is refused
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["add-story", &url, "--topic", "1"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is already in"));
+}
+
+#[test]
+fn test_prune_and_undo() {
+    let site = MockServer::start(|request| match request.path.as_str() {
+        "/vision-pro" => Response::html(
+            "<p>Apple cut the price of the Vision Pro headset by a thousand dollars on \
+             Tuesday, hoping that a lower price will reach the buyers and developers it \
+             needs.</p>",
+        ),
+        "/foldable" => Response::html(
+            "<p>Apple is said to be working on a foldable iPhone for next year, according to \
+             people who claim to have seen prototypes of the device in its supply chain.</p>",
+        ),
+        _ => Response::status(404),
+    });
+    let bookmarks = serde_json::json!([
+        {"_id": 1, "title": "Apple cuts the price of Vision Pro", "tags": ["weekly"],
+         "link": format!("{}/vision-pro", site.url()), "created": chrono::Utc::now().to_rfc3339()},
+        {"_id": 2, "title": "Rumor: a foldable iPhone next year", "tags": ["weekly"],
+         "link": format!("{}/foldable", site.url()), "created": chrono::Utc::now().to_rfc3339()},
+    ]);
+    let raindrop = MockServer::start(move |request| {
+        let items = if request.path.contains("page=0") {
+            bookmarks.clone()
+        } else {
+            serde_json::json!([])
+        };
+        Response::json(&serde_json::json!({ "items": items, "count": 2 }))
+    });
+    let sandbox = Sandbox::new(CONFIG);
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["collect", "--show", "weekly"])
+        .env("RAINDROP_TOKEN", "raindrop-token")
+        .env("RAINDROP_API_URL", raindrop.url())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let org = || fs::read_to_string(&sandbox.documents_with("org")[0]).unwrap();
+    assert!(org().contains("foldable iPhone"));
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["prune", "--title-contains", "rumor"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Pruned 1 story(ies)"), "{}", stdout);
+    assert!(!org().contains("foldable iPhone"), "{}", org());
+    assert!(org().contains("Vision Pro"), "{}", org());
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_briefing"))
+        .args(["prune", "--undo"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Restored 1 story(ies)"), "{}", stdout);
+    assert!(org().contains("foldable iPhone"), "{}", org());
+}
@@ -0,0 +1,119 @@
+//! Weekly collection-readiness digest (`collect-stories --digest`).
+//!
+//! Meant to run from a Monday timer: for each show it counts the bookmarks
+//! tagged since the previous episode, how many of them are paywalled, and
+//! what summarizing them would cost, so thin or troublesome weeks show up
+//! early rather than on the night before recording.
+
+use chrono::DateTime;
+use shared::timezone::Tz;
+use std::fmt::Write as _;
+
+/// Used until the usage log has enough history to estimate from.
+pub const DEFAULT_COST_PER_STORY: f64 = 0.001;
+
+/// Shows with fewer bookmarks than this get a nudge in the digest.
+pub const THIN_WEEK_BOOKMARKS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct ShowDigest {
+    pub name: String,
+    pub slug: String,
+    /// The next episode's date, in the show's time zone
+    pub next_show: DateTime<Tz>,
+    pub bookmarks: usize,
+    pub paywalled: usize,
+    pub failed: usize,
+}
+
+impl ShowDigest {
+    pub fn projected_cost(&self, cost_per_story: f64) -> f64 {
+        self.bookmarks as f64 * cost_per_story
+    }
+
+    pub fn to_json(&self, cost_per_story: f64) -> serde_json::Value {
+        serde_json::json!({
+            "show": self.name,
+            "show_slug": self.slug,
+            "next_show": self.next_show.format("%Y-%m-%d").to_string(),
+            "bookmarks": self.bookmarks,
+            "paywalled": self.paywalled,
+            "failed": self.failed,
+            "projected_cost_usd": self.projected_cost(cost_per_story),
+        })
+    }
+}
+
+/// Plain-text digest suitable for an email body.
+pub fn format_digest(shows: &[ShowDigest], cost_per_story: f64, now: DateTime<Tz>) -> String {
+    let mut out = format!("Briefing readiness for {}\n", now.format("%A, %-d %B %Y"));
+
+    for show in shows {
+        let days_left = (show.next_show.date_naive() - now.date_naive()).num_days();
+        let _ = write!(
+            out,
+            "\n{} ({}, in {} day{})\n",
+            show.name,
+            show.next_show.format("%a %-d %b"),
+            days_left,
+            if days_left == 1 { "" } else { "s" }
+        );
+        let _ = writeln!(out, "  Bookmarks so far: {}", show.bookmarks);
+        let _ = writeln!(out, "  Paywalled:        {}", show.paywalled);
+        if show.failed > 0 {
+            let _ = writeln!(out, "  Failed to fetch:  {}", show.failed);
+        }
+        let _ = writeln!(
+            out,
+            "  Projected cost:   ${:.3}",
+            show.projected_cost(cost_per_story)
+        );
+        if show.bookmarks < THIN_WEEK_BOOKMARKS {
+            let _ = writeln!(out, "  -> Light week so far; time to start tagging.");
+        } else if show.paywalled * 4 > show.bookmarks {
+            let _ = writeln!(
+                out,
+                "  -> Over a quarter paywalled; check browser logins or find alternate sources."
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn show(bookmarks: usize, paywalled: usize) -> ShowDigest {
+        ShowDigest {
+            name: "This Week in Tech".to_string(),
+            slug: "twit".to_string(),
+            next_show: shared::timezone::local()
+                .with_ymd_and_hms(2026, 2, 8, 0, 0, 0)
+                .unwrap(),
+            bookmarks,
+            paywalled,
+            failed: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_digest_counts_and_nudges() {
+        let now = shared::timezone::local()
+            .with_ymd_and_hms(2026, 2, 2, 9, 0, 0)
+            .unwrap();
+
+        let text = format_digest(&[show(40, 3)], 0.002, now);
+        assert!(text.starts_with("Briefing readiness for Monday, 2 February 2026"));
+        assert!(text.contains("This Week in Tech (Sun 8 Feb, in 6 days)"));
+        assert!(text.contains("Bookmarks so far: 40"));
+        assert!(text.contains("Projected cost:   $0.080"));
+        assert!(!text.contains("->"));
+        assert!(!text.contains("Failed"));
+
+        assert!(format_digest(&[show(3, 0)], 0.002, now).contains("Light week"));
+        assert!(format_digest(&[show(20, 6)], 0.002, now).contains("paywalled;"));
+    }
+}
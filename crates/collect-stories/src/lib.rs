@@ -0,0 +1,1654 @@
+//! Collects the week's bookmarks, fetches and summarizes the articles and
+//! writes the org draft. `briefing collect` runs this; the
+//! `collect-stories` binary is kept for existing scripts.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+use shared::clustering::StoryDetails;
+use shared::discovery::{DiscoverySettings, Suggestion};
+use shared::extractor::FetchFailure;
+use shared::filter::Filter;
+use shared::hooks::{HookPayload, HookPoint};
+use shared::org_template::OrgTemplate;
+use shared::progress::{JsonLinesProgress, Progress};
+use shared::prompts::{AudienceLevel, Prompt, PromptKind, SummaryLength};
+use shared::schedule::Schedule;
+use shared::settings::Settings;
+use shared::shows::ShowSettings;
+use shared::sources::SourceSettings;
+use shared::timezone::ZoneExt;
+use shared::triage::{FailedFetch, RetryFile};
+use shared::usage::UsageSettings;
+use shared::{
+    raindrop::Bookmark, save_stories, ArticleContent, BriefingData, ClaudeSummarizer, Config,
+    ContentExtractor, ExtractionResult, RaindropClient, Story, Summary, Topic, TopicClusterer,
+    UsageTracker,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::{self as stdio, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+mod digest;
+
+fn cache_path() -> PathBuf {
+    let dir = dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("podcast-briefing");
+    std::fs::create_dir_all(&dir).ok();
+    dir.join("summaries.json")
+}
+
+/// Summary cache entries are kept per length and audience level, so
+/// changing either summarizes the articles again instead of reusing
+/// summaries written for the old style.
+fn summary_key(url: &str, length: SummaryLength, level: AudienceLevel) -> String {
+    format!("{}/{} {}", length.name(), level.name(), url)
+}
+
+fn load_summary_cache() -> HashMap<String, Summary> {
+    let path = cache_path();
+    match std::fs::read_to_string(&path) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn save_summary_cache(cache: &HashMap<String, Summary>) {
+    let path = cache_path();
+    if let Ok(data) = serde_json::to_string(cache) {
+        std::fs::write(&path, data).ok();
+    }
+}
+
+fn extraction_cache_path() -> PathBuf {
+    cache_path().with_file_name("extractions.json")
+}
+
+/// Extracted articles and paywall hits from earlier runs (including
+/// `--prefetch`), keyed by bookmark URL. Failures aren't cached so they're
+/// retried next time.
+fn load_extraction_cache() -> HashMap<String, ExtractionResult> {
+    match std::fs::read_to_string(extraction_cache_path()) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn save_extraction_cache(cache: &HashMap<String, ExtractionResult>) {
+    if let Ok(data) = serde_json::to_string(cache) {
+        std::fs::write(extraction_cache_path(), data).ok();
+    }
+}
+
+/// Keep translated articles in the extraction cache in place of the
+/// originals, so each is translated once.
+fn cache_translations(content_map: &HashMap<String, ArticleContent>, urls: &[String]) {
+    if urls.is_empty() {
+        return;
+    }
+    let mut cache = load_extraction_cache();
+    for url in urls {
+        if let Some(content) = content_map.get(url) {
+            cache.insert(url.clone(), ExtractionResult::Success(content.clone()));
+        }
+    }
+    save_extraction_cache(&cache);
+}
+
+/// Fetch every URL not already in the extraction cache, or every URL with
+/// `refetch`. Returns all results and how many came from the cache.
+async fn extract_articles(
+    extractor: &ContentExtractor,
+    urls: Vec<String>,
+    refetch: bool,
+) -> (Vec<(String, ExtractionResult)>, usize) {
+    let mut cache = load_extraction_cache();
+    if refetch {
+        for url in &urls {
+            cache.remove(url);
+        }
+    }
+    let (cached, uncached): (Vec<String>, Vec<String>) =
+        urls.into_iter().partition(|url| cache.contains_key(url));
+
+    let fetched = extractor.fetch_articles_parallel(uncached).await;
+    if !fetched.is_empty() {
+        remember(&mut cache, &fetched);
+        save_extraction_cache(&cache);
+    }
+
+    let cached_count = cached.len();
+    let mut results: Vec<(String, ExtractionResult)> = cached
+        .into_iter()
+        .filter_map(|url| cache.remove(&url).map(|result| (url, result)))
+        .collect();
+    results.extend(fetched);
+    (results, cached_count)
+}
+
+fn remember(cache: &mut HashMap<String, ExtractionResult>, results: &[(String, ExtractionResult)]) {
+    for (url, result) in results {
+        if matches!(
+            result,
+            ExtractionResult::Success(_) | ExtractionResult::Paywalled
+        ) {
+            cache.insert(url.clone(), result.clone());
+        }
+    }
+}
+
+/// Add the successes and paywall hits in `results` to the extraction
+/// cache, for fetches made outside a collection (`briefing retry-failed`).
+pub fn remember_extractions(results: &[(String, ExtractionResult)]) {
+    let mut cache = load_extraction_cache();
+    remember(&mut cache, results);
+    save_extraction_cache(&cache);
+}
+
+/// The triage report entry for `bookmark`, from this run's fetch or, for a
+/// paywall hit cached by an earlier run, what is known of it.
+fn failed_fetch(extractor: &ContentExtractor, bookmark: &Bookmark) -> FailedFetch {
+    let failure = extractor
+        .failure(&bookmark.link)
+        .unwrap_or_else(|| FetchFailure {
+            status: Some(403),
+            error: "Paywalled in an earlier run".to_string(),
+            cookies: extractor.sends_cookies(&bookmark.link),
+        });
+    FailedFetch::new(&bookmark.link, &bookmark.title, failure)
+}
+
+fn log_error(message: &str) {
+    let log_path = "/tmp/collect-stories-errors.log";
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(log_path) {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S");
+        let _ = writeln!(file, "[{}] {}", timestamp, shared::logging::redact(message));
+    }
+}
+
+fn prompt_show_selection(shows: &[ShowSettings]) -> Result<ShowSettings> {
+    let shows = shared::shows::configured(shows)?;
+    println!("Which show?");
+    for (i, show) in shows.iter().enumerate() {
+        println!("  {}) {} ({})", i + 1, show.slug, show.name);
+    }
+    print!("\nEnter your choice (1-{}): ", shows.len());
+    stdio::stdout().flush()?;
+
+    let mut input = String::new();
+    stdio::stdin().read_line(&mut input)?;
+
+    input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| shows.get(i))
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Invalid selection. Please choose 1-{}.", shows.len()))
+}
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Args {
+    /// Show to collect stories for, by its slug in `[[shows]]`
+    #[arg(short, long)]
+    pub show: Option<String>,
+
+    /// Collect every show in `[[shows]]` in turn, writing a story file and
+    /// org draft for each
+    #[arg(long, conflicts_with_all = ["show", "digest", "prefetch", "progress_json"])]
+    pub all_shows: bool,
+
+    /// Send all summaries as one Message Batches job (cheaper; falls back to
+    /// individual requests if the API doesn't support batches)
+    #[arg(long)]
+    pub batch: bool,
+
+    /// After clustering, have the model edit each topic's summaries as a set
+    /// for a consistent voice, less repetition and tighter wording
+    #[arg(long)]
+    pub editorial_pass: bool,
+
+    /// Report collection readiness (bookmarks, paywalled, projected cost) for
+    /// every show, or just --show, instead of collecting
+    #[arg(long)]
+    pub digest: bool,
+
+    /// Fetch and summarize new bookmarks into the caches without writing a
+    /// briefing, so the real run later is quick
+    #[arg(long)]
+    pub prefetch: bool,
+
+    /// With --prefetch, keep polling Raindrop every MINUTES
+    #[arg(long, value_name = "MINUTES", requires = "prefetch")]
+    pub watch: Option<u64>,
+
+    /// Write progress events to FILE as JSON lines instead of printing dots
+    #[arg(long, value_name = "FILE")]
+    pub progress_json: Option<PathBuf>,
+
+    /// Search only this Raindrop collection ID (overrides [raindrop] in
+    /// config.toml)
+    #[arg(long, value_name = "ID")]
+    pub collection: Option<i64>,
+
+    /// Download every article again, ignoring the extraction and page
+    /// caches
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// How long summaries are: one-liner, standard or deep-dive (overrides
+    /// `summary_length` in [prompts])
+    #[arg(long, value_name = "LENGTH")]
+    pub summary_length: Option<SummaryLength>,
+
+    /// What summaries assume listeners know: general, informed or expert
+    /// (overrides `audience_level` in [prompts])
+    #[arg(long, value_name = "LEVEL")]
+    pub audience_level: Option<AudienceLevel>,
+
+    /// List top Hacker News and Techmeme stories that weren't bookmarked at
+    /// the end of the org draft (turns on [briefing.discovery])
+    #[arg(long)]
+    pub discover: bool,
+
+    /// Stop after this stage instead of writing the org draft (for
+    /// `briefing run --to`)
+    #[arg(skip)]
+    pub stop_after: Option<StopAfter>,
+
+    /// Fetch every article again instead of using the extraction cache
+    #[arg(skip)]
+    pub refetch: bool,
+
+    /// Summarize every article again instead of using the summary cache
+    #[arg(skip)]
+    pub resummarize: bool,
+}
+
+/// Where a partial collection stops. Each stage leaves its results where
+/// the next run finds them: the extraction and summary caches, and the
+/// story file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopAfter {
+    Collect,
+    Extract,
+    Summarize,
+    Cluster,
+}
+
+pub async fn run(args: Args) -> Result<()> {
+    collect(args).await.map(|_| ())
+}
+
+/// Like [`run`], returning the org draft written, if any.
+pub async fn collect(args: Args) -> Result<Option<PathBuf>> {
+    if args.all_shows {
+        return collect_all_shows(args).await.map(|()| None);
+    }
+    let config = Config::from_env()?;
+    let mut settings = Settings::load()?;
+    if let Some(id) = args.collection {
+        settings.raindrop.collection = Some(id);
+        settings.raindrop.collections.clear();
+    }
+    settings
+        .prompts
+        .set_summary_style(args.summary_length, args.audience_level);
+    if args.discover {
+        settings.briefing.discovery.enabled = true;
+    }
+    let filters = settings
+        .filter
+        .compile()
+        .context("Invalid [filter] expression in config.toml")?;
+    let usage = UsageTracker::new();
+
+    let requested_show = args
+        .show
+        .map(|slug| shared::shows::find(&settings.shows, &slug).cloned())
+        .transpose()?;
+
+    if args.digest {
+        return run_digest(requested_show, config, &settings, &filters)
+            .await
+            .map(|()| None);
+    }
+    if args.prefetch {
+        return run_prefetch(requested_show, config, &settings, &filters, args.watch)
+            .await
+            .map(|()| None);
+    }
+
+    let progress: Progress = match &args.progress_json {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            Arc::new(JsonLinesProgress::new(file))
+        }
+        None => shared::progress::console(),
+    };
+
+    // Determine which show to use
+    let show = match requested_show {
+        Some(show) => show,
+        None => prompt_show_selection(&settings.shows)?,
+    };
+
+    let show_info = show.info();
+    settings.apply_show(&show.slug);
+    settings.briefing.org_template = OrgTemplate::load(&show.slug)?;
+    println!("\n✓ Selected: {}", show_info.name);
+    let ai_enabled = shared::llm::credentials_available();
+    if !ai_enabled {
+        println!(
+            "⚠ {} is not set: AI disabled. Stories get extracted ledes instead of summaries and are grouped by site.",
+            shared::llm::key_env_var()
+        );
+    }
+    let summarize_prompt = Prompt::load(PromptKind::Summarize, &show_info, &settings.prompts)?;
+    let excerpt_prompt = summarize_prompt.clone();
+    let summary_length = settings.prompts.summary_length(&show_info);
+    let audience_level = settings.prompts.audience_level(&show_info);
+    let cluster_prompt = Prompt::load(PromptKind::Cluster, &show_info, &settings.prompts)?;
+    let split_prompt = Prompt::load(PromptKind::Split, &show_info, &settings.prompts)?;
+    let edit_prompt = Prompt::load(PromptKind::Edit, &show_info, &settings.prompts)?;
+    let connect_prompt = Prompt::load(PromptKind::Connect, &show_info, &settings.prompts)?;
+    let followup_prompt = Prompt::load(PromptKind::Followup, &show_info, &settings.prompts)?;
+    let translate_prompt = Prompt::load(PromptKind::Translate, &show_info, &settings.prompts)?;
+    let entities_prompt = Prompt::load(PromptKind::Entities, &show_info, &settings.prompts)?;
+
+    // The show's schedule, in its own time zone
+    let schedule = Schedule::new(&show, &settings.schedule)?;
+    let now = schedule.now();
+
+    // Automatically determine lookback window based on show schedule
+    let previous_end = schedule.previous_end(now);
+    // Raindrop's `created:>` filter is exclusive and date-only. Pass end_date - 1
+    // day so bookmarks from the show's end date are returned; we filter client-
+    // side below for precise cutoff at the actual end time.
+    let since = previous_end.date_naive() - Duration::days(1);
+
+    println!(
+        "  Collecting stories since previous {} ended ({} {})",
+        show_info.name,
+        previous_end.format("%A, %-d %B"),
+        previous_end.format("%-l%P")
+    );
+
+    println!("\n📚 Fetching bookmarks from Raindrop.io...");
+    let raindrop_client = RaindropClient::new(config.raindrop_api_token)?;
+    let bookmarks = raindrop_client
+        .fetch_bookmarks(
+            settings.raindrop.collection_for(&show_info.slug),
+            &show_info.tag,
+            since,
+        )
+        .await
+        .context("Failed to fetch bookmarks")?;
+
+    if bookmarks.is_empty() {
+        println!(
+            "No bookmarks found with tag {} since {}.",
+            show_info.tag,
+            previous_end.format("%A, %-d %B %Y")
+        );
+        return Ok(None);
+    }
+
+    // Everything bookmarked counts against the discovery suggestions, even
+    // what the cutoff and filters drop below
+    let bookmarked: Vec<(String, String)> = bookmarks
+        .iter()
+        .map(|b| (b.link.clone(), b.title.clone()))
+        .collect();
+
+    // Drop bookmarks created before the previous show actually ended
+    // (Raindrop's date filter is imprecise, so some boundary-day bookmarks
+    // from before the cutoff hour may be included).
+    let before_filter = bookmarks.len();
+    let bookmarks = after_cutoff(bookmarks, previous_end.with_timezone(&Utc));
+    let pre_cutoff_removed = before_filter - bookmarks.len();
+    if pre_cutoff_removed > 0 {
+        println!(
+            "🧹 Dropped {} bookmark(s) from before previous show end",
+            pre_cutoff_removed
+        );
+    }
+
+    if bookmarks.is_empty() {
+        println!("No bookmarks remain after applying precise cutoff.");
+        return Ok(None);
+    }
+
+    // Deduplicate by URL before expensive extraction/summarization
+    let original_count = bookmarks.len();
+    let bookmarks = deduplicate_bookmarks(bookmarks);
+    let duplicates_removed = original_count - bookmarks.len();
+    if duplicates_removed > 0 {
+        println!("🗑️  Removed {} duplicate URL(s)", duplicates_removed);
+    }
+
+    if bookmarks.is_empty() {
+        println!("No unique bookmarks after deduplication.");
+        return Ok(None);
+    }
+
+    // User curation rules from config.toml, applied before any fetching
+    let bookmarks = apply_filters(bookmarks, &filters, Utc::now())?;
+    if bookmarks.is_empty() {
+        println!("No bookmarks remain after applying filters.");
+        return Ok(None);
+    }
+
+    println!("✓ Found {} bookmarks", bookmarks.len());
+    if args.stop_after == Some(StopAfter::Collect) {
+        println!("\n⏹  Stopped after collecting bookmarks");
+        return Ok(None);
+    }
+
+    println!("\n🌐 Extracting article content...");
+    let extractor = ContentExtractor::new()?
+        .with_progress(progress.clone())
+        .with_deadline(settings.budget.fetch_deadline())
+        .with_fetch_settings(settings.fetch.clone())
+        .with_refresh(args.refresh);
+    let urls: Vec<String> = bookmarks.iter().map(|b| b.link.clone()).collect();
+    let (content_results, cached_extractions) =
+        extract_articles(&extractor, urls, args.refetch || args.refresh).await;
+    if cached_extractions > 0 {
+        println!("  {} already fetched by an earlier run", cached_extractions);
+    }
+
+    // Create maps for successful extractions and paywalled URLs
+    let mut content_map: HashMap<String, ArticleContent> = HashMap::new();
+    let mut paywalled_urls: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut skipped_count = 0;
+    let mut out_of_time_count = 0;
+    let by_link: HashMap<&str, &Bookmark> =
+        bookmarks.iter().map(|b| (b.link.as_str(), b)).collect();
+    let mut failures: Vec<FailedFetch> = Vec::new();
+
+    for (url, result) in content_results {
+        if matches!(
+            result,
+            ExtractionResult::Paywalled | ExtractionResult::Failed(_)
+        ) {
+            if let Some(bookmark) = by_link.get(url.as_str()) {
+                failures.push(failed_fetch(&extractor, bookmark));
+            }
+        }
+        match result {
+            ExtractionResult::Success(content) => {
+                content_map.insert(url, content);
+            }
+            ExtractionResult::Paywalled => {
+                paywalled_urls.insert(url);
+            }
+            // Domain rule or robots.txt: fall through to the bookmark excerpt below
+            ExtractionResult::Skipped => {
+                skipped_count += 1;
+            }
+            ExtractionResult::OutOfTime => {
+                out_of_time_count += 1;
+            }
+            ExtractionResult::Failed(reason) => {
+                log_error(&format!("Failed to extract: {} - {}", url, reason));
+            }
+        }
+    }
+
+    let successful_extractions = content_map.len();
+    let paywalled_count = paywalled_urls.len();
+    let failed_count = bookmarks.len()
+        - successful_extractions
+        - paywalled_count
+        - skipped_count
+        - out_of_time_count;
+
+    println!(
+        "✓ Extracted {}/{} articles ({} paywalled, {} skipped by domain rules or robots.txt, {} failed)",
+        successful_extractions,
+        bookmarks.len(),
+        paywalled_count,
+        skipped_count,
+        failed_count
+    );
+    if out_of_time_count > 0 {
+        println!(
+            "⏱  Fetch budget of {} min ran out: {} article(s) not fetched, using their excerpts",
+            settings.budget.fetch_minutes, out_of_time_count
+        );
+    }
+    if args.stop_after == Some(StopAfter::Extract) {
+        println!("\n⏹  Stopped after extraction");
+        return Ok(None);
+    }
+
+    if settings.briefing.translate && ai_enabled {
+        let wanted = content_map
+            .values()
+            .filter(|content| shared::translation::needs_translation(content))
+            .count();
+        if wanted > 0 {
+            println!("\n🌍 Translating {} non-English article(s)...", wanted);
+            let translator = shared::translation::Translator::new()
+                .context("Failed to initialize translator")?
+                .with_usage(usage.clone())
+                .with_prompt(translate_prompt)
+                .with_progress(progress.clone());
+            let translated = translator.translate_all(content_map.iter_mut()).await;
+            cache_translations(&content_map, &translated);
+            println!("✓ Translated {}/{} article(s)", translated.len(), wanted);
+        }
+    }
+
+    // Only summarize articles that have content
+    let mut summary_map: HashMap<String, Summary> = HashMap::new();
+    // Shared by the article and excerpt summaries
+    let summarize_deadline = settings.budget.summarize_deadline();
+
+    // For catching summaries that just repeat the headline
+    let headlines: HashMap<String, String> = bookmarks
+        .iter()
+        .map(|b| (b.link.clone(), b.title.clone()))
+        .collect();
+
+    if !content_map.is_empty() {
+        // Load cached summaries to avoid re-summarizing
+        let mut cache = load_summary_cache();
+        let mut cached_count = 0;
+
+        let articles_for_summary: Vec<(String, String)> = content_map
+            .iter()
+            .filter_map(|(url, content)| {
+                let key = summary_key(url, summary_length, audience_level);
+                if let Some(summary) = cache.get(&key).filter(|_| !args.resummarize) {
+                    // Only reuse successful summaries from cache
+                    if matches!(summary, Summary::Editorial { .. } | Summary::Product { .. }) {
+                        summary_map.insert(url.clone(), summary.clone());
+                        cached_count += 1;
+                        return None;
+                    }
+                }
+                Some((url.clone(), content.text.clone()))
+            })
+            .collect();
+
+        let new_count = articles_for_summary.len();
+        println!(
+            "\n🤖 Summarizing articles with Claude AI... ({} cached, {} new)",
+            cached_count, new_count
+        );
+        if !ai_enabled && new_count > 0 {
+            println!("  AI disabled: using each article's opening as its lede");
+        }
+
+        if !ai_enabled {
+            // Not cached: a later run with a key should summarize these properly
+            for (url, text) in articles_for_summary {
+                summary_map.insert(url, shared::summarizer::extractive_summary(&text));
+            }
+        } else if !articles_for_summary.is_empty() {
+            let summarizer = ClaudeSummarizer::new()?
+                .with_usage(usage.clone())
+                .with_prompt(summarize_prompt)
+                .with_length(summary_length)
+                .with_progress(progress.clone())
+                .with_headlines(headlines.clone())
+                .with_deadline(summarize_deadline);
+
+            let summary_results = if args.batch && articles_for_summary.len() > 1 {
+                match summarizer
+                    .summarize_articles_batch(articles_for_summary.clone())
+                    .await
+                {
+                    Ok(results) => results,
+                    Err(e) => {
+                        println!(
+                            "⚠ Batch summarization unavailable ({:#}), sending requests individually",
+                            e
+                        );
+                        summarizer
+                            .summarize_articles_parallel(articles_for_summary)
+                            .await?
+                    }
+                }
+            } else {
+                summarizer
+                    .summarize_articles_parallel(articles_for_summary)
+                    .await?
+            };
+
+            for (url, summary) in summary_results {
+                // Cache successful summaries for future runs
+                if matches!(summary, Summary::Editorial { .. } | Summary::Product { .. }) {
+                    cache.insert(
+                        summary_key(&url, summary_length, audience_level),
+                        summary.clone(),
+                    );
+                }
+                summary_map.insert(url, summary);
+            }
+
+            save_summary_cache(&cache);
+        }
+
+        let successful_summaries = summary_map
+            .values()
+            .filter(|s| matches!(s, Summary::Editorial { .. } | Summary::Product { .. }))
+            .count();
+
+        println!(
+            "✓ Successfully summarized {}/{} articles",
+            successful_summaries,
+            summary_map.len()
+        );
+        let out_of_time = summary_map
+            .values()
+            .filter(
+                |s| matches!(s, Summary::Failed(reason) if reason == shared::budget::OUT_OF_TIME),
+            )
+            .count();
+        if out_of_time > 0 {
+            println!(
+                "⏱  Summarize budget of {} min ran out: {} article(s) left for the next run",
+                settings.budget.summarize_minutes, out_of_time
+            );
+        }
+    }
+
+    // Articles that couldn't be fetched: summarize the Raindrop excerpt instead
+    let mut excerpt_summaries: HashMap<String, Summary> = HashMap::new();
+    let excerpt_inputs: Vec<(String, String)> = bookmarks
+        .iter()
+        .filter(|b| !content_map.contains_key(&b.link))
+        .filter_map(|b| {
+            let input = shared::summarizer::excerpt_input(&b.title, b.excerpt.as_deref()?)?;
+            Some((b.link.clone(), input))
+        })
+        .collect();
+    if ai_enabled && !excerpt_inputs.is_empty() {
+        println!(
+            "\n📎 Summarizing {} unfetched article(s) from their Raindrop excerpts...",
+            excerpt_inputs.len()
+        );
+        let summarizer = ClaudeSummarizer::new()?
+            .with_usage(usage.clone())
+            .with_prompt(excerpt_prompt)
+            .with_length(summary_length)
+            .with_progress(progress.clone())
+            .with_headlines(headlines.clone())
+            .with_deadline(summarize_deadline);
+        for (url, summary) in summarizer
+            .summarize_articles_parallel(excerpt_inputs)
+            .await?
+        {
+            if matches!(summary, Summary::Editorial { .. } | Summary::Product { .. }) {
+                excerpt_summaries.insert(url, shared::summarizer::mark_excerpt_based(summary));
+            }
+        }
+    }
+    if args.stop_after == Some(StopAfter::Summarize) {
+        report_usage(&usage, &settings.usage, &show_info.slug);
+        println!("\n⏹  Stopped after summarizing");
+        return Ok(None);
+    }
+    let story_fallback = |bookmark: &Bookmark, reason: &str| -> Summary {
+        excerpt_summaries
+            .get(&bookmark.link)
+            .cloned()
+            .unwrap_or_else(|| fallback_summary(bookmark, reason))
+    };
+
+    // Create stories for ALL bookmarks
+    let stories: Vec<Story> = bookmarks
+        .iter()
+        .map(|bookmark| {
+            // Check if article was paywalled
+            if paywalled_urls.contains(&bookmark.link) {
+                return Story {
+                    title: bookmark.title.clone(),
+                    url: bookmark.link.clone(),
+                    created: bookmark.created.clone(),
+                    summary: story_fallback(bookmark, "Paywalled - summary unavailable"),
+                    details: Some(StoryDetails::for_url(&bookmark.link)),
+                    starred: settings.raindrop.is_starred(bookmark),
+                    ..Default::default()
+                };
+            }
+
+            // Check if we have content
+            if let Some(article_content) = content_map.get(&bookmark.link) {
+                let created = article_content
+                    .published_date
+                    .clone()
+                    .unwrap_or_else(|| bookmark.created.clone());
+
+                let summary = summary_map
+                    .get(&bookmark.link)
+                    .cloned()
+                    .unwrap_or_else(|| story_fallback(bookmark, "Summarization failed"));
+
+                return Story {
+                    title: bookmark.title.clone(),
+                    url: bookmark.link.clone(),
+                    created,
+                    summary,
+                    details: Some(StoryDetails::from_article(&bookmark.link, article_content)),
+                    starred: settings.raindrop.is_starred(bookmark),
+                    ..Default::default()
+                };
+            }
+
+            // No content extracted - use excerpt if available
+            Story {
+                title: bookmark.title.clone(),
+                url: bookmark.link.clone(),
+                created: bookmark.created.clone(),
+                summary: story_fallback(bookmark, "Summary not available"),
+                details: Some(StoryDetails::for_url(&bookmark.link)),
+                starred: settings.raindrop.is_starred(bookmark),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    println!(
+        "\n📊 Total stories: {} ({}  successfully summarized, {} failed)",
+        stories.len(),
+        stories
+            .iter()
+            .filter(|s| matches!(
+                s.summary,
+                Summary::Editorial { .. } | Summary::Product { .. }
+            ))
+            .count(),
+        stories
+            .iter()
+            .filter(|s| matches!(s.summary, Summary::Failed(_)))
+            .count()
+    );
+
+    // Merge stories that are the same article under different URLs (AMP,
+    // tracking params, redirects) or syndicated copies with matching titles.
+    let resolved_urls: HashMap<String, String> = content_map
+        .iter()
+        .map(|(url, content)| (url.clone(), content.final_url.clone()))
+        .collect();
+    let (mut stories, merged) = shared::dedup::merge_duplicates(stories, &resolved_urls);
+    if !merged.is_empty() {
+        println!("🗑️  Merged {} near-duplicate story(ies):", merged.len());
+        for m in &merged {
+            println!(
+                "    \"{}\" → \"{}\" ({})",
+                m.dropped_title, m.kept_title, m.reason
+            );
+        }
+    }
+
+    let entities = &settings.briefing.entities;
+    if entities.enabled && ai_enabled {
+        println!("\n🏷  Tagging companies, products and people...");
+        let tagger = shared::entities::EntityTagger::new()
+            .context("Failed to initialize entity tagger")?
+            .with_usage(usage.clone())
+            .with_prompt(entities_prompt)
+            .with_progress(progress.clone());
+        let tagged = tagger.tag_stories(&mut stories, entities).await;
+        println!("✓ Tagged {}/{} story(ies)", tagged, stories.len());
+    } else if entities.enabled {
+        let tagged = entities.tag_known(&mut stories);
+        println!("🏷  Tagged {} story(ies) from the config's names", tagged);
+    }
+
+    let mut topics = if ai_enabled {
+        println!("\n🔗 Clustering stories by topic...");
+        let clusterer = TopicClusterer::new()
+            .context("Failed to initialize topic clusterer")?
+            .with_usage(usage.clone())
+            .with_prompt(cluster_prompt)
+            .with_split_prompt(split_prompt)
+            .with_limits(settings.clustering.clone())
+            .with_progress(progress.clone());
+        clusterer
+            .cluster_stories(stories)
+            .await
+            .context("Failed to cluster stories")?
+    } else {
+        println!("\n🔗 Grouping stories by site (AI disabled)...");
+        let topics = shared::clustering::cluster_by_domain(stories);
+        shared::clustering::apply_size_limits(topics, &settings.clustering)
+    };
+
+    println!("✓ Organized into {} topics", topics.len());
+    settings.briefing.topic_order.apply(&mut topics);
+    shared::clustering::starred_first(&mut topics);
+
+    if args.editorial_pass && ai_enabled {
+        println!("\n✏️  Editing summaries topic by topic...");
+        let editor = shared::editor::SummaryEditor::new()
+            .context("Failed to initialize summary editor")?
+            .with_usage(usage.clone())
+            .with_prompt(edit_prompt)
+            .with_progress(progress.clone());
+        let (edited, changed) = editor.edit_topics(topics).await;
+        topics = edited;
+        println!("✓ Rewrote {} summary(ies)", changed);
+    }
+
+    if settings.briefing.connections && ai_enabled {
+        println!("\n🧵 Writing how each topic's stories connect...");
+        let writer = shared::connections::ConnectionWriter::new()
+            .context("Failed to initialize connections writer")?
+            .with_usage(usage.clone())
+            .with_prompt(connect_prompt)
+            .with_progress(progress.clone());
+        let written = writer.add_connections(&mut topics).await;
+        println!("✓ Added {} connections paragraph(s)", written);
+    }
+
+    // Calculate the show date for the filename (e.g., next Tuesday for MBW)
+    let show_date = schedule.next_date(now);
+
+    let archive = shared::archive::Archive::open_default();
+    match archive.previously_covered(
+        show_date.date_naive(),
+        topics.iter().flat_map(|t| &t.stories),
+    ) {
+        Ok(covered) => {
+            let n = mark_followups(&mut topics, &covered, settings.briefing.locale);
+            if n > 0 {
+                println!("↩ {} story(ies) were in an earlier briefing", n);
+            }
+            if n > 0 && settings.briefing.followup_recaps && ai_enabled {
+                println!("\n↩ Recapping their earlier coverage...");
+                let writer = shared::followups::FollowupWriter::new()
+                    .context("Failed to initialize follow-up writer")?
+                    .with_usage(usage.clone())
+                    .with_prompt(followup_prompt)
+                    .with_progress(progress.clone());
+                let written = writer.add_recaps(&mut topics, &covered).await;
+                println!("✓ Added {} recap(s)", written);
+            }
+        }
+        Err(e) => tracing::warn!("Skipping follow-up check: {:#}", e),
+    }
+
+    match archive.carryover(&show_info.slug, show_date.date_naive()) {
+        Ok(carried) => {
+            let n = add_carryover(&mut topics, carried);
+            if n > 0 {
+                println!("↪ Held {} story(ies) from last week for another look", n);
+            }
+        }
+        Err(e) => tracing::warn!("Skipping carryover: {:#}", e),
+    }
+    report_sources(&topics, &settings.briefing.sources);
+
+    // Keep the structured stories for `briefing review` and `briefing merge`
+    let mut story_data = BriefingData::new(show_info.clone(), topics.clone());
+    if settings.briefing.discovery.enabled {
+        story_data.missed = discover(
+            &settings.briefing.discovery,
+            previous_end.with_timezone(&Utc),
+            &bookmarked,
+        )
+        .await;
+    }
+    let story_file = match shared::io::new_story_filename(&show_info.slug, show_date.date_naive())
+        .and_then(|filename| save_stories(&story_data, &filename))
+    {
+        Ok(path) => {
+            println!("💾 Story data saved to: {}", path.display());
+            Some(path)
+        }
+        Err(e) => {
+            println!("⚠ Could not save story data: {:#}", e);
+            None
+        }
+    };
+    if let Some(story_file) = story_file {
+        // Bookmark order, rather than the order the fetches finished in
+        failures.sort_by_key(|f| bookmarks.iter().position(|b| b.link == f.url));
+        let retry = RetryFile {
+            story_file,
+            failures,
+        };
+        if let Err(e) = retry.save() {
+            println!("⚠ Could not save the failed fetches: {:#}", e);
+        }
+        failures = retry.failures;
+    }
+    if args.stop_after == Some(StopAfter::Cluster) {
+        report_usage(&usage, &settings.usage, &show_info.slug);
+        println!("\n⏹  Stopped after clustering");
+        return Ok(None);
+    }
+
+    println!("\n📝 Generating org-mode document...");
+    let mut org_content = shared::briefing::BriefingGenerator::generate_org_mode_with(
+        &topics,
+        &show_info.name,
+        show_date,
+        &settings.briefing,
+    );
+    org_content.push_str(&shared::discovery::org_section(&story_data.missed));
+    let org_filepath = shared::briefing::BriefingGenerator::save_org_mode(
+        &org_content,
+        &show_info.slug,
+        show_date,
+    )
+    .context("Failed to save org-mode file")?;
+
+    println!(
+        "\n✅ Org-mode document saved to: {}",
+        org_filepath.display()
+    );
+
+    report_usage(&usage, &settings.usage, &show_info.slug);
+
+    if !failures.is_empty() {
+        println!("\n⚠ {} article(s) couldn't be fetched:", failures.len());
+        print!("{}", shared::triage::report(&failures));
+        println!("  Try them again with: briefing retry-failed");
+    }
+
+    let payload = HookPayload {
+        hook: HookPoint::PostCollect.name(),
+        show: show_info.name.clone(),
+        show_slug: show_info.slug.clone(),
+        show_date: show_date.format("%Y-%m-%d").to_string(),
+        artifacts: vec![org_filepath.clone()],
+        details: serde_json::json!({
+            "topics": topics.len(),
+            "stories": topics.iter().map(|t| t.stories.len()).sum::<usize>(),
+        }),
+    };
+    match settings.hooks.run(HookPoint::PostCollect, &payload).await {
+        Ok(0) => {}
+        Ok(n) => println!("🪝 Ran {} post-collect hook(s)", n),
+        Err(e) => println!("⚠ {:#}", e),
+    }
+
+    Ok(Some(org_filepath))
+}
+
+/// Note on each story in `covered` which earlier briefing it was in,
+/// returning how many were marked.
+fn mark_followups(
+    topics: &mut [Topic],
+    covered: &HashMap<String, shared::archive::EarlierStory>,
+    locale: shared::dates::Locale,
+) -> usize {
+    let mut marked = 0;
+    for story in topics.iter_mut().flat_map(|t| &mut t.stories) {
+        if let Some(earlier) = covered.get(&shared::dedup::canonicalize_url(&story.url)) {
+            story.previously = Some(earlier.coverage.label(locale));
+            marked += 1;
+        }
+    }
+    marked
+}
+
+/// Append the `carried` stories that aren't already in `topics` as their own
+/// topic, returning how many were added.
+fn add_carryover(topics: &mut Vec<Topic>, carried: Vec<Story>) -> usize {
+    let present: HashSet<String> = topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .map(|s| shared::dedup::canonicalize_url(&s.url))
+        .collect();
+    let stories: Vec<Story> = carried
+        .into_iter()
+        .filter(|s| !present.contains(&shared::dedup::canonicalize_url(&s.url)))
+        .collect();
+    let added = stories.len();
+    if added > 0 {
+        topics.push(Topic {
+            title: shared::archive::HELD_TOPIC.to_string(),
+            stories,
+            connections: None,
+            notes: None,
+            rationale: None,
+        });
+    }
+    added
+}
+
+/// Collect each configured show in turn. The extraction and summary caches
+/// are shared, so an article bookmarked for several shows is fetched and
+/// summarized once, by the first show that has it. A show that fails
+/// doesn't stop the rest.
+async fn collect_all_shows(args: Args) -> Result<()> {
+    let settings = Settings::load()?;
+    let shows = shared::shows::configured(&settings.shows)?;
+
+    let mut drafts = Vec::new();
+    let mut failed = Vec::new();
+    for show in shows {
+        println!("\n━━━ {} ━━━", show.name);
+        let show_args = Args {
+            show: Some(show.slug.clone()),
+            all_shows: false,
+            ..args.clone()
+        };
+        match Box::pin(collect(show_args)).await {
+            Ok(Some(path)) => drafts.push(path),
+            Ok(None) => {}
+            Err(e) => {
+                println!("⚠ {} failed: {:#}", show.name, e);
+                failed.push(show.slug.clone());
+            }
+        }
+    }
+
+    println!(
+        "\n✓ Wrote {} org draft(s) for {} show(s)",
+        drafts.len(),
+        shows.len()
+    );
+    for path in &drafts {
+        println!("  {}", path.display());
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("Collection failed for {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Count each show's bookmarks so far this week, print a readiness digest,
+/// save it to the data dir and hand it to the `digest` hooks (e.g. to mail
+/// it to the producer).
+async fn run_digest(
+    only: Option<ShowSettings>,
+    config: Config,
+    settings: &Settings,
+    filters: &[Filter],
+) -> Result<()> {
+    let now = shared::timezone::local().now();
+    let raindrop_client = RaindropClient::new(config.raindrop_api_token)?;
+    let extractor = ContentExtractor::new()?.with_fetch_settings(settings.fetch.clone());
+
+    let shows = match &only {
+        Some(show) => std::slice::from_ref(show),
+        None => shared::shows::configured(&settings.shows)?,
+    };
+    let mut digests = Vec::new();
+    for show in shows {
+        let info = show.info();
+        println!("📚 Checking {}...", info.name);
+
+        let schedule = Schedule::new(show, &settings.schedule)?;
+        let bookmarks =
+            show_bookmarks(&raindrop_client, show, &schedule, settings, filters).await?;
+
+        let urls: Vec<String> = bookmarks.iter().map(|b| b.link.clone()).collect();
+        let mut paywalled = 0;
+        let mut failed = 0;
+        for (url, result) in extract_articles(&extractor, urls, false).await.0 {
+            match result {
+                ExtractionResult::Paywalled => paywalled += 1,
+                ExtractionResult::Failed(reason) => {
+                    log_error(&format!("Failed to extract: {} - {}", url, reason));
+                    failed += 1;
+                }
+                ExtractionResult::Success(_)
+                | ExtractionResult::Skipped
+                | ExtractionResult::OutOfTime => {}
+            }
+        }
+
+        digests.push(digest::ShowDigest {
+            next_show: schedule.next_date(schedule.now()),
+            name: info.name,
+            slug: info.slug,
+            bookmarks: bookmarks.len(),
+            paywalled,
+            failed,
+        });
+    }
+
+    let cost_per_story = shared::usage::cost_per_story(&shared::usage::default_log_path())
+        .unwrap_or(digest::DEFAULT_COST_PER_STORY);
+    let text = digest::format_digest(&digests, cost_per_story, now);
+    println!("\n{}", text);
+
+    let date = now.format("%Y-%m-%d").to_string();
+    let path = shared::config::data_dir()
+        .join("digests")
+        .join(format!("{}.txt", date));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, &text).with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("✓ Digest saved to: {}", path.display());
+
+    let (show, show_slug) = match only {
+        Some(show) => {
+            let info = show.info();
+            (info.name, info.slug)
+        }
+        None => ("All shows".to_string(), "all".to_string()),
+    };
+    let payload = HookPayload {
+        hook: HookPoint::Digest.name(),
+        show,
+        show_slug,
+        show_date: date,
+        artifacts: vec![path],
+        details: serde_json::json!({
+            "cost_per_story_usd": cost_per_story,
+            "shows": digests.iter().map(|d| d.to_json(cost_per_story)).collect::<Vec<_>>(),
+        }),
+    };
+    match settings.hooks.run(HookPoint::Digest, &payload).await {
+        Ok(0) => {}
+        Ok(n) => println!("🪝 Ran {} digest hook(s)", n),
+        Err(e) => println!("⚠ {:#}", e),
+    }
+
+    Ok(())
+}
+
+/// Fetch, extract and summarize bookmarks that aren't cached yet, for every
+/// show (or just `only`), so the real collection run mostly hits the caches.
+/// With `watch`, repeat every that many minutes until interrupted.
+async fn run_prefetch(
+    only: Option<ShowSettings>,
+    config: Config,
+    settings: &Settings,
+    filters: &[Filter],
+    watch: Option<u64>,
+) -> Result<()> {
+    let raindrop_client = RaindropClient::new(config.raindrop_api_token)?;
+    let extractor = ContentExtractor::new()?.with_fetch_settings(settings.fetch.clone());
+    let shows = match &only {
+        Some(show) => std::slice::from_ref(show),
+        None => shared::shows::configured(&settings.shows)?,
+    };
+
+    loop {
+        let usage = UsageTracker::new();
+        for show in shows {
+            if let Err(e) = prefetch_show(
+                &raindrop_client,
+                &extractor,
+                show,
+                settings,
+                filters,
+                &usage,
+            )
+            .await
+            {
+                tracing::warn!("prefetch for {} failed: {:#}", show.name, e);
+            }
+        }
+        report_usage(&usage, &settings.usage, "prefetch");
+
+        let Some(minutes) = watch else {
+            return Ok(());
+        };
+        println!(
+            "💤 Next check at {}",
+            (Local::now() + Duration::minutes(minutes as i64)).format("%-l:%M%P")
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(minutes * 60)).await;
+    }
+}
+
+async fn prefetch_show(
+    raindrop_client: &RaindropClient,
+    extractor: &ContentExtractor,
+    show: &ShowSettings,
+    settings: &Settings,
+    filters: &[Filter],
+    usage: &UsageTracker,
+) -> Result<()> {
+    let info = show.info();
+    let schedule = Schedule::new(show, &settings.schedule)?;
+    let bookmarks = show_bookmarks(raindrop_client, show, &schedule, settings, filters).await?;
+
+    let urls: Vec<String> = bookmarks.iter().map(|b| b.link.clone()).collect();
+    let (results, cached_extractions) = extract_articles(extractor, urls, false).await;
+
+    let mut cache = load_summary_cache();
+    let to_summarize: Vec<(String, String)> = results
+        .into_iter()
+        .filter_map(|(url, result)| match result {
+            ExtractionResult::Success(content) if !cache.contains_key(&url) => {
+                Some((url, content.text))
+            }
+            ExtractionResult::Failed(reason) => {
+                log_error(&format!("Failed to extract: {} - {}", url, reason));
+                None
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut summarized = 0;
+    if !to_summarize.is_empty() && shared::llm::credentials_available() {
+        let summarizer = ClaudeSummarizer::new()?
+            .with_usage(usage.clone())
+            .with_prompt(Prompt::load(
+                PromptKind::Summarize,
+                &info,
+                &settings.prompts,
+            )?)
+            .with_length(settings.prompts.summary_length(&info))
+            .with_headlines(
+                bookmarks
+                    .iter()
+                    .map(|b| (b.link.clone(), b.title.clone()))
+                    .collect(),
+            );
+        for (url, summary) in summarizer.summarize_articles_parallel(to_summarize).await? {
+            if matches!(summary, Summary::Editorial { .. } | Summary::Product { .. }) {
+                cache.insert(url, summary);
+                summarized += 1;
+            }
+        }
+        save_summary_cache(&cache);
+    }
+
+    println!(
+        "✓ {}: {} bookmark(s), {} newly fetched, {} newly summarized",
+        info.name,
+        bookmarks.len(),
+        bookmarks.len() - cached_extractions,
+        summarized
+    );
+    Ok(())
+}
+
+/// This week's bookmarks for `show` after the precise cutoff, dedup and the
+/// config filters, without the step-by-step output of a full run.
+async fn show_bookmarks(
+    raindrop_client: &RaindropClient,
+    show: &ShowSettings,
+    schedule: &Schedule,
+    settings: &Settings,
+    filters: &[Filter],
+) -> Result<Vec<Bookmark>> {
+    let info = show.info();
+    let previous_end = schedule.previous_end(schedule.now());
+    let bookmarks = raindrop_client
+        .fetch_bookmarks(
+            settings.raindrop.collection_for(&info.slug),
+            &info.tag,
+            previous_end.date_naive() - Duration::days(1),
+        )
+        .await
+        .with_context(|| format!("Failed to fetch bookmarks for {}", info.name))?;
+    let bookmarks = after_cutoff(bookmarks, previous_end.with_timezone(&Utc));
+    apply_filters(deduplicate_bookmarks(bookmarks), filters, Utc::now())
+}
+
+/// Drop bookmarks created at or before `cutoff` (Raindrop's date filter is
+/// day-granular). Bookmarks with unparseable dates are kept.
+fn after_cutoff(bookmarks: Vec<Bookmark>, cutoff: DateTime<Utc>) -> Vec<Bookmark> {
+    bookmarks
+        .into_iter()
+        .filter(|b| {
+            DateTime::parse_from_rfc3339(&b.created)
+                .map(|dt| dt.with_timezone(&Utc) > cutoff)
+                .unwrap_or(true)
+        })
+        .collect()
+}
+
+/// Description for a story without a generated summary: the bookmark's note
+/// (written by the user, so shown as-is), else its excerpt marked as such.
+fn fallback_summary(bookmark: &Bookmark, reason: &str) -> Summary {
+    if let Some(note) = bookmark.note.as_deref().filter(|n| !n.trim().is_empty()) {
+        return Summary::Editorial {
+            lede: note.to_string(),
+            nutgraf: String::new(),
+            quotes: Vec::new(),
+            reaction: None,
+            by_the_numbers: None,
+        };
+    }
+    match bookmark.excerpt.as_deref().filter(|e| !e.trim().is_empty()) {
+        Some(excerpt) => shared::summarizer::excerpt_summary(excerpt),
+        None => Summary::Failed(reason.to_string()),
+    }
+}
+
+/// Sites listed in the console; the org draft lists them all.
+const SOURCES_SHOWN: usize = 8;
+
+/// Print how many stories each site supplied, and warn about any site over
+/// the configured share.
+fn report_sources(topics: &[Topic], settings: &SourceSettings) {
+    let Some(report) = settings.report(topics) else {
+        return;
+    };
+    println!("\n📰 {}", report.heading());
+    let lines = report.lines();
+    for line in lines.iter().take(SOURCES_SHOWN) {
+        println!("  {}", line);
+    }
+    if lines.len() > SOURCES_SHOWN {
+        println!("  … and {} more", lines.len() - SOURCES_SHOWN);
+    }
+    for warning in settings.warnings(&report) {
+        println!("⚠ {}", warning);
+    }
+}
+
+/// Top stories from Hacker News and Techmeme since `since` that weren't
+/// bookmarked, with a count printed.
+async fn discover(
+    settings: &DiscoverySettings,
+    since: DateTime<Utc>,
+    bookmarked: &[(String, String)],
+) -> Vec<Suggestion> {
+    println!("\n🔭 Looking for stories you might have missed...");
+    let missed = settings.suggestions(since, Utc::now(), bookmarked).await;
+    println!(
+        "✓ {} top story(ies) from elsewhere weren't bookmarked",
+        missed.len()
+    );
+    missed
+}
+
+/// Print what this run's API calls cost and append it to the usage log.
+fn report_usage(usage: &UsageTracker, settings: &UsageSettings, show_slug: &str) {
+    let Some(report) = usage.report(settings) else {
+        return;
+    };
+    println!("\n💰 API usage:\n{}", report);
+
+    if settings.log {
+        let path = shared::usage::default_log_path();
+        if let Err(e) = usage.append_to_log(&path, show_slug, settings) {
+            log_error(&format!("Failed to append usage log: {:#}", e));
+        }
+    }
+}
+
+/// Keep only the bookmarks every filter expression accepts.
+fn apply_filters(
+    bookmarks: Vec<Bookmark>,
+    filters: &[Filter],
+    now: DateTime<Utc>,
+) -> Result<Vec<Bookmark>> {
+    if filters.is_empty() {
+        return Ok(bookmarks);
+    }
+
+    let mut kept = Vec::with_capacity(bookmarks.len());
+    let mut rejected: HashMap<&str, usize> = HashMap::new();
+    for bookmark in bookmarks {
+        let fields = shared::filter::bookmark_fields(&bookmark, now);
+        let mut failed = None;
+        for filter in filters {
+            if !filter.matches(&fields)? {
+                failed = Some(filter.source());
+                break;
+            }
+        }
+        match failed {
+            Some(source) => *rejected.entry(source).or_default() += 1,
+            None => kept.push(bookmark),
+        }
+    }
+
+    for (source, count) in rejected {
+        println!("🔎 Filtered out {} bookmark(s) by `{}`", count, source);
+    }
+    Ok(kept)
+}
+
+/// Remove bookmarks with duplicate URLs, keeping the most recently created one.
+/// URLs are compared in canonical form, so tracking params and AMP variants of
+/// the same link count as duplicates.
+fn deduplicate_bookmarks(bookmarks: Vec<Bookmark>) -> Vec<Bookmark> {
+    use std::collections::hash_map::Entry;
+    let mut seen: HashMap<String, Bookmark> = HashMap::new();
+
+    for bookmark in bookmarks {
+        match seen.entry(shared::dedup::canonicalize_url(&bookmark.link)) {
+            Entry::Occupied(mut e) => {
+                if bookmark.created > e.get().created {
+                    e.insert(bookmark);
+                }
+            }
+            Entry::Vacant(e) => {
+                e.insert(bookmark);
+            }
+        }
+    }
+
+    seen.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::testing::story;
+
+    fn make_bookmark(id: i64, url: &str, created: &str) -> Bookmark {
+        Bookmark {
+            id,
+            title: format!("Article {}", id),
+            link: url.to_string(),
+            excerpt: None,
+            note: None,
+            tags: vec![],
+            created: created.to_string(),
+        }
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        chrono::NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    // ==================== deduplicate_bookmarks ====================
+
+    #[test]
+    fn test_deduplicate_keeps_first_unique_url() {
+        let bookmarks = vec![
+            make_bookmark(1, "https://example.com/a", "2026-01-01"),
+            make_bookmark(2, "https://example.com/b", "2026-01-02"),
+        ];
+
+        let result = deduplicate_bookmarks(bookmarks);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_deduplicate_removes_same_url_different_ids() {
+        let bookmarks = vec![
+            make_bookmark(1, "https://example.com/article", "2026-01-01"),
+            make_bookmark(2, "https://example.com/article", "2026-01-02"),
+        ];
+
+        let result = deduplicate_bookmarks(bookmarks);
+        assert_eq!(result.len(), 1);
+        // Keeps the newer one
+        assert_eq!(result[0].id, 2);
+    }
+
+    #[test]
+    fn test_deduplicate_keeps_newest_created() {
+        let bookmarks = vec![
+            make_bookmark(1, "https://example.com/article", "2026-01-05"),
+            make_bookmark(2, "https://example.com/article", "2026-01-01"),
+            make_bookmark(3, "https://example.com/article", "2026-01-10"),
+        ];
+
+        let result = deduplicate_bookmarks(bookmarks);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, 3); // Newest
+    }
+
+    #[test]
+    fn test_deduplicate_preserves_different_urls() {
+        let bookmarks = vec![
+            make_bookmark(1, "https://example.com/a", "2026-01-01"),
+            make_bookmark(2, "https://example.com/b", "2026-01-01"),
+            make_bookmark(3, "https://example.com/c", "2026-01-01"),
+        ];
+
+        let result = deduplicate_bookmarks(bookmarks);
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn test_deduplicate_treats_tracking_variants_as_same_url() {
+        let bookmarks = vec![
+            make_bookmark(1, "https://example.com/a", "2026-01-01"),
+            make_bookmark(2, "https://example.com/a?utm_source=rss", "2026-01-02"),
+        ];
+
+        let result = deduplicate_bookmarks(bookmarks);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, 2);
+    }
+
+    #[test]
+    fn test_deduplicate_empty_input() {
+        let bookmarks: Vec<Bookmark> = vec![];
+        let result = deduplicate_bookmarks(bookmarks);
+        assert_eq!(result.len(), 0);
+    }
+
+    // ==================== apply_filters ====================
+
+    #[test]
+    fn test_apply_filters_requires_all_expressions() {
+        let bookmarks = vec![
+            make_bookmark(1, "https://youtube.com/watch?v=1", "2026-01-09T00:00:00Z"),
+            make_bookmark(2, "https://example.com/old", "2025-12-01T00:00:00Z"),
+            make_bookmark(3, "https://example.com/new", "2026-01-09T00:00:00Z"),
+        ];
+        let filters = vec![
+            Filter::parse(r#"domain != "youtube.com""#).unwrap(),
+            Filter::parse("age_days < 10").unwrap(),
+        ];
+
+        let result = apply_filters(bookmarks, &filters, utc(2026, 1, 10, 0)).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, 3);
+    }
+
+    // ==================== fallback_summary ====================
+
+    #[test]
+    fn test_fallback_summary_prefers_note_then_marked_excerpt() {
+        let mut bookmark = make_bookmark(1, "https://example.com/a", "2026-01-09T00:00:00Z");
+        assert!(matches!(
+            fallback_summary(&bookmark, "Paywalled"),
+            Summary::Failed(reason) if reason == "Paywalled"
+        ));
+
+        bookmark.excerpt = Some("The publisher's teaser.".to_string());
+        match fallback_summary(&bookmark, "Paywalled") {
+            Summary::Editorial { lede, nutgraf, .. } => {
+                assert_eq!(lede, "The publisher's teaser.");
+                assert_eq!(nutgraf, shared::summarizer::EXCERPT_NOTE);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        bookmark.note = Some("Leo: lead with this".to_string());
+        match fallback_summary(&bookmark, "Paywalled") {
+            Summary::Editorial { lede, nutgraf, .. } => {
+                assert_eq!(lede, "Leo: lead with this");
+                assert!(nutgraf.is_empty());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_add_carryover_skips_stories_already_collected() {
+        let story = |title: &str, url: &str| story(title).with_url(url).build();
+        let mut topics = vec![Topic {
+            title: "Apple".to_string(),
+            stories: vec![story("Bookmarked again", "https://example.com/a")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let carried = vec![
+            story("Bookmarked again", "https://www.example.com/a?utm_source=x"),
+            story("Left over", "https://example.com/b"),
+        ];
+
+        assert_eq!(add_carryover(&mut topics, carried), 1);
+        assert_eq!(topics[1].title, shared::archive::HELD_TOPIC);
+        assert_eq!(topics[1].stories[0].title, "Left over");
+        assert_eq!(add_carryover(&mut topics, Vec::new()), 0);
+        assert_eq!(topics.len(), 2);
+    }
+
+    #[test]
+    fn test_mark_followups() {
+        let story = |url: &str| story("Vision Pro recall").with_url(url).build();
+        let mut topics = vec![Topic {
+            title: "Apple".to_string(),
+            stories: vec![
+                story("https://www.example.com/a?utm_source=x"),
+                story("https://example.com/b"),
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let covered = HashMap::from([(
+            shared::dedup::canonicalize_url("https://example.com/a"),
+            shared::archive::EarlierStory {
+                coverage: shared::archive::Coverage {
+                    show: "TWiT".to_string(),
+                    date: chrono::NaiveDate::from_ymd_opt(2026, 1, 25).unwrap(),
+                },
+                story: story("https://example.com/a"),
+            },
+        )]);
+
+        assert_eq!(
+            mark_followups(&mut topics, &covered, shared::dates::Locale::default()),
+            1
+        );
+        assert_eq!(
+            topics[0].stories[0].previously.as_deref(),
+            Some("TWiT Sun 25 Jan")
+        );
+        assert_eq!(topics[0].stories[1].previously, None);
+    }
+
+    #[test]
+    fn test_summary_key_includes_the_style() {
+        let url = "https://example.com/a";
+        let key = summary_key(url, SummaryLength::Standard, AudienceLevel::Informed);
+        let cache = HashMap::from([(key.clone(), Summary::Insufficient)]);
+
+        assert!(cache.contains_key(&summary_key(
+            url,
+            SummaryLength::Standard,
+            AudienceLevel::Informed
+        )));
+        assert!(!cache.contains_key(&summary_key(
+            url,
+            SummaryLength::OneLiner,
+            AudienceLevel::Informed
+        )));
+        assert!(!cache.contains_key(&summary_key(
+            url,
+            SummaryLength::Standard,
+            AudienceLevel::Expert
+        )));
+        assert!(!cache.contains_key(url));
+    }
+}
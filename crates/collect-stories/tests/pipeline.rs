@@ -0,0 +1,431 @@
+//! collect-stories end to end: bookmarks from a mock Raindrop, articles from
+//! a mock site and summaries and topics from a mock LLM API, checked against
+//! the org draft in `fixtures/weekly.org`.
+//!
+//! Run with `UPDATE_FIXTURES=1` to rewrite the fixture after an intended
+//! change to the output; prepare-briefing's test reads the same file.
+
+use chrono::Timelike;
+use serde_json::{json, Value};
+use shared::testing::{MockServer, Request, Response, Sandbox};
+use std::fs;
+use std::path::Path;
+
+const CONFIG: &str = r#"
+# The articles' dates are fixed and the show date isn't, so their ages
+# would change the fixture
+[briefing.staleness]
+days = 0
+
+[[shows]]
+slug = "weekly"
+name = "The Weekly Show"
+day = "sunday"
+ends = 18
+"#;
+
+/// What the site's address and the bookmarks' save time read as in the
+/// fixture.
+const SITE: &str = "https://news.example";
+const SAVED: &str = "2026-01-30T08:00:00+00:00";
+
+fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+fn site() -> MockServer {
+    MockServer::start(|request| match request.path.as_str() {
+        "/vision-pro" => Response::html(&fixture("articles/vision-pro.html")),
+        "/gemini" => Response::html(&fixture("articles/gemini.html")),
+        "/watch" => Response::html(&fixture("articles/watch.html")),
+        _ => Response::status(404),
+    })
+}
+
+/// When the bookmarks were saved: just now, so after the previous episode
+/// whatever the day.
+fn saved_at() -> String {
+    chrono::Utc::now().with_nanosecond(0).unwrap().to_rfc3339()
+}
+
+fn raindrop(site: &str, created: &str) -> MockServer {
+    let bookmark = |id: i64, path: &str, title: &str, excerpt: &str| {
+        json!({
+            "_id": id,
+            "title": title,
+            "link": format!("{}{}", site, path),
+            "excerpt": excerpt,
+            "note": "",
+            "tags": ["weekly"],
+            "created": created,
+        })
+    };
+    let items = json!([
+        bookmark(1, "/vision-pro", "Apple cuts the price of Vision Pro", ""),
+        bookmark(2, "/gemini", "Google brings Gemini to Chrome", ""),
+        bookmark(3, "/watch", "Apple Watch gets blood pressure alerts", ""),
+        bookmark(
+            4,
+            "/gone",
+            "Microsoft tests ads in the Start menu",
+            "Windows 11 testers are seeing app ads in the Start menu's recommended section."
+        ),
+    ]);
+    MockServer::start(move |request| {
+        if request.path.contains("page=0") {
+            Response::json(&json!({ "items": items, "count": 4 }))
+        } else {
+            Response::json(&json!({ "items": [], "count": 4 }))
+        }
+    })
+}
+
+fn reply(text: &str) -> Response {
+    Response::json(&json!({
+        "content": [{ "type": "text", "text": text }],
+        "usage": { "input_tokens": 100, "output_tokens": 50 },
+    }))
+}
+
+/// Groups the `N: title - lede` lines of the clustering prompt by the
+/// company in the title, the way the real model would.
+fn cluster(prompt: &str) -> String {
+    let topics: Vec<Value> = ["Apple", "Google", "Microsoft"]
+        .iter()
+        .map(|company| {
+            let indices: Vec<usize> = prompt
+                .lines()
+                .filter_map(|line| {
+                    let (index, rest) = line.split_once(": ")?;
+                    let index = index.parse().ok()?;
+                    rest.starts_with(company).then_some(index)
+                })
+                .collect();
+            json!({ "title": company, "article_indices": indices })
+        })
+        .collect();
+    json!({ "topics": topics }).to_string()
+}
+
+fn llm() -> MockServer {
+    MockServer::start(|request: &Request| {
+        let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        let prompt = body["messages"][0]["content"].as_str().unwrap_or_default();
+        if prompt.contains("article_indices") {
+            return reply(&cluster(prompt));
+        }
+        let summary = if prompt.contains("Vision Pro headset") {
+            "FORMAT: EDITORIAL\n\
+             LEDE: Apple dropped the US price of its headset by $1,000, to $2,499.\n\
+             NUTGRAF: Sales have been slow, and a bigger audience could draw the developers it needs.\n\
+             QUOTE: \"The new price reflects lower component costs\" -- Apple"
+        } else if prompt.contains("Gemini assistant") {
+            "FORMAT: EDITORIAL\n\
+             LEDE: Chrome on Windows and macOS now has Gemini built in.\n\
+             NUTGRAF: It can answer questions about the open page, which has privacy groups asking what gets sent."
+        } else if prompt.contains("blood") {
+            "FORMAT: EDITORIAL\n\
+             LEDE: An update lets the watch warn of signs of high blood pressure.\n\
+             NUTGRAF: Regulators in the US and Europe cleared the feature, which needs no cuff."
+        } else if prompt.contains("Start menu") {
+            "FORMAT: EDITORIAL\n\
+             LEDE: Windows 11 testers are seeing app ads in the Start menu.\n\
+             NUTGRAF: Microsoft hasn't said whether the ads will ship to everyone."
+        } else {
+            "Insufficient content for summary"
+        };
+        reply(summary)
+    })
+}
+
+#[test]
+fn test_collect_writes_org_draft() {
+    let site = site();
+    let saved = saved_at();
+    let raindrop = raindrop(site.url(), &saved);
+    let llm = llm();
+    let sandbox = Sandbox::new(CONFIG);
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_collect-stories"))
+        .args(["--show", "weekly"])
+        .env("RAINDROP_TOKEN", "raindrop-token")
+        .env("RAINDROP_API_URL", raindrop.url())
+        .env("ZAI_API_KEY", "llm-key")
+        .env("BRIEFING_LLM_URL", format!("{}/v1/messages", llm.url()))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "collect-stories failed:\n{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let search = &raindrop.requests()[0];
+    assert!(search.path.starts_with("/raindrops/0?"), "{}", search.path);
+    assert!(search.path.contains("weekly"), "{}", search.path);
+    assert_eq!(
+        search.header("authorization"),
+        Some("Bearer raindrop-token")
+    );
+    // Each article once; the missing one is retried before falling back
+    // to the excerpt. Fetches run in parallel, so the order varies.
+    let mut fetched: Vec<String> = site.requests().into_iter().map(|r| r.path).collect();
+    fetched.sort();
+    fetched.dedup();
+    assert_eq!(fetched, ["/gemini", "/gone", "/vision-pro", "/watch"]);
+    // Three summaries and the clustering; the excerpt is used as it is
+    assert_eq!(llm.requests().len(), 4);
+    assert_eq!(llm.requests()[0].header("x-api-key"), Some("llm-key"));
+
+    let org_files = sandbox.documents_with("org");
+    assert_eq!(org_files.len(), 1, "{:?}", org_files);
+    let name = org_files[0].file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("weekly-"), "{}", name);
+
+    // The date line, the site's port and the missing article's date (its
+    // bookmark's save time) depend on the run
+    let org = fs::read_to_string(&org_files[0]).unwrap();
+    let org = org
+        .replace(site.url(), SITE)
+        .replace(":SOURCE: 127.0.0.1", ":SOURCE: news.example")
+        .replace(&saved, SAVED);
+    let org: String = org
+        .lines()
+        .map(|line| match line.starts_with("#+DATE:") {
+            true => "#+DATE: (show date)\n".to_string(),
+            false => format!("{}\n", line),
+        })
+        .collect();
+    let fixture_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/weekly.org");
+    if std::env::var_os("UPDATE_FIXTURES").is_some() {
+        fs::write(&fixture_path, &org).unwrap();
+    }
+    assert_eq!(org, fixture("weekly.org"));
+
+    // The stories are kept for `briefing review` too
+    let stories_dir = sandbox.home().join(".local/share/podcast-briefing/stories");
+    let story_files: Vec<_> = fs::read_dir(&stories_dir).unwrap().collect();
+    assert_eq!(story_files.len(), 1);
+}
+
+#[test]
+fn test_all_shows_share_the_caches() {
+    let site = site();
+    let raindrop = raindrop(site.url(), &saved_at());
+    let llm = llm();
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[[shows]]\nslug = \"extra\"\nname = \"The Extra Show\"\nday = \"sunday\"\nends = 12\n",
+        CONFIG
+    ));
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_collect-stories"))
+        .arg("--all-shows")
+        .env("RAINDROP_TOKEN", "raindrop-token")
+        .env("RAINDROP_API_URL", raindrop.url())
+        .env("ZAI_API_KEY", "llm-key")
+        .env("BRIEFING_LLM_URL", format!("{}/v1/messages", llm.url()))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "collect-stories failed:\n{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Wrote 2 org draft(s) for 2 show(s)"),
+        "{}",
+        stdout
+    );
+
+    // Both shows' searches, but each article fetched and summarized once;
+    // only the missing one is tried again
+    let searches: Vec<String> = raindrop
+        .requests()
+        .into_iter()
+        .map(|r| r.path)
+        .filter(|p| p.contains("page=0"))
+        .collect();
+    assert_eq!(searches.len(), 2, "{:?}", searches);
+    let fetched: Vec<String> = site
+        .requests()
+        .into_iter()
+        .map(|r| r.path)
+        .filter(|p| p != "/gone")
+        .collect();
+    assert_eq!(fetched.len(), 3, "{:?}", fetched);
+    // Three summaries and a clustering per show
+    assert_eq!(llm.requests().len(), 5);
+
+    let mut names: Vec<String> = sandbox
+        .documents_with("org")
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 2, "{:?}", names);
+    assert!(names[0].starts_with("extra-") && names[1].starts_with("weekly-"));
+    let stories_dir = sandbox.home().join(".local/share/podcast-briefing/stories");
+    assert_eq!(fs::read_dir(&stories_dir).unwrap().count(), 2);
+}
+
+#[test]
+fn test_translates_non_english_articles() {
+    let site = MockServer::start(|request| match request.path.as_str() {
+        "/heise" => Response::html(&fixture("articles/heise.html")),
+        _ => Response::status(404),
+    });
+    let bookmarks = json!([{
+        "_id": 1,
+        "title": "Apple ruft die Armbänder der Vision Pro zurück",
+        "link": format!("{}/heise", site.url()),
+        "excerpt": "",
+        "note": "",
+        "tags": ["weekly"],
+        "created": saved_at(),
+    }]);
+    let raindrop = MockServer::start(move |request| {
+        let items = if request.path.contains("page=0") {
+            bookmarks.clone()
+        } else {
+            json!([])
+        };
+        Response::json(&json!({ "items": items, "count": 1 }))
+    });
+    let llm = MockServer::start(|request: &Request| {
+        let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        let prompt = body["messages"][0]["content"].as_str().unwrap_or_default();
+        if prompt.contains("article_indices") {
+            reply(&cluster(prompt))
+        } else if prompt.contains("Translate the German article") {
+            reply(
+                "Apple is recalling the Vision Pro head straps because some of them can \
+                 break. The company will replace them for free.",
+            )
+        } else if prompt.contains("Apple is recalling") {
+            reply(
+                "FORMAT: EDITORIAL\n\
+                 LEDE: Apple will replace Vision Pro head straps that can break.\n\
+                 NUTGRAF: It is not the first problem with the headset's accessories.",
+            )
+        } else {
+            reply("Insufficient content for summary")
+        }
+    });
+    let sandbox = Sandbox::new(&format!("[briefing]\ntranslate = true\n{}", CONFIG));
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_collect-stories"))
+        .args(["--show", "weekly"])
+        .env("RAINDROP_TOKEN", "raindrop-token")
+        .env("RAINDROP_API_URL", raindrop.url())
+        .env("ZAI_API_KEY", "llm-key")
+        .env("BRIEFING_LLM_URL", format!("{}/v1/messages", llm.url()))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "collect-stories failed:\n{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Translated 1/1 article(s)"), "{}", stdout);
+    // The translation and its summary; one story needs no clustering
+    let prompts: Vec<String> = llm
+        .requests()
+        .iter()
+        .map(|r| String::from_utf8_lossy(&r.body).into_owned())
+        .collect();
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts[0].contains("Translate the German article"));
+
+    let org_files = sandbox.documents_with("org");
+    let org = fs::read_to_string(&org_files[0]).unwrap();
+    assert!(org.contains(":LANGUAGE: de\n:TRANSLATED: yes\n"), "{}", org);
+    assert!(
+        org.contains("Apple will replace Vision Pro head straps"),
+        "{}",
+        org
+    );
+}
+
+#[test]
+fn test_topic_size_limits() {
+    let site = site();
+    let saved = saved_at();
+    let raindrop = raindrop(site.url(), &saved);
+    let collect = |clustering: &str| {
+        let llm = llm();
+        let sandbox = Sandbox::new(&format!("[clustering]\n{}\n{}", clustering, CONFIG));
+        let output = sandbox
+            .command(env!("CARGO_BIN_EXE_collect-stories"))
+            .args(["--show", "weekly"])
+            .env("RAINDROP_TOKEN", "raindrop-token")
+            .env("RAINDROP_API_URL", raindrop.url())
+            .env("ZAI_API_KEY", "llm-key")
+            .env("BRIEFING_LLM_URL", format!("{}/v1/messages", llm.url()))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let org = fs::read_to_string(&sandbox.documents_with("org")[0]).unwrap();
+        // The topics, up to the empty back-of-the-book sections
+        let topics: Vec<String> = org
+            .lines()
+            .filter(|line| line.starts_with("* "))
+            .take_while(|line| *line != "* Leo's Picks")
+            .map(|line| line.to_string())
+            .collect();
+        let prompts: Vec<String> = llm
+            .requests()
+            .iter()
+            .map(|r| String::from_utf8_lossy(&r.body).into_owned())
+            .filter(|body| body.contains("article_indices"))
+            .collect();
+        (topics, prompts, org)
+    };
+
+    // Google and Microsoft have one story each
+    let (topics, prompts, org) = collect("min_topic_size = 2");
+    assert_eq!(topics, ["* Apple", "* In Other News"]);
+    let other_news = org.find("* In Other News").unwrap();
+    assert!(
+        org.find("Google brings Gemini").unwrap() > other_news,
+        "{}",
+        org
+    );
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains("at least 2 articles"), "{}", prompts[0]);
+
+    // The model is asked to split Apple's two stories; the mock keeps them
+    // together, so they're split in half
+    let (topics, prompts, _) = collect("max_topic_size = 1");
+    assert_eq!(
+        topics,
+        [
+            "* Apple (1/2)",
+            "* Apple (2/2)",
+            "* Google",
+            "* Microsoft",
+            "* In Other News"
+        ]
+    );
+    assert_eq!(prompts.len(), 2);
+    assert!(
+        prompts[1].contains("filed under the topic \\\"Apple\\\""),
+        "{}",
+        prompts[1]
+    );
+}
@@ -0,0 +1,1095 @@
+//! Turns an edited org draft into the HTML briefing and links CSV and
+//! uploads them. `briefing prepare` and `briefing publish` run this; the
+//! `prepare-briefing` binary is kept for existing scripts.
+
+use anyhow::{Context, Result};
+use shared::archive::Archive;
+use shared::briefing::{BriefingExtras, LinksFormat};
+use shared::dedup::canonicalize_url;
+use shared::hooks::{HookPayload, HookPoint};
+use shared::manifest::{Manifest, Status, Upload};
+use shared::output::Artifact;
+use shared::raindrop::RaindropSettings;
+use shared::schedule::Schedule;
+use shared::settings::Settings;
+use shared::timezone::{Tz, ZoneExt};
+use shared::upload::{UploadSettings, Uploader};
+use shared::{BriefingData, Config, RaindropClient, ShowInfo, Topic};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, clap::Args)]
+pub struct Args {
+    /// Path to the org-mode file (if not provided, will list available files)
+    #[arg(short, long)]
+    pub file: Option<PathBuf>,
+
+    /// HTML theme: light, dark, print, or the path of a CSS file layered over
+    /// the light theme (overrides [briefing] theme in config.toml)
+    #[arg(long, value_name = "THEME")]
+    pub theme: Option<String>,
+
+    /// Download each story's image and show thumbnails in the HTML
+    #[arg(long)]
+    pub images: bool,
+
+    /// Check every link first and add a status column to the CSV
+    #[arg(long)]
+    pub check_links: bool,
+
+    /// Add these story columns to the end of the CSV, e.g.
+    /// `date,source,author,summary-type,words`
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+    pub csv_columns: Vec<shared::briefing::CsvColumn>,
+
+    /// Also save the final rundown as structured data: json, yaml or both
+    /// (`json,yaml`)
+    #[arg(long, value_name = "FORMATS", value_delimiter = ',')]
+    pub export: Vec<shared::export::ExportFormat>,
+
+    /// Save the files without uploading them (upload later with
+    /// `briefing publish`)
+    #[arg(long)]
+    pub no_upload: bool,
+
+    /// Upload the latest briefing (of the draft's show, with `--file`) again
+    /// without preparing it anew
+    #[arg(long, conflicts_with = "no_upload")]
+    pub reupload: bool,
+
+    /// Stop without writing anything if a story is missing its URL, date
+    /// or summary
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Default, clap::Args)]
+pub struct PublishArgs {
+    /// The briefing's HTML file (default: the most recently prepared); the
+    /// links CSV beside it goes too
+    pub file: Option<PathBuf>,
+
+    /// Without a file, publish this show's most recent briefing
+    #[arg(short, long, conflicts_with = "file")]
+    pub show: Option<String>,
+
+    /// Only show whether the server's copies are current
+    #[arg(long)]
+    pub status: bool,
+
+    /// Upload the files even if the server already has them
+    #[arg(long, conflicts_with = "status")]
+    pub force: bool,
+}
+
+pub async fn run(args: Args) -> Result<()> {
+    if args.reupload {
+        // The briefing's date is the show date, not the draft's, so go by
+        // the show
+        let show = args.file.as_deref().map(extract_show_slug).transpose()?;
+        return publish(PublishArgs {
+            show,
+            force: true,
+            ..Default::default()
+        })
+        .await;
+    }
+
+    let org_file = if let Some(path) = args.file {
+        path
+    } else {
+        select_org_file()?
+    };
+
+    println!("📖 Reading org file: {}", org_file.display());
+    let org_content = fs::read_to_string(&org_file)
+        .context(format!("Failed to read org file: {}", org_file.display()))?;
+
+    println!("🔍 Parsing org-mode content...");
+    let (show_name, topics, incomplete) = shared::org::parse_org_mode_checked(&org_content)?;
+    if !incomplete.is_empty() {
+        println!("⚠ Incomplete stories ({}):", incomplete.len());
+        for story in &incomplete {
+            println!("  {}", story);
+        }
+        if args.strict {
+            anyhow::bail!(
+                "{} incomplete stories in {} (--strict); fill in their URL, date and summary first",
+                incomplete.len(),
+                org_file.display()
+            );
+        }
+    }
+    let mut topics = deduplicate_stories(topics);
+    // Stars added while editing the draft move those stories up too
+    shared::clustering::starred_first(&mut topics);
+
+    println!(
+        "✓ Parsed {} topics with {} total stories",
+        topics.len(),
+        topics.iter().map(|t| t.stories.len()).sum::<usize>()
+    );
+
+    let show_slug = extract_show_slug(&org_file)?;
+
+    let mut settings = Settings::load()?;
+    settings.apply_show(&show_slug);
+    let show = shared::shows::find(&settings.shows, &show_slug)
+        .ok()
+        .cloned();
+    let show_info = match &show {
+        Some(show) => show.info(),
+        None => ShowInfo::new(&show_name, &show_slug, format!("#{}", show_slug)),
+    };
+    // The title in the file wins, so a renamed briefing keeps its name
+    let show_name = if show_name.is_empty() {
+        show_info.name.clone()
+    } else {
+        show_name
+    };
+
+    // Calculate the show date for the filename (e.g., next Tuesday for a
+    // Tuesday show). A show missing from [[shows]] keeps the file's date.
+    let show_date = match &show {
+        Some(show) => {
+            let schedule = Schedule::new(show, &settings.schedule)?;
+            schedule.next_date(schedule.now())
+        }
+        None => {
+            println!(
+                "⚠ No [[shows]] entry for '{}' in config.toml; using the file's date",
+                show_slug
+            );
+            let zone = shared::timezone::local();
+            file_date(&org_file).map_or_else(|| zone.now(), |date| zone.at(date.into()))
+        }
+    };
+    if let Some(theme) = args.theme {
+        settings.briefing.theme = Some(theme);
+    }
+    // Catch a mistyped theme before any files are written
+    shared::assets::Theme::load(settings.briefing.theme.as_deref())?;
+
+    for story in topics.iter().flat_map(|t| &t.stories) {
+        let flags = settings.briefing.disclosures.flags(story);
+        if !flags.is_empty() {
+            let flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+            println!(
+                "⚠ Disclosure needed: {} — {}",
+                story.title,
+                flags.join("; ")
+            );
+        }
+        let stale = settings
+            .briefing
+            .staleness
+            .stale_days(story, show_date.date_naive());
+        if let Some(days) = stale {
+            println!(
+                "⚠ Stale: {} — {}",
+                story.title,
+                shared::staleness::label(days)
+            );
+        }
+        let reasons = settings.briefing.sensitive.reasons(story);
+        if !reasons.is_empty() {
+            println!(
+                "◆ Careful framing: {} — {}",
+                story.title,
+                reasons.join(", ")
+            );
+        }
+    }
+
+    let mut extras = BriefingExtras {
+        events: shared::calendar::upcoming(
+            &settings.briefing.calendar,
+            &show_slug,
+            show_date.date_naive(),
+        )
+        .await,
+        csv_columns: args.csv_columns,
+        ..Default::default()
+    };
+    if !extras.events.is_empty() {
+        println!("📅 {} upcoming event(s) this week", extras.events.len());
+    }
+    match Archive::open_default().covered_elsewhere(&show_slug, show_date.date_naive()) {
+        Ok(covered) => extras.covered = covered,
+        Err(e) => tracing::warn!("Skipping cross-show check: {:#}", e),
+    }
+    for story in topics.iter().flat_map(|t| &t.stories) {
+        if let Some(coverage) = extras.covered.get(&canonicalize_url(&story.url)) {
+            let shows: Vec<String> = coverage.iter().map(|c| c.to_string()).collect();
+            println!("↺ Also covered on {}: {}", shows.join(" and "), story.title);
+        }
+    }
+    if settings.briefing.shortener.enabled() {
+        let urls: Vec<String> = topics
+            .iter()
+            .flat_map(|t| &t.stories)
+            .map(|s| s.url.clone())
+            .collect();
+        extras.short_links =
+            shared::shortener::shorten_all(&settings.briefing.shortener, &urls).await;
+        println!("🔗 {} short link(s)", extras.short_links.len());
+    }
+    if args.check_links {
+        println!("🔎 Checking links...");
+        extras.link_status = shared::links::check_all(&topics).await;
+        let problems = shared::links::report(&topics, &extras.link_status);
+        for line in &problems {
+            println!("  {}", line);
+        }
+        println!(
+            "✓ Checked {} link(s): {} dead, {} to look at",
+            extras.link_status.len(),
+            shared::links::dead_count(&extras.link_status),
+            problems.len()
+        );
+    }
+    if args.images {
+        let dir = shared::images::output_dir(&show_slug, show_date);
+        extras.thumbnails = shared::images::capture_all(&topics, &dir).await;
+        println!(
+            "🖼  {} thumbnail(s) saved to: {}",
+            extras.thumbnails.len(),
+            dir.display()
+        );
+    }
+    if settings.briefing.trends {
+        match Archive::open_default().recent(&show_slug, show_date.date_naive(), TREND_DAYS) {
+            Ok(history) => {
+                extras.trends = shared::trends::detect(&topics, show_date.date_naive(), &history);
+            }
+            Err(e) => tracing::warn!("Skipping trends: {:#}", e),
+        }
+    }
+    for &years in &settings.briefing.lookback_years {
+        match Archive::open_default().lookback(&show_slug, show_date.date_naive(), years) {
+            Ok(Some(lookback)) => extras.lookbacks.push(lookback),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping {}-year lookback: {:#}", years, e),
+        }
+    }
+
+    println!("\n📝 Generating HTML briefing...");
+    let html_content = shared::briefing::BriefingGenerator::generate_with_extras(
+        &topics,
+        &show_name,
+        show_date,
+        &settings.briefing,
+        &extras,
+    );
+    let html_filepath =
+        shared::briefing::BriefingGenerator::save(&html_content, &show_slug, show_date)
+            .context("Failed to save HTML file")?;
+
+    println!("✓ HTML saved to: {}", html_filepath.display());
+
+    let mut host_views = Vec::new();
+    if settings.briefing.per_host_views {
+        for host in shared::briefing::assigned_hosts(&topics) {
+            let own = shared::briefing::stories_for_host(&topics, &host);
+            let title = format!("{}: {}'s", show_name, settings.briefing.host_name(&host));
+            let html = shared::briefing::BriefingGenerator::generate_with(
+                &own,
+                &title,
+                show_date,
+                &settings.briefing,
+            );
+            let path = shared::briefing::BriefingGenerator::save_host_view(
+                &html, &show_slug, show_date, &host,
+            )
+            .context("Failed to save host briefing")?;
+            println!(
+                "✓ {} stories for {}: {}",
+                own.iter().map(|t| t.stories.len()).sum::<usize>(),
+                host,
+                path.display()
+            );
+            host_views.push(path);
+        }
+    }
+
+    println!("\n📊 Generating links CSV...");
+    let csv_content =
+        shared::briefing::BriefingGenerator::generate_links_csv_with(&topics, &extras);
+    let csv_filepath =
+        shared::briefing::BriefingGenerator::save_links_csv(&csv_content, &show_slug, show_date)
+            .context("Failed to save CSV file")?;
+
+    println!("✓ CSV saved to: {}", csv_filepath.display());
+
+    let mut links_exports = Vec::new();
+    for &format in &settings.briefing.links_exports {
+        let content = match format {
+            LinksFormat::Opml => shared::briefing::BriefingGenerator::generate_links_opml(
+                &topics,
+                &show_name,
+                show_date,
+                settings.briefing.locale,
+            ),
+            LinksFormat::Org => shared::briefing::BriefingGenerator::generate_links_org(
+                &topics,
+                &show_name,
+                show_date,
+                settings.briefing.locale,
+            ),
+        };
+        let path = shared::briefing::BriefingGenerator::save_links_export(
+            &content, &show_slug, show_date, format,
+        )
+        .context("Failed to save links export")?;
+        println!(
+            "✓ Links {} saved to: {}",
+            format.extension().to_uppercase(),
+            path.display()
+        );
+        links_exports.push(path);
+    }
+
+    let mut social_posts = None;
+    if settings.briefing.social.enabled {
+        let social = &settings.briefing.social;
+        let posts = shared::social::generate(
+            &topics,
+            &social.hashtag(&show_slug),
+            social.limit,
+            &extras.short_links,
+        );
+        let path = shared::social::save(&posts, &show_slug, show_date)
+            .context("Failed to save social posts")?;
+        println!(
+            "✓ {} social post(s) saved to: {}",
+            posts.lines().count(),
+            path.display()
+        );
+        social_posts = Some(path);
+    }
+
+    // Keep the final edited rundown searchable alongside past briefings
+    let data = BriefingData::new(
+        ShowInfo::new(show_name.clone(), show_slug.clone(), show_info.tag),
+        topics,
+    );
+    let mut rundown_exports = Vec::new();
+    for &format in &args.export {
+        let content = format.render(&data)?;
+        let path = shared::export::save(&content, &show_slug, show_date, format)
+            .context("Failed to save rundown export")?;
+        println!(
+            "✓ Rundown {} saved to: {}",
+            format.extension().to_uppercase(),
+            path.display()
+        );
+        rundown_exports.push(path);
+    }
+    match Archive::open_default().store(&data, show_date.date_naive()) {
+        Ok(path) => println!("✓ Archived to: {}", path.display()),
+        Err(e) => println!("⚠ Failed to archive briefing: {:#}", e),
+    }
+
+    if settings.raindrop.writes_back() {
+        match mark_briefed_in_raindrop(&settings.raindrop, &data, show_date).await {
+            Ok(n) => println!("✓ Marked {} bookmark(s) as briefed in Raindrop.io", n),
+            Err(e) => println!("⚠ Failed to update Raindrop.io: {:#}", e),
+        }
+    }
+
+    let payload = HookPayload {
+        hook: HookPoint::PreUpload.name(),
+        show: show_name.clone(),
+        show_slug: show_slug.clone(),
+        show_date: show_date.format("%Y-%m-%d").to_string(),
+        artifacts: [html_filepath.clone(), csv_filepath.clone()]
+            .into_iter()
+            .chain(host_views)
+            .chain(links_exports)
+            .chain(rundown_exports)
+            .chain(social_posts)
+            .collect(),
+        details: serde_json::Value::Null,
+    };
+
+    if args.no_upload {
+        println!("\n☁️  Not uploaded (--no-upload); run `briefing publish` when ready");
+    } else {
+        upload_with_hooks(
+            &settings,
+            payload,
+            &html_filepath,
+            &csv_filepath,
+            &data.topics,
+            false,
+        )
+        .await;
+    }
+
+    println!("\n✅ Done!");
+
+    Ok(())
+}
+
+/// Upload a briefing prepared earlier, running the upload hooks as
+/// `prepare` does.
+pub async fn publish(args: PublishArgs) -> Result<()> {
+    let html_file = match args.file {
+        Some(path) => path,
+        None => latest_briefing(args.show.as_deref())?,
+    };
+    let csv_file = links_csv_for(&html_file);
+    for path in [&html_file, &csv_file] {
+        if !path.exists() {
+            anyhow::bail!("{} not found; run `briefing prepare` first", path.display());
+        }
+    }
+    let show_slug = extract_show_slug(&html_file)?;
+    let mut settings = Settings::load()?;
+    settings.apply_show(&show_slug);
+    let show_name = shared::shows::find(&settings.shows, &show_slug)
+        .map(|show| show.name.clone())
+        .unwrap_or_else(|_| show_slug.clone());
+    let show_date = file_date(&html_file)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    if settings.upload.url.is_none() {
+        anyhow::bail!("No [upload] url in {}", Settings::path().display());
+    }
+    if args.status {
+        return publish_status(&settings.upload, &show_slug, &html_file, &csv_file).await;
+    }
+
+    println!("📤 Publishing {}", html_file.display());
+    let payload = HookPayload {
+        hook: HookPoint::PreUpload.name(),
+        show: show_name,
+        show_slug,
+        show_date,
+        artifacts: vec![html_file.clone(), csv_file.clone()],
+        details: serde_json::Value::Null,
+    };
+    // The archived copy has the topics for the chat message
+    let topics = file_date(&html_file)
+        .and_then(|date| {
+            let archive = Archive::open_default();
+            let entry = archive.find(&payload.show_slug, Some(date)).ok()??;
+            archive.load(&entry).ok()
+        })
+        .map(|data| data.topics)
+        .unwrap_or_default();
+    upload_with_hooks(
+        &settings, payload, &html_file, &csv_file, &topics, args.force,
+    )
+    .await;
+    Ok(())
+}
+
+/// Compare the briefing's files with what was last uploaded, and with the
+/// server's copies when the WebDAV login is set.
+async fn publish_status(
+    settings: &UploadSettings,
+    show_slug: &str,
+    html_file: &Path,
+    csv_file: &Path,
+) -> Result<()> {
+    let manifest = Manifest::load(&Manifest::default_path())?;
+    println!("📋 {}", html_file.display());
+    let server = Uploader::connect(settings).await;
+    let mut current = true;
+    for (label, path, name) in [
+        ("HTML", html_file, "index.html"),
+        ("CSV ", csv_file, "links.csv"),
+    ] {
+        let Some(url) = settings.file_url(show_slug, name) else {
+            continue;
+        };
+        let contents =
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let remote = match &server {
+            Ok(server) => Some(server.stat(&url).await?),
+            Err(_) => None,
+        };
+        // The URL is the show's, so its last upload may be another briefing
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let last = manifest.upload(&url);
+        let upload = last.filter(|upload| upload.file == file);
+        let status = Status::of(upload, &contents, remote.as_ref().map(Option::as_ref));
+        let icon = if status == Status::Current {
+            "✓"
+        } else {
+            "⚠"
+        };
+        let when = upload
+            .and_then(|u| chrono::DateTime::parse_from_rfc3339(&u.uploaded).ok())
+            .map(|time| format!(" (uploaded {})", time.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        println!("  {} {} {} → {}{}", icon, label, status, url, when);
+        if let Some(other) = last.filter(|upload| upload.file != file) {
+            println!("      (the server has {} there)", other.file);
+        }
+        current &= status == Status::Current;
+    }
+    if let Err(e) = server {
+        println!("  (the server's copies weren't checked: {:#})", e);
+    }
+    if !current {
+        println!("\nRun `briefing publish` to upload what changed");
+    }
+    Ok(())
+}
+
+/// Run the pre-upload hooks, upload, then run the post-upload hooks and
+/// post to the `[notify]` webhooks. Problems are reported rather than
+/// returned: the files are saved locally either way.
+async fn upload_with_hooks(
+    settings: &Settings,
+    mut payload: HookPayload,
+    html_path: &Path,
+    csv_path: &Path,
+    topics: &[Topic],
+    force: bool,
+) {
+    if settings.upload.url.is_none() {
+        println!("\n☁️  No [upload] url in config.toml, so nothing was uploaded");
+        return;
+    }
+
+    // A failing pre-upload hook vetoes the upload
+    let hooks = &settings.hooks;
+    if let Err(e) = hooks.run(HookPoint::PreUpload, &payload).await {
+        println!("\n⚠ Upload skipped: {:#} (files saved locally)", e);
+        return;
+    }
+
+    println!("\n☁️  Uploading...");
+    match upload(
+        &settings.upload,
+        &payload.show_slug,
+        html_path,
+        csv_path,
+        force,
+    )
+    .await
+    {
+        Ok(Uploaded { sent: 0, .. }) => {
+            println!(
+                "✓ Already up to date on {} (--force uploads it again)",
+                settings.upload.method.name()
+            );
+        }
+        Ok(Uploaded { urls, .. }) => {
+            println!("✓ Uploaded to {}", settings.upload.method.name());
+
+            payload.hook = HookPoint::PostUpload.name();
+            payload.details = serde_json::json!({ "uploaded": urls });
+            match hooks.run(HookPoint::PostUpload, &payload).await {
+                Ok(0) => {}
+                Ok(n) => println!("🪝 Ran {} post-upload hook(s)", n),
+                Err(e) => println!("⚠ {:#}", e),
+            }
+            if settings.notify.is_enabled() {
+                notify(settings, &payload, &urls[0], topics).await;
+            }
+        }
+        Err(e) => {
+            println!("⚠ Upload failed: {} (files saved locally)", e);
+        }
+    }
+}
+
+/// Tell the team's chat channels the briefing is up.
+async fn notify(settings: &Settings, payload: &HookPayload, html_url: &str, topics: &[Topic]) {
+    let date = chrono::NaiveDate::parse_from_str(&payload.show_date, "%Y-%m-%d")
+        .map(|date| {
+            settings
+                .briefing
+                .locale
+                .format_date(date, shared::dates::DateFormat::Long)
+        })
+        .unwrap_or_else(|_| payload.show_date.clone());
+    let message = shared::notify::message(
+        &payload.show,
+        &date,
+        &settings.notify.link_for(&payload.show_slug, html_url),
+        topics,
+    );
+    for (service, result) in settings.notify.post(&message).await {
+        match result {
+            Ok(()) => println!("✓ Posted to {}", service),
+            Err(e) => println!("⚠ Could not post to {}: {:#}", service, e),
+        }
+    }
+}
+
+/// How much of the archive trend detection looks at (about a month).
+const TREND_DAYS: i64 = 35;
+
+/// How far back to look for the bookmarks behind a briefing's stories. The
+/// collection window is at most two weeks; this leaves room for stories
+/// carried over from an earlier week.
+const WRITE_BACK_DAYS: i64 = 30;
+
+/// Tag and/or move the Raindrop bookmarks the briefing's stories came from,
+/// matched by canonical URL.
+async fn mark_briefed_in_raindrop(
+    settings: &RaindropSettings,
+    data: &BriefingData,
+    show_date: chrono::DateTime<Tz>,
+) -> Result<usize> {
+    let config = Config::from_env()?;
+    let client = RaindropClient::new(config.raindrop_api_token)?;
+    let bookmarks = client
+        .fetch_bookmarks(
+            settings.collection_for(&data.show.slug),
+            &data.show.tag,
+            show_date.date_naive() - chrono::Duration::days(WRITE_BACK_DAYS),
+        )
+        .await?;
+
+    let used: HashSet<String> = data
+        .topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .map(|s| canonicalize_url(&s.url))
+        .collect();
+    let briefed: Vec<_> = bookmarks
+        .into_iter()
+        .filter(|b| used.contains(&canonicalize_url(&b.link)))
+        .collect();
+
+    let tag = settings.briefed_tag_for(&data.show.slug, show_date.date_naive());
+    client
+        .mark_briefed(&briefed, tag.as_deref(), settings.briefed_collection)
+        .await
+}
+
+/// What [`upload`] did.
+struct Uploaded {
+    /// Where the HTML and CSV are
+    urls: Vec<String>,
+    /// How many of them were sent, rather than already on the server
+    sent: usize,
+}
+
+/// Put the HTML and CSV in the show's upload folder, both at once, and
+/// check the server has all of each. Unless `force`, a file the server
+/// already has, as recorded in the manifest, is skipped. Each file that
+/// made it is recorded even if the other one failed.
+async fn upload(
+    settings: &UploadSettings,
+    show_slug: &str,
+    html_path: &Path,
+    csv_path: &Path,
+    force: bool,
+) -> Result<Uploaded> {
+    let (Some(html_url), Some(csv_url)) = (
+        settings.file_url(show_slug, "index.html"),
+        settings.file_url(show_slug, "links.csv"),
+    ) else {
+        anyhow::bail!("No [upload] url in config.toml");
+    };
+    let server = Uploader::connect(settings).await?;
+    let manifest_path = Manifest::default_path();
+    let mut manifest = Manifest::load(&manifest_path)?;
+
+    let uploads = tokio::join!(
+        upload_file(&server, &manifest, "HTML", html_path, &html_url, force),
+        upload_file(&server, &manifest, "CSV ", csv_path, &csv_url, force),
+    );
+    let uploads = [uploads.0, uploads.1];
+    let mut sent = 0;
+    for upload in uploads.iter().filter_map(|u| u.as_ref().ok()).flatten() {
+        manifest.record(upload.clone());
+        sent += 1;
+    }
+    if sent > 0 {
+        manifest.save(&manifest_path)?;
+    }
+    for upload in uploads {
+        upload?;
+    }
+
+    Ok(Uploaded {
+        urls: vec![html_url, csv_url],
+        sent,
+    })
+}
+
+/// Upload `path` to `url`, returning the record for the manifest, or `None`
+/// when the server already has this version.
+async fn upload_file(
+    server: &Uploader,
+    manifest: &Manifest,
+    label: &str,
+    path: &Path,
+    url: &str,
+    force: bool,
+) -> Result<Option<Upload>> {
+    let contents =
+        fs::read(path).with_context(|| format!("Failed to read {} for upload", path.display()))?;
+    let upload = manifest.upload(url);
+    // Only ask the server once the local copy is known to be unchanged
+    if !force
+        && Status::of(upload, &contents, None) == Status::Current
+        && Status::of(upload, &contents, Some(server.stat(url).await?.as_ref())) == Status::Current
+    {
+        println!("  = {} unchanged at {}", label, url);
+        return Ok(None);
+    }
+    let record = Upload::new(path, url, &contents);
+    let remote = server.upload(url, contents).await?;
+    println!("  ✓ {} → {}", label, url);
+    Ok(Some(record.with_version(remote.version)))
+}
+
+fn select_org_file() -> Result<PathBuf> {
+    let documents_dir = shared::output::settings().dir(Artifact::Org)?;
+
+    // Find all .org files in the drafts directory
+    let mut org_files: Vec<PathBuf> = fs::read_dir(&documents_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext == "org")
+                .unwrap_or(false)
+        })
+        // Links exports aren't drafts
+        .filter(|path| !path.to_string_lossy().ends_with("-LINKS.org"))
+        .collect();
+
+    if org_files.is_empty() {
+        anyhow::bail!("No .org files found in {}", documents_dir.display());
+    }
+
+    // Sort by modification time (newest first)
+    org_files.sort_by_key(|path| {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(std::cmp::Reverse)
+    });
+
+    println!("Available org files:\n");
+    for (i, file) in org_files.iter().enumerate() {
+        let filename = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("<invalid filename>");
+        let modified = fs::metadata(file)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| {
+                let datetime: chrono::DateTime<chrono::Local> = t.into();
+                datetime.format("%Y-%m-%d %H:%M").to_string()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+
+        println!("  {}) {} (modified: {})", i + 1, filename, modified);
+    }
+
+    print!("\nSelect file (1-{}): ", org_files.len());
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let selection: usize = input
+        .trim()
+        .parse()
+        .context("Invalid selection. Please enter a number.")?;
+
+    if selection < 1 || selection > org_files.len() {
+        anyhow::bail!(
+            "Selection out of range. Please choose 1-{}",
+            org_files.len()
+        );
+    }
+
+    Ok(org_files[selection - 1].clone())
+}
+
+/// The links CSV saved with `html_file`: `{slug}-{date}-LINKS.csv` beside
+/// `{slug}-{date}.html` unless `[output]` says otherwise.
+fn links_csv_for(html_file: &Path) -> PathBuf {
+    shared::output::settings()
+        .matching(Artifact::Html, html_file, Artifact::Csv, "", "csv")
+        .unwrap_or_else(|| {
+            let stem = html_file
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            html_file.with_file_name(format!("{}-LINKS.csv", stem))
+        })
+}
+
+/// The newest `{slug}-{date}.html` in the HTML output directory, or the
+/// newest for `show`, leaving out per-host views.
+fn latest_briefing(show: Option<&str>) -> Result<PathBuf> {
+    let documents_dir = shared::output::settings().dir(Artifact::Html)?;
+    fs::read_dir(&documents_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("html"))
+        .filter(|path| links_csv_for(path).exists())
+        .filter(|path| show.is_none_or(|show| extract_show_slug(path).is_ok_and(|s| s == show)))
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .ok_or_else(|| anyhow::anyhow!("No prepared briefings in {}", documents_dir.display()))
+}
+
+fn extract_show_slug(org_file: &Path) -> Result<String> {
+    let filename = org_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid filename"))?;
+
+    // Filename format is: {show_slug}-{YYYY-MM-DD}.org, or whatever
+    // [output] template put the slug before the date
+    if let (Some(slug), Some(_)) = shared::archive::parse_filename(filename) {
+        return Ok(slug);
+    }
+
+    // No date: guess the slug from the dashes
+    let parts: Vec<&str> = filename.split('-').collect();
+
+    if parts.len() >= 4 {
+        // If we have at least 4 parts, assume last 3 are YYYY-MM-DD
+        // Take everything except the last 3 parts
+        Ok(parts[..parts.len() - 3].join("-"))
+    } else {
+        // Fallback: use the first part
+        Ok(parts[0].to_string())
+    }
+}
+
+/// The date in a `{show_slug}-{YYYY-MM-DD}.org` filename.
+fn file_date(org_file: &Path) -> Option<chrono::NaiveDate> {
+    let stem = org_file.file_stem()?.to_str()?;
+    shared::archive::parse_filename(stem).1
+}
+
+/// Remove stories with duplicate URLs across all topics.
+/// Keeps the first occurrence of each URL. Empty URLs are not deduplicated.
+/// Topics left without stories are dropped unless they have notes.
+fn deduplicate_stories(topics: Vec<Topic>) -> Vec<Topic> {
+    let mut seen_urls: HashSet<String> = HashSet::new();
+    let mut removed = 0;
+
+    let result: Vec<Topic> = topics
+        .into_iter()
+        .map(|mut topic| {
+            let before = topic.stories.len();
+            topic.stories.retain(|story| {
+                if story.url.is_empty() {
+                    true // Keep stories without URLs
+                } else {
+                    seen_urls.insert(story.url.clone())
+                }
+            });
+            removed += before - topic.stories.len();
+            topic
+        })
+        .filter(|topic| !topic.stories.is_empty() || topic.notes.is_some())
+        .collect();
+
+    if removed > 0 {
+        println!("🗑️  Removed {} duplicate URL(s)", removed);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::{Story, Summary};
+
+    // ==================== extract_show_slug Tests ====================
+
+    #[test]
+    fn test_extract_show_slug_twit() {
+        let path = PathBuf::from("/home/user/Documents/twit-2026-02-01.org");
+        let result = extract_show_slug(&path).unwrap();
+        assert_eq!(result, "twit");
+    }
+
+    #[test]
+    fn test_extract_show_slug_mbw() {
+        let path = PathBuf::from("/home/user/Documents/mbw-2026-02-03.org");
+        let result = extract_show_slug(&path).unwrap();
+        assert_eq!(result, "mbw");
+    }
+
+    #[test]
+    fn test_extract_show_slug_with_hyphens() {
+        let path = PathBuf::from("/home/user/Documents/intelligent-machines-2026-02-04.org");
+        let result = extract_show_slug(&path).unwrap();
+        assert_eq!(result, "intelligent-machines");
+    }
+
+    #[test]
+    fn test_extract_show_slug_short_name() {
+        let path = PathBuf::from("im-2026-02-04.org");
+        let result = extract_show_slug(&path).unwrap();
+        assert_eq!(result, "im");
+    }
+
+    #[test]
+    fn test_extract_show_slug_from_a_template_name() {
+        let path = PathBuf::from("twit-2026-02-01-2.org");
+        assert_eq!(extract_show_slug(&path).unwrap(), "twit");
+        let path = PathBuf::from("twit_briefing_2026-02-01.html");
+        assert_eq!(extract_show_slug(&path).unwrap(), "twit_briefing");
+    }
+
+    // ==================== deduplicate_stories Tests ====================
+
+    #[test]
+    fn test_deduplicate_removes_duplicate_url_in_same_topic() {
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![
+                Story {
+                    title: "First".to_string(),
+                    url: "https://example.com/article".to_string(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                },
+                Story {
+                    title: "Duplicate".to_string(),
+                    url: "https://example.com/article".to_string(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                },
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+
+        let result = deduplicate_stories(topics);
+        assert_eq!(result[0].stories.len(), 1);
+        assert_eq!(result[0].stories[0].title, "First");
+    }
+
+    #[test]
+    fn test_deduplicate_removes_duplicate_url_across_topics() {
+        let topics = vec![
+            Topic {
+                title: "Topic A".to_string(),
+                stories: vec![Story {
+                    title: "First".to_string(),
+                    url: "https://example.com/shared".to_string(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                }],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Topic B".to_string(),
+                stories: vec![Story {
+                    title: "Duplicate".to_string(),
+                    url: "https://example.com/shared".to_string(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                }],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ];
+
+        let result = deduplicate_stories(topics);
+        // Topic B becomes empty and is filtered out
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].title, "Topic A");
+        assert_eq!(result[0].stories.len(), 1);
+    }
+
+    #[test]
+    fn test_deduplicate_keeps_notes_sections() {
+        let topics = vec![Topic {
+            title: "Picks".to_string(),
+            stories: Vec::new(),
+            connections: None,
+            notes: Some("- A great app".to_string()),
+            rationale: None,
+        }];
+
+        let result = deduplicate_stories(topics);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].notes.as_deref(), Some("- A great app"));
+    }
+
+    #[test]
+    fn test_deduplicate_preserves_unique_urls() {
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![
+                Story {
+                    title: "First".to_string(),
+                    url: "https://example.com/first".to_string(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                },
+                Story {
+                    title: "Second".to_string(),
+                    url: "https://example.com/second".to_string(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                },
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+
+        let result = deduplicate_stories(topics);
+        assert_eq!(result[0].stories.len(), 2);
+    }
+
+    #[test]
+    fn test_deduplicate_empty_url_not_deduplicated() {
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![
+                Story {
+                    title: "No URL".to_string(),
+                    url: String::new(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                },
+                Story {
+                    title: "Also No URL".to_string(),
+                    url: String::new(),
+                    created: "2026-01-01".to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                },
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+
+        let result = deduplicate_stories(topics);
+        // Empty URLs should NOT be treated as duplicates of each other
+        assert_eq!(result[0].stories.len(), 2);
+    }
+}
@@ -0,0 +1,360 @@
+//! prepare-briefing end to end: the org draft collect-stories' test checks
+//! in, turned into HTML and CSV and uploaded to a mock WebDAV server.
+//!
+//! Run with `UPDATE_FIXTURES=1` to rewrite the fixtures after an intended
+//! change to the output.
+
+use shared::testing::{MockServer, Response, Sandbox};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SHOW: &str = r#"
+# The show date follows the run date, so the stories' ages would too
+[briefing.staleness]
+days = 0
+
+[[shows]]
+slug = "weekly"
+name = "The Weekly Show"
+day = "sunday"
+ends = 18
+"#;
+
+/// The org draft from collect-stories' test.
+fn draft() -> String {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../collect-stories/tests/fixtures/weekly.org");
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Compares `actual` with the fixture `name`, rewriting it first under
+/// `UPDATE_FIXTURES`.
+fn assert_fixture(name: &str, actual: &str) {
+    let path = fixture_path(name);
+    if std::env::var_os("UPDATE_FIXTURES").is_some() {
+        fs::write(&path, actual).unwrap();
+    }
+    let expected =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    assert_eq!(actual, expected, "{} differs", name);
+}
+
+#[test]
+fn test_prepare_writes_and_uploads_briefing() {
+    let dav = MockServer::webdav();
+    let chat = MockServer::start(|_| Response::status(204));
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[upload]\nurl = \"{}/Briefings\"\n[notify]\ndiscord = \"{}/webhook\"\n",
+        SHOW,
+        dav.url(),
+        chat.url()
+    ));
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    fs::write(&org_file, draft()).unwrap();
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_prepare-briefing"))
+        .arg("--file")
+        .arg(&org_file)
+        .env("WEBDAV_USER", "leo")
+        .env("WEBDAV_PASSWORD", "secret")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "prepare-briefing failed:\n{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The old name still works and says what replaces it
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "prepare-briefing is now `briefing prepare --file {}`",
+        org_file.display()
+    )));
+
+    let html_files = sandbox.documents_with("html");
+    let csv_files = sandbox.documents_with("csv");
+    assert_eq!(html_files.len(), 1, "{:?}", html_files);
+    assert_eq!(csv_files.len(), 1, "{:?}", csv_files);
+    let html = fs::read_to_string(&html_files[0]).unwrap();
+    let csv = fs::read_to_string(&csv_files[0]).unwrap();
+
+    assert_fixture("weekly-LINKS.csv", &csv);
+    let start = html.find("<body>").expect("HTML body");
+    let end = html.find("</body>").expect("HTML body end");
+    // The heading carries the show date and when the briefing was prepared
+    let body: String = html[start..end]
+        .lines()
+        .map(|line| match line.starts_with("<h1") {
+            true => "<h1>(show date)</h1>\n".to_string(),
+            false => format!("{}\n", line),
+        })
+        .collect();
+    assert_fixture("weekly-body.html", &body);
+
+    // Both go up at once, so in either order, and are checked afterwards
+    let mut puts = dav.requests_for("PUT");
+    puts.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(puts.len(), 2);
+    assert_eq!(puts[0].path, "/Briefings/weekly/index.html");
+    assert_eq!(puts[0].body, html.as_bytes());
+    assert_eq!(puts[1].path, "/Briefings/weekly/links.csv");
+    assert_eq!(puts[1].body, csv.as_bytes());
+    // leo:secret
+    assert_eq!(
+        puts[0].header("authorization"),
+        Some("Basic bGVvOnNlY3JldA==")
+    );
+    assert_eq!(dav.requests_for("HEAD").len(), 2);
+
+    // The team hears about it once it's up
+    let posts = chat.requests();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].method, "POST");
+    let message: serde_json::Value = serde_json::from_slice(&posts[0].body).unwrap();
+    let message = message["content"].as_str().unwrap();
+    assert!(
+        message.starts_with("📋 The Weekly Show briefing for "),
+        "{}",
+        message
+    );
+    assert!(message.contains(&format!(
+        "is ready: {}/Briefings/weekly/index.html\n",
+        dav.url()
+    )));
+    assert!(message.ends_with("4 stories in 3 topics:\n• Apple (2)\n• Google (1)\n• Microsoft (1)"));
+}
+
+#[test]
+fn test_images_flag_embeds_thumbnails() {
+    // A 1x1 PNG
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f,
+        0x15, 0xc4, 0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8,
+        0xcf, 0xc0, 0xf0, 0x1f, 0x00, 0x05, 0x00, 0x01, 0xff, 0x89, 0x99, 0x3d, 0x1d, 0x00, 0x00,
+        0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+    let site = MockServer::start(|request| match request.path.as_str() {
+        "/images/vision-pro.png" => Response {
+            status: 200,
+            content_type: "image/png".to_string(),
+            body: PNG.to_vec(),
+            headers: Vec::new(),
+        },
+        _ => Response::status(404),
+    });
+    let sandbox = Sandbox::new(SHOW);
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    let draft = draft().replace(
+        ":IMAGE: https://news.example",
+        &format!(":IMAGE: {}", site.url()),
+    );
+    fs::write(&org_file, draft).unwrap();
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_prepare-briefing"))
+        .arg("--file")
+        .arg(&org_file)
+        .arg("--images")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "prepare-briefing failed:\n{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let html = fs::read_to_string(&sandbox.documents_with("html")[0]).unwrap();
+    assert_eq!(html.matches("<img class=\"thumbnail\"").count(), 1);
+    assert!(html.contains("</h3>\n    <img class=\"thumbnail\" src=\"data:image/png;base64,"));
+
+    let images_dir = fs::read_dir(sandbox.documents())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.to_string_lossy().ends_with("-images"))
+        .expect("images folder");
+    assert!(images_dir.join("01.png").exists());
+}
+
+#[test]
+fn test_check_links_flag_adds_status_column() {
+    let site = MockServer::start(|request| match request.path.as_str() {
+        "/vision-pro" | "/gemini" => Response::status(200),
+        "/watch" => Response::status(403),
+        _ => Response::status(404),
+    });
+    let sandbox = Sandbox::new(SHOW);
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    fs::write(
+        &org_file,
+        draft().replace("https://news.example", site.url()),
+    )
+    .unwrap();
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_prepare-briefing"))
+        .arg("--file")
+        .arg(&org_file)
+        .arg("--check-links")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "prepare-briefing failed:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("✓ Checked 4 link(s): 1 dead, 2 to look at"),
+        "{}",
+        stdout
+    );
+
+    let requests = site.requests();
+    assert!(requests.iter().all(|r| r.method == "HEAD"));
+    let csv = fs::read_to_string(&sandbox.documents_with("csv")[0]).unwrap();
+    let csv = csv.replace(site.url(), "https://news.example");
+    assert_eq!(
+        csv,
+        ",Apple,Apple cuts the price of Vision Pro,,https://news.example/vision-pro,ok\n\
+         ,,Apple Watch gets blood pressure alerts,,https://news.example/watch,paywalled\n\
+         ,,,,,\n\
+         ,Google,Google brings Gemini to Chrome,,https://news.example/gemini,ok\n\
+         ,,,,,\n\
+         ,Microsoft,Microsoft tests ads in the Start menu,,https://news.example/gone,dead (404 Not Found)\n\
+         ,,,,,\n"
+    );
+}
+
+#[test]
+fn test_export_flag_saves_the_rundown() {
+    let sandbox = Sandbox::new(SHOW);
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    fs::write(&org_file, draft()).unwrap();
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_prepare-briefing"))
+        .arg("--file")
+        .arg(&org_file)
+        .args(["--no-upload", "--export", "json,yaml"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "prepare-briefing failed:\n{}",
+        stdout
+    );
+
+    let json = &sandbox.documents_with("json")[0];
+    assert!(json.to_string_lossy().ends_with("-RUNDOWN.json"));
+    let data: shared::BriefingData =
+        serde_json::from_str(&fs::read_to_string(json).unwrap()).unwrap();
+    assert_eq!(data.show.slug, "weekly");
+    assert_eq!(data.topics[0].title, "Apple");
+    assert_eq!(
+        data.topics[0].stories[0].title,
+        "Apple cuts the price of Vision Pro"
+    );
+
+    let yaml = fs::read_to_string(&sandbox.documents_with("yaml")[0]).unwrap();
+    assert!(yaml.contains("\nshow:\n"), "{}", yaml);
+    assert!(yaml.contains("  slug: \"weekly\"\n"), "{}", yaml);
+    assert!(
+        yaml.contains("title: \"Apple cuts the price of Vision Pro\"\n"),
+        "{}",
+        yaml
+    );
+}
+
+#[test]
+fn test_strict_flag_refuses_incomplete_stories() {
+    let sandbox = Sandbox::new(SHOW);
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    let draft = draft().replace(
+        "*** URL\nhttps://news.example/watch\n\n*** Date\n2026-01-29T12:00:00+00:00\n\n",
+        "",
+    );
+    fs::write(&org_file, draft).unwrap();
+    let prepare = |strict: bool| {
+        let mut command = sandbox.command(env!("CARGO_BIN_EXE_prepare-briefing"));
+        command.arg("--file").arg(&org_file).arg("--no-upload");
+        if strict {
+            command.arg("--strict");
+        }
+        command.output().unwrap()
+    };
+
+    let output = prepare(true);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("line 34: \"Apple Watch gets blood pressure alerts\" has no URL, date"),
+        "{}",
+        stdout
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--strict"));
+    assert!(sandbox.documents_with("html").is_empty());
+
+    // Without --strict it's only a warning
+    let output = prepare(false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("⚠ Incomplete stories (1):"), "{}", stdout);
+    assert_eq!(sandbox.documents_with("html").len(), 1);
+}
+
+#[test]
+fn test_output_settings_place_and_number_files() {
+    let sandbox = Sandbox::new(&format!(
+        "{}\n[output.html]\ndir = \"~/Briefings\"\nname = \"{{slug}}-{{date}}-{{rev}}\"\n\n\
+         [output.csv]\ndir = \"~/Briefings/links\"\n",
+        SHOW
+    ));
+    let org_file = sandbox.documents().join("weekly-2026-02-01.org");
+    fs::write(&org_file, draft()).unwrap();
+    let files = |dir: &Path, extension: &str| {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(extension))
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+
+    for _ in 0..2 {
+        let output = sandbox
+            .command(env!("CARGO_BIN_EXE_prepare-briefing"))
+            .arg("--file")
+            .arg(&org_file)
+            .arg("--no-upload")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "prepare-briefing failed:\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+
+    let briefings = sandbox.home().join("Briefings");
+    let mut html = files(&briefings, "html");
+    html.sort_by_key(String::len);
+    assert_eq!(html.len(), 2, "{:?}", html);
+    assert!(html[0].starts_with("weekly-"), "{:?}", html);
+    assert_eq!(html[1], html[0].replace(".html", "-2.html"));
+    let csv = files(&briefings.join("links"), "csv");
+    assert_eq!(csv, vec![html[0].replace(".html", "-LINKS.csv")]);
+    assert!(sandbox.documents_with("html").is_empty());
+}
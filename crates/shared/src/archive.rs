@@ -0,0 +1,871 @@
+//! Archive of past briefings.
+//!
+//! Each briefing is stored as a `BriefingData` JSON file under
+//! `~/.local/share/podcast-briefing/archive/{slug}/{YYYY-MM-DD}.json`, keyed by
+//! show and show date. The archive is small enough (a few hundred files) that
+//! search simply scans it. The one index is `coverage-index.json` at the
+//! archive's root: the story URLs and titles of each archived briefing, so
+//! noting stories another show covers, or that were briefed before,
+//! doesn't load every briefing. It's kept up
+//! to date by [`Archive::store`] and caught up with files added or removed
+//! by hand when it's read.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::clustering::Story;
+use crate::dates::{DateFormat, Locale};
+use crate::dedup::{canonicalize_url, similarity, TITLE_THRESHOLD};
+use crate::models::BriefingData;
+use crate::quotes::Quote;
+use crate::summarizer::Summary;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub slug: String,
+    pub date: NaiveDate,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub slug: String,
+    pub date: NaiveDate,
+    pub topic: String,
+    pub title: String,
+    pub url: String,
+}
+
+/// What a show covered some years before a briefing.
+#[derive(Debug, Clone)]
+pub struct Lookback {
+    pub years: u32,
+    /// Date of the archived briefing
+    pub date: NaiveDate,
+    /// (title, url) of its stories, in briefing order
+    pub stories: Vec<(String, String)>,
+}
+
+/// A story another show briefs too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub show: String,
+    pub date: NaiveDate,
+}
+
+impl Coverage {
+    /// The show and its date, e.g. "TWiT Sun 1 Feb".
+    pub fn label(&self, locale: Locale) -> String {
+        format!(
+            "{} {}",
+            self.show,
+            locale.format_date(self.date, DateFormat::Short)
+        )
+    }
+}
+
+/// A story as an earlier briefing had it, from
+/// [`Archive::previously_covered`].
+#[derive(Debug, Clone)]
+pub struct EarlierStory {
+    pub coverage: Coverage,
+    pub story: Story,
+}
+
+impl std::fmt::Display for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label(Locale::default()))
+    }
+}
+
+/// How many days apart two shows' briefings can be for a story they share
+/// to be noted in each.
+const COVERAGE_WINDOW_DAYS: i64 = 7;
+
+/// Name of the coverage index in the archive's root.
+const COVERAGE_INDEX: &str = "coverage-index.json";
+
+/// One archived briefing in the coverage index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedBriefing {
+    slug: String,
+    show: String,
+    /// `YYYY-MM-DD`, as in the archive's file names
+    date: String,
+    /// Canonical URLs of its stories
+    urls: Vec<String>,
+    /// Titles of its stories; missing from indexes written before titles
+    /// were kept
+    #[serde(default)]
+    titles: Vec<String>,
+}
+
+impl IndexedBriefing {
+    fn new(data: &BriefingData, date: NaiveDate) -> Self {
+        Self {
+            slug: data.show.slug.clone(),
+            show: data.show.name.clone(),
+            date: date.format("%Y-%m-%d").to_string(),
+            urls: data
+                .topics
+                .iter()
+                .flat_map(|t| &t.stories)
+                .map(|s| canonicalize_url(&s.url))
+                .collect(),
+            titles: data
+                .topics
+                .iter()
+                .flat_map(|t| &t.stories)
+                .map(|s| s.title.clone())
+                .collect(),
+        }
+    }
+
+    fn is(&self, slug: &str, date: NaiveDate) -> bool {
+        self.slug == slug && self.date == date.format("%Y-%m-%d").to_string()
+    }
+}
+
+/// How far an archived show may be from the exact anniversary and still
+/// count as "this week".
+const LOOKBACK_WINDOW_DAYS: i64 = 3;
+
+/// Stories left over from a briefing older than this aren't carried over.
+const CARRYOVER_DAYS: i64 = 14;
+
+/// Title of the topic collect-stories puts carried-over stories in.
+pub const HELD_TOPIC: &str = "Held from last week";
+
+pub struct Archive {
+    root: PathBuf,
+}
+
+impl Archive {
+    /// The archive in the data dir.
+    pub fn open_default() -> Self {
+        Self::at(crate::config::data_dir().join("archive"))
+    }
+
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn path_for(&self, slug: &str, date: NaiveDate) -> PathBuf {
+        self.root
+            .join(slug)
+            .join(format!("{}.json", date.format("%Y-%m-%d")))
+    }
+
+    pub fn contains(&self, slug: &str, date: NaiveDate) -> bool {
+        self.path_for(slug, date).exists()
+    }
+
+    /// Store a briefing under its show slug and `date`, replacing any
+    /// existing entry for that show and date.
+    pub fn store(&self, data: &BriefingData, date: NaiveDate) -> Result<PathBuf> {
+        let path = self.path_for(&data.show.slug, date);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(data).context("Failed to serialize briefing")?;
+        fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let mut index = self.read_index();
+        index.retain(|b| !b.is(&data.show.slug, date));
+        index.push(IndexedBriefing::new(data, date));
+        self.write_index(&index)?;
+        Ok(path)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join(COVERAGE_INDEX)
+    }
+
+    /// The coverage index as last written; empty if there's none yet.
+    fn read_index(&self) -> Vec<IndexedBriefing> {
+        fs::read_to_string(self.index_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_index(&self, index: &[IndexedBriefing]) -> Result<()> {
+        let path = self.index_path();
+        let json = serde_json::to_string(index).context("Failed to serialize coverage index")?;
+        fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The coverage index, first dropping briefings no longer in the
+    /// archive (or indexed without their titles) and adding ones stored
+    /// some other way (e.g. copied in from another machine).
+    fn coverage_index(&self) -> Result<Vec<IndexedBriefing>> {
+        let entries = self.entries()?;
+        let mut index = self.read_index();
+        let before = index.clone();
+        index.retain(|b| {
+            b.titles.len() == b.urls.len() && entries.iter().any(|e| b.is(&e.slug, e.date))
+        });
+        for entry in &entries {
+            if !index.iter().any(|b| b.is(&entry.slug, entry.date)) {
+                index.push(IndexedBriefing::new(&self.load(entry)?, entry.date));
+            }
+        }
+        if index != before {
+            self.write_index(&index)?;
+        }
+        Ok(index)
+    }
+
+    /// All archived briefings, oldest first.
+    pub fn entries(&self) -> Result<Vec<ArchiveEntry>> {
+        let mut entries = Vec::new();
+        if !self.root.exists() {
+            return Ok(entries);
+        }
+
+        for show_dir in fs::read_dir(&self.root)
+            .with_context(|| format!("Failed to read {}", self.root.display()))?
+        {
+            let show_dir = show_dir?.path();
+            let Some(slug) = show_dir.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !show_dir.is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(&show_dir)? {
+                let path = file?.path();
+                let date = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+                if let (Some(date), Some("json")) =
+                    (date, path.extension().and_then(|e| e.to_str()))
+                {
+                    entries.push(ArchiveEntry {
+                        slug: slug.to_string(),
+                        date,
+                        path,
+                    });
+                }
+            }
+        }
+
+        entries.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.slug.cmp(&b.slug)));
+        Ok(entries)
+    }
+
+    pub fn load(&self, entry: &ArchiveEntry) -> Result<BriefingData> {
+        let content = fs::read_to_string(&entry.path)
+            .with_context(|| format!("Failed to read {}", entry.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", entry.path.display()))
+    }
+
+    /// The archived `slug` briefing nearest to `years` before `date`, if
+    /// one falls within the same week.
+    pub fn lookback(&self, slug: &str, date: NaiveDate, years: u32) -> Result<Option<Lookback>> {
+        let target = date
+            .with_year(date.year() - years as i32)
+            // Feb 29 in a year without one
+            .or_else(|| (date - chrono::Duration::days(1)).with_year(date.year() - years as i32));
+        let Some(target) = target else {
+            return Ok(None);
+        };
+
+        let nearest = self
+            .entries()?
+            .into_iter()
+            .filter(|e| e.slug == slug)
+            .map(|e| ((e.date - target).num_days().abs(), e))
+            .filter(|(distance, _)| *distance <= LOOKBACK_WINDOW_DAYS)
+            .min_by_key(|(distance, _)| *distance);
+        let Some((_, entry)) = nearest else {
+            return Ok(None);
+        };
+
+        let data = self.load(&entry)?;
+        Ok(Some(Lookback {
+            years,
+            date: entry.date,
+            stories: data
+                .topics
+                .into_iter()
+                .flat_map(|t| t.stories)
+                .map(|s| (s.title, s.url))
+                .collect(),
+        }))
+    }
+
+    /// The `slug` briefings from the `days` before `date`, newest first.
+    pub fn recent(
+        &self,
+        slug: &str,
+        date: NaiveDate,
+        days: i64,
+    ) -> Result<Vec<(NaiveDate, BriefingData)>> {
+        let since = date - chrono::Duration::days(days);
+        let mut recent = Vec::new();
+        for entry in self.entries()?.into_iter().rev() {
+            if entry.slug != slug || entry.date >= date || entry.date < since {
+                continue;
+            }
+            recent.push((entry.date, self.load(&entry)?));
+        }
+        Ok(recent)
+    }
+
+    /// The latest archived `slug` briefing, or the one on `date`.
+    pub fn find(&self, slug: &str, date: Option<NaiveDate>) -> Result<Option<ArchiveEntry>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .rfind(|e| e.slug == slug && date.is_none_or(|d| e.date == d)))
+    }
+
+    /// Stories from the last `slug` briefing before `date` that
+    /// `briefing reconcile` recorded as not discussed. Nothing is carried
+    /// over from a briefing that wasn't reconciled, and stories that were
+    /// already held once are dropped: those in the [`HELD_TOPIC`] topic and,
+    /// since a held story may have been moved to another topic or renamed,
+    /// those that were in the briefing before as well.
+    pub fn carryover(&self, slug: &str, date: NaiveDate) -> Result<Vec<Story>> {
+        let Some((last_date, data)) = self.recent(slug, date, CARRYOVER_DAYS)?.into_iter().next()
+        else {
+            return Ok(Vec::new());
+        };
+        let Some(discussed) = &data.discussed else {
+            return Ok(Vec::new());
+        };
+        let mut skipped: HashSet<String> =
+            discussed.iter().map(|url| canonicalize_url(url)).collect();
+        if let Some((_, before)) = self
+            .recent(slug, last_date, CARRYOVER_DAYS)?
+            .into_iter()
+            .next()
+        {
+            skipped.extend(
+                before
+                    .topics
+                    .iter()
+                    .flat_map(|t| &t.stories)
+                    .map(|s| canonicalize_url(&s.url)),
+            );
+        }
+        Ok(data
+            .topics
+            .into_iter()
+            .filter(|t| t.title != HELD_TOPIC)
+            .flat_map(|t| t.stories)
+            .filter(|s| !skipped.contains(&canonicalize_url(&s.url)))
+            .collect())
+    }
+
+    /// Stories in briefings for shows other than `slug` within a week of
+    /// `date`, before or after it, keyed by canonical URL. Of two
+    /// briefings sharing a story, the one prepared second sees the first
+    /// right away; the first sees the second when it's prepared again.
+    pub fn covered_elsewhere(
+        &self,
+        slug: &str,
+        date: NaiveDate,
+    ) -> Result<HashMap<String, Vec<Coverage>>> {
+        let mut covered: HashMap<String, Vec<Coverage>> = HashMap::new();
+        for briefing in self.coverage_index()? {
+            let Ok(other) = NaiveDate::parse_from_str(&briefing.date, "%Y-%m-%d") else {
+                continue;
+            };
+            if briefing.slug == slug || (other - date).num_days().abs() >= COVERAGE_WINDOW_DAYS {
+                continue;
+            }
+            for url in briefing.urls {
+                covered.entry(url).or_default().push(Coverage {
+                    show: briefing.show.clone(),
+                    date: other,
+                });
+            }
+        }
+        for coverage in covered.values_mut() {
+            coverage.sort_by_key(|c| c.date);
+        }
+        Ok(covered)
+    }
+
+    /// The latest briefing before `date`, for any show, that had each of
+    /// `stories`: the same canonical URL or a near-identical title, with
+    /// the story as it was briefed then. Keyed by the story's canonical
+    /// URL; stories never briefed are left out.
+    pub fn previously_covered<'a>(
+        &self,
+        date: NaiveDate,
+        stories: impl IntoIterator<Item = &'a Story>,
+    ) -> Result<HashMap<String, EarlierStory>> {
+        let mut earlier: Vec<(NaiveDate, IndexedBriefing)> = self
+            .coverage_index()?
+            .into_iter()
+            .filter_map(|b| Some((NaiveDate::parse_from_str(&b.date, "%Y-%m-%d").ok()?, b)))
+            .filter(|(other, _)| *other < date)
+            .collect();
+        earlier.sort_by_key(|(other, _)| std::cmp::Reverse(*other));
+
+        let mut loaded: HashMap<PathBuf, Vec<Story>> = HashMap::new();
+        let mut covered = HashMap::new();
+        for story in stories {
+            let url = canonicalize_url(&story.url);
+            let found = earlier.iter().find_map(|(other, b)| {
+                let position = b.urls.iter().position(|u| *u == url).or_else(|| {
+                    b.titles
+                        .iter()
+                        .position(|t| similarity(t, &story.title) >= TITLE_THRESHOLD)
+                })?;
+                Some((other, b, position))
+            });
+            let Some((other, briefing, position)) = found else {
+                continue;
+            };
+
+            // The index lists stories in briefing order
+            let path = self.path_for(&briefing.slug, *other);
+            if !loaded.contains_key(&path) {
+                let entry = ArchiveEntry {
+                    slug: briefing.slug.clone(),
+                    date: *other,
+                    path: path.clone(),
+                };
+                let stories = self
+                    .load(&entry)?
+                    .topics
+                    .into_iter()
+                    .flat_map(|t| t.stories)
+                    .collect();
+                loaded.insert(path.clone(), stories);
+            }
+            if let Some(then) = loaded[&path].get(position) {
+                covered.insert(
+                    url,
+                    EarlierStory {
+                        coverage: Coverage {
+                            show: briefing.show.clone(),
+                            date: *other,
+                        },
+                        story: then.clone(),
+                    },
+                );
+            }
+        }
+        Ok(covered)
+    }
+
+    /// Stories whose title, URL or summary contain every word of `query`
+    /// (case-insensitive), newest briefing first.
+    pub fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut hits = Vec::new();
+        if words.is_empty() {
+            return Ok(hits);
+        }
+
+        for entry in self.entries()?.into_iter().rev() {
+            let data = match self.load(&entry) {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::warn!("skipping {:#}", e);
+                    continue;
+                }
+            };
+            for topic in &data.topics {
+                for story in &topic.stories {
+                    let haystack = searchable_text(story);
+                    if words.iter().all(|w| haystack.contains(w)) {
+                        hits.push(SearchHit {
+                            slug: entry.slug.clone(),
+                            date: entry.date,
+                            topic: topic.title.clone(),
+                            title: story.title.clone(),
+                            url: story.url.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(hits)
+    }
+}
+
+/// A summary's quotes on one line, speakers included.
+fn quote_lines(quotes: &[Quote]) -> String {
+    quotes
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn searchable_text(story: &Story) -> String {
+    let mut text = format!("{} {}", story.title, story.url);
+    match &story.summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        } => {
+            let quotes = quote_lines(quotes);
+            for part in [
+                Some(lede),
+                Some(nutgraf),
+                Some(&quotes),
+                reaction.as_ref(),
+                by_the_numbers.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                text.push(' ');
+                text.push_str(part);
+            }
+        }
+        Summary::Product {
+            the_product,
+            quotes,
+            reaction,
+            ..
+        } => {
+            let quotes = quote_lines(quotes);
+            for part in [Some(the_product), Some(&quotes), reaction.as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                text.push(' ');
+                text.push_str(part);
+            }
+        }
+        Summary::Insufficient | Summary::Failed(_) => {}
+    }
+    text.to_lowercase()
+}
+
+/// Split a briefing filename stem such as `weekly-2026-02-01` into the show
+/// slug and date. Either part may be missing from legacy filenames.
+pub fn parse_filename(stem: &str) -> (Option<String>, Option<NaiveDate>) {
+    let bytes = stem.as_bytes();
+    for start in 0..bytes.len().saturating_sub(9) {
+        let Some(candidate) = stem.get(start..start + 10) else {
+            continue;
+        };
+        if let Ok(date) = NaiveDate::parse_from_str(candidate, "%Y-%m-%d") {
+            let slug = stem[..start]
+                .trim_end_matches(['-', '_', ' '])
+                .to_lowercase();
+            return ((!slug.is_empty()).then_some(slug), Some(date));
+        }
+    }
+    let slug = stem.trim().to_lowercase();
+    ((!slug.is_empty()).then_some(slug), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::Topic;
+    use crate::models::ShowInfo;
+    use tempfile::tempdir;
+
+    fn briefing(slug: &str, title: &str, lede: &str) -> BriefingData {
+        BriefingData::new(
+            ShowInfo::new("Show", slug, format!("#{}", slug)),
+            vec![Topic {
+                title: "Apple".to_string(),
+                stories: vec![Story {
+                    title: title.to_string(),
+                    url: "https://example.com/a".to_string(),
+                    summary: Summary::Editorial {
+                        lede: lede.to_string(),
+                        nutgraf: "It matters.".to_string(),
+                        quotes: Vec::new(),
+                        reaction: None,
+                        by_the_numbers: None,
+                    },
+                    ..Default::default()
+                }],
+                connections: None,
+                notes: None,
+                rationale: None,
+            }],
+        )
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_store_and_list_entries() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        archive
+            .store(&briefing("twit", "A", "x"), date("2026-02-01"))
+            .unwrap();
+        archive
+            .store(&briefing("mbw", "B", "y"), date("2025-06-03"))
+            .unwrap();
+
+        let entries = archive.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].slug, "mbw");
+        assert!(archive.contains("twit", date("2026-02-01")));
+        assert_eq!(archive.load(&entries[1]).unwrap().topics.len(), 1);
+    }
+
+    #[test]
+    fn test_search_matches_all_words_in_summary() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        archive
+            .store(
+                &briefing("twit", "Vision Pro ships", "Apple ships headset"),
+                date("2024-02-04"),
+            )
+            .unwrap();
+        archive
+            .store(
+                &briefing("twit", "Pixel 9", "Google phone"),
+                date("2024-08-18"),
+            )
+            .unwrap();
+
+        let hits = archive.search("apple HEADSET").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "Vision Pro ships");
+        assert!(archive.search("apple google").unwrap().is_empty());
+        assert!(archive.search("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lookback_finds_same_week() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        archive
+            .store(
+                &briefing("twit", "Vision Pro ships", "x"),
+                date("2025-02-02"),
+            )
+            .unwrap();
+        archive
+            .store(&briefing("twit", "Too early", "x"), date("2025-01-26"))
+            .unwrap();
+        archive
+            .store(&briefing("mbw", "Other show", "x"), date("2025-02-01"))
+            .unwrap();
+
+        let found = archive
+            .lookback("twit", date("2026-02-01"), 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.date, date("2025-02-02"));
+        assert_eq!(found.stories[0].0, "Vision Pro ships");
+        assert!(archive
+            .lookback("twit", date("2026-02-01"), 5)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_carryover_only_after_reconcile() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        let mut last_week = briefing("twit", "Discussed", "x");
+        let mut skipped = last_week.topics[0].stories[0].clone();
+        skipped.title = "Didn't get to it".to_string();
+        skipped.url = "https://example.com/b".to_string();
+        last_week.topics[0].stories.push(skipped);
+        archive.store(&last_week, date("2026-01-25")).unwrap();
+
+        let this_week = date("2026-02-01");
+        assert!(archive.carryover("twit", this_week).unwrap().is_empty());
+
+        last_week.discussed = Some(vec!["https://www.example.com/a/".to_string()]);
+        archive.store(&last_week, date("2026-01-25")).unwrap();
+        let carried = archive.carryover("twit", this_week).unwrap();
+        assert_eq!(carried.len(), 1);
+        assert_eq!(carried[0].title, "Didn't get to it");
+
+        // Held once already
+        last_week.topics[0].title = HELD_TOPIC.to_string();
+        archive.store(&last_week, date("2026-01-25")).unwrap();
+        assert!(archive.carryover("twit", this_week).unwrap().is_empty());
+
+        let found = archive.find("twit", None).unwrap().unwrap();
+        assert_eq!(found.date, date("2026-01-25"));
+        assert!(archive.find("twit", Some(this_week)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_carryover_skips_held_story_moved_to_another_topic() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        // Two weeks ago the story wasn't discussed
+        let mut first = briefing("twit", "Didn't get to it", "x");
+        first.discussed = Some(Vec::new());
+        archive.store(&first, date("2026-01-18")).unwrap();
+
+        // Last week it was held, then moved to "Apple" and renamed during
+        // review, and again not discussed
+        let mut held = briefing("twit", "Still didn't get to it", "x");
+        held.topics[0].title = "Apple".to_string();
+        let mut discussed = held.topics[0].stories[0].clone();
+        discussed.title = "Discussed".to_string();
+        discussed.url = "https://example.com/b".to_string();
+        let mut fresh = discussed.clone();
+        fresh.title = "New last week".to_string();
+        fresh.url = "https://example.com/c".to_string();
+        held.topics[0].stories.extend([discussed, fresh]);
+        held.discussed = Some(vec!["https://example.com/b".to_string()]);
+        archive.store(&held, date("2026-01-25")).unwrap();
+
+        let carried = archive.carryover("twit", date("2026-02-01")).unwrap();
+        let titles: Vec<&str> = carried.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["New last week"]);
+    }
+
+    #[test]
+    fn test_covered_elsewhere_this_week() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        let mut mbw = briefing("mbw", "Vision Pro ships", "x");
+        mbw.show.name = "MBW".to_string();
+        archive.store(&mbw, date("2026-02-03")).unwrap();
+        archive
+            .store(&briefing("im", "Old news", "x"), date("2026-01-21"))
+            .unwrap();
+        archive
+            .store(&briefing("twit", "Our own", "x"), date("2026-02-01"))
+            .unwrap();
+
+        let covered = archive
+            .covered_elsewhere("twit", date("2026-02-08"))
+            .unwrap();
+        let hits = &covered[&canonicalize_url("https://example.com/a")];
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].to_string(), "MBW Tue 3 Feb");
+
+        // And the other way: MBW's briefing, prepared again, notes TWiT's
+        // from later in the week
+        let mut twit = briefing("twit", "Vision Pro ships", "x");
+        twit.show.name = "TWiT".to_string();
+        archive.store(&twit, date("2026-02-08")).unwrap();
+        let covered = archive
+            .covered_elsewhere("mbw", date("2026-02-03"))
+            .unwrap();
+        let hits = &covered[&canonicalize_url("https://example.com/a")];
+        assert_eq!(
+            hits.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            ["Show Sun 1 Feb", "TWiT Sun 8 Feb"]
+        );
+    }
+
+    #[test]
+    fn test_previously_covered() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        let mut twit = briefing("twit", "Apple recalls the Vision Pro headset", "x");
+        twit.show.name = "TWiT".to_string();
+        archive.store(&twit, date("2026-01-25")).unwrap();
+        let mut older = briefing("mbw", "Vision Pro ships", "x");
+        older.show.name = "MBW".to_string();
+        archive.store(&older, date("2026-01-13")).unwrap();
+
+        let mut same_url = twit.topics[0].stories[0].clone();
+        same_url.title = "Vision Pro recall widens".to_string();
+        let mut same_title = same_url.clone();
+        same_title.url = "https://other.example/recall".to_string();
+        same_title.title = "Apple recalls the Vision Pro headset".to_string();
+        let mut fresh = same_title.clone();
+        fresh.url = "https://other.example/new".to_string();
+        fresh.title = "Google launches a phone".to_string();
+
+        let covered = archive
+            .previously_covered(date("2026-02-01"), [&same_url, &same_title, &fresh])
+            .unwrap();
+        // The latest earlier briefing counts
+        let earlier = &covered[&canonicalize_url(&same_url.url)];
+        assert_eq!(earlier.coverage.to_string(), "TWiT Sun 25 Jan");
+        assert_eq!(earlier.story.title, "Apple recalls the Vision Pro headset");
+        assert_eq!(
+            covered[&canonicalize_url(&same_title.url)]
+                .coverage
+                .to_string(),
+            "TWiT Sun 25 Jan"
+        );
+        assert_eq!(covered.len(), 2);
+
+        // Not briefings on or after the show date
+        let covered = archive
+            .previously_covered(date("2026-01-13"), [&same_url])
+            .unwrap();
+        assert!(covered.is_empty());
+    }
+
+    #[test]
+    fn test_coverage_index_follows_the_archive() {
+        let dir = tempdir().unwrap();
+        let archive = Archive::at(dir.path());
+        archive
+            .store(
+                &briefing("mbw", "Vision Pro ships", "x"),
+                date("2026-02-03"),
+            )
+            .unwrap();
+        assert!(dir.path().join(COVERAGE_INDEX).exists());
+        // The index isn't mistaken for a show
+        assert_eq!(archive.entries().unwrap().len(), 1);
+
+        // Briefings copied in or deleted by hand are caught up with
+        let copied = archive.path_for("im", date("2026-02-04"));
+        fs::create_dir_all(copied.parent().unwrap()).unwrap();
+        fs::copy(archive.path_for("mbw", date("2026-02-03")), &copied).unwrap();
+        fs::remove_file(archive.path_for("mbw", date("2026-02-03"))).unwrap();
+
+        let covered = archive
+            .covered_elsewhere("twit", date("2026-02-08"))
+            .unwrap();
+        let hits = &covered[&canonicalize_url("https://example.com/a")];
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].date, date("2026-02-04"));
+        assert_eq!(archive.read_index().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_filename() {
+        assert_eq!(
+            parse_filename("twit-2026-02-01"),
+            (Some("twit".to_string()), Some(date("2026-02-01")))
+        );
+        assert_eq!(
+            parse_filename("intelligent-machines-2025-01-08"),
+            (
+                Some("intelligent-machines".to_string()),
+                Some(date("2025-01-08"))
+            )
+        );
+        assert_eq!(
+            parse_filename("2024-03-10"),
+            (None, Some(date("2024-03-10")))
+        );
+        assert_eq!(
+            parse_filename("MBW notes"),
+            (Some("mbw notes".to_string()), None)
+        );
+    }
+}
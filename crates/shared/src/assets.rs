@@ -0,0 +1,191 @@
+//! Default assets compiled into the binaries.
+//!
+//! Everything under `crates/shared/assets/` is embedded at build time, so a
+//! single downloaded binary works on a fresh machine. `export` writes the
+//! assets (plus the generated story-file JSON schema) to the config dir, and
+//! `load` prefers those customized copies over the built-in ones.
+
+use anyhow::{Context, Result};
+use rust_embed::RustEmbed;
+use std::path::{Path, PathBuf};
+
+use crate::models::BriefingData;
+
+#[derive(RustEmbed)]
+#[folder = "assets/"]
+struct Embedded;
+
+/// Path the story-file schema is exported under.
+pub const SCHEMA_PATH: &str = "schema/briefing-data.schema.json";
+
+/// Look up an embedded text asset by its path relative to `assets/`.
+pub fn get(path: &str) -> Option<String> {
+    Embedded::get(path).map(|file| String::from_utf8_lossy(&file.data).into_owned())
+}
+
+/// Load an asset, preferring a customized copy in the config dir over the
+/// embedded default.
+pub fn load(path: &str) -> Option<String> {
+    let custom = crate::config::config_dir().join(path);
+    match std::fs::read_to_string(&custom) {
+        Ok(content) => Some(content),
+        Err(_) => get(path),
+    }
+}
+
+/// Names of all embedded assets, sorted.
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = Embedded::iter().map(|name| name.into_owned()).collect();
+    names.sort();
+    names
+}
+
+/// The HTML stylesheet: the user's exported copy if present, else the built-in.
+pub fn default_theme() -> String {
+    load("themes/default.css").unwrap_or_default()
+}
+
+/// Names of the built-in themes.
+pub const THEMES: &[&str] = &["light", "dark", "print"];
+
+/// A stylesheet for the HTML briefing.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub css: String,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            css: default_theme(),
+        }
+    }
+
+    /// Resolve `name`: `light` (the default), `dark`, `print`, any
+    /// `themes/{name}.css` in the config dir, or the path of a CSS file.
+    /// Every theme is layered over the light stylesheet, so an override
+    /// file only needs the rules it changes.
+    pub fn load(name: Option<&str>) -> Result<Self> {
+        let name = match name {
+            None | Some("light") | Some("default") => return Ok(Self::light()),
+            Some(name) => name,
+        };
+        let overlay = if name.ends_with(".css") || name.contains('/') {
+            let path = match (name.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(name),
+            };
+            std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read theme {}", path.display()))?
+        } else {
+            load(&format!("themes/{}.css", name)).with_context(|| {
+                format!("Unknown theme `{}` (built in: {})", name, THEMES.join(", "))
+            })?
+        };
+        Ok(Self {
+            name: name.to_string(),
+            css: format!("{}\n{}", default_theme(), overlay),
+        })
+    }
+
+    /// Print-friendly output has every topic expanded, since collapsed
+    /// `<details>` don't print.
+    pub fn expands_topics(&self) -> bool {
+        self.name == "print"
+    }
+}
+
+/// JSON schema for the `BriefingData` story files, generated from the types.
+pub fn briefing_schema() -> String {
+    let schema = schemars::schema_for!(BriefingData);
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+/// Write every embedded asset and the schema under `dir`. Existing files are
+/// left alone unless `force` is set, so re-exporting never clobbers edits.
+/// Returns `(written, skipped)` paths.
+pub fn export(dir: &Path, force: bool) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let mut files: Vec<(String, String)> = list()
+        .into_iter()
+        .filter_map(|name| get(&name).map(|content| (name, content)))
+        .collect();
+    files.push((SCHEMA_PATH.to_string(), briefing_schema()));
+
+    let mut written = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, content) in files {
+        let path = dir.join(&name);
+        if path.exists() && !force {
+            skipped.push(path);
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok((written, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_default_theme_is_embedded() {
+        assert!(list().contains(&"themes/default.css".to_string()));
+        assert!(default_theme().contains("details.topic"));
+    }
+
+    #[test]
+    fn test_themes_layer_over_light() {
+        let light = Theme::load(None).unwrap();
+        assert_eq!(light.css, default_theme());
+
+        let dark = Theme::load(Some("dark")).unwrap();
+        assert!(dark.css.starts_with(&light.css));
+        assert!(dark.css.contains("#1e1f22"));
+        assert!(Theme::load(Some("print")).unwrap().expands_topics());
+        assert!(Theme::load(Some("neon")).is_err());
+
+        let dir = tempdir().unwrap();
+        let custom = dir.path().join("studio.css");
+        std::fs::write(&custom, "h2 { color: purple; }").unwrap();
+        let theme = Theme::load(custom.to_str()).unwrap();
+        assert!(theme.css.ends_with("h2 { color: purple; }"));
+        assert!(!theme.expands_topics());
+    }
+
+    #[test]
+    fn test_schema_describes_story_fields() {
+        let schema = briefing_schema();
+        assert!(schema.contains("\"topics\""));
+        assert!(schema.contains("\"stories\""));
+        assert!(schema.contains("\"Editorial\""));
+    }
+
+    #[test]
+    fn test_export_does_not_overwrite_without_force() {
+        let dir = tempdir().unwrap();
+        let (written, skipped) = export(dir.path(), false).unwrap();
+        assert!(skipped.is_empty());
+        assert!(written.contains(&dir.path().join(SCHEMA_PATH)));
+
+        let theme = dir.path().join("themes/default.css");
+        std::fs::write(&theme, "/* mine */").unwrap();
+
+        let (_, skipped) = export(dir.path(), false).unwrap();
+        assert!(skipped.contains(&theme));
+        assert_eq!(std::fs::read_to_string(&theme).unwrap(), "/* mine */");
+
+        export(dir.path(), true).unwrap();
+        assert!(std::fs::read_to_string(&theme).unwrap().contains("body"));
+    }
+}
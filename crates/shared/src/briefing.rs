@@ -1,159 +1,699 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Local, Utc};
-use std::fs;
+use chrono::DateTime;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
 
-use crate::clustering::Topic;
+use crate::archive::{Coverage, Lookback};
+use crate::assets::Theme;
+use crate::calendar::{CalendarSettings, Event};
+use crate::clustering::{Story, StoryDetails, Topic};
+use crate::dates::{self, DateFormat, Locale, ParsedDate};
+use crate::dedup::canonicalize_url;
+use crate::disclosures::DisclosureSettings;
+use crate::discovery::DiscoverySettings;
+use crate::entities::{Entities, EntitySettings};
+use crate::links::LinkStatus;
+use crate::org_template::OrgTemplate;
+use crate::output::Artifact;
+use crate::quotes::Quote;
+use crate::sensitive::SensitiveSettings;
+use crate::shortener::ShortenerSettings;
+use crate::social::SocialSettings;
+use crate::sources::SourceSettings;
+use crate::staleness::StalenessSettings;
 use crate::summarizer::Summary;
+use crate::talk_time::TalkTimeSettings;
+use crate::timezone::{Tz, ZoneExt};
+use crate::topic_order::TopicOrder;
+use crate::trends::Trend;
+
+/// `[briefing]` section of config.toml.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BriefingSettings {
+    /// Open topics that cover two or more products with a table comparing
+    /// their cost, availability and platforms
+    pub comparison_tables: bool,
+    /// Start the briefing with a "By the Numbers" list of key figures
+    pub by_the_numbers: bool,
+    /// Open topics of three or more stories with a "How these connect"
+    /// paragraph written by the model (one extra request per topic)
+    pub connections: bool,
+    /// Recap what an earlier briefing said about each follow-up story in
+    /// one line written by the model (one extra request per follow-up)
+    pub followup_recaps: bool,
+    /// Translate non-English articles into English before summarizing
+    /// them (one extra request per article)
+    pub translate: bool,
+    /// Also write an HTML briefing per host with only their stories
+    pub per_host_views: bool,
+    /// Display name and label color by host tag, e.g. `[briefing.hosts.leo]`
+    pub hosts: HashMap<String, HostSettings>,
+    /// Sponsors and companies whose stories need an on-air disclaimer
+    pub disclosures: DisclosureSettings,
+    /// Tag stories with the companies, products and people they name
+    pub entities: EntitySettings,
+    /// How old a story can be before it's flagged
+    pub staleness: StalenessSettings,
+    /// Mark stories about layoffs, lawsuits, breaches and deaths
+    pub sensitive: SensitiveSettings,
+    /// Estimated minutes per topic and in total, in the contents list
+    pub talk_time: TalkTimeSettings,
+    /// ICS feeds for the "This Week" section
+    pub calendar: CalendarSettings,
+    /// End with what the show covered this many years ago, e.g. `[1, 5]`
+    pub lookback_years: Vec<u32>,
+    /// HTML theme: `light` (default), `dark`, `print` or a CSS file path
+    pub theme: Option<String>,
+    /// Note topics that have been building over recent weeks
+    pub trends: bool,
+    /// Short links for each story, for reading URLs on air
+    pub shortener: ShortenerSettings,
+    /// Ready-to-post snippets for each topic's lead story
+    pub social: SocialSettings,
+    /// Stories per site, with a warning when one site has too many
+    pub sources: SourceSettings,
+    /// Top Hacker News and Techmeme stories that weren't bookmarked
+    pub discovery: DiscoverySettings,
+    /// Structured links files to write besides the CSV, e.g. `["opml", "org"]`
+    pub links_exports: Vec<LinksFormat>,
+    /// Language for dates in the briefing and org draft, e.g. `"de"`;
+    /// English if unset
+    pub locale: Locale,
+    /// Topics to pin first and last after clustering, and how to sort the
+    /// rest; a `[[shows]]` table's `topic_order` overrides it
+    pub topic_order: TopicOrder,
+    /// Write story URLs in the org draft as org links titled with the
+    /// headline, `[[https://…][Title]]`
+    pub org_links: bool,
+    /// Layout of each story in the org draft, from `templates/org-story.org`
+    #[serde(skip)]
+    pub org_template: OrgTemplate,
+}
 
-pub struct BriefingGenerator;
+/// A topic → story → URL export for rundown and show-notes software.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinksFormat {
+    Opml,
+    Org,
+}
 
-impl BriefingGenerator {
-    fn format_date(date_str: &str) -> String {
-        // Try RFC 3339 first (e.g., "2026-02-07T02:15:35.268Z")
-        if let Ok(dt) = date_str.parse::<DateTime<Utc>>() {
-            return dt.format("%-d-%b-%Y %-I:%M%p").to_string();
+impl LinksFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            LinksFormat::Opml => "opml",
+            LinksFormat::Org => "org",
+        }
+    }
+}
+
+/// A per-story metadata column for the links CSV, from `--csv-columns`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// The article's published date, or the bookmark's
+    Date,
+    /// Site the article is on, e.g. `theverge.com`
+    Source,
+    Author,
+    /// `editorial`, `product`, `insufficient` or `failed`
+    SummaryType,
+    /// Words in the extracted article text
+    Words,
+}
+
+impl CsvColumn {
+    pub const ALL: &[CsvColumn] = &[
+        CsvColumn::Date,
+        CsvColumn::Source,
+        CsvColumn::Author,
+        CsvColumn::SummaryType,
+        CsvColumn::Words,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Date => "date",
+            CsvColumn::Source => "source",
+            CsvColumn::Author => "author",
+            CsvColumn::SummaryType => "summary-type",
+            CsvColumn::Words => "words",
         }
-        // Try common date-only formats (legacy org files)
-        for fmt in &["%a, %e %b %Y", "%a, %d %b %Y", "%Y-%m-%d"] {
-            if let Ok(nd) = chrono::NaiveDate::parse_from_str(date_str.trim(), fmt) {
-                return nd.format("%-d-%b-%Y").to_string();
+    }
+
+    fn value(self, story: &Story) -> String {
+        let details = story.details.as_ref();
+        match self {
+            CsvColumn::Date => story.created.clone(),
+            CsvColumn::Source => details.and_then(|d| d.source.clone()).unwrap_or_default(),
+            CsvColumn::Author => details.and_then(|d| d.author.clone()).unwrap_or_default(),
+            CsvColumn::SummaryType => match story.summary {
+                Summary::Editorial { .. } => "editorial",
+                Summary::Product { .. } => "product",
+                Summary::Insufficient => "insufficient",
+                Summary::Failed(_) => "failed",
             }
+            .to_string(),
+            CsvColumn::Words => details
+                .and_then(|d| d.words)
+                .map(|w| w.to_string())
+                .unwrap_or_default(),
         }
-        // Fallback to original string
-        date_str.to_string()
     }
+}
 
-    /// Calculate the next show date as a DateTime
-    pub fn next_show_datetime(show_name: &str, from_date: DateTime<Utc>) -> DateTime<Utc> {
-        use chrono::{Datelike, Timelike, Weekday};
+impl std::str::FromStr for CsvColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('_', "-");
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|c| c.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|c| c.name()).collect();
+                format!("unknown CSV column '{}' (use {})", s, names.join(", "))
+            })
+    }
+}
 
-        // Show schedule: (target weekday, cutoff hour in Pacific time)
-        // After the cutoff hour on show day, we target NEXT week's show
-        let (target_weekday, cutoff_hour) = match show_name {
-            "This Week in Tech" => (Weekday::Sun, 18),    // 6p Pacific
-            "MacBreak Weekly" => (Weekday::Tue, 14),      // 2p Pacific
-            "Intelligent Machines" => (Weekday::Wed, 18), // 6p Pacific
-            _ => (Weekday::Sun, 18),                      // Default to Sunday 6p
-        };
+/// Briefing sections that come from outside the stories, gathered by the
+/// caller before generating.
+#[derive(Debug, Clone, Default)]
+pub struct BriefingExtras {
+    /// Upcoming events, from [`crate::calendar::upcoming`]
+    pub events: Vec<Event>,
+    /// Past briefings, from [`crate::archive::Archive::lookback`]
+    pub lookbacks: Vec<Lookback>,
+    /// Recurring topics, from [`crate::trends::detect`]
+    pub trends: Vec<Trend>,
+    /// Stories other shows briefed this week by canonical URL, from
+    /// [`crate::archive::Archive::covered_elsewhere`]
+    pub covered: HashMap<String, Vec<Coverage>>,
+    /// Short link by story URL, from [`crate::shortener::shorten_all`]
+    pub short_links: HashMap<String, String>,
+    /// Thumbnail data URI by story URL, from [`crate::images::capture_all`]
+    pub thumbnails: HashMap<String, String>,
+    /// Link check by story URL, from [`crate::links::check_all`]
+    pub link_status: HashMap<String, LinkStatus>,
+    /// Metadata columns after all the others in the links CSV
+    pub csv_columns: Vec<CsvColumn>,
+}
 
-        let current_day = from_date.weekday().num_days_from_monday();
-        let target_day = target_weekday.num_days_from_monday();
-        let current_hour = from_date.hour();
+/// Most archived stories listed per lookback.
+const MAX_LOOKBACK_STORIES: usize = 10;
 
-        // Calculate days until next occurrence of target day
-        let days_until_target = if current_day == target_day {
-            // Today is show day - check if we're past the cutoff
-            if current_hour >= cutoff_hour {
-                7 // Past cutoff, use next week
-            } else {
-                0 // Before cutoff, use today
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HostSettings {
+    pub name: Option<String>,
+    /// Any CSS color
+    pub color: Option<String>,
+}
+
+/// Label colors for hosts without one configured, picked by tag so a host
+/// keeps the same color from week to week.
+const HOST_COLORS: &[&str] = &[
+    "#c0392b", "#2980b9", "#27ae60", "#8e44ad", "#d35400", "#16a085", "#2c3e50", "#b7950b",
+];
+
+impl BriefingSettings {
+    /// Display name for a host tag: the configured name, or the tag
+    /// capitalized.
+    pub fn host_name(&self, tag: &str) -> String {
+        if let Some(name) = self.hosts.get(tag).and_then(|h| h.name.clone()) {
+            return name;
+        }
+        let mut chars = tag.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    }
+
+    fn host_color(&self, tag: &str) -> String {
+        self.hosts
+            .get(tag)
+            .and_then(|h| h.color.clone())
+            .unwrap_or_else(|| {
+                let hash = tag.bytes().fold(0usize, |h, b| h * 31 + b as usize);
+                HOST_COLORS[hash % HOST_COLORS.len()].to_string()
+            })
+    }
+}
+
+/// Every host assigned to at least one story, sorted.
+pub fn assigned_hosts(topics: &[Topic]) -> Vec<String> {
+    let hosts: BTreeSet<&String> = topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .flat_map(|s| &s.hosts)
+        .collect();
+    hosts.into_iter().cloned().collect()
+}
+
+/// Only the stories assigned to `host`, dropping topics left empty.
+pub fn stories_for_host(topics: &[Topic], host: &str) -> Vec<Topic> {
+    topics
+        .iter()
+        .map(|topic| Topic {
+            title: topic.title.clone(),
+            stories: topic
+                .stories
+                .iter()
+                .filter(|s| s.hosts.iter().any(|h| h == host))
+                .cloned()
+                .collect(),
+            connections: topic.connections.clone(),
+            notes: topic.notes.clone(),
+            rationale: topic.rationale.clone(),
+        })
+        .filter(|topic| !topic.stories.is_empty())
+        .collect()
+}
+
+/// The product stories of one topic laid out as a table. Columns that are
+/// empty for every product are left out.
+#[derive(Debug, PartialEq)]
+struct Comparison<'a> {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Comparison<'a> {
+    /// `None` unless the topic has at least two product stories.
+    fn for_topic(topic: &'a Topic) -> Option<Self> {
+        let products: Vec<[&str; 4]> = topic
+            .stories
+            .iter()
+            .filter_map(|story| match &story.summary {
+                Summary::Product {
+                    the_product,
+                    cost,
+                    availability,
+                    platforms,
+                    ..
+                } => Some([
+                    the_product.as_str(),
+                    cost.as_str(),
+                    availability.as_str(),
+                    platforms.as_str(),
+                ]),
+                _ => None,
+            })
+            .collect();
+        if products.len() < 2 {
+            return None;
+        }
+
+        let keep: Vec<usize> = (0..4)
+            .filter(|&col| col == 0 || products.iter().any(|p| !p[col].trim().is_empty()))
+            .collect();
+        let names = ["Product", "Cost", "Availability", "Platforms"];
+        Some(Self {
+            columns: keep.iter().map(|&col| names[col]).collect(),
+            rows: products
+                .iter()
+                .map(|p| keep.iter().map(|&col| p[col]).collect())
+                .collect(),
+        })
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::from("    <table class=\"comparison\">\n      <tr>");
+        for column in &self.columns {
+            html.push_str(&format!("<th>{}</th>", column));
+        }
+        html.push_str("</tr>\n");
+        for row in &self.rows {
+            html.push_str("      <tr>");
+            for cell in row {
+                html.push_str(&format!(
+                    "<td>{}</td>",
+                    BriefingGenerator::escape_html(cell)
+                ));
             }
-        } else if current_day < target_day {
-            // Target day is later this week
-            target_day - current_day
-        } else {
-            // Target day already passed this week, use next week
-            7 - (current_day - target_day)
+            html.push_str("</tr>\n");
+        }
+        html.push_str("    </table>\n");
+        html
+    }
+
+    /// An aligned org table. `|` inside a cell would split it, so it becomes
+    /// `/`.
+    fn to_org(&self) -> String {
+        let mut cells: Vec<Vec<String>> =
+            vec![self.columns.iter().map(|c| c.to_string()).collect()];
+        for row in &self.rows {
+            cells.push(row.iter().map(|c| c.trim().replace('|', "/")).collect());
+        }
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|col| {
+                cells
+                    .iter()
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let line = |row: &[String]| {
+            let padded: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            format!("| {} |\n", padded.join(" | "))
         };
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+
+        let mut org = line(&cells[0]);
+        org.push_str(&format!("|{}|\n", rule.join("+")));
+        for row in &cells[1..] {
+            org.push_str(&line(row));
+        }
+        org
+    }
+}
+
+pub struct BriefingGenerator;
+
+impl BriefingGenerator {
+    fn format_date(date_str: &str, locale: Locale) -> String {
+        match dates::parse(date_str) {
+            Some(ParsedDate::At(at)) => locale.format(at.naive_utc(), DateFormat::StampTime),
+            Some(ParsedDate::On(date)) => locale.format_date(date, DateFormat::Stamp),
+            // Fallback to original string
+            None => date_str.to_string(),
+        }
+    }
 
-        from_date + chrono::Duration::days(days_until_target as i64)
+    pub fn generate(topics: &[Topic], show_name: &str, date: DateTime<Tz>) -> String {
+        Self::generate_with(topics, show_name, date, &BriefingSettings::default())
     }
 
-    fn calculate_next_show_date(show_name: &str, from_date: DateTime<Utc>) -> String {
-        let next_show = Self::next_show_datetime(show_name, from_date);
-        // Format as "Tue, 3 February 2026"
-        next_show.format("%a, %-d %B %Y").to_string()
+    /// [`generate`](Self::generate), honoring the `[briefing]` settings.
+    pub fn generate_with(
+        topics: &[Topic],
+        show_name: &str,
+        date: DateTime<Tz>,
+        settings: &BriefingSettings,
+    ) -> String {
+        Self::generate_with_extras(
+            topics,
+            show_name,
+            date,
+            settings,
+            &BriefingExtras::default(),
+        )
     }
 
-    pub fn generate(topics: &[Topic], show_name: &str, date: DateTime<Utc>) -> String {
+    /// [`generate_with`](Self::generate_with), adding the "This Week" and
+    /// "Years Ago" sections from `extras`.
+    pub fn generate_with_extras(
+        topics: &[Topic],
+        show_name: &str,
+        date: DateTime<Tz>,
+        settings: &BriefingSettings,
+        extras: &BriefingExtras,
+    ) -> String {
         let mut html = String::new();
 
         // Format date as "Sunday, 1 February 2026"
-        let formatted_date = date.format("%A, %-d %B %Y").to_string();
+        let locale = settings.locale;
+        let formatted_date = locale.format(date.naive_local(), DateFormat::Long);
 
         // HTML header with styling
         html.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
         html.push_str("  <meta charset=\"UTF-8\">\n");
+        // For `briefing import-legacy`, which can't read the name from the
+        // title
+        html.push_str(&format!(
+            "  <meta name=\"show\" content=\"{}\">\n",
+            Self::escape_html(show_name)
+        ));
         html.push_str(&format!(
             "  <title>{} Briefing - {}</title>\n",
             show_name, formatted_date
         ));
         html.push_str("  <style>\n");
-        html.push_str("    body { font-family: Arial, sans-serif; max-width: 900px; margin: 40px auto; padding: 0 20px; line-height: 1.6; }\n");
-        html.push_str("    h1 { color: #2c3e50; border-bottom: 3px solid #3498db; padding-bottom: 10px; text-align: center; }\n");
-        html.push_str(
-            "    h1 .show-name { display: block; font-size: 1.2em; margin-bottom: 10px; }\n",
-        );
-        html.push_str("    h1 .date { display: block; font-size: 0.8em; font-weight: normal; color: #555; }\n");
-        html.push_str("    h1 .prepared { display: block; font-size: 0.7em; font-weight: normal; color: #888; margin-top: 5px; }\n");
-        html.push_str("    h2 { color: #34495e; margin: 0; padding: 10px; background-color: #ecf0f1; border-left: 4px solid #3498db; }\n");
-        html.push_str("    h3 { color: #2c3e50; margin-top: 25px; }\n");
-        html.push_str("    .metadata { color: #7f8c8d; font-size: 0.9em; margin: 5px 0; }\n");
-        html.push_str("    .link { color: #3498db; text-decoration: none; }\n");
-        html.push_str("    .link:hover { text-decoration: underline; }\n");
-        html.push_str("    details.topic { margin: 40px 0 20px 0; }\n");
-        html.push_str(
-            "    details.topic > summary { display: block; cursor: pointer; user-select: none; }\n",
-        );
-        html.push_str("    details.topic > summary:hover h2 { background-color: #d5dbdb; }\n");
-        html.push_str(
-            "    details.topic > summary h2::before { content: '▼ '; font-size: 0.8em; }\n",
-        );
-        html.push_str("    details.topic:not([open]) > summary h2::before { content: '▶ '; }\n");
-        html.push_str("    details.article { margin: 15px 0; padding: 10px; background-color: #f8f9fa; border-radius: 4px; }\n");
-        html.push_str("    details.article > summary { display: none; }\n");
-        html.push_str("    ul { margin: 10px 0; padding-left: 20px; }\n");
-        html.push_str("    li { margin: 8px 0; }\n");
-        html.push_str("    hr { border: none; border-top: 1px solid #ddd; margin: 30px 0; }\n");
-        html.push_str("    .error { color: #e74c3c; font-style: italic; }\n");
+        let theme = Theme::load(settings.theme.as_deref()).unwrap_or_else(|e| {
+            tracing::warn!("{:#}; using the light theme", e);
+            Theme::light()
+        });
+        for line in theme.css.lines() {
+            html.push_str(&format!("    {}\n", line));
+        }
         html.push_str("  </style>\n");
         html.push_str("</head>\n<body>\n");
 
-        // Main title (three lines)
-        // Get current local time for "Prepared" timestamp
-        let prepared_time = Local::now();
-        // Determine PST/PDT based on UTC offset (-8 = PST, -7 = PDT)
-        let tz_abbrev = if prepared_time.offset().local_minus_utc() == -8 * 3600 {
-            "PST"
-        } else {
-            "PDT"
-        };
+        // Main title (three lines), prepared now in the show's time zone
+        let prepared_time = date.timezone().now();
         let prepared_str = format!(
             "{} {}",
-            prepared_time.format("%a %-d %b %Y at %H:%M"),
-            tz_abbrev
+            locale.format(prepared_time.naive_local(), DateFormat::Prepared),
+            prepared_time.offset()
         );
 
         html.push_str(&format!(
-            "<h1><span class=\"show-name\">{} Briefing</span><span class=\"date\">For {}</span><span class=\"prepared\">(Prepared {})</span></h1>\n",
+            "<h1 id=\"top\"><span class=\"show-name\">{} Briefing</span><span class=\"date\">For {}</span><span class=\"prepared\">(Prepared {})</span></h1>\n",
             show_name, formatted_date, prepared_str
         ));
 
+        if !topics.is_empty() {
+            html.push_str("<nav class=\"toc\">\n  <h2>Contents</h2>\n  <ol>\n");
+            for (index, topic) in topics.iter().enumerate() {
+                let count = match topic.stories.len() {
+                    0 => String::new(),
+                    1 => " <span class=\"count\">(1 story)</span>".to_string(),
+                    n => format!(" <span class=\"count\">({} stories)</span>", n),
+                };
+                let minutes = if settings.talk_time.enabled {
+                    format!(
+                        " <span class=\"talk-time\">~{}</span>",
+                        crate::talk_time::duration(settings.talk_time.topic_minutes(topic))
+                    )
+                } else {
+                    String::new()
+                };
+                html.push_str(&format!(
+                    "    <li><a href=\"#topic-{}\">{}</a>{}{}</li>\n",
+                    index + 1,
+                    Self::escape_html(&topic.title),
+                    count,
+                    minutes
+                ));
+            }
+            html.push_str("  </ol>\n");
+            if settings.talk_time.enabled {
+                html.push_str(&format!(
+                    "  <p class=\"talk-time\"><strong>Estimated talk time:</strong> {}</p>\n",
+                    settings.talk_time.total(topics)
+                ));
+            }
+            html.push_str("</nav>\n");
+        }
+
+        if !extras.events.is_empty() {
+            html.push_str("<aside class=\"this-week\">\n");
+            html.push_str("  <h2>This Week</h2>\n  <ul>\n");
+            for event in &extras.events {
+                let time = event
+                    .time
+                    .map(|t| {
+                        format!(
+                            " {}",
+                            locale.format(event.date.and_time(t), DateFormat::Time)
+                        )
+                    })
+                    .unwrap_or_default();
+                let location = event
+                    .location
+                    .as_ref()
+                    .map(|l| format!(" ({})", Self::escape_html(l)))
+                    .unwrap_or_default();
+                html.push_str(&format!(
+                    "    <li><strong>{}{}</strong> — {}{}</li>\n",
+                    locale.format_date(event.date, DateFormat::Short),
+                    time,
+                    Self::escape_html(&event.summary),
+                    location
+                ));
+            }
+            html.push_str("  </ul>\n</aside>\n");
+        }
+
+        if !extras.trends.is_empty() {
+            html.push_str("<aside class=\"trends\">\n");
+            html.push_str("  <h2>Trends</h2>\n  <ul>\n");
+            for trend in &extras.trends {
+                html.push_str(&format!(
+                    "    <li>{}</li>\n",
+                    Self::escape_html(&trend.note())
+                ));
+            }
+            html.push_str("  </ul>\n</aside>\n");
+        }
+
+        if settings.by_the_numbers {
+            let figures = crate::numbers::extract(topics);
+            if !figures.is_empty() {
+                html.push_str("<aside class=\"by-the-numbers\">\n");
+                html.push_str("  <h2>By the Numbers</h2>\n  <ul>\n");
+                for figure in &figures {
+                    html.push_str(&format!(
+                        "    <li><strong>{}</strong> — <a href=\"{}\" class=\"link\" target=\"_blank\">{}</a></li>\n",
+                        Self::escape_html(&figure.figure),
+                        Self::escape_html(&figure.url),
+                        Self::escape_html(&figure.title)
+                    ));
+                }
+                html.push_str("  </ul>\n</aside>\n");
+            }
+        }
+
         // Topics
         for (index, topic) in topics.iter().enumerate() {
-            html.push_str("<details class=\"topic\">\n");
+            html.push_str(&format!(
+                "<details class=\"topic\" id=\"topic-{}\"{}>\n",
+                index + 1,
+                if theme.expands_topics() { " open" } else { "" }
+            ));
             html.push_str(&format!(
                 "  <summary><h2>{}. {}</h2></summary>\n",
                 index + 1,
                 Self::escape_html(&topic.title)
             ));
             html.push_str("  <div>\n");
+            if let Some(rationale) = &topic.rationale {
+                html.push_str(&format!(
+                    "    <p class=\"rationale\">{}</p>\n",
+                    Self::escape_html(rationale)
+                ));
+            }
+            if let Some(connections) = &topic.connections {
+                html.push_str(&format!(
+                    "    <p class=\"connections\"><strong>How these connect:</strong> {}</p>\n",
+                    Self::escape_html(connections)
+                ));
+            }
+            if let Some(notes) = &topic.notes {
+                html.push_str(&Self::notes_html("notes", None, notes));
+            }
+            if settings.comparison_tables {
+                if let Some(comparison) = Comparison::for_topic(topic) {
+                    html.push_str(&comparison.to_html());
+                }
+            }
 
             for story in &topic.stories {
+                let labels: String = story
+                    .hosts
+                    .iter()
+                    .map(|host| {
+                        format!(
+                            " <span class=\"host\" style=\"background-color: {}\">{}</span>",
+                            Self::escape_html(&settings.host_color(host)),
+                            Self::escape_html(&settings.host_name(host))
+                        )
+                    })
+                    .collect();
+                let details = story
+                    .details
+                    .as_ref()
+                    .map(Self::details_html)
+                    .unwrap_or_default();
+                let stale = settings
+                    .staleness
+                    .stale_days(story, date.date_naive())
+                    .map(|days| {
+                        format!(
+                            " <span class=\"stale\">⚠ {}</span>",
+                            crate::staleness::label(days)
+                        )
+                    })
+                    .unwrap_or_default();
+                let reasons = settings.sensitive.reasons(story);
+                let sensitive = if reasons.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        " <span class=\"sensitive\" title=\"Careful framing: {}\">◆</span>",
+                        Self::escape_html(&reasons.join(", "))
+                    )
+                };
+                let star = if story.starred {
+                    " class=\"starred\"><span class=\"star\" title=\"Must cover\">★</span> "
+                } else {
+                    ">"
+                };
                 html.push_str(&format!(
-                    "    <h3>{}</h3>\n",
-                    Self::escape_html(&story.title)
+                    "    <h3{}{}{}{}{}{}</h3>\n",
+                    star,
+                    Self::escape_html(&story.title),
+                    sensitive,
+                    labels,
+                    stale,
+                    details
                 ));
+                if let Some(entities) = story.entities.as_ref().filter(|e| !e.is_empty()) {
+                    html.push_str(&Self::entities_html(entities));
+                }
+                let flags = settings.disclosures.flags(story);
+                if !flags.is_empty() {
+                    let flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+                    html.push_str(&format!(
+                        "    <div class=\"disclosure\">⚠ Disclosure needed: {}</div>\n",
+                        Self::escape_html(&flags.join("; "))
+                    ));
+                }
+                match (&story.followup, &story.previously) {
+                    (Some(recap), Some(previously)) => html.push_str(&format!(
+                        "    <div class=\"followup\">↩ Previously: {} <span class=\"followup-source\">({})</span></div>\n",
+                        Self::escape_html(recap),
+                        Self::escape_html(previously)
+                    )),
+                    (Some(recap), None) => html.push_str(&format!(
+                        "    <div class=\"followup\">↩ Previously: {}</div>\n",
+                        Self::escape_html(recap)
+                    )),
+                    (None, Some(previously)) => html.push_str(&format!(
+                        "    <div class=\"followup\">↩ Follow-up: covered on {}</div>\n",
+                        Self::escape_html(previously)
+                    )),
+                    (None, None) => {}
+                }
+                if let Some(coverage) = extras.covered.get(&canonicalize_url(&story.url)) {
+                    let shows: Vec<String> = coverage.iter().map(|c| c.label(locale)).collect();
+                    html.push_str(&format!(
+                        "    <div class=\"covered\">↺ Also covered on {}</div>\n",
+                        Self::escape_html(&shows.join(" and "))
+                    ));
+                }
+                if let Some(thumbnail) = extras.thumbnails.get(&story.url) {
+                    html.push_str(&format!(
+                        "    <img class=\"thumbnail\" src=\"{}\" alt=\"\">\n",
+                        Self::escape_html(thumbnail)
+                    ));
+                }
                 html.push_str("    <div class=\"metadata\">\n");
                 html.push_str(&format!(
                     "      <strong>Link:</strong> <a href=\"{}\" class=\"link\" target=\"_blank\">{}</a><br>\n",
                     Self::escape_html(&story.url),
                     Self::escape_html(&story.url)
                 ));
+                if let Some(short) = extras.short_links.get(&story.url) {
+                    html.push_str(&format!(
+                        "      <strong>Short link:</strong> <a href=\"{}\" class=\"link short-link\" target=\"_blank\">{}</a><br>\n",
+                        Self::escape_html(short),
+                        Self::escape_html(short.split_once("://").map_or(short, |(_, rest)| rest))
+                    ));
+                }
                 html.push_str(&format!(
                     "      <strong>Date:</strong> {}\n",
-                    Self::format_date(&story.created)
+                    Self::format_date(&story.created, locale)
                 ));
                 html.push_str("    </div>\n");
 
@@ -161,21 +701,25 @@ impl BriefingGenerator {
                     Summary::Editorial {
                         lede,
                         nutgraf,
-                        quote,
+                        quotes,
+                        reaction,
+                        by_the_numbers,
                     } => {
                         html.push_str("    <details class=\"article\" open>\n");
                         html.push_str("      <summary></summary>\n");
-                        if let Some(q) = quote {
-                            html.push_str(&format!(
-                                "      <p>{}</p>\n",
-                                Self::format_quote_html(q)
-                            ));
-                        }
+                        html.push_str(&Self::quotes_html(quotes));
                         html.push_str(&format!(
                             "      <p><strong>{}</strong></p>\n",
                             Self::escape_html(lede)
                         ));
                         html.push_str(&format!("      <p>{}</p>\n", Self::escape_html(nutgraf)));
+                        if let Some(numbers) = by_the_numbers {
+                            html.push_str(&format!(
+                                "      <p class=\"numbers\"><strong>By the numbers:</strong> {}</p>\n",
+                                Self::escape_html(numbers)
+                            ));
+                        }
+                        html.push_str(&Self::reaction_html(reaction.as_deref()));
                         html.push_str("    </details>\n");
                     }
                     Summary::Product {
@@ -183,16 +727,12 @@ impl BriefingGenerator {
                         cost,
                         availability,
                         platforms,
-                        quote,
+                        quotes,
+                        reaction,
                     } => {
                         html.push_str("    <details class=\"article\" open>\n");
                         html.push_str("      <summary></summary>\n");
-                        if let Some(q) = quote {
-                            html.push_str(&format!(
-                                "      <p>{}</p>\n",
-                                Self::format_quote_html(q)
-                            ));
-                        }
+                        html.push_str(&Self::quotes_html(quotes));
                         html.push_str(&format!(
                             "      <p><strong>The product:</strong> {}</p>\n",
                             Self::escape_html(the_product)
@@ -215,20 +755,49 @@ impl BriefingGenerator {
                                 Self::escape_html(platforms)
                             ));
                         }
+                        html.push_str(&Self::reaction_html(reaction.as_deref()));
                         html.push_str("    </details>\n");
                     }
                     Summary::Insufficient | Summary::Failed(_) => {
                         html.push_str("    <p class=\"error\">Summary not available</p>\n");
                     }
                 }
+                if let Some(notes) = &story.notes {
+                    html.push_str(&Self::notes_html("host-notes", Some("Host notes"), notes));
+                }
 
                 html.push_str("    <hr>\n");
             }
 
+            html.push_str("    <p class=\"back-to-top\"><a href=\"#top\">↑ Back to top</a></p>\n");
             html.push_str("  </div>\n");
             html.push_str("</details>\n");
         }
 
+        if !extras.lookbacks.is_empty() {
+            html.push_str("<aside class=\"lookback\">\n");
+            for lookback in &extras.lookbacks {
+                let ago = match lookback.years {
+                    1 => "One Year Ago".to_string(),
+                    n => format!("{} Years Ago", n),
+                };
+                html.push_str(&format!(
+                    "  <h2>{} This Week <span class=\"date\">{}</span></h2>\n  <ul>\n",
+                    ago,
+                    locale.format_date(lookback.date, DateFormat::Medium)
+                ));
+                for (title, url) in lookback.stories.iter().take(MAX_LOOKBACK_STORIES) {
+                    html.push_str(&format!(
+                        "    <li><a href=\"{}\" class=\"link\" target=\"_blank\">{}</a></li>\n",
+                        Self::escape_html(url),
+                        Self::escape_html(title)
+                    ));
+                }
+                html.push_str("  </ul>\n");
+            }
+            html.push_str("</aside>\n");
+        }
+
         // Add footer section
         html.push_str("<hr style=\"margin: 60px 0 30px 0; border-top: 2px solid #3498db;\">\n");
         html.push_str("<h2 style=\"text-align: center; color: #2c3e50;\">Stories will be updated as needed until show time.</h2>\n");
@@ -237,6 +806,104 @@ impl BriefingGenerator {
         html
     }
 
+    /// A story's source, author, length and language beside its headline,
+    /// e.g. "heise.de · Jane Doe · 6 min read · translated from German".
+    fn details_html(details: &StoryDetails) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        parts.extend(details.source.iter().map(|s| Self::escape_html(s)));
+        parts.extend(details.author.iter().map(|a| Self::escape_html(a)));
+        match (details.words, details.reading_minutes) {
+            (Some(words), Some(minutes)) => {
+                parts.push(format!("{} words, {} min read", words, minutes))
+            }
+            (Some(words), None) => parts.push(format!("{} words", words)),
+            (None, Some(minutes)) => parts.push(format!("{} min read", minutes)),
+            (None, None) => {}
+        }
+        if let Some(language) = &details.language {
+            let name = crate::language::name(language);
+            parts.push(if details.translated {
+                format!("translated from {}", name)
+            } else {
+                format!("in {}", name)
+            });
+        }
+        if parts.is_empty() {
+            return String::new();
+        }
+        format!(" <span class=\"details\">{}</span>", parts.join(" · "))
+    }
+
+    /// The tag line under a story's headline, one label per company,
+    /// product and person.
+    fn entities_html(entities: &Entities) -> String {
+        let labels: Vec<String> = [
+            ("company", &entities.companies),
+            ("product", &entities.products),
+            ("person", &entities.people),
+        ]
+        .into_iter()
+        .flat_map(|(kind, names)| {
+            names.iter().map(move |name| {
+                format!(
+                    "<span class=\"entity {}\">{}</span>",
+                    kind,
+                    Self::escape_html(name)
+                )
+            })
+        })
+        .collect();
+        format!("    <div class=\"entities\">{}</div>\n", labels.join(" "))
+    }
+
+    /// Topic or story notes: paragraphs at blank lines, `- ` items as a
+    /// list, and org links `[[url][text]]` as links.
+    fn notes_html(class: &str, label: Option<&str>, notes: &str) -> String {
+        let mut html = format!("    <div class=\"{}\">\n", class);
+        if let Some(label) = label {
+            html.push_str(&format!("      <p class=\"label\">{}</p>\n", label));
+        }
+        for block in notes.split("\n\n").map(str::trim).filter(|b| !b.is_empty()) {
+            let lines: Vec<&str> = block.lines().map(str::trim).collect();
+            if lines.iter().all(|l| l.starts_with("- ")) {
+                html.push_str("      <ul>\n");
+                for line in lines {
+                    html.push_str(&format!(
+                        "        <li>{}</li>\n",
+                        Self::org_links(&Self::escape_html(&line[2..]))
+                    ));
+                }
+                html.push_str("      </ul>\n");
+            } else {
+                html.push_str(&format!(
+                    "      <p>{}</p>\n",
+                    Self::org_links(&Self::escape_html(&lines.join(" ")))
+                ));
+            }
+        }
+        html.push_str("    </div>\n");
+        html
+    }
+
+    /// `[[url][text]]` and `[[url]]` in already-escaped text as anchors.
+    fn org_links(text: &str) -> String {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("[[") {
+            let Some(end) = rest[start..].find("]]").map(|e| start + e) else {
+                break;
+            };
+            let (url, label) = rest[start + 2..end]
+                .split_once("][")
+                .unwrap_or((&rest[start + 2..end], &rest[start + 2..end]));
+            out.push_str(&rest[..start]);
+            out.push_str(&format!("<a href=\"{}\">{}</a>", url, label));
+            rest = &rest[end + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+
     fn escape_html(text: &str) -> String {
         text.replace('&', "&amp;")
             .replace('<', "&lt;")
@@ -245,21 +912,108 @@ impl BriefingGenerator {
             .replace('\'', "&#39;")
     }
 
-    /// Format a quote for HTML: italic quote text, normal attribution
-    /// Input: `"quote text" -- Speaker Name`
-    /// Output: `<em>"quote text"</em> -- Speaker Name`
-    fn format_quote_html(quote: &str) -> String {
-        if let Some(sep_pos) = quote.find(" -- ") {
-            let quote_text = Self::escape_html(&quote[..sep_pos]);
-            let attribution = Self::escape_html(&quote[sep_pos..]);
-            format!("<em>{}</em>{}", quote_text, attribution)
-        } else {
-            format!("<em>{}</em>", Self::escape_html(quote))
+    fn reaction_html(reaction: Option<&str>) -> String {
+        reaction.map_or_else(String::new, |reaction| {
+            format!(
+                "      <p class=\"reaction\"><strong>Reaction:</strong> {}</p>\n",
+                Self::escape_html(reaction)
+            )
+        })
+    }
+
+    /// One quote as a paragraph, several as a list.
+    fn quotes_html(quotes: &[Quote]) -> String {
+        match quotes {
+            [] => String::new(),
+            [quote] => format!("      <p>{}</p>\n", Self::format_quote_html(quote)),
+            quotes => {
+                let mut html = String::from("      <ul class=\"quotes\">\n");
+                for quote in quotes {
+                    html.push_str(&format!(
+                        "        <li>{}</li>\n",
+                        Self::format_quote_html(quote)
+                    ));
+                }
+                html.push_str("      </ul>\n");
+                html
+            }
         }
     }
 
+    /// Format a quote for HTML: italic quote text, the speaker in bold
+    /// Output: `<em>"quote text"</em> -- <strong>Speaker Name</strong>, Role`
+    /// A free-form quote is all italic.
+    fn format_quote_html(quote: &Quote) -> String {
+        let Some(speaker) = &quote.speaker else {
+            return format!("<em>{}</em>", Self::escape_html(&quote.text));
+        };
+        let role = quote
+            .role
+            .as_ref()
+            .map_or_else(String::new, |role| format!(", {}", Self::escape_html(role)));
+        format!(
+            "<em>{}</em> -- <strong>{}</strong>{}",
+            Self::escape_html(&format!("\"{}\"", quote.text)),
+            Self::escape_html(speaker),
+            role
+        )
+    }
+
     pub fn generate_links_csv(topics: &[Topic]) -> String {
+        Self::generate_links_csv_with(topics, &BriefingExtras::default())
+    }
+
+    /// [`generate_links_csv`](Self::generate_links_csv) with each story's
+    /// short link, if any, in column F, its link check, if any, in the
+    /// column after, and then the `csv_columns` in the order given. With
+    /// `csv_columns` a header row names every column, so the metadata can
+    /// be told apart on import.
+    pub fn generate_links_csv_with(topics: &[Topic], extras: &BriefingExtras) -> String {
         let mut csv = String::new();
+        // Each metadata column once, where it was first asked for
+        let mut metadata: Vec<CsvColumn> = Vec::new();
+        for column in &extras.csv_columns {
+            if !metadata.contains(column) {
+                metadata.push(*column);
+            }
+        }
+        if !metadata.is_empty() {
+            csv.push_str(",topic,title,,url");
+            if !extras.short_links.is_empty() {
+                csv.push_str(",short-link");
+            }
+            if !extras.link_status.is_empty() {
+                csv.push_str(",link-status");
+            }
+            for column in &metadata {
+                csv.push(',');
+                csv.push_str(column.name());
+            }
+            csv.push('\n');
+        }
+        let extra_columns = |story: Option<&Story>| -> String {
+            let url = story.map_or("", |s| s.url.as_str());
+            let mut columns = String::new();
+            if !extras.short_links.is_empty() {
+                columns.push(',');
+                columns.push_str(&Self::escape_csv(
+                    extras.short_links.get(url).map_or("", String::as_str),
+                ));
+            }
+            if !extras.link_status.is_empty() {
+                columns.push(',');
+                if let Some(status) = extras.link_status.get(url) {
+                    columns.push_str(&Self::escape_csv(&status.to_string()));
+                }
+            }
+            for column in &metadata {
+                columns.push(',');
+                if let Some(story) = story {
+                    columns.push_str(&Self::escape_csv(&column.value(story)));
+                }
+            }
+            columns
+        };
 
         for topic in topics {
             let mut first_article = true;
@@ -268,29 +1022,99 @@ impl BriefingGenerator {
                 if first_article {
                     // First article row: blank A, topic title in B, article title in C, blank D, link in E
                     csv.push_str(&format!(
-                        ",{},{},,{}\n",
+                        ",{},{},,{}{}\n",
                         Self::escape_csv(&topic.title),
                         Self::escape_csv(&story.title),
-                        Self::escape_csv(&story.url)
+                        Self::escape_csv(&story.url),
+                        extra_columns(Some(story))
                     ));
                     first_article = false;
                 } else {
                     // Subsequent article rows: blank A, blank B, title in C, blank D, link in E
                     csv.push_str(&format!(
-                        ",,{},,{}\n",
+                        ",,{},,{}{}\n",
                         Self::escape_csv(&story.title),
-                        Self::escape_csv(&story.url)
+                        Self::escape_csv(&story.url),
+                        extra_columns(Some(story))
                     ));
                 }
             }
 
             // Blank row between topics
-            csv.push_str(",,,,\n");
+            csv.push_str(",,,,");
+            csv.push_str(&extra_columns(None));
+            csv.push('\n');
         }
 
         csv
     }
 
+    /// The links as an OPML outline: one outline per topic, with a `link`
+    /// outline per story.
+    pub fn generate_links_opml(
+        topics: &[Topic],
+        show_name: &str,
+        date: DateTime<Tz>,
+        locale: Locale,
+    ) -> String {
+        let mut opml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        opml.push_str("<opml version=\"2.0\">\n  <head>\n");
+        opml.push_str(&format!(
+            "    <title>{} Links - {}</title>\n",
+            Self::escape_html(show_name),
+            locale.format(date.naive_local(), DateFormat::Title)
+        ));
+        opml.push_str(&format!(
+            "    <dateCreated>{}</dateCreated>\n",
+            date.to_rfc2822()
+        ));
+        opml.push_str("  </head>\n  <body>\n");
+        for topic in topics {
+            opml.push_str(&format!(
+                "    <outline text=\"{}\">\n",
+                Self::escape_html(&topic.title)
+            ));
+            for story in &topic.stories {
+                opml.push_str(&format!(
+                    "      <outline text=\"{}\" type=\"link\" url=\"{}\"/>\n",
+                    Self::escape_html(&story.title),
+                    Self::escape_html(&story.url)
+                ));
+            }
+            opml.push_str("    </outline>\n");
+        }
+        opml.push_str("  </body>\n</opml>\n");
+        opml
+    }
+
+    /// The links as a minimal org file: a heading per topic and a linked
+    /// subheading per story, without summaries.
+    pub fn generate_links_org(
+        topics: &[Topic],
+        show_name: &str,
+        date: DateTime<Tz>,
+        locale: Locale,
+    ) -> String {
+        let mut org = format!(
+            "#+TITLE: {} Links - {}\n\n",
+            show_name,
+            locale.format(date.naive_local(), DateFormat::Title)
+        );
+        for topic in topics {
+            org.push_str(&format!("* {}\n", topic.title));
+            for story in &topic.stories {
+                // Brackets would end the link description early
+                let title = story.title.replace('[', "(").replace(']', ")");
+                if story.url.is_empty() {
+                    org.push_str(&format!("** {}\n", title));
+                } else {
+                    org.push_str(&format!("** [[{}][{}]]\n", story.url, title));
+                }
+            }
+        }
+        org
+    }
+
     fn escape_csv(text: &str) -> String {
         // If the text contains comma, quote, or newline, wrap in quotes and escape quotes
         if text.contains(',') || text.contains('"') || text.contains('\n') {
@@ -300,111 +1124,211 @@ impl BriefingGenerator {
         }
     }
 
-    pub fn save(content: &str, show_slug: &str, date: DateTime<Utc>) -> Result<PathBuf> {
-        let filename = format!("{}-{}.html", show_slug, date.format("%Y-%m-%d"));
+    pub fn save(content: &str, show_slug: &str, date: DateTime<Tz>) -> Result<PathBuf> {
+        let filepath = crate::output::settings().path(
+            Artifact::Html,
+            show_slug,
+            date.date_naive(),
+            "",
+            "html",
+        )?;
 
-        let documents_dir = dirs::document_dir().unwrap_or_else(|| PathBuf::from("."));
-        let filepath = documents_dir.join(&filename);
+        crate::io::save_file(&filepath, content).context("Failed to write briefing file")?;
+
+        Ok(filepath)
+    }
 
-        fs::write(&filepath, content).context("Failed to write briefing file")?;
+    /// Save a per-host view as `{slug}-{date}-{host}.html`.
+    pub fn save_host_view(
+        content: &str,
+        show_slug: &str,
+        date: DateTime<Tz>,
+        host: &str,
+    ) -> Result<PathBuf> {
+        let filepath = crate::output::settings().path(
+            Artifact::Html,
+            show_slug,
+            date.date_naive(),
+            &format!("-{}", host),
+            "html",
+        )?;
+
+        crate::io::save_file(&filepath, content).context("Failed to write host briefing file")?;
 
         Ok(filepath)
     }
 
-    pub fn save_links_csv(content: &str, show_slug: &str, date: DateTime<Utc>) -> Result<PathBuf> {
-        let filename = format!("{}-{}-LINKS.csv", show_slug, date.format("%Y-%m-%d"));
+    pub fn save_links_csv(content: &str, show_slug: &str, date: DateTime<Tz>) -> Result<PathBuf> {
+        let filepath = crate::output::settings().path(
+            Artifact::Csv,
+            show_slug,
+            date.date_naive(),
+            "",
+            "csv",
+        )?;
+
+        crate::io::save_file(&filepath, content).context("Failed to write links CSV file")?;
 
-        let documents_dir = dirs::document_dir().unwrap_or_else(|| PathBuf::from("."));
-        let filepath = documents_dir.join(&filename);
+        Ok(filepath)
+    }
 
-        fs::write(&filepath, content).context("Failed to write links CSV file")?;
+    pub fn save_links_export(
+        content: &str,
+        show_slug: &str,
+        date: DateTime<Tz>,
+        format: LinksFormat,
+    ) -> Result<PathBuf> {
+        let filepath = crate::output::settings().path(
+            Artifact::Csv,
+            show_slug,
+            date.date_naive(),
+            "",
+            format.extension(),
+        )?;
+
+        crate::io::save_file(&filepath, content).context("Failed to write links export")?;
 
         Ok(filepath)
     }
 
-    pub fn generate_org_mode(topics: &[Topic], show_name: &str, date: DateTime<Utc>) -> String {
-        let mut org = String::new();
+    /// The org draft for the episode on `date`.
+    pub fn generate_org_mode(topics: &[Topic], show_name: &str, date: DateTime<Tz>) -> String {
+        Self::generate_org_mode_with(topics, show_name, date, &BriefingSettings::default())
+    }
 
-        let next_show_date = Self::calculate_next_show_date(show_name, date);
+    /// [`generate_org_mode`](Self::generate_org_mode), honoring the
+    /// `[briefing]` settings. Comparison tables sit between the topic heading
+    /// and its first story, where `parse_org_mode` ignores them; the HTML
+    /// table is rebuilt from the (possibly edited) product summaries.
+    pub fn generate_org_mode_with(
+        topics: &[Topic],
+        show_name: &str,
+        date: DateTime<Tz>,
+        settings: &BriefingSettings,
+    ) -> String {
+        let mut org = String::new();
+        let topic_stars = settings.org_template.topic_stars();
 
         // Properties
         org.push_str(&format!("#+TITLE: {} Briefing Book\n", show_name));
-        org.push_str(&format!("#+DATE: {}\n\n", next_show_date));
+        org.push_str(&format!("{} {}\n", crate::org::SHOW_KEYWORD, show_name));
+        org.push_str(&format!(
+            "#+DATE: {}\n\n",
+            settings
+                .locale
+                .format(date.naive_local(), DateFormat::Weekday)
+        ));
+        org.push_str(&settings.sources.org_block(topics));
+
+        // Regenerated from the summaries by prepare-briefing, like the
+        // comparison tables; `parse_org_mode` skips it.
+        if settings.by_the_numbers {
+            let figures = crate::numbers::extract(topics);
+            if !figures.is_empty() {
+                org.push_str(&format!("{} By the Numbers\n\n", topic_stars));
+                for figure in &figures {
+                    org.push_str(&format!("- *{}* :: {}\n", figure.figure, figure.title));
+                }
+                org.push('\n');
+            }
+        }
 
         // Topics
         for topic in topics {
-            org.push_str(&format!("* {}\n\n", topic.title));
+            org.push_str(&format!("{} {}\n\n", topic_stars, topic.title));
+            if let Some(rationale) = &topic.rationale {
+                org.push_str(&format!(
+                    "{}{}\n\n",
+                    crate::org::RATIONALE_PREFIX,
+                    rationale
+                ));
+            }
+            if let Some(connections) = &topic.connections {
+                org.push_str(&format!(
+                    "{}{}\n\n",
+                    crate::org::CONNECTIONS_PREFIX,
+                    connections
+                ));
+            }
+            if let Some(notes) = &topic.notes {
+                org.push_str(&format!("{}\n\n", notes));
+            }
+            if settings.comparison_tables {
+                if let Some(comparison) = Comparison::for_topic(topic) {
+                    org.push_str(&comparison.to_org());
+                    org.push('\n');
+                }
+            }
 
             for story in &topic.stories {
-                // Article title
-                org.push_str(&format!("** {}\n\n", story.title));
-
-                // URL
-                org.push_str(&format!("*** URL\n{}\n\n", story.url));
-
-                // Date
-                if !story.created.is_empty() {
-                    org.push_str(&format!("*** Date\n{}\n\n", story.created));
+                // Informational only: prepare-briefing flags stories afresh
+                let mut lines = String::new();
+                let flags = settings.disclosures.flags(story);
+                if !flags.is_empty() {
+                    let flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+                    lines.push_str(&format!(
+                        "{}{}\n\n",
+                        crate::org::DISCLOSURE_PREFIX,
+                        flags.join("; ")
+                    ));
                 }
-
-                // Summary
-                org.push_str("*** Summary\n");
-                match &story.summary {
-                    Summary::Editorial {
-                        lede,
-                        nutgraf,
-                        quote,
-                    } => {
-                        if let Some(q) = quote {
-                            org.push_str(&format!("{}\n\n", q));
-                        }
-                        org.push_str(&format!("{}\n\n", lede));
-                        org.push_str(&format!("{}\n", nutgraf));
-                    }
-                    Summary::Product {
-                        the_product,
-                        cost,
-                        availability,
-                        platforms,
-                        quote,
-                    } => {
-                        if let Some(q) = quote {
-                            org.push_str(&format!("{}\n\n", q));
-                        }
-                        org.push_str(&format!("The product: {}\n", the_product));
-                        if !cost.is_empty() {
-                            org.push_str(&format!("Cost: {}\n", cost));
-                        }
-                        if !availability.is_empty() {
-                            org.push_str(&format!("Availability: {}\n", availability));
-                        }
-                        if !platforms.is_empty() {
-                            org.push_str(&format!("Platforms: {}\n", platforms));
-                        }
-                    }
-                    Summary::Insufficient | Summary::Failed(_) => {
-                        org.push_str("Summary not available\n");
-                    }
+                if let Some(days) = settings.staleness.stale_days(story, date.date_naive()) {
+                    lines.push_str(&format!(
+                        "{}{}\n\n",
+                        crate::org::STALE_PREFIX,
+                        crate::staleness::label(days)
+                    ));
                 }
-                org.push('\n');
+                let reasons = settings.sensitive.reasons(story);
+                if !reasons.is_empty() {
+                    lines.push_str(&format!(
+                        "{}{}\n\n",
+                        crate::org::SENSITIVE_PREFIX,
+                        reasons.join(", ")
+                    ));
+                }
+                if let Some(previously) = &story.previously {
+                    lines.push_str(&format!(
+                        "{}{}\n",
+                        crate::org::PREVIOUSLY_PREFIX,
+                        previously
+                    ));
+                }
+                if let Some(recap) = &story.followup {
+                    lines.push_str(&format!("{}{}\n", crate::org::RECAP_PREFIX, recap));
+                }
+                if story.previously.is_some() || story.followup.is_some() {
+                    lines.push('\n');
+                }
+                org.push_str(
+                    &settings
+                        .org_template
+                        .render(story, &lines, settings.org_links),
+                );
             }
         }
 
-        // Add three empty topics at the end
-        org.push_str("* In Other News\n\n");
-        org.push_str("* Leo's Picks\n\n");
-        org.push_str("* In Memoriam\n\n");
+        // Add the empty back-of-the-book sections, unless the topics
+        // already carry them
+        for section in ["In Other News", "Leo's Picks", "In Memoriam"] {
+            if !topics.iter().any(|t| t.title == section) {
+                org.push_str(&format!("{} {}\n\n", topic_stars, section));
+            }
+        }
 
         org
     }
 
-    pub fn save_org_mode(content: &str, show_slug: &str, date: DateTime<Utc>) -> Result<PathBuf> {
-        let filename = format!("{}-{}.org", show_slug, date.format("%Y-%m-%d"));
+    pub fn save_org_mode(content: &str, show_slug: &str, date: DateTime<Tz>) -> Result<PathBuf> {
+        let filepath = crate::output::settings().path(
+            Artifact::Org,
+            show_slug,
+            date.date_naive(),
+            "",
+            "org",
+        )?;
 
-        let documents_dir = dirs::document_dir().unwrap_or_else(|| PathBuf::from("."));
-        let filepath = documents_dir.join(&filename);
-
-        fs::write(&filepath, content).context("Failed to write org-mode file")?;
+        crate::io::save_file(&filepath, content).context("Failed to write org-mode file")?;
 
         Ok(filepath)
     }
@@ -416,62 +1340,6 @@ mod tests {
     use crate::Story;
     use chrono::TimeZone;
 
-    #[test]
-    fn test_mbw_from_sunday_evening() {
-        // Sunday Feb 1, 2026 at 9:25 PM -> next MBW is Tuesday Feb 3
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 21, 25, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("MacBreak Weekly", date);
-        assert_eq!(result, "Tue, 3 February 2026");
-    }
-
-    #[test]
-    fn test_twit_from_sunday_after_cutoff() {
-        // Sunday Feb 1, 2026 at 7 PM (after 6 PM cutoff) -> next TWiT is Feb 8
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 19, 0, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("This Week in Tech", date);
-        assert_eq!(result, "Sun, 8 February 2026");
-    }
-
-    #[test]
-    fn test_twit_from_sunday_before_cutoff() {
-        // Sunday Feb 1, 2026 at 5 PM (before 6 PM cutoff) -> TWiT is today
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 17, 0, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("This Week in Tech", date);
-        assert_eq!(result, "Sun, 1 February 2026");
-    }
-
-    #[test]
-    fn test_mbw_from_tuesday_after_cutoff() {
-        // Tuesday Feb 3, 2026 at 3 PM (after 2 PM cutoff) -> next MBW is Feb 10
-        let date = Utc.with_ymd_and_hms(2026, 2, 3, 15, 0, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("MacBreak Weekly", date);
-        assert_eq!(result, "Tue, 10 February 2026");
-    }
-
-    #[test]
-    fn test_mbw_from_tuesday_before_cutoff() {
-        // Tuesday Feb 3, 2026 at 1 PM (before 2 PM cutoff) -> MBW is today
-        let date = Utc.with_ymd_and_hms(2026, 2, 3, 13, 0, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("MacBreak Weekly", date);
-        assert_eq!(result, "Tue, 3 February 2026");
-    }
-
-    #[test]
-    fn test_im_from_wednesday_after_cutoff() {
-        // Wednesday Feb 4, 2026 at 7 PM (after 6 PM cutoff) -> next IM is Feb 11
-        let date = Utc.with_ymd_and_hms(2026, 2, 4, 19, 0, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("Intelligent Machines", date);
-        assert_eq!(result, "Wed, 11 February 2026");
-    }
-
-    #[test]
-    fn test_im_from_sunday() {
-        // Sunday Feb 1, 2026 -> next IM is Wednesday Feb 4
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 21, 25, 0).unwrap();
-        let result = BriefingGenerator::calculate_next_show_date("Intelligent Machines", date);
-        assert_eq!(result, "Wed, 4 February 2026");
-    }
-
     // ==================== HTML Escaping Tests ====================
 
     #[test]
@@ -516,10 +1384,16 @@ mod tests {
                 url: "https://example.com/x\"><script>alert(1)</script>".to_string(),
                 created: "2026-02-01T15:30:00Z".to_string(),
                 summary: Summary::Insufficient,
+                ..Default::default()
             }],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
 
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
         let html = BriefingGenerator::generate(&topics, "This Week in Tech", date);
 
         // The raw attribute breakout must not survive.
@@ -566,13 +1440,13 @@ mod tests {
 
     #[test]
     fn test_format_date_valid_iso() {
-        let result = BriefingGenerator::format_date("2026-02-01T15:30:00Z");
+        let result = BriefingGenerator::format_date("2026-02-01T15:30:00Z", Locale::En);
         assert_eq!(result, "1-Feb-2026 3:30PM");
     }
 
     #[test]
     fn test_format_date_invalid_fallback() {
-        let result = BriefingGenerator::format_date("not a date");
+        let result = BriefingGenerator::format_date("not a date", Locale::En);
         assert_eq!(result, "not a date");
     }
 
@@ -591,12 +1465,20 @@ mod tests {
                 summary: Summary::Editorial {
                     lede: "Acme Corp announced a new widget".to_string(),
                     nutgraf: "The announcement matters because it changes the industry".to_string(),
-                    quote: None,
+                    quotes: Vec::new(),
+                    reaction: None,
+                    by_the_numbers: None,
                 },
+                ..Default::default()
             }],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
 
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
         let html = BriefingGenerator::generate(&topics, "TWiT", date);
 
         assert!(html.contains("TWiT Briefing"));
@@ -619,17 +1501,28 @@ mod tests {
                 summary: Summary::Editorial {
                     lede: "Point \"quoted\"".to_string(),
                     nutgraf: "It matters".to_string(),
-                    quote: None,
+                    quotes: Vec::new(),
+                    reaction: Some("Fans & critics agree".to_string()),
+                    by_the_numbers: None,
                 },
+                ..Default::default()
             }],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
 
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
         let html = BriefingGenerator::generate(&topics, "Test", date);
 
         assert!(html.contains("Apple &amp; Google"));
         assert!(html.contains("Test &lt;script&gt;"));
         assert!(html.contains("Point &quot;quoted&quot;"));
+        assert!(html.contains(
+            "<p class=\"reaction\"><strong>Reaction:</strong> Fans &amp; critics agree</p>"
+        ));
     }
 
     // ==================== CSV Generation Tests ====================
@@ -646,14 +1539,19 @@ mod tests {
                     url: "https://a.com".to_string(),
                     created: "2026-02-01".to_string(),
                     summary: Summary::Insufficient,
+                    ..Default::default()
                 },
                 Story {
                     title: "Article 2".to_string(),
                     url: "https://b.com".to_string(),
                     created: "2026-02-01".to_string(),
                     summary: Summary::Insufficient,
+                    ..Default::default()
                 },
             ],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
 
         let csv = BriefingGenerator::generate_links_csv(&topics);
@@ -662,6 +1560,194 @@ mod tests {
         assert!(csv.contains(",Apple,Article 1,,https://a.com"));
         // Second row should have blank topic
         assert!(csv.contains(",,Article 2,,https://b.com"));
+
+        let short_links: HashMap<String, String> = [(
+            "https://a.com".to_string(),
+            "https://twit.to/a1".to_string(),
+        )]
+        .into();
+        let mut extras = BriefingExtras {
+            short_links,
+            ..Default::default()
+        };
+        let csv = BriefingGenerator::generate_links_csv_with(&topics, &extras);
+        assert_eq!(
+            csv,
+            ",Apple,Article 1,,https://a.com,https://twit.to/a1\n,,Article 2,,https://b.com,\n,,,,,\n"
+        );
+
+        // The link check goes in the column after the short link
+        extras.link_status = [
+            ("https://a.com".to_string(), LinkStatus::Ok),
+            (
+                "https://b.com".to_string(),
+                LinkStatus::Dead("404 Not Found".to_string()),
+            ),
+        ]
+        .into();
+        let csv = BriefingGenerator::generate_links_csv_with(&topics, &extras);
+        assert_eq!(
+            csv,
+            ",Apple,Article 1,,https://a.com,https://twit.to/a1,ok\n\
+             ,,Article 2,,https://b.com,,dead (404 Not Found)\n,,,,,,\n"
+        );
+        let without_short_links = BriefingExtras {
+            link_status: extras.link_status.clone(),
+            ..Default::default()
+        };
+        let csv = BriefingGenerator::generate_links_csv_with(&topics, &without_short_links);
+        assert!(csv.starts_with(",Apple,Article 1,,https://a.com,ok\n"));
+
+        // Metadata columns come last, in the order asked for
+        let mut topics = topics;
+        topics[0].stories[0].details = Some(StoryDetails {
+            source: Some("a.com".to_string()),
+            author: Some("Smith, Jane".to_string()),
+            words: Some(812),
+            ..Default::default()
+        });
+        let with_metadata = BriefingExtras {
+            csv_columns: "words,source,author,summary-type,date"
+                .split(',')
+                .map(|c| c.parse().unwrap())
+                .collect(),
+            ..Default::default()
+        };
+        let csv = BriefingGenerator::generate_links_csv_with(&topics, &with_metadata);
+        assert_eq!(
+            csv,
+            ",topic,title,,url,words,source,author,summary-type,date\n\
+             ,Apple,Article 1,,https://a.com,812,a.com,\"Smith, Jane\",insufficient,2026-02-01\n\
+             ,,Article 2,,https://b.com,,,,insufficient,2026-02-01\n,,,,,,,,,\n"
+        );
+        assert_eq!("Summary_Type".parse(), Ok(CsvColumn::SummaryType));
+        assert!("isbn"
+            .parse::<CsvColumn>()
+            .unwrap_err()
+            .contains("use date, source"));
+
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let html = BriefingGenerator::generate_with_extras(
+            &topics,
+            "TWiT",
+            date,
+            &BriefingSettings::default(),
+            &extras,
+        );
+        assert!(html.contains(">twit.to/a1</a>"));
+        assert_eq!(html.matches("Short link:").count(), 1);
+    }
+
+    #[test]
+    fn test_csv_column_names() {
+        for column in CsvColumn::ALL {
+            assert_eq!(column.name().parse(), Ok(*column));
+        }
+        assert_eq!(" Words ".parse(), Ok(CsvColumn::Words));
+        assert_eq!("summary-type".parse(), Ok(CsvColumn::SummaryType));
+        let error = "published".parse::<CsvColumn>().unwrap_err();
+        assert_eq!(
+            error,
+            "unknown CSV column 'published' (use date, source, author, summary-type, words)"
+        );
+        assert!("".parse::<CsvColumn>().is_err());
+    }
+
+    #[test]
+    fn test_links_csv_metadata_columns() {
+        use crate::summarizer::Summary;
+
+        let topics = vec![Topic {
+            title: "Apple".to_string(),
+            stories: vec![Story {
+                title: "Article 1".to_string(),
+                url: "https://a.com".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Failed("timed out".to_string()),
+                details: Some(StoryDetails {
+                    source: Some("a.com".to_string()),
+                    words: Some(90),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let csv = |columns: &[CsvColumn], short_links: bool| {
+            let extras = BriefingExtras {
+                csv_columns: columns.to_vec(),
+                short_links: if short_links {
+                    [("https://a.com".to_string(), "https://twit.to/a".to_string())].into()
+                } else {
+                    HashMap::new()
+                },
+                ..Default::default()
+            };
+            BriefingGenerator::generate_links_csv_with(&topics, &extras)
+        };
+
+        // No header without metadata columns
+        assert_eq!(csv(&[], false), ",Apple,Article 1,,https://a.com\n,,,,\n");
+
+        // In the order asked for, after the short link
+        use CsvColumn::*;
+        assert_eq!(
+            csv(&[Source, Date], true),
+            ",topic,title,,url,short-link,source,date\n\
+             ,Apple,Article 1,,https://a.com,https://twit.to/a,a.com,2026-02-01\n\
+             ,,,,,,,\n"
+        );
+        assert_eq!(
+            csv(&[Date, Source], false),
+            ",topic,title,,url,date,source\n\
+             ,Apple,Article 1,,https://a.com,2026-02-01,a.com\n,,,,,,\n"
+        );
+
+        // A column asked for twice comes out once, where it was first listed
+        assert_eq!(
+            csv(&[Words, SummaryType, Words, Author], false),
+            ",topic,title,,url,words,summary-type,author\n\
+             ,Apple,Article 1,,https://a.com,90,failed,\n,,,,,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_links_opml_and_org() {
+        use crate::summarizer::Summary;
+
+        let topics = vec![Topic {
+            title: "Apple & Google".to_string(),
+            stories: vec![Story {
+                title: "iOS 20 [beta] \"ships\"".to_string(),
+                url: "https://a.com/?x=1&y=2".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Insufficient,
+                ..Default::default()
+            }],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let opml = BriefingGenerator::generate_links_opml(&topics, "TWiT", date, Locale::En);
+        assert!(opml.contains("<title>TWiT Links - February 1, 2026</title>"));
+        assert!(opml.contains("<outline text=\"Apple &amp; Google\">"));
+        assert!(opml.contains(
+            "<outline text=\"iOS 20 [beta] &quot;ships&quot;\" type=\"link\" url=\"https://a.com/?x=1&amp;y=2\"/>"
+        ));
+
+        let org = BriefingGenerator::generate_links_org(&topics, "TWiT", date, Locale::En);
+        assert_eq!(
+            org,
+            "#+TITLE: TWiT Links - February 1, 2026\n\n* Apple & Google\n** [[https://a.com/?x=1&y=2][iOS 20 (beta) \"ships\"]]\n"
+        );
     }
 
     // ==================== Org Mode Generation Tests ====================
@@ -679,12 +1765,20 @@ mod tests {
                 summary: Summary::Editorial {
                     lede: "Apple unveiled a new M5 chip at its spring event".to_string(),
                     nutgraf: "The announcement signals Apple's continued investment in custom silicon, which has reshaped the laptop and desktop market since the M1 transition in 2020.".to_string(),
-                    quote: Some("\"A quote\" -- Author".to_string()),
+                    quotes: vec![Quote::parse("\"A quote\" -- Author")],
+                    reaction: None,
+                    by_the_numbers: None,
                 },
-            }],
+..Default::default()
+}],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
 
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
         let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
 
         assert!(org.contains("#+TITLE: TWiT Briefing Book"));
@@ -702,14 +1796,1039 @@ mod tests {
         assert!(org.contains("Apple unveiled a new M5 chip at its spring event\n\nThe announcement signals"));
     }
 
+    #[test]
+    fn test_dates_follow_locale() {
+        let story = Story {
+            title: "Story".to_string(),
+            url: "https://example.com/a".to_string(),
+            created: "2026-02-01T15:30:00Z".to_string(),
+            summary: Summary::Failed("n/a".to_string()),
+            ..Default::default()
+        };
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let settings = BriefingSettings {
+            locale: Locale::De,
+            ..Default::default()
+        };
+
+        let html = BriefingGenerator::generate_with(&topics, "TWiT", date, &settings);
+        assert!(html.contains("For Sonntag, 1. Februar 2026"));
+        assert!(html.contains("<strong>Date:</strong> 01.02.2026 15:30"));
+        let org = BriefingGenerator::generate_org_mode_with(&topics, "TWiT", date, &settings);
+        assert!(org.contains("#+DATE: So, 1. Februar 2026"));
+        // The story's own date stays as collected, for the round trip
+        assert!(org.contains("2026-02-01T15:30:00Z"));
+    }
+
     #[test]
     fn test_generate_org_mode_includes_standard_sections() {
         let topics = vec![];
-        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
         let org = BriefingGenerator::generate_org_mode(&topics, "Test", date);
 
         assert!(org.contains("* In Other News"));
         assert!(org.contains("* Leo's Picks"));
         assert!(org.contains("* In Memoriam"));
     }
+
+    // ==================== Comparison Table Tests ====================
+
+    fn product(the_product: &str, cost: &str, platforms: &str) -> Story {
+        Story {
+            title: format!("{} launched", the_product),
+            url: format!("https://example.com/{}", the_product.len()),
+            created: "2026-02-01".to_string(),
+            summary: Summary::Product {
+                the_product: the_product.to_string(),
+                cost: cost.to_string(),
+                availability: String::new(),
+                platforms: platforms.to_string(),
+                quotes: Vec::new(),
+                reaction: None,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_comparison_tables_for_product_topics() {
+        let topics = vec![Topic {
+            title: "Laptops".to_string(),
+            stories: vec![
+                product("MacBook Air M5", "$1,099", "macOS"),
+                product("Surface Laptop 8", "$999 | $1,299", "Windows 11"),
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let settings = BriefingSettings {
+            comparison_tables: true,
+            ..Default::default()
+        };
+
+        let org = BriefingGenerator::generate_org_mode_with(&topics, "TWiT", date, &settings);
+        assert!(org.contains(
+            "* Laptops\n\n\
+             | Product          | Cost          | Platforms  |\n\
+             |------------------+---------------+------------|\n\
+             | MacBook Air M5   | $1,099        | macOS      |\n\
+             | Surface Laptop 8 | $999 / $1,299 | Windows 11 |\n\n\
+             ** MacBook Air M5 launched"
+        ));
+        // The table doesn't disturb parsing the stories back
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories.len(), 2);
+
+        let html = BriefingGenerator::generate_with(&topics, "TWiT", date, &settings);
+        assert!(html.contains("<tr><th>Product</th><th>Cost</th><th>Platforms</th></tr>"));
+        assert!(html.contains("<td>$999 | $1,299</td>"));
+
+        // Off by default, and one product alone isn't a comparison
+        assert!(!BriefingGenerator::generate(&topics, "TWiT", date).contains("<table"));
+        let single = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        assert!(
+            !BriefingGenerator::generate_with(&single, "TWiT", date, &settings).contains("<table")
+        );
+    }
+
+    #[test]
+    fn test_connections_paragraph() {
+        let topics = vec![Topic {
+            title: "Laptops".to_string(),
+            stories: vec![
+                product("MacBook Air M5", "$1,099", "macOS"),
+                product("Surface Laptop 8", "$999", "Windows 11"),
+            ],
+            connections: Some("Both bet on ARM & battery life.".to_string()),
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains(
+            "* Laptops\n\nHow these connect: Both bet on ARM & battery life.\n\n** MacBook Air M5"
+        ));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].connections, topics[0].connections);
+        assert_eq!(parsed[0].stories.len(), 2);
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains(
+            "<p class=\"connections\"><strong>How these connect:</strong> Both bet on ARM &amp; battery life.</p>"
+        ));
+    }
+
+    #[test]
+    fn test_rationale_subtitle() {
+        let topics = vec![Topic {
+            title: "Laptops".to_string(),
+            stories: vec![product("MacBook Air M5", "$1,099", "macOS")],
+            connections: Some("Both bet on ARM.".to_string()),
+            notes: None,
+            rationale: Some("New ARM laptops <this week>".to_string()),
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains(
+            "* Laptops\n\nGrouped because: New ARM laptops <this week>\n\nHow these connect:"
+        ));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].rationale, topics[0].rationale);
+        assert_eq!(parsed[0].connections, topics[0].connections);
+        assert_eq!(parsed[0].notes, None);
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains("<p class=\"rationale\">New ARM laptops &lt;this week&gt;</p>"));
+    }
+
+    #[test]
+    fn test_by_the_numbers_round_trip() {
+        let mut story = product("Widget", "$10", "iOS");
+        story.summary = Summary::Editorial {
+            lede: "Intel agreed to buy a chip designer.".to_string(),
+            nutgraf: "The deal doubles its GPU team.".to_string(),
+            quotes: Vec::new(),
+            reaction: Some("Commenters doubt it.".to_string()),
+            by_the_numbers: Some("$3B acquisition, 12,000 layoffs & 40% YoY".to_string()),
+        };
+        let topics = vec![Topic {
+            title: "Chips".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains(
+            "The deal doubles its GPU team.\n\nBy the numbers: $3B acquisition, 12,000 layoffs & 40% YoY\n\nReaction:"
+        ));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        match &parsed[0].stories[0].summary {
+            Summary::Editorial {
+                by_the_numbers,
+                reaction,
+                ..
+            } => {
+                assert_eq!(
+                    by_the_numbers.as_deref(),
+                    Some("$3B acquisition, 12,000 layoffs & 40% YoY")
+                );
+                assert_eq!(reaction.as_deref(), Some("Commenters doubt it."));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(parsed[0].stories[0].notes, None);
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains(
+            "<p class=\"numbers\"><strong>By the numbers:</strong> $3B acquisition, 12,000 layoffs &amp; 40% YoY</p>"
+        ));
+    }
+
+    #[test]
+    fn test_story_notes_round_trip() {
+        let mut story = product("Widget", "$10", "iOS");
+        story.notes = Some(
+            "Ask about pricing & [[https://example.com/w][the review]]\n\n- Demo it".to_string(),
+        );
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains(
+            "    <div class=\"host-notes\">\n      <p class=\"label\">Host notes</p>\n      <p>Ask about pricing &amp; <a href=\"https://example.com/w\">the review</a></p>\n      <ul>\n        <li>Demo it</li>\n      </ul>\n    </div>\n    <hr>"
+        ));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains("*** Notes\nAsk about pricing"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].notes, topics[0].stories[0].notes);
+        assert!(matches!(
+            &parsed[0].stories[0].summary,
+            Summary::Product { the_product, .. } if the_product == "Widget"
+        ));
+    }
+
+    #[test]
+    fn test_starred_story_round_trip() {
+        let mut starred = product("Widget", "$10", "iOS");
+        starred.starred = true;
+        starred.hosts = vec!["leo".to_string()];
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![starred, product("Gizmo", "$20", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains(
+            "<h3 class=\"starred\"><span class=\"star\" title=\"Must cover\">★</span> Widget launched <span class=\"host\""
+        ));
+        assert!(html.contains("<h3>Gizmo launched</h3>"));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains("** TODO Widget launched :leo:\n"));
+        assert!(org.contains("** Gizmo launched\n"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].title, "Widget launched");
+        assert!(parsed[0].stories[0].starred);
+        assert!(!parsed[0].stories[1].starred);
+
+        // Marking it done keeps the star
+        let (_, parsed) = crate::org::parse_org_mode(&org.replace("** TODO", "** DONE")).unwrap();
+        assert_eq!(parsed[0].stories[0].title, "Widget launched");
+        assert!(parsed[0].stories[0].starred);
+    }
+
+    #[test]
+    fn test_org_links_round_trip() {
+        let mut story = product("Widget [beta]", "$10", "iOS");
+        story.url = "https://example.com/widget".to_string();
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let settings = BriefingSettings {
+            org_links: true,
+            ..Default::default()
+        };
+
+        let org = BriefingGenerator::generate_org_mode_with(&topics, "TWiT", date, &settings);
+        assert!(org.contains("*** URL\n[[https://example.com/widget][Widget (beta) launched]]\n"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].url, "https://example.com/widget");
+        assert_eq!(parsed[0].stories[0].title, "Widget [beta] launched");
+    }
+
+    #[test]
+    fn test_followup_round_trip() {
+        let mut followup = product("Widget", "$10", "iOS");
+        followup.previously = Some("TWiT Sun 25 Jan".to_string());
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![followup, product("Gizmo", "$20", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains("** Widget launched\n\nPREVIOUSLY: TWiT Sun 25 Jan\n"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(
+            parsed[0].stories[0].previously.as_deref(),
+            Some("TWiT Sun 25 Jan")
+        );
+        assert_eq!(parsed[0].stories[0].notes, None);
+        assert_eq!(parsed[0].stories[1].previously, None);
+
+        let html = BriefingGenerator::generate(&parsed, "TWiT", date);
+        assert_eq!(html.matches("<div class=\"followup\">").count(), 1);
+        assert!(html.contains("↩ Follow-up: covered on TWiT Sun 25 Jan"));
+
+        // With a recap, it's shown with the earlier briefing after it
+        let mut topics = parsed;
+        topics[0].stories[0].followup = Some("Apple recalled the straps.".to_string());
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org
+            .contains("PREVIOUSLY: TWiT Sun 25 Jan\nRECAP: Apple recalled the straps.\n\n*** URL"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(
+            parsed[0].stories[0].followup.as_deref(),
+            Some("Apple recalled the straps.")
+        );
+        assert_eq!(parsed[0].stories[0].notes, None);
+        let html = BriefingGenerator::generate(&parsed, "TWiT", date);
+        assert!(html.contains(
+            "<div class=\"followup\">↩ Previously: Apple recalled the straps. <span class=\"followup-source\">(TWiT Sun 25 Jan)</span></div>"
+        ));
+    }
+
+    #[test]
+    fn test_story_language_round_trip() {
+        let mut story = product("Widget", "$10", "iOS");
+        story.details = Some(StoryDetails {
+            source: Some("heise.de".to_string()),
+            language: Some("de".to_string()),
+            translated: true,
+            ..Default::default()
+        });
+        let mut untranslated = product("Gadget", "$20", "iOS");
+        untranslated.details = Some(StoryDetails {
+            language: Some("fr".to_string()),
+            ..Default::default()
+        });
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![story, untranslated],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains("<span class=\"details\">heise.de · translated from German</span>"));
+        assert!(html.contains("<span class=\"details\">in French</span>"));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains(":SOURCE: heise.de\n:LANGUAGE: de\n:TRANSLATED: yes\n:END:"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].details, topics[0].stories[0].details);
+        assert_eq!(parsed[0].stories[1].details, topics[0].stories[1].details);
+    }
+
+    #[test]
+    fn test_entities_round_trip() {
+        let mut story = product("Widget", "$10", "iOS");
+        story.entities = Some(Entities {
+            companies: vec!["Apple".to_string(), "AT&T".to_string()],
+            products: Vec::new(),
+            people: vec!["Tim Cook".to_string()],
+        });
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains(
+            "** Widget launched\n:DETAILS:\n:COMPANIES: Apple, AT&T\n:PEOPLE: Tim Cook\n:END:\n"
+        ));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].entities, topics[0].stories[0].entities);
+        assert_eq!(parsed[0].stories[0].details, None);
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains(
+            "<div class=\"entities\"><span class=\"entity company\">Apple</span> \
+             <span class=\"entity company\">AT&amp;T</span> \
+             <span class=\"entity person\">Tim Cook</span></div>"
+        ));
+    }
+
+    #[test]
+    fn test_story_details_round_trip() {
+        let mut story = product("Widget", "$10", "iOS");
+        story.details = Some(StoryDetails {
+            source: Some("theverge.com".to_string()),
+            author: Some("Jane & Joe".to_string()),
+            words: Some(1200),
+            reading_minutes: Some(6),
+            image: Some("https://cdn.theverge.com/widget.jpg".to_string()),
+            ..Default::default()
+        });
+        let topics = vec![Topic {
+            title: "Gadgets".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains(
+            "<h3>Widget launched <span class=\"details\">theverge.com · Jane &amp; Joe · 1200 words, 6 min read</span></h3>"
+        ));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert!(org.contains(
+            "** Widget launched\n:DETAILS:\n:SOURCE: theverge.com\n:AUTHOR: Jane & Joe\n\
+             :WORDS: 1200\n:READING_TIME: 6 min\n\
+             :IMAGE: https://cdn.theverge.com/widget.jpg\n:END:\n\n*** URL"
+        ));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].details, topics[0].stories[0].details);
+        assert_eq!(parsed[0].stories[0].entities, None);
+
+        // Thumbnails only appear once they've been downloaded
+        assert!(!html.contains("<img"));
+        let extras = BriefingExtras {
+            thumbnails: [(
+                topics[0].stories[0].url.clone(),
+                "data:image/png;base64,YWJj".to_string(),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let html = BriefingGenerator::generate_with_extras(
+            &topics,
+            "TWiT",
+            date,
+            &BriefingSettings::default(),
+            &extras,
+        );
+        assert!(html.contains(
+            "</h3>\n    <img class=\"thumbnail\" src=\"data:image/png;base64,YWJj\" alt=\"\">\n    <div class=\"metadata\">"
+        ));
+    }
+
+    #[test]
+    fn test_notes_sections_round_trip() {
+        let org = "#+TITLE: TWiT Briefing Book\n\n\
+                   * Apple\n\n\
+                   ** iPhone 17 Announced\n\n*** URL\nhttps://example.com/a\n\n\
+                   *** Summary\nApple announced it.\n\nIt matters.\n\n\
+                   * Leo's Picks\n\n\
+                   - [[https://example.com/pick?a=1&b=2][A great app]]\n\
+                   - A book & a movie\n\n\
+                   Read the ad for Squarespace here.\n\n\
+                   * In Memoriam\n\n";
+        let (_, topics) = crate::org::parse_org_mode(org).unwrap();
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[1].title, "Leo's Picks");
+        assert!(topics[1].stories.is_empty());
+        assert_eq!(
+            topics[1].notes.as_deref(),
+            Some(
+                "- [[https://example.com/pick?a=1&b=2][A great app]]\n\
+                 - A book & a movie\n\nRead the ad for Squarespace here."
+            )
+        );
+
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains("<li><a href=\"#topic-2\">Leo&#39;s Picks</a></li>"));
+        assert!(html.contains(
+            "      <ul>\n        <li><a href=\"https://example.com/pick?a=1&amp;b=2\">A great app</a></li>\n        <li>A book &amp; a movie</li>\n      </ul>\n      <p>Read the ad for Squarespace here.</p>"
+        ));
+
+        // Written back once, notes included
+        let again = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        assert_eq!(again.matches("* Leo's Picks").count(), 1);
+        let (_, reparsed) = crate::org::parse_org_mode(&again).unwrap();
+        assert_eq!(reparsed[1].notes, topics[1].notes);
+    }
+
+    #[test]
+    fn test_by_the_numbers_section() {
+        let mut story = product("Surface Laptop 8", "$999", "Windows 11");
+        story.title = "Microsoft's new laptop".to_string();
+        let topics = vec![Topic {
+            title: "Laptops".to_string(),
+            stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let settings = BriefingSettings {
+            by_the_numbers: true,
+            ..Default::default()
+        };
+
+        let org = BriefingGenerator::generate_org_mode_with(&topics, "TWiT", date, &settings);
+        assert!(org.contains("* By the Numbers\n\n- *$999* :: Microsoft's new laptop\n\n* Laptops"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed.len(), 1);
+
+        let html = BriefingGenerator::generate_with(&topics, "TWiT", date, &settings);
+        assert!(html.contains("<li><strong>$999</strong> — <a href="));
+        assert!(!BriefingGenerator::generate(&topics, "TWiT", date).contains("<aside"));
+    }
+
+    #[test]
+    fn test_host_labels_and_views() {
+        let mut tagged = product("MacBook Air M5", "$1,099", "macOS");
+        tagged.hosts = vec!["leo".to_string(), "andy".to_string()];
+        let topics = vec![
+            Topic {
+                title: "Laptops".to_string(),
+                stories: vec![tagged, product("Surface Laptop 8", "$999", "Windows")],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Phones".to_string(),
+                stories: vec![product("Pixel 11", "$799", "Android")],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ];
+        let mut settings = BriefingSettings::default();
+        settings.hosts.insert(
+            "andy".to_string(),
+            HostSettings {
+                name: Some("Andy Ihnatko".to_string()),
+                color: Some("teal".to_string()),
+            },
+        );
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate_with(&topics, "MBW", date, &settings);
+        assert!(html.contains(
+            "<h3>MacBook Air M5 launched <span class=\"host\" style=\"background-color: #"
+        ));
+        assert!(html.contains(">Leo</span> <span class=\"host\" style=\"background-color: teal\">Andy Ihnatko</span></h3>"));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "MBW", date);
+        assert!(org.contains("** MacBook Air M5 launched :leo:andy:\n"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].title, "MacBook Air M5 launched");
+        assert_eq!(parsed[0].stories[0].hosts, ["leo", "andy"]);
+
+        assert_eq!(assigned_hosts(&topics), ["andy", "leo"]);
+        let view = stories_for_host(&topics, "leo");
+        assert_eq!(view.len(), 1);
+        assert_eq!(view[0].stories.len(), 1);
+    }
+
+    #[test]
+    fn test_disclosure_banner() {
+        let topics = vec![Topic {
+            title: "Laptops".to_string(),
+            stories: vec![
+                product("MacBook Air M5", "$1,099", "macOS"),
+                product("Surface Laptop 8", "$999", "Windows"),
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let settings = BriefingSettings {
+            disclosures: DisclosureSettings {
+                sponsors: vec!["Surface".to_string()],
+                companies: Vec::new(),
+            },
+            ..Default::default()
+        };
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate_with(&topics, "MBW", date, &settings);
+        assert_eq!(html.matches("<div class=\"disclosure\">").count(), 1);
+        assert!(html.contains(
+            "<h3>Surface Laptop 8 launched</h3>\n    <div class=\"disclosure\">⚠ Disclosure needed: Surface (sponsor)</div>"
+        ));
+
+        let org = BriefingGenerator::generate_org_mode_with(&topics, "MBW", date, &settings);
+        assert!(org.contains("DISCLOSURE NEEDED: Surface (sponsor)\n"));
+        // The banner doesn't leak into the parsed story
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[1].url, topics[0].stories[1].url);
+    }
+
+    #[test]
+    fn test_stale_badge() {
+        let mut old = product("Pixel 10", "$699", "Android");
+        old.created = "2026-01-18".to_string();
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![old, product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 0, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "MBW", date);
+        assert_eq!(html.matches("class=\"stale\"").count(), 1);
+        assert!(html.contains("<h3>Pixel 10 launched <span class=\"stale\">⚠ 14 days old</span>"));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "MBW", date);
+        assert!(org.contains("STALE: 14 days old\n"));
+        // The flag is recomputed, not parsed into the story
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].notes, None);
+        assert_eq!(parsed[0].stories[0].created, "2026-01-18");
+
+        let settings = BriefingSettings {
+            staleness: StalenessSettings { days: 0 },
+            ..Default::default()
+        };
+        let html = BriefingGenerator::generate_with(&topics, "MBW", date, &settings);
+        assert!(!html.contains("class=\"stale\""));
+    }
+
+    #[test]
+    fn test_sensitive_marker() {
+        let mut layoffs = product("Pixel 10", "$699", "Android");
+        layoffs.title = "Google lays off Pixel team".to_string();
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![layoffs, product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 0, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "MBW", date);
+        assert_eq!(html.matches("class=\"sensitive\"").count(), 1);
+        assert!(html.contains(
+            "<h3>Google lays off Pixel team <span class=\"sensitive\" title=\"Careful framing: layoffs\">◆</span>"
+        ));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "MBW", date);
+        assert!(org.contains("SENSITIVE: layoffs\n"));
+        // Like the other flags, it isn't parsed into the story
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        assert_eq!(parsed[0].stories[0].notes, None);
+
+        let settings = BriefingSettings {
+            sensitive: SensitiveSettings {
+                enabled: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let html = BriefingGenerator::generate_with(&topics, "MBW", date, &settings);
+        assert!(!html.contains("class=\"sensitive\""));
+    }
+
+    #[test]
+    fn test_quote_speaker_in_bold() {
+        let with_quotes = |lines: &[&str]| {
+            let mut story = product("Pixel 11", "$799", "Android");
+            if let Summary::Product { quotes, .. } = &mut story.summary {
+                *quotes = lines.iter().map(|line| Quote::parse(line)).collect();
+            }
+            story
+        };
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![
+                with_quotes(&["“Our best yet” — Rick Osterloh, Google"]),
+                with_quotes(&["\"Our best yet,\" Osterloh said <again>"]),
+                with_quotes(&["\"Fast\" -- Ann", "\"Pricey\" -- Bo (reviewer)"]),
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 0, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "MBW", date);
+        assert!(html.contains(
+            "<p><em>&quot;Our best yet&quot;</em> -- <strong>Rick Osterloh</strong>, Google</p>"
+        ));
+        // Free-form quotes from hand-edited drafts are shown as written
+        assert!(
+            html.contains("<p><em>&quot;Our best yet,&quot; Osterloh said &lt;again&gt;</em></p>")
+        );
+
+        // Several quotes make a list
+        assert!(html.contains(
+            "<ul class=\"quotes\">\n        <li><em>&quot;Fast&quot;</em> -- <strong>Ann</strong></li>\n        <li><em>&quot;Pricey&quot;</em> -- <strong>Bo</strong>, reviewer</li>\n      </ul>"
+        ));
+
+        let org = BriefingGenerator::generate_org_mode(&topics, "MBW", date);
+        assert!(org.contains("\"Our best yet\" -- Rick Osterloh, Google\n"));
+        let (_, parsed) = crate::org::parse_org_mode(&org).unwrap();
+        for (parsed, story) in parsed[0].stories.iter().zip(&topics[0].stories) {
+            match (&parsed.summary, &story.summary) {
+                (Summary::Product { quotes, .. }, Summary::Product { quotes: want, .. }) => {
+                    assert_eq!(quotes, want)
+                }
+                other => panic!("Expected Summary::Product, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_this_week_section() {
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let events = vec![
+            Event {
+                date: chrono::NaiveDate::from_ymd_opt(2026, 2, 3).unwrap(),
+                time: chrono::NaiveTime::from_hms_opt(10, 0, 0),
+                summary: "Samsung Galaxy Unpacked".to_string(),
+                location: Some("San Jose, CA".to_string()),
+            },
+            Event {
+                date: chrono::NaiveDate::from_ymd_opt(2026, 2, 5).unwrap(),
+                time: None,
+                summary: "Apple Q1 earnings".to_string(),
+                location: None,
+            },
+        ];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let settings = BriefingSettings::default();
+
+        let extras = BriefingExtras {
+            events,
+            ..Default::default()
+        };
+        let html =
+            BriefingGenerator::generate_with_extras(&topics, "TWiT", date, &settings, &extras);
+        assert!(html.contains(
+            "<li><strong>Tue 3 Feb 10:00 AM</strong> — Samsung Galaxy Unpacked (San Jose, CA)</li>"
+        ));
+        assert!(html.contains("<li><strong>Thu 5 Feb</strong> — Apple Q1 earnings</li>"));
+        assert!(html.find("This Week").unwrap() < html.find("id=\"topic-1\"").unwrap());
+        assert!(
+            !BriefingGenerator::generate_with(&topics, "TWiT", date, &settings)
+                .contains("this-week\">")
+        );
+    }
+
+    #[test]
+    fn test_lookback_section_at_end() {
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let extras = BriefingExtras {
+            lookbacks: vec![
+                Lookback {
+                    years: 1,
+                    date: chrono::NaiveDate::from_ymd_opt(2025, 2, 2).unwrap(),
+                    stories: vec![(
+                        "Vision Pro ships".to_string(),
+                        "https://example.com/vp".to_string(),
+                    )],
+                },
+                Lookback {
+                    years: 5,
+                    date: chrono::NaiveDate::from_ymd_opt(2021, 1, 31).unwrap(),
+                    stories: vec![(
+                        "GameStop & Reddit".to_string(),
+                        "https://example.com/gme".to_string(),
+                    )],
+                },
+            ],
+            ..Default::default()
+        };
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate_with_extras(
+            &topics,
+            "TWiT",
+            date,
+            &BriefingSettings::default(),
+            &extras,
+        );
+        assert!(html.contains(
+            "<h2>One Year Ago This Week <span class=\"date\">2 February 2025</span></h2>"
+        ));
+        assert!(html.contains("<h2>5 Years Ago This Week"));
+        assert!(html.contains(">GameStop &amp; Reddit</a>"));
+        assert!(html.find("id=\"topic-1\"").unwrap() < html.find("One Year Ago").unwrap());
+    }
+
+    #[test]
+    fn test_print_theme_expands_topics() {
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+        let settings = BriefingSettings {
+            theme: Some("print".to_string()),
+            ..Default::default()
+        };
+
+        let html = BriefingGenerator::generate_with(&topics, "TWiT", date, &settings);
+        assert!(html.contains("<details class=\"topic\" id=\"topic-1\" open>"));
+        assert!(html.contains("font-family: Georgia"));
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains("<details class=\"topic\" id=\"topic-1\">"));
+    }
+
+    #[test]
+    fn test_trends_note() {
+        let topics = vec![Topic {
+            title: "AI Chips".to_string(),
+            stories: vec![product("H300", "$30,000", "Data centers")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let extras = BriefingExtras {
+            trends: vec![Trend {
+                topic: "AI Chips".to_string(),
+                counts: vec![1, 2, 1],
+            }],
+            ..Default::default()
+        };
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate_with_extras(
+            &topics,
+            "TWiT",
+            date,
+            &BriefingSettings::default(),
+            &extras,
+        );
+        assert!(html.contains(
+            "<h2>Trends</h2>\n  <ul>\n    <li>Third consecutive week of AI Chips stories (1 → 2 → 1)</li>"
+        ));
+    }
+
+    #[test]
+    fn test_covered_elsewhere_label() {
+        let topics = vec![Topic {
+            title: "Phones".to_string(),
+            stories: vec![product("Pixel 11", "$799", "Android")],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+        let mut extras = BriefingExtras::default();
+        extras.covered.insert(
+            canonicalize_url(&format!(
+                "https://www.example.com/{}?utm_source=x",
+                "Pixel 11".len()
+            )),
+            vec![Coverage {
+                show: "MacBreak Weekly".to_string(),
+                date: chrono::NaiveDate::from_ymd_opt(2026, 2, 3).unwrap(),
+            }],
+        );
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 8, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate_with_extras(
+            &topics,
+            "TWiT",
+            date,
+            &BriefingSettings::default(),
+            &extras,
+        );
+        assert!(html
+            .contains("<div class=\"covered\">↺ Also covered on MacBreak Weekly Tue 3 Feb</div>"));
+    }
+
+    #[test]
+    fn test_table_of_contents_links_to_topics() {
+        let topics = vec![
+            Topic {
+                title: "Laptops & Tablets".to_string(),
+                stories: vec![
+                    product("MacBook Air M5", "$1,099", "macOS"),
+                    product("Surface Laptop 8", "$999", "Windows"),
+                ],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Phones".to_string(),
+                stories: vec![product("Pixel 11", "$799", "Android")],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ];
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+        assert!(html.contains("<h1 id=\"top\">"));
+        assert!(html.contains(
+            "<li><a href=\"#topic-1\">Laptops &amp; Tablets</a> <span class=\"count\">(2 stories)</span></li>"
+        ));
+        assert!(html.contains(
+            "<li><a href=\"#topic-2\">Phones</a> <span class=\"count\">(1 story)</span></li>"
+        ));
+        assert!(html.contains("<details class=\"topic\" id=\"topic-2\">"));
+        assert_eq!(html.matches("href=\"#top\"").count(), 2);
+        assert!(html.find("<nav class=\"toc\">").unwrap() < html.find("id=\"topic-1\"").unwrap());
+        assert!(!html.contains("class=\"talk-time\""));
+    }
+
+    #[test]
+    fn test_talk_time_in_contents() {
+        let topics = vec![
+            Topic {
+                title: "Laptops".to_string(),
+                stories: vec![
+                    product("MacBook Air M5", "$1,099", "macOS"),
+                    product("Surface Laptop 8", "$999", "Windows"),
+                ],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Phones".to_string(),
+                stories: vec![product("Pixel 11", "$799", "Android")],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ];
+        let settings = BriefingSettings {
+            talk_time: TalkTimeSettings {
+                enabled: true,
+                minutes_per_story: Some(25),
+                show_minutes: Some(120),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let date = crate::timezone::local()
+            .with_ymd_and_hms(2026, 2, 1, 12, 0, 0)
+            .unwrap();
+
+        let html = BriefingGenerator::generate_with(&topics, "TWiT", date, &settings);
+        assert!(html.contains(
+            "<span class=\"count\">(2 stories)</span> <span class=\"talk-time\">~50 min</span></li>"
+        ));
+        assert!(html.contains("<span class=\"talk-time\">~25 min</span></li>"));
+        assert!(html.contains(
+            "<p class=\"talk-time\"><strong>Estimated talk time:</strong> 1 h 15 min, 45 min under the 2 h show</p>\n</nav>"
+        ));
+    }
 }
@@ -0,0 +1,125 @@
+//! Wall-clock budgets for the slow phases of a run (`[budget]` in
+//! config.toml).
+//!
+//! On a heavy news week fetching and summarizing can take long enough to
+//! miss show prep. Each phase gets a budget in minutes; once it runs out, no
+//! new work starts. Requests already in flight finish, the remaining items
+//! are marked as skipped, and the run goes on to build the briefing with
+//! what it has. Skipped items aren't cached, so the next run picks them up.
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Deserialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Reason given for summaries skipped because the budget ran out.
+pub const OUT_OF_TIME: &str = "Skipped: the summarize time budget ran out";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BudgetSettings {
+    /// Minutes for fetching articles; 0 for no limit
+    pub fetch_minutes: u64,
+    /// Minutes for summarizing, excerpts included; 0 for no limit
+    pub summarize_minutes: u64,
+}
+
+impl Default for BudgetSettings {
+    fn default() -> Self {
+        Self {
+            fetch_minutes: 5,
+            summarize_minutes: 15,
+        }
+    }
+}
+
+impl BudgetSettings {
+    /// When a fetch phase starting now must stop starting new work.
+    pub fn fetch_deadline(&self) -> Option<Instant> {
+        deadline(self.fetch_minutes)
+    }
+
+    pub fn summarize_deadline(&self) -> Option<Instant> {
+        deadline(self.summarize_minutes)
+    }
+}
+
+fn deadline(minutes: u64) -> Option<Instant> {
+    (minutes > 0).then(|| Instant::now() + Duration::from_secs(minutes * 60))
+}
+
+pub fn expired(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|d| Instant::now() >= d)
+}
+
+/// Run `task` on each item, up to `concurrency` at once, in the manner of
+/// `buffer_unordered`. After `deadline` no new item is started; the results
+/// of those in flight are still collected. Returns the results in
+/// completion order and the items that never started.
+pub async fn run_until<T, R, F, Fut>(
+    items: Vec<T>,
+    concurrency: usize,
+    deadline: Option<Instant>,
+    mut task: F,
+) -> (Vec<R>, Vec<T>)
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = R>,
+{
+    let mut pending = items.into_iter();
+    let mut in_flight = FuturesUnordered::new();
+    let mut results = Vec::new();
+    loop {
+        while in_flight.len() < concurrency.max(1) && !expired(deadline) {
+            match pending.next() {
+                Some(item) => in_flight.push(task(item)),
+                None => break,
+            }
+        }
+        match in_flight.next().await {
+            Some(result) => results.push(result),
+            None => break,
+        }
+    }
+    (results, pending.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_until_without_deadline_runs_everything() {
+        let (mut results, skipped) =
+            run_until(vec![1, 2, 3], 2, None, |n| async move { n * 10 }).await;
+        results.sort();
+        assert_eq!(results, [10, 20, 30]);
+        assert!(skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_until_finishes_in_flight_and_skips_the_rest() {
+        let deadline = Some(Instant::now() + Duration::from_millis(50));
+        let (results, skipped) = run_until((0..6).collect(), 2, deadline, |n| async move {
+            tokio::time::sleep(Duration::from_millis(40)).await;
+            n
+        })
+        .await;
+        // Two batches of two start before the deadline; both finish
+        assert_eq!(results.len(), 4);
+        assert_eq!(skipped, [4, 5]);
+
+        let past = Some(Instant::now());
+        let (results, skipped) = run_until(vec![1], 2, past, |n| async move { n }).await;
+        assert!(results.is_empty());
+        assert_eq!(skipped, [1]);
+    }
+
+    #[test]
+    fn test_zero_minutes_means_no_limit() {
+        let settings: BudgetSettings = toml::from_str("fetch_minutes = 0").unwrap();
+        assert!(settings.fetch_deadline().is_none());
+        assert_eq!(settings.summarize_minutes, 15);
+        assert!(settings.summarize_deadline().is_some());
+    }
+}
@@ -0,0 +1,250 @@
+//! "This Week": upcoming dates from iCalendar (ICS) feeds.
+//!
+//! Earnings calendars, launch events and conference schedules are commonly
+//! published as ICS feeds. `prepare-briefing` reads the feeds configured
+//! for a show and opens the briefing with the events in the days after the
+//! show. Only what the briefing needs is parsed: each event's start, title
+//! and location. Recurring events show their first occurrence only.
+//!
+//! Times with a `TZID` are shown as written; UTC times are converted to
+//! local time.
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The `[briefing.calendar]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CalendarSettings {
+    /// How many days after the show date to include
+    pub days: u32,
+    pub feeds: Vec<Feed>,
+}
+
+impl Default for CalendarSettings {
+    fn default() -> Self {
+        Self {
+            days: 7,
+            feeds: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Feed {
+    /// An `http(s)://` URL or a local file path (`~/` allowed)
+    pub url: String,
+    /// Show slugs the feed is for; every show when empty
+    pub shows: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Event {
+    pub date: NaiveDate,
+    /// `None` for all-day events
+    pub time: Option<NaiveTime>,
+    pub summary: String,
+    pub location: Option<String>,
+}
+
+/// Events from every feed for `show_slug` that start within the configured
+/// number of days from `from`, in date order. A feed that can't be read is
+/// skipped with a warning; it shouldn't hold up the briefing.
+pub async fn upcoming(settings: &CalendarSettings, show_slug: &str, from: NaiveDate) -> Vec<Event> {
+    let feeds: Vec<&Feed> = settings
+        .feeds
+        .iter()
+        .filter(|f| f.shows.is_empty() || f.shows.iter().any(|s| s == show_slug))
+        .collect();
+    if feeds.is_empty() {
+        return Vec::new();
+    }
+
+    let mut events = Vec::new();
+    for feed in feeds {
+        match read_feed(&feed.url).await {
+            Ok(text) => events.extend(parse_ics(&text)),
+            Err(e) => tracing::warn!("Skipping calendar {}: {:#}", feed.url, e),
+        }
+    }
+    within(events, from, settings.days)
+}
+
+async fn read_feed(url: &str) -> Result<String> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        let path = match (url.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => url.into(),
+        };
+        return std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()));
+    }
+    let response = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?;
+    let body = crate::net::read_body_capped(response, crate::net::MAX_BODY_BYTES)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Events starting on `from` or in the `days` after it, sorted, without
+/// repeats from overlapping feeds.
+pub fn within(mut events: Vec<Event>, from: NaiveDate, days: u32) -> Vec<Event> {
+    let until = from + chrono::Duration::days(days as i64);
+    events.retain(|e| e.date >= from && e.date <= until);
+    events.sort();
+    events.dedup_by(|a, b| a.date == b.date && a.summary == b.summary);
+    events
+}
+
+/// The fields of the `VEVENT` being read.
+#[derive(Default)]
+struct Partial {
+    start: Option<(NaiveDate, Option<NaiveTime>)>,
+    summary: Option<String>,
+    location: Option<String>,
+}
+
+/// Every `VEVENT` in an iCalendar document that has a start and a title.
+pub fn parse_ics(text: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut current: Option<Partial> = None;
+
+    for line in unfold(text) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // "DTSTART;TZID=America/New_York" -> "DTSTART"
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name.to_ascii_uppercase().as_str(), current.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(Partial::default())
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let event = current.take().unwrap_or_default();
+                if let (Some((date, time)), Some(summary)) = (event.start, event.summary) {
+                    events.push(Event {
+                        date,
+                        time,
+                        summary,
+                        location: event.location,
+                    });
+                }
+            }
+            ("DTSTART", Some(event)) => event.start = parse_start(value, params),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(value)),
+            ("LOCATION", Some(event)) if !value.trim().is_empty() => {
+                event.location = Some(unescape(value))
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Join continuation lines (RFC 5545 folds long lines with a leading space).
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_start(value: &str, params: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let value = value.trim();
+    if params.to_ascii_uppercase().contains("VALUE=DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(|d| (d, None));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = Utc.from_utc_datetime(&naive).with_timezone(&Local);
+        return Some((local.date_naive(), Some(local.time())));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some((naive.date(), Some(naive.time())))
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+DTSTART;VALUE=DATE:20260205\r\n\
+SUMMARY:Apple Q1 earnings\\, after the bell\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+DTSTART;TZID=America/Los_Angeles:20260203T100000\r\n\
+SUMMARY:Samsung Galaxy Unpacked\r\n\
+LOCATION:San Jose\r\n \\, CA\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+DTSTART:20260301\r\n\
+SUMMARY:MWC\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:No start date\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn test_parse_ics() {
+        let events = parse_ics(ICS);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].summary, "Apple Q1 earnings, after the bell");
+        assert_eq!(events[0].time, None);
+        assert_eq!(events[1].date, NaiveDate::from_ymd_opt(2026, 2, 3).unwrap());
+        assert_eq!(events[1].time, NaiveTime::from_hms_opt(10, 0, 0));
+        assert_eq!(events[1].location.as_deref(), Some("San Jose, CA"));
+    }
+
+    #[test]
+    fn test_within_window_sorted_and_deduped() {
+        let mut events = parse_ics(ICS);
+        events.extend(parse_ics(ICS));
+        let from = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+
+        let week = within(events, from, 7);
+        let titles: Vec<&str> = week.iter().map(|e| e.summary.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Samsung Galaxy Unpacked",
+                "Apple Q1 earnings, after the bell"
+            ]
+        );
+    }
+}
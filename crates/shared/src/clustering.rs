@@ -1,28 +1,255 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, FixedOffset, NaiveDate};
-use reqwest::Client;
+use chrono::{DateTime, FixedOffset};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::entities::Entities;
+use crate::extractor::ArticleContent;
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind};
 use crate::summarizer::Summary;
+use crate::usage::UsageTracker;
 
-const GLM_MODEL: &str = "glm-5.2";
-const ZAI_API_URL: &str = "https://api.z.ai/api/anthropic/v1/messages";
 const CLUSTER_TIMEOUT: Duration = Duration::from_secs(90);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Story {
     pub title: String,
     pub url: String,
     pub created: String,
     pub summary: Summary,
+    /// Hosts leading this story, as org tags without colons (e.g. `leo`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    /// The host's own text under the story in the org file (talking
+    /// points, questions for guests), kept as written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Source, byline and length of the article
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<StoryDetails>,
+    /// A must-cover story, listed first in its topic
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub starred: bool,
+    /// The earlier briefing that had this story, e.g. `TWiT Sun 25 Jan`,
+    /// from [`crate::archive::Archive::previously_covered`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previously: Option<String>,
+    /// One line recapping that earlier coverage, from
+    /// [`crate::followups::FollowupWriter`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub followup: Option<String>,
+    /// Companies, products and people the story names, from
+    /// [`crate::entities`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entities: Option<Entities>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Reading speed for [`StoryDetails::reading_minutes`].
+pub const WORDS_PER_MINUTE: usize = 230;
+
+/// Facts about a story's article, shown beside its headline.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StoryDetails {
+    /// Site the article is on, e.g. `theverge.com`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Words in the extracted article text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<usize>,
+    /// Estimated minutes to read the whole article
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_minutes: Option<u32>,
+    /// The article's share image, downloaded for thumbnails by
+    /// `prepare-briefing --images`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// ISO 639-1 code of the article's language, when it isn't English
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// The summary was written from an English translation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub translated: bool,
+}
+
+impl StoryDetails {
+    /// Details of a story that wasn't fetched: only its site.
+    pub fn for_url(url: &str) -> Self {
+        Self {
+            source: source_domain(url),
+            ..Default::default()
+        }
+    }
+
+    /// Details from a fetched article, with the site it was served from.
+    pub fn from_article(url: &str, content: &ArticleContent) -> Self {
+        let words = (content.word_count > 0).then_some(content.word_count);
+        Self {
+            source: source_domain(&content.final_url).or_else(|| source_domain(url)),
+            author: content.author.clone(),
+            words,
+            reading_minutes: words.map(|w| w.div_ceil(WORDS_PER_MINUTE) as u32),
+            image: content.image.clone(),
+            language: content
+                .language
+                .clone()
+                .filter(|l| !crate::language::is_english(Some(l))),
+            translated: content.translated,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The host of `url` without `www.`, e.g. `theverge.com`.
+pub fn source_domain(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|h| h.trim_start_matches("www.").to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Topic {
     pub title: String,
     pub stories: Vec<Story>,
+    /// "How these connect" paragraph for topics of several stories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connections: Option<String>,
+    /// Free text under the heading, kept as written. A topic with notes and
+    /// no stories is a section like "Leo's Picks" or an ad read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Why the clusterer grouped these stories, one line for the editor,
+    /// with `[clustering] rationale`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+}
+
+/// `[clustering]` section of config.toml: how big a topic may be, and
+/// whether the model explains its topics. Everything is off by default.
+/// The size limits are put to the model with the clustering prompt and
+/// enforced afterwards all the same.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClusterSettings {
+    /// Topics with fewer stories go into the `other_news` topic; 0 or 1
+    /// keeps every topic
+    pub min_topic_size: usize,
+    /// Topics with more stories are split into subtopics (one extra request
+    /// per topic, or in even parts without an API key); 0 for no limit
+    pub max_topic_size: usize,
+    /// Title of the topic the small ones are merged into
+    pub other_news: String,
+    /// Ask for a line on why each topic's stories belong together, shown
+    /// under the topic's heading
+    pub rationale: bool,
+}
+
+impl Default for ClusterSettings {
+    fn default() -> Self {
+        Self {
+            min_topic_size: 0,
+            max_topic_size: 0,
+            other_news: "In Other News".to_string(),
+            rationale: false,
+        }
+    }
+}
+
+impl ClusterSettings {
+    fn too_big(&self, topic: &Topic) -> bool {
+        self.max_topic_size > 0 && topic.stories.len() > self.max_topic_size
+    }
+
+    /// The limits and the rationale request, as instructions for the
+    /// clustering prompt.
+    fn instructions(&self) -> Option<String> {
+        let mut rules = Vec::new();
+        if self.min_topic_size > 1 {
+            rules.push(format!(
+                "Every topic needs at least {} articles; put articles that don't fit one in a topic titled \"{}\".",
+                self.min_topic_size, self.other_news
+            ));
+        }
+        if self.max_topic_size > 0 {
+            rules.push(format!(
+                "No topic may have more than {} articles; split bigger ones by subtopic.",
+                self.max_topic_size
+            ));
+        }
+        let mut instructions = Vec::new();
+        if !rules.is_empty() {
+            instructions.push(format!("Topic size: {}", rules.join(" ")));
+        }
+        if self.rationale {
+            instructions.push(
+                "Also give each topic a \"rationale\": one short line on why its articles belong together."
+                    .to_string(),
+            );
+        }
+        (!instructions.is_empty()).then(|| instructions.join("\n"))
+    }
+}
+
+/// Enforce `limits` on `topics`: split topics that are still too big into
+/// even parts, then merge the ones too small into the `other_news` topic,
+/// which goes last.
+pub fn apply_size_limits(topics: Vec<Topic>, limits: &ClusterSettings) -> Vec<Topic> {
+    let mut sized = Vec::new();
+    for topic in topics {
+        if !limits.too_big(&topic) {
+            sized.push(topic);
+            continue;
+        }
+        let count = topic.stories.len();
+        let parts = count.div_ceil(limits.max_topic_size);
+        let mut stories = topic.stories.into_iter();
+        for part in 1..=parts {
+            // The first parts take one more when it doesn't divide evenly
+            let size = count / parts + usize::from(part <= count % parts);
+            sized.push(Topic {
+                title: format!("{} ({}/{})", topic.title, part, parts),
+                stories: stories.by_ref().take(size).collect(),
+                connections: None,
+                notes: None,
+                rationale: topic.rationale.clone(),
+            });
+        }
+    }
+    if limits.min_topic_size <= 1 {
+        return sized;
+    }
+
+    let is_other = |topic: &Topic| topic.title.eq_ignore_ascii_case(&limits.other_news);
+    let mut other: Vec<Story> = Vec::new();
+    let mut kept = Vec::new();
+    for topic in sized {
+        if is_other(&topic) || topic.stories.len() < limits.min_topic_size {
+            other.extend(topic.stories);
+        } else {
+            kept.push(topic);
+        }
+    }
+    if !other.is_empty() {
+        kept.push(Topic {
+            title: limits.other_news.clone(),
+            stories: other,
+            connections: None,
+            notes: None,
+            rationale: None,
+        });
+    }
+    kept
 }
 
 #[derive(Deserialize)]
@@ -34,27 +261,58 @@ struct ClusteringResult {
 struct TopicCluster {
     title: String,
     article_indices: Vec<usize>,
+    #[serde(default)]
+    rationale: Option<String>,
 }
 
 pub struct TopicClusterer {
-    client: Client,
-    api_key: String,
+    llm: LlmClient,
+    prompt: Prompt,
+    split_prompt: Prompt,
+    limits: ClusterSettings,
+    progress: Progress,
 }
 
 impl TopicClusterer {
     pub fn new() -> Result<Self> {
-        // The API key lives in the env var named by BRIEFING_LLM_KEY_ENV
-        // (default ZAI_API_KEY), so a run can target a different backend —
-        // e.g. Anthropic via ANTHROPIC_API_KEY — without code changes.
-        let key_var = std::env::var("BRIEFING_LLM_KEY_ENV")
-            .unwrap_or_else(|_| "ZAI_API_KEY".to_string());
-        let api_key = std::env::var(&key_var)
-            .with_context(|| format!("{key_var} not set"))?;
-        let client = Client::builder()
-            .timeout(CLUSTER_TIMEOUT)
-            .build()
-            .context("Failed to build HTTP client")?;
-        Ok(TopicClusterer { client, api_key })
+        Ok(TopicClusterer {
+            llm: LlmClient::from_env(CLUSTER_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Cluster),
+            split_prompt: Prompt::builtin(PromptKind::Split),
+            limits: ClusterSettings::default(),
+            progress: crate::progress::console(),
+        })
+    }
+
+    /// Record token usage for clustering requests into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Use `prompt` for splitting topics over the size limit.
+    pub fn with_split_prompt(mut self, prompt: Prompt) -> Self {
+        self.split_prompt = prompt;
+        self
+    }
+
+    /// Keep topics within these sizes.
+    pub fn with_limits(mut self, limits: ClusterSettings) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Report clustering retries to `progress`.
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
     }
 
     pub async fn cluster_stories(&self, stories: Vec<Story>) -> Result<Vec<Topic>> {
@@ -66,9 +324,78 @@ impl TopicClusterer {
             return Ok(vec![Topic {
                 title: "News".to_string(),
                 stories,
+                connections: None,
+                notes: None,
+                rationale: None,
             }]);
         }
 
+        let stage = Stage::Cluster;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: stories.len(),
+        });
+        // Clustering is one request, so every story waits on it
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            Arc::new(AtomicUsize::new(stories.len())),
+        );
+        let input = stories.clone();
+        let topics = match self.cluster_with_retries(stories).await {
+            Ok(topics) => Ok(self.split_big_topics(topics).await),
+            Err(e) => Err(e),
+        };
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+        topics.map(|topics| each_story_once(&input, apply_size_limits(topics, &self.limits)))
+    }
+
+    /// Ask the model to split each topic over the size limit by subtopic.
+    /// A topic it can't split is left for [`apply_size_limits`].
+    async fn split_big_topics(&self, topics: Vec<Topic>) -> Vec<Topic> {
+        let mut split = Vec::with_capacity(topics.len());
+        for topic in topics {
+            if !self.limits.too_big(&topic) {
+                split.push(topic);
+                continue;
+            }
+            match self.try_split_with_ai(&topic).await {
+                Ok(subtopics) => split.extend(subtopics),
+                Err(e) => {
+                    tracing::warn!("Could not split \"{}\": {:#}", topic.title, e);
+                    split.push(topic);
+                }
+            }
+        }
+        split
+    }
+
+    async fn try_split_with_ai(&self, topic: &Topic) -> Result<Vec<Topic>> {
+        let max = self.limits.max_topic_size.to_string();
+        let articles = articles_list(&topic.stories);
+        let last_index = (topic.stories.len() - 1).to_string();
+        let prompt = self.split_prompt.render(&[
+            ("topic", topic.title.as_str()),
+            ("articles", articles.as_str()),
+            ("last_index", last_index.as_str()),
+            ("max", max.as_str()),
+        ]);
+        let response = self.llm.complete("split", &prompt, 1024).await?;
+        let (mut subtopics, repairs) = parse_clusters(response.as_str(), &topic.stories)?;
+        repairs.log(&format!("Splitting \"{}\"", topic.title));
+        let Some(first) = subtopics.first_mut() else {
+            anyhow::bail!("No subtopics in the response");
+        };
+        // Left out by the model: keep them with the first subtopic
+        first
+            .stories
+            .extend(repairs.unassigned.iter().map(|&i| topic.stories[i].clone()));
+        Ok(subtopics)
+    }
+
+    async fn cluster_with_retries(&self, stories: Vec<Story>) -> Result<Vec<Topic>> {
         // Retry logic with exponential backoff for rate limits
         for attempt in 0..5 {
             match self.try_cluster_with_ai(&stories).await {
@@ -88,7 +415,7 @@ impl TopicClusterer {
                         error_msg.contains("rate_limit") || error_msg.contains("429");
 
                     if attempt == 4 {
-                        eprintln!(
+                        tracing::warn!(
                             "Clustering failed after {} attempts: {}, using chronological fallback",
                             attempt + 1,
                             e
@@ -96,23 +423,29 @@ impl TopicClusterer {
                         return Ok(self.fallback_chronological(stories));
                     }
 
-                    // Longer backoff for rate limits
-                    let backoff = if is_rate_limit {
-                        std::time::Duration::from_secs(15 * (attempt + 1) as u64)
-                    } else {
-                        std::time::Duration::from_millis(1000 * (2_u64.pow(attempt as u32)))
-                    };
+                    let backoff = self.llm.retry_delay(attempt);
 
-                    if is_rate_limit {
-                        eprintln!("Rate limit hit during clustering, waiting {:?} before retry {} of 5...", backoff, attempt + 2);
+                    let event = if is_rate_limit {
+                        ProgressEvent::RateLimited {
+                            stage: Stage::Cluster,
+                            attempt: attempt + 1,
+                            wait_secs: backoff.as_secs(),
+                        }
                     } else {
-                        eprintln!(
-                            "Clustering error (attempt {} of 5): {}, retrying after {:?}...",
-                            attempt + 1,
-                            e,
-                            backoff
-                        );
-                    }
+                        ProgressEvent::Retrying {
+                            stage: Stage::Cluster,
+                            attempt: attempt + 1,
+                            wait_secs: backoff.as_secs(),
+                            error: error_msg,
+                        }
+                    };
+                    tracing::debug!(
+                        attempt = attempt + 1,
+                        wait_secs = backoff.as_secs(),
+                        "clustering request failed: {}",
+                        e
+                    );
+                    self.progress.report(&event);
 
                     tokio::time::sleep(backoff).await;
                 }
@@ -124,154 +457,34 @@ impl TopicClusterer {
     }
 
     async fn try_cluster_with_ai(&self, stories: &[Story]) -> Result<Vec<Topic>> {
-        let articles_text = stories
-            .iter()
-            .enumerate()
-            .map(|(idx, story)| {
-                let first_point = match &story.summary {
-                    Summary::Editorial { lede, .. } => lede.as_str(),
-                    Summary::Product { the_product, .. } => the_product.as_str(),
-                    _ => "",
-                };
-                format!("{}: {} - {}", idx, story.title, first_point)
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let prompt = format!(
-            r#"You are analyzing a list of news articles for a tech podcast briefing.
-
-GROUPING RULES (in priority order):
-1. PRIMARY: If an article is primarily about a specific company (Google, Apple, Microsoft, Tesla, Meta, Amazon, etc.), use the company name as the topic title
-2. Group all articles about the same company together under that company's name
-3. For articles not primarily about a single company, use a descriptive topic (e.g., "AI Development", "Privacy & Security", "Industry News")
-4. Use concise topic names (1-3 words preferred, company names exactly as they are commonly known)
-
-Articles:
-{}
-
-Format your response as JSON:
-{{
-  "topics": [
-    {{
-      "title": "Apple",
-      "article_indices": [0, 3, 7]
-    }},
-    {{
-      "title": "Google",
-      "article_indices": [1, 5]
-    }},
-    {{
-      "title": "AI Development",
-      "article_indices": [2, 4, 6]
-    }}
-  ]
-}}
-
-Important: Every article index from 0 to {} must appear in exactly one topic."#,
-            articles_text,
-            stories.len() - 1
-        );
-
-        // Endpoint/model are env-overridable for testing alternate backends
-        // (e.g. a local llama.cpp /v1/messages server). Defaults to z.ai GLM.
-        let model = std::env::var("BRIEFING_LLM_MODEL").unwrap_or_else(|_| GLM_MODEL.to_string());
-        let url = std::env::var("BRIEFING_LLM_URL").unwrap_or_else(|_| ZAI_API_URL.to_string());
-
-        let body = json!({
-            "model": model,
-            "max_tokens": 1024,
-            "messages": [{"role": "user", "content": prompt}]
-        });
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", &self.api_key))
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .context("Clustering API request failed")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            if status == 401 {
-                anyhow::bail!("authentication_error: {}", text);
-            }
-            anyhow::bail!("API error {}: {}", status, text);
+        let articles_text = articles_list(stories);
+        let last_index = (stories.len() - 1).to_string();
+        let mut prompt = self.prompt.render(&[
+            ("articles", articles_text.as_str()),
+            ("last_index", last_index.as_str()),
+        ]);
+        if let Some(instructions) = self.limits.instructions() {
+            prompt.push_str("\n\n");
+            prompt.push_str(&instructions);
         }
 
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .context("Failed to parse clustering API response")?;
-
-        let response_text = data["content"][0]["text"]
-            .as_str()
-            .context("No text in clustering API response")?;
-
-        let json_text = if let Some(start) = response_text.find('{') {
-            if let Some(end) = response_text.rfind('}') {
-                &response_text[start..=end]
-            } else {
-                response_text
-            }
-        } else {
-            response_text
-        };
-
-        let clustering_result: ClusteringResult =
-            serde_json::from_str(json_text).context("Failed to parse clustering JSON response")?;
-
-        let mut topics = Vec::new();
-        let mut assigned = vec![false; stories.len()];
-        for cluster in clustering_result.topics {
-            let mut topic_stories = Vec::new();
-            for &idx in &cluster.article_indices {
-                // Skip out-of-range indices and indices the model listed twice
-                if idx < stories.len() && !assigned[idx] {
-                    assigned[idx] = true;
-                    topic_stories.push(stories[idx].clone());
-                }
-            }
-            // Sort stories oldest-first so the org file starts in chronological order
-            topic_stories.sort_by(|a, b| {
-                let date_a = parse_date_for_sorting(&a.created);
-                let date_b = parse_date_for_sorting(&b.created);
-                match (date_a, date_b) {
-                    (Some(a), Some(b)) => a.cmp(&b),
-                    (Some(_), None) => std::cmp::Ordering::Less,
-                    (None, Some(_)) => std::cmp::Ordering::Greater,
-                    (None, None) => std::cmp::Ordering::Equal,
-                }
-            });
-            if !topic_stories.is_empty() {
-                topics.push(Topic {
-                    title: cluster.title,
-                    stories: topic_stories,
-                });
-            }
-        }
+        let response = self.llm.complete("cluster", &prompt, 1024).await?;
+        let (mut topics, repairs) = parse_clusters(response.as_str(), stories)?;
+        repairs.log("Clustering");
 
         // The model is told to assign every index, but it can omit some; don't
         // silently drop those stories from the briefing.
-        let unassigned: Vec<Story> = assigned
-            .iter()
-            .enumerate()
-            .filter(|(_, done)| !**done)
-            .map(|(idx, _)| stories[idx].clone())
-            .collect();
-        if !unassigned.is_empty() {
-            eprintln!(
-                "Clustering left {} story(ies) unassigned, adding them to \"More News\"",
-                unassigned.len()
-            );
+        if !repairs.unassigned.is_empty() {
             topics.push(Topic {
-                title: "More News".to_string(),
-                stories: unassigned,
+                title: CATCH_ALL.to_string(),
+                stories: repairs
+                    .unassigned
+                    .iter()
+                    .map(|&i| stories[i].clone())
+                    .collect(),
+                connections: None,
+                notes: None,
+                rationale: None,
             });
         }
 
@@ -286,53 +499,279 @@ Important: Every article index from 0 to {} must appear in exactly one topic."#,
         vec![Topic {
             title: "News Stories".to_string(),
             stories,
+            connections: None,
+            notes: None,
+            rationale: None,
         }]
     }
 }
 
-/// Parse a date string for sorting. Handles RFC 3339 and common date-only formats.
-fn parse_date_for_sorting(date_str: &str) -> Option<DateTime<FixedOffset>> {
-    if date_str.is_empty() {
-        return None;
-    }
-    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-        return Some(dt);
-    }
-    for fmt in &[
-        "%a, %e %b %Y",
-        "%a, %d %b %Y",
-        "%e %b %Y",
-        "%d %b %Y",
-        "%Y-%m-%d",
-    ] {
-        if let Ok(nd) = NaiveDate::parse_from_str(date_str.trim(), fmt) {
-            return nd
-                .and_hms_opt(0, 0, 0)
-                .map(|ndt| ndt.and_utc().fixed_offset());
+/// One line per story for the clustering prompts: index, headline, the
+/// summary's opening and the story's entities in brackets.
+fn articles_list(stories: &[Story]) -> String {
+    stories
+        .iter()
+        .enumerate()
+        .map(|(idx, story)| {
+            let first_point = match &story.summary {
+                Summary::Editorial { lede, .. } => lede.as_str(),
+                Summary::Product { the_product, .. } => the_product.as_str(),
+                _ => "",
+            };
+            let entities = story
+                .entities
+                .as_ref()
+                .map(|e| e.names().collect::<Vec<_>>())
+                .filter(|names| !names.is_empty())
+                .map(|names| format!(" [{}]", names.join(", ")))
+                .unwrap_or_default();
+            format!("{}: {} - {}{}", idx, story.title, first_point, entities)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Title of the topic for stories the model didn't assign.
+const CATCH_ALL: &str = "More News";
+
+/// What [`parse_clusters`] fixed in a response, by article index.
+#[derive(Debug, Default, PartialEq)]
+struct Repairs {
+    /// In no topic
+    unassigned: Vec<usize>,
+    /// Listed again after their first topic, and left there
+    duplicates: Vec<usize>,
+    /// Past the last article
+    out_of_range: Vec<usize>,
+}
+
+impl Repairs {
+    fn describe(&self) -> Option<String> {
+        let mut fixes = Vec::new();
+        if !self.duplicates.is_empty() {
+            fixes.push(format!(
+                "kept {} story(ies) listed twice in their first topic only ({})",
+                self.duplicates.len(),
+                indices(&self.duplicates)
+            ));
+        }
+        if !self.out_of_range.is_empty() {
+            fixes.push(format!(
+                "ignored {} index(es) past the last article ({})",
+                self.out_of_range.len(),
+                indices(&self.out_of_range)
+            ));
+        }
+        if !self.unassigned.is_empty() {
+            fixes.push(format!(
+                "left {} story(ies) unassigned ({}), added to \"{}\"",
+                self.unassigned.len(),
+                indices(&self.unassigned),
+                CATCH_ALL
+            ));
+        }
+        (!fixes.is_empty()).then(|| fixes.join("; "))
+    }
+
+    fn log(&self, what: &str) {
+        if let Some(fixes) = self.describe() {
+            tracing::warn!("{} response repaired: {}", what, fixes);
         }
     }
-    None
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn indices(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    // ==================== Story / Topic Construction ====================
-
-    fn make_story(title: &str, url: &str, created: &str) -> Story {
-        Story {
-            title: title.to_string(),
-            url: url.to_string(),
-            created: created.to_string(),
-            summary: Summary::Editorial {
-                lede: "Test lede".to_string(),
-                nutgraf: "Test nutgraf".to_string(),
-                quote: None,
-            },
+/// `topics` with every story of `input` exactly once: a story that turns up
+/// again is dropped, one that went missing is added to the catch-all topic.
+/// The steps after clustering shouldn't do either, but the briefing must
+/// not lose a story if one does.
+fn each_story_once(input: &[Story], mut topics: Vec<Topic>) -> Vec<Topic> {
+    let mut expected: HashMap<&str, usize> = HashMap::new();
+    for story in input {
+        *expected.entry(story.url.as_str()).or_default() += 1;
+    }
+    let mut extra = 0;
+    for topic in &mut topics {
+        topic
+            .stories
+            .retain(|story| match expected.get_mut(story.url.as_str()) {
+                Some(left) if *left > 0 => {
+                    *left -= 1;
+                    true
+                }
+                _ => {
+                    extra += 1;
+                    false
+                }
+            });
+    }
+    topics.retain(|topic| !topic.stories.is_empty());
+
+    let mut missing = Vec::new();
+    for story in input.iter().rev() {
+        if let Some(left) = expected
+            .get_mut(story.url.as_str())
+            .filter(|left| **left > 0)
+        {
+            *left -= 1;
+            missing.push(story.clone());
+        }
+    }
+    missing.reverse();
+    if extra > 0 || !missing.is_empty() {
+        tracing::warn!(
+            "Topics repaired: dropped {} repeated story(ies), added {} missing one(s) to \"{}\"",
+            extra,
+            missing.len(),
+            CATCH_ALL
+        );
+    }
+    if !missing.is_empty() {
+        match topics.iter_mut().find(|t| t.title == CATCH_ALL) {
+            Some(topic) => topic.stories.extend(missing),
+            None => topics.push(Topic {
+                title: CATCH_ALL.to_string(),
+                stories: missing,
+                connections: None,
+                notes: None,
+                rationale: None,
+            }),
+        }
+    }
+    topics
+}
+
+/// The topics in a clustering response, and what had to be fixed in it.
+fn parse_clusters(response_text: &str, stories: &[Story]) -> Result<(Vec<Topic>, Repairs)> {
+    let json_text = if let Some(start) = response_text.find('{') {
+        if let Some(end) = response_text.rfind('}') {
+            &response_text[start..=end]
+        } else {
+            response_text
+        }
+    } else {
+        response_text
+    };
+
+    let clustering_result: ClusteringResult =
+        serde_json::from_str(json_text).context("Failed to parse clustering JSON response")?;
+
+    let mut topics = Vec::new();
+    let mut repairs = Repairs::default();
+    let mut assigned = vec![false; stories.len()];
+    for cluster in clustering_result.topics {
+        let mut topic_stories = Vec::new();
+        for &idx in &cluster.article_indices {
+            if idx >= stories.len() {
+                repairs.out_of_range.push(idx);
+            } else if assigned[idx] {
+                repairs.duplicates.push(idx);
+            } else {
+                assigned[idx] = true;
+                topic_stories.push(stories[idx].clone());
+            }
+        }
+        // Sort stories oldest-first so the org file starts in chronological order
+        topic_stories.sort_by(|a, b| {
+            let date_a = parse_date_for_sorting(&a.created);
+            let date_b = parse_date_for_sorting(&b.created);
+            match (date_a, date_b) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        });
+        if !topic_stories.is_empty() {
+            topics.push(Topic {
+                title: cluster.title,
+                stories: topic_stories,
+                connections: None,
+                notes: None,
+                rationale: cluster
+                    .rationale
+                    .map(|r| r.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .filter(|r| !r.is_empty()),
+            });
+        }
+    }
+
+    repairs.unassigned = assigned
+        .iter()
+        .enumerate()
+        .filter(|(_, done)| !**done)
+        .map(|(idx, _)| idx)
+        .collect();
+    Ok((topics, repairs))
+}
+
+/// Group stories by publishing site, for runs without an LLM. Sites with a
+/// single story are collected under "Other Sources"; larger groups come
+/// first.
+pub fn cluster_by_domain(stories: Vec<Story>) -> Vec<Topic> {
+    let mut groups: Vec<(String, Vec<Story>)> = Vec::new();
+    for story in stories {
+        let domain = source_domain(&story.url).unwrap_or_default();
+        match groups.iter_mut().find(|(d, _)| *d == domain) {
+            Some((_, group)) => group.push(story),
+            None => groups.push((domain, vec![story])),
         }
     }
 
+    let (mut topics, singles): (Vec<_>, Vec<_>) = groups
+        .into_iter()
+        .partition(|(domain, group)| group.len() > 1 && !domain.is_empty());
+    topics.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+    let mut topics: Vec<Topic> = topics
+        .into_iter()
+        .map(|(title, stories)| Topic {
+            title,
+            stories,
+            connections: None,
+            notes: None,
+            rationale: None,
+        })
+        .collect();
+    let other: Vec<Story> = singles.into_iter().flat_map(|(_, group)| group).collect();
+    if !other.is_empty() {
+        topics.push(Topic {
+            title: "Other Sources".to_string(),
+            stories: other,
+            connections: None,
+            notes: None,
+            rationale: None,
+        });
+    }
+    topics
+}
+
+/// Move starred stories to the top of each topic, keeping the order within
+/// the starred and the other stories.
+pub fn starred_first(topics: &mut [Topic]) {
+    for topic in topics {
+        topic.stories.sort_by_key(|story| !story.starred);
+    }
+}
+
+/// Parse a date string for sorting, in any format [`dates::parse`](crate::dates::parse)
+/// reads. Date-only values sort as midnight UTC.
+fn parse_date_for_sorting(date_str: &str) -> Option<DateTime<FixedOffset>> {
+    crate::dates::parse(date_str).map(|date| date.instant())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
     // ==================== parse_date_for_sorting ====================
 
     #[test]
@@ -391,17 +830,313 @@ mod tests {
 
     fn make_clusterer() -> TopicClusterer {
         TopicClusterer {
-            client: reqwest::Client::new(),
-            api_key: "test".to_string(),
+            llm: LlmClient::new(
+                "test".to_string(),
+                crate::llm::DEFAULT_MODEL.to_string(),
+                crate::llm::DEFAULT_API_URL.to_string(),
+                CLUSTER_TIMEOUT,
+            )
+            .unwrap(),
+            prompt: Prompt::builtin(PromptKind::Cluster),
+            split_prompt: Prompt::builtin(PromptKind::Split),
+            limits: ClusterSettings::default(),
+            progress: std::sync::Arc::new(crate::progress::NoProgress),
         }
     }
 
+    #[test]
+    fn test_story_details() {
+        let content = ArticleContent {
+            text: String::new(),
+            published_date: None,
+            final_url: "https://www.theverge.com/2026/2/1/story".to_string(),
+            author: Some("Jane Doe".to_string()),
+            word_count: 1200,
+            image: Some("https://cdn.theverge.com/a.jpg".to_string()),
+            language: None,
+            translated: false,
+            title: None,
+        };
+        let details = StoryDetails::from_article("https://t.co/abc", &content);
+        assert_eq!(details.source.as_deref(), Some("theverge.com"));
+        assert_eq!(details.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(details.words, Some(1200));
+        assert_eq!(details.reading_minutes, Some(6));
+        assert_eq!(
+            details.image.as_deref(),
+            Some("https://cdn.theverge.com/a.jpg")
+        );
+
+        let details = StoryDetails::for_url("https://example.com/a");
+        assert_eq!(details.source.as_deref(), Some("example.com"));
+        assert_eq!(details.words, None);
+        assert!(StoryDetails::for_url("not a url").is_empty());
+    }
+
+    #[test]
+    fn test_cluster_by_domain() {
+        let stories = vec![
+            story("A")
+                .with_url("https://www.theverge.com/a")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+            story("B")
+                .with_url("https://arstechnica.com/b")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+            story("C")
+                .with_url("https://theverge.com/c")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+            story("D")
+                .with_url("not a url")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+        ];
+        let topics = cluster_by_domain(stories);
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].title, "theverge.com");
+        assert_eq!(topics[0].stories.len(), 2);
+        assert_eq!(topics[1].title, "Other Sources");
+        assert_eq!(topics[1].stories[0].title, "B");
+        assert!(cluster_by_domain(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_starred_first() {
+        let mut stories: Vec<Story> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|t| {
+                story(t)
+                    .with_url("https://example.com")
+                    .with_lede("Test lede", "Test nutgraf")
+                    .build()
+            })
+            .collect();
+        stories[1].starred = true;
+        stories[3].starred = true;
+        let mut topics = vec![Topic {
+            title: "Apple".to_string(),
+            stories,
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+
+        starred_first(&mut topics);
+        let titles: Vec<&str> = topics[0].stories.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["B", "D", "A", "C"]);
+    }
+
+    fn sized_topics(sizes: &[(&str, usize)]) -> Vec<Topic> {
+        sizes
+            .iter()
+            .map(|(title, size)| Topic {
+                title: title.to_string(),
+                stories: (0..*size)
+                    .map(|i| {
+                        story(&format!("{} {}", title, i))
+                            .with_url("https://example.com")
+                            .with_lede("Test lede", "Test nutgraf")
+                            .build()
+                    })
+                    .collect(),
+                connections: None,
+                notes: None,
+                rationale: None,
+            })
+            .collect()
+    }
+
+    fn sizes(topics: &[Topic]) -> Vec<(&str, usize)> {
+        topics
+            .iter()
+            .map(|t| (t.title.as_str(), t.stories.len()))
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_size_limits() {
+        let limits = ClusterSettings {
+            min_topic_size: 2,
+            max_topic_size: 3,
+            ..Default::default()
+        };
+        let topics = sized_topics(&[
+            ("In Other News", 1),
+            ("Apple", 7),
+            ("Google", 1),
+            ("AI", 2),
+            ("Space", 1),
+        ]);
+        let topics = apply_size_limits(topics, &limits);
+        assert_eq!(
+            sizes(&topics),
+            [
+                ("Apple (1/3)", 3),
+                ("Apple (2/3)", 2),
+                ("Apple (3/3)", 2),
+                ("AI", 2),
+                ("In Other News", 3),
+            ]
+        );
+        assert_eq!(topics[2].stories[1].title, "Apple 6");
+        assert_eq!(topics[4].stories[0].title, "In Other News 0");
+
+        let unlimited = sized_topics(&[("Apple", 7), ("Google", 1)]);
+        assert_eq!(
+            sizes(&apply_size_limits(unlimited, &ClusterSettings::default())),
+            [("Apple", 7), ("Google", 1)]
+        );
+    }
+
+    #[test]
+    fn test_size_instructions() {
+        assert_eq!(ClusterSettings::default().instructions(), None);
+        let limits = ClusterSettings {
+            min_topic_size: 2,
+            max_topic_size: 8,
+            ..Default::default()
+        };
+        let instructions = limits.instructions().unwrap();
+        assert!(instructions.contains("at least 2 articles"));
+        assert!(instructions.contains("titled \"In Other News\""));
+        assert!(instructions.contains("more than 8 articles"));
+        assert!(!instructions.contains("rationale"));
+
+        let rationale = ClusterSettings {
+            rationale: true,
+            ..Default::default()
+        };
+        assert!(rationale.instructions().unwrap().contains("\"rationale\""));
+    }
+
+    #[test]
+    fn test_articles_list_with_entities() {
+        let mut tagged = story("Price cut")
+            .with_url("https://example.com/a")
+            .with_lede("Test lede", "Test nutgraf")
+            .build();
+        tagged.entities = Some(Entities {
+            companies: vec!["Apple".to_string()],
+            products: vec!["Vision Pro".to_string()],
+            people: Vec::new(),
+        });
+        let stories = [
+            tagged,
+            story("Other")
+                .with_url("https://example.com/b")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+        ];
+        assert_eq!(
+            articles_list(&stories),
+            "0: Price cut - Test lede [Apple, Vision Pro]\n1: Other - Test lede"
+        );
+    }
+
+    #[test]
+    fn test_parse_clusters_rationale() {
+        let stories = vec![
+            story("A")
+                .with_url("https://example.com/a")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+            story("B")
+                .with_url("https://example.com/b")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+        ];
+        let response = r#"{"topics": [
+            {"title": "Apple", "article_indices": [0], "rationale": "  Both about\n Apple "},
+            {"title": "Google", "article_indices": [1], "rationale": " "}
+        ]}"#;
+        let (topics, _) = parse_clusters(response, &stories).unwrap();
+        assert_eq!(topics[0].rationale.as_deref(), Some("Both about Apple"));
+        assert_eq!(topics[1].rationale, None);
+    }
+
+    #[test]
+    fn test_parse_clusters_repairs_indices() {
+        let stories: Vec<Story> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|t| {
+                story(t)
+                    .with_url(&format!("https://example.com/{}", t))
+                    .with_lede("Test lede", "Test nutgraf")
+                    .build()
+            })
+            .collect();
+        let response = r#"Here you go:
+            {"topics": [
+                {"title": "Apple", "article_indices": [0, 1, 9]},
+                {"title": "Google", "article_indices": [1, 2]}
+            ]}"#;
+        let (topics, repairs) = parse_clusters(response, &stories).unwrap();
+        assert_eq!(sizes(&topics), [("Apple", 2), ("Google", 1)]);
+        assert_eq!(
+            repairs,
+            Repairs {
+                unassigned: vec![3],
+                duplicates: vec![1],
+                out_of_range: vec![9],
+            }
+        );
+        assert_eq!(
+            repairs.describe().unwrap(),
+            "kept 1 story(ies) listed twice in their first topic only (1); \
+             ignored 1 index(es) past the last article (9); \
+             left 1 story(ies) unassigned (3), added to \"More News\""
+        );
+        assert!(Repairs::default().describe().is_none());
+    }
+
+    #[test]
+    fn test_each_story_once() {
+        let input: Vec<Story> = ["A", "B", "C"]
+            .iter()
+            .map(|t| {
+                story(t)
+                    .with_url(&format!("https://example.com/{}", t))
+                    .with_lede("Test lede", "Test nutgraf")
+                    .build()
+            })
+            .collect();
+        let topics = vec![
+            Topic {
+                title: "Apple".to_string(),
+                stories: vec![input[0].clone(), input[0].clone()],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Repeats".to_string(),
+                stories: vec![input[0].clone()],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ];
+        let topics = each_story_once(&input, topics);
+        assert_eq!(sizes(&topics), [("Apple", 1), ("More News", 2)]);
+        assert_eq!(topics[1].stories[0].title, "B");
+    }
+
     #[test]
     fn test_topic_clusterer_fallback_chronological() {
         let clusterer = make_clusterer();
         let stories = vec![
-            make_story("A", "https://a.com", "2026-01-01"),
-            make_story("B", "https://b.com", "2026-01-02"),
+            story("A")
+                .with_url("https://a.com")
+                .with_created("2026-01-01")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
+            story("B")
+                .with_url("https://b.com")
+                .with_created("2026-01-02")
+                .with_lede("Test lede", "Test nutgraf")
+                .build(),
         ];
 
         let topics = clusterer.fallback_chronological(stories.clone());
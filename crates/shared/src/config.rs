@@ -1,5 +1,22 @@
 use anyhow::{Context, Result};
 use std::env;
+use std::path::PathBuf;
+
+/// Directory holding user configuration and customized assets
+/// (`~/.config/podcast-briefing`).
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("podcast-briefing")
+}
+
+/// Directory holding generated data such as story files and logs
+/// (`~/.local/share/podcast-briefing`).
+pub fn data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("podcast-briefing")
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -0,0 +1,237 @@
+//! "How these connect" paragraphs (`[briefing] connections = true`).
+//!
+//! For each topic with at least [`MIN_STORIES`] stories, the model gets the
+//! stories' titles and ledes and writes a short paragraph on the thread
+//! running through them. The paragraph opens the topic in the org file and
+//! the HTML briefing, so the host has a way into the topic rather than a
+//! list of separate items. It can be edited or deleted in the org file like
+//! any summary.
+
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clustering::Topic;
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind};
+use crate::summarizer::Summary;
+use crate::usage::UsageTracker;
+
+/// Topics with fewer stories don't get a paragraph.
+pub const MIN_STORIES: usize = 3;
+
+/// Longer replies are treated as the model ignoring the brief.
+const MAX_WORDS: usize = 120;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Attempts per topic before leaving it without a paragraph.
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct ConnectionWriter {
+    llm: LlmClient,
+    prompt: Prompt,
+    progress: Progress,
+}
+
+impl ConnectionWriter {
+    pub fn new() -> Result<Self> {
+        Ok(ConnectionWriter {
+            llm: LlmClient::from_env(CONNECT_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Connect),
+            progress: crate::progress::console(),
+        })
+    }
+
+    /// Record token usage for these requests into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Fill in `connections` for every topic with enough stories that
+    /// doesn't have a paragraph yet. Returns how many were written.
+    pub async fn add_connections(&self, topics: &mut [Topic]) -> usize {
+        let wanted: Vec<usize> = (0..topics.len())
+            .filter(|&i| topics[i].connections.is_none() && topics[i].stories.len() >= MIN_STORIES)
+            .collect();
+        if wanted.is_empty() {
+            return 0;
+        }
+
+        let stage = Stage::Connect;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: wanted.len(),
+        });
+        let queued = Arc::new(AtomicUsize::new(wanted.len()));
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            queued.clone(),
+        );
+        let paragraphs: Vec<(usize, Option<String>)> = stream::iter(wanted)
+            .map(|i| {
+                let topic = &topics[i];
+                let queued = queued.clone();
+                async move {
+                    let paragraph = self.connect(topic).await;
+                    queued.fetch_sub(1, Ordering::Relaxed);
+                    (i, paragraph)
+                }
+            })
+            .buffer_unordered(crate::rate_limit::MAX_CONCURRENCY)
+            .collect()
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+
+        let mut written = 0;
+        for (i, paragraph) in paragraphs {
+            if paragraph.is_some() {
+                written += 1;
+            }
+            topics[i].connections = paragraph;
+        }
+        written
+    }
+
+    async fn connect(&self, topic: &Topic) -> Option<String> {
+        let prompt = self.prompt.render(&[
+            ("topic", topic.title.as_str()),
+            ("stories", request_text(topic).as_str()),
+        ]);
+        for attempt in 0..MAX_ATTEMPTS {
+            match self.llm.complete("connect", &prompt, 512).await {
+                Ok(reply) => {
+                    let paragraph = clean(&reply);
+                    if paragraph.is_none() {
+                        tracing::warn!(
+                            "Unusable connections paragraph for \"{}\": {}",
+                            topic.title,
+                            reply.trim()
+                        );
+                    }
+                    return paragraph;
+                }
+                Err(e) if attempt + 1 < MAX_ATTEMPTS => {
+                    let backoff = self.llm.retry_delay(attempt);
+                    self.progress.report(&ProgressEvent::Retrying {
+                        stage: Stage::Connect,
+                        attempt: attempt + 1,
+                        wait_secs: backoff.as_secs(),
+                        error: e.to_string(),
+                    });
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    tracing::warn!("No connections paragraph for \"{}\": {}", topic.title, e);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// One `- Title: lede` line per story; stories without a summary get the
+/// title alone.
+fn request_text(topic: &Topic) -> String {
+    topic
+        .stories
+        .iter()
+        .map(|story| {
+            let lede = match &story.summary {
+                Summary::Editorial { lede, .. } => lede.as_str(),
+                Summary::Product { the_product, .. } => the_product.as_str(),
+                Summary::Insufficient | Summary::Failed(_) => "",
+            };
+            if lede.is_empty() {
+                format!("- {}", story.title)
+            } else {
+                format!("- {}: {}", story.title, lede)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The reply as one line, without a label or wrapping quotes, or `None` if
+/// it is empty or far too long.
+fn clean(reply: &str) -> Option<String> {
+    let text = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = text.trim_start_matches(['*', '#']).trim();
+    let text = match text.split_once(':') {
+        Some((label, rest)) if label.to_lowercase().contains("how these connect") => rest,
+        _ => text,
+    };
+    let text = text
+        .trim_matches(|c: char| c == '*' || c.is_whitespace())
+        .trim_matches('"')
+        .trim();
+    let words = text.split_whitespace().count();
+    (words > 0 && words <= MAX_WORDS).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    #[test]
+    fn test_request_text() {
+        let topic = Topic {
+            title: "AI".to_string(),
+            stories: vec![
+                story("OpenAI raises again")
+                    .with_summary(Summary::Editorial {
+                        lede: "OpenAI raised $40B.".to_string(),
+                        nutgraf: "It matters.".to_string(),
+                        quotes: Vec::new(),
+                        reaction: None,
+                        by_the_numbers: None,
+                    })
+                    .build(),
+                story("Nvidia earnings")
+                    .with_summary(Summary::Insufficient)
+                    .build(),
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        };
+        assert_eq!(
+            request_text(&topic),
+            "- OpenAI raises again: OpenAI raised $40B.\n- Nvidia earnings"
+        );
+    }
+
+    #[test]
+    fn test_clean_reply() {
+        assert_eq!(
+            clean("**How these connect:** \"Everyone is\nbuying GPUs.\"\n").as_deref(),
+            Some("Everyone is buying GPUs.")
+        );
+        assert_eq!(
+            clean("The money: it flows one way.").as_deref(),
+            Some("The money: it flows one way.")
+        );
+        assert_eq!(clean("  \n"), None);
+        assert_eq!(clean(&"word ".repeat(MAX_WORDS + 1)), None);
+    }
+}
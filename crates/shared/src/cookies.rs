@@ -10,17 +10,17 @@ pub fn load_browser_cookies() -> Result<CookieStore> {
     if let Some(firefox_path) = find_firefox_cookies() {
         match load_firefox_cookies_from_db(&firefox_path, &mut cookie_store) {
             Ok(count) if count > 0 => {
-                eprintln!("✓ Loaded {} cookies from Firefox", count);
+                tracing::info!("✓ Loaded {} cookies from Firefox", count);
             }
             Ok(_) => {
-                eprintln!("  Note: Found Firefox cookies but loaded 0");
+                tracing::info!("Note: Found Firefox cookies but loaded 0");
             }
             Err(e) => {
-                eprintln!("  Warning: Could not load Firefox cookies: {}", e);
+                tracing::warn!("Could not load Firefox cookies: {}", e);
             }
         }
     } else {
-        eprintln!("  Note: No Firefox cookies found (paywalled sites may not work)");
+        tracing::info!("Note: No Firefox cookies found (paywalled sites may not work)");
     }
 
     Ok(cookie_store)
@@ -92,24 +92,40 @@ fn find_firefox_cookies() -> Option<PathBuf> {
     None
 }
 
-/// Open a SQLite database read-only and immutable.
+/// Open the cookie database in place, read-only, without copying it.
 ///
-/// `immutable=1` tells SQLite to assume the file cannot change, so it reads a
-/// database that is locked by a running browser (WAL mode) without taking any
-/// lock — and without us copying the entire cookie jar to a predictable, shared
-/// /tmp path (which could leak or persist on a crash).
+/// A plain read-only open sees cookies the browser has only written to the
+/// `-wal` file so far. If a running browser's lock makes that fail, the
+/// database is opened with `immutable=1` instead: SQLite then takes no lock
+/// and ignores the WAL, so the newest cookies may be missing. Either way the
+/// cookie jar is never copied to a temp file, where it could leak or be left
+/// behind after a crash.
 fn open_cookie_db(db_path: &Path) -> Result<Connection> {
-    use rusqlite::OpenFlags;
-
     let uri = Url::from_file_path(db_path)
-        .map(|u| format!("{u}?immutable=1"))
         .map_err(|_| anyhow::anyhow!("cookie database path is not absolute: {db_path:?}"))?;
 
-    Connection::open_with_flags(
-        &uri,
+    match open_read_only(&format!("{uri}?mode=ro")) {
+        Ok(conn) => Ok(conn),
+        Err(e) => {
+            tracing::debug!("cookie database is locked ({}), reading it immutable", e);
+            open_read_only(&format!("{uri}?immutable=1"))
+                .context("Failed to open cookies database read-only")
+        }
+    }
+}
+
+fn open_read_only(uri: &str) -> rusqlite::Result<Connection> {
+    use rusqlite::OpenFlags;
+
+    let conn = Connection::open_with_flags(
+        uri,
         OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
-    )
-    .context("Failed to open cookies database read-only")
+    )?;
+    // Don't wait on the browser's lock; opening is lazy, so touch the schema
+    // to find out now
+    conn.busy_timeout(std::time::Duration::ZERO)?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+    Ok(conn)
 }
 
 fn load_firefox_cookies_from_db(
@@ -206,4 +222,27 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn test_open_cookie_db_sees_uncheckpointed_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("cookies.sqlite");
+
+        // Like a running browser: WAL mode, recent writes not yet checkpointed
+        let browser = Connection::open(&db_path).unwrap();
+        browser
+            .execute_batch(
+                "PRAGMA journal_mode=WAL;
+                 PRAGMA wal_autocheckpoint=0;
+                 CREATE TABLE moz_cookies (host TEXT, value TEXT);
+                 INSERT INTO moz_cookies VALUES ('example.com', 'fresh');",
+            )
+            .unwrap();
+
+        let conn = open_cookie_db(&db_path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT count(*) FROM moz_cookies", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
 }
@@ -0,0 +1,722 @@
+//! Dates in the briefings, written the way the show's audience reads them,
+//! and read back from whatever the articles and editors wrote.
+//!
+//! [`Locale`] is set by `locale` in `[briefing]` or per show in `[[shows]]`.
+//! It picks the month and day names and the usual order, so a German show
+//! gets "Sonntag, 1. Februar 2026" where the default English has "Sunday,
+//! 1 February 2026". [`parse`] accepts RFC 3339 and RFC 2822 timestamps and
+//! the common written forms in any of the supported languages.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// A language supported for dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+    It,
+    Nl,
+    Pt,
+}
+
+/// The ways a date appears in the outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    /// "Sunday, 1 February 2026", for the briefing's title
+    Long,
+    /// "Sun, 1 February 2026", for the org draft's `#+DATE`
+    Weekday,
+    /// "1 February 2026"
+    Medium,
+    /// "Sun 1 Feb", for calendar events
+    Short,
+    /// "1-Feb-2026", a story's date
+    Stamp,
+    /// "1-Feb-2026 3:30PM", a story's date and time
+    StampTime,
+    /// "3:30 PM"
+    Time,
+    /// "Sun 1 Feb 2026 at 15:30", when the briefing was prepared
+    Prepared,
+    /// "February 1, 2026", for the links exports
+    Title,
+}
+
+struct Style {
+    /// Monday first
+    days: [&'static str; 7],
+    days_short: [&'static str; 7],
+    months: [&'static str; 12],
+    months_short: [&'static str; 12],
+    /// Patterns in [`DateFormat`] order
+    patterns: [&'static str; 9],
+}
+
+const EN: Style = Style {
+    days: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    days_short: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    patterns: [
+        "%A, %-d %B %Y",
+        "%a, %-d %B %Y",
+        "%-d %B %Y",
+        "%a %-d %b",
+        "%-d-%b-%Y",
+        "%-d-%b-%Y %-I:%M%p",
+        "%-I:%M %p",
+        "%a %-d %b %Y at %H:%M",
+        "%B %-d, %Y",
+    ],
+};
+
+const DE: Style = Style {
+    days: [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ],
+    days_short: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+    ],
+    patterns: [
+        "%A, %-d. %B %Y",
+        "%a, %-d. %B %Y",
+        "%-d. %B %Y",
+        "%a %-d. %b",
+        "%d.%m.%Y",
+        "%d.%m.%Y %H:%M",
+        "%H:%M",
+        "%a %-d. %b %Y um %H:%M",
+        "%-d. %B %Y",
+    ],
+};
+
+const ES: Style = Style {
+    days: [
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+        "domingo",
+    ],
+    days_short: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    months_short: [
+        "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+    ],
+    patterns: [
+        "%A, %-d de %B de %Y",
+        "%a, %-d de %B de %Y",
+        "%-d de %B de %Y",
+        "%a %-d %b",
+        "%d/%m/%Y",
+        "%d/%m/%Y %H:%M",
+        "%H:%M",
+        "%a %-d %b %Y a las %H:%M",
+        "%-d de %B de %Y",
+    ],
+};
+
+const FR: Style = Style {
+    days: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    days_short: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    months_short: [
+        "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov", "déc",
+    ],
+    patterns: [
+        "%A %-d %B %Y",
+        "%a %-d %B %Y",
+        "%-d %B %Y",
+        "%a %-d %b",
+        "%d/%m/%Y",
+        "%d/%m/%Y %H:%M",
+        "%H:%M",
+        "%a %-d %b %Y à %H:%M",
+        "%-d %B %Y",
+    ],
+};
+
+const IT: Style = Style {
+    days: [
+        "lunedì",
+        "martedì",
+        "mercoledì",
+        "giovedì",
+        "venerdì",
+        "sabato",
+        "domenica",
+    ],
+    days_short: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    months: [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    months_short: [
+        "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+    ],
+    patterns: [
+        "%A %-d %B %Y",
+        "%a %-d %B %Y",
+        "%-d %B %Y",
+        "%a %-d %b",
+        "%d/%m/%Y",
+        "%d/%m/%Y %H:%M",
+        "%H:%M",
+        "%a %-d %b %Y alle %H:%M",
+        "%-d %B %Y",
+    ],
+};
+
+const NL: Style = Style {
+    days: [
+        "maandag",
+        "dinsdag",
+        "woensdag",
+        "donderdag",
+        "vrijdag",
+        "zaterdag",
+        "zondag",
+    ],
+    days_short: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+    months: [
+        "januari",
+        "februari",
+        "maart",
+        "april",
+        "mei",
+        "juni",
+        "juli",
+        "augustus",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+    months_short: [
+        "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+    ],
+    patterns: [
+        "%A %-d %B %Y",
+        "%a %-d %B %Y",
+        "%-d %B %Y",
+        "%a %-d %b",
+        "%d-%m-%Y",
+        "%d-%m-%Y %H:%M",
+        "%H:%M",
+        "%a %-d %b %Y om %H:%M",
+        "%-d %B %Y",
+    ],
+};
+
+const PT: Style = Style {
+    days: [
+        "segunda-feira",
+        "terça-feira",
+        "quarta-feira",
+        "quinta-feira",
+        "sexta-feira",
+        "sábado",
+        "domingo",
+    ],
+    days_short: ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+    months: [
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+    ],
+    months_short: [
+        "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+    ],
+    patterns: [
+        "%A, %-d de %B de %Y",
+        "%a, %-d de %B de %Y",
+        "%-d de %B de %Y",
+        "%a %-d %b",
+        "%d/%m/%Y",
+        "%d/%m/%Y %H:%M",
+        "%H:%M",
+        "%a %-d %b %Y às %H:%M",
+        "%-d de %B de %Y",
+    ],
+};
+
+const LOCALES: [(Locale, &str); 7] = [
+    (Locale::En, "en"),
+    (Locale::De, "de"),
+    (Locale::Es, "es"),
+    (Locale::Fr, "fr"),
+    (Locale::It, "it"),
+    (Locale::Nl, "nl"),
+    (Locale::Pt, "pt"),
+];
+
+impl Locale {
+    /// The locale for a language tag such as `de`, `fr-CA` or `pt_BR`.
+    /// Only the language counts; regional variants share its formats.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        LOCALES
+            .iter()
+            .find(|(_, code)| *code == language)
+            .map(|(locale, _)| *locale)
+    }
+
+    fn style(self) -> &'static Style {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+            Locale::Es => &ES,
+            Locale::Fr => &FR,
+            Locale::It => &IT,
+            Locale::Nl => &NL,
+            Locale::Pt => &PT,
+        }
+    }
+
+    /// `at` written in `format`.
+    pub fn format(self, at: NaiveDateTime, format: DateFormat) -> String {
+        let style = self.style();
+        let pattern = style.patterns[format as usize];
+        // Put the names in before chrono sees the pattern, which would
+        // otherwise write them in English
+        let weekday = at.weekday().num_days_from_monday() as usize;
+        let month = at.month0() as usize;
+        let mut localized = String::new();
+        let mut rest = pattern;
+        while let Some(i) = rest.find('%') {
+            localized.push_str(&rest[..i]);
+            let spec = &rest[i..(i + 2).min(rest.len())];
+            match spec {
+                "%A" => localized.push_str(style.days[weekday]),
+                "%a" => localized.push_str(style.days_short[weekday]),
+                "%B" => localized.push_str(style.months[month]),
+                "%b" => localized.push_str(style.months_short[month]),
+                _ => {
+                    localized.push('%');
+                    rest = &rest[i + 1..];
+                    continue;
+                }
+            }
+            rest = &rest[i + 2..];
+        }
+        localized.push_str(rest);
+        at.format(&localized).to_string()
+    }
+
+    /// [`format`](Self::format) for a date.
+    pub fn format_date(self, date: NaiveDate, format: DateFormat) -> String {
+        self.format(date.and_time(NaiveTime::MIN), format)
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tag = String::deserialize(deserializer)?;
+        Locale::from_tag(&tag).ok_or_else(|| {
+            let codes: Vec<&str> = LOCALES.iter().map(|(_, code)| *code).collect();
+            serde::de::Error::custom(format!(
+                "unsupported locale '{}' (use {})",
+                tag,
+                codes.join(", ")
+            ))
+        })
+    }
+}
+
+/// A date read by [`parse`], with its time if the text had one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsedDate {
+    At(DateTime<FixedOffset>),
+    On(NaiveDate),
+}
+
+impl ParsedDate {
+    /// The moment, taking a bare date as midnight UTC.
+    pub fn instant(self) -> DateTime<FixedOffset> {
+        match self {
+            ParsedDate::At(at) => at,
+            ParsedDate::On(date) => date.and_time(NaiveTime::MIN).and_utc().fixed_offset(),
+        }
+    }
+
+    pub fn date(self) -> NaiveDate {
+        match self {
+            ParsedDate::At(at) => at.date_naive(),
+            ParsedDate::On(date) => date,
+        }
+    }
+}
+
+/// Written forms with a time, after [`to_english`]. Times without a zone
+/// are taken as UTC.
+const DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d-%b-%Y %I:%M%p",
+    "%A, %d %B %Y %I:%M %p",
+    "%B %d, %Y %I:%M %p",
+    "%d.%m.%Y %H:%M",
+];
+
+/// Written forms of a date, after [`to_english`].
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%a, %e %b %Y",
+    "%a, %d %b %Y",
+    "%A, %d %B %Y",
+    "%A %d %B %Y",
+    "%A, %B %d, %Y",
+    "%e %b %Y",
+    "%d %b %Y",
+    "%d %b. %Y",
+    "%d %B %Y",
+    "%d-%b-%Y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%B %d %Y",
+    "%A, %d. %B %Y",
+    "%d. %B %Y",
+    "%A, %d de %B de %Y",
+    "%d de %B de %Y",
+    "%d.%m.%Y",
+];
+
+/// Read a date or timestamp, in any supported language. Slashed dates are
+/// month first (`02/01/2026` is 1 February) unless the first number can
+/// only be a day.
+pub fn parse(text: &str) -> Option<ParsedDate> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Some(ParsedDate::At(at));
+    }
+    if let Ok(at) = DateTime::parse_from_rfc2822(text) {
+        return Some(ParsedDate::At(at));
+    }
+
+    let text = to_english(text);
+    let text = text.trim();
+    for format in DATE_TIME_FORMATS {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(ParsedDate::At(Utc.from_utc_datetime(&naive).fixed_offset()));
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Some(ParsedDate::On(date));
+        }
+    }
+    slashed(text).map(ParsedDate::On)
+}
+
+fn slashed(text: &str) -> Option<NaiveDate> {
+    let parts: Vec<u32> = text
+        .split('/')
+        .map(|p| p.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let [first, second, year] = parts[..] else {
+        return None;
+    };
+    let (month, day) = if first > 12 {
+        (second, first)
+    } else {
+        (first, second)
+    };
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+}
+
+/// `text` with day and month names of the other languages replaced by
+/// the English ones. Short names that are also English words or month
+/// abbreviations (Spanish "mar" for martes) are left alone.
+fn to_english(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        out.push_str(english_name(word).unwrap_or(word));
+        word.clear();
+    };
+    for c in text.chars() {
+        // Portuguese day names are hyphenated
+        if c.is_alphabetic() || (c == '-' && !word.is_empty()) {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+fn english_name(word: &str) -> Option<&'static str> {
+    let word = word.trim_end_matches('-').to_lowercase();
+    if word.is_empty() {
+        return None;
+    }
+    let is_english = |w: &str| {
+        EN.months
+            .iter()
+            .chain(&EN.months_short)
+            .chain(&EN.days)
+            .chain(&EN.days_short)
+            .any(|e| e.eq_ignore_ascii_case(w))
+    };
+    if is_english(&word) {
+        return None;
+    }
+    for (locale, _) in &LOCALES[1..] {
+        let style = locale.style();
+        for (i, name) in style.months.iter().enumerate() {
+            if name.to_lowercase() == word {
+                return Some(EN.months[i]);
+            }
+        }
+        for (i, name) in style.days.iter().enumerate() {
+            if name.to_lowercase() == word {
+                return Some(EN.days[i]);
+            }
+        }
+        for (i, name) in style.months_short.iter().enumerate() {
+            if name.to_lowercase() == word && name.chars().count() > 2 {
+                return Some(EN.months_short[i]);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_english_formats_are_unchanged() {
+        let sunday = at(2026, 2, 1, 15, 30);
+        let en = Locale::En;
+        assert_eq!(
+            en.format(sunday, DateFormat::Long),
+            "Sunday, 1 February 2026"
+        );
+        assert_eq!(
+            en.format(sunday, DateFormat::Weekday),
+            "Sun, 1 February 2026"
+        );
+        assert_eq!(en.format(sunday, DateFormat::Short), "Sun 1 Feb");
+        assert_eq!(
+            en.format(sunday, DateFormat::StampTime),
+            "1-Feb-2026 3:30PM"
+        );
+        assert_eq!(
+            en.format(sunday, DateFormat::Prepared),
+            "Sun 1 Feb 2026 at 15:30"
+        );
+        assert_eq!(en.format(sunday, DateFormat::Title), "February 1, 2026");
+    }
+
+    #[test]
+    fn test_other_languages() {
+        let sunday = at(2026, 3, 1, 15, 30);
+        assert_eq!(
+            Locale::De.format(sunday, DateFormat::Long),
+            "Sonntag, 1. März 2026"
+        );
+        assert_eq!(
+            Locale::De.format(sunday, DateFormat::StampTime),
+            "01.03.2026 15:30"
+        );
+        assert_eq!(
+            Locale::Es.format(sunday, DateFormat::Long),
+            "domingo, 1 de marzo de 2026"
+        );
+        assert_eq!(Locale::Fr.format(sunday, DateFormat::Short), "dim 1 mars");
+        assert_eq!(
+            Locale::Pt.format(sunday, DateFormat::Prepared),
+            "dom 1 mar 2026 às 15:30"
+        );
+        assert_eq!(Locale::Nl.format(sunday, DateFormat::Time), "15:30");
+    }
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Locale::from_tag("de"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("fr-CA"), Some(Locale::Fr));
+        assert_eq!(Locale::from_tag("pt_BR"), Some(Locale::Pt));
+        assert_eq!(Locale::from_tag("EN-us"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("ja"), None);
+
+        let err = toml::from_str::<toml::Table>("locale = \"ja\"")
+            .unwrap()
+            .get("locale")
+            .unwrap()
+            .clone()
+            .try_into::<Locale>()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("unsupported locale 'ja'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let feb1 = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        for text in [
+            "2026-02-01",
+            "Sun, 1 Feb 2026",
+            "1 February 2026",
+            "February 1, 2026",
+            "Feb 1, 2026",
+            "Sunday, February 1, 2026",
+            "01.02.2026",
+            "1-Feb-2026",
+            "02/01/2026",
+            "Sonntag, 1. Februar 2026",
+            "1 de febrero de 2026",
+            "dimanche 1 février 2026",
+            "1 febbraio 2026",
+            "zondag 1 februari 2026",
+            "domingo, 1 de fevereiro de 2026",
+            "1 févr. 2026",
+        ] {
+            assert_eq!(
+                parse(text),
+                Some(ParsedDate::On(feb1)),
+                "{} should be 1 February",
+                text
+            );
+        }
+        assert_eq!(
+            parse("13/02/2026").unwrap().date().to_string(),
+            "2026-02-13"
+        );
+
+        let instant = |text| parse(text).unwrap().instant().to_rfc3339();
+        assert_eq!(
+            instant("2026-02-01T15:30:00+01:00"),
+            "2026-02-01T15:30:00+01:00"
+        );
+        assert_eq!(
+            instant("Sun, 01 Feb 2026 15:30:00 +0000"),
+            "2026-02-01T15:30:00+00:00"
+        );
+        assert_eq!(instant("1-Feb-2026 3:30PM"), "2026-02-01T15:30:00+00:00");
+        assert_eq!(
+            instant("Wednesday, 28 January 2026 3:17 PM"),
+            "2026-01-28T15:17:00+00:00"
+        );
+        assert_eq!(instant("2026-02-01"), "2026-02-01T00:00:00+00:00");
+
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("not a date"), None);
+        assert_eq!(parse("31/31/2026"), None);
+    }
+}
@@ -0,0 +1,475 @@
+//! Story deduplication across sources.
+//!
+//! The same story regularly gets bookmarked more than once: an AMP link from a
+//! phone, a copy with `utm_*` tracking from a newsletter, a syndicated version
+//! on another outlet. Exact-URL dedup misses all of those, so this pass
+//! canonicalizes URLs (after redirects have been followed by the extractor) and
+//! falls back to title/lede similarity to merge near-duplicates before
+//! clustering.
+
+use std::collections::{HashMap, HashSet};
+use url::Url;
+
+use crate::clustering::Story;
+use crate::summarizer::Summary;
+
+/// Query parameters that only exist for tracking and never change the content.
+/// Generic names like `ref`, `cid` or `ito` are left alone: plenty of sites
+/// use them to pick the article.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "dclid",
+    "msclkid",
+    "mc_cid",
+    "mc_eid",
+    "igshid",
+    "cmpid",
+    "ref_src",
+    "smid",
+    "smtyp",
+    "guccounter",
+    "ncid",
+    "sr_share",
+    "taid",
+];
+
+/// Title similarity at or above which two stories are treated as the same story.
+pub(crate) const TITLE_THRESHOLD: f64 = 0.8;
+
+/// Looser title similarity that counts as a duplicate when the ledes also agree.
+const TITLE_WITH_LEDE_THRESHOLD: f64 = 0.5;
+const LEDE_THRESHOLD: f64 = 0.5;
+
+/// A story that was folded into another one.
+#[derive(Debug, Clone)]
+pub struct MergedStory {
+    pub kept_title: String,
+    pub dropped_title: String,
+    pub dropped_url: String,
+    pub reason: &'static str,
+}
+
+/// Normalize a URL so that trivially different links to the same article compare
+/// equal: lowercase host without `www.`/`amp.`, no fragment, no tracking params,
+/// no AMP path/query markers and no trailing slash. Unparseable input is
+/// returned trimmed.
+pub fn canonicalize_url(raw: &str) -> String {
+    let Ok(mut url) = Url::parse(raw.trim()) else {
+        return raw.trim().to_string();
+    };
+
+    url.set_fragment(None);
+
+    if let Some(host) = url.host_str() {
+        let host = host.to_ascii_lowercase();
+        let host = host
+            .strip_prefix("www.")
+            .or_else(|| host.strip_prefix("amp."))
+            .unwrap_or(&host)
+            .to_string();
+        let _ = url.set_host(Some(&host));
+    }
+
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| {
+            let k = k.to_ascii_lowercase();
+            !k.starts_with("utm_")
+                && !TRACKING_PARAMS.contains(&k.as_str())
+                && k != "amp"
+                && k != "outputtype"
+        })
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+
+    let path = url.path().to_string();
+    let path = path
+        .strip_suffix("/amp/")
+        .or_else(|| path.strip_suffix("/amp"))
+        .or_else(|| path.strip_suffix(".amp"))
+        .unwrap_or(&path)
+        .trim_end_matches('/')
+        .to_string();
+    url.set_path(&path);
+
+    // Treat http and https as the same document
+    let mut canonical = url.to_string();
+    if let Some(rest) = canonical.strip_prefix("http://") {
+        canonical = format!("https://{rest}");
+    }
+    canonical.trim_end_matches('/').to_string()
+}
+
+/// Lowercased word set with short/stop words removed, for similarity checks.
+/// Words with a digit are kept however short, and a version number stays one
+/// word, so "M4", "18.2" and "S24" tell stories apart.
+fn tokens(text: &str) -> HashSet<String> {
+    const STOP: &[&str] = &[
+        "the", "and", "for", "with", "that", "this", "from", "into", "its", "are", "was", "has",
+        "have", "will", "after", "over", "says", "said",
+    ];
+    text.split(|c: char| !c.is_alphanumeric() && c != '.')
+        .map(|w| w.trim_matches('.').to_lowercase())
+        .filter(|w| (w.len() > 2 || has_digit(w)) && !STOP.contains(&w.as_str()))
+        .collect()
+}
+
+fn has_digit(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Whether each title has a number, model or version the other lacks, as in
+/// "iOS 18.2 released" and "iOS 18.3 released": those are different stories
+/// however alike the rest of the words are.
+fn numbers_conflict(a: &str, b: &str) -> bool {
+    let numbers = |text: &str| -> HashSet<String> {
+        tokens(text).into_iter().filter(|w| has_digit(w)).collect()
+    };
+    let (a, b) = (numbers(a), numbers(b));
+    !a.is_subset(&b) && !b.is_subset(&a)
+}
+
+/// Jaccard similarity of the word sets of two strings (0.0 to 1.0).
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = tokens(a);
+    let b = tokens(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count() as f64;
+    let union = a.union(&b).count() as f64;
+    shared / union
+}
+
+fn lede(story: &Story) -> &str {
+    match &story.summary {
+        Summary::Editorial { lede, .. } => lede,
+        Summary::Product { the_product, .. } => the_product,
+        _ => "",
+    }
+}
+
+fn has_summary(story: &Story) -> bool {
+    matches!(
+        story.summary,
+        Summary::Editorial { .. } | Summary::Product { .. }
+    )
+}
+
+/// Why two stories are duplicates, or `None` if they are distinct.
+fn duplicate_reason(a: &Story, a_key: &str, b: &Story, b_key: &str) -> Option<&'static str> {
+    if a_key == b_key {
+        return Some("same canonical URL");
+    }
+    if numbers_conflict(&a.title, &b.title) {
+        return None;
+    }
+    let title_sim = similarity(&a.title, &b.title);
+    if title_sim >= TITLE_THRESHOLD {
+        return Some("near-identical title");
+    }
+    if title_sim >= TITLE_WITH_LEDE_THRESHOLD && similarity(lede(a), lede(b)) >= LEDE_THRESHOLD {
+        return Some("similar title and summary");
+    }
+    None
+}
+
+/// Merge duplicate stories, keeping the first occurrence unless a later copy has
+/// a usable summary and the kept one doesn't. `resolved_urls` maps a story's URL
+/// to the final URL after redirects (as seen by the extractor); stories missing
+/// from the map are compared on their own URL.
+///
+/// Returns the surviving stories in their original order plus a record of every
+/// story that was folded away, so the caller can report it.
+pub fn merge_duplicates(
+    stories: Vec<Story>,
+    resolved_urls: &HashMap<String, String>,
+) -> (Vec<Story>, Vec<MergedStory>) {
+    let mut kept: Vec<(String, Story)> = Vec::new();
+    let mut merged = Vec::new();
+
+    for story in stories {
+        let source = resolved_urls.get(&story.url).unwrap_or(&story.url);
+        let key = canonicalize_url(source);
+
+        let existing = kept
+            .iter()
+            .position(|(k, s)| duplicate_reason(s, k, &story, &key).is_some());
+
+        match existing {
+            Some(pos) => {
+                let (kept_key, kept_story) = &kept[pos];
+                let reason =
+                    duplicate_reason(kept_story, kept_key, &story, &key).unwrap_or("duplicate");
+                // Starring either copy stars the story that stays
+                let starred = kept_story.starred || story.starred;
+                let previously = kept_story.previously.clone().or(story.previously.clone());
+                if !has_summary(kept_story) && has_summary(&story) {
+                    let (_, old) = std::mem::replace(&mut kept[pos], (key, story));
+                    merged.push(MergedStory {
+                        kept_title: kept[pos].1.title.clone(),
+                        dropped_title: old.title,
+                        dropped_url: old.url,
+                        reason,
+                    });
+                } else {
+                    merged.push(MergedStory {
+                        kept_title: kept_story.title.clone(),
+                        dropped_title: story.title,
+                        dropped_url: story.url,
+                        reason,
+                    });
+                }
+                kept[pos].1.starred = starred;
+                kept[pos].1.previously = previously;
+            }
+            None => kept.push((key, story)),
+        }
+    }
+
+    (kept.into_iter().map(|(_, s)| s).collect(), merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    fn editorial(lede: &str) -> Summary {
+        Summary::Editorial {
+            lede: lede.to_string(),
+            nutgraf: "It matters.".to_string(),
+            quotes: Vec::new(),
+            reaction: None,
+            by_the_numbers: None,
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_strips_tracking_params() {
+        assert_eq!(
+            canonicalize_url("https://www.example.com/story?utm_source=nl&utm_medium=email&id=7"),
+            "https://example.com/story?id=7"
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/story?fbclid=abc&gclid=def&mc_cid=1"),
+            "https://example.com/story"
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_keeps_generic_params() {
+        // These pick the page on some sites, e.g. forum.php?ref=12
+        for url in [
+            "https://example.com/forum.php?ref=12",
+            "https://example.com/shop?cid=42",
+            "https://example.com/view?ito=3",
+        ] {
+            assert_eq!(canonicalize_url(url), url);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_strips_amp_variants() {
+        let canonical = "https://example.com/2026/02/story";
+        assert_eq!(
+            canonicalize_url("https://amp.example.com/2026/02/story/"),
+            canonical
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/2026/02/story/amp/"),
+            canonical
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/2026/02/story?amp=1"),
+            canonical
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_ignores_scheme_and_fragment() {
+        assert_eq!(
+            canonicalize_url("http://Example.com/a#comments"),
+            canonicalize_url("https://example.com/a")
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_unparseable_passthrough() {
+        assert_eq!(canonicalize_url("  not a url "), "not a url");
+    }
+
+    #[test]
+    fn test_similarity_bounds() {
+        assert_eq!(
+            similarity("Apple launches M5 MacBook", "Apple launches M5 MacBook"),
+            1.0
+        );
+        assert_eq!(similarity("Apple launches M5", "Tesla recalls cars"), 0.0);
+        assert_eq!(similarity("", "anything"), 0.0);
+    }
+
+    #[test]
+    fn test_merge_same_canonical_url() {
+        let stories = vec![
+            story("Story")
+                .with_url("https://example.com/a?utm_source=x")
+                .with_created("2026-02-01")
+                .with_summary(editorial("A"))
+                .build(),
+            story("Story (AMP)")
+                .with_url("https://example.com/a/amp")
+                .with_created("2026-02-01")
+                .with_summary(editorial("B"))
+                .build(),
+        ];
+        let (stories, merged) = merge_duplicates(stories, &HashMap::new());
+        assert_eq!(stories.len(), 1);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].reason, "same canonical URL");
+    }
+
+    #[test]
+    fn test_merge_uses_resolved_redirects() {
+        let stories = vec![
+            story("One")
+                .with_url("https://example.com/real")
+                .with_created("2026-02-01")
+                .with_summary(editorial("A"))
+                .build(),
+            story("Two")
+                .with_url("https://short.link/xyz")
+                .with_created("2026-02-01")
+                .with_summary(editorial("B"))
+                .build(),
+        ];
+        let resolved = HashMap::from([(
+            "https://short.link/xyz".to_string(),
+            "https://example.com/real".to_string(),
+        )]);
+        let (stories, _) = merge_duplicates(stories, &resolved);
+        assert_eq!(stories.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_syndicated_copy_by_title() {
+        let stories = vec![
+            story("Microsoft agrees to buy Activision Blizzard for $69 billion")
+                .with_url("https://theverge.com/a")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Microsoft will acquire Activision"))
+                .build(),
+            story("Microsoft agrees to buy Activision Blizzard for $69 billion")
+                .with_url("https://news.yahoo.com/b")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Syndicated copy"))
+                .build(),
+        ];
+        let (stories, merged) = merge_duplicates(stories, &HashMap::new());
+        assert_eq!(stories.len(), 1);
+        assert_eq!(stories[0].url, "https://theverge.com/a");
+        assert_eq!(merged[0].reason, "near-identical title");
+    }
+
+    #[test]
+    fn test_merge_prefers_summarized_copy() {
+        let mut stories = vec![
+            story("Same story")
+                .with_url("https://example.com/a")
+                .with_created("2026-02-01")
+                .with_summary(Summary::Failed("x".into()))
+                .build(),
+            story("Same story")
+                .with_url("https://example.com/a?utm_campaign=y")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Good"))
+                .build(),
+        ];
+        stories[0].starred = true;
+        let (stories, merged) = merge_duplicates(stories, &HashMap::new());
+        assert_eq!(stories.len(), 1);
+        assert!(matches!(stories[0].summary, Summary::Editorial { .. }));
+        assert_eq!(merged[0].dropped_url, "https://example.com/a");
+        // The dropped copy's star carries over
+        assert!(stories[0].starred);
+    }
+
+    #[test]
+    fn test_merge_keeps_distinct_stories() {
+        let stories = vec![
+            story("Apple ships new iPad")
+                .with_url("https://a.com/1")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Apple iPad"))
+                .build(),
+            story("Google updates Search")
+                .with_url("https://b.com/2")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Google search"))
+                .build(),
+        ];
+        let (stories, merged) = merge_duplicates(stories, &HashMap::new());
+        assert_eq!(stories.len(), 2);
+        assert!(merged.is_empty());
+    }
+
+    #[test]
+    fn test_tokens_keep_numbers_and_versions() {
+        let words = tokens("Apple launches M4 MacBook with iOS 18.2.");
+        for word in ["m4", "18.2", "ios", "macbook"] {
+            assert!(words.contains(word), "{word} missing from {words:?}");
+        }
+        assert!(!words.contains("with"));
+        assert!(similarity("Apple launches M4 MacBook", "Apple launches M5 MacBook") < 1.0);
+    }
+
+    #[test]
+    fn test_merge_keeps_titles_differing_in_a_number() {
+        for (a, b) in [
+            ("Apple launches M4 MacBook", "Apple launches M5 MacBook"),
+            ("iOS 18.2 released", "iOS 18.3 released"),
+            ("Samsung Galaxy S24 review", "Samsung Galaxy S25 review"),
+        ] {
+            let stories = vec![
+                story(a)
+                    .with_url("https://a.com/1")
+                    .with_created("2026-02-01")
+                    .with_summary(editorial("Apple released an update today."))
+                    .build(),
+                story(b)
+                    .with_url("https://b.com/2")
+                    .with_created("2026-02-01")
+                    .with_summary(editorial("Apple released an update today."))
+                    .build(),
+            ];
+            let (stories, merged) = merge_duplicates(stories, &HashMap::new());
+            assert_eq!(stories.len(), 2, "{a} / {b} were merged");
+            assert!(merged.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_merge_same_number_with_extra_detail() {
+        let stories = vec![
+            story("iOS 18.2 released")
+                .with_url("https://a.com/1")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Apple ships iOS 18.2."))
+                .build(),
+            story("iOS 18.2 released to everyone")
+                .with_url("https://b.com/2")
+                .with_created("2026-02-01")
+                .with_summary(editorial("Apple ships iOS 18.2."))
+                .build(),
+        ];
+        let (stories, merged) = merge_duplicates(stories, &HashMap::new());
+        assert_eq!(stories.len(), 1);
+        assert_eq!(merged[0].reason, "similar title and summary");
+    }
+}
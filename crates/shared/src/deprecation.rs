@@ -0,0 +1,62 @@
+//! The deprecation path for `collect-stories` and `prepare-briefing` from
+//! before the `briefing` CLI. They stay as shims for a few releases: each
+//! takes its old flags, runs the `briefing` subcommand that replaced it and
+//! first prints a hint with the command to use instead.
+
+/// The note the old binary `old` prints to stderr before running
+/// `briefing <subcommand>` with this process's arguments.
+pub fn migration_hint(old: &str, subcommand: &str) -> String {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    hint(old, subcommand, &args)
+}
+
+fn hint(old: &str, subcommand: &str, args: &[String]) -> String {
+    format!(
+        "note: {} is now `{}`; this name will go away in a future release",
+        old,
+        command_line(subcommand, args)
+    )
+}
+
+/// `args` as a `briefing` command line, quoted for a shell where needed.
+fn command_line(subcommand: &str, args: &[String]) -> String {
+    let mut line = format!("briefing {}", subcommand);
+    for arg in args {
+        line.push(' ');
+        if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'') {
+            line.push_str(&format!("'{}'", arg.replace('\'', r"'\''")));
+        } else {
+            line.push_str(arg);
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        assert_eq!(command_line("collect", &[]), "briefing collect");
+        let args = ["--file".to_string(), "My Briefing.org".to_string()];
+        assert_eq!(
+            command_line("prepare", &args),
+            "briefing prepare --file 'My Briefing.org'"
+        );
+        assert_eq!(
+            command_line("collect", &["it's".to_string(), String::new()]),
+            r"briefing collect 'it'\''s' ''"
+        );
+    }
+
+    #[test]
+    fn test_hint() {
+        let args = ["--show".to_string(), "twit".to_string()];
+        assert_eq!(
+            hint("collect-stories", "collect", &args),
+            "note: collect-stories is now `briefing collect --show twit`; \
+             this name will go away in a future release"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Sponsor and conflict-of-interest flags.
+//!
+//! A story that names a sponsor or a company the hosts have ties to gets a
+//! "disclosure needed" banner in the briefing, so the disclaimer is read on
+//! air. Names are matched as whole words, ignoring case, against the
+//! story's title and summary.
+
+use serde::Deserialize;
+
+use crate::clustering::Story;
+use crate::summarizer::Summary;
+
+/// The `[briefing.disclosures]` section of `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DisclosureSettings {
+    /// Companies currently advertising on the network
+    pub sponsors: Vec<String>,
+    /// Other companies whose stories need a disclaimer
+    pub companies: Vec<Company>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Company {
+    pub name: String,
+    /// Products or subsidiaries that should also trigger the flag
+    pub aliases: Vec<String>,
+    /// What the hosts should disclose, e.g. "Leo owns Apple stock"
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flag {
+    pub company: String,
+    /// "sponsor", or the company's note
+    pub reason: String,
+}
+
+impl std::fmt::Display for Flag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.company, self.reason)
+    }
+}
+
+impl DisclosureSettings {
+    pub fn is_empty(&self) -> bool {
+        self.sponsors.is_empty() && self.companies.is_empty()
+    }
+
+    /// Every sponsor and company `story` mentions, in config order.
+    pub fn flags(&self, story: &Story) -> Vec<Flag> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let text = story_text(story).to_lowercase();
+
+        let sponsors = self
+            .sponsors
+            .iter()
+            .filter(|name| mentions(&text, name))
+            .map(|name| Flag {
+                company: name.clone(),
+                reason: "sponsor".to_string(),
+            });
+        let companies = self
+            .companies
+            .iter()
+            .filter(|c| {
+                std::iter::once(&c.name)
+                    .chain(&c.aliases)
+                    .any(|n| mentions(&text, n))
+            })
+            .map(|c| Flag {
+                company: c.name.clone(),
+                reason: c
+                    .note
+                    .clone()
+                    .unwrap_or_else(|| "disclosure required".to_string()),
+            });
+        sponsors.chain(companies).collect()
+    }
+}
+
+/// The story's title and summary fields, one per line.
+pub(crate) fn story_text(story: &Story) -> String {
+    let mut text = story.title.clone();
+    let quotes: Vec<String> = match &story.summary {
+        Summary::Editorial { quotes, .. } | Summary::Product { quotes, .. } => {
+            quotes.iter().map(ToString::to_string).collect()
+        }
+        Summary::Insufficient | Summary::Failed(_) => Vec::new(),
+    };
+    let fields: Vec<&String> = match &story.summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes: _,
+            reaction,
+            by_the_numbers,
+        } => [lede, nutgraf]
+            .into_iter()
+            .chain(&quotes)
+            .chain(reaction)
+            .chain(by_the_numbers)
+            .collect(),
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes: _,
+            reaction,
+        } => [the_product, cost, availability, platforms]
+            .into_iter()
+            .chain(&quotes)
+            .chain(reaction)
+            .collect(),
+        Summary::Insufficient | Summary::Failed(_) => Vec::new(),
+    };
+    for field in fields {
+        text.push('\n');
+        text.push_str(field);
+    }
+    text
+}
+
+/// Whether lowercased `text` contains `name` as a whole word or phrase.
+pub(crate) fn mentions(text: &str, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return false;
+    }
+    let is_word = |c: char| c.is_alphanumeric();
+    text.match_indices(&name).any(|(at, _)| {
+        let before = text[..at].chars().next_back();
+        let after = text[at + name.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    #[test]
+    fn test_flags_sponsors_and_companies() {
+        let settings = DisclosureSettings {
+            sponsors: vec!["Squarespace".to_string(), "Bitwarden".to_string()],
+            companies: vec![Company {
+                name: "Microsoft".to_string(),
+                aliases: vec!["Xbox".to_string()],
+                note: Some("Paul consults for Microsoft".to_string()),
+            }],
+        };
+
+        let flags = settings.flags(
+            &story("New Xbox handheld")
+                .with_lede(
+                    "The console syncs saves through squarespace-hosted pages.",
+                    "More to come.",
+                )
+                .build(),
+        );
+        assert_eq!(
+            flags,
+            vec![
+                Flag {
+                    company: "Squarespace".to_string(),
+                    reason: "sponsor".to_string(),
+                },
+                Flag {
+                    company: "Microsoft".to_string(),
+                    reason: "Paul consults for Microsoft".to_string(),
+                },
+            ]
+        );
+
+        // Whole words only
+        assert!(settings
+            .flags(
+                &story("Xboxes everywhere")
+                    .with_lede("Microsofts galore", "More to come.")
+                    .build()
+            )
+            .is_empty());
+        assert!(DisclosureSettings::default()
+            .flags(&story("Microsoft").with_lede("", "More to come.").build())
+            .is_empty());
+    }
+}
@@ -0,0 +1,437 @@
+//! "Stories You Might Have Missed": what Hacker News and Techmeme led with
+//! (`[briefing.discovery] enabled = true`, or `collect-stories --discover`).
+//!
+//! After collecting, collect-stories asks the Hacker News search API for the
+//! most upvoted stories since the previous show ended and reads Techmeme's
+//! headline feed. Those already bookmarked, by canonical URL or by a
+//! near-identical headline, are dropped, and the rest are listed at the end
+//! of the org draft. They aren't part of the briefing: `parse_org_mode`
+//! skips the section, and `briefing add-story <url>` adds one that deserves
+//! a spot. The list is kept in the story file, so the section is written
+//! again whenever the draft is.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::dedup::{canonicalize_url, similarity, TITLE_THRESHOLD};
+
+/// Heading of the org section, which `parse_org_mode` skips.
+pub const SECTION_TITLE: &str = "Stories You Might Have Missed";
+
+const HACKER_NEWS_SEARCH: &str = "https://hn.algolia.com/api/v1/search";
+const HACKER_NEWS_ITEM: &str = "https://news.ycombinator.com/item?id=";
+const TECHMEME_FEED: &str = "https://www.techmeme.com/feed.xml";
+
+/// Hacker News stories asked for; more than `limit`, since some will be
+/// bookmarked already.
+const HACKER_NEWS_HITS: usize = 50;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The `[briefing.discovery]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiscoverySettings {
+    /// List top stories from elsewhere that weren't bookmarked
+    pub enabled: bool,
+    pub hacker_news: bool,
+    pub techmeme: bool,
+    /// Hacker News stories with fewer points are left out
+    pub min_points: u64,
+    /// Most stories listed from each source
+    pub limit: usize,
+}
+
+impl Default for DiscoverySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hacker_news: true,
+            techmeme: true,
+            min_points: 100,
+            limit: 10,
+        }
+    }
+}
+
+/// A story from another site's front page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Suggestion {
+    pub title: String,
+    pub url: String,
+    /// `Hacker News` or `Techmeme`
+    pub source: String,
+    /// Hacker News points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<u64>,
+    /// The Hacker News thread, when `url` is the article
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discussion: Option<String>,
+}
+
+impl Suggestion {
+    /// `[[url][Title]] (Hacker News, 512 points)`
+    fn org_line(&self) -> String {
+        let mut about = self.source.clone();
+        if let Some(points) = self.points {
+            about.push_str(&format!(", {} points", points));
+        }
+        if let Some(discussion) = &self.discussion {
+            about.push_str(&format!(", {}", crate::org::link(discussion, "comments")));
+        }
+        format!("{} ({})", crate::org::link(&self.url, &self.title), about)
+    }
+}
+
+impl DiscoverySettings {
+    /// Top stories published between `since` and `until` that aren't among
+    /// `bookmarked` (URL and title pairs). A source that can't be read is
+    /// skipped with a warning; it shouldn't hold up the draft.
+    pub async fn suggestions(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        bookmarked: &[(String, String)],
+    ) -> Vec<Suggestion> {
+        let client = match Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(crate::net::BROWSER_USER_AGENT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!("Skipping discovery: {}", e);
+                return Vec::new();
+            }
+        };
+        let mut found = Vec::new();
+        if self.hacker_news {
+            match hacker_news(&client, since, until, self.min_points).await {
+                Ok(stories) => found.push(stories),
+                Err(e) => tracing::warn!("Skipping Hacker News: {:#}", e),
+            }
+        }
+        if self.techmeme {
+            match techmeme(&client, since, until).await {
+                Ok(stories) => found.push(stories),
+                Err(e) => tracing::warn!("Skipping Techmeme: {:#}", e),
+            }
+        }
+        found
+            .into_iter()
+            .flat_map(|stories| {
+                not_bookmarked(stories, bookmarked)
+                    .into_iter()
+                    .take(self.limit)
+            })
+            .collect()
+    }
+}
+
+/// `suggestions` without those in `bookmarked` (URL and title pairs) or
+/// listed earlier, in order.
+pub fn not_bookmarked(
+    suggestions: Vec<Suggestion>,
+    bookmarked: &[(String, String)],
+) -> Vec<Suggestion> {
+    let mut seen: HashSet<String> = bookmarked
+        .iter()
+        .map(|(url, _)| canonicalize_url(url))
+        .collect();
+    let mut titles: Vec<&str> = bookmarked.iter().map(|(_, title)| title.as_str()).collect();
+    let mut kept = Vec::new();
+    for suggestion in &suggestions {
+        let urls = std::iter::once(&suggestion.url).chain(&suggestion.discussion);
+        let urls: Vec<String> = urls.map(|url| canonicalize_url(url)).collect();
+        if urls.iter().any(|url| seen.contains(url))
+            || titles
+                .iter()
+                .any(|title| similarity(title, &suggestion.title) >= TITLE_THRESHOLD)
+        {
+            continue;
+        }
+        seen.extend(urls);
+        titles.push(&suggestion.title);
+        kept.push(suggestion.clone());
+    }
+    kept
+}
+
+/// The org section listing `suggestions`, or nothing when there are none.
+pub fn org_section(suggestions: &[Suggestion]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    let mut org = format!(
+        "* {}\n\nNot in this week's bookmarks. Add one with =briefing add-story URL=.\n\n",
+        SECTION_TITLE
+    );
+    for suggestion in suggestions {
+        org.push_str(&format!("- {}\n", suggestion.org_line()));
+    }
+    org.push('\n');
+    org
+}
+
+async fn hacker_news(
+    client: &Client,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    min_points: u64,
+) -> Result<Vec<Suggestion>> {
+    let url = format!(
+        "{}?tags=story&hitsPerPage={}&numericFilters=created_at_i>{},created_at_i<{},points>={}",
+        HACKER_NEWS_SEARCH,
+        HACKER_NEWS_HITS,
+        since.timestamp(),
+        until.timestamp(),
+        min_points
+    );
+    let results: Value = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {}", url))?;
+    Ok(hacker_news_stories(&results))
+}
+
+/// The stories of a Hacker News search reply, most points first. Ask HN
+/// and other posts without a link point to their thread.
+fn hacker_news_stories(results: &Value) -> Vec<Suggestion> {
+    let mut stories: Vec<Suggestion> = results["hits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|hit| {
+            let title = hit["title"].as_str()?.trim();
+            let thread = format!("{}{}", HACKER_NEWS_ITEM, hit["objectID"].as_str()?);
+            let (url, discussion) = match hit["url"].as_str().filter(|url| !url.is_empty()) {
+                Some(url) => (url.to_string(), Some(thread)),
+                None => (thread, None),
+            };
+            (!title.is_empty()).then(|| Suggestion {
+                title: title.to_string(),
+                url,
+                source: "Hacker News".to_string(),
+                points: hit["points"].as_u64(),
+                discussion,
+            })
+        })
+        .collect();
+    stories.sort_by_key(|s| std::cmp::Reverse(s.points));
+    stories
+}
+
+async fn techmeme(
+    client: &Client,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<Suggestion>> {
+    let feed = client
+        .get(TECHMEME_FEED)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", TECHMEME_FEED))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch {}", TECHMEME_FEED))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read {}", TECHMEME_FEED))?;
+    Ok(techmeme_stories(&feed, since, until))
+}
+
+/// The items of Techmeme's RSS feed published between `since` and `until`,
+/// in feed order. Each links to the article it leads with rather than to
+/// Techmeme, and the `(Author / Site)` credit is dropped from the title.
+fn techmeme_stories(feed: &str, since: DateTime<Utc>, until: DateTime<Utc>) -> Vec<Suggestion> {
+    feed.split("<item>")
+        .skip(1)
+        .filter_map(|item| {
+            let item = item.split("</item>").next()?;
+            let published = DateTime::parse_from_rfc2822(&element(item, "pubDate")?).ok()?;
+            if published < since || published > until {
+                return None;
+            }
+            let title = element(item, "title")?;
+            let title = match title.rsplit_once(" (") {
+                Some((headline, credit)) if credit.ends_with(')') && credit.contains('/') => {
+                    headline
+                }
+                _ => &title,
+            };
+            let url = element(item, "description")
+                .and_then(|html| article_link(&html))
+                .or_else(|| element(item, "link"))?;
+            Some(Suggestion {
+                title: title.trim().to_string(),
+                url,
+                source: "Techmeme".to_string(),
+                points: None,
+                discussion: None,
+            })
+        })
+        .collect()
+}
+
+/// The text of the first `<tag>` in `xml`, unwrapped and unescaped.
+fn element(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    let text = xml[start..end].trim();
+    let text = match text
+        .strip_prefix("<![CDATA[")
+        .and_then(|t| t.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.to_string(),
+        None => text
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&"),
+    };
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// The first link in `html` that leaves Techmeme.
+fn article_link(html: &str) -> Option<String> {
+    let document = scraper::Html::parse_fragment(html);
+    let anchors = scraper::Selector::parse("a[href]").ok()?;
+    let link = document
+        .select(&anchors)
+        .filter_map(|a| a.value().attr("href"))
+        .find(|href| {
+            href.starts_with("http")
+                && crate::clustering::source_domain(href).as_deref() != Some("techmeme.com")
+        })
+        .map(str::to_string);
+    link
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(title: &str, url: &str) -> Suggestion {
+        Suggestion {
+            title: title.to_string(),
+            url: url.to_string(),
+            source: "Hacker News".to_string(),
+            points: Some(300),
+            discussion: None,
+        }
+    }
+
+    #[test]
+    fn test_hacker_news_stories() {
+        let results = serde_json::json!({"hits": [
+            {"title": "Show HN: A tiny database", "url": "https://example.com/db",
+             "points": 120, "objectID": "41"},
+            {"title": "Ask HN: What are you working on?", "url": null,
+             "points": 480, "objectID": "42"},
+            {"title": "", "url": "https://example.com/blank", "points": 900, "objectID": "43"}
+        ]});
+        let stories = hacker_news_stories(&results);
+        assert_eq!(stories.len(), 2);
+        assert_eq!(stories[0].title, "Ask HN: What are you working on?");
+        assert_eq!(stories[0].url, "https://news.ycombinator.com/item?id=42");
+        assert_eq!(stories[0].discussion, None);
+        assert_eq!(stories[1].url, "https://example.com/db");
+        assert_eq!(
+            stories[1].discussion.as_deref(),
+            Some("https://news.ycombinator.com/item?id=41")
+        );
+    }
+
+    #[test]
+    fn test_techmeme_stories() {
+        let feed = r#"<rss><channel><title>Techmeme</title>
+            <item>
+            <title>Apple unveils the M5 MacBook Pro &amp; more (Mark Gurman / Bloomberg)</title>
+            <link>http://www.techmeme.com/261013/p5#a261013p5</link>
+            <description><![CDATA[<A HREF="http://www.techmeme.com/261013/p5#a261013p5"><IMG SRC="x.jpg"></A>
+            <P><A HREF="https://www.bloomberg.com/news/m5">Mark Gurman / Bloomberg</A>:</P>]]></description>
+            <pubDate>Tue, 13 Oct 2026 10:00:00 -0400</pubDate>
+            </item>
+            <item>
+            <title>A story from last month</title>
+            <link>http://www.techmeme.com/260901/p1</link>
+            <pubDate>Tue, 01 Sep 2026 10:00:00 -0400</pubDate>
+            </item>
+            </channel></rss>"#;
+        let since = "2026-10-07T00:00:00Z".parse().unwrap();
+        let until = "2026-10-14T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            techmeme_stories(feed, since, until),
+            [Suggestion {
+                title: "Apple unveils the M5 MacBook Pro & more".to_string(),
+                url: "https://www.bloomberg.com/news/m5".to_string(),
+                source: "Techmeme".to_string(),
+                points: None,
+                discussion: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_not_bookmarked() {
+        let bookmarked = [
+            (
+                "https://www.theverge.com/a?utm_source=rss".to_string(),
+                "Something else".to_string(),
+            ),
+            (
+                "https://example.com/other".to_string(),
+                "Intel lays off 15,000 workers".to_string(),
+            ),
+        ];
+        let mut thread = suggestion("Discussed on HN", "https://blog.example.com/post");
+        thread.discussion = Some("https://news.ycombinator.com/item?id=7".to_string());
+        let kept = not_bookmarked(
+            vec![
+                suggestion("Same link", "https://theverge.com/a"),
+                suggestion("Intel lays off 15,000 workers", "https://ft.com/intel"),
+                suggestion("New to us", "https://arstechnica.com/new"),
+                suggestion("New to us, again", "https://arstechnica.com/new"),
+                thread.clone(),
+            ],
+            &[
+                bookmarked[0].clone(),
+                bookmarked[1].clone(),
+                (
+                    "https://news.ycombinator.com/item?id=7".to_string(),
+                    "HN thread".to_string(),
+                ),
+            ],
+        );
+        let titles: Vec<&str> = kept.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["New to us"]);
+        assert_eq!(not_bookmarked(vec![thread], &bookmarked).len(), 1);
+    }
+
+    #[test]
+    fn test_org_section() {
+        let mut story = suggestion("Rust [2026] edition", "https://example.com/rust");
+        story.discussion = Some("https://news.ycombinator.com/item?id=9".to_string());
+        assert_eq!(
+            org_section(&[story]),
+            "* Stories You Might Have Missed\n\n\
+             Not in this week's bookmarks. Add one with =briefing add-story URL=.\n\n\
+             - [[https://example.com/rust][Rust (2026) edition]] (Hacker News, 300 points, \
+             [[https://news.ycombinator.com/item?id=9][comments]])\n\n"
+        );
+        assert_eq!(org_section(&[]), "");
+    }
+}
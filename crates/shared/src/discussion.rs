@@ -0,0 +1,350 @@
+//! Hacker News and Reddit threads.
+//!
+//! A bookmark of a discussion is really a bookmark of the article under
+//! discussion plus what people said about it. The thread comes from each
+//! site's public JSON API, and the summarizer gets the linked article (or,
+//! for Ask HN and self posts, the post itself) followed by a
+//! [`REACTION_HEADING`] section of the top comments, which it condenses
+//! into the summary's REACTION line.
+
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use reqwest::Client;
+use scraper::Html;
+use serde_json::Value;
+
+use crate::extractor::ArticleContent;
+use crate::media::paragraphs;
+
+/// Opens the comments section of the summarizer's input.
+pub const REACTION_HEADING: &str = "Community reaction";
+
+/// Top-level comments passed on, in the site's ranking.
+const TOP_COMMENTS: usize = 8;
+/// Longer comments are cut to this many characters.
+const MAX_COMMENT_CHARS: usize = 600;
+
+const HACKER_NEWS_API: &str = "https://hacker-news.firebaseio.com/v0";
+
+/// A link to a discussion thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Thread {
+    HackerNews { id: String },
+    Reddit { id: String },
+}
+
+/// Which thread `url` links to, if any.
+pub fn detect(url: &str) -> Option<Thread> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.trim_start_matches("www.");
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match (host, segments.as_slice()) {
+        ("news.ycombinator.com", ["item"]) => {
+            let id = parsed
+                .query_pairs()
+                .find(|(key, _)| key == "id")
+                .map(|(_, value)| value.into_owned())?;
+            (!id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+                .then_some(Thread::HackerNews { id })
+        }
+        (
+            "reddit.com" | "old.reddit.com" | "new.reddit.com" | "np.reddit.com",
+            ["r", _, "comments", id, ..] | ["comments", id, ..],
+        )
+        | ("redd.it", [id]) => {
+            id.bytes()
+                .all(|b| b.is_ascii_alphanumeric())
+                .then(|| Thread::Reddit {
+                    id: id.to_ascii_lowercase(),
+                })
+        }
+        _ => None,
+    }
+}
+
+/// A thread and its top comments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Discussion {
+    /// `Hacker News` or `Reddit`
+    pub site: &'static str,
+    pub title: String,
+    /// The article being discussed; `None` for a text post
+    pub link: Option<String>,
+    /// The post's own text, for Ask HN and self posts
+    pub text: String,
+    pub author: Option<String>,
+    /// RFC 3339
+    pub published: Option<String>,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub author: String,
+    pub text: String,
+}
+
+impl Discussion {
+    /// The comments as a [`REACTION_HEADING`] section, or an empty string
+    /// if nobody has commented yet.
+    pub fn reaction(&self) -> String {
+        if self.comments.is_empty() {
+            return String::new();
+        }
+        let mut section = format!("{} on {} (top comments):\n", REACTION_HEADING, self.site);
+        for comment in &self.comments {
+            section.push_str(&format!(
+                "\n- {}: {}\n",
+                comment.author,
+                truncate(&comment.text.replace('\n', " "), MAX_COMMENT_CHARS)
+            ));
+        }
+        section
+    }
+
+    /// `article` with the comments after it. The word count stays the
+    /// article's, so the reading time isn't padded by the comments.
+    pub fn with_article(&self, mut article: ArticleContent) -> ArticleContent {
+        let reaction = self.reaction();
+        if !reaction.is_empty() {
+            article.text = format!("{}\n\n{}", article.text.trim_end(), reaction);
+        }
+        article
+    }
+
+    /// The thread on its own, for text posts.
+    pub fn content(&self, url: &str) -> ArticleContent {
+        let body = format!("{}\n\n{}", self.title, self.text.trim());
+        self.with_article(ArticleContent {
+            word_count: body.split_whitespace().count(),
+            text: body,
+            published_date: self.published.clone(),
+            final_url: url.to_string(),
+            author: self.author.clone(),
+            image: None,
+            language: None,
+            translated: false,
+            title: None,
+        })
+    }
+}
+
+/// The thread at `thread`, from the site's API.
+pub async fn fetch(client: &Client, thread: &Thread) -> Result<Discussion> {
+    match thread {
+        Thread::HackerNews { id } => hacker_news(client, id).await,
+        Thread::Reddit { id } => reddit(client, id).await,
+    }
+}
+
+async fn get_json(client: &Client, url: &str) -> Result<Value> {
+    client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {}", url))
+}
+
+async fn hacker_news(client: &Client, id: &str) -> Result<Discussion> {
+    let item = get_json(client, &format!("{}/item/{}.json", HACKER_NEWS_API, id)).await?;
+    // `kids` are in ranked order; dead and deleted ones are dropped later,
+    // so ask for a few spare
+    let kids: Vec<String> = item["kids"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_u64)
+        .take(TOP_COMMENTS * 3 / 2)
+        .map(|kid| format!("{}/item/{}.json", HACKER_NEWS_API, kid))
+        .collect();
+    let comments = join_all(kids.iter().map(|kid| get_json(client, kid))).await;
+    let comments: Vec<Value> = comments.into_iter().filter_map(Result::ok).collect();
+    hacker_news_discussion(&item, &comments).context("Not a Hacker News story")
+}
+
+/// The story `item` with its top-level `comments`, from the HN API.
+fn hacker_news_discussion(item: &Value, comments: &[Value]) -> Option<Discussion> {
+    let title = item["title"].as_str()?.to_string();
+    let comments = comments
+        .iter()
+        .filter(|c| c["deleted"].as_bool() != Some(true) && c["dead"].as_bool() != Some(true))
+        .filter_map(|c| {
+            Some(Comment {
+                author: c["by"].as_str()?.to_string(),
+                text: html_text(c["text"].as_str()?),
+            })
+        })
+        .filter(|c| !c.text.is_empty())
+        .take(TOP_COMMENTS)
+        .collect();
+    Some(Discussion {
+        site: "Hacker News",
+        title,
+        link: item["url"].as_str().map(str::to_string),
+        text: item["text"].as_str().map(html_text).unwrap_or_default(),
+        author: item["by"].as_str().map(str::to_string),
+        published: item["time"]
+            .as_i64()
+            .and_then(|time| chrono::DateTime::from_timestamp(time, 0))
+            .map(|time| time.to_rfc3339()),
+        comments,
+    })
+}
+
+async fn reddit(client: &Client, id: &str) -> Result<Discussion> {
+    let listing = get_json(
+        client,
+        &format!(
+            "https://www.reddit.com/comments/{}.json?sort=top&depth=1&limit={}&raw_json=1",
+            id,
+            TOP_COMMENTS * 2
+        ),
+    )
+    .await?;
+    reddit_discussion(&listing).context("Not a Reddit post")
+}
+
+/// The post and its top-level comments in a `/comments/{id}.json` listing.
+fn reddit_discussion(listing: &Value) -> Option<Discussion> {
+    let post = &listing[0]["data"]["children"][0]["data"];
+    let title = post["title"].as_str()?.to_string();
+    let comments = listing[1]["data"]["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|child| child["kind"] == "t1")
+        .map(|child| &child["data"])
+        .filter(|c| c["stickied"].as_bool() != Some(true))
+        .filter_map(|c| {
+            let author = c["author"].as_str()?;
+            let text = c["body"].as_str()?.trim();
+            let removed = matches!(text, "[deleted]" | "[removed]");
+            (author != "AutoModerator" && !removed && !text.is_empty()).then(|| Comment {
+                author: author.to_string(),
+                text: text.to_string(),
+            })
+        })
+        .take(TOP_COMMENTS)
+        .collect();
+    let is_self = post["is_self"].as_bool() == Some(true);
+    Some(Discussion {
+        site: "Reddit",
+        title,
+        link: post["url"]
+            .as_str()
+            .filter(|_| !is_self)
+            .map(str::to_string),
+        text: post["selftext"].as_str().unwrap_or_default().to_string(),
+        author: post["author"].as_str().map(|a| format!("u/{}", a)),
+        published: post["created_utc"]
+            .as_f64()
+            .and_then(|time| chrono::DateTime::from_timestamp(time as i64, 0))
+            .map(|time| time.to_rfc3339()),
+        comments,
+    })
+}
+
+fn html_text(html: &str) -> String {
+    paragraphs(&Html::parse_fragment(html))
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            detect("https://news.ycombinator.com/item?id=42900000"),
+            Some(Thread::HackerNews {
+                id: "42900000".to_string()
+            })
+        );
+        assert_eq!(detect("https://news.ycombinator.com/news"), None);
+        assert_eq!(detect("https://news.ycombinator.com/item?id=abc"), None);
+        let reddit = Some(Thread::Reddit {
+            id: "1iabc2d".to_string(),
+        });
+        assert_eq!(
+            detect("https://www.reddit.com/r/apple/comments/1iabc2d/vision_pro_recall/"),
+            reddit
+        );
+        assert_eq!(detect("https://old.reddit.com/comments/1iabc2d"), reddit);
+        assert_eq!(detect("https://redd.it/1iabc2d"), reddit);
+        assert_eq!(detect("https://www.reddit.com/r/apple/"), None);
+    }
+
+    #[test]
+    fn test_hacker_news_discussion() {
+        let item = json!({"by": "pg", "title": "Vision Pro recall", "time": 1769976000,
+                          "url": "https://example.com/recall", "kids": [1, 2, 3]});
+        let comments = [
+            json!({"by": "alice", "text": "<p>Saw this coming.</p><p>The strap &amp; the battery.</p>"}),
+            json!({"deleted": true}),
+            json!({"by": "bob", "text": "Dead comment", "dead": true}),
+            json!({"by": "carol", "text": "Good."}),
+        ];
+        let discussion = hacker_news_discussion(&item, &comments).unwrap();
+        assert_eq!(
+            discussion.link.as_deref(),
+            Some("https://example.com/recall")
+        );
+        assert_eq!(
+            discussion.published.as_deref(),
+            Some("2026-02-01T20:00:00+00:00")
+        );
+        assert_eq!(
+            discussion.reaction(),
+            "Community reaction on Hacker News (top comments):\n\n\
+             - alice: Saw this coming. The strap & the battery.\n\n\
+             - carol: Good.\n"
+        );
+    }
+
+    #[test]
+    fn test_reddit_discussion() {
+        let comment = |author: &str, body: &str, stickied: bool| json!({"kind": "t1", "data": {"author": author, "body": body, "stickied": stickied}});
+        let listing = json!([
+            {"data": {"children": [{"data": {"title": "Ask: best NAS?", "is_self": true,
+                "url": "https://www.reddit.com/r/homelab/comments/abc/", "selftext": "Budget is $500.",
+                "author": "someone", "created_utc": 1769976000.0}}]}},
+            {"data": {"children": [
+                comment("AutoModerator", "Read the rules", true),
+                comment("dave", "Synology, every time.", false),
+                comment("erin", "[deleted]", false),
+                {"kind": "more", "data": {}}
+            ]}}
+        ]);
+        let discussion = reddit_discussion(&listing).unwrap();
+        assert_eq!(discussion.link, None);
+        assert_eq!(discussion.author.as_deref(), Some("u/someone"));
+
+        let content = discussion.content("https://redd.it/abc");
+        assert_eq!(
+            content.text,
+            "Ask: best NAS?\n\nBudget is $500.\n\n\
+             Community reaction on Reddit (top comments):\n\n\
+             - dave: Synology, every time.\n"
+        );
+        assert_eq!(content.word_count, 6);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a long comment here", 6), "a long…");
+    }
+}
@@ -0,0 +1,202 @@
+//! Per-domain fetch rules for the extractor.
+//!
+//! Some publishers only serve full articles to a search-engine user agent, need
+//! a consent cookie, or are better fetched from a rewritten URL. Rather than
+//! hard-coding those quirks, `ContentExtractor` reads them from
+//! `~/.config/podcast-briefing/domain_rules.toml`:
+//!
+//! ```toml
+//! [[rule]]
+//! domain = "example.com"          # also matches subdomains
+//! user_agent = "Googlebot/2.1 (+http://www.google.com/bot.html)"
+//! headers = { Referer = "https://www.google.com/" }
+//! cookies = "consent=yes; region=us"
+//! rewrite = [{ from = "/amp/", to = "/" }]
+//!
+//! [[rule]]
+//! domain = "x.com"
+//! skip_extraction = true          # use the Raindrop excerpt instead
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const RULES_FILE: &str = "domain_rules.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DomainRules {
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<DomainRule>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DomainRule {
+    pub domain: String,
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Raw `Cookie` header value; replaces browser cookies for this domain
+    pub cookies: Option<String>,
+    #[serde(default)]
+    pub rewrite: Vec<Rewrite>,
+    /// Don't fetch at all; the story falls back to the bookmark excerpt
+    #[serde(default)]
+    pub skip_extraction: bool,
+}
+
+/// Plain substring replacement applied to the URL before fetching.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rewrite {
+    pub from: String,
+    pub to: String,
+}
+
+impl DomainRules {
+    /// Load rules from the config dir. A missing file means no rules; a file
+    /// that fails to parse is reported and ignored, since the rules only
+    /// tune how pages are fetched and every page can be fetched without them.
+    pub fn load() -> Self {
+        let path = crate::config::config_dir().join(RULES_FILE);
+        match Self::from_file(&path) {
+            Ok(rules) => rules,
+            Err(e) => {
+                tracing::warn!("ignoring {}: {:#}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Invalid domain rules")
+    }
+
+    /// The most specific rule whose domain matches the URL's host (exactly or
+    /// as a parent domain).
+    pub fn for_url(&self, url: &str) -> Option<&DomainRule> {
+        let host = url::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+        self.rules
+            .iter()
+            .map(|rule| (rule.domain(), rule))
+            .filter(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+            .max_by_key(|(domain, _)| domain.len())
+            .map(|(_, rule)| rule)
+    }
+}
+
+impl DomainRule {
+    /// `domain` as it's matched: lowercase, without spaces or a leading dot.
+    fn domain(&self) -> String {
+        self.domain
+            .trim()
+            .trim_start_matches('.')
+            .to_ascii_lowercase()
+    }
+
+    /// Apply this rule's URL rewrites in order.
+    pub fn rewrite_url(&self, url: &str) -> String {
+        self.rewrite
+            .iter()
+            .fold(url.to_string(), |url, r| url.replace(&r.from, &r.to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+        [[rule]]
+        domain = "example.com"
+        user_agent = "Googlebot"
+        headers = { Referer = "https://www.google.com/" }
+        rewrite = [{ from = "/amp/", to = "/" }]
+
+        [[rule]]
+        domain = "news.example.com"
+        cookies = "consent=yes"
+
+        [[rule]]
+        domain = "youtube.com"
+        skip_extraction = true
+    "#;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = DomainRules::from_toml(SAMPLE).unwrap();
+        assert_eq!(rules.rules.len(), 3);
+        assert_eq!(rules.rules[0].user_agent.as_deref(), Some("Googlebot"));
+        assert_eq!(
+            rules.rules[0].headers.get("Referer").map(String::as_str),
+            Some("https://www.google.com/")
+        );
+        assert!(rules.rules[2].skip_extraction);
+    }
+
+    #[test]
+    fn test_for_url_matches_subdomains() {
+        let rules = DomainRules::from_toml(SAMPLE).unwrap();
+        let rule = rules.for_url("https://www.example.com/story").unwrap();
+        assert_eq!(rule.domain, "example.com");
+        assert!(rules.for_url("https://notexample.com/story").is_none());
+    }
+
+    #[test]
+    fn test_for_url_prefers_most_specific() {
+        let rules = DomainRules::from_toml(SAMPLE).unwrap();
+        let rule = rules.for_url("https://news.example.com/a").unwrap();
+        assert_eq!(rule.cookies.as_deref(), Some("consent=yes"));
+    }
+
+    #[test]
+    fn test_for_url_ranks_the_matched_domain() {
+        // The padded parent rule is longer as written but less specific
+        let rules = DomainRules::from_toml(
+            r#"
+            [[rule]]
+            domain = "   .Example.com   "
+            cookies = "parent=yes"
+
+            [[rule]]
+            domain = "news.example.com"
+            cookies = "child=yes"
+            "#,
+        )
+        .unwrap();
+        let rule = rules.for_url("https://news.example.com/a").unwrap();
+        assert_eq!(rule.cookies.as_deref(), Some("child=yes"));
+        let rule = rules.for_url("https://example.com/a").unwrap();
+        assert_eq!(rule.cookies.as_deref(), Some("parent=yes"));
+    }
+
+    #[test]
+    fn test_rewrite_url() {
+        let rules = DomainRules::from_toml(SAMPLE).unwrap();
+        let rule = rules.for_url("https://example.com/amp/story").unwrap();
+        assert_eq!(
+            rule.rewrite_url("https://example.com/amp/story"),
+            "https://example.com/story"
+        );
+    }
+
+    #[test]
+    fn test_invalid_toml_is_error() {
+        assert!(DomainRules::from_toml("[[rule]]\ndomain = 5").is_err());
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let rules = DomainRules::from_file(Path::new("/nonexistent/domain_rules.toml")).unwrap();
+        assert!(rules.rules.is_empty());
+    }
+}
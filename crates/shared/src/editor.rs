@@ -0,0 +1,380 @@
+//! The optional editorial pass (`collect-stories --editorial-pass`).
+//!
+//! Summaries are written one article at a time, so stories in the same topic
+//! can differ in voice and repeat each other when they cover the same event.
+//! The editor sends each topic's summaries back to the model together and
+//! asks for a rewrite as a set: one voice, no repetition between stories,
+//! tighter wording.
+//!
+//! The rewrite is checked story by story. A story keeps its original summary
+//! when the reply leaves it out, changes its format, or fails
+//! [`problems`](crate::summarizer::problems). Quotes are never sent, so they
+//! stay verbatim. Stories with placeholder summaries (AI disabled, excerpt
+//! only, failed) are left alone.
+
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clustering::{Story, Topic};
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind};
+use crate::summarizer::{self, Summary, AI_DISABLED_NOTE, EXCERPT_NOTE};
+use crate::usage::UsageTracker;
+
+const EDIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Attempts per topic before keeping its summaries as they are.
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct SummaryEditor {
+    llm: LlmClient,
+    prompt: Prompt,
+    progress: Progress,
+}
+
+impl SummaryEditor {
+    pub fn new() -> Result<Self> {
+        Ok(SummaryEditor {
+            llm: LlmClient::from_env(EDIT_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Edit),
+            progress: crate::progress::console(),
+        })
+    }
+
+    /// Record token usage for editing requests into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Edit every topic's summaries, returning the topics in their original
+    /// order and the number of summaries that were rewritten.
+    pub async fn edit_topics(&self, topics: Vec<Topic>) -> (Vec<Topic>, usize) {
+        let stage = Stage::Edit;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: topics.len(),
+        });
+        let queued = Arc::new(AtomicUsize::new(topics.len()));
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            queued.clone(),
+        );
+        let edited: Vec<(Topic, usize)> = stream::iter(topics)
+            .map(|topic| {
+                let queued = queued.clone();
+                async move {
+                    let edited = self.edit_topic(topic).await;
+                    queued.fetch_sub(1, Ordering::Relaxed);
+                    edited
+                }
+            })
+            .buffered(crate::rate_limit::MAX_CONCURRENCY)
+            .collect()
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+
+        let changed = edited.iter().map(|(_, n)| n).sum();
+        (
+            edited.into_iter().map(|(topic, _)| topic).collect(),
+            changed,
+        )
+    }
+
+    async fn edit_topic(&self, mut topic: Topic) -> (Topic, usize) {
+        let editable: Vec<usize> = (0..topic.stories.len())
+            .filter(|&i| is_editable(&topic.stories[i].summary))
+            .collect();
+        if editable.is_empty() {
+            return (topic, 0);
+        }
+
+        let stories: Vec<&Story> = editable.iter().map(|&i| &topic.stories[i]).collect();
+        let prompt = self.prompt.render(&[
+            ("topic", topic.title.as_str()),
+            ("stories", request_text(&stories).as_str()),
+        ]);
+
+        let mut reply = None;
+        for attempt in 0..MAX_ATTEMPTS {
+            match self.llm.complete("edit", &prompt, 4096).await {
+                Ok(text) => {
+                    reply = Some(text);
+                    break;
+                }
+                Err(e) => {
+                    if attempt + 1 == MAX_ATTEMPTS {
+                        tracing::warn!(
+                            "Editorial pass failed for \"{}\", keeping its summaries: {}",
+                            topic.title,
+                            e
+                        );
+                        break;
+                    }
+                    let backoff = self.llm.retry_delay(attempt);
+                    self.progress.report(&ProgressEvent::Retrying {
+                        stage: Stage::Edit,
+                        attempt: attempt + 1,
+                        wait_secs: backoff.as_secs(),
+                        error: e.to_string(),
+                    });
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+        let Some(reply) = reply else {
+            return (topic, 0);
+        };
+
+        let rewrites = parse_reply(&reply);
+        let mut changed = 0;
+        for (n, &i) in editable.iter().enumerate() {
+            let story = &mut topic.stories[i];
+            match rewrites.get(n).and_then(|r| accept(story, r.as_deref())) {
+                Some(summary) => {
+                    story.summary = summary;
+                    changed += 1;
+                }
+                None => tracing::debug!(story = %story.title, "kept original summary"),
+            }
+        }
+        (topic, changed)
+    }
+}
+
+/// Only real model summaries are worth editing; placeholders and their notes
+/// would be lost or reworded.
+fn is_editable(summary: &Summary) -> bool {
+    match summary {
+        Summary::Editorial { nutgraf, .. } => {
+            !nutgraf.contains(AI_DISABLED_NOTE) && !nutgraf.contains(EXCERPT_NOTE)
+        }
+        Summary::Product { the_product, .. } => !the_product.contains(EXCERPT_NOTE),
+        Summary::Insufficient | Summary::Failed(_) => false,
+    }
+}
+
+/// The stories in the reply format, numbered from 1, without quotes.
+fn request_text(stories: &[&Story]) -> String {
+    let mut text = String::new();
+    for (n, story) in stories.iter().enumerate() {
+        text.push_str(&format!("STORY {}\nTITLE: {}\n", n + 1, story.title));
+        match &story.summary {
+            Summary::Editorial { lede, nutgraf, .. } => {
+                text.push_str(&format!(
+                    "FORMAT: EDITORIAL\nLEDE: {}\nNUTGRAF: {}\n",
+                    lede, nutgraf
+                ));
+            }
+            Summary::Product {
+                the_product,
+                cost,
+                availability,
+                platforms,
+                ..
+            } => {
+                text.push_str(&format!("FORMAT: PRODUCT\nTHE_PRODUCT: {}\n", the_product));
+                for (name, value) in [
+                    ("COST", cost),
+                    ("AVAILABILITY", availability),
+                    ("PLATFORMS", platforms),
+                ] {
+                    if !value.is_empty() {
+                        text.push_str(&format!("{}: {}\n", name, value));
+                    }
+                }
+            }
+            Summary::Insufficient | Summary::Failed(_) => {}
+        }
+        text.push('\n');
+    }
+    text.trim_end().to_string()
+}
+
+/// The text of each `STORY n` block, indexed by `n - 1`. Numbers missing
+/// from the reply are `None`.
+fn parse_reply(reply: &str) -> Vec<Option<String>> {
+    let mut blocks: Vec<Option<String>> = Vec::new();
+    let mut current: Option<usize> = None;
+    for line in reply.lines() {
+        let header = line
+            .trim()
+            .trim_matches('*')
+            .strip_prefix("STORY ")
+            .and_then(|n| n.trim().trim_end_matches(':').parse::<usize>().ok());
+        if let Some(n) = header.filter(|&n| n > 0) {
+            if blocks.len() < n {
+                blocks.resize(n, None);
+            }
+            blocks[n - 1] = Some(String::new());
+            current = Some(n - 1);
+        } else if let Some(block) = current.and_then(|i| blocks[i].as_mut()) {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    blocks
+}
+
+/// The rewritten summary for `story`, if it is usable: same format, every
+/// required field, and no [`summarizer::problems`]. The original quotes,
+/// reaction and key figures are kept.
+fn accept(story: &Story, rewrite: Option<&str>) -> Option<Summary> {
+    let summary = match (summarizer::parse_fields(rewrite?), &story.summary) {
+        (
+            Summary::Editorial { lede, nutgraf, .. },
+            Summary::Editorial {
+                quotes,
+                reaction,
+                by_the_numbers,
+                ..
+            },
+        ) => Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes: quotes.clone(),
+            reaction: reaction.clone(),
+            by_the_numbers: by_the_numbers.clone(),
+        },
+        (
+            Summary::Product {
+                the_product,
+                cost,
+                availability,
+                platforms,
+                ..
+            },
+            Summary::Product {
+                quotes, reaction, ..
+            },
+        ) => Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes: quotes.clone(),
+            reaction: reaction.clone(),
+        },
+        _ => return None,
+    };
+    summarizer::problems(&summary, Some(&story.title))
+        .is_empty()
+        .then_some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quotes::Quote;
+    use crate::testing::story;
+
+    fn editorial(lede: &str, nutgraf: &str, quote: Option<&str>) -> Summary {
+        Summary::Editorial {
+            lede: lede.to_string(),
+            nutgraf: nutgraf.to_string(),
+            quotes: quote.into_iter().map(Quote::parse).collect(),
+            reaction: None,
+            by_the_numbers: None,
+        }
+    }
+
+    #[test]
+    fn test_request_text_skips_quotes() {
+        let first = story("Apple beats estimates")
+            .with_summary(editorial(
+                "Apple earned more.",
+                "iPhone sales grew.",
+                Some("\"Wow\" -- Tim"),
+            ))
+            .build();
+        let second = story("Vision Pro 2")
+            .with_summary(Summary::Product {
+                the_product: "A headset.".to_string(),
+                cost: "$2,999".to_string(),
+                availability: String::new(),
+                platforms: String::new(),
+                quotes: Vec::new(),
+                reaction: None,
+            })
+            .build();
+        assert_eq!(
+            request_text(&[&first, &second]),
+            "STORY 1\nTITLE: Apple beats estimates\nFORMAT: EDITORIAL\n\
+             LEDE: Apple earned more.\nNUTGRAF: iPhone sales grew.\n\n\
+             STORY 2\nTITLE: Vision Pro 2\nFORMAT: PRODUCT\n\
+             THE_PRODUCT: A headset.\nCOST: $2,999"
+        );
+    }
+
+    #[test]
+    fn test_parse_reply_numbers_blocks() {
+        let reply = "Here you go:\n\n**STORY 2**\nFORMAT: EDITORIAL\nLEDE: b\n\n\
+                     STORY 1:\nFORMAT: EDITORIAL\nLEDE: a\n";
+        let blocks = parse_reply(reply);
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].as_deref().unwrap().contains("LEDE: a"));
+        assert!(blocks[1].as_deref().unwrap().contains("LEDE: b"));
+        assert!(parse_reply("STORY 3\nLEDE: c")[..2]
+            .iter()
+            .all(Option::is_none));
+    }
+
+    #[test]
+    fn test_accept_keeps_quote_and_rejects_bad_rewrites() {
+        let original = story("Apple beats estimates")
+            .with_summary(editorial(
+                "Apple earned more.",
+                "iPhone sales grew.",
+                Some("\"Wow\" -- Tim"),
+            ))
+            .build();
+
+        let summary = accept(
+            &original,
+            Some("FORMAT: EDITORIAL\nLEDE: Apple posted record profit.\nNUTGRAF: iPhones led.\nQUOTE: \"Made up\" -- Nobody"),
+        )
+        .unwrap();
+        assert!(matches!(
+            summary,
+            Summary::Editorial { ref lede, ref quotes, .. }
+                if lede == "Apple posted record profit."
+                    && quotes == &[Quote::parse("\"Wow\" -- Tim")]
+        ));
+
+        // Missing from the reply, format changed, or fails the checks
+        assert!(accept(&original, None).is_none());
+        assert!(accept(&original, Some("FORMAT: PRODUCT\nTHE_PRODUCT: x")).is_none());
+        assert!(accept(
+            &original,
+            Some("FORMAT: EDITORIAL\nLEDE: The article says Apple earned more.\nNUTGRAF: x")
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_placeholders_are_not_editable() {
+        assert!(is_editable(&editorial("a", "b", None)));
+        assert!(!is_editable(&editorial("a", AI_DISABLED_NOTE, None)));
+        assert!(!is_editable(&summarizer::excerpt_summary("An excerpt")));
+        assert!(!is_editable(&Summary::Insufficient));
+    }
+}
@@ -0,0 +1,329 @@
+//! Companies, products and people named in each story
+//! (`[briefing.entities] enabled = true`).
+//!
+//! After summarizing, the model reads each story's title and summary and
+//! lists the companies, products and people it is about (one extra request
+//! per story). Names from the `companies`, `products` and `people` lists in
+//! the config are tagged too, as whole words ignoring case, which is all
+//! that happens without an API key. The tags go with each article to the
+//! clustering prompt, so stories about the same company land in one topic
+//! even when their headlines don't name it, and they are shown as a tag
+//! line under each headline in the briefing. In the org draft they are the
+//! `:COMPANIES:`, `:PRODUCTS:` and `:PEOPLE:` lines of the details drawer,
+//! where they can be corrected.
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clustering::Story;
+use crate::disclosures::{mentions, story_text};
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind};
+use crate::usage::UsageTracker;
+
+/// Names of each kind kept from a reply; more means the model listed
+/// everything in sight.
+const MAX_PER_KIND: usize = 5;
+
+const TAG_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Attempts per story before tagging it with the config lists alone.
+const MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Entities {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub companies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub products: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<String>,
+}
+
+impl Entities {
+    pub fn is_empty(&self) -> bool {
+        self.companies.is_empty() && self.products.is_empty() && self.people.is_empty()
+    }
+
+    /// Every name, companies first, then products, then people.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.companies
+            .iter()
+            .chain(&self.products)
+            .chain(&self.people)
+            .map(String::as_str)
+    }
+
+    /// Add the names of `other` that aren't here yet, ignoring case.
+    pub fn merge(&mut self, other: Entities) {
+        for (mine, theirs) in [
+            (&mut self.companies, other.companies),
+            (&mut self.products, other.products),
+            (&mut self.people, other.people),
+        ] {
+            for name in theirs {
+                if !mine.iter().any(|m| m.eq_ignore_ascii_case(&name)) {
+                    mine.push(name);
+                }
+            }
+        }
+    }
+}
+
+/// The `[briefing.entities]` section of `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EntitySettings {
+    /// Tag every story with the companies, products and people it names
+    pub enabled: bool,
+    /// Names tagged on every story that mentions them, with or without
+    /// the model
+    pub companies: Vec<String>,
+    pub products: Vec<String>,
+    pub people: Vec<String>,
+}
+
+impl EntitySettings {
+    /// The names from the config lists that `story` mentions, in config
+    /// order.
+    pub fn known(&self, story: &Story) -> Entities {
+        let text = story_text(story).to_lowercase();
+        let found = |names: &[String]| -> Vec<String> {
+            names
+                .iter()
+                .filter(|name| mentions(&text, name))
+                .map(|name| name.trim().to_string())
+                .collect()
+        };
+        Entities {
+            companies: found(&self.companies),
+            products: found(&self.products),
+            people: found(&self.people),
+        }
+    }
+
+    /// Tag `stories` with the config lists alone. Returns how many got tags.
+    pub fn tag_known(&self, stories: &mut [Story]) -> usize {
+        let mut tagged = 0;
+        for story in stories {
+            let known = self.known(story);
+            if known.is_empty() {
+                continue;
+            }
+            story
+                .entities
+                .get_or_insert_with(Default::default)
+                .merge(known);
+            tagged += 1;
+        }
+        tagged
+    }
+}
+
+pub struct EntityTagger {
+    llm: LlmClient,
+    prompt: Prompt,
+    progress: Progress,
+}
+
+impl EntityTagger {
+    pub fn new() -> Result<Self> {
+        Ok(EntityTagger {
+            llm: LlmClient::from_env(TAG_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Entities),
+            progress: crate::progress::console(),
+        })
+    }
+
+    /// Record token usage for these requests into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Tag every story that has no tags yet with what the model finds and
+    /// the names from `settings` it mentions. Returns how many got tags.
+    pub async fn tag_stories(&self, stories: &mut [Story], settings: &EntitySettings) -> usize {
+        let wanted: Vec<usize> = (0..stories.len())
+            .filter(|&i| stories[i].entities.is_none())
+            .collect();
+        if wanted.is_empty() {
+            return 0;
+        }
+
+        let stage = Stage::Entities;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: wanted.len(),
+        });
+        let queued = Arc::new(AtomicUsize::new(wanted.len()));
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            queued.clone(),
+        );
+        let found: Vec<(usize, Option<Entities>)> = stream::iter(wanted)
+            .map(|i| {
+                let story = &stories[i];
+                let queued = queued.clone();
+                async move {
+                    let entities = self.tag(story).await;
+                    queued.fetch_sub(1, Ordering::Relaxed);
+                    (i, entities)
+                }
+            })
+            .buffer_unordered(crate::rate_limit::MAX_CONCURRENCY)
+            .collect()
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+
+        let mut tagged = 0;
+        for (i, entities) in found {
+            let mut entities = entities.unwrap_or_default();
+            entities.merge(settings.known(&stories[i]));
+            if !entities.is_empty() {
+                stories[i].entities = Some(entities);
+                tagged += 1;
+            }
+        }
+        tagged
+    }
+
+    async fn tag(&self, story: &Story) -> Option<Entities> {
+        let prompt = self.prompt.render(&[("story", story_text(story).as_str())]);
+        for attempt in 0..MAX_ATTEMPTS {
+            match self.llm.complete("entities", &prompt, 256).await {
+                Ok(reply) => match parse_reply(&reply) {
+                    Ok(entities) => return Some(entities),
+                    Err(e) => {
+                        tracing::warn!("Unusable entities for \"{}\": {:#}", story.title, e);
+                        return None;
+                    }
+                },
+                Err(e) if attempt + 1 < MAX_ATTEMPTS => {
+                    let backoff = self.llm.retry_delay(attempt);
+                    self.progress.report(&ProgressEvent::Retrying {
+                        stage: Stage::Entities,
+                        attempt: attempt + 1,
+                        wait_secs: backoff.as_secs(),
+                        error: e.to_string(),
+                    });
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    tracing::warn!("No entities for \"{}\": {}", story.title, e);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The JSON object in a reply, with blank and repeated names dropped and
+/// each kind cut to [`MAX_PER_KIND`].
+fn parse_reply(reply: &str) -> Result<Entities> {
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => reply,
+    };
+    let parsed: Entities = serde_json::from_str(json).context("Failed to parse entities JSON")?;
+    let mut entities = Entities::default();
+    let clean = |names: Vec<String>| -> Vec<String> {
+        names
+            .into_iter()
+            .map(|n| n.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|n| !n.is_empty())
+            .take(MAX_PER_KIND)
+            .collect()
+    };
+    entities.merge(Entities {
+        companies: clean(parsed.companies),
+        products: clean(parsed.products),
+        people: clean(parsed.people),
+    });
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    #[test]
+    fn test_known_names() {
+        let settings = EntitySettings {
+            enabled: true,
+            companies: vec!["Apple".to_string(), "Meta".to_string()],
+            products: vec!["Vision Pro".to_string()],
+            people: vec!["Tim Cook".to_string()],
+        };
+        let mut stories = vec![
+            story("Apple cuts Vision Pro price")
+                .with_lede("Tim Cook says demand grew.", "It matters.")
+                .build(),
+            story("Metadata rules change")
+                .with_lede("Regulators act.", "It matters.")
+                .build(),
+        ];
+        assert_eq!(settings.tag_known(&mut stories), 1);
+        let entities = stories[0].entities.as_ref().unwrap();
+        assert_eq!(
+            entities.names().collect::<Vec<_>>(),
+            ["Apple", "Vision Pro", "Tim Cook"]
+        );
+        assert_eq!(stories[1].entities, None);
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let reply = r#"Here they are:
+            {"companies": ["Apple", "apple", " "],
+             "products": ["Vision  Pro"],
+             "people": ["Tim Cook"]}"#;
+        assert_eq!(
+            parse_reply(reply).unwrap(),
+            Entities {
+                companies: vec!["Apple".to_string()],
+                products: vec!["Vision Pro".to_string()],
+                people: vec!["Tim Cook".to_string()],
+            }
+        );
+        assert_eq!(parse_reply("{}").unwrap(), Entities::default());
+        assert!(parse_reply("no idea").is_err());
+    }
+
+    #[test]
+    fn test_merge_ignores_case() {
+        let mut entities = Entities {
+            companies: vec!["OpenAI".to_string()],
+            ..Default::default()
+        };
+        entities.merge(Entities {
+            companies: vec!["openai".to_string(), "Nvidia".to_string()],
+            people: vec!["Sam Altman".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(entities.companies, ["OpenAI", "Nvidia"]);
+        assert_eq!(entities.people, ["Sam Altman"]);
+    }
+}
@@ -0,0 +1,199 @@
+//! The final edited rundown as structured data (`prepare-briefing
+//! --export json,yaml`).
+//!
+//! Both formats hold the same [`BriefingData`] as the story files, so the
+//! exported schema (`briefing export-assets`) describes them too. They are
+//! written beside the HTML as `{slug}-{date}-RUNDOWN.json` and `.yaml` for
+//! show-notes generators and the website to pick up.
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::models::BriefingData;
+use crate::timezone::Tz;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Yaml => "yaml",
+        }
+    }
+
+    pub fn render(self, data: &BriefingData) -> Result<String> {
+        match self {
+            ExportFormat::Json => {
+                serde_json::to_string_pretty(data).context("Failed to serialize briefing")
+            }
+            ExportFormat::Yaml => {
+                let value = serde_json::to_value(data).context("Failed to serialize briefing")?;
+                Ok(to_yaml(&value))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "yaml" | "yml" => Ok(ExportFormat::Yaml),
+            _ => Err(format!("unknown export format '{}' (use json, yaml)", s)),
+        }
+    }
+}
+
+pub fn save(
+    content: &str,
+    show_slug: &str,
+    date: DateTime<Tz>,
+    format: ExportFormat,
+) -> Result<PathBuf> {
+    let filepath = crate::output::settings().path(
+        crate::output::Artifact::Html,
+        show_slug,
+        date.date_naive(),
+        "-RUNDOWN",
+        format.extension(),
+    )?;
+
+    crate::io::save_file(&filepath, content).context("Failed to write rundown export")?;
+
+    Ok(filepath)
+}
+
+/// Block-style YAML for a JSON value, keys in alphabetical order. Strings
+/// are always double-quoted, which YAML reads with the same escapes as
+/// JSON.
+fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    if is_block(value) {
+        write_block(&mut out, value, 0);
+    } else {
+        out.push_str(&scalar(value));
+        out.push('\n');
+    }
+    out
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                out.push_str(&pad);
+                out.push_str(&key_text(key));
+                out.push(':');
+                write_child(out, child, indent + 2);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                if is_block(item) {
+                    // `- first: ...` with the rest of the item lined up under it
+                    let mut nested = String::new();
+                    write_block(&mut nested, item, indent + 2);
+                    out.push_str(&pad);
+                    out.push_str("- ");
+                    out.push_str(&nested[indent + 2..]);
+                } else {
+                    out.push_str(&pad);
+                    out.push_str("- ");
+                    out.push_str(&scalar(item));
+                    out.push('\n');
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A mapping value, after its `key:`.
+fn write_child(out: &mut String, value: &Value, indent: usize) {
+    if is_block(value) {
+        out.push('\n');
+        write_block(out, value, indent);
+    } else {
+        out.push(' ');
+        out.push_str(&scalar(value));
+        out.push('\n');
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => Value::String(s.clone()).to_string(),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+fn key_text(key: &str) -> String {
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_yaml() {
+        let value = serde_json::json!({
+            "show": { "name": "TWiT", "tag": "#twit" },
+            "topics": [
+                {
+                    "title": "Apple \"rumors\"",
+                    "stories": [{ "url": "https://a.com", "hosts": ["leo", "andy"] }],
+                    "notes": null
+                }
+            ],
+            "empty": [],
+            "line breaks": "one\ntwo"
+        });
+        assert_eq!(
+            to_yaml(&value),
+            "empty: []\n\
+             \"line breaks\": \"one\\ntwo\"\n\
+             show:\n  name: \"TWiT\"\n  tag: \"#twit\"\n\
+             topics:\n  - notes: null\n    stories:\n      \
+             - hosts:\n          - \"leo\"\n          - \"andy\"\n        url: \"https://a.com\"\n    \
+             title: \"Apple \\\"rumors\\\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("JSON".parse(), Ok(ExportFormat::Json));
+        assert_eq!("yml".parse(), Ok(ExportFormat::Yaml));
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
@@ -1,27 +1,139 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use futures::stream::{self, StreamExt};
 use reqwest::Client;
 use scraper::{Html, Selector};
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::Semaphore;
 
-#[derive(Debug, Clone)]
+use crate::domain_rules::{DomainRule, DomainRules};
+use crate::http_cache::{CachedPage, HttpCache};
+use crate::politeness::{FetchSettings, Politeness};
+use crate::progress::{Progress, ProgressEvent, Stage};
+
+/// With the `headless` feature, static fetches yielding less text than this
+/// are re-rendered in headless Chrome.
+#[cfg(feature = "headless")]
+pub const HEADLESS_FALLBACK_CHARS: usize = 500;
+
+/// Static fetch came back as an empty app shell: let a real browser run the
+/// page's scripts, and keep whichever version yields more text.
+#[cfg(feature = "headless")]
+async fn render_if_empty(
+    url: &str,
+    user_agent: Option<&str>,
+    html: String,
+    text: String,
+) -> (String, String) {
+    if text.trim().len() >= HEADLESS_FALLBACK_CHARS {
+        return (html, text);
+    }
+    match crate::headless::render(url, user_agent).await {
+        Ok(rendered) => {
+            let rendered_text = html2text::from_read(rendered.as_bytes(), 100);
+            if rendered_text.trim().len() > text.trim().len() {
+                return (rendered, rendered_text);
+            }
+        }
+        Err(e) => tracing::warn!("Headless render failed for {}: {:#}", url, e),
+    }
+    (html, text)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArticleContent {
     pub text: String,
+    /// Headline from the page's metadata
+    #[serde(default)]
+    pub title: Option<String>,
     pub published_date: Option<String>,
+    /// URL the article was actually served from, after following redirects
+    pub final_url: String,
+    /// Byline from the page's metadata
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Words in `text`
+    #[serde(default)]
+    pub word_count: usize,
+    /// Absolute URL of the page's share image (og:image)
+    #[serde(default)]
+    pub image: Option<String>,
+    /// ISO 639-1 code of the article's language, from [`crate::language`]
+    #[serde(default)]
+    pub language: Option<String>,
+    /// `text` is an English translation of the article
+    #[serde(default)]
+    pub translated: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExtractionResult {
     Success(ArticleContent),
     Paywalled,
+    /// A domain rule or the site's robots.txt said not to fetch this URL
+    Skipped,
+    /// The fetch time budget ran out before this URL was tried
+    OutOfTime,
     Failed(String),
 }
 
+impl ExtractionResult {
+    /// Short label used in progress events.
+    pub fn outcome(&self) -> &'static str {
+        match self {
+            ExtractionResult::Success(_) => "success",
+            ExtractionResult::Paywalled => "paywalled",
+            ExtractionResult::Skipped => "skipped",
+            ExtractionResult::OutOfTime => "out_of_time",
+            ExtractionResult::Failed(_) => "failed",
+        }
+    }
+}
+
+/// What `ContentExtractor::download` got back.
+enum Download {
+    Page(CachedPage),
+    /// PDFs are turned into text right away and not cached
+    Pdf(ArticleContent),
+}
+
+/// Why a URL couldn't be fetched, for the triage report at the end of a
+/// collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchFailure {
+    /// The site's answer; `None` if it never gave one
+    pub status: Option<u16>,
+    pub error: String,
+    /// Browser cookies or a domain rule's Cookie header went with the request
+    pub cookies: bool,
+}
+
+/// An HTTP error status, kept under the message so the triage report can
+/// tell a 403 from a timeout.
+#[derive(Debug)]
+struct HttpStatus(u16);
+
+impl std::fmt::Display for HttpStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP {}", self.0)
+    }
+}
+
+impl std::error::Error for HttpStatus {}
+
 pub struct ContentExtractor {
     client: Client,
     semaphore: Arc<Semaphore>,
+    rules: DomainRules,
+    politeness: Politeness,
+    cache: Option<HttpCache>,
+    /// Domains the browser has cookies for
+    cookie_domains: Vec<String>,
+    failures: Mutex<HashMap<String, FetchFailure>>,
+    progress: Progress,
+    deadline: Option<Instant>,
 }
 
 impl ContentExtractor {
@@ -30,9 +142,14 @@ impl ContentExtractor {
         let cookie_jar = Arc::new(reqwest::cookie::Jar::default());
 
         // Load Firefox cookies for accessing paywalled sites
+        let mut cookie_domains = Vec::new();
         if let Ok(browser_cookies) = crate::cookies::load_browser_cookies() {
             for cookie in browser_cookies.iter_any() {
                 if let Some(domain) = cookie.domain() {
+                    let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+                    if !cookie_domains.contains(&domain) {
+                        cookie_domains.push(domain.clone());
+                    }
                     let url_str = format!("https://{}", domain);
                     if let Ok(url) = url::Url::parse(&url_str) {
                         let cookie_str = format!("{}={}", cookie.name(), cookie.value());
@@ -44,36 +161,97 @@ impl ContentExtractor {
 
         let client = Client::builder()
             .timeout(std::time::Duration::from_secs(30))
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36")
+            .user_agent(crate::net::BROWSER_USER_AGENT)
             .cookie_provider(cookie_jar)
             .build()
             .context("Failed to create HTTP client")?;
 
         let semaphore = Arc::new(Semaphore::new(10));
+        let rules = DomainRules::load();
+
+        Ok(Self {
+            client,
+            semaphore,
+            rules,
+            politeness: Politeness::default(),
+            cache: HttpCache::open_default(&FetchSettings::default()),
+            cookie_domains,
+            failures: Mutex::default(),
+            progress: crate::progress::console(),
+            deadline: None,
+        })
+    }
+
+    /// Report fetches from `fetch_articles_parallel` to `progress`.
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
 
-        Ok(Self { client, semaphore })
+    /// Start no new fetches in `fetch_articles_parallel` after `deadline`.
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Use the per-site limits, robots.txt setting and cache ages in
+    /// `settings` (`[fetch]`) instead of the defaults.
+    pub fn with_fetch_settings(mut self, settings: FetchSettings) -> Self {
+        let refresh = self.cache.as_ref().is_some_and(HttpCache::is_refreshing);
+        self.cache = HttpCache::open_default(&settings).map(|cache| cache.refreshing(refresh));
+        self.politeness = Politeness::new(settings);
+        self
+    }
+
+    /// Download every page again instead of using the page cache.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.cache = self.cache.map(|cache| cache.refreshing(refresh));
+        self
     }
 
     pub async fn fetch_article_content(&self, url: &str) -> ExtractionResult {
+        let rule = self.rules.for_url(url);
+        if rule.is_some_and(|r| r.skip_extraction) {
+            return ExtractionResult::Skipped;
+        }
+        let user_agent = rule
+            .and_then(|r| r.user_agent.as_deref())
+            .unwrap_or(crate::net::BROWSER_USER_AGENT);
+        if !self.politeness.allows(&self.client, url, user_agent).await {
+            tracing::debug!(url, "disallowed by robots.txt");
+            return ExtractionResult::Skipped;
+        }
+
         let _permit = match self.semaphore.acquire().await {
             Ok(p) => p,
             Err(e) => return ExtractionResult::Failed(e.to_string()),
         };
 
         for attempt in 0..3 {
-            match self.try_fetch_article(url).await {
-                Ok(content) => return ExtractionResult::Success(content),
+            let turn = self.politeness.turn(url, user_agent).await;
+            let fetched = self.try_fetch_article(url).await;
+            drop(turn);
+            match fetched {
+                Ok(mut content) => {
+                    if content.language.is_none() {
+                        content.language = crate::language::detect(None, &content.text);
+                    }
+                    return ExtractionResult::Success(content);
+                }
                 Err(e) => {
                     let error_msg = e.to_string();
                     // Don't retry 403 errors - they're paywalls
                     if error_msg.contains("403") {
+                        self.record_failure(url, &e);
                         return ExtractionResult::Paywalled;
                     }
                     if attempt == 2 {
-                        eprintln!("Failed to fetch {}: {}", url, e);
+                        tracing::warn!("Failed to fetch {}: {}", url, e);
+                        self.record_failure(url, &e);
                         return ExtractionResult::Failed(error_msg);
                     }
                     let backoff = std::time::Duration::from_millis(500 * (2_u64.pow(attempt)));
+                    tracing::debug!(url, attempt = attempt + 1, "fetch failed, retrying: {}", e);
                     tokio::time::sleep(backoff).await;
                 }
             }
@@ -82,37 +260,198 @@ impl ContentExtractor {
         ExtractionResult::Failed("Max retries exceeded".to_string())
     }
 
-    async fn try_fetch_article(&self, url: &str) -> Result<ArticleContent> {
-        let response = self
-            .client
+    fn record_failure(&self, url: &str, error: &anyhow::Error) {
+        let failure = FetchFailure {
+            status: error.downcast_ref::<HttpStatus>().map(|status| status.0),
+            error: error.to_string(),
+            cookies: self.sends_cookies(url),
+        };
+        self.failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(url.to_string(), failure);
+    }
+
+    /// Why `url` couldn't be fetched by this extractor, if it was tried
+    /// and failed or hit a paywall.
+    pub fn failure(&self, url: &str) -> Option<FetchFailure> {
+        self.failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
             .get(url)
+            .cloned()
+    }
+
+    /// Whether a request for `url` carries cookies.
+    pub fn sends_cookies(&self, url: &str) -> bool {
+        if self.rules.for_url(url).is_some_and(|r| r.cookies.is_some()) {
+            return true;
+        }
+        let Some(host) = url::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        else {
+            return false;
+        };
+        self.cookie_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    }
+
+    /// The article `discussion` links to with the comments after it, or the
+    /// post itself for a text post.
+    async fn discussed_article(
+        &self,
+        discussion: &crate::discussion::Discussion,
+        url: &str,
+    ) -> Result<ArticleContent> {
+        let link = discussion
+            .link
+            .as_deref()
+            .filter(|link| crate::discussion::detect(link).is_none());
+        match link {
+            Some(link) => {
+                let article = Box::pin(self.try_fetch_article(link)).await?;
+                Ok(discussion.with_article(article))
+            }
+            None => Ok(discussion.content(url)),
+        }
+    }
+
+    async fn try_fetch_article(&self, url: &str) -> Result<ArticleContent> {
+        let rule = self.rules.for_url(url);
+        let url = rule.map_or_else(|| url.to_string(), |r| r.rewrite_url(url));
+
+        // Videos and podcast episodes have their text somewhere other than
+        // the page
+        if let Some(media) = crate::media::detect(&url) {
+            match crate::media::fetch(&self.client, &media, &url).await {
+                Ok(content) => return Ok(content),
+                Err(e) => tracing::debug!(url, "{:?}: {:#}; fetching the page", media, e),
+            }
+        }
+
+        // Hacker News and Reddit: the linked article and the top comments
+        if let Some(thread) = crate::discussion::detect(&url) {
+            match crate::discussion::fetch(&self.client, &thread).await {
+                Ok(discussion) => return self.discussed_article(&discussion, &url).await,
+                Err(e) => tracing::debug!(url, "{:?}: {:#}; fetching the page", thread, e),
+            }
+        }
+
+        let cache = self.cache.as_ref();
+        let page = match cache.and_then(|cache| cache.get(&url)) {
+            Some(page) if cache.is_some_and(|cache| cache.is_fresh(&page)) => page,
+            cached => match self.download(rule, &url, cached).await? {
+                Download::Page(page) => {
+                    if let Some(cache) = cache {
+                        cache.store(&page);
+                    }
+                    page
+                }
+                Download::Pdf(content) => return Ok(content),
+            },
+        };
+        self.article_from_page(rule, page).await
+    }
+
+    /// Fetch `url`, or with `cached`, ask whether it has changed since.
+    async fn download(
+        &self,
+        rule: Option<&DomainRule>,
+        url: &str,
+        cached: Option<CachedPage>,
+    ) -> Result<Download> {
+        let mut request = self.client.get(url);
+        if let Some(rule) = rule {
+            if let Some(ua) = &rule.user_agent {
+                request = request.header(reqwest::header::USER_AGENT, ua);
+            }
+            // An explicit Cookie header takes precedence over the browser jar
+            if let Some(cookies) = &rule.cookies {
+                request = request.header(reqwest::header::COOKIE, cookies);
+            }
+            for (name, value) in &rule.headers {
+                request = request.header(name, value);
+            }
+        }
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request
             .send()
             .await
             .context("Failed to send HTTP request")?;
 
         let status = response.status();
+        if let (304, Some(cached)) = (status.as_u16(), cached) {
+            return Ok(Download::Page(CachedPage {
+                fetched: Utc::now().timestamp(),
+                ..cached
+            }));
+        }
+        let final_url = response.url().to_string();
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let content_language = header(reqwest::header::CONTENT_LANGUAGE);
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
 
         // Provide specific error messages for common HTTP status codes
-        match status.as_u16() {
-            401 => anyhow::bail!("Access denied (401 Unauthorized) - requires login"),
-            403 => anyhow::bail!(
-                "Access forbidden (403 Forbidden) - may be paywalled or blocking bots"
-            ),
-            404 => anyhow::bail!("Page not found (404) - article may have been removed"),
-            429 => anyhow::bail!("Rate limited (429) - too many requests"),
-            500..=599 => anyhow::bail!("Server error ({}) - website is having issues", status),
-            _ if !status.is_success() => anyhow::bail!("HTTP error: {}", status),
-            _ => {}
+        let message = match status.as_u16() {
+            401 => "Access denied (401 Unauthorized) - requires login".to_string(),
+            403 => {
+                "Access forbidden (403 Forbidden) - may be paywalled or blocking bots".to_string()
+            }
+            404 => "Page not found (404) - article may have been removed".to_string(),
+            429 => "Rate limited (429) - too many requests".to_string(),
+            500..=599 => format!("Server error ({}) - website is having issues", status),
+            _ if !status.is_success() => format!("HTTP error: {}", status),
+            _ => String::new(),
+        };
+        if !message.is_empty() {
+            return Err(anyhow::Error::new(HttpStatus(status.as_u16())).context(message));
         }
 
         // Only parse HTML/text bodies; skip binaries (PDFs, images, downloads)
         // that would otherwise be force-decoded and fed to the HTML parser.
-        let content_type = response
-            .headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("")
+        let content_type = header(reqwest::header::CONTENT_TYPE)
+            .unwrap_or_default()
             .to_ascii_lowercase();
+        if crate::pdf::is_pdf(&content_type, &final_url) {
+            let body = crate::net::read_body_capped(response, crate::net::MAX_BODY_BYTES)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read response body: {}", e))?;
+            let text = crate::pdf::text(&body).await?;
+            if text.trim().len() < 100 {
+                anyhow::bail!(
+                    "Content too short ({} chars) - may be a scanned PDF",
+                    text.trim().len()
+                );
+            }
+            return Ok(Download::Pdf(ArticleContent {
+                word_count: text.split_whitespace().count(),
+                text,
+                published_date: None,
+                final_url,
+                author: None,
+                image: None,
+                language: None,
+                translated: false,
+                title: None,
+            }));
+        }
         if !content_type.is_empty()
             && !content_type.contains("html")
             && !content_type.contains("text/")
@@ -124,14 +463,47 @@ impl ContentExtractor {
         let body = crate::net::read_body_capped(response, crate::net::MAX_BODY_BYTES)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to read response body: {}", e))?;
-        let html = String::from_utf8_lossy(&body).into_owned();
+        Ok(Download::Page(CachedPage {
+            url: url.to_string(),
+            final_url,
+            content_type,
+            content_language,
+            etag,
+            last_modified,
+            fetched: Utc::now().timestamp(),
+            body: String::from_utf8_lossy(&body).into_owned(),
+        }))
+    }
 
-        // Extract publication date from HTML meta tags
-        let published_date = self.extract_published_date(&html);
+    #[cfg_attr(not(feature = "headless"), allow(unused_variables))]
+    async fn article_from_page(
+        &self,
+        rule: Option<&DomainRule>,
+        page: CachedPage,
+    ) -> Result<ArticleContent> {
+        let CachedPage {
+            final_url,
+            content_language,
+            body: html,
+            ..
+        } = page;
 
         // Convert HTML to text
         let text = html2text::from_read(html.as_bytes(), 100);
 
+        #[cfg(feature = "headless")]
+        let (html, text) = {
+            let user_agent = rule.and_then(|r| r.user_agent.as_deref());
+            render_if_empty(&final_url, user_agent, html, text).await
+        };
+
+        // Extract publication date and byline from HTML meta tags
+        let published_date = self.extract_published_date(&html);
+        let author = extract_author(&html);
+        let title = extract_title(&html);
+        let image = extract_image(&html, &final_url);
+        let declared = extract_language(&html).or(content_language);
+
         if text.trim().is_empty() {
             anyhow::bail!("No text content extracted - may require JavaScript or login");
         }
@@ -144,8 +516,15 @@ impl ContentExtractor {
         }
 
         Ok(ArticleContent {
+            word_count: text.split_whitespace().count(),
+            language: crate::language::detect(declared.as_deref(), &text),
             text,
             published_date,
+            image,
+            final_url,
+            author,
+            title,
+            translated: false,
         })
     }
 
@@ -207,18 +586,153 @@ impl ContentExtractor {
         &self,
         urls: Vec<String>,
     ) -> Vec<(String, ExtractionResult)> {
-        stream::iter(urls)
-            .map(|url| {
-                let url_clone = url.clone();
-                async move {
-                    let result = self.fetch_article_content(&url).await;
-                    (url_clone, result)
-                }
+        let stage = Stage::Extract;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: urls.len(),
+        });
+        let urls = crate::politeness::interleave(urls);
+        if let Some(cache) = &self.cache {
+            let pruned = cache.prune();
+            if pruned > 0 {
+                tracing::debug!("Deleted {} expired page(s) from the page cache", pruned);
+            }
+        }
+        let (mut results, out_of_time) =
+            crate::budget::run_until(urls, 10, self.deadline, |url| async move {
+                self.progress
+                    .report(&ProgressEvent::FetchStarted { url: url.clone() });
+                let result = self.fetch_article_content(&url).await;
+                self.progress.report(&ProgressEvent::FetchDone {
+                    url: url.clone(),
+                    outcome: result.outcome(),
+                });
+                (url, result)
             })
-            .buffer_unordered(10)
-            .collect()
-            .await
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+        if !out_of_time.is_empty() {
+            tracing::warn!(
+                "Fetch time budget ran out; {} article(s) not fetched",
+                out_of_time.len()
+            );
+        }
+        results.extend(
+            out_of_time
+                .into_iter()
+                .map(|url| (url, ExtractionResult::OutOfTime)),
+        );
+        results
+    }
+}
+
+/// The article's byline: the first of the usual author meta tags or
+/// markup that holds a name rather than a profile URL.
+/// The language the page says it is in.
+fn extract_language(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selectors = [
+        ("html[lang]", "lang"),
+        (r#"meta[property="og:locale"]"#, "content"),
+        (r#"meta[http-equiv="content-language" i]"#, "content"),
+    ];
+    selectors.iter().find_map(|(selector, attr)| {
+        let selector = Selector::parse(selector).ok()?;
+        let element = document.select(&selector).next()?;
+        let value = element.value().attr(attr)?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn extract_author(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selectors = [
+        r#"meta[name="author"]"#,
+        r#"meta[property="article:author"]"#,
+        r#"meta[name="parsely-author"]"#,
+        r#"meta[name="sailthru.author"]"#,
+        r#"meta[name="byl"]"#,
+        r#"[itemprop="author"] [itemprop="name"]"#,
+        r#"[rel="author"]"#,
+    ];
+    for selector_str in selectors {
+        let Ok(selector) = Selector::parse(selector_str) else {
+            continue;
+        };
+        for element in document.select(&selector) {
+            let text = match element.value().attr("content") {
+                Some(content) => content.to_string(),
+                None => element.text().collect(),
+            };
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let name = text
+                .strip_prefix("By ")
+                .or_else(|| text.strip_prefix("by "))
+                .unwrap_or(&text);
+            if !name.is_empty() && !name.contains("://") {
+                return Some(name.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// The page's headline, from og:title or the equivalents, else `<title>`.
+fn extract_title(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selectors = [
+        (r#"meta[property="og:title"]"#, Some("content")),
+        (r#"meta[name="twitter:title"]"#, Some("content")),
+        (r#"meta[property="twitter:title"]"#, Some("content")),
+        ("head title", None),
+    ];
+    selectors.iter().find_map(|(selector, attr)| {
+        let selector = Selector::parse(selector).ok()?;
+        let element = document.select(&selector).next()?;
+        let text = match attr {
+            Some(attr) => element.value().attr(attr)?.to_string(),
+            None => element.text().collect(),
+        };
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!text.is_empty()).then_some(text)
+    })
+}
+
+/// The page's share image, from og:image or the equivalents, resolved
+/// against `page_url`.
+fn extract_image(html: &str, page_url: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selectors = [
+        (r#"meta[property="og:image:secure_url"]"#, "content"),
+        (r#"meta[property="og:image"]"#, "content"),
+        (r#"meta[name="og:image"]"#, "content"),
+        (r#"meta[name="twitter:image"]"#, "content"),
+        (r#"meta[property="twitter:image"]"#, "content"),
+        (r#"link[rel="image_src"]"#, "href"),
+    ];
+    let base = url::Url::parse(page_url).ok();
+    for (selector_str, attr) in selectors {
+        let Ok(selector) = Selector::parse(selector_str) else {
+            continue;
+        };
+        for element in document.select(&selector) {
+            let Some(value) = element.value().attr(attr).map(str::trim) else {
+                continue;
+            };
+            if value.is_empty() {
+                continue;
+            }
+            let resolved = match &base {
+                Some(base) => base.join(value).ok(),
+                None => url::Url::parse(value).ok(),
+            };
+            if let Some(url) = resolved.filter(|u| matches!(u.scheme(), "http" | "https")) {
+                return Some(url.to_string());
+            }
+        }
     }
+    None
 }
 
 #[cfg(test)]
@@ -231,6 +745,13 @@ mod tests {
         let extractor = ContentExtractor {
             client: reqwest::Client::new(),
             semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            deadline: None,
+            rules: DomainRules::default(),
+            politeness: Politeness::default(),
+            cache: None,
+            cookie_domains: Vec::new(),
+            failures: Mutex::default(),
+            progress: Arc::new(crate::progress::NoProgress),
         };
         extractor.format_date(date_str)
     }
@@ -273,11 +794,91 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_extract_author() {
+        assert_eq!(
+            extract_author(r#"<meta name="author" content="Jane Doe">"#).as_deref(),
+            Some("Jane Doe")
+        );
+        // A profile URL is skipped for the name in the markup
+        let html = r#"
+            <meta property="article:author" content="https://example.com/staff/jd">
+            <span itemprop="author"><span itemprop="name">By  Jane
+                Doe</span></span>
+        "#;
+        assert_eq!(extract_author(html).as_deref(), Some("Jane Doe"));
+        assert_eq!(extract_author("<p>No byline</p>"), None);
+    }
+
+    #[test]
+    fn test_extract_title() {
+        let html = r#"
+            <head><title>Straps recalled | Example News</title>
+            <meta property="og:title" content="Apple recalls  Vision Pro straps"></head>
+        "#;
+        assert_eq!(
+            extract_title(html).as_deref(),
+            Some("Apple recalls Vision Pro straps")
+        );
+        assert_eq!(
+            extract_title("<head><title>\n  Straps recalled\n</title></head>").as_deref(),
+            Some("Straps recalled")
+        );
+        assert_eq!(extract_title("<p>No title</p>"), None);
+    }
+
+    #[test]
+    fn test_extract_language() {
+        assert_eq!(
+            extract_language(r#"<html lang="de-DE"><body></body></html>"#).as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(
+            extract_language(r#"<meta property="og:locale" content="fr_FR">"#).as_deref(),
+            Some("fr_FR")
+        );
+        assert_eq!(
+            extract_language(r#"<meta http-equiv="Content-Language" content="es">"#).as_deref(),
+            Some("es")
+        );
+        assert_eq!(extract_language("<p>Nothing declared</p>"), None);
+    }
+
+    #[test]
+    fn test_extract_image() {
+        let page = "https://example.com/news/story";
+        assert_eq!(
+            extract_image(
+                r#"<meta property="og:image" content="https://cdn.example.com/a.jpg">"#,
+                page
+            )
+            .as_deref(),
+            Some("https://cdn.example.com/a.jpg")
+        );
+        // Relative paths resolve against the page; data URIs are skipped
+        let html = r#"
+            <meta property="og:image" content="data:image/png;base64,AAAA">
+            <meta name="twitter:image" content="/img/b.png">
+        "#;
+        assert_eq!(
+            extract_image(html, page).as_deref(),
+            Some("https://example.com/img/b.png")
+        );
+        assert_eq!(extract_image("<p>No image</p>", page), None);
+    }
+
     #[test]
     fn test_extract_published_date_og_tag() {
         let extractor = ContentExtractor {
             client: reqwest::Client::new(),
             semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            deadline: None,
+            rules: DomainRules::default(),
+            politeness: Politeness::default(),
+            cache: None,
+            cookie_domains: Vec::new(),
+            failures: Mutex::default(),
+            progress: Arc::new(crate::progress::NoProgress),
         };
 
         let html = r#"
@@ -297,6 +898,13 @@ mod tests {
         let extractor = ContentExtractor {
             client: reqwest::Client::new(),
             semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            deadline: None,
+            rules: DomainRules::default(),
+            politeness: Politeness::default(),
+            cache: None,
+            cookie_domains: Vec::new(),
+            failures: Mutex::default(),
+            progress: Arc::new(crate::progress::NoProgress),
         };
 
         let html = r#"
@@ -316,6 +924,13 @@ mod tests {
         let extractor = ContentExtractor {
             client: reqwest::Client::new(),
             semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            deadline: None,
+            rules: DomainRules::default(),
+            politeness: Politeness::default(),
+            cache: None,
+            cookie_domains: Vec::new(),
+            failures: Mutex::default(),
+            progress: Arc::new(crate::progress::NoProgress),
         };
 
         let html = r#"
@@ -328,11 +943,41 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_skip_extraction_rule() {
+        let extractor = ContentExtractor {
+            client: reqwest::Client::new(),
+            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            deadline: None,
+            rules: DomainRules::from_toml(
+                "[[rule]]\ndomain = \"youtube.com\"\nskip_extraction = true",
+            )
+            .unwrap(),
+            politeness: Politeness::default(),
+            cache: None,
+            cookie_domains: Vec::new(),
+            failures: Mutex::default(),
+            progress: Arc::new(crate::progress::NoProgress),
+        };
+
+        let result = extractor
+            .fetch_article_content("https://www.youtube.com/watch?v=abc")
+            .await;
+        assert!(matches!(result, ExtractionResult::Skipped));
+    }
+
     #[test]
     fn test_article_content_struct() {
         let content = ArticleContent {
             text: "Article text".to_string(),
             published_date: Some("2026-02-01T00:00:00+00:00".to_string()),
+            final_url: "https://example.com/article".to_string(),
+            author: None,
+            word_count: 2,
+            image: None,
+            language: None,
+            translated: false,
+            title: None,
         };
 
         assert_eq!(content.text, "Article text");
@@ -347,6 +992,13 @@ mod tests {
         let success = ExtractionResult::Success(ArticleContent {
             text: "text".to_string(),
             published_date: None,
+            final_url: "https://example.com".to_string(),
+            author: None,
+            word_count: 1,
+            image: None,
+            language: None,
+            translated: false,
+            title: None,
         });
         assert!(matches!(success, ExtractionResult::Success(_)));
 
@@ -356,4 +1008,61 @@ mod tests {
         let failed = ExtractionResult::Failed("error".to_string());
         assert!(matches!(failed, ExtractionResult::Failed(_)));
     }
+
+    #[tokio::test]
+    async fn test_page_cache_revalidates() {
+        use crate::testing::{MockServer, Response};
+
+        let server = MockServer::start(|request| match request.header("If-None-Match") {
+            Some("\"v1\"") => Response::status(304),
+            _ => Response::html(&format!("<p>{}</p>", "Cached story text. ".repeat(10)))
+                .with_header("ETag", "\"v1\""),
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let extractor = |fresh_hours| ContentExtractor {
+            client: reqwest::Client::new(),
+            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
+            deadline: None,
+            rules: DomainRules::default(),
+            politeness: Politeness::default(),
+            cache: HttpCache::at(
+                dir.path(),
+                &FetchSettings {
+                    fresh_hours,
+                    ..Default::default()
+                },
+            ),
+            cookie_domains: Vec::new(),
+            failures: Mutex::default(),
+            progress: Arc::new(crate::progress::NoProgress),
+        };
+        let url = format!("{}/story", server.url());
+        let text = |result| match result {
+            ExtractionResult::Success(content) => content.text,
+            other => panic!("{:?}", other),
+        };
+
+        // Stale after 0 hours: asked again, and the 304 reuses the page
+        let first = text(extractor(0).fetch_article_content(&url).await);
+        let second = text(extractor(0).fetch_article_content(&url).await);
+        assert_eq!(first, second);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].header("If-None-Match"), Some("\"v1\""));
+
+        // Fresh: not asked at all
+        text(extractor(12).fetch_article_content(&url).await);
+        assert_eq!(server.requests().len(), 2);
+
+        // Refreshing: downloaded without the ETag
+        text(
+            extractor(12)
+                .with_refresh(true)
+                .fetch_article_content(&url)
+                .await,
+        );
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].header("If-None-Match"), None);
+    }
 }
@@ -0,0 +1,506 @@
+//! Story filter expressions.
+//!
+//! Curation rules live in the `[filter]` section of `config.toml` as small
+//! boolean expressions, checked against each bookmark before any article is
+//! fetched or summarized. A bookmark is kept only if every expression is true:
+//!
+//! ```toml
+//! [filter]
+//! keep = [
+//!     'domain != "youtube.com" && age_days < 10',
+//!     '!tags.contains("skip")',
+//! ]
+//! ```
+//!
+//! The language has string, number, boolean and list values; `&&`, `||`, `!`,
+//! the six comparison operators, parentheses, and the methods `contains`,
+//! `starts_with`, `ends_with` and `len`. See `FIELDS` for the variables.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::raindrop::Bookmark;
+
+/// Variables available to filter expressions.
+pub const FIELDS: &[&str] = &[
+    "title", "url", "domain", "excerpt", "note", "tags", "age_days",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Str(_) => "string",
+            Value::Num(_) => "number",
+            Value::Bool(_) => "bool",
+            Value::List(_) => "list",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(&'static str),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Value),
+    Var(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(&'static str, Box<Expr>, Box<Expr>),
+    Method(Box<Expr>, String, Vec<Expr>),
+}
+
+/// The `[filter]` section of `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Filters {
+    /// Keep a bookmark only if all of these are true
+    pub keep: Vec<String>,
+}
+
+impl Filters {
+    pub fn compile(&self) -> Result<Vec<Filter>> {
+        self.keep.iter().map(|src| Filter::parse(src)).collect()
+    }
+}
+
+/// A parsed filter expression.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+impl Filter {
+    /// Parse an expression, rejecting unknown variables up front so a typo
+    /// fails before any fetching starts.
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source).with_context(|| format!("In filter `{}`", source))?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser
+            .parse_or()
+            .and_then(|expr| match parser.peek() {
+                None => Ok(expr),
+                Some(tok) => anyhow::bail!("Unexpected {:?}", tok),
+            })
+            .with_context(|| format!("In filter `{}`", source))?;
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn matches(&self, fields: &HashMap<&'static str, Value>) -> Result<bool> {
+        match eval(&self.expr, fields).with_context(|| format!("In filter `{}`", self.source))? {
+            Value::Bool(b) => Ok(b),
+            other => anyhow::bail!(
+                "Filter `{}` evaluated to a {}, not a bool",
+                self.source,
+                other.type_name()
+            ),
+        }
+    }
+}
+
+/// The variables a bookmark exposes to filters.
+pub fn bookmark_fields(bookmark: &Bookmark, now: DateTime<Utc>) -> HashMap<&'static str, Value> {
+    let domain = url::Url::parse(&bookmark.link)
+        .ok()
+        .and_then(|u| {
+            u.host_str()
+                .map(|h| h.trim_start_matches("www.").to_string())
+        })
+        .unwrap_or_default();
+    let age_days = DateTime::parse_from_rfc3339(&bookmark.created)
+        .map(|created| (now - created.with_timezone(&Utc)).num_minutes() as f64 / 1440.0)
+        .unwrap_or(0.0);
+
+    HashMap::from([
+        ("title", Value::Str(bookmark.title.clone())),
+        ("url", Value::Str(bookmark.link.clone())),
+        ("domain", Value::Str(domain)),
+        (
+            "excerpt",
+            Value::Str(bookmark.excerpt.clone().unwrap_or_default()),
+        ),
+        (
+            "note",
+            Value::Str(bookmark.note.clone().unwrap_or_default()),
+        ),
+        ("tags", Value::List(bookmark.tags.clone())),
+        ("age_days", Value::Num(age_days)),
+    ])
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => anyhow::bail!("Unterminated string"),
+                    Some('\\') => {
+                        s.extend(chars.get(i + 1));
+                        i += 2;
+                    }
+                    Some(&ch) if ch == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&ch) => {
+                        s.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let num = text
+                .parse()
+                .with_context(|| format!("Invalid number {}", text))?;
+            tokens.push(Token::Num(num));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let op = ["&&", "||", "==", "!=", "<=", ">="]
+                .into_iter()
+                .find(|op| *op == two)
+                .or_else(|| {
+                    ["!", "<", ">", "(", ")", ".", ","]
+                        .into_iter()
+                        .find(|op| op.starts_with(c))
+                })
+                .with_context(|| format!("Unexpected character '{}'", c))?;
+            i += op.len();
+            tokens.push(Token::Op(op));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<()> {
+        if !self.eat(op) {
+            anyhow::bail!("Expected '{}'", op);
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat("||") {
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_comparison()?;
+        while self.eat("&&") {
+            left = Expr::And(Box::new(left), Box::new(self.parse_comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr> {
+        let left = self.parse_unary()?;
+        for op in ["==", "!=", "<=", ">=", "<", ">"] {
+            if self.eat(op) {
+                let right = self.parse_unary()?;
+                return Ok(Expr::Compare(op, Box::new(left), Box::new(right)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        let mut expr = self.parse_primary()?;
+        while self.eat(".") {
+            let method = match self.next() {
+                Some(Token::Ident(name)) => name,
+                _ => anyhow::bail!("Expected a method name after '.'"),
+            };
+            self.expect("(")?;
+            let mut args = Vec::new();
+            if !self.eat(")") {
+                loop {
+                    args.push(self.parse_or()?);
+                    if self.eat(")") {
+                        break;
+                    }
+                    self.expect(",")?;
+                }
+            }
+            expr = Expr::Method(Box::new(expr), method, args);
+        }
+        Ok(expr)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::Str(s))),
+            Some(Token::Num(n)) => Ok(Expr::Literal(Value::Num(n))),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                _ if FIELDS.contains(&name.as_str()) => Ok(Expr::Var(name)),
+                _ => anyhow::bail!(
+                    "Unknown variable '{}' (available: {})",
+                    name,
+                    FIELDS.join(", ")
+                ),
+            },
+            Some(Token::Op("(")) => {
+                let expr = self.parse_or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(tok) => anyhow::bail!("Unexpected {:?}", tok),
+            None => anyhow::bail!("Unexpected end of expression"),
+        }
+    }
+}
+
+fn eval(expr: &Expr, fields: &HashMap<&'static str, Value>) -> Result<Value> {
+    Ok(match expr {
+        Expr::Literal(v) => v.clone(),
+        Expr::Var(name) => fields
+            .get(name.as_str())
+            .cloned()
+            .with_context(|| format!("Variable '{}' is not set", name))?,
+        Expr::Not(inner) => Value::Bool(!as_bool(eval(inner, fields)?)?),
+        // Short-circuit, so `a && b` never evaluates a failing `b` needlessly
+        Expr::And(l, r) => Value::Bool(as_bool(eval(l, fields)?)? && as_bool(eval(r, fields)?)?),
+        Expr::Or(l, r) => Value::Bool(as_bool(eval(l, fields)?)? || as_bool(eval(r, fields)?)?),
+        Expr::Compare(op, l, r) => compare(op, eval(l, fields)?, eval(r, fields)?)?,
+        Expr::Method(target, name, args) => {
+            let target = eval(target, fields)?;
+            let args = args
+                .iter()
+                .map(|a| eval(a, fields))
+                .collect::<Result<Vec<_>>>()?;
+            call_method(target, name, &args)?
+        }
+    })
+}
+
+fn as_bool(value: Value) -> Result<bool> {
+    match value {
+        Value::Bool(b) => Ok(b),
+        other => anyhow::bail!("Expected a bool, found a {}", other.type_name()),
+    }
+}
+
+fn compare(op: &str, left: Value, right: Value) -> Result<Value> {
+    use std::cmp::Ordering;
+
+    let ordering = match (&left, &right) {
+        (Value::Num(a), Value::Num(b)) => a.partial_cmp(b),
+        (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) if matches!(op, "==" | "!=") => Some(a.cmp(b)),
+        _ => None,
+    }
+    .with_context(|| {
+        format!(
+            "Can't compare a {} with a {} using '{}'",
+            left.type_name(),
+            right.type_name(),
+            op
+        )
+    })?;
+
+    Ok(Value::Bool(match op {
+        "==" => ordering == Ordering::Equal,
+        "!=" => ordering != Ordering::Equal,
+        "<" => ordering == Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        _ => ordering != Ordering::Less,
+    }))
+}
+
+fn call_method(target: Value, name: &str, args: &[Value]) -> Result<Value> {
+    let arg = || match args {
+        [Value::Str(s)] => Ok(s.as_str()),
+        _ => anyhow::bail!("{}() takes one string argument", name),
+    };
+
+    Ok(match (name, &target) {
+        // Tag matching ignores case, as Raindrop tags do
+        ("contains", Value::List(items)) => {
+            let needle = arg()?;
+            Value::Bool(items.iter().any(|item| item.eq_ignore_ascii_case(needle)))
+        }
+        ("contains", Value::Str(s)) => Value::Bool(s.contains(arg()?)),
+        ("starts_with", Value::Str(s)) => Value::Bool(s.starts_with(arg()?)),
+        ("ends_with", Value::Str(s)) => Value::Bool(s.ends_with(arg()?)),
+        ("len", Value::Str(s)) if args.is_empty() => Value::Num(s.chars().count() as f64),
+        ("len", Value::List(items)) if args.is_empty() => Value::Num(items.len() as f64),
+        _ => anyhow::bail!("No method {}() on a {}", name, target.type_name()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bookmark(link: &str, tags: &[&str], created: &str) -> Bookmark {
+        Bookmark {
+            id: 1,
+            title: "Apple announces new iPhone".to_string(),
+            link: link.to_string(),
+            excerpt: None,
+            note: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created: created.to_string(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 11, 0, 0, 0).unwrap()
+    }
+
+    fn check(expr: &str, b: &Bookmark) -> bool {
+        Filter::parse(expr)
+            .unwrap()
+            .matches(&bookmark_fields(b, now()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_example_expression() {
+        let expr = r#"domain != "youtube.com" && age_days < 10 && !tags.contains("skip")"#;
+        let fresh = bookmark(
+            "https://www.theverge.com/a",
+            &["twit"],
+            "2026-01-08T00:00:00Z",
+        );
+        assert!(check(expr, &fresh));
+
+        let video = bookmark(
+            "https://www.youtube.com/watch?v=x",
+            &[],
+            "2026-01-08T00:00:00Z",
+        );
+        assert!(!check(expr, &video));
+
+        let stale = bookmark("https://theverge.com/a", &[], "2025-12-01T00:00:00Z");
+        assert!(!check(expr, &stale));
+
+        let skipped = bookmark("https://theverge.com/a", &["SKIP"], "2026-01-08T00:00:00Z");
+        assert!(!check(expr, &skipped));
+    }
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        let b = bookmark("https://example.com/a", &[], "2026-01-10T00:00:00Z");
+        assert!(check("true || false && false", &b));
+        assert!(!check("(true || false) && false", &b));
+        assert!(check("!false == true", &b));
+    }
+
+    #[test]
+    fn test_string_methods() {
+        let b = bookmark(
+            "https://news.example.com/a",
+            &["a", "b"],
+            "2026-01-10T00:00:00Z",
+        );
+        assert!(check(r#"domain.ends_with("example.com")"#, &b));
+        assert!(check(r#"title.contains("iPhone") && tags.len() == 2"#, &b));
+        assert!(check("url.starts_with('https://')", &b));
+    }
+
+    #[test]
+    fn test_unknown_variable_is_parse_error() {
+        let err = Filter::parse("age < 10").unwrap_err();
+        assert!(format!("{:#}", err).contains("Unknown variable 'age'"));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(Filter::parse("age_days < ").is_err());
+        assert!(Filter::parse("(true").is_err());
+        assert!(Filter::parse("title == \"x").is_err());
+        assert!(Filter::parse("true true").is_err());
+        assert!(Filter::parse("title # 3").is_err());
+    }
+
+    #[test]
+    fn test_type_errors_surface_at_eval() {
+        let b = bookmark("https://example.com/a", &[], "2026-01-10T00:00:00Z");
+        let fields = bookmark_fields(&b, now());
+        assert!(Filter::parse("age_days < \"x\"")
+            .unwrap()
+            .matches(&fields)
+            .is_err());
+        assert!(Filter::parse("title").unwrap().matches(&fields).is_err());
+    }
+}
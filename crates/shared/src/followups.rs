@@ -0,0 +1,243 @@
+//! "Previously" recaps for follow-up stories (`[briefing] followup_recaps
+//! = true`).
+//!
+//! collect-stories marks a story as a follow-up when an earlier briefing
+//! had it (see [`crate::archive::Archive::previously_covered`]). For each
+//! of those, the model gets the earlier story's title and lede and this
+//! week's, and writes one sentence recapping the earlier coverage. It's
+//! shown under the story's headline in the org file and the HTML briefing,
+//! and can be edited or deleted like any summary.
+
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::archive::EarlierStory;
+use crate::clustering::{Story, Topic};
+use crate::dedup::canonicalize_url;
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind};
+use crate::summarizer::Summary;
+use crate::usage::UsageTracker;
+
+/// Longer replies are treated as the model ignoring the brief.
+const MAX_WORDS: usize = 50;
+
+const FOLLOWUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Attempts per story before leaving it without a recap.
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct FollowupWriter {
+    llm: LlmClient,
+    prompt: Prompt,
+    progress: Progress,
+}
+
+impl FollowupWriter {
+    pub fn new() -> Result<Self> {
+        Ok(FollowupWriter {
+            llm: LlmClient::from_env(FOLLOWUP_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Followup),
+            progress: crate::progress::console(),
+        })
+    }
+
+    /// Record token usage for these requests into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Fill in `followup` for every story in `earlier` (keyed by canonical
+    /// URL) that doesn't have a recap yet. Returns how many were written.
+    pub async fn add_recaps(
+        &self,
+        topics: &mut [Topic],
+        earlier: &HashMap<String, EarlierStory>,
+    ) -> usize {
+        let wanted: Vec<(usize, usize, &EarlierStory)> = topics
+            .iter()
+            .enumerate()
+            .flat_map(|(t, topic)| {
+                topic
+                    .stories
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(s, story)| {
+                        if story.followup.is_some() {
+                            return None;
+                        }
+                        Some((t, s, earlier.get(&canonicalize_url(&story.url))?))
+                    })
+            })
+            .collect();
+        if wanted.is_empty() {
+            return 0;
+        }
+
+        let stage = Stage::Followup;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: wanted.len(),
+        });
+        let queued = Arc::new(AtomicUsize::new(wanted.len()));
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            queued.clone(),
+        );
+        let recaps: Vec<(usize, usize, Option<String>)> = stream::iter(wanted)
+            .map(|(t, s, then)| {
+                let story = &topics[t].stories[s];
+                let queued = queued.clone();
+                async move {
+                    let recap = self.recap(story, then).await;
+                    queued.fetch_sub(1, Ordering::Relaxed);
+                    (t, s, recap)
+                }
+            })
+            .buffer_unordered(crate::rate_limit::MAX_CONCURRENCY)
+            .collect()
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+
+        let mut written = 0;
+        for (t, s, recap) in recaps {
+            if recap.is_some() {
+                written += 1;
+            }
+            topics[t].stories[s].followup = recap;
+        }
+        written
+    }
+
+    async fn recap(&self, story: &Story, earlier: &EarlierStory) -> Option<String> {
+        let prompt = self.prompt.render(&[
+            ("story", story_text(story).as_str()),
+            (
+                "earlier",
+                format!("{}: {}", earlier.coverage, story_text(&earlier.story)).as_str(),
+            ),
+        ]);
+        for attempt in 0..MAX_ATTEMPTS {
+            match self.llm.complete("followup", &prompt, 256).await {
+                Ok(reply) => {
+                    let recap = clean(&reply);
+                    if recap.is_none() {
+                        tracing::warn!(
+                            "Unusable follow-up recap for \"{}\": {}",
+                            story.title,
+                            reply.trim()
+                        );
+                    }
+                    return recap;
+                }
+                Err(e) if attempt + 1 < MAX_ATTEMPTS => {
+                    let backoff = self.llm.retry_delay(attempt);
+                    self.progress.report(&ProgressEvent::Retrying {
+                        stage: Stage::Followup,
+                        attempt: attempt + 1,
+                        wait_secs: backoff.as_secs(),
+                        error: e.to_string(),
+                    });
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    tracing::warn!("No follow-up recap for \"{}\": {}", story.title, e);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The title and lede; stories without a summary get the title alone.
+fn story_text(story: &Story) -> String {
+    let lede = match &story.summary {
+        Summary::Editorial { lede, .. } => lede.as_str(),
+        Summary::Product { the_product, .. } => the_product.as_str(),
+        Summary::Insufficient | Summary::Failed(_) => "",
+    };
+    if lede.is_empty() {
+        story.title.clone()
+    } else {
+        format!("{} - {}", story.title, lede)
+    }
+}
+
+/// The reply as one line, without a "Previously:" label or wrapping quotes,
+/// or `None` if it is empty or far too long.
+fn clean(reply: &str) -> Option<String> {
+    let text = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = text.trim_start_matches(['*', '#']).trim();
+    let text = match text.split_once(':') {
+        Some((label, rest)) if label.to_lowercase().contains("previously") => rest,
+        _ => text,
+    };
+    let text = text
+        .trim_matches(|c: char| c == '*' || c.is_whitespace())
+        .trim_matches('"')
+        .trim();
+    let words = text.split_whitespace().count();
+    (words > 0 && words <= MAX_WORDS).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_story_text() {
+        let mut story = Story {
+            title: "Apple recalls Vision Pro".to_string(),
+            url: String::new(),
+            summary: Summary::Editorial {
+                lede: "Straps were faulty.".to_string(),
+                nutgraf: "It matters.".to_string(),
+                quotes: Vec::new(),
+                reaction: None,
+                by_the_numbers: None,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            story_text(&story),
+            "Apple recalls Vision Pro - Straps were faulty."
+        );
+        story.summary = Summary::Insufficient;
+        assert_eq!(story_text(&story), "Apple recalls Vision Pro");
+    }
+
+    #[test]
+    fn test_clean_reply() {
+        assert_eq!(
+            clean("**Previously:** \"Apple recalled\nthe straps.\"\n").as_deref(),
+            Some("Apple recalled the straps.")
+        );
+        assert_eq!(
+            clean("On TWiT: Apple recalled the straps.").as_deref(),
+            Some("On TWiT: Apple recalled the straps.")
+        );
+        assert_eq!(clean("  \n"), None);
+        assert_eq!(clean(&"word ".repeat(MAX_WORDS + 1)), None);
+    }
+}
@@ -0,0 +1,100 @@
+//! Headless Chrome rendering for pages that build their content in JavaScript.
+//!
+//! Only compiled with the `headless` feature. Rather than driving the browser
+//! over the DevTools protocol, this shells out to `chrome --headless
+//! --dump-dom`, which runs the page's scripts and prints the resulting DOM.
+//! Set `BRIEFING_CHROME` to pick a specific browser binary.
+
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Browser binaries tried in order when `BRIEFING_CHROME` isn't set.
+const CANDIDATES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+];
+
+/// Hard limit on a single render, including browser startup.
+const RENDER_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Find a usable Chrome/Chromium binary.
+pub fn find_chrome() -> Option<String> {
+    if let Ok(path) = std::env::var("BRIEFING_CHROME") {
+        if !path.trim().is_empty() {
+            return Some(path);
+        }
+    }
+    CANDIDATES
+        .iter()
+        .find(|name| {
+            std::process::Command::new(name)
+                .arg("--version")
+                .output()
+                .is_ok_and(|out| out.status.success())
+        })
+        .map(|name| name.to_string())
+}
+
+fn chrome_args(url: &str, user_agent: Option<&str>) -> Vec<String> {
+    let mut args = vec![
+        "--headless=new".to_string(),
+        "--disable-gpu".to_string(),
+        "--no-first-run".to_string(),
+        "--mute-audio".to_string(),
+        // Give scripts time to fetch and render the article body
+        "--virtual-time-budget=10000".to_string(),
+        "--dump-dom".to_string(),
+    ];
+    if let Some(ua) = user_agent {
+        args.push(format!("--user-agent={}", ua));
+    }
+    args.push(url.to_string());
+    args
+}
+
+/// Load `url` in headless Chrome and return the rendered HTML.
+pub async fn render(url: &str, user_agent: Option<&str>) -> Result<String> {
+    let chrome = find_chrome()
+        .context("No Chrome/Chromium found for headless rendering (set BRIEFING_CHROME)")?;
+
+    let child = Command::new(&chrome)
+        .args(chrome_args(url, user_agent))
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(RENDER_TIMEOUT, child)
+        .await
+        .context("Headless render timed out")?
+        .with_context(|| format!("Failed to run {}", chrome))?;
+
+    if !output.status.success() {
+        anyhow::bail!("Headless render exited with {}", output.status);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chrome_args_end_with_url() {
+        let args = chrome_args("https://example.com/a", Some("Bot/1.0"));
+        assert!(args.contains(&"--dump-dom".to_string()));
+        assert!(args.contains(&"--user-agent=Bot/1.0".to_string()));
+        assert_eq!(args.last().unwrap(), "https://example.com/a");
+    }
+
+    #[test]
+    fn test_chrome_args_without_user_agent() {
+        let args = chrome_args("https://example.com/a", None);
+        assert!(!args.iter().any(|a| a.starts_with("--user-agent")));
+    }
+}
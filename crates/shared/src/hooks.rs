@@ -0,0 +1,242 @@
+//! External command hooks.
+//!
+//! Site-specific steps (posting to a CMS, pinging the studio dashboard) are
+//! configured as shell commands in the `[hooks]` section of `config.toml`:
+//!
+//! ```toml
+//! [hooks]
+//! post_collect = ["~/bin/notify-producer"]
+//! pre_upload = ["~/bin/check-briefing"]   # non-zero exit cancels the upload
+//! post_upload = ["~/bin/post-to-cms --section news"]
+//! digest = ["mail -s 'Briefing readiness' producer@example.com <"]
+//! ```
+//!
+//! Each command runs through `sh -c` with the artifact paths appended as
+//! arguments, `BRIEFING_HOOK` set to the hook name, and a JSON `HookPayload`
+//! on stdin. A command still running after [`HOOK_TIMEOUT`] is killed and
+//! counts as failed.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// How long one hook command may run.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPoint {
+    /// After collect-stories saves the org-mode draft
+    PostCollect,
+    /// Before prepare-briefing uploads the HTML and CSV
+    PreUpload,
+    /// After a successful upload
+    PostUpload,
+    /// After `collect-stories --digest` writes the weekly readiness digest
+    Digest,
+}
+
+impl HookPoint {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookPoint::PostCollect => "post-collect",
+            HookPoint::PreUpload => "pre-upload",
+            HookPoint::PostUpload => "post-upload",
+            HookPoint::Digest => "digest",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub post_collect: Vec<String>,
+    pub pre_upload: Vec<String>,
+    pub post_upload: Vec<String>,
+    pub digest: Vec<String>,
+}
+
+/// JSON document written to each hook's stdin.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookPayload {
+    pub hook: &'static str,
+    pub show: String,
+    pub show_slug: String,
+    pub show_date: String,
+    pub artifacts: Vec<PathBuf>,
+    /// Hook-specific extras (story counts, upload URLs, ...)
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value,
+}
+
+impl Hooks {
+    pub fn commands(&self, point: HookPoint) -> &[String] {
+        match point {
+            HookPoint::PostCollect => &self.post_collect,
+            HookPoint::PreUpload => &self.pre_upload,
+            HookPoint::PostUpload => &self.post_upload,
+            HookPoint::Digest => &self.digest,
+        }
+    }
+
+    /// Run every command configured for `point` in order, stopping at the
+    /// first one that fails. Returns how many commands ran successfully.
+    pub async fn run(&self, point: HookPoint, payload: &HookPayload) -> Result<usize> {
+        self.run_within(point, payload, HOOK_TIMEOUT).await
+    }
+
+    async fn run_within(
+        &self,
+        point: HookPoint,
+        payload: &HookPayload,
+        timeout: Duration,
+    ) -> Result<usize> {
+        let commands = self.commands(point);
+        if commands.is_empty() {
+            return Ok(0);
+        }
+
+        let json = serde_json::to_vec(payload).context("Failed to serialize hook payload")?;
+        for command in commands {
+            run_command(command, point, &payload.artifacts, &json, timeout)
+                .await
+                .with_context(|| format!("{} hook `{}` failed", point.name(), command))?;
+        }
+        Ok(commands.len())
+    }
+}
+
+async fn run_command(
+    command: &str,
+    point: HookPoint,
+    artifacts: &[PathBuf],
+    payload: &[u8],
+    timeout: Duration,
+) -> Result<()> {
+    // `$0` is the hook name, so `"$@"` expands to exactly the artifact paths
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", command))
+        .arg(point.name())
+        .args(artifacts)
+        .env("BRIEFING_HOOK", point.name())
+        .stdin(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start hook")?;
+
+    // Write from a task so a hook that never reads stdin can't deadlock us
+    let mut stdin = child.stdin.take().context("Failed to open hook stdin")?;
+    let payload = payload.to_vec();
+    let writer = tokio::spawn(async move {
+        // A hook that exits without reading stdin is fine
+        let _ = stdin.write_all(&payload).await;
+    });
+
+    let status = tokio::time::timeout(timeout, child.wait()).await;
+    writer.abort();
+    // Dropping `child` on a timeout kills it
+    let status = status
+        .map_err(|_| anyhow!("timed out after {}s", timeout.as_secs()))?
+        .context("Failed to wait for hook")?;
+
+    if !status.success() {
+        anyhow::bail!("exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn payload(artifacts: Vec<PathBuf>) -> HookPayload {
+        HookPayload {
+            hook: HookPoint::PostCollect.name(),
+            show: "This Week in Tech".to_string(),
+            show_slug: "twit".to_string(),
+            show_date: "2026-01-04".to_string(),
+            artifacts,
+            details: serde_json::json!({ "stories": 12 }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hook_receives_args_and_payload() {
+        let dir = tempdir().unwrap();
+        let out = dir.path().display();
+        let hooks = Hooks {
+            post_collect: vec![format!(
+                "f() {{ cat > {out}/stdin.json; echo \"$BRIEFING_HOOK $*\" > {out}/args; }}; f"
+            )],
+            ..Default::default()
+        };
+
+        let ran = hooks
+            .run(
+                HookPoint::PostCollect,
+                &payload(vec![PathBuf::from("/tmp/twit-2026-01-04.org")]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ran, 1);
+
+        let args = std::fs::read_to_string(dir.path().join("args")).unwrap();
+        assert_eq!(args.trim(), "post-collect /tmp/twit-2026-01-04.org");
+
+        let stdin = std::fs::read_to_string(dir.path().join("stdin.json")).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&stdin).unwrap();
+        assert_eq!(json["show_slug"], "twit");
+        assert_eq!(json["details"]["stories"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook_stops_the_chain() {
+        let dir = tempdir().unwrap();
+        let marker = dir.path().join("second-ran");
+        let hooks = Hooks {
+            pre_upload: vec!["exit 3".to_string(), format!("touch {}", marker.display())],
+            ..Default::default()
+        };
+
+        let err = hooks
+            .run(HookPoint::PreUpload, &payload(Vec::new()))
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("pre-upload hook `exit 3` failed"));
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_slow_hook_times_out() {
+        let hooks = Hooks {
+            post_upload: vec!["sleep 10".to_string()],
+            ..Default::default()
+        };
+
+        let started = std::time::Instant::now();
+        let err = hooks
+            .run_within(
+                HookPoint::PostUpload,
+                &payload(Vec::new()),
+                Duration::from_millis(200),
+            )
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("post-upload hook `sleep 10` failed: timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_no_commands_is_noop() {
+        let ran = Hooks::default()
+            .run(HookPoint::PostUpload, &payload(Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(ran, 0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Downloaded article pages, kept in the data dir (`http-cache/`).
+//!
+//! Running collection again, re-summarizing a story in `briefing review` or
+//! starting `briefing run --from extract` would otherwise download every
+//! article again. A page fetched in the last `fresh_hours` is reused as is;
+//! an older one is asked for again with its `ETag` and `Last-Modified`, and
+//! a `304 Not Modified` reuses it without the download. Pages older than
+//! `cache_days` are deleted. `collect-stories --refresh` skips the cache
+//! and downloads everything (and caches the new copies).
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::politeness::FetchSettings;
+
+/// A page as it was downloaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPage {
+    pub url: String,
+    /// After redirects
+    pub final_url: String,
+    pub content_type: String,
+    #[serde(default)]
+    pub content_language: Option<String>,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Unix time of the download, or of the last `304`
+    pub fetched: i64,
+    pub body: String,
+}
+
+impl CachedPage {
+    fn age(&self) -> Duration {
+        let seconds = chrono::Utc::now().timestamp() - self.fetched;
+        Duration::from_secs(seconds.max(0) as u64)
+    }
+}
+
+pub struct HttpCache {
+    root: PathBuf,
+    fresh_for: Duration,
+    keep_for: Duration,
+    refresh: bool,
+}
+
+impl HttpCache {
+    /// The cache in the data dir, with `settings`' ages; `None` when
+    /// `cache_days` is 0.
+    pub fn open_default(settings: &FetchSettings) -> Option<Self> {
+        Self::at(crate::config::data_dir().join("http-cache"), settings)
+    }
+
+    pub fn at(root: impl Into<PathBuf>, settings: &FetchSettings) -> Option<Self> {
+        (settings.cache_days > 0).then(|| Self {
+            root: root.into(),
+            fresh_for: Duration::from_secs(settings.fresh_hours * 60 * 60),
+            keep_for: Duration::from_secs(settings.cache_days * 24 * 60 * 60),
+            refresh: false,
+        })
+    }
+
+    /// Ignore what's cached, but still cache new downloads.
+    pub fn refreshing(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.refresh
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        self.root
+            .join(format!("{:x}.json", Sha256::digest(url.as_bytes())))
+    }
+
+    /// The cached copy of `url`, unless it has expired.
+    pub fn get(&self, url: &str) -> Option<CachedPage> {
+        if self.refresh {
+            return None;
+        }
+        let json = std::fs::read_to_string(self.path_for(url)).ok()?;
+        let page: CachedPage = serde_json::from_str(&json).ok()?;
+        (page.url == url && page.age() < self.keep_for).then_some(page)
+    }
+
+    /// Whether `page` is recent enough to use without asking the site.
+    pub fn is_fresh(&self, page: &CachedPage) -> bool {
+        page.age() < self.fresh_for
+    }
+
+    /// Keep `page` for next time. Failures are only logged; the cache is
+    /// an optimization.
+    pub fn store(&self, page: &CachedPage) {
+        let path = self.path_for(&page.url);
+        let result = std::fs::create_dir_all(&self.root)
+            .and_then(|_| std::fs::write(&path, serde_json::to_string(page).unwrap_or_default()));
+        if let Err(e) = result {
+            tracing::warn!("Could not save {}: {}", path.display(), e);
+        }
+    }
+
+    /// Delete pages older than `cache_days`. Returns how many.
+    pub fn prune(&self) -> usize {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return 0;
+        };
+        entries
+            .flatten()
+            .filter(|entry| {
+                let expired = std::fs::read_to_string(entry.path())
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<CachedPage>(&json).ok())
+                    .is_none_or(|page| page.age() >= self.keep_for);
+                expired && std::fs::remove_file(entry.path()).is_ok()
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, age: Duration) -> CachedPage {
+        CachedPage {
+            url: url.to_string(),
+            final_url: url.to_string(),
+            content_type: "text/html".to_string(),
+            content_language: None,
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            fetched: chrono::Utc::now().timestamp() - age.as_secs() as i64,
+            body: "<p>Story</p>".to_string(),
+        }
+    }
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    #[test]
+    fn test_round_trip_and_freshness() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::at(dir.path(), &FetchSettings::default()).unwrap();
+        assert_eq!(cache.get("https://example.com/a"), None);
+
+        let fresh = page("https://example.com/a", HOUR);
+        cache.store(&fresh);
+        assert_eq!(cache.get("https://example.com/a"), Some(fresh.clone()));
+        assert!(cache.is_fresh(&fresh));
+        assert!(!cache.is_fresh(&page("https://example.com/a", HOUR * 48)));
+
+        let refreshing = HttpCache::at(dir.path(), &FetchSettings::default())
+            .unwrap()
+            .refreshing(true);
+        assert_eq!(refreshing.get("https://example.com/a"), None);
+    }
+
+    #[test]
+    fn test_prune_expired_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::at(dir.path(), &FetchSettings::default()).unwrap();
+        let old = page("https://example.com/old", HOUR * 24 * 30);
+        cache.store(&old);
+        cache.store(&page("https://example.com/new", HOUR));
+        assert_eq!(cache.get("https://example.com/old"), None);
+
+        assert_eq!(cache.prune(), 1);
+        assert!(cache.get("https://example.com/new").is_some());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_cache_days_zero_disables() {
+        let settings = FetchSettings {
+            cache_days: 0,
+            ..Default::default()
+        };
+        assert!(HttpCache::at("/nonexistent", &settings).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Story thumbnails for `prepare-briefing --images`.
+//!
+//! Each story's share image (the `:IMAGE:` line collect-stories records) is
+//! downloaded into a `{slug}-{date}-images` folder next to the briefing and
+//! shrunk to [`THUMBNAIL_WIDTH`] with ImageMagick when it's installed. The
+//! HTML embeds the thumbnails as data URIs, so the uploaded index.html
+//! still works on its own.
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use chrono::DateTime;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::clustering::Topic;
+use crate::timezone::Tz;
+
+/// Widest a thumbnail is made, in pixels.
+pub const THUMBNAIL_WIDTH: u32 = 240;
+
+/// Largest image downloaded; anything bigger is skipped.
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Where the images for a briefing go, beside the HTML, e.g.
+/// `~/Documents/twit-2026-02-01-images`.
+pub fn output_dir(show_slug: &str, date: DateTime<Tz>) -> PathBuf {
+    crate::output::settings()
+        .dir(crate::output::Artifact::Html)
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join(format!("{}-{}-images", show_slug, date.format("%Y-%m-%d")))
+}
+
+/// File extension for an image content type; `None` for types a browser
+/// may not show or that could carry script (SVG).
+fn extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/avif" => Some("avif"),
+        _ => None,
+    }
+}
+
+fn data_uri(content_type: &str, bytes: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        content_type,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// Shrink `path` in place to at most [`THUMBNAIL_WIDTH`] wide with
+/// ImageMagick (`magick`, or `convert` from older versions). Returns false
+/// when neither is installed or both fail, leaving the file as it was.
+fn resize(path: &Path) -> bool {
+    let geometry = format!("{}x>", THUMBNAIL_WIDTH);
+    ["magick", "convert"].iter().any(|program| {
+        Command::new(program)
+            .arg(path)
+            .args(["-resize", &geometry, "-strip"])
+            .arg(path)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+/// Thumbnails for the stories in `topics` that have an image, as data URIs
+/// keyed by story URL. Images are numbered in briefing order in `dir`.
+/// Images that can't be fetched are left out with a warning; the story just
+/// has no thumbnail.
+pub async fn capture_all(topics: &[Topic], dir: &Path) -> HashMap<String, String> {
+    let images: Vec<(&str, &str)> = topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .filter_map(|story| {
+            let image = story.details.as_ref()?.image.as_deref()?;
+            Some((story.url.as_str(), image))
+        })
+        .collect();
+    if images.is_empty() {
+        return HashMap::new();
+    }
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(20))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Skipping images: {}", e);
+            return HashMap::new();
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        tracing::warn!("Skipping images: could not create {}: {}", dir.display(), e);
+        return HashMap::new();
+    }
+
+    let mut thumbnails = HashMap::new();
+    let mut warned_resize = false;
+    for (number, (story_url, image_url)) in images.into_iter().enumerate() {
+        match capture(&client, image_url, dir, number + 1).await {
+            Ok((content_type, path)) => {
+                if !resize(&path) && !warned_resize {
+                    tracing::warn!("ImageMagick not found; thumbnails are the full-size images");
+                    warned_resize = true;
+                }
+                match std::fs::read(&path) {
+                    Ok(bytes) => {
+                        thumbnails.insert(story_url.to_string(), data_uri(&content_type, &bytes));
+                    }
+                    Err(e) => tracing::warn!("Could not read {}: {}", path.display(), e),
+                }
+            }
+            Err(e) => tracing::warn!("Could not fetch image {}: {:#}", image_url, e),
+        }
+    }
+    thumbnails
+}
+
+/// Download `url` to `dir/{number}.{ext}`, returning its content type and
+/// path.
+async fn capture(
+    client: &reqwest::Client,
+    url: &str,
+    dir: &Path,
+    number: usize,
+) -> Result<(String, PathBuf)> {
+    let response = client.get(url).send().await.context("request failed")?;
+    if !response.status().is_success() {
+        bail!("HTTP {}", response.status());
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let Some(ext) = extension(&content_type) else {
+        bail!("not an image ({})", content_type);
+    };
+    let bytes = crate::net::read_body_capped(response, MAX_IMAGE_BYTES)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    let path = dir.join(format!("{:02}.{}", number, ext));
+    std::fs::write(&path, bytes).with_context(|| format!("writing {}", path.display()))?;
+    let content_type = content_type.split(';').next().unwrap_or_default();
+    Ok((content_type.trim().to_string(), path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(extension("image/jpeg"), Some("jpg"));
+        assert_eq!(extension("image/PNG; charset=binary"), Some("png"));
+        assert_eq!(extension("image/svg+xml"), None);
+        assert_eq!(extension("text/html"), None);
+        assert_eq!(extension(""), None);
+    }
+
+    #[test]
+    fn test_data_uri() {
+        assert_eq!(data_uri("image/png", b"abc"), "data:image/png;base64,YWJj");
+    }
+}
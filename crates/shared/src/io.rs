@@ -1,20 +1,81 @@
 use anyhow::{Context, Result};
-use chrono::DateTime;
+use chrono::{DateTime, NaiveDate};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::clustering::Story;
+use crate::models::{BriefingData, ShowInfo};
+use crate::summarizer::Summary;
+
+/// Write `contents` to `path` without ever leaving it half-written: they go
+/// to a temporary file beside it that is then renamed over it. The version
+/// being replaced is kept as `{file}.bak`, with older ones shifted along up
+/// to `[output] backups`.
+pub fn save_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    save_file_keeping(path, contents.as_ref(), crate::output::settings().backups)
+}
 
-use crate::models::BriefingData;
+fn save_file_keeping(path: &Path, contents: &[u8], keep: usize) -> Result<()> {
+    // A name of its own, so two writers can't share it. It's deleted when
+    // dropped, so nothing is left behind if anything below fails.
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let prefix = format!(".{}.", name);
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix).suffix(".tmp");
+    // The umask's permissions, as `File::create` gives, rather than 0600
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+    let mut temp = builder
+        .tempfile_in(dir)
+        .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+    temp.write_all(contents)
+        .and_then(|()| temp.as_file().sync_all())
+        .with_context(|| format!("Failed to write {}", temp.path().display()))?;
+    if keep > 0 && path.exists() {
+        let _ = fs::remove_file(backup_path(path, keep));
+        for n in (1..keep).rev() {
+            let older = backup_path(path, n);
+            if older.exists() {
+                fs::rename(&older, backup_path(path, n + 1))
+                    .with_context(|| format!("Failed to rotate {}", older.display()))?;
+            }
+        }
+        // A copy, so `path` stays in place until the rename below
+        let backup = backup_path(path, 1);
+        fs::copy(path, &backup)
+            .with_context(|| format!("Failed to back up to {}", backup.display()))?;
+    }
+    temp.persist(path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
 
-/// Get the default directory for storing story files
-pub fn get_default_stories_dir() -> Result<PathBuf> {
-    let data_dir = dirs::data_local_dir()
-        .context("Could not determine local data directory")?
-        .join("podcast-briefing")
-        .join("stories");
+/// `{file}.bak` for the newest backup, `{file}.bak.{n}` for older ones.
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    if n <= 1 {
+        sibling(path, ".bak")
+    } else {
+        sibling(path, &format!(".bak.{}", n))
+    }
+}
 
-    fs::create_dir_all(&data_dir).context("Failed to create stories directory")?;
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
 
-    Ok(data_dir)
+/// Get the directory for storing story files (`[output.json] dir`, or
+/// `stories/` in the data dir)
+pub fn get_default_stories_dir() -> Result<PathBuf> {
+    crate::output::settings()
+        .dir(crate::output::Artifact::Json)
+        .context("Failed to create stories directory")
 }
 
 /// Save story data to a JSON file
@@ -24,11 +85,81 @@ pub fn save_stories(data: &BriefingData, filename: &str) -> Result<PathBuf> {
 
     let json = serde_json::to_string_pretty(data).context("Failed to serialize briefing data")?;
 
-    fs::write(&filepath, json).context("Failed to write story file")?;
+    save_file(&filepath, json).context("Failed to write story file")?;
 
     Ok(filepath)
 }
 
+/// A story filename for `slug` on `date` from `[output.json] name`. The
+/// default, `{slug}-{date}-{rev}`, gives `{slug}-{date}.json`, or
+/// `{slug}-{date}-2.json` and so on when an earlier run already saved one,
+/// so that a second collection in the same week can be merged instead of
+/// replacing the first.
+pub fn new_story_filename(slug: &str, date: NaiveDate) -> Result<String> {
+    let path =
+        crate::output::settings().path(crate::output::Artifact::Json, slug, date, "", "json")?;
+    Ok(path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Stories gathered from several story files for one show.
+#[derive(Debug)]
+pub struct MergedStories {
+    pub show: ShowInfo,
+    pub stories: Vec<Story>,
+    /// Stories dropped because another file already had the same URL
+    pub duplicates: usize,
+}
+
+/// Combine the stories of `files` (in order) and drop repeats by canonical
+/// URL. The first copy of a story is kept unless only a later one has a
+/// summary. Topics are discarded; the caller re-clusters the union. All
+/// files must be for the same show.
+pub fn merge_stories(files: Vec<BriefingData>) -> Result<MergedStories> {
+    let mut files = files.into_iter();
+    let first = files.next().context("No story files to merge")?;
+    let show = first.show.clone();
+
+    let mut kept: Vec<(String, Story)> = Vec::new();
+    let mut duplicates = 0;
+    for data in std::iter::once(first).chain(files) {
+        if data.show.slug != show.slug {
+            anyhow::bail!(
+                "Cannot merge stories for {} with stories for {}",
+                data.show.name,
+                show.name
+            );
+        }
+        for story in data.topics.into_iter().flat_map(|t| t.stories) {
+            let key = crate::dedup::canonicalize_url(&story.url);
+            match kept.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, existing)) => {
+                    duplicates += 1;
+                    let summarized = |s: &Story| {
+                        matches!(
+                            s.summary,
+                            Summary::Editorial { .. } | Summary::Product { .. }
+                        )
+                    };
+                    if !summarized(existing) && summarized(&story) {
+                        *existing = story;
+                    }
+                }
+                None => kept.push((key, story)),
+            }
+        }
+    }
+
+    Ok(MergedStories {
+        show,
+        stories: kept.into_iter().map(|(_, s)| s).collect(),
+        duplicates,
+    })
+}
+
 /// Load story data from a JSON file
 pub fn load_stories(filepath: &PathBuf) -> Result<BriefingData> {
     // Check if file exists
@@ -84,7 +215,7 @@ pub fn list_story_files() -> Result<Vec<(PathBuf, BriefingData)>> {
                         files.push((path, data));
                     }
                     Err(e) => {
-                        eprintln!("Warning: Could not load {}: {}", path.display(), e);
+                        tracing::warn!("Could not load {}: {}", path.display(), e);
                     }
                 }
             }
@@ -109,6 +240,48 @@ mod tests {
     use crate::summarizer::Summary;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_save_file_keeps_backups() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("twit-2026-02-01.html");
+        for version in ["one", "two", "three", "four"] {
+            save_file_keeping(&path, version.as_bytes(), 2).unwrap();
+        }
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(path.clone()), "four");
+        assert_eq!(read(dir.path().join("twit-2026-02-01.html.bak")), "three");
+        assert_eq!(read(dir.path().join("twit-2026-02-01.html.bak.2")), "two");
+        assert!(!dir.path().join("twit-2026-02-01.html.bak.3").exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_save_file_without_backups() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("twit-2026-02-01.org");
+        save_file_keeping(&path, b"one", 0).unwrap();
+        save_file_keeping(&path, b"two", 0).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_save_file_failure_leaves_the_original() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("twit-2026-02-01.org");
+        fs::write(&path, "good").unwrap();
+        // A directory where the backup would go
+        fs::create_dir(dir.path().join("twit-2026-02-01.org.bak")).unwrap();
+        assert!(save_file_keeping(&path, b"bad", 1).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "good");
+        // The temporary file went with the failure
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        // Nowhere to write at all
+        let missing = dir.path().join("gone").join("twit-2026-02-01.org");
+        assert!(save_file_keeping(&missing, b"bad", 1).is_err());
+    }
+
     fn make_test_data() -> BriefingData {
         let show = ShowInfo::new("Test Show", "test", "TEST");
         let story = Story {
@@ -118,18 +291,26 @@ mod tests {
             summary: Summary::Editorial {
                 lede: "Acme Corp announced a new widget".to_string(),
                 nutgraf: "It changes the industry".to_string(),
-                quote: None,
+                quotes: Vec::new(),
+                reaction: None,
+                by_the_numbers: None,
             },
+            ..Default::default()
         };
         let topics = vec![Topic {
             title: "News".to_string(),
             stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
         BriefingData {
             version: "1.0".to_string(),
             created_at: "2026-02-01T00:00:00Z".to_string(),
             show,
             topics,
+            discussed: None,
+            missed: Vec::new(),
         }
     }
 
@@ -194,6 +375,8 @@ mod tests {
             created_at: "2026-02-01T00:00:00Z".to_string(),
             show,
             topics: vec![],
+            discussed: None,
+            missed: Vec::new(),
         };
         let json = serde_json::to_string_pretty(&data).unwrap();
         fs::write(&filepath, json).unwrap();
@@ -209,4 +392,39 @@ mod tests {
         assert!(dir.to_string_lossy().contains("podcast-briefing"));
         assert!(dir.to_string_lossy().contains("stories"));
     }
+
+    #[test]
+    fn test_merge_stories_dedups_by_url() {
+        let story = |title: &str, url: &str| {
+            crate::testing::story(title)
+                .with_url(url)
+                .with_created("2026-02-01")
+                .build()
+        };
+        let early = make_test_data();
+        let mut late = make_test_data();
+        late.topics[0].stories = vec![
+            // Same article with tracking junk
+            story("Test Article again", "https://example.com/?utm_source=x"),
+            story("Late Story", "https://example.com/late"),
+        ];
+
+        let merged = merge_stories(vec![early, late]).unwrap();
+        assert_eq!(merged.duplicates, 1);
+        let titles: Vec<&str> = merged.stories.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Test Article", "Late Story"]);
+
+        // A later copy with a summary replaces an unsummarized one
+        let mut early = make_test_data();
+        early.topics[0].stories[0].summary = Summary::Failed("timeout".to_string());
+        let merged = merge_stories(vec![early, make_test_data()]).unwrap();
+        assert!(matches!(
+            merged.stories[0].summary,
+            Summary::Editorial { .. }
+        ));
+
+        let mut other_show = make_test_data();
+        other_show.show = ShowInfo::new("Other", "other", "OTHER");
+        assert!(merge_stories(vec![make_test_data(), other_show]).is_err());
+    }
 }
@@ -0,0 +1,523 @@
+//! Uploads through JMAP file storage (`[upload] method = "jmap"`), as
+//! Fastmail offers with an API token instead of a WebDAV app password.
+//!
+//! Files are uploaded as blobs and then placed in the file tree with
+//! `FileNode/set`, creating any folder on the way that isn't there yet.
+//! Paths start at the top of the account's files, e.g.
+//! `/Briefings/twit/index.html`.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::manifest::RemoteFile;
+
+/// API token, read from the environment like the other keys.
+pub const TOKEN_ENV_VAR: &str = "FASTMAIL_API_TOKEN";
+
+/// Fastmail's session resource, used unless `[upload] session` says
+/// otherwise.
+pub const DEFAULT_SESSION_URL: &str = "https://api.fastmail.com/jmap/session";
+
+const CORE: &str = "urn:ietf:params:jmap:core";
+
+/// A JMAP session with file storage.
+pub struct Jmap {
+    client: reqwest::Client,
+    token: String,
+    api_url: String,
+    upload_url: String,
+    account_id: String,
+    /// The file storage capability the server offers
+    capability: String,
+}
+
+/// A file or folder as `FileNode/get` returns it.
+#[derive(Debug, Clone)]
+struct Node {
+    id: String,
+    parent_id: Option<String>,
+    size: Option<u64>,
+    blob_id: Option<String>,
+}
+
+impl Jmap {
+    /// Open a session at `session_url` with the token from [`TOKEN_ENV_VAR`].
+    pub async fn connect(session_url: &str) -> Result<Self> {
+        let token = std::env::var(TOKEN_ENV_VAR)
+            .with_context(|| format!("{} not set in environment", TOKEN_ENV_VAR))?;
+        Self::connect_with(session_url, token).await
+    }
+
+    async fn connect_with(session_url: &str, token: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .context("Failed to create HTTP client")?;
+        let response = client
+            .get(session_url)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", session_url))?;
+        if !response.status().is_success() {
+            bail!(
+                "JMAP session at {} failed: HTTP {}",
+                session_url,
+                response.status()
+            );
+        }
+        let session: Value = response
+            .json()
+            .await
+            .context("Failed to parse JMAP session")?;
+
+        // Servers name the capability differently while it's a draft
+        let Some((capability, account_id)) = session["primaryAccounts"]
+            .as_object()
+            .and_then(|accounts| accounts.iter().find(|(name, _)| name.ends_with("filenode")))
+        else {
+            bail!("The JMAP account has no file storage; does the token have access to Files?");
+        };
+        let text = |key: &str| {
+            session[key]
+                .as_str()
+                .map(str::to_string)
+                .with_context(|| format!("JMAP session has no {}", key))
+        };
+        Ok(Self {
+            api_url: text("apiUrl")?,
+            upload_url: text("uploadUrl")?,
+            account_id: account_id.as_str().unwrap_or_default().to_string(),
+            capability: capability.clone(),
+            client,
+            token,
+        })
+    }
+
+    /// Put `body` at `path`, replacing the file there and creating the
+    /// folders it goes in.
+    pub async fn put(&self, path: &str, body: Vec<u8>, content_type: &str) -> Result<()> {
+        let (folders, name) = split(path)?;
+        let blob_id = self.upload_blob(body, content_type).await?;
+        let parent = self.folder(&folders).await?;
+        let existing = self.child(parent.as_deref(), name).await?;
+
+        let args = match existing {
+            Some(node) => json!({
+                "accountId": self.account_id,
+                "update": { node.id: { "blobId": blob_id } },
+            }),
+            None => json!({
+                "accountId": self.account_id,
+                "create": { "file": {
+                    "parentId": parent,
+                    "name": name,
+                    "blobId": blob_id,
+                    "type": content_type,
+                } },
+            }),
+        };
+        let result = self.call(vec![("FileNode/set", args)]).await?;
+        check_set(&result[0], path)?;
+        Ok(())
+    }
+
+    /// The size of the file at `path`, or `None` if there isn't one.
+    pub async fn size(&self, path: &str) -> Result<Option<u64>> {
+        Ok(self.stat(path).await?.map(|file| file.size))
+    }
+
+    /// The size and blob id of the file at `path`, or `None` if there isn't
+    /// one. Every upload is a new blob, so the id tells copies apart.
+    pub async fn stat(&self, path: &str) -> Result<Option<RemoteFile>> {
+        let (folders, name) = split(path)?;
+        let mut parent = None;
+        for folder in folders {
+            match self.child(parent.as_deref(), folder).await? {
+                Some(node) => parent = Some(node.id),
+                None => return Ok(None),
+            }
+        }
+        Ok(self
+            .child(parent.as_deref(), name)
+            .await?
+            .map(|node| RemoteFile {
+                size: node.size.unwrap_or_default(),
+                version: node.blob_id,
+            }))
+    }
+
+    /// The id of the folder at `path` (`None` for the top), created if
+    /// need be. Uploads running at the same time may both find a folder
+    /// missing; the one that loses the race uses the folder the other made.
+    async fn folder(&self, path: &[&str]) -> Result<Option<String>> {
+        let mut parent: Option<String> = None;
+        for name in path {
+            if let Some(node) = self.child(parent.as_deref(), name).await? {
+                parent = Some(node.id);
+                continue;
+            }
+            let result = self
+                .call(vec![(
+                    "FileNode/set",
+                    json!({
+                        "accountId": self.account_id,
+                        "create": { "folder": { "parentId": parent, "name": name } },
+                    }),
+                )])
+                .await?;
+            if let Some(existing) = already_exists(&result[0]) {
+                let id = match existing {
+                    Some(id) => id,
+                    None => self
+                        .child(parent.as_deref(), name)
+                        .await?
+                        .map(|node| node.id)
+                        .with_context(|| format!("Folder {} exists but can't be found", name))?,
+                };
+                parent = Some(id);
+                continue;
+            }
+            check_set(&result[0], name)?;
+            let id = result[0]["created"]["folder"]["id"]
+                .as_str()
+                .with_context(|| format!("Creating folder {} returned no id", name))?;
+            parent = Some(id.to_string());
+        }
+        Ok(parent)
+    }
+
+    /// The node named `name` in the folder `parent`.
+    async fn child(&self, parent: Option<&str>, name: &str) -> Result<Option<Node>> {
+        let result = self
+            .call(vec![
+                (
+                    "FileNode/query",
+                    json!({ "accountId": self.account_id, "filter": { "name": name } }),
+                ),
+                (
+                    "FileNode/get",
+                    json!({
+                        "accountId": self.account_id,
+                        "#ids": { "resultOf": "0", "name": "FileNode/query", "path": "/ids" },
+                        "properties": ["id", "parentId", "name", "size", "blobId"],
+                    }),
+                ),
+            ])
+            .await?;
+        let nodes = result[1]["list"].as_array().cloned().unwrap_or_default();
+        Ok(nodes
+            .iter()
+            .filter(|node| node["name"].as_str() == Some(name))
+            .map(|node| Node {
+                id: node["id"].as_str().unwrap_or_default().to_string(),
+                parent_id: node["parentId"].as_str().map(str::to_string),
+                size: node["size"].as_u64(),
+                blob_id: node["blobId"].as_str().map(str::to_string),
+            })
+            .find(|node| node.parent_id.as_deref() == parent))
+    }
+
+    async fn upload_blob(&self, body: Vec<u8>, content_type: &str) -> Result<String> {
+        let url = self.upload_url.replace("{accountId}", &self.account_id);
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .context("Failed to upload blob")?;
+        if !response.status().is_success() {
+            bail!("Blob upload failed: HTTP {}", response.status());
+        }
+        let blob: Value = response
+            .json()
+            .await
+            .context("Failed to parse blob upload")?;
+        blob["blobId"]
+            .as_str()
+            .map(str::to_string)
+            .context("Blob upload returned no blobId")
+    }
+
+    /// Make the method calls in one request, returning each one's arguments.
+    async fn call(&self, calls: Vec<(&str, Value)>) -> Result<Vec<Value>> {
+        let method_calls: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (method, args))| json!([method, args, i.to_string()]))
+            .collect();
+        let response = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(&self.token)
+            .json(&json!({
+                "using": [CORE, self.capability],
+                "methodCalls": method_calls,
+            }))
+            .send()
+            .await
+            .context("Failed to reach the JMAP API")?;
+        if !response.status().is_success() {
+            bail!("JMAP request failed: HTTP {}", response.status());
+        }
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse JMAP response")?;
+        let responses = body["methodResponses"]
+            .as_array()
+            .context("JMAP response has no methodResponses")?;
+        responses
+            .iter()
+            .map(|response| match response[0].as_str() {
+                Some("error") => bail!(
+                    "JMAP error: {}",
+                    response[1]["description"]
+                        .as_str()
+                        .or(response[1]["type"].as_str())
+                        .unwrap_or("unknown")
+                ),
+                _ => Ok(response[1].clone()),
+            })
+            .collect()
+    }
+}
+
+/// The folders and file name of `path`.
+fn split(path: &str) -> Result<(Vec<&str>, &str)> {
+    let mut parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    let name = parts
+        .pop()
+        .with_context(|| format!("No file name in {}", path))?;
+    Ok((parts, name))
+}
+
+/// Whether `FileNode/set` refused to create a node because one of that name
+/// is already there, with the existing node's id if the server gave it.
+fn already_exists(result: &Value) -> Option<Option<String>> {
+    let error = result["notCreated"].as_object()?.values().next()?;
+    (error["type"].as_str() == Some("alreadyExists"))
+        .then(|| error["existingId"].as_str().map(str::to_string))
+}
+
+/// Fail if `FileNode/set` didn't make a change.
+fn check_set(result: &Value, what: &str) -> Result<()> {
+    for key in ["notCreated", "notUpdated"] {
+        if let Some(error) = result[key].as_object().and_then(|m| m.values().next()) {
+            bail!(
+                "Could not save {}: {}",
+                what,
+                error["description"]
+                    .as_str()
+                    .or(error["type"].as_str())
+                    .unwrap_or("unknown error")
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockServer, Request, Response};
+    use std::sync::{Barrier, Mutex};
+
+    /// A JMAP server with a file tree in memory. Blobs are stored by their
+    /// content, so the blob id is the file. Like Fastmail, it refuses a
+    /// second node of the same name in a folder.
+    fn server() -> MockServer {
+        server_with(None)
+    }
+
+    /// [`server`], holding each of the first lookups until `barrier` lets
+    /// them all answer at once, so uploads race to make the same folder.
+    fn server_with(barrier: Option<Barrier>) -> MockServer {
+        let nodes: Mutex<Vec<Value>> = Mutex::new(Vec::new());
+        let held = std::sync::atomic::AtomicUsize::new(0);
+        let url = std::sync::Arc::new(Mutex::new(String::new()));
+        let base = url.clone();
+        let server = MockServer::start(move |request: &Request| {
+            let base = base.lock().unwrap().clone();
+            match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/session") => Response::json(&json!({
+                    "apiUrl": format!("{}/api", base),
+                    "uploadUrl": format!("{}/upload/{{accountId}}/", base),
+                    "primaryAccounts": { "urn:ietf:params:jmap:filenode": "u1" },
+                })),
+                ("POST", "/upload/u1/") => Response::json(&json!({
+                    "blobId": request.body_text(),
+                })),
+                ("POST", "/api") => {
+                    let body: Value = serde_json::from_slice(&request.body).unwrap();
+                    if let Some(barrier) = &barrier {
+                        let order = std::sync::atomic::Ordering::SeqCst;
+                        if request.body_text().contains("FileNode/query")
+                            && held.fetch_add(1, order) < 2
+                        {
+                            barrier.wait();
+                        }
+                    }
+                    let mut nodes = nodes.lock().unwrap();
+                    let mut responses = Vec::new();
+                    let mut ids = Vec::new();
+                    for call in body["methodCalls"].as_array().unwrap() {
+                        let args = &call[1];
+                        let response = match call[0].as_str().unwrap() {
+                            "FileNode/query" => {
+                                ids = nodes
+                                    .iter()
+                                    .filter(|n| n["name"] == args["filter"]["name"])
+                                    .map(|n| n["id"].clone())
+                                    .collect();
+                                json!({ "ids": ids })
+                            }
+                            "FileNode/get" => {
+                                let list: Vec<Value> = nodes
+                                    .iter()
+                                    .filter(|n| ids.contains(&n["id"]))
+                                    .cloned()
+                                    .collect();
+                                json!({ "list": list })
+                            }
+                            "FileNode/set" => {
+                                let mut created = serde_json::Map::new();
+                                let mut not_created = serde_json::Map::new();
+                                for (key, node) in args["create"].as_object().into_iter().flatten()
+                                {
+                                    if let Some(existing) = nodes.iter().find(|n| {
+                                        n["name"] == node["name"]
+                                            && n["parentId"] == node["parentId"]
+                                    }) {
+                                        not_created.insert(
+                                            key.clone(),
+                                            json!({
+                                                "type": "alreadyExists",
+                                                "existingId": existing["id"],
+                                            }),
+                                        );
+                                        continue;
+                                    }
+                                    let mut node = node.clone();
+                                    let id = format!("n{}", nodes.len() + 1);
+                                    node["id"] = json!(id);
+                                    if let Some(blob) = node["blobId"].as_str() {
+                                        node["size"] = json!(blob.len());
+                                    }
+                                    created.insert(key.clone(), json!({ "id": id }));
+                                    nodes.push(node);
+                                }
+                                for (id, patch) in args["update"].as_object().into_iter().flatten()
+                                {
+                                    let node = nodes.iter_mut().find(|n| n["id"] == *id).unwrap();
+                                    let blob = patch["blobId"].as_str().unwrap();
+                                    node["blobId"] = json!(blob);
+                                    node["size"] = json!(blob.len());
+                                }
+                                json!({ "created": created, "notCreated": not_created })
+                            }
+                            _ => json!({}),
+                        };
+                        responses.push(json!([call[0], response, call[2]]));
+                    }
+                    Response::json(&json!({ "methodResponses": responses }))
+                }
+                _ => Response::status(404),
+            }
+        });
+        *url.lock().unwrap() = server.url().to_string();
+        server
+    }
+
+    #[tokio::test]
+    async fn test_put_creates_folders_and_replaces_files() {
+        let server = server();
+        let jmap = Jmap::connect_with(&format!("{}/session", server.url()), "token".into())
+            .await
+            .unwrap();
+        assert_eq!(jmap.account_id, "u1");
+        assert_eq!(jmap.size("/Briefings/twit/index.html").await.unwrap(), None);
+
+        jmap.put(
+            "/Briefings/twit/index.html",
+            b"<html>".to_vec(),
+            "text/html",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            jmap.size("/Briefings/twit/index.html").await.unwrap(),
+            Some(6)
+        );
+        // The folders made for the first show are reused for the second
+        jmap.put("/Briefings/mbw/index.html", b"<html>".to_vec(), "text/html")
+            .await
+            .unwrap();
+        jmap.put(
+            "/Briefings/twit/index.html",
+            b"<html></html>".to_vec(),
+            "text/html",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            jmap.size("/Briefings/twit/index.html").await.unwrap(),
+            Some(13)
+        );
+        let file = jmap.stat("/Briefings/twit/index.html").await.unwrap();
+        assert_eq!(file.unwrap().version.as_deref(), Some("<html></html>"));
+        assert_eq!(jmap.size("/twit/index.html").await.unwrap(), None);
+
+        let creates: Vec<String> = server
+            .requests_for("POST")
+            .iter()
+            .filter(|r| r.path == "/api" && r.body_text().contains("\"create\""))
+            .map(|r| r.body_text())
+            .collect();
+        // Briefings, twit, index.html, mbw, index.html
+        assert_eq!(creates.len(), 5, "{:#?}", creates);
+        assert!(server.requests()[0]
+            .header("authorization")
+            .is_some_and(|auth| auth == "Bearer token"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_puts_share_a_new_folder() {
+        // Both uploads find Briefings missing before either creates it
+        let server = server_with(Some(Barrier::new(2)));
+        let jmap = Jmap::connect_with(&format!("{}/session", server.url()), "token".into())
+            .await
+            .unwrap();
+
+        let (html, csv) = tokio::join!(
+            jmap.put(
+                "/Briefings/twit/index.html",
+                b"<html>".to_vec(),
+                "text/html"
+            ),
+            jmap.put("/Briefings/twit/stories.csv", b"a,b".to_vec(), "text/csv"),
+        );
+        html.unwrap();
+        csv.unwrap();
+
+        assert_eq!(
+            jmap.size("/Briefings/twit/index.html").await.unwrap(),
+            Some(6)
+        );
+        assert_eq!(
+            jmap.size("/Briefings/twit/stories.csv").await.unwrap(),
+            Some(3)
+        );
+        let creates = server
+            .requests_for("POST")
+            .iter()
+            .filter(|r| r.path == "/api" && r.body_text().contains("\"create\""))
+            .count();
+        // Briefings twice (one refused), twit once or twice, and both files
+        assert!((5..=6).contains(&creates), "{} creates", creates);
+    }
+}
@@ -0,0 +1,255 @@
+//! Which language an article is written in.
+//!
+//! The text decides when it can: non-Latin scripts are recognized by their
+//! letters, and the common Western European languages by how often their
+//! commonest words turn up. Otherwise the page's own declaration (`<html
+//! lang>`, `og:locale`, `Content-Language`) is used. Sites often declare a
+//! default that doesn't match the article, which is why it only breaks
+//! ties.
+
+/// The commonest words of each language told apart by its words.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "is", "that", "it", "for", "was", "with", "on", "are",
+            "this", "be", "have", "by", "not", "but", "they", "from",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "und", "das", "ist", "nicht", "ein", "eine", "zu", "den", "mit", "sich",
+            "auf", "für", "dem", "von", "auch", "es", "im", "wird",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "des", "est", "une", "un", "que", "pour", "dans", "qui",
+            "pas", "sur", "du", "au", "avec", "ce", "il", "sont",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "que", "del", "una", "es", "por", "con", "para", "se", "como",
+            "más", "pero", "su", "al", "lo", "está", "son",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "di", "che", "e", "per", "non", "sono", "con", "della", "gli", "è", "anche",
+            "una", "nel", "alla", "questo", "dei", "delle", "ha", "più",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "e", "que", "do", "da", "em", "um", "uma", "não", "para", "com", "é", "no",
+            "na", "dos", "das", "mais", "como", "ao",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "dat", "niet", "op", "te", "zijn", "voor",
+            "met", "ook", "die", "maar", "er", "wordt", "bij", "naar",
+        ],
+    ),
+    (
+        "sv",
+        &[
+            "och", "att", "det", "som", "är", "på", "för", "med", "inte", "av", "till", "den",
+            "har", "om", "ett", "kan", "var", "också", "men", "sig",
+        ],
+    ),
+];
+
+/// Words read when guessing; the opening of an article is plenty.
+const SAMPLE_WORDS: usize = 2000;
+
+/// English names, for "translated from German".
+const NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nb", "Norwegian"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("zh", "Chinese"),
+];
+
+/// The ISO 639-1 code of `text`'s language, e.g. `de`, falling back to
+/// `declared` (a language tag like `de-AT` or `de_DE`) when the text
+/// doesn't say.
+pub fn detect(declared: Option<&str>, text: &str) -> Option<String> {
+    guess(text)
+        .map(str::to_string)
+        .or_else(|| declared.and_then(primary_subtag))
+}
+
+/// Whether `code` is English, or unknown.
+pub fn is_english(code: Option<&str>) -> bool {
+    code.is_none_or(|code| code == "en")
+}
+
+/// The English name of the language `code`, or the code itself.
+pub fn name(code: &str) -> String {
+    NAMES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or_else(|| code.to_uppercase(), |(_, name)| name.to_string())
+}
+
+/// `de` from `de-AT`, `de_DE` or `DE`.
+fn primary_subtag(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    (primary.len() == 2 && primary.bytes().all(|b| b.is_ascii_lowercase())).then_some(primary)
+}
+
+fn guess(text: &str) -> Option<&'static str> {
+    script(text).or_else(|| by_words(text))
+}
+
+/// The language of a non-Latin script making up most of the letters.
+fn script(text: &str) -> Option<&'static str> {
+    let mut letters = 0;
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    for c in text
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .take(SAMPLE_WORDS * 5)
+    {
+        letters += 1;
+        let language = match c as u32 {
+            0x3040..=0x30FF => "ja",
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+            0x4E00..=0x9FFF => "zh",
+            0x0400..=0x04FF => "ru",
+            0x0370..=0x03FF => "el",
+            0x0600..=0x06FF => "ar",
+            0x0590..=0x05FF => "he",
+            0x0E00..=0x0E7F => "th",
+            0x0900..=0x097F => "hi",
+            _ => continue,
+        };
+        match counts.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((language, 1)),
+        }
+    }
+    let total: usize = counts.iter().map(|(_, n)| n).sum();
+    if letters == 0 || total * 10 < letters * 3 {
+        return None;
+    }
+    // Japanese mixes kana with kanji, which alone would read as Chinese
+    if counts.iter().any(|(l, n)| *l == "ja" && n * 10 >= total) {
+        return Some("ja");
+    }
+    counts.into_iter().max_by_key(|(_, n)| *n).map(|(l, _)| l)
+}
+
+/// The language whose common words are clearly the most frequent.
+fn by_words(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .take(SAMPLE_WORDS)
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < 20 {
+        return None;
+    }
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(language, common)| {
+            let hits = words
+                .iter()
+                .filter(|w| common.contains(&w.as_str()))
+                .count();
+            (*language, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+    let (best, hits) = scores[0];
+    let runner_up = scores[1].1;
+    // A tenth of the words, and well ahead of the next language
+    (hits * 10 >= words.len() && hits * 4 >= runner_up * 5).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_by_words() {
+        let english = "Apple is recalling the straps that shipped with the Vision Pro \
+            because they can break, and the company says that it will replace them for free \
+            for anyone who has one.";
+        let german = "Apple ruft die Armbänder der Vision Pro zurück, weil sie brechen können. \
+            Das Unternehmen will sie kostenlos ersetzen, und es ist nicht das erste Mal, dass \
+            der Konzern mit dem Zubehör Probleme hat.";
+        let french = "Apple rappelle les bracelets du Vision Pro, qui peuvent casser. La \
+            société dit qu'elle les remplacera gratuitement pour tous les clients qui en ont \
+            un, et ce n'est pas la première fois.";
+        let spanish = "Apple retira las correas del Vision Pro porque se pueden romper, y la \
+            empresa dice que las va a reemplazar gratis para todos los clientes que tengan \
+            una, como lo hizo con el modelo anterior.";
+        assert_eq!(detect(None, english).as_deref(), Some("en"));
+        assert_eq!(detect(Some("en"), german).as_deref(), Some("de"));
+        assert_eq!(detect(None, french).as_deref(), Some("fr"));
+        assert_eq!(detect(None, spanish).as_deref(), Some("es"));
+    }
+
+    #[test]
+    fn test_detect_by_script() {
+        assert_eq!(
+            detect(None, "アップルはビジョンプロのストラップをリコールした。").as_deref(),
+            Some("ja")
+        );
+        assert_eq!(
+            detect(None, "苹果召回了头显的表带。").as_deref(),
+            Some("zh")
+        );
+        assert_eq!(
+            detect(None, "Apple отзывает ремешки Vision Pro").as_deref(),
+            Some("ru")
+        );
+    }
+
+    #[test]
+    fn test_declared_language_breaks_ties() {
+        assert_eq!(detect(Some("de-AT"), "Vision Pro").as_deref(), Some("de"));
+        assert_eq!(detect(Some("pt_BR"), "").as_deref(), Some("pt"));
+        assert_eq!(detect(Some("x-klingon"), ""), None);
+        assert_eq!(detect(None, "Vision Pro"), None);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(name("de"), "German");
+        assert_eq!(name("xx"), "XX");
+        assert!(is_english(None));
+        assert!(is_english(Some("en")));
+        assert!(!is_english(Some("de")));
+    }
+}
@@ -1,15 +1,72 @@
 // Public modules
+pub mod archive;
+pub mod assets;
 pub mod briefing;
+pub mod budget;
+pub mod calendar;
 pub mod clustering;
 pub mod config;
+pub mod connections;
 pub mod cookies;
+pub mod dates;
+pub mod dedup;
+pub mod deprecation;
+pub mod disclosures;
+pub mod discovery;
+pub mod discussion;
+pub mod domain_rules;
+pub mod editor;
+pub mod entities;
+pub mod export;
 pub mod extractor;
+pub mod filter;
+pub mod followups;
+#[cfg(feature = "headless")]
+pub mod headless;
+pub mod hooks;
+pub mod http_cache;
+pub mod images;
 pub mod io;
+pub mod jmap;
+pub mod language;
+pub mod links;
+pub mod llm;
+pub mod logging;
+pub mod manifest;
+pub mod media;
 pub mod models;
 pub mod net;
+pub mod notify;
+pub mod numbers;
+pub mod org;
+pub mod org_template;
+pub mod output;
+pub mod pdf;
+pub mod politeness;
+pub mod progress;
+pub mod prompts;
+pub mod quotes;
 pub mod raindrop;
+pub mod rate_limit;
+pub mod schedule;
+pub mod sensitive;
+pub mod settings;
+pub mod shortener;
+pub mod shows;
+pub mod social;
+pub mod sources;
+pub mod staleness;
 pub mod summarizer;
-pub mod time;
+pub mod talk_time;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
+pub mod timezone;
+pub mod topic_order;
+pub mod translation;
+pub mod trends;
+pub mod triage;
+pub mod upload;
+pub mod usage;
 
 // Re-export commonly used types
 pub use clustering::{Story, Topic, TopicClusterer};
@@ -19,4 +76,4 @@ pub use io::{get_default_stories_dir, list_story_files, load_stories, save_stori
 pub use models::{BriefingData, ShowInfo};
 pub use raindrop::RaindropClient;
 pub use summarizer::{ClaudeSummarizer, Summary};
-pub use time::local_wallclock_as_utc;
+pub use usage::UsageTracker;
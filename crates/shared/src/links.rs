@@ -0,0 +1,178 @@
+//! Link checks before a briefing is published.
+//!
+//! Every story URL gets a HEAD request (falling back to GET for servers
+//! that refuse HEAD), following redirects. A link is dead if it errors or
+//! ends in a 4xx/5xx, paywalled if the site answers 401, 402 or 403, and
+//! flagged when it redirects to a different site, which is often a parked
+//! domain or a login wall.
+
+use futures::stream::{self, StreamExt};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::clustering::{source_domain, Topic};
+
+/// Links checked at once.
+const CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkStatus {
+    Ok,
+    /// Ended up on another domain, e.g. `consent.example.com`
+    Redirected(String),
+    Paywalled,
+    /// The HTTP status or the error
+    Dead(String),
+}
+
+impl LinkStatus {
+    pub fn is_ok(&self) -> bool {
+        *self == Self::Ok
+    }
+
+    /// Status from the final response: `status` at `final_url`, reached
+    /// from `url`.
+    fn from_response(url: &str, final_url: &str, status: StatusCode) -> Self {
+        if matches!(status.as_u16(), 401..=403) {
+            return Self::Paywalled;
+        }
+        if status.is_client_error() || status.is_server_error() {
+            return Self::Dead(status.to_string());
+        }
+        match (source_domain(url), source_domain(final_url)) {
+            (Some(from), Some(to)) if !same_site(&from, &to) => Self::Redirected(to),
+            _ => Self::Ok,
+        }
+    }
+}
+
+/// Column text in the links CSV, e.g. `dead (404 Not Found)`.
+impl fmt::Display for LinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Redirected(to) => write!(f, "redirects to {}", to),
+            Self::Paywalled => write!(f, "paywalled"),
+            Self::Dead(reason) => write!(f, "dead ({})", reason),
+        }
+    }
+}
+
+/// Whether `a` and `b` are the same site, counting subdomains of one
+/// another (`theverge.com` and `amp.theverge.com`) as the same.
+fn same_site(a: &str, b: &str) -> bool {
+    a == b || a.ends_with(&format!(".{}", b)) || b.ends_with(&format!(".{}", a))
+}
+
+/// Check every story URL in `topics`, keyed by URL.
+pub async fn check_all(topics: &[Topic]) -> HashMap<String, LinkStatus> {
+    let mut urls: Vec<&str> = topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .map(|s| s.url.as_str())
+        .filter(|url| !url.is_empty())
+        .collect();
+    urls.sort_unstable();
+    urls.dedup();
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent(crate::net::BROWSER_USER_AGENT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Skipping link checks: {}", e);
+            return HashMap::new();
+        }
+    };
+    stream::iter(urls)
+        .map(|url| {
+            let client = &client;
+            async move { (url.to_string(), check(client, url).await) }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// One line per story whose link isn't ok, in briefing order, e.g.
+/// `✗ Apple cuts prices: dead (404 Not Found) https://…`.
+pub fn report(topics: &[Topic], statuses: &HashMap<String, LinkStatus>) -> Vec<String> {
+    topics
+        .iter()
+        .flat_map(|t| &t.stories)
+        .filter_map(|story| {
+            let status = statuses.get(&story.url).filter(|s| !s.is_ok())?;
+            let mark = match status {
+                LinkStatus::Dead(_) => "✗",
+                _ => "⚠",
+            };
+            Some(format!(
+                "{} {}: {} {}",
+                mark, story.title, status, story.url
+            ))
+        })
+        .collect()
+}
+
+/// How many of `statuses` are dead.
+pub fn dead_count(statuses: &HashMap<String, LinkStatus>) -> usize {
+    statuses
+        .values()
+        .filter(|s| matches!(s, LinkStatus::Dead(_)))
+        .count()
+}
+
+async fn check(client: &reqwest::Client, url: &str) -> LinkStatus {
+    let response = match client.head(url).send().await {
+        // Some servers don't answer HEAD properly
+        Ok(response)
+            if matches!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ) =>
+        {
+            client.get(url).send().await
+        }
+        result => result,
+    };
+    match response {
+        Ok(response) => LinkStatus::from_response(url, response.url().as_str(), response.status()),
+        Err(e) if e.is_timeout() => LinkStatus::Dead("timed out".to_string()),
+        Err(e) if e.is_connect() => LinkStatus::Dead("no connection".to_string()),
+        Err(e) => LinkStatus::Dead(e.without_url().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response() {
+        let status = |final_url: &str, code: u16| {
+            LinkStatus::from_response(
+                "https://www.theverge.com/a",
+                final_url,
+                StatusCode::from_u16(code).unwrap(),
+            )
+        };
+        assert_eq!(status("https://www.theverge.com/a", 200), LinkStatus::Ok);
+        assert_eq!(status("https://amp.theverge.com/a", 200), LinkStatus::Ok);
+        assert_eq!(
+            status("https://parked.example/", 200),
+            LinkStatus::Redirected("parked.example".to_string())
+        );
+        assert_eq!(
+            status("https://www.theverge.com/a", 403),
+            LinkStatus::Paywalled
+        );
+        assert_eq!(
+            status("https://www.theverge.com/a", 404).to_string(),
+            "dead (404 Not Found)"
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Minimal client for the Anthropic-compatible messages endpoint used by the
+//! summarizer and clusterer.
+//!
+//! Every request goes through a shared [`RateLimiter`], so callers don't
+//! need their own concurrency limits.
+//!
+//! Besides single requests it can submit many prompts at once through the
+//! Message Batches API (`{url}/batches`), which Anthropic bills at half price.
+//! Backends without that endpoint just fail the submission, and callers fall
+//! back to individual requests.
+
+use anyhow::{Context, Result};
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::rate_limit::RateLimiter;
+use crate::usage::UsageTracker;
+
+pub const DEFAULT_MODEL: &str = "glm-5.2";
+pub const DEFAULT_API_URL: &str = "https://api.z.ai/api/anthropic/v1/messages";
+
+/// Name of the env var holding the API key (BRIEFING_LLM_KEY_ENV, default
+/// ZAI_API_KEY).
+pub fn key_env_var() -> String {
+    std::env::var("BRIEFING_LLM_KEY_ENV").unwrap_or_else(|_| "ZAI_API_KEY".to_string())
+}
+
+/// Whether an API key is configured. Without one the tools still run, with
+/// extracted ledes instead of summaries and stories grouped by site.
+pub fn credentials_available() -> bool {
+    std::env::var(key_env_var()).is_ok_and(|key| !key.trim().is_empty())
+}
+
+/// Batches are polled this often until they end.
+pub const BATCH_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Give up on a batch after this long; Anthropic expires them after 24h.
+pub const BATCH_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Progress of a submitted batch, from its `request_counts`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchProgress {
+    pub processing: u64,
+    pub succeeded: u64,
+    pub errored: u64,
+    pub canceled: u64,
+    pub expired: u64,
+}
+
+impl BatchProgress {
+    fn from_status(status: &Value) -> Self {
+        let counts = &status["request_counts"];
+        let count = |key: &str| counts[key].as_u64().unwrap_or(0);
+        Self {
+            processing: count("processing"),
+            succeeded: count("succeeded"),
+            errored: count("errored"),
+            canceled: count("canceled"),
+            expired: count("expired"),
+        }
+    }
+
+    pub fn done(&self) -> u64 {
+        self.succeeded + self.errored + self.canceled + self.expired
+    }
+}
+
+pub struct LlmClient {
+    client: Client,
+    api_key: String,
+    model: String,
+    url: String,
+    usage: UsageTracker,
+    limiter: Arc<RateLimiter>,
+}
+
+impl LlmClient {
+    /// Build a client from the environment.
+    ///
+    /// The API key lives in the env var named by BRIEFING_LLM_KEY_ENV
+    /// (default ZAI_API_KEY), so a run can target a different backend —
+    /// e.g. Anthropic via ANTHROPIC_API_KEY — without code changes.
+    /// Endpoint/model are overridable via BRIEFING_LLM_URL/BRIEFING_LLM_MODEL
+    /// for testing alternate backends (e.g. a local llama.cpp /v1/messages
+    /// server). Defaults to z.ai GLM.
+    pub fn from_env(timeout: Duration) -> Result<Self> {
+        let key_var = key_env_var();
+        let api_key = std::env::var(&key_var).with_context(|| format!("{key_var} not set"))?;
+        let model =
+            std::env::var("BRIEFING_LLM_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+        let url = std::env::var("BRIEFING_LLM_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+        Self::new(api_key, model, url, timeout)
+    }
+
+    pub fn new(api_key: String, model: String, url: String, timeout: Duration) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self {
+            client,
+            api_key,
+            model,
+            url,
+            usage: UsageTracker::default(),
+            limiter: RateLimiter::shared(),
+        })
+    }
+
+    /// Record token usage for every request into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Use `limiter` instead of the process-wide one.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+
+    /// How long to wait before retrying a failed request: the limiter's
+    /// pause after a rate limit, otherwise exponential from one second.
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        self.limiter
+            .remaining_pause()
+            .max(Duration::from_millis(1000 * 2_u64.pow(attempt)))
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn authed(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header("Authorization", format!("Bearer {}", &self.api_key))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+    }
+
+    fn params(&self, prompt: &str, max_tokens: u32) -> Value {
+        json!({
+            "model": self.model,
+            "max_tokens": max_tokens,
+            "messages": [{"role": "user", "content": prompt}]
+        })
+    }
+
+    /// Send a single-turn prompt and return the text of the reply. `purpose`
+    /// labels the request in the usage report.
+    pub async fn complete(&self, purpose: &str, prompt: &str, max_tokens: u32) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
+        tracing::debug!(purpose, model = %self.model, prompt_chars = prompt.len(), "API request");
+        let response = self
+            .authed(self.client.post(&self.url))
+            .header("content-type", "application/json")
+            .json(&self.params(prompt, max_tokens))
+            .send()
+            .await
+            .context("API request failed")?;
+        self.limiter
+            .record(response.status().as_u16(), response.headers());
+        let data = check_response(response).await?;
+
+        self.usage.record_response(purpose, &self.model, &data);
+
+        message_text(&data).context("No text in API response")
+    }
+
+    /// Run `prompts` (custom id, prompt) as one batch job and return each
+    /// reply's text by custom id. `progress` is called after every poll.
+    /// Requests that errored or expired are reported per id so the caller can
+    /// retry them individually.
+    pub async fn complete_batch(
+        &self,
+        purpose: &str,
+        prompts: &[(String, String)],
+        max_tokens: u32,
+        mut progress: impl FnMut(&BatchProgress),
+    ) -> Result<HashMap<String, Result<String>>> {
+        let batches_url = format!("{}/batches", self.url.trim_end_matches('/'));
+        let requests: Vec<Value> = prompts
+            .iter()
+            .map(|(id, prompt)| json!({"custom_id": id, "params": self.params(prompt, max_tokens)}))
+            .collect();
+
+        let permit = self.limiter.acquire().await;
+        let response = self
+            .authed(self.client.post(&batches_url))
+            .header("content-type", "application/json")
+            .json(&json!({ "requests": requests }))
+            .send()
+            .await
+            .context("Batch submission failed")?;
+        self.limiter
+            .record(response.status().as_u16(), response.headers());
+        drop(permit);
+        let mut status = check_response(response).await?;
+        let id = status["id"]
+            .as_str()
+            .context("No id in batch response")?
+            .to_string();
+        tracing::debug!(purpose, batch = %id, requests = prompts.len(), "Submitted batch");
+
+        let started = std::time::Instant::now();
+        while status["processing_status"].as_str() != Some("ended") {
+            if started.elapsed() > BATCH_TIMEOUT {
+                anyhow::bail!("Batch {} did not finish within {:?}", id, BATCH_TIMEOUT);
+            }
+            tokio::time::sleep(BATCH_POLL_INTERVAL).await;
+            let response = self
+                .authed(self.client.get(format!("{}/{}", batches_url, id)))
+                .send()
+                .await
+                .context("Batch status request failed")?;
+            status = check_response(response).await?;
+            progress(&BatchProgress::from_status(&status));
+        }
+
+        let results_url = status["results_url"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}/{}/results", batches_url, id));
+        let response = self
+            .authed(self.client.get(&results_url))
+            .send()
+            .await
+            .context("Batch results request failed")?;
+        if !response.status().is_success() {
+            anyhow::bail!("API error {} fetching batch results", response.status());
+        }
+        let body = response
+            .text()
+            .await
+            .context("Failed to read batch results")?;
+
+        Ok(parse_batch_results(&body, |message| {
+            self.usage.record_response(purpose, &self.model, message)
+        }))
+    }
+}
+
+async fn check_response(response: reqwest::Response) -> Result<Value> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = crate::logging::redact(&response.text().await.unwrap_or_default());
+        if status == 401 {
+            anyhow::bail!("authentication_error: {}", text);
+        }
+        anyhow::bail!("API error {}: {}", status, text);
+    }
+
+    response
+        .json()
+        .await
+        .context("Failed to parse API response")
+}
+
+fn message_text(message: &Value) -> Option<String> {
+    message["content"][0]["text"].as_str().map(str::to_string)
+}
+
+/// Parse a batch results JSON-lines body. `on_message` sees every succeeded
+/// message (for usage accounting).
+fn parse_batch_results(
+    body: &str,
+    mut on_message: impl FnMut(&Value),
+) -> HashMap<String, Result<String>> {
+    let mut results = HashMap::new();
+    for line in body.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let Some(id) = entry["custom_id"].as_str() else {
+            continue;
+        };
+        let result = &entry["result"];
+        let outcome = match result["type"].as_str() {
+            Some("succeeded") => {
+                on_message(&result["message"]);
+                message_text(&result["message"]).context("No text in batch result")
+            }
+            Some("errored") => Err(anyhow::anyhow!(
+                "Batch request errored: {}",
+                result["error"]
+            )),
+            other => Err(anyhow::anyhow!(
+                "Batch request {}",
+                other.unwrap_or("missing")
+            )),
+        };
+        results.insert(id.to_string(), outcome);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_results() {
+        let body = r#"{"custom_id":"0","result":{"type":"succeeded","message":{"content":[{"type":"text","text":"FORMAT: EDITORIAL"}],"usage":{"input_tokens":900,"output_tokens":80}}}}
+{"custom_id":"1","result":{"type":"errored","error":{"type":"overloaded_error"}}}
+{"custom_id":"2","result":{"type":"expired"}}
+not json
+"#;
+        let mut messages = 0;
+        let results = parse_batch_results(body, |_| messages += 1);
+
+        assert_eq!(messages, 1);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["0"].as_ref().unwrap(), "FORMAT: EDITORIAL");
+        assert!(results["1"]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("overloaded_error"));
+        assert!(results["2"]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("expired"));
+    }
+
+    #[test]
+    fn test_batch_progress_from_status() {
+        let status = json!({
+            "processing_status": "in_progress",
+            "request_counts": {"processing": 50, "succeeded": 8, "errored": 2, "canceled": 0, "expired": 0}
+        });
+        let progress = BatchProgress::from_status(&status);
+        assert_eq!(progress.processing, 50);
+        assert_eq!(progress.done(), 10);
+    }
+}
@@ -0,0 +1,245 @@
+//! What was uploaded where.
+//!
+//! Every upload is recorded with its URL, local file, time, size, SHA-256
+//! and the server's version of it (its ETag, or the JMAP blob id) in
+//! `~/.local/share/podcast-briefing/published/uploads.json`, one record per
+//! remote URL. Every briefing of a show goes to the same `index.html`, so
+//! uploading another one replaces the record it left. Publishing again skips
+//! the files whose local copy and server copy both still match the record,
+//! and `briefing publish --status` reports which ones don't.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::timezone::ZoneExt;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub uploads: Vec<Upload>,
+}
+
+/// One file as it was uploaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upload {
+    /// The local file's name, e.g. `twit-2026-02-01-LINKS.csv`
+    pub file: String,
+    pub url: String,
+    /// RFC 3339, in the configured time zone
+    pub uploaded: String,
+    pub size: u64,
+    pub sha256: String,
+    /// The server's ETag or blob id for the copy just uploaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// What the server says about its copy of a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteFile {
+    pub size: u64,
+    /// Changes whenever the content does: a WebDAV ETag or a JMAP blob id,
+    /// if the server gives one
+    pub version: Option<String>,
+}
+
+impl Upload {
+    /// A record of `contents`, read from `path`, going up to `url` now.
+    pub fn new(path: &Path, url: &str, contents: &[u8]) -> Self {
+        Self {
+            file: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            url: url.to_string(),
+            uploaded: crate::timezone::local().now().to_rfc3339(),
+            size: contents.len() as u64,
+            sha256: sha256(contents),
+            version: None,
+        }
+    }
+
+    /// Note the server's version of the copy uploaded.
+    pub fn with_version(mut self, version: Option<String>) -> Self {
+        self.version = version;
+        self
+    }
+}
+
+impl Manifest {
+    /// Where the manifest is kept.
+    pub fn default_path() -> PathBuf {
+        crate::config::data_dir()
+            .join("published")
+            .join("uploads.json")
+    }
+
+    /// The manifest at `path`; empty if nothing was published yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize manifest")?;
+        crate::io::save_file(path, json)
+    }
+
+    /// The last upload to `url`.
+    pub fn upload(&self, url: &str) -> Option<&Upload> {
+        self.uploads.iter().find(|upload| upload.url == url)
+    }
+
+    /// Note `upload`, replacing any earlier upload to the same URL.
+    pub fn record(&mut self, upload: Upload) {
+        self.uploads.retain(|u| u.url != upload.url);
+        self.uploads.push(upload);
+    }
+}
+
+/// How a local file compares with its last upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Never uploaded
+    NotPublished,
+    /// Edited or prepared again since it was uploaded
+    Changed,
+    /// The server no longer has it
+    Missing,
+    /// The server's copy isn't the one that was uploaded
+    Differs,
+    Current,
+}
+
+impl Status {
+    /// Compare `contents` with `upload`, and with `remote` (the server's
+    /// copy, `None` inside if it has none) when it was checked. The server's
+    /// copy is compared by version when both it and the record have one,
+    /// and by size otherwise.
+    pub fn of(
+        upload: Option<&Upload>,
+        contents: &[u8],
+        remote: Option<Option<&RemoteFile>>,
+    ) -> Self {
+        let Some(upload) = upload else {
+            return Status::NotPublished;
+        };
+        if upload.sha256 != sha256(contents) {
+            return Status::Changed;
+        }
+        let Some(remote) = remote else {
+            return Status::Current;
+        };
+        let Some(remote) = remote else {
+            return Status::Missing;
+        };
+        let same = match (&upload.version, &remote.version) {
+            (Some(recorded), Some(now)) => recorded == now,
+            _ => remote.size == upload.size,
+        };
+        if same {
+            Status::Current
+        } else {
+            Status::Differs
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::NotPublished => "not published",
+            Status::Changed => "changed since it was published",
+            Status::Missing => "missing from the server",
+            Status::Differs => "different on the server",
+            Status::Current => "current",
+        })
+    }
+}
+
+/// Lowercase hex SHA-256 of `contents`.
+pub fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn remote(size: u64, version: Option<&str>) -> RemoteFile {
+        RemoteFile {
+            size,
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let upload = Upload::new(Path::new("twit.html"), "https://dav/twit", b"<html>");
+        assert_eq!(upload.size, 6);
+        assert_eq!(Status::of(None, b"<html>", None), Status::NotPublished);
+        assert_eq!(Status::of(Some(&upload), b"<html>", None), Status::Current);
+        assert_eq!(
+            Status::of(Some(&upload), b"<html>", Some(Some(&remote(6, None)))),
+            Status::Current
+        );
+        assert_eq!(
+            Status::of(Some(&upload), b"<html>!", Some(Some(&remote(6, None)))),
+            Status::Changed
+        );
+        assert_eq!(
+            Status::of(Some(&upload), b"<html>", Some(None)),
+            Status::Missing
+        );
+        assert_eq!(
+            Status::of(Some(&upload), b"<html>", Some(Some(&remote(3, None)))),
+            Status::Differs
+        );
+    }
+
+    #[test]
+    fn test_status_compares_versions() {
+        let upload = Upload::new(Path::new("twit.html"), "https://dav/twit", b"<html>")
+            .with_version(Some("\"v1\"".to_string()));
+        let status = |remote: RemoteFile| Status::of(Some(&upload), b"<html>", Some(Some(&remote)));
+        assert_eq!(status(remote(6, Some("\"v1\""))), Status::Current);
+        // Another file of the same size
+        assert_eq!(status(remote(6, Some("\"v2\""))), Status::Differs);
+        // A server that stopped sending ETags falls back to the size
+        assert_eq!(status(remote(6, None)), Status::Current);
+    }
+
+    #[test]
+    fn test_record_and_reload() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("published").join("twit-2026-02-01.json");
+        let mut manifest = Manifest::load(&path).unwrap();
+        assert!(manifest.uploads.is_empty());
+
+        manifest.record(Upload::new(Path::new("a.html"), "https://dav/a", b"one"));
+        manifest.record(Upload::new(Path::new("a.csv"), "https://dav/b", b"two"));
+        manifest.record(Upload::new(Path::new("a.html"), "https://dav/a", b"three"));
+        manifest.save(&path).unwrap();
+
+        let manifest = Manifest::load(&path).unwrap();
+        assert_eq!(manifest.uploads.len(), 2);
+        assert_eq!(manifest.upload("https://dav/a").unwrap().size, 5);
+        assert_eq!(manifest.upload("https://dav/b").unwrap().file, "a.csv");
+        assert_eq!(
+            manifest.upload("https://dav/b").unwrap().sha256,
+            "3fc4ccfe745870e2c0d99f71f30ff0656c8dedd41cc1d7d3d376b0dbe685e2f3"
+        );
+    }
+}
@@ -0,0 +1,779 @@
+//! Extractors for links whose pages have little article text: YouTube
+//! videos, Apple Podcasts episodes and posts on X, Bluesky and Mastodon.
+//!
+//! A video's text is its title, description and, when it has captions, the
+//! transcript. An episode's is its show notes, from the iTunes lookup API.
+//! A post's is the post, with the rest of the thread its author wrote
+//! around it, from each network's public API (oEmbed for X, which gives the
+//! post alone). [`ContentExtractor`](crate::extractor::ContentExtractor)
+//! tries these first and falls back to fetching the page if they fail.
+
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use scraper::{Html, Selector};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::extractor::ArticleContent;
+
+/// Bluesky's public API, which needs no login.
+const BLUESKY_API: &str = "https://public.api.bsky.app/xrpc";
+
+/// A link with its own extractor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Media {
+    YouTube {
+        id: String,
+    },
+    ApplePodcast {
+        show: String,
+        episode: String,
+    },
+    /// A post on X (or Twitter, as the link may say)
+    XPost {
+        user: String,
+        id: String,
+    },
+    /// `actor` is a handle or a DID; `post` the record key
+    Bluesky {
+        actor: String,
+        post: String,
+    },
+    Mastodon {
+        instance: String,
+        id: String,
+    },
+}
+
+/// Which kind of media `url` links to, if any.
+pub fn detect(url: &str) -> Option<Media> {
+    let parsed = url::Url::parse(url).ok()?;
+    let full_host = parsed.host_str()?;
+    let host = full_host.trim_start_matches("www.");
+    let query = |name: &str| {
+        parsed
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    let numeric = |id: &str| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit());
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let video = match (host, segments.as_slice()) {
+        ("youtube.com" | "m.youtube.com", ["watch"]) => query("v"),
+        ("youtube.com" | "m.youtube.com", ["shorts" | "live" | "embed", id]) => {
+            Some(id.to_string())
+        }
+        ("youtu.be", [id]) => Some(id.to_string()),
+        ("podcasts.apple.com", [.., show]) => {
+            let show = show.strip_prefix("id")?;
+            let episode = query("i")?;
+            return (numeric(show) && numeric(&episode)).then(|| Media::ApplePodcast {
+                show: show.to_string(),
+                episode,
+            });
+        }
+        (
+            "x.com" | "twitter.com" | "mobile.x.com" | "mobile.twitter.com",
+            [user, "status", id, ..],
+        ) => {
+            return numeric(id).then(|| Media::XPost {
+                user: user.to_string(),
+                id: id.to_string(),
+            });
+        }
+        ("bsky.app", ["profile", actor, "post", post]) => {
+            return Some(Media::Bluesky {
+                actor: actor.to_string(),
+                post: post.to_string(),
+            });
+        }
+        // Mastodon and the servers like it: `/@user/123456789`
+        (_, [user, id]) if user.starts_with('@') && numeric(id) && id.len() >= 6 => {
+            return Some(Media::Mastodon {
+                instance: full_host.to_string(),
+                id: id.to_string(),
+            });
+        }
+        _ => None,
+    }?;
+    let valid = video.len() == 11
+        && video
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(Media::YouTube { id: video })
+}
+
+/// The text of the video, episode or post at `url`.
+pub async fn fetch(client: &Client, media: &Media, url: &str) -> Result<ArticleContent> {
+    let content = match media {
+        Media::YouTube { id } => youtube(client, id, url).await?,
+        Media::ApplePodcast { show, episode } => apple_podcast(client, show, episode, url).await?,
+        // A post is worth summarizing however short it is
+        Media::XPost { user, id } => return x_post(client, user, id, url).await,
+        Media::Bluesky { actor, post } => return bluesky(client, actor, post, url).await,
+        Media::Mastodon { instance, id } => return mastodon(client, instance, id, url).await,
+    };
+    if content.text.trim().len() < 100 {
+        bail!(
+            "Description too short ({} chars)",
+            content.text.trim().len()
+        );
+    }
+    Ok(content)
+}
+
+async fn youtube(client: &Client, id: &str, url: &str) -> Result<ArticleContent> {
+    let html = client
+        .get(format!("https://www.youtube.com/watch?v={}", id))
+        .header(reqwest::header::ACCEPT_LANGUAGE, "en-US,en;q=0.9")
+        .send()
+        .await
+        .context("Failed to fetch the video page")?
+        .error_for_status()
+        .context("Failed to fetch the video page")?
+        .text()
+        .await
+        .context("Failed to read the video page")?;
+    let player = player_response(&html).context("No player data on the video page")?;
+    let transcript = match caption_url(&player) {
+        Some(captions) => match transcript(client, &captions).await {
+            Ok(text) => Some(text).filter(|t| !t.is_empty()),
+            Err(e) => {
+                tracing::debug!(url, "no transcript: {:#}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    Ok(video_content(&player, transcript.as_deref(), id, url))
+}
+
+async fn transcript(client: &Client, captions: &str) -> Result<String> {
+    let xml = client
+        .get(captions)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(parse_transcript(&xml))
+}
+
+/// The `ytInitialPlayerResponse` object embedded in a watch page.
+fn player_response(html: &str) -> Option<Value> {
+    let start = html.find("ytInitialPlayerResponse")?;
+    let rest = &html[start..];
+    let json = &rest[rest.find('{')?..];
+    // The object is followed by more script, which the stream leaves unread
+    serde_json::Deserializer::from_str(json)
+        .into_iter::<Value>()
+        .next()?
+        .ok()
+}
+
+/// The English captions, written ones before automatic, else the first.
+fn caption_url(player: &Value) -> Option<String> {
+    let tracks =
+        player["captions"]["playerCaptionsTracklistRenderer"]["captionTracks"].as_array()?;
+    let english = |track: &&Value| {
+        track["languageCode"]
+            .as_str()
+            .is_some_and(|lang| lang.starts_with("en"))
+    };
+    let automatic = |track: &&Value| track["kind"].as_str() == Some("asr");
+    tracks
+        .iter()
+        .filter(english)
+        .find(|track| !automatic(track))
+        .or_else(|| tracks.iter().find(english))
+        .or_else(|| tracks.first())?["baseUrl"]
+        .as_str()
+        .map(str::to_string)
+}
+
+fn video_content(player: &Value, transcript: Option<&str>, id: &str, url: &str) -> ArticleContent {
+    let details = &player["videoDetails"];
+    let microformat = &player["microformat"]["playerMicroformatRenderer"];
+    let mut text = String::new();
+    for part in [
+        details["title"].as_str(),
+        details["shortDescription"].as_str(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        text.push_str(part.trim());
+        text.push_str("\n\n");
+    }
+    if let Some(transcript) = transcript {
+        text.push_str("Transcript:\n");
+        text.push_str(transcript);
+        text.push('\n');
+    }
+    let published = microformat["publishDate"]
+        .as_str()
+        .or(microformat["uploadDate"].as_str())
+        .and_then(rfc3339);
+    ArticleContent {
+        word_count: text.split_whitespace().count(),
+        text,
+        published_date: published,
+        final_url: url.to_string(),
+        author: details["author"].as_str().map(str::to_string),
+        image: Some(format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", id)),
+        language: None,
+        translated: false,
+        title: None,
+    }
+}
+
+/// The spoken text of a timed-text caption file, as one paragraph.
+fn parse_transcript(xml: &str) -> String {
+    let mut lines = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<text") {
+        let after = &rest[start..];
+        let (Some(open), Some(close)) = (after.find('>'), after.find("</text>")) else {
+            break;
+        };
+        if open < close {
+            // Captions are escaped once for XML and often again inside
+            let line = unescape(&unescape(&after[open + 1..close]));
+            lines.extend(line.split_whitespace().map(str::to_string));
+        }
+        rest = &after[close + "</text>".len()..];
+    }
+    lines.join(" ")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+async fn apple_podcast(
+    client: &Client,
+    show: &str,
+    episode: &str,
+    url: &str,
+) -> Result<ArticleContent> {
+    let lookup: Value = client
+        .get(format!(
+            "https://itunes.apple.com/lookup?id={}&entity=podcastEpisode&limit=200",
+            show
+        ))
+        .send()
+        .await
+        .context("Failed to look up the podcast")?
+        .error_for_status()
+        .context("Failed to look up the podcast")?
+        .json()
+        .await
+        .context("Failed to parse the podcast lookup")?;
+    episode_content(&lookup, episode, url)
+        .with_context(|| format!("Episode {} not in the podcast's recent episodes", episode))
+}
+
+fn episode_content(lookup: &Value, episode: &str, url: &str) -> Option<ArticleContent> {
+    let results = lookup["results"].as_array()?;
+    let found = results.iter().find(|result| {
+        result["wrapperType"].as_str() == Some("podcastEpisode")
+            && result["trackId"]
+                .as_u64()
+                .map(|id| id.to_string())
+                .as_deref()
+                == Some(episode)
+    })?;
+    let mut text = String::new();
+    for part in [found["trackName"].as_str(), found["description"].as_str()]
+        .into_iter()
+        .flatten()
+    {
+        text.push_str(part.trim());
+        text.push_str("\n\n");
+    }
+    Some(ArticleContent {
+        word_count: text.split_whitespace().count(),
+        text,
+        published_date: found["releaseDate"].as_str().and_then(rfc3339),
+        final_url: url.to_string(),
+        author: found["collectionName"].as_str().map(str::to_string),
+        image: found["artworkUrl600"].as_str().map(str::to_string),
+        language: None,
+        translated: false,
+        title: None,
+    })
+}
+
+async fn get_json(client: &Client, url: &str) -> Result<Value> {
+    client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {}", url))
+}
+
+async fn x_post(client: &Client, user: &str, id: &str, url: &str) -> Result<ArticleContent> {
+    let post_url = format!("https://twitter.com/{}/status/{}", user, id);
+    let embed = get_json(
+        client,
+        &format!(
+            "https://publish.twitter.com/oembed?url={}&omit_script=true&dnt=true",
+            urlencoding::encode(&post_url)
+        ),
+    )
+    .await?;
+    x_content(&embed, url).context("The post has no text")
+}
+
+/// The post in an X oEmbed response: `<blockquote><p>text</p>&mdash;
+/// Name (@user) <a>February 1, 2026</a></blockquote>`.
+fn x_content(embed: &Value, url: &str) -> Option<ArticleContent> {
+    let html = Html::parse_fragment(embed["html"].as_str()?);
+    let text = paragraphs(&html);
+    if text.is_empty() {
+        return None;
+    }
+    let link = Selector::parse("blockquote > a").ok()?;
+    let published = html
+        .select(&link)
+        .next_back()
+        .map(|a| a.text().collect::<String>())
+        .and_then(|date| chrono::NaiveDate::parse_from_str(date.trim(), "%B %d, %Y").ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .and_then(|date| rfc3339(&date));
+    let author = embed["author_name"].as_str().unwrap_or("Someone");
+    Some(post_content(author, "X", &[text], published, None, url))
+}
+
+async fn bluesky(client: &Client, actor: &str, post: &str, url: &str) -> Result<ArticleContent> {
+    let did = if actor.starts_with("did:") {
+        actor.to_string()
+    } else {
+        let resolved = get_json(
+            client,
+            &format!(
+                "{}/com.atproto.identity.resolveHandle?handle={}",
+                BLUESKY_API,
+                urlencoding::encode(actor)
+            ),
+        )
+        .await?;
+        resolved["did"]
+            .as_str()
+            .with_context(|| format!("No account {}", actor))?
+            .to_string()
+    };
+    let uri = format!("at://{}/app.bsky.feed.post/{}", did, post);
+    let thread = get_json(
+        client,
+        &format!(
+            "{}/app.bsky.feed.getPostThread?uri={}&depth=20&parentHeight=20",
+            BLUESKY_API,
+            urlencoding::encode(&uri)
+        ),
+    )
+    .await?;
+    bluesky_content(&thread["thread"], url).context("The post has no text")
+}
+
+/// The post in a `getPostThread` response, with the posts before and after
+/// it that its author wrote as replies to themselves.
+fn bluesky_content(thread: &Value, url: &str) -> Option<ArticleContent> {
+    let post = &thread["post"];
+    let author = &post["author"];
+    let did = author["did"].as_str()?;
+    let by_author = |node: &Value| node["post"]["author"]["did"].as_str() == Some(did);
+    let text = |node: &Value| node["post"]["record"]["text"].as_str().map(str::to_string);
+
+    let mut before = Vec::new();
+    let mut parent = &thread["parent"];
+    while by_author(parent) {
+        before.extend(text(parent));
+        parent = &parent["parent"];
+    }
+    before.reverse();
+    let mut posts = before;
+    posts.push(text(thread)?);
+    let mut node = thread;
+    while let Some(reply) = node["replies"]
+        .as_array()
+        .and_then(|replies| replies.iter().find(|r| by_author(r)))
+    {
+        posts.extend(text(reply));
+        node = reply;
+    }
+
+    let name = match (author["displayName"].as_str(), author["handle"].as_str()) {
+        (Some(name), Some(handle)) if !name.is_empty() => format!("{} (@{})", name, handle),
+        (_, Some(handle)) => format!("@{}", handle),
+        _ => did.to_string(),
+    };
+    let image = post["embed"]["images"][0]["thumb"]
+        .as_str()
+        .map(str::to_string);
+    let published = post["record"]["createdAt"].as_str().and_then(rfc3339);
+    Some(post_content(
+        &name, "Bluesky", &posts, published, image, url,
+    ))
+}
+
+async fn mastodon(client: &Client, instance: &str, id: &str, url: &str) -> Result<ArticleContent> {
+    let api = format!("https://{}/api/v1/statuses/{}", instance, id);
+    let status = get_json(client, &api).await?;
+    // Without the thread, the post alone still does
+    let context = match get_json(client, &format!("{}/context", api)).await {
+        Ok(context) => context,
+        Err(e) => {
+            tracing::debug!(url, "no thread: {:#}", e);
+            Value::Null
+        }
+    };
+    mastodon_content(&status, &context, url).context("The post has no text")
+}
+
+/// The status, with the posts before and after it in `context` that its
+/// author wrote as replies to themselves.
+fn mastodon_content(status: &Value, context: &Value, url: &str) -> Option<ArticleContent> {
+    let account = &status["account"];
+    let account_id = account["id"].as_str()?;
+    let by_author = |post: &&Value| post["account"]["id"].as_str() == Some(account_id);
+    let text = |post: &Value| {
+        let text = paragraphs(&Html::parse_fragment(post["content"].as_str()?));
+        (!text.is_empty()).then_some(text)
+    };
+
+    let mut posts: Vec<String> = context["ancestors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(by_author)
+        .filter_map(text)
+        .collect();
+    posts.push(text(status)?);
+    let mut thread: HashSet<&str> = status["id"].as_str().into_iter().collect();
+    for post in context["descendants"].as_array().into_iter().flatten() {
+        let continues = post["in_reply_to_id"]
+            .as_str()
+            .is_some_and(|id| thread.contains(id));
+        if by_author(&post) && continues {
+            thread.extend(post["id"].as_str());
+            posts.extend(text(post));
+        }
+    }
+
+    let acct = account["acct"].as_str().unwrap_or_default();
+    let name = match account["display_name"].as_str() {
+        Some(name) if !name.is_empty() => format!("{} (@{})", name, acct),
+        _ => format!("@{}", acct),
+    };
+    let image = status["media_attachments"][0]["preview_url"]
+        .as_str()
+        .map(str::to_string);
+    let published = status["created_at"].as_str().and_then(rfc3339);
+    Some(post_content(
+        &name, "Mastodon", &posts, published, image, url,
+    ))
+}
+
+/// The text of each paragraph in `html`, a paragraph apiece.
+pub(crate) fn paragraphs(html: &Html) -> String {
+    let selector = Selector::parse("p").expect("valid selector");
+    let mut text: Vec<String> = html
+        .select(&selector)
+        .map(|p| p.text().collect::<Vec<_>>().join(" "))
+        .collect();
+    if text.is_empty() {
+        text.push(html.root_element().text().collect());
+    }
+    text.iter()
+        .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `posts` by `author` on `site`, as one text.
+fn post_content(
+    author: &str,
+    site: &str,
+    posts: &[String],
+    published_date: Option<String>,
+    image: Option<String>,
+    url: &str,
+) -> ArticleContent {
+    let text = format!("{} on {}:\n\n{}\n", author, site, posts.join("\n\n"));
+    ArticleContent {
+        word_count: text.split_whitespace().count(),
+        text,
+        published_date,
+        final_url: url.to_string(),
+        author: Some(author.to_string()),
+        image,
+        language: None,
+        translated: false,
+        title: None,
+    }
+}
+
+/// A date or date-time as RFC 3339, like the article dates.
+pub(crate) fn rfc3339(date: &str) -> Option<String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(date) {
+        return Some(time.with_timezone(&chrono::Utc).to_rfc3339());
+    }
+    let day = chrono::NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?;
+    Some(day.and_hms_opt(0, 0, 0)?.and_utc().to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect() {
+        let video = Some(Media::YouTube {
+            id: "dQw4w9WgXcQ".to_string(),
+        });
+        assert_eq!(
+            detect("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42"),
+            video
+        );
+        assert_eq!(detect("https://youtu.be/dQw4w9WgXcQ?si=abc"), video);
+        assert_eq!(detect("https://m.youtube.com/shorts/dQw4w9WgXcQ"), video);
+        assert_eq!(detect("https://www.youtube.com/@twit"), None);
+        assert_eq!(detect("https://www.youtube.com/watch?v=short"), None);
+        assert_eq!(
+            detect("https://podcasts.apple.com/us/podcast/twit/id1234567?i=1000600000001"),
+            Some(Media::ApplePodcast {
+                show: "1234567".to_string(),
+                episode: "1000600000001".to_string(),
+            })
+        );
+        // The show page, not an episode
+        assert_eq!(
+            detect("https://podcasts.apple.com/us/podcast/twit/id1234567"),
+            None
+        );
+        assert_eq!(detect("https://www.theverge.com/watch?v=dQw4w9WgXcQ"), None);
+    }
+
+    #[test]
+    fn test_detect_posts() {
+        assert_eq!(
+            detect("https://x.com/leolaporte/status/1886000000000000001?s=20"),
+            Some(Media::XPost {
+                user: "leolaporte".to_string(),
+                id: "1886000000000000001".to_string(),
+            })
+        );
+        assert!(matches!(
+            detect("https://mobile.twitter.com/leolaporte/status/123"),
+            Some(Media::XPost { .. })
+        ));
+        assert_eq!(detect("https://x.com/leolaporte"), None);
+        assert_eq!(
+            detect("https://bsky.app/profile/leo.bsky.social/post/3lhxyz2abc"),
+            Some(Media::Bluesky {
+                actor: "leo.bsky.social".to_string(),
+                post: "3lhxyz2abc".to_string(),
+            })
+        );
+        assert_eq!(
+            detect("https://twit.social/@leo/113900000000000001"),
+            Some(Media::Mastodon {
+                instance: "twit.social".to_string(),
+                id: "113900000000000001".to_string(),
+            })
+        );
+        assert_eq!(detect("https://twit.social/@leo"), None);
+        assert_eq!(detect("https://example.com/@home/2026"), None);
+    }
+
+    #[test]
+    fn test_x_content() {
+        let embed = json!({
+            "author_name": "Leo Laporte",
+            "html": "<blockquote class=\"twitter-tweet\"><p lang=\"en\">New <a href=\"#\">#TWiT</a> is up!<br>Go listen</p>&mdash; Leo Laporte (@leolaporte) <a href=\"https://twitter.com/x\">February 1, 2026</a></blockquote>"
+        });
+        let content = x_content(&embed, "https://x.com/leolaporte/status/1").unwrap();
+        assert_eq!(
+            content.text,
+            "Leo Laporte on X:\n\nNew #TWiT is up! Go listen\n"
+        );
+        assert_eq!(
+            content.published_date.as_deref(),
+            Some("2026-02-01T00:00:00+00:00")
+        );
+        assert!(x_content(&json!({"html": "<blockquote></blockquote>"}), "u").is_none());
+    }
+
+    #[test]
+    fn test_bluesky_thread() {
+        let post = |did: &str, text: &str| {
+            json!({"author": {"did": did, "handle": "leo.bsky.social", "displayName": "Leo"},
+                   "record": {"text": text, "createdAt": "2026-02-01T18:00:00.000Z"}})
+        };
+        let thread = json!({
+            "post": post("did:plc:leo", "Two"),
+            "parent": {"post": post("did:plc:leo", "One"),
+                       "parent": {"post": post("did:plc:someone", "Question?")}},
+            "replies": [
+                {"post": post("did:plc:someone", "Nice")},
+                {"post": post("did:plc:leo", "Three"), "replies": []}
+            ]
+        });
+        let content = bluesky_content(&thread, "https://bsky.app/x").unwrap();
+        assert_eq!(
+            content.text,
+            "Leo (@leo.bsky.social) on Bluesky:\n\nOne\n\nTwo\n\nThree\n"
+        );
+        assert_eq!(
+            content.published_date.as_deref(),
+            Some("2026-02-01T18:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_mastodon_thread() {
+        let status = |id: &str, account: &str, reply_to: Option<&str>, html: &str| {
+            json!({"id": id, "in_reply_to_id": reply_to, "content": html,
+                   "account": {"id": account, "acct": "leo@twit.social", "display_name": ""},
+                   "created_at": "2026-02-01T18:00:00.000Z"})
+        };
+        let main = status(
+            "2",
+            "1",
+            Some("1"),
+            "<p>Second</p><p>with <b>two</b> paragraphs</p>",
+        );
+        let context = json!({
+            "ancestors": [status("1", "1", None, "<p>First</p>")],
+            "descendants": [
+                status("3", "9", Some("2"), "<p>Reply from someone</p>"),
+                status("4", "1", Some("3"), "<p>Answering them</p>"),
+                status("5", "1", Some("2"), "<p>Third</p>")
+            ]
+        });
+        let content = mastodon_content(&main, &context, "https://twit.social/@leo/2").unwrap();
+        assert_eq!(
+            content.text,
+            "@leo@twit.social on Mastodon:\n\nFirst\n\nSecond\nwith two paragraphs\n\nThird\n"
+        );
+        // Without the thread
+        let content = mastodon_content(&main, &Value::Null, "u").unwrap();
+        assert_eq!(content.word_count, 7);
+    }
+
+    #[test]
+    fn test_video_page() {
+        let html = r#"<script>var ytInitialPlayerResponse = {"videoDetails":
+            {"title":"Vision Pro teardown","author":"iFixit",
+             "shortDescription":"We take apart the headset."},
+            "microformat":{"playerMicroformatRenderer":{"publishDate":"2026-02-01T08:00:00-08:00"}},
+            "captions":{"playerCaptionsTracklistRenderer":{"captionTracks":[
+                {"baseUrl":"https://yt/asr","languageCode":"en","kind":"asr"},
+                {"baseUrl":"https://yt/de","languageCode":"de"},
+                {"baseUrl":"https://yt/en","languageCode":"en-US"}]}}};
+            var meta = {};</script>"#;
+        let player = player_response(html).unwrap();
+        assert_eq!(caption_url(&player).as_deref(), Some("https://yt/en"));
+
+        let content = video_content(
+            &player,
+            Some("Hi there."),
+            "dQw4w9WgXcQ",
+            "https://youtu.be/x",
+        );
+        assert_eq!(
+            content.text,
+            "Vision Pro teardown\n\nWe take apart the headset.\n\nTranscript:\nHi there.\n"
+        );
+        assert_eq!(content.author.as_deref(), Some("iFixit"));
+        assert_eq!(
+            content.published_date.as_deref(),
+            Some("2026-02-01T16:00:00+00:00")
+        );
+        assert_eq!(
+            content.image.as_deref(),
+            Some("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg")
+        );
+    }
+
+    #[test]
+    fn test_parse_transcript() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8" ?><transcript>
+            <text start="0.5" dur="2">Welcome back &amp;amp; hello</text>
+            <text start="2.5" dur="3">it&amp;#39;s   the
+            show</text><text start="6" dur="1"></text></transcript>"#;
+        assert_eq!(parse_transcript(xml), "Welcome back & hello it's the show");
+        assert_eq!(unescape("AT&T &#x263A; &bogus;"), "AT&T ☺ &bogus;");
+    }
+
+    #[test]
+    fn test_episode_content() {
+        let lookup = json!({ "results": [
+            { "wrapperType": "track", "kind": "podcast", "trackId": 1234567 },
+            { "wrapperType": "podcastEpisode", "trackId": 1000600000001u64,
+              "trackName": "TWiT 1000: Party Time", "collectionName": "This Week in Tech",
+              "description": "Leo and the panel look back.", "releaseDate": "2026-02-01T23:00:00Z",
+              "artworkUrl600": "https://is1.mzstatic.com/art.jpg" }
+        ]});
+        let content =
+            episode_content(&lookup, "1000600000001", "https://podcasts.apple.com/x").unwrap();
+        assert_eq!(
+            content.text,
+            "TWiT 1000: Party Time\n\nLeo and the panel look back.\n\n"
+        );
+        assert_eq!(content.author.as_deref(), Some("This Week in Tech"));
+        assert_eq!(
+            content.published_date.as_deref(),
+            Some("2026-02-01T23:00:00+00:00")
+        );
+        assert!(episode_content(&lookup, "1234567", "https://podcasts.apple.com/x").is_none());
+    }
+}
@@ -1,9 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::clustering::Topic;
+use crate::discovery::Suggestion;
 
 /// Metadata about the show
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ShowInfo {
     pub name: String,
     pub slug: String,
@@ -21,12 +23,20 @@ impl ShowInfo {
 }
 
 /// Complete briefing data for serialization
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BriefingData {
     pub version: String,
     pub created_at: String,
     pub show: ShowInfo,
     pub topics: Vec<Topic>,
+    /// URLs of the stories discussed on air, recorded after the show by
+    /// `briefing reconcile`; `None` until then
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discussed: Option<Vec<String>>,
+    /// Top stories from elsewhere that weren't bookmarked, listed at the
+    /// end of the org draft
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missed: Vec<Suggestion>,
 }
 
 impl BriefingData {
@@ -36,6 +46,8 @@ impl BriefingData {
             created_at: chrono::Utc::now().to_rfc3339(),
             show,
             topics,
+            discussed: None,
+            missed: Vec::new(),
         }
     }
 }
@@ -69,6 +81,9 @@ mod tests {
         let topics = vec![Topic {
             title: "Tech News".to_string(),
             stories: vec![],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
 
         let data = BriefingData::new(show.clone(), topics);
@@ -90,12 +105,18 @@ mod tests {
             summary: Summary::Editorial {
                 lede: "Acme Corp announced a new widget".to_string(),
                 nutgraf: "It changes the industry".to_string(),
-                quote: None,
+                quotes: Vec::new(),
+                reaction: None,
+                by_the_numbers: None,
             },
+            ..Default::default()
         };
         let topics = vec![Topic {
             title: "News".to_string(),
             stories: vec![story],
+            connections: None,
+            notes: None,
+            rationale: None,
         }];
         let data = BriefingData::new(show, topics);
 
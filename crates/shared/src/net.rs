@@ -5,6 +5,9 @@
 //! exhaust memory, silently killing the 3am run. Reading the body with a hard
 //! byte cap prevents that.
 
+/// User agent for fetching pages; some sites turn away anything else.
+pub const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
+
 /// Maximum number of bytes to read from a single HTTP response body.
 pub const MAX_BODY_BYTES: usize = 16 * 1024 * 1024; // 16 MiB
 
@@ -0,0 +1,173 @@
+//! The `[notify]` section of `config.toml`: Slack and Discord webhooks that
+//! get a message when a briefing is uploaded, so the production team knows
+//! the briefing book is ready.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::clustering::Topic;
+
+/// Discord rejects longer messages; Slack allows more, but a channel
+/// message shouldn't be longer either.
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NotifySettings {
+    /// Slack incoming webhook URL
+    pub slack: Option<String>,
+    /// Discord channel webhook URL
+    pub discord: Option<String>,
+    /// Link to post instead of the uploaded file's URL, with `{slug}` for
+    /// the show, for when the WebDAV folder is published at another address
+    pub link: Option<String>,
+}
+
+impl NotifySettings {
+    pub fn is_enabled(&self) -> bool {
+        self.slack.is_some() || self.discord.is_some()
+    }
+
+    /// The briefing's address for the message.
+    pub fn link_for(&self, show_slug: &str, uploaded_url: &str) -> String {
+        match &self.link {
+            Some(link) => link.replace("{slug}", show_slug),
+            None => uploaded_url.to_string(),
+        }
+    }
+
+    /// Post `message` to every configured webhook. Returns each service's
+    /// name and how it went.
+    pub async fn post(&self, message: &str) -> Vec<(&'static str, Result<()>)> {
+        let client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => return vec![("webhooks", Err(e.into()))],
+        };
+        let mut results = Vec::new();
+        if let Some(url) = &self.slack {
+            let body = serde_json::json!({ "text": message });
+            results.push(("Slack", send(&client, url, &body).await));
+        }
+        if let Some(url) = &self.discord {
+            let body = serde_json::json!({ "content": message });
+            results.push(("Discord", send(&client, url, &body).await));
+        }
+        results
+    }
+}
+
+async fn send(client: &reqwest::Client, url: &str, body: &serde_json::Value) -> Result<()> {
+    let response = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| e.without_url())
+        .context("request failed")?;
+    if !response.status().is_success() {
+        bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+/// The message for an uploaded briefing: a line with the show, date and
+/// link, then each topic with its story count. Topics that don't fit are
+/// counted at the end.
+pub fn message(show: &str, date: &str, url: &str, topics: &[Topic]) -> String {
+    let stories: usize = topics.iter().map(|t| t.stories.len()).sum();
+    let mut text = format!("📋 {} briefing for {} is ready: {}", show, date, url);
+    if topics.is_empty() {
+        return text;
+    }
+    text.push_str(&format!(
+        "\n{} {} in {} {}:",
+        stories,
+        if stories == 1 { "story" } else { "stories" },
+        topics.len(),
+        if topics.len() == 1 { "topic" } else { "topics" }
+    ));
+    for (i, topic) in topics.iter().enumerate() {
+        let line = format!("\n• {} ({})", topic.title, topic.stories.len());
+        let more = format!("\n…and {} more", topics.len() - i);
+        if text.chars().count() + line.chars().count() + more.chars().count() > MAX_MESSAGE_CHARS {
+            text.push_str(&more);
+            break;
+        }
+        text.push_str(&line);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::Story;
+    use crate::summarizer::Summary;
+
+    fn topic(title: &str, stories: usize) -> Topic {
+        Topic {
+            title: title.to_string(),
+            stories: (0..stories)
+                .map(|i| Story {
+                    title: format!("Story {}", i),
+                    url: format!("https://example.com/{}", i),
+                    summary: Summary::Failed("none".to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+            connections: None,
+            notes: None,
+            rationale: None,
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        let text = message(
+            "This Week in Tech",
+            "Sunday, 1 February 2026",
+            "https://dav.example.com/twit/index.html",
+            &[topic("Apple", 2), topic("Google", 1)],
+        );
+        assert_eq!(
+            text,
+            "📋 This Week in Tech briefing for Sunday, 1 February 2026 is ready: \
+             https://dav.example.com/twit/index.html\n\
+             3 stories in 2 topics:\n\
+             • Apple (2)\n\
+             • Google (1)"
+        );
+    }
+
+    #[test]
+    fn test_long_message_is_cut() {
+        let topics: Vec<Topic> = (0..200)
+            .map(|i| topic(&format!("Topic number {}", i), 1))
+            .collect();
+        let text = message("TWiT", "today", "https://x", &topics);
+        assert!(text.chars().count() <= MAX_MESSAGE_CHARS);
+        assert!(text.contains("• Topic number 0 (1)"));
+        assert!(text.ends_with(" more"), "{}", text);
+    }
+
+    #[test]
+    fn test_link_for() {
+        let settings: NotifySettings = toml::from_str(
+            r#"
+            slack = "https://hooks.slack.com/x"
+            link = "https://briefings.example.com/{slug}/"
+            "#,
+        )
+        .unwrap();
+        assert!(settings.is_enabled());
+        assert_eq!(
+            settings.link_for("twit", "https://dav.example.com/twit/index.html"),
+            "https://briefings.example.com/twit/"
+        );
+        assert!(!NotifySettings::default().is_enabled());
+    }
+}
@@ -0,0 +1,240 @@
+//! "By the Numbers": key figures pulled from the finished summaries.
+//!
+//! Each story contributes at most one figure, picked in order of how well
+//! it plays as a quick hit: money, then headcounts and other counted
+//! things, then percentages, then a product's availability date. The
+//! scanning is purely textual, so it costs nothing and needs no API key.
+
+use crate::clustering::Topic;
+use crate::summarizer::Summary;
+
+/// Most figures shown in one briefing.
+pub const MAX_FIGURES: usize = 10;
+
+const SCALES: &[&str] = &[
+    "thousand", "million", "billion", "trillion", "k", "m", "b", "bn", "tn",
+];
+
+/// Nouns that make a bare number worth calling out.
+const COUNTED: &[&str] = &[
+    "layoffs",
+    "jobs",
+    "employees",
+    "workers",
+    "staff",
+    "people",
+    "users",
+    "subscribers",
+    "customers",
+    "accounts",
+    "devices",
+    "downloads",
+    "records",
+];
+
+const MONTHS: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Money,
+    Count,
+    Percent,
+    Date,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figure {
+    /// The number as written, e.g. "$6.6 billion" or "12,000 jobs"
+    pub figure: String,
+    /// Title of the story it came from
+    pub title: String,
+    pub url: String,
+}
+
+/// Up to [`MAX_FIGURES`] figures, in briefing order.
+pub fn extract(topics: &[Topic]) -> Vec<Figure> {
+    topics
+        .iter()
+        .flat_map(|topic| &topic.stories)
+        .filter_map(|story| {
+            let candidates = match &story.summary {
+                Summary::Editorial {
+                    lede,
+                    nutgraf,
+                    by_the_numbers,
+                    ..
+                } => {
+                    let mut found = scan(lede);
+                    found.extend(scan(nutgraf));
+                    found.extend(by_the_numbers.iter().flat_map(|n| scan(n)));
+                    found
+                }
+                Summary::Product {
+                    the_product,
+                    cost,
+                    availability,
+                    ..
+                } => {
+                    let mut found = scan(cost);
+                    found.extend(scan(the_product));
+                    if is_date(availability) {
+                        found.push((Kind::Date, availability.trim().to_string()));
+                    }
+                    found
+                }
+                Summary::Insufficient | Summary::Failed(_) => Vec::new(),
+            };
+            // `min_by_key` keeps the first of equally good figures
+            let (_, figure) = candidates.into_iter().min_by_key(|(kind, _)| *kind)?;
+            Some(Figure {
+                figure,
+                title: story.title.clone(),
+                url: story.url.clone(),
+            })
+        })
+        .take(MAX_FIGURES)
+        .collect()
+}
+
+/// Every figure in `text`, in order of appearance.
+fn scan(text: &str) -> Vec<(Kind, String)> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = trim_punct(words[i]);
+        let next = words.get(i + 1).map(|w| trim_punct(w));
+        let scaled = next.filter(|n| SCALES.contains(&n.to_lowercase().as_str()));
+
+        let money = word
+            .strip_prefix(['$', '€', '£'])
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+        if money {
+            let figure = match scaled {
+                Some(scale) => format!("{} {}", word, scale),
+                None => word.to_string(),
+            };
+            found.push((Kind::Money, figure));
+            i += 1 + usize::from(scaled.is_some());
+            continue;
+        }
+
+        if is_number(word.trim_end_matches('%')) {
+            if word.ends_with('%') {
+                found.push((Kind::Percent, word.to_string()));
+            } else if next.is_some_and(|n| n.eq_ignore_ascii_case("percent")) {
+                found.push((Kind::Percent, format!("{}%", word)));
+            } else {
+                // "12,000 jobs", "1.2 million users", "500 more workers"
+                let noun_at = i + 1 + usize::from(scaled.is_some());
+                let counted = (noun_at..words.len().min(noun_at + 2))
+                    .find(|&j| COUNTED.contains(&trim_punct(words[j]).to_lowercase().as_str()));
+                if let Some(j) = counted {
+                    let figure: Vec<&str> = words[i..=j].iter().map(|w| trim_punct(w)).collect();
+                    found.push((Kind::Count, figure.join(" ")));
+                    i = j + 1;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    found
+}
+
+fn trim_punct(word: &str) -> &str {
+    word.trim_matches(|c: char| matches!(c, ',' | '.' | ';' | ':' | '(' | ')' | '"' | '\''))
+}
+
+/// Digits with optional thousands separators and decimal point.
+fn is_number(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_digit())
+        && word.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+        // Years aren't figures
+        && !(word.len() == 4 && (word.starts_with("19") || word.starts_with("20")))
+}
+
+/// A short availability line that names a date, like "March 15" or
+/// "Ships Oct. 3".
+fn is_date(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    text.len() <= 40
+        && text.chars().any(|c| c.is_ascii_digit())
+        && lower
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .any(|w| w.len() >= 3 && MONTHS.iter().any(|m| w.starts_with(m)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::Story;
+    use crate::testing::story;
+
+    #[test]
+    fn test_scan_recognizes_money_counts_and_percentages() {
+        let found = scan(
+            "Intel will cut 15,000 jobs, about 15 percent of staff, after losing $1.6 billion. \
+             Profits rose 12% in 2024.",
+        );
+        assert_eq!(
+            found,
+            vec![
+                (Kind::Count, "15,000 jobs".to_string()),
+                (Kind::Percent, "15%".to_string()),
+                (Kind::Money, "$1.6 billion".to_string()),
+                (Kind::Percent, "12%".to_string()),
+            ]
+        );
+        assert!(scan("Apple shipped a thing in 2026 to 3 reviewers.").is_empty());
+        assert_eq!(
+            scan("now reaches 1.2 million monthly users")[0].1,
+            "1.2 million monthly users"
+        );
+    }
+
+    #[test]
+    fn test_extract_takes_best_figure_per_story() {
+        let topics = vec![Topic {
+            title: "Business".to_string(),
+            stories: vec![
+                story("Intel layoffs")
+                    .with_lede(
+                        "Intel will cut 15,000 jobs after losing $1.6 billion.",
+                        "It matters because of what comes next.",
+                    )
+                    .build(),
+                story("Quiet week")
+                    .with_lede(
+                        "Nothing numeric happened.",
+                        "It matters because of what comes next.",
+                    )
+                    .build(),
+                Story {
+                    title: "Pixel 11".to_string(),
+                    url: "https://example.com/pixel".to_string(),
+                    summary: Summary::Product {
+                        the_product: "Google's new phone".to_string(),
+                        cost: String::new(),
+                        availability: "Ships Oct. 3".to_string(),
+                        platforms: "Android".to_string(),
+                        quotes: Vec::new(),
+                        reaction: None,
+                    },
+                    ..Default::default()
+                },
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        }];
+
+        let figures = extract(&topics);
+        assert_eq!(figures.len(), 2);
+        assert_eq!(figures[0].figure, "$1.6 billion");
+        assert_eq!(figures[0].title, "Intel layoffs");
+        assert_eq!(figures[1].figure, "Ships Oct. 3");
+    }
+}
@@ -0,0 +1,1237 @@
+//! Parsing of the edited org-mode briefing back into topics and stories.
+//!
+//! This is the inverse of `BriefingGenerator::generate_org_mode`: the
+//! top-level headings are topics, the ones below them stories, and `URL`,
+//! `Date` and `Summary` sections one level further down carry each story's
+//! fields. The draft usually starts at level 1 (`* Topic`, `** Story`,
+//! `*** URL`), but an org template can move it deeper. Tags on a story
+//! heading (`** Title :leo:andy:`) are the hosts assigned to it, and a
+//! `TODO` keyword (`** TODO Title`) stars it as a must-cover story. It stays
+//! starred once the keyword is cycled to `DONE`.
+//! A line starting with [`CONNECTIONS_PREFIX`] between a topic heading and
+//! its first story is the topic's "How these connect" paragraph, and one
+//! starting with [`RATIONALE_PREFIX`] the clusterer's reason for it. Any other
+//! text there is kept as the topic's notes, so sections without stories
+//! (picks, ad reads, housekeeping) survive the round trip. Likewise, text
+//! under a story that isn't one of its fields (before its first section,
+//! in `*** Notes` or another section of the host's own, or after a complete
+//! summary) becomes the story's notes. A `:DETAILS:` drawer right under
+//! a story heading holds its source, author and length and the companies,
+//! products and people it names, and a
+//! [`PREVIOUSLY_PREFIX`] line under it names the earlier briefing a
+//! follow-up story was in, with a [`RECAP_PREFIX`] line recapping it.
+//! A `:PROPERTIES:` drawer there can carry the fields instead of sections,
+//! the org-agenda way: `:URL:`, `:DATE:`, the details keys, and
+//! `:PRIORITY: A` to star the story. Other drawers are skipped.
+//! Org links (`[[https://…][Title]]`), which Emacs often makes of pasted
+//! URLs, are read as their target in the URL field and as their
+//! description in titles and summaries.
+
+use anyhow::Result;
+
+use crate::clustering::{Story, StoryDetails, Topic};
+use crate::entities::Entities;
+use crate::quotes::Quote;
+use crate::summarizer::Summary;
+
+/// Label of the topic's connections paragraph in the org file.
+pub const CONNECTIONS_PREFIX: &str = "How these connect: ";
+
+/// Label of the line on why the clusterer grouped a topic's stories.
+pub const RATIONALE_PREFIX: &str = "Grouped because: ";
+
+/// Label of an editorial summary's key figures in the org file.
+pub const BY_THE_NUMBERS_PREFIX: &str = "By the numbers: ";
+
+/// Label of the informational disclosure line under a story heading.
+pub const DISCLOSURE_PREFIX: &str = "DISCLOSURE NEEDED: ";
+
+/// Label of the informational age line under an old story's heading.
+pub const STALE_PREFIX: &str = "STALE: ";
+
+/// Label of the informational line under a story that needs careful
+/// framing.
+pub const SENSITIVE_PREFIX: &str = "SENSITIVE: ";
+
+/// Label of the line naming the earlier briefing a follow-up story was in.
+pub const PREVIOUSLY_PREFIX: &str = "PREVIOUSLY: ";
+
+/// Label of the one-line recap of that earlier coverage.
+pub const RECAP_PREFIX: &str = "RECAP: ";
+
+/// Keyword on a starred story's heading.
+pub const STARRED_KEYWORD: &str = "TODO";
+
+/// What org's default keyword cycle turns [`STARRED_KEYWORD`] into.
+const DONE_KEYWORD: &str = "DONE";
+
+/// Section holding a story's notes.
+pub const NOTES_SECTION: &str = "Notes";
+
+/// Opens the drawer of a story's [`StoryDetails`].
+pub const DETAILS_DRAWER: &str = ":DETAILS:";
+
+/// Opens the standard org property drawer.
+const PROPERTIES_DRAWER: &str = ":PROPERTIES:";
+
+const DRAWER_END: &str = ":END:";
+
+/// Org priority that stars a story (`:PRIORITY: A`).
+const STARRED_PRIORITY: &str = "A";
+
+/// Story sections with fields of their own.
+const FIELD_SECTIONS: &[&str] = &["URL", "Date", "Summary"];
+
+/// Split trailing org tags off a heading: `"Title :leo:andy:"` gives
+/// `("Title", ["leo", "andy"])`.
+pub fn split_tags(heading: &str) -> (&str, Vec<String>) {
+    let heading = heading.trim_end();
+    let Some((title, last)) = heading.rsplit_once(char::is_whitespace) else {
+        return (heading, Vec::new());
+    };
+    let is_tags = last.len() > 2
+        && last.starts_with(':')
+        && last.ends_with(':')
+        && last
+            .chars()
+            .all(|c| c == ':' || c == '_' || c == '@' || c.is_alphanumeric());
+    if !is_tags || last.contains("::") {
+        return (heading, Vec::new());
+    }
+    let tags = last
+        .split(':')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect();
+    (title.trim_end(), tags)
+}
+
+/// Split a `TODO` or `DONE` keyword off a heading, which stars the story.
+fn split_keyword(heading: &str) -> (&str, bool) {
+    for keyword in [STARRED_KEYWORD, DONE_KEYWORD] {
+        if let Some(title) = heading
+            .strip_prefix(keyword)
+            .and_then(|rest| rest.strip_prefix(' '))
+        {
+            return (title.trim_start(), true);
+        }
+    }
+    (heading, false)
+}
+
+/// A story's details and entities as an org drawer, or nothing if there
+/// are none.
+pub fn details_drawer(details: &StoryDetails, entities: Option<&Entities>) -> String {
+    let mut drawer = String::new();
+    let mut property = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            drawer.push_str(&format!(":{}: {}\n", key, value));
+        }
+    };
+    property("SOURCE", details.source.clone());
+    property("AUTHOR", details.author.clone());
+    property("WORDS", details.words.map(|w| w.to_string()));
+    property(
+        "READING_TIME",
+        details.reading_minutes.map(|m| format!("{} min", m)),
+    );
+    property("IMAGE", details.image.clone());
+    property("LANGUAGE", details.language.clone());
+    property("TRANSLATED", details.translated.then(|| "yes".to_string()));
+    if let Some(entities) = entities {
+        for (key, names) in [
+            ("COMPANIES", &entities.companies),
+            ("PRODUCTS", &entities.products),
+            ("PEOPLE", &entities.people),
+        ] {
+            property(key, (!names.is_empty()).then(|| names.join(", ")));
+        }
+    }
+    if drawer.is_empty() {
+        return drawer;
+    }
+    format!("{}\n{}{}\n", DETAILS_DRAWER, drawer, DRAWER_END)
+}
+
+/// Read a `:COMPANIES:`, `:PRODUCTS:` or `:PEOPLE:` line of a details
+/// drawer into the story's entities. False for any other line.
+fn read_entities(story: &mut Story, line: &str) -> bool {
+    let Some((key, value)) = line.strip_prefix(':').and_then(|l| l.split_once(':')) else {
+        return false;
+    };
+    let key = key.to_ascii_uppercase();
+    if !["COMPANIES", "PRODUCTS", "PEOPLE"].contains(&key.as_str()) {
+        return false;
+    }
+    let names: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .collect();
+    let mut entities = story.entities.take().unwrap_or_default();
+    match key.as_str() {
+        "COMPANIES" => entities.companies = names,
+        "PRODUCTS" => entities.products = names,
+        _ => entities.people = names,
+    }
+    story.entities = (!entities.is_empty()).then_some(entities);
+    true
+}
+
+/// Read one `:KEY: value` line of a details drawer. Unknown keys are
+/// ignored.
+fn read_detail(details: &mut StoryDetails, line: &str) {
+    let Some((key, value)) = line.strip_prefix(':').and_then(|l| l.split_once(':')) else {
+        return;
+    };
+    let value = value.trim();
+    // Leading digits, so "1,200" and "6 min" read as numbers
+    let number = || {
+        let digits: String = value
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == ',')
+            .filter(char::is_ascii_digit)
+            .collect();
+        digits.parse::<usize>().ok()
+    };
+    match key.to_ascii_uppercase().as_str() {
+        "SOURCE" => details.source = (!value.is_empty()).then(|| value.to_string()),
+        "AUTHOR" => details.author = (!value.is_empty()).then(|| value.to_string()),
+        "WORDS" => details.words = number(),
+        "READING_TIME" => details.reading_minutes = number().map(|m| m as u32),
+        "IMAGE" => details.image = (!value.is_empty()).then(|| value.to_string()),
+        "LANGUAGE" => details.language = (!value.is_empty()).then(|| value.to_string()),
+        "TRANSLATED" => details.translated = value.eq_ignore_ascii_case("yes"),
+        _ => {}
+    }
+}
+
+/// An org link, `[[https://example.com][Title]]`, to `url` described
+/// as `title`.
+pub fn link(url: &str, title: &str) -> String {
+    // Brackets would end the description early
+    let title = title.replace('[', "(").replace(']', ")");
+    if title.trim().is_empty() {
+        format!("[[{}]]", url)
+    } else {
+        format!("[[{}][{}]]", url, title)
+    }
+}
+
+/// `text` with each org link replaced by its description, or by its target
+/// if it has none.
+pub fn unwrap_links(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let Some(end) = rest[start..].find("]]").map(|e| start + e) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let inner = &rest[start + 2..end];
+        match inner.split_once("][") {
+            Some((_, description)) if !description.trim().is_empty() => out.push_str(description),
+            Some((target, _)) => out.push_str(target),
+            None => out.push_str(inner),
+        }
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The target of `text` if it is a single org link, else `text` itself.
+fn link_target(text: &str) -> &str {
+    let Some(inner) = text
+        .trim()
+        .strip_prefix("[[")
+        .and_then(|t| t.strip_suffix("]]"))
+    else {
+        return text;
+    };
+    if inner.contains("]]") || inner.contains("[[") {
+        return text;
+    }
+    inner
+        .split_once("][")
+        .map_or(inner, |(target, _)| target)
+        .trim()
+}
+
+/// Read a `:KEY: value` line of a story's property drawer.
+fn read_property(story: &mut Story, line: &str) {
+    let Some((key, value)) = line.strip_prefix(':').and_then(|l| l.split_once(':')) else {
+        return;
+    };
+    let value = value.trim();
+    match key.to_ascii_uppercase().as_str() {
+        "URL" => story.url = link_target(value).to_string(),
+        "DATE" => story.created = value.to_string(),
+        "PRIORITY" => {
+            if value.eq_ignore_ascii_case(STARRED_PRIORITY) {
+                story.starred = true;
+            }
+        }
+        _ => {
+            if !read_entities(story, line) {
+                read_detail(story.details.get_or_insert_with(Default::default), line)
+            }
+        }
+    }
+}
+
+/// Sections prepare-briefing builds afresh from the stories, or that only
+/// suggest stories; their org text is for reference only.
+const REGENERATED_SECTIONS: &[&str] = &["By the Numbers", crate::discovery::SECTION_TITLE];
+
+/// A parsed story without a URL, date or summary, which the briefing would
+/// show with that part missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompleteStory {
+    /// 1-based line of the story heading in the org file
+    pub line: usize,
+    pub title: String,
+    /// `"URL"`, `"date"` and/or `"summary"`
+    pub missing: Vec<&'static str>,
+}
+
+impl IncompleteStory {
+    fn check(story: &Story, line: usize) -> Option<Self> {
+        let mut missing = Vec::new();
+        if story.url.trim().is_empty() {
+            missing.push("URL");
+        }
+        if story.created.trim().is_empty() {
+            missing.push("date");
+        }
+        if matches!(story.summary, Summary::Insufficient | Summary::Failed(_)) {
+            missing.push("summary");
+        }
+        (!missing.is_empty()).then(|| IncompleteStory {
+            line,
+            title: story.title.clone(),
+            missing,
+        })
+    }
+}
+
+impl std::fmt::Display for IncompleteStory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: \"{}\" has no {}",
+            self.line,
+            self.title,
+            self.missing.join(", ")
+        )
+    }
+}
+
+/// The keyword line naming the show, `#+SHOW: This Week in Tech`. The
+/// `#+TITLE:` is free text, so the name isn't read from it.
+pub const SHOW_KEYWORD: &str = "#+SHOW:";
+
+/// Parse an org briefing into its show name (from [`SHOW_KEYWORD`], empty
+/// if the file has none) and topics.
+/// Topics with neither stories nor notes (e.g. an untouched "In Memoriam")
+/// are dropped.
+pub fn parse_org_mode(content: &str) -> Result<(String, Vec<Topic>)> {
+    let (show_name, topics, _) = parse_org_mode_checked(content)?;
+    Ok((show_name, topics))
+}
+
+/// [`parse_org_mode`], also listing the stories that came out incomplete,
+/// in file order.
+pub fn parse_org_mode_checked(content: &str) -> Result<(String, Vec<Topic>, Vec<IncompleteStory>)> {
+    let lines = content.lines();
+    let top = content
+        .lines()
+        .filter_map(|line| heading(line.trim()))
+        .map(|(level, _)| level)
+        .min()
+        .unwrap_or(1);
+    let mut show_name = String::new();
+    let mut topics: Vec<Topic> = Vec::new();
+    let mut current_topic: Option<Topic> = None;
+    let mut current_story: Option<Story> = None;
+    let mut current_section: Option<String> = None;
+    let mut notes: Vec<&str> = Vec::new();
+    let mut story_notes: Vec<String> = Vec::new();
+    let mut in_details = false;
+    // Heading lines of the current topic's stories, then of every kept
+    // topic's
+    let mut story_line = 0;
+    let mut story_lines: Vec<usize> = Vec::new();
+    let mut lines_by_topic: Vec<Vec<usize>> = Vec::new();
+    let mut in_properties = false;
+    let mut in_drawer = false;
+    let mut lede: Option<String> = None;
+    let mut nutgraf: Option<String> = None;
+    let mut the_product: Option<String> = None;
+    let mut cost: Option<String> = None;
+    let mut availability: Option<String> = None;
+    let mut platforms: Option<String> = None;
+    let mut quotes: Vec<Quote> = Vec::new();
+    let mut reaction: Option<String> = None;
+    let mut by_the_numbers: Option<String> = None;
+    let mut in_comment = false;
+
+    for (index, line) in lines.enumerate() {
+        let trimmed = line.trim();
+
+        // Comment blocks, like the sources breakdown, are for reading only
+        if in_comment || trimmed.eq_ignore_ascii_case("#+BEGIN_COMMENT") {
+            in_comment = !trimmed.eq_ignore_ascii_case("#+END_COMMENT");
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix(SHOW_KEYWORD) {
+            show_name = name.trim().to_string();
+            continue;
+        }
+
+        // Skip other properties
+        if trimmed.starts_with("#+") {
+            continue;
+        }
+
+        let heading = heading(trimmed);
+
+        // Top-level heading: Topic
+        if let Some((_, title)) = heading.filter(|(level, _)| *level == top) {
+            // Save previous topic if exists
+            if let Some(mut topic) = current_topic.take() {
+                if let Some(story) = current_story.take() {
+                    topic.stories.push(finish_story(story, &mut story_notes));
+                    story_lines.push(story_line);
+                }
+                if finish_topic(&mut topics, topic, std::mem::take(&mut notes)) {
+                    lines_by_topic.push(std::mem::take(&mut story_lines));
+                }
+            }
+            story_lines.clear();
+
+            // Start new topic
+            current_topic = Some(Topic {
+                title: unwrap_links(title.trim()),
+                stories: Vec::new(),
+                connections: None,
+                notes: None,
+                rationale: None,
+            });
+            current_story = None;
+            current_section = None;
+            continue;
+        }
+
+        // One level down: Story title
+        if let Some((_, title)) = heading.filter(|(level, _)| *level == top + 1) {
+            // Save previous story if exists
+            if let Some(story) = current_story.take() {
+                if let Some(ref mut topic) = current_topic {
+                    topic.stories.push(finish_story(story, &mut story_notes));
+                    story_lines.push(story_line);
+                }
+            }
+            story_notes.clear();
+
+            // Start new story
+            story_line = index + 1;
+            let (title, hosts) = split_tags(title);
+            let (title, starred) = split_keyword(title);
+            current_story = Some(Story {
+                title: unwrap_links(title.trim()),
+                url: String::new(),
+                summary: Summary::Insufficient,
+                hosts,
+                starred,
+                ..Default::default()
+            });
+            current_section = None;
+            in_details = false;
+            in_properties = false;
+            in_drawer = false;
+            lede = None;
+            nutgraf = None;
+            the_product = None;
+            cost = None;
+            availability = None;
+            platforms = None;
+            quotes.clear();
+            reaction = None;
+            by_the_numbers = None;
+            continue;
+        }
+
+        // Two levels down: Section (URL or Summary)
+        if let Some((_, section)) = heading.filter(|(level, _)| *level == top + 2) {
+            let section = section.trim();
+            if current_story.is_some()
+                && section != NOTES_SECTION
+                && !FIELD_SECTIONS.contains(&section)
+            {
+                // The host's own section: keep its name with the notes
+                if !story_notes.is_empty() {
+                    story_notes.push(String::new());
+                }
+                story_notes.push(format!("{}:", section.trim_end_matches(':')));
+            }
+            current_section = Some(section.to_string());
+            continue;
+        }
+
+        // The story's drawers: its details, and any a template adds
+        if let Some(story) = current_story.as_mut() {
+            if trimmed == DETAILS_DRAWER {
+                in_details = true;
+                continue;
+            }
+            if in_details {
+                if trimmed == DRAWER_END {
+                    in_details = false;
+                } else if !read_entities(story, trimmed) {
+                    read_detail(story.details.get_or_insert_with(Default::default), trimmed);
+                }
+                continue;
+            }
+            if in_properties {
+                if trimmed == DRAWER_END {
+                    in_properties = false;
+                } else {
+                    read_property(story, trimmed);
+                }
+                continue;
+            }
+            if in_drawer {
+                in_drawer = trimmed != DRAWER_END;
+                continue;
+            }
+            if current_section.is_none() && trimmed == PROPERTIES_DRAWER {
+                in_properties = true;
+                continue;
+            }
+            if current_section.is_none() && is_drawer(trimmed) {
+                in_drawer = true;
+                continue;
+            }
+        }
+
+        // Topic text before the first story
+        if let (None, Some(topic)) = (&current_story, current_topic.as_mut()) {
+            if let Some(text) = trimmed.strip_prefix(CONNECTIONS_PREFIX.trim_end()) {
+                let text = text.trim();
+                topic.connections = (!text.is_empty()).then(|| text.to_string());
+            } else if let Some(text) = trimmed.strip_prefix(RATIONALE_PREFIX.trim_end()) {
+                let text = text.trim();
+                topic.rationale = (!text.is_empty()).then(|| text.to_string());
+            } else {
+                notes.push(line.trim_end());
+            }
+            continue;
+        }
+
+        // Story text outside its fields
+        let in_fields = current_section
+            .as_deref()
+            .is_some_and(|s| FIELD_SECTIONS.contains(&s));
+        if !in_fields {
+            if let (Some(story), Some(label)) = (
+                current_story.as_mut(),
+                trimmed.strip_prefix(PREVIOUSLY_PREFIX.trim_end()),
+            ) {
+                let label = label.trim();
+                story.previously = (!label.is_empty()).then(|| label.to_string());
+                continue;
+            }
+            if let (Some(story), Some(recap)) = (
+                current_story.as_mut(),
+                trimmed.strip_prefix(RECAP_PREFIX.trim_end()),
+            ) {
+                let recap = recap.trim();
+                story.followup = (!recap.is_empty()).then(|| recap.to_string());
+                continue;
+            }
+            if !trimmed.starts_with(DISCLOSURE_PREFIX.trim_end())
+                && !trimmed.starts_with(STALE_PREFIX.trim_end())
+                && !trimmed.starts_with(SENSITIVE_PREFIX.trim_end())
+            {
+                story_notes.push(line.trim_end().to_string());
+            }
+            continue;
+        }
+
+        // Content lines
+        if !trimmed.is_empty() {
+            if let Some(ref section) = current_section {
+                match section.as_str() {
+                    "URL" => {
+                        if let Some(ref mut story) = current_story {
+                            story.url = link_target(trimmed).to_string();
+                        }
+                    }
+                    "Date" => {
+                        if let Some(ref mut story) = current_story {
+                            story.created = trimmed.to_string();
+                        }
+                    }
+                    "Summary" => {
+                        let text = unwrap_links(trimmed);
+                        let trimmed = text.as_str();
+                        if trimmed.starts_with(['"', '“']) {
+                            quotes.push(Quote::parse(trimmed));
+                        } else if let Some(val) = trimmed.strip_prefix("LEDE: ") {
+                            lede = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix("NUTGRAF: ") {
+                            nutgraf = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix("The product: ") {
+                            the_product = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix("Cost: ") {
+                            cost = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix("Availability: ") {
+                            availability = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix("Platforms: ") {
+                            platforms = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix("Reaction: ") {
+                            reaction = Some(val.to_string());
+                        } else if let Some(val) = trimmed.strip_prefix(BY_THE_NUMBERS_PREFIX) {
+                            by_the_numbers = Some(val.to_string());
+                        } else if the_product.is_none() && lede.is_none() {
+                            // Positional fallback: first unlabeled paragraph = lede
+                            lede = Some(trimmed.to_string());
+                        } else if the_product.is_none() && nutgraf.is_none() {
+                            // Positional fallback: second unlabeled paragraph = nutgraf
+                            nutgraf = Some(trimmed.to_string());
+                        } else {
+                            // Past a complete summary: the host's own text
+                            if !story_notes.is_empty() {
+                                story_notes.push(String::new());
+                            }
+                            story_notes.push(trimmed.to_string());
+                        }
+
+                        // Build summary from accumulated fields
+                        if let Some(ref mut story) = current_story {
+                            if let Some(ref prod) = the_product {
+                                story.summary = Summary::Product {
+                                    the_product: prod.clone(),
+                                    cost: cost.clone().unwrap_or_default(),
+                                    availability: availability.clone().unwrap_or_default(),
+                                    platforms: platforms.clone().unwrap_or_default(),
+                                    quotes: quotes.clone(),
+                                    reaction: reaction.clone(),
+                                };
+                            } else if let (Some(ref l), Some(ref n)) = (&lede, &nutgraf) {
+                                story.summary = Summary::Editorial {
+                                    lede: l.clone(),
+                                    nutgraf: n.clone(),
+                                    quotes: quotes.clone(),
+                                    reaction: reaction.clone(),
+                                    by_the_numbers: by_the_numbers.clone(),
+                                };
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Save last story and topic
+    if let Some(story) = current_story {
+        if let Some(ref mut topic) = current_topic {
+            topic.stories.push(finish_story(story, &mut story_notes));
+            story_lines.push(story_line);
+        }
+    }
+    if let Some(topic) = current_topic {
+        if finish_topic(&mut topics, topic, notes) {
+            lines_by_topic.push(story_lines);
+        }
+    }
+
+    if topics.is_empty() {
+        anyhow::bail!(
+            "No topics found in org file. Make sure the file follows the expected format."
+        );
+    }
+
+    // Preserve org file order — user may have manually reordered stories after editing
+
+    let incomplete = topics
+        .iter()
+        .zip(&lines_by_topic)
+        .flat_map(|(topic, lines)| topic.stories.iter().zip(lines))
+        .filter_map(|(story, &line)| IncompleteStory::check(story, line))
+        .collect();
+
+    Ok((show_name, topics, incomplete))
+}
+
+/// The level and text of an org heading line: `"** Title"` gives
+/// `(2, "Title")`.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '*').count();
+    let title = line[level..].strip_prefix(' ')?;
+    (level > 0).then_some((level, title))
+}
+
+/// The first line of a drawer, like `:PROPERTIES:` or `:LOGBOOK:`.
+fn is_drawer(line: &str) -> bool {
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c == '_' || c == '-')
+}
+
+/// Attach the text gathered under `story` as its notes, with runs of blank
+/// lines cut to one.
+fn finish_story(mut story: Story, notes: &mut Vec<String>) -> Story {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in notes.iter().map(|l| l.trim_end()) {
+        if !line.is_empty() || lines.last().is_some_and(|l| !l.is_empty()) {
+            lines.push(line);
+        }
+    }
+    let text = lines.join("\n");
+    let text = text.trim_end();
+    if !text.is_empty() {
+        story.notes = Some(text.to_string());
+    }
+    notes.clear();
+    if story.details.as_ref().is_some_and(StoryDetails::is_empty) {
+        story.details = None;
+    }
+    story
+}
+
+/// Attach `notes` to `topic` and keep it if it has stories or notes.
+/// Returns whether it was kept.
+fn finish_topic(topics: &mut Vec<Topic>, mut topic: Topic, notes: Vec<&str>) -> bool {
+    if REGENERATED_SECTIONS.contains(&topic.title.as_str()) {
+        return false;
+    }
+    let notes: Vec<&str> = notes
+        .into_iter()
+        // A comparison table, rebuilt from the product stories
+        .filter(|line| topic.stories.is_empty() || !line.trim_start().starts_with('|'))
+        .collect();
+    let notes = notes.join("\n").trim_matches('\n').to_string();
+    if !notes.trim().is_empty() {
+        topic.notes = Some(notes);
+    }
+    let keep = !topic.stories.is_empty() || topic.notes.is_some();
+    if keep {
+        topics.push(topic);
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== parse_org_mode Tests ====================
+
+    #[test]
+    fn test_parse_org_mode_editorial() {
+        let content = r#"#+TITLE: TWiT Briefing Book
+#+SHOW: TWiT
+#+DATE: Sun, 2 February 2026
+
+* Apple
+
+** iPhone 17 Announced
+
+*** URL
+https://example.com/iphone17
+
+*** Date
+2026-02-01
+
+*** Summary
+Apple announced the iPhone 17 with a new A19 chip.
+
+The new chip delivers 40% better performance, continuing Apple's push into custom silicon across its lineup. This matters for the broader industry as competitors scramble to match Apple's vertical integration strategy.
+"#;
+
+        let (show_name, topics) = parse_org_mode(content).unwrap();
+
+        assert_eq!(show_name, "TWiT");
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].title, "Apple");
+        assert_eq!(topics[0].stories.len(), 1);
+        assert_eq!(topics[0].stories[0].title, "iPhone 17 Announced");
+        assert_eq!(topics[0].stories[0].url, "https://example.com/iphone17");
+
+        if let Summary::Editorial { lede, nutgraf, .. } = &topics[0].stories[0].summary {
+            assert!(lede.contains("iPhone 17"));
+            assert!(nutgraf.contains("custom silicon"));
+        } else {
+            panic!("Expected Summary::Editorial");
+        }
+    }
+
+    #[test]
+    fn test_comment_blocks_are_skipped() {
+        let content = r#"#+TITLE: Test Briefing
+#+BEGIN_COMMENT
+Sources: 1 stories from 1 sites
+- test.com: 1 (100%)
+#+END_COMMENT
+
+* News
+
+** Story Title
+
+*** URL
+https://test.com
+
+*** Summary
+Something happened involving someone.
+
+#+begin_comment
+* Not a topic
+#+end_comment
+It matters because of reasons that affect the broader landscape.
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].stories[0].notes, None);
+        match &topics[0].stories[0].summary {
+            Summary::Editorial { nutgraf, .. } => assert!(nutgraf.starts_with("It matters")),
+            other => panic!("Expected Summary::Editorial, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missed_stories_are_skipped() {
+        let missed = crate::discovery::Suggestion {
+            title: "A story elsewhere".to_string(),
+            url: "https://example.com/elsewhere".to_string(),
+            source: "Techmeme".to_string(),
+            points: None,
+            discussion: None,
+        };
+        let content = format!(
+            "* News\n\n** Story Title\n*** URL\nhttps://test.com\n\n{}",
+            crate::discovery::org_section(&[missed])
+        );
+        let (_, topics) = parse_org_mode(&content).unwrap();
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].title, "News");
+        assert_eq!(topics[0].notes, None);
+    }
+
+    #[test]
+    fn test_parse_org_mode_with_quote() {
+        let content = r#"#+TITLE: Test Briefing
+
+* News
+
+** Story Title
+
+*** URL
+https://test.com
+
+*** Summary
+"This is a quote" -- Author Name
+
+Something happened involving someone.
+
+It matters because of reasons that affect the broader landscape.
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+
+        if let Summary::Editorial { quotes, .. } = &topics[0].stories[0].summary {
+            assert_eq!(quotes.len(), 1);
+            let quote = &quotes[0];
+            assert_eq!(quote.text, "This is a quote");
+            assert_eq!(quote.speaker.as_deref(), Some("Author Name"));
+        } else {
+            panic!("Expected Summary::Editorial");
+        }
+    }
+
+    #[test]
+    fn test_parse_org_mode_product() {
+        let content = r#"#+TITLE: Test Briefing
+
+* Products
+
+** New Gadget Review
+
+*** URL
+https://test.com/gadget
+
+*** Summary
+The product: A revolutionary new widget that does everything.
+Cost: Starting at $299.
+Availability: Ships March 2026.
+Platforms: iOS, Android, Web.
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+
+        if let Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            ..
+        } = &topics[0].stories[0].summary
+        {
+            assert!(the_product.contains("revolutionary"));
+            assert!(cost.contains("$299"));
+            assert!(availability.contains("March"));
+            assert!(platforms.contains("iOS"));
+        } else {
+            panic!("Expected Summary::Product");
+        }
+    }
+
+    #[test]
+    fn test_parse_org_mode_multiple_topics() {
+        let content = r#"#+TITLE: TWiT Briefing
+
+* Apple
+
+** Apple Story
+
+*** URL
+https://apple.com
+
+*** Summary
+Apple did something.
+
+It matters for the industry.
+
+* Google
+
+** Google Story
+
+*** URL
+https://google.com
+
+*** Summary
+Google did something.
+
+It also matters for the industry.
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].title, "Apple");
+        assert_eq!(topics[1].title, "Google");
+    }
+
+    #[test]
+    fn test_parse_org_mode_details_drawer() {
+        let content = "* Apple\n\n** iPhone 17 Announced\n                       :DETAILS:\n:SOURCE: theverge.com\n:AUTHOR: Jane Doe\n                       :WORDS: 1,200\n:READING_TIME: 6 min\n:IMAGE: https://cdn.example.com/a.jpg\n:END:\n\n                       *** URL\nhttps://example.com/a\n\n                       *** Summary\nApple announced it.\n\nIt matters.\n\n                       ** No Details\n:DETAILS:\n:END:\n\n*** URL\nhttps://example.com/b\n";
+        let (_, topics) = parse_org_mode(content).unwrap();
+        let stories = &topics[0].stories;
+        assert_eq!(
+            stories[0].details,
+            Some(StoryDetails {
+                source: Some("theverge.com".to_string()),
+                author: Some("Jane Doe".to_string()),
+                words: Some(1200),
+                reading_minutes: Some(6),
+                image: Some("https://cdn.example.com/a.jpg".to_string()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(stories[0].notes, None);
+        assert_eq!(stories[1].details, None);
+        assert_eq!(stories[1].url, "https://example.com/b");
+    }
+
+    #[test]
+    fn test_parse_org_mode_nested_draft() {
+        let content = "#+TITLE: TWiT Briefing Book\n\n** Apple\n\n*** TODO iPhone 17 :leo:\n:PROPERTIES:\n:CATEGORY: phones\n:END:\n\n**** Summary\nApple announced it.\n\nIt matters.\n\n**** URL\nhttps://example.com/a\n\n** Google\n\n*** Pixel 10\n**** URL\nhttps://example.com/b\n";
+        let (_, topics) = parse_org_mode(content).unwrap();
+        assert_eq!(topics.len(), 2);
+        let story = &topics[0].stories[0];
+        assert_eq!(story.title, "iPhone 17");
+        assert!(story.starred);
+        assert_eq!(story.hosts, vec!["leo"]);
+        assert_eq!(story.url, "https://example.com/a");
+        assert_eq!(story.notes, None);
+        assert!(matches!(story.summary, Summary::Editorial { .. }));
+        assert_eq!(topics[1].stories[0].url, "https://example.com/b");
+    }
+
+    #[test]
+    fn test_parse_org_mode_unwraps_links() {
+        let content = "* [[https://apple.com][Apple]]\n\n** [[https://example.com/a][iPhone 17]] Announced :leo:\n:PROPERTIES:\n:URL: [[https://example.com/old]]\n:END:\n\n*** URL\n[[https://example.com/a][iPhone 17 Announced]]\n\n*** Summary\nApple announced it at [[https://apple.com/event][its event]].\n\nIt matters for [[https://example.com/c]].\n\n** No Link\n:PROPERTIES:\n:URL: [[https://example.com/b]]\n:END:\n";
+        let (_, topics) = parse_org_mode(content).unwrap();
+        assert_eq!(topics[0].title, "Apple");
+        let stories = &topics[0].stories;
+        assert_eq!(stories[0].title, "iPhone 17 Announced");
+        assert_eq!(stories[0].hosts, vec!["leo"]);
+        assert_eq!(stories[0].url, "https://example.com/a");
+        assert!(matches!(
+            &stories[0].summary,
+            Summary::Editorial { lede, nutgraf, .. }
+                if lede == "Apple announced it at its event."
+                    && nutgraf == "It matters for https://example.com/c."
+        ));
+        assert_eq!(stories[1].url, "https://example.com/b");
+    }
+
+    #[test]
+    fn test_link_helpers() {
+        assert_eq!(
+            link("https://a.com", "Apple [rumor]"),
+            "[[https://a.com][Apple (rumor)]]"
+        );
+        assert_eq!(link("https://a.com", ""), "[[https://a.com]]");
+        assert_eq!(link_target(" [[https://a.com][A]] "), "https://a.com");
+        assert_eq!(link_target("https://a.com"), "https://a.com");
+        assert_eq!(
+            link_target("[[a][A]] and [[b][B]]"),
+            "[[a][A]] and [[b][B]]"
+        );
+        assert_eq!(unwrap_links("an [[unclosed link"), "an [[unclosed link");
+    }
+
+    #[test]
+    fn test_parse_org_mode_checked_lists_incomplete_stories() {
+        let content = "* By the Numbers\n\n** Not a story\n\n* Apple\n\n** Complete\n*** URL\nhttps://example.com/a\n*** Date\n2026-01-30\n*** Summary\nApple announced it.\n\nIt matters.\n\n** No URL\n*** Date\n2026-01-30\n*** Summary\nSummary not available\n\n* Empty\n\n* Google\n\n** Only a URL\n*** URL\nhttps://example.com/b\n";
+        let (_, topics, incomplete) = parse_org_mode_checked(content).unwrap();
+        assert_eq!(topics.len(), 2);
+        assert_eq!(
+            incomplete,
+            vec![
+                IncompleteStory {
+                    line: 17,
+                    title: "No URL".to_string(),
+                    missing: vec!["URL", "summary"],
+                },
+                IncompleteStory {
+                    line: 27,
+                    title: "Only a URL".to_string(),
+                    missing: vec!["date", "summary"],
+                },
+            ]
+        );
+        assert_eq!(
+            incomplete[0].to_string(),
+            "line 17: \"No URL\" has no URL, summary"
+        );
+    }
+
+    #[test]
+    fn test_parse_org_mode_property_drawer() {
+        let content = "* Apple\n\n** iPhone 17 Announced :leo:\n:PROPERTIES:\n:URL: https://example.com/a\n:DATE: 2026-01-30\n:PRIORITY: A\n:SOURCE: theverge.com\n:CATEGORY: phones\n:END:\nAsk Andy about the price.\n\n*** Summary\nApple announced it.\n\nIt matters.\n\n** iPhone 17 Air\n:PROPERTIES:\n:URL: https://example.com/old\n:PRIORITY: B\n:END:\n\n*** URL\nhttps://example.com/b\n";
+        let (_, topics) = parse_org_mode(content).unwrap();
+        let stories = &topics[0].stories;
+        assert_eq!(stories[0].url, "https://example.com/a");
+        assert_eq!(stories[0].created, "2026-01-30");
+        assert!(stories[0].starred);
+        assert_eq!(
+            stories[0].details,
+            Some(StoryDetails {
+                source: Some("theverge.com".to_string()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            stories[0].notes.as_deref(),
+            Some("Ask Andy about the price.")
+        );
+        assert!(matches!(stories[0].summary, Summary::Editorial { .. }));
+        // A URL section still wins over the drawer
+        assert_eq!(stories[1].url, "https://example.com/b");
+        assert!(!stories[1].starred);
+        assert_eq!(stories[1].details, None);
+    }
+
+    #[test]
+    fn test_parse_org_mode_story_notes() {
+        let content = r#"#+TITLE: TWiT Briefing Book
+
+* Apple
+
+** iPhone 17 Announced
+
+DISCLOSURE NEEDED: Apple (sponsor)
+
+Ask Andy whether he's upgrading.
+
+*** URL
+https://example.com/iphone17
+
+*** Summary
+Apple announced the iPhone 17.
+
+It matters.
+
+Mention the price hike.
+
+*** Questions for guests
+- Is the A19 worth it?
+- What about the camera?
+
+** Quiet Story
+
+*** URL
+https://example.com/quiet
+
+*** Summary
+Something happened.
+
+It matters too.
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+        let stories = &topics[0].stories;
+        assert_eq!(stories.len(), 2);
+        assert_eq!(
+            stories[0].notes.as_deref(),
+            Some(
+                "Ask Andy whether he's upgrading.\n\nMention the price hike.\n\n\
+                 Questions for guests:\n- Is the A19 worth it?\n- What about the camera?"
+            )
+        );
+        // The notes don't leak into the summary
+        assert!(matches!(
+            &stories[0].summary,
+            Summary::Editorial { nutgraf, .. } if nutgraf == "It matters."
+        ));
+        assert_eq!(stories[1].notes, None);
+    }
+
+    #[test]
+    fn test_parse_org_mode_skips_empty_topics() {
+        let content = r#"#+TITLE: Test
+
+* Has Stories
+
+** A Story
+
+*** URL
+https://example.com
+
+*** Summary
+Something happened.
+
+It matters for the industry.
+
+* Empty Topic
+
+* In Other News
+
+* Leo's Picks
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+
+        // Only "Has Stories" should be included
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].title, "Has Stories");
+    }
+
+    #[test]
+    fn test_parse_org_mode_extracts_show_name() {
+        let content = r#"#+TITLE: Tuesday's Rundown
+#+SHOW: MacBreak Weekly
+
+* Topic
+
+** Story
+
+*** URL
+https://test.com
+
+*** Summary
+Something happened.
+
+It matters for the industry.
+"#;
+
+        let (show_name, _) = parse_org_mode(content).unwrap();
+        assert_eq!(show_name, "MacBreak Weekly");
+
+        // Without the keyword the caller uses the configured show's name
+        let untagged = content.replace("#+SHOW: MacBreak Weekly\n", "");
+        let (show_name, _) = parse_org_mode(&untagged).unwrap();
+        assert_eq!(show_name, "");
+    }
+
+    #[test]
+    fn test_parse_org_mode_no_topics_error() {
+        let content = r#"#+TITLE: Empty Briefing
+
+* In Other News
+
+* Leo's Picks
+"#;
+
+        let result = parse_org_mode(content);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("No topics found"));
+    }
+
+    #[test]
+    fn test_parse_org_mode_with_date() {
+        let content = r#"#+TITLE: Test Briefing
+
+* Topic
+
+** Story
+
+*** URL
+https://test.com
+
+*** Date
+Sat, 1 Feb 2026
+
+*** Summary
+Something happened.
+
+It matters for the industry.
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+        assert_eq!(topics[0].stories[0].created, "Sat, 1 Feb 2026");
+    }
+
+    #[test]
+    fn test_split_tags() {
+        assert_eq!(
+            split_tags("Apple's new chip :leo:Andy:"),
+            (
+                "Apple's new chip",
+                vec!["leo".to_string(), "andy".to_string()]
+            )
+        );
+        assert_eq!(split_tags("No tags here"), ("No tags here", vec![]));
+        // Colons in ordinary titles aren't tags
+        assert_eq!(split_tags("Apple: the :) year").1, Vec::<String>::new());
+        assert_eq!(split_tags("Vision Pro :: review").1, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_split_keyword() {
+        assert_eq!(
+            split_keyword("TODO Apple's new chip"),
+            ("Apple's new chip", true)
+        );
+        assert_eq!(
+            split_keyword("DONE Apple's new chip"),
+            ("Apple's new chip", true)
+        );
+        assert_eq!(split_keyword("TODOs for 2026"), ("TODOs for 2026", false));
+        assert_eq!(
+            split_keyword("Apple's new chip"),
+            ("Apple's new chip", false)
+        );
+    }
+}
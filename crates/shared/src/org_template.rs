@@ -0,0 +1,351 @@
+//! Layout of each story in the org draft.
+//!
+//! The built-in template lives in `assets/templates/org-story.org` and is
+//! exported with the other assets (`briefing export-assets`). Edit the copy
+//! in the config dir to change it for every show, or put one under
+//! `templates/{slug}/org-story.org` for a single show.
+//!
+//! The first line is the story heading; its stars set the story level, with
+//! topics one level up and the URL/Date/Summary sections one level down, so
+//! `*** {{ title }}` nests the whole draft one level deeper. A placeholder
+//! alone on its line is a block that expands to whole lines, or nothing:
+//! `details` (the `:DETAILS:` drawer), `flags` (disclosure, stale,
+//! sensitive and follow-up lines), and the `url`, `date`, `summary` and `notes` sections.
+//! The others fill in within a line: `keyword` (`TODO ` on starred stories),
+//! `title`, `tags` (` :leo:andy:`), `link`, `published`, `source` and
+//! `author`. Anything else is copied as is; `parse_org_mode` reads property
+//! drawers and the sections in any order, so the edited draft still
+//! comes back. Unknown names are rejected when the template is loaded.
+
+use anyhow::{bail, Context, Result};
+
+use crate::clustering::Story;
+use crate::org::{details_drawer, BY_THE_NUMBERS_PREFIX, NOTES_SECTION, STARRED_KEYWORD};
+use crate::prompts::placeholders;
+use crate::summarizer::Summary;
+
+/// Path of the shared template, relative to the config dir.
+pub const TEMPLATE_PATH: &str = "templates/org-story.org";
+
+/// Placeholders that stand on a line of their own.
+const BLOCKS: &[&str] = &["details", "flags", "url", "date", "summary", "notes"];
+
+/// Placeholders filled in within a line.
+const FIELDS: &[&str] = &[
+    "keyword",
+    "title",
+    "tags",
+    "link",
+    "published",
+    "source",
+    "author",
+];
+
+#[derive(Debug, Clone)]
+pub struct OrgTemplate {
+    template: String,
+    level: usize,
+}
+
+impl Default for OrgTemplate {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl OrgTemplate {
+    /// Load the template for the show `slug`, preferring its own copy, then
+    /// the customized shared copy, then the built-in.
+    pub fn load(slug: &str) -> Result<Self> {
+        let template = crate::assets::load(&format!("templates/{}/org-story.org", slug))
+            .or_else(|| crate::assets::load(TEMPLATE_PATH))
+            .context("No org story template")?;
+        Self::from_template(&template).context("Invalid org story template")
+    }
+
+    pub fn builtin() -> Self {
+        let template = crate::assets::get(TEMPLATE_PATH).unwrap_or_default();
+        Self::from_template(&template).expect("built-in org template is valid")
+    }
+
+    pub fn from_template(template: &str) -> Result<Self> {
+        for line in template.lines() {
+            for name in placeholders(line)? {
+                if BLOCKS.contains(&name) {
+                    if !is_block_line(line) {
+                        bail!("`{{{{ {} }}}}` must be on a line of its own", name);
+                    }
+                } else if !FIELDS.contains(&name) {
+                    let known: Vec<&str> = BLOCKS.iter().chain(FIELDS).copied().collect();
+                    bail!(
+                        "unknown variable `{}` (available: {})",
+                        name,
+                        known.join(", ")
+                    );
+                }
+            }
+        }
+        let heading = template.lines().next().unwrap_or_default();
+        let level = heading.chars().take_while(|&c| c == '*').count();
+        if level < 2 || !heading[level..].starts_with(' ') {
+            bail!("the first line must be the story heading, at level 2 or deeper (`** {{{{ title }}}}`)");
+        }
+        Ok(Self {
+            template: template.to_string(),
+            level,
+        })
+    }
+
+    /// Stars of a topic heading.
+    pub fn topic_stars(&self) -> String {
+        "*".repeat(self.level - 1)
+    }
+
+    /// The org text of `story`. `flags` is the block of informational lines
+    /// under its heading, each paragraph ending in a blank line. With
+    /// `links`, the URL section holds an org link titled with the headline.
+    pub fn render(&self, story: &Story, flags: &str, links: bool) -> String {
+        let section = "*".repeat(self.level + 1);
+        let details = story.details.clone().unwrap_or_default();
+        let tags = if story.hosts.is_empty() {
+            String::new()
+        } else {
+            format!(" :{}:", story.hosts.join(":"))
+        };
+        let keyword = if story.starred {
+            format!("{} ", STARRED_KEYWORD)
+        } else {
+            String::new()
+        };
+        let fields = [
+            ("keyword", keyword),
+            ("title", story.title.clone()),
+            ("tags", tags),
+            ("link", story.url.clone()),
+            ("published", story.created.clone()),
+            ("source", details.source.clone().unwrap_or_default()),
+            ("author", details.author.clone().unwrap_or_default()),
+        ];
+
+        let mut org = String::new();
+        for line in self.template.lines() {
+            let block = is_block_line(line)
+                .then(|| placeholders(line).ok()?.first().copied())
+                .flatten()
+                .filter(|name| BLOCKS.contains(name));
+            match block {
+                Some("details") => org.push_str(&details_drawer(&details, story.entities.as_ref())),
+                Some("flags") => org.push_str(flags),
+                Some("url") => {
+                    let url = if links {
+                        crate::org::link(&story.url, &story.title)
+                    } else {
+                        story.url.clone()
+                    };
+                    org.push_str(&format!("{} URL\n{}\n\n", section, url));
+                }
+                Some("date") => {
+                    if !story.created.is_empty() {
+                        org.push_str(&format!("{} Date\n{}\n\n", section, story.created));
+                    }
+                }
+                Some("summary") => {
+                    org.push_str(&format!("{} Summary\n", section));
+                    org.push_str(&summary_text(&story.summary));
+                    org.push('\n');
+                }
+                Some("notes") => {
+                    if let Some(notes) = &story.notes {
+                        org.push_str(&format!("{} {}\n{}\n\n", section, NOTES_SECTION, notes));
+                    }
+                }
+                _ => {
+                    org.push_str(&fill(line, &fields));
+                    org.push('\n');
+                }
+            }
+        }
+        org
+    }
+}
+
+/// A line holding one placeholder and nothing else.
+fn is_block_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("{{") && line.ends_with("}}") && line.matches("{{").count() == 1
+}
+
+fn fill(line: &str, fields: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        // `from_template` has already checked every placeholder closes.
+        let end = rest[start..].find("}}").map_or(rest.len(), |e| start + e);
+        let name = rest[start + 2..end].trim();
+        if let Some((_, value)) = fields.iter().find(|(k, _)| *k == name) {
+            out.push_str(value);
+        }
+        rest = rest.get(end + 2..).unwrap_or_default();
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The summary section's body, as `parse_org_mode` reads it back.
+fn summary_text(summary: &Summary) -> String {
+    let mut text = String::new();
+    match summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        } => {
+            for q in quotes {
+                text.push_str(&format!("{}\n\n", q));
+            }
+            text.push_str(&format!("{}\n\n", lede));
+            text.push_str(&format!("{}\n", nutgraf));
+            if let Some(numbers) = by_the_numbers {
+                text.push_str(&format!("\n{}{}\n", BY_THE_NUMBERS_PREFIX, numbers));
+            }
+            if let Some(reaction) = reaction {
+                text.push_str(&format!("\nReaction: {}\n", reaction));
+            }
+        }
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        } => {
+            for q in quotes {
+                text.push_str(&format!("{}\n\n", q));
+            }
+            text.push_str(&format!("The product: {}\n", the_product));
+            if !cost.is_empty() {
+                text.push_str(&format!("Cost: {}\n", cost));
+            }
+            if !availability.is_empty() {
+                text.push_str(&format!("Availability: {}\n", availability));
+            }
+            if !platforms.is_empty() {
+                text.push_str(&format!("Platforms: {}\n", platforms));
+            }
+            if let Some(reaction) = reaction {
+                text.push_str(&format!("Reaction: {}\n", reaction));
+            }
+        }
+        Summary::Insufficient | Summary::Failed(_) => {
+            text.push_str("Summary not available\n");
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::{StoryDetails, Topic};
+    use crate::org::parse_org_mode;
+
+    fn vision_pro() -> Story {
+        Story {
+            notes: Some("Ask Andy.".to_string()),
+            details: Some(StoryDetails {
+                source: Some("theverge.com".to_string()),
+                ..Default::default()
+            }),
+            ..crate::testing::story("Apple recalls Vision Pro")
+                .with_url("https://example.com/a")
+                .with_created("2026-02-01")
+                .with_lede("Straps were faulty.", "It matters.")
+                .with_hosts(&["leo"])
+                .starred()
+                .build()
+        }
+    }
+
+    #[test]
+    fn test_builtin_template() {
+        let org = OrgTemplate::builtin().render(&vision_pro(), "", false);
+        assert_eq!(
+            org,
+            "** TODO Apple recalls Vision Pro :leo:\n\
+             :DETAILS:\n:SOURCE: theverge.com\n:END:\n\n\
+             *** URL\nhttps://example.com/a\n\n\
+             *** Date\n2026-02-01\n\n\
+             *** Summary\nStraps were faulty.\n\nIt matters.\n\n\
+             *** Notes\nAsk Andy.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_custom_template_round_trips() {
+        let template = OrgTemplate::from_template(
+            "*** {{ keyword }}{{ title }}{{ tags }}\n\
+             :PROPERTIES:\n:LINK: {{ link }}\n:SOURCE: {{ source }}\n:END:\n\n\
+             {{ summary }}\n{{ url }}\n{{ notes }}\n",
+        )
+        .unwrap();
+        assert_eq!(template.topic_stars(), "**");
+        let org = format!(
+            "#+TITLE: TWiT Briefing Book\n\n** Apple\n\n{}",
+            template.render(&vision_pro(), "", false)
+        );
+        assert!(org.contains(
+            "*** TODO Apple recalls Vision Pro :leo:\n:PROPERTIES:\n:LINK: https://example.com/a\n"
+        ));
+        assert!(org.find("**** Summary").unwrap() < org.find("**** URL").unwrap());
+
+        let (_, topics) = parse_org_mode(&org).unwrap();
+        let [Topic { title, stories, .. }] = topics.as_slice() else {
+            panic!("{:?}", topics);
+        };
+        assert_eq!(title, "Apple");
+        assert_eq!(stories[0].title, "Apple recalls Vision Pro");
+        assert!(stories[0].starred);
+        assert_eq!(stories[0].url, "https://example.com/a");
+        assert_eq!(stories[0].hosts, vec!["leo"]);
+        assert!(matches!(
+            &stories[0].summary,
+            Summary::Editorial { lede, nutgraf, .. }
+                if lede == "Straps were faulty." && nutgraf == "It matters."
+        ));
+        assert_eq!(stories[0].notes.as_deref(), Some("Ask Andy."));
+    }
+
+    #[test]
+    fn test_reaction_round_trips() {
+        let mut story = vision_pro();
+        story.summary = Summary::Editorial {
+            lede: "Straps were faulty.".to_string(),
+            nutgraf: "It matters.".to_string(),
+            quotes: Vec::new(),
+            reaction: Some("Commenters saw it coming.".to_string()),
+            by_the_numbers: None,
+        };
+        let org = OrgTemplate::builtin().render(&story, "", false);
+        assert!(org.contains("It matters.\n\nReaction: Commenters saw it coming.\n"));
+
+        let (_, topics) = parse_org_mode(&format!("* Apple\n\n{}", org)).unwrap();
+        assert!(matches!(
+            &topics[0].stories[0].summary,
+            Summary::Editorial { reaction: Some(reaction), .. }
+                if reaction == "Commenters saw it coming."
+        ));
+        assert_eq!(topics[0].stories[0].notes.as_deref(), Some("Ask Andy."));
+    }
+
+    #[test]
+    fn test_rejects_bad_templates() {
+        assert!(OrgTemplate::from_template("** {{ titel }}\n").is_err());
+        assert!(OrgTemplate::from_template("** {{ title }}\nSee {{ url }}\n").is_err());
+        assert!(OrgTemplate::from_template("* {{ title }}\n").is_err());
+        assert!(OrgTemplate::from_template("{{ url }}\n** {{ title }}\n").is_err());
+    }
+}
@@ -0,0 +1,357 @@
+//! Where the tools save their files (`[output]` in config.toml).
+//!
+//! Each kind of artifact has a directory and a filename template:
+//!
+//! - `org`: the drafts, in Documents as `{slug}-{date}.org`
+//! - `html`: the briefing, in Documents as `{slug}-{date}.html`, with the
+//!   per-host views, social posts and rundown exports beside it
+//! - `csv`: the links CSV, in Documents as `{slug}-{date}-LINKS.csv`, with
+//!   the OPML and org links exports beside it
+//! - `json`: the story files, in the data dir as `{slug}-{date}-{rev}.json`
+//!
+//! Templates name the file without its extension and must have `{slug}`
+//! before `{date}` (`YYYY-MM-DD`), so the other commands can still tell a
+//! file's show and date. `{rev}` numbers the files of one show and date:
+//! it is left out (with the `-` before it) for the first and becomes `2`,
+//! `3` and so on when that name is taken. Without `{rev}`, a new file
+//! replaces the old one, which is kept as a backup (see
+//! [`save_file`](crate::io::save_file)).
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::archive::parse_filename;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Artifact {
+    Org,
+    Html,
+    Csv,
+    Json,
+}
+
+impl Artifact {
+    fn default_name(self) -> &'static str {
+        match self {
+            Artifact::Org | Artifact::Html => "{slug}-{date}",
+            Artifact::Csv => "{slug}-{date}-LINKS",
+            Artifact::Json => "{slug}-{date}-{rev}",
+        }
+    }
+
+    fn default_dir(self) -> Result<PathBuf> {
+        match self {
+            Artifact::Json => Ok(dirs::data_local_dir()
+                .context("Could not determine local data directory")?
+                .join("podcast-briefing")
+                .join("stories")),
+            _ => Ok(dirs::document_dir().unwrap_or_else(|| PathBuf::from("."))),
+        }
+    }
+}
+
+/// `[output]` section of config.toml.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OutputSettings {
+    /// Earlier versions of each briefing, draft and story file to keep, as
+    /// `{file}.bak`, `{file}.bak.2` and so on (0 keeps none)
+    pub backups: usize,
+    pub org: Location,
+    pub html: Location,
+    pub csv: Location,
+    pub json: Location,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            backups: 1,
+            org: Location::default(),
+            html: Location::default(),
+            csv: Location::default(),
+            json: Location::default(),
+        }
+    }
+}
+
+/// `[output.org]` and the like; either part may be left out.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Location {
+    /// Directory for these files, e.g. `~/Org/briefings`
+    pub dir: Option<String>,
+    /// Filename template without the extension, e.g. `{slug}-{date}-{rev}`
+    pub name: Option<String>,
+}
+
+/// The `[output]` settings, read on first use.
+pub fn settings() -> &'static OutputSettings {
+    static SETTINGS: OnceLock<OutputSettings> = OnceLock::new();
+    // A broken config.toml is reported by the command that loads it
+    SETTINGS.get_or_init(|| {
+        let path = crate::settings::Settings::path();
+        crate::settings::Settings::from_file(&path)
+            .map(|settings| settings.output)
+            .unwrap_or_default()
+    })
+}
+
+impl OutputSettings {
+    fn location(&self, artifact: Artifact) -> &Location {
+        match artifact {
+            Artifact::Org => &self.org,
+            Artifact::Html => &self.html,
+            Artifact::Csv => &self.csv,
+            Artifact::Json => &self.json,
+        }
+    }
+
+    fn name(&self, artifact: Artifact) -> &str {
+        self.location(artifact)
+            .name
+            .as_deref()
+            .unwrap_or(artifact.default_name())
+    }
+
+    /// The directory `artifact` files go in, created if need be.
+    pub fn dir(&self, artifact: Artifact) -> Result<PathBuf> {
+        let dir = match &self.location(artifact).dir {
+            Some(dir) => match (dir.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(dir),
+            },
+            None => artifact.default_dir()?,
+        };
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(dir)
+    }
+
+    /// Where to save the `artifact` for `slug` on `date`. `suffix` and
+    /// `extension` follow the name, as in `-leo` and `html` for a host view.
+    /// With `{rev}` in the template, the first name not already taken.
+    pub fn path(
+        &self,
+        artifact: Artifact,
+        slug: &str,
+        date: NaiveDate,
+        suffix: &str,
+        extension: &str,
+    ) -> Result<PathBuf> {
+        let template = self.name(artifact);
+        check_template(template)
+            .with_context(|| format!("Invalid [output.{}] name", artifact_key(artifact)))?;
+        let dir = self.dir(artifact)?;
+        let mut rev = 1;
+        loop {
+            let path = dir.join(format!(
+                "{}{}.{}",
+                render(template, slug, date, rev),
+                suffix,
+                extension
+            ));
+            if template.contains("{rev}") && path.exists() {
+                rev += 1;
+                continue;
+            }
+            return Ok(path);
+        }
+    }
+
+    /// The `to` file saved in the same run as the `from` file at `path`,
+    /// e.g. the links CSV of a briefing. `None` if `path` doesn't follow the
+    /// `from` template.
+    pub fn matching(
+        &self,
+        from: Artifact,
+        path: &Path,
+        to: Artifact,
+        suffix: &str,
+        extension: &str,
+    ) -> Option<PathBuf> {
+        let stem = path.file_stem()?.to_str()?;
+        let (Some(slug), Some(date)) = parse_filename(stem) else {
+            return None;
+        };
+        let from_template = self.name(from);
+        let revs = if from_template.contains("{rev}") {
+            1..=1000
+        } else {
+            1..=1
+        };
+        let rev = revs
+            .into_iter()
+            .find(|&rev| render(from_template, &slug, date, rev).eq_ignore_ascii_case(stem))?;
+        let name = render(self.name(to), &slug, date, rev);
+        Some(
+            self.dir(to)
+                .ok()?
+                .join(format!("{}{}.{}", name, suffix, extension)),
+        )
+    }
+}
+
+fn artifact_key(artifact: Artifact) -> &'static str {
+    match artifact {
+        Artifact::Org => "org",
+        Artifact::Html => "html",
+        Artifact::Csv => "csv",
+        Artifact::Json => "json",
+    }
+}
+
+/// Only `{slug}`, `{date}` and `{rev}`, with the show before the date.
+fn check_template(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map(|e| start + e)
+            .context("unclosed `{`")?;
+        let name = &rest[start + 1..end];
+        if !["slug", "date", "rev"].contains(&name) {
+            bail!(
+                "unknown variable `{{{}}}` (available: slug, date, rev)",
+                name
+            );
+        }
+        rest = &rest[end + 1..];
+    }
+    if template.contains(['/', '\\']) {
+        bail!("`{}` must be a file name, not a path", template);
+    }
+    match (template.find("{slug}"), template.find("{date}")) {
+        (Some(slug), Some(date)) if slug < date => Ok(()),
+        _ => bail!("`{}` needs `{{slug}}` followed by `{{date}}`", template),
+    }
+}
+
+fn render(template: &str, slug: &str, date: NaiveDate, rev: usize) -> String {
+    let name = template
+        .replace("{slug}", slug)
+        .replace("{date}", &date.format("%Y-%m-%d").to_string());
+    if rev > 1 {
+        return name.replace("{rev}", &rev.to_string());
+    }
+    // The first file has no number, nor the separator before it
+    let mut out = String::with_capacity(name.len());
+    let mut rest = name.as_str();
+    while let Some(start) = rest.find("{rev}") {
+        let before = &rest[..start];
+        out.push_str(before.strip_suffix(['-', '_', '.', ' ']).unwrap_or(before));
+        rest = &rest[start + "{rev}".len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 2, 1).unwrap()
+    }
+
+    fn settings_in(dir: &Path, name: &str) -> OutputSettings {
+        let location = Location {
+            dir: Some(dir.to_string_lossy().into_owned()),
+            name: Some(name.to_string()),
+        };
+        OutputSettings {
+            html: location.clone(),
+            csv: Location {
+                name: Some("{slug}-{date}-{rev}-links".to_string()),
+                ..location
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render("{slug}-{date}", "twit", date(), 1),
+            "twit-2026-02-01"
+        );
+        assert_eq!(
+            render("{slug}-{date}-{rev}", "twit", date(), 1),
+            "twit-2026-02-01"
+        );
+        assert_eq!(
+            render("{slug}-{date}-{rev}", "twit", date(), 3),
+            "twit-2026-02-01-3"
+        );
+        assert_eq!(
+            render("{slug}_{date}_r{rev}", "twit", date(), 1),
+            "twit_2026-02-01_r"
+        );
+    }
+
+    #[test]
+    fn test_check_template() {
+        assert!(check_template("{slug}-{date}-{rev}").is_ok());
+        assert!(check_template("briefing {slug} {date}").is_ok());
+        assert!(check_template("{date}-{slug}").is_err());
+        assert!(check_template("{slug}").is_err());
+        assert!(check_template("{slug}-{date}-{host}").is_err());
+        assert!(check_template("shows/{slug}-{date}").is_err());
+    }
+
+    #[test]
+    fn test_path_numbers_taken_names() {
+        let dir = tempdir().unwrap();
+        let settings = settings_in(dir.path(), "{slug}-{date}-{rev}");
+        let first = settings
+            .path(Artifact::Html, "twit", date(), "", "html")
+            .unwrap();
+        assert_eq!(first, dir.path().join("twit-2026-02-01.html"));
+        std::fs::write(&first, "").unwrap();
+        let second = settings
+            .path(Artifact::Html, "twit", date(), "", "html")
+            .unwrap();
+        assert_eq!(second, dir.path().join("twit-2026-02-01-2.html"));
+        std::fs::write(&second, "").unwrap();
+        // Each file is numbered on its own
+        assert_eq!(
+            settings
+                .path(Artifact::Html, "twit", date(), "-leo", "html")
+                .unwrap(),
+            dir.path().join("twit-2026-02-01-leo.html")
+        );
+
+        assert_eq!(
+            settings.matching(Artifact::Html, &second, Artifact::Csv, "", "csv"),
+            Some(dir.path().join("twit-2026-02-01-2-links.csv"))
+        );
+        assert_eq!(
+            settings.matching(Artifact::Html, &first, Artifact::Csv, "", "csv"),
+            Some(dir.path().join("twit-2026-02-01-links.csv"))
+        );
+    }
+
+    #[test]
+    fn test_path_without_rev_replaces() {
+        let dir = tempdir().unwrap();
+        let settings = settings_in(dir.path(), "{slug}-{date}");
+        let path = settings
+            .path(Artifact::Html, "twit", date(), "", "html")
+            .unwrap();
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(
+            settings
+                .path(Artifact::Html, "twit", date(), "", "html")
+                .unwrap(),
+            path
+        );
+        let bad = settings_in(dir.path(), "{date}");
+        assert!(bad
+            .path(Artifact::Html, "twit", date(), "", "html")
+            .is_err());
+    }
+}
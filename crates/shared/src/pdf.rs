@@ -0,0 +1,119 @@
+//! Text of PDF links, for reports and papers that would otherwise be
+//! skipped as an unsupported content type.
+//!
+//! Like headless rendering, this shells out rather than linking a parser:
+//! the downloaded file goes through poppler's `pdftotext` (in the
+//! `poppler-utils` package, or `brew install poppler`). Set
+//! `BRIEFING_PDFTOTEXT` to pick a specific binary. Without one, PDF links
+//! fail as before and fall back to the bookmark excerpt.
+
+use anyhow::{bail, Context, Result};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Hard limit on converting one file.
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The `pdftotext` binary to run.
+pub fn find_pdftotext() -> String {
+    std::env::var("BRIEFING_PDFTOTEXT")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+        .unwrap_or_else(|| "pdftotext".to_string())
+}
+
+/// Whether `content_type` or, for servers that don't say, `url` is a PDF.
+pub fn is_pdf(content_type: &str, url: &str) -> bool {
+    content_type.contains("application/pdf")
+        || (content_type.contains("octet-stream")
+            && url
+                .split(['?', '#'])
+                .next()
+                .is_some_and(|path| path.to_ascii_lowercase().ends_with(".pdf")))
+}
+
+/// The text of the PDF `bytes`.
+pub async fn text(bytes: &[u8]) -> Result<String> {
+    text_with(&find_pdftotext(), bytes).await
+}
+
+async fn text_with(program: &str, bytes: &[u8]) -> Result<String> {
+    if !bytes.starts_with(b"%PDF") {
+        bail!("Not a PDF file");
+    }
+    let input = temp_path();
+    tokio::fs::write(&input, bytes)
+        .await
+        .with_context(|| format!("Failed to write {}", input.display()))?;
+    let child = Command::new(program)
+        .args(["-q", "-enc", "UTF-8"])
+        .arg(&input)
+        .arg("-")
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(CONVERT_TIMEOUT, child).await;
+    let _ = tokio::fs::remove_file(&input).await;
+    let output = output
+        .context("PDF conversion timed out")?
+        .with_context(|| format!("Failed to run {} (install poppler for PDF links)", program))?;
+    if !output.status.success() {
+        bail!("{} exited with {}", program, output.status);
+    }
+    // Page breaks come out as form feeds
+    Ok(String::from_utf8_lossy(&output.stdout).replace('\u{c}', "\n"))
+}
+
+fn temp_path() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    std::env::temp_dir().join(format!(
+        "briefing-{}-{}.pdf",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pdf() {
+        assert!(is_pdf("application/pdf", "https://example.com/report"));
+        assert!(is_pdf(
+            "application/octet-stream",
+            "https://example.com/Report.PDF?dl=1"
+        ));
+        assert!(!is_pdf(
+            "application/octet-stream",
+            "https://example.com/setup.exe"
+        ));
+        assert!(!is_pdf("text/html", "https://example.com/a.pdf"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_text_runs_pdftotext() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stands in for pdftotext: prints the file it was given, page break
+        // and all
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("pdftotext");
+        std::fs::write(&program, "#!/bin/sh\ntail -c +10 \"$4\"\n").unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let program = program.to_string_lossy();
+
+        let text = text_with(&program, b"%PDF-1.7\nPage one\x0cPage two")
+            .await
+            .unwrap();
+        assert_eq!(text, "Page one\nPage two");
+        assert!(text_with(&program, b"<html>").await.is_err());
+        assert!(text_with("/nonexistent/pdftotext", b"%PDF-1.7\n")
+            .await
+            .is_err());
+    }
+}
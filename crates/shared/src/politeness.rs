@@ -0,0 +1,453 @@
+//! Going easy on the sites articles come from (`[fetch]` in config.toml).
+//!
+//! A week's bookmarks often hold a dozen links to one publisher, and ten
+//! requests at once to one site is a good way to get blocked. The extractor
+//! sends at most `per_domain` requests to a site at a time and, with
+//! `crawl_delay_secs`, spaces out the starts. With `robots_txt = true` it
+//! also reads each site's robots.txt once per run: disallowed articles are
+//! skipped like a `skip_extraction` rule (the story gets the bookmark
+//! excerpt), and a longer `Crawl-delay` there wins over the setting.
+
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, OwnedSemaphorePermit, Semaphore};
+
+/// A robots.txt `Crawl-delay` longer than this is cut to it, so one site
+/// can't hold up the run.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+const ROBOTS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Bigger robots.txt files are treated as missing.
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FetchSettings {
+    /// Skip articles the site's robots.txt disallows
+    pub robots_txt: bool,
+    /// Requests to one site at a time; 0 for no limit
+    pub per_domain: usize,
+    /// Seconds between the starts of two requests to one site
+    pub crawl_delay_secs: f64,
+    /// Reuse a page downloaded this recently without asking the site; see
+    /// [`crate::http_cache`]
+    pub fresh_hours: u64,
+    /// Days a downloaded page is kept; 0 to cache nothing
+    pub cache_days: u64,
+}
+
+impl Default for FetchSettings {
+    fn default() -> Self {
+        Self {
+            robots_txt: false,
+            per_domain: 2,
+            crawl_delay_secs: 0.0,
+            fresh_hours: 12,
+            cache_days: 14,
+        }
+    }
+}
+
+/// Per-site limits for one run.
+pub struct Politeness {
+    settings: FetchSettings,
+    sites: Mutex<HashMap<String, Arc<Site>>>,
+}
+
+struct Site {
+    slots: Option<Arc<Semaphore>>,
+    /// When the next request may start
+    next_start: Mutex<Instant>,
+    robots: OnceCell<Robots>,
+}
+
+/// A request's place with its site; the next one may start once it's dropped.
+pub struct Turn {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Default for Politeness {
+    fn default() -> Self {
+        Self::new(FetchSettings::default())
+    }
+}
+
+impl Politeness {
+    pub fn new(settings: FetchSettings) -> Self {
+        Self {
+            settings,
+            sites: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn site(&self, url: &str) -> Arc<Site> {
+        let mut sites = self.sites.lock().unwrap_or_else(|e| e.into_inner());
+        sites
+            .entry(site_key(url))
+            .or_insert_with(|| {
+                Arc::new(Site {
+                    slots: (self.settings.per_domain > 0)
+                        .then(|| Arc::new(Semaphore::new(self.settings.per_domain))),
+                    next_start: Mutex::new(Instant::now()),
+                    robots: OnceCell::new(),
+                })
+            })
+            .clone()
+    }
+
+    /// Whether the site's robots.txt lets `user_agent` fetch `url`. Always
+    /// true unless `robots_txt` is on, and when robots.txt can't be read.
+    pub async fn allows(&self, client: &Client, url: &str, user_agent: &str) -> bool {
+        if !self.settings.robots_txt {
+            return true;
+        }
+        let Ok(parsed) = url::Url::parse(url) else {
+            return true;
+        };
+        let site = self.site(url);
+        let robots = site
+            .robots
+            .get_or_init(|| fetch_robots(client, &parsed))
+            .await;
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+        robots.allows(user_agent, &path)
+    }
+
+    /// Wait for a free slot with `url`'s site and for its crawl delay to
+    /// pass since the last request started.
+    pub async fn turn(&self, url: &str, user_agent: &str) -> Turn {
+        let site = self.site(url);
+        let permit = match &site.slots {
+            Some(slots) => slots.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        let delay = self.crawl_delay(&site, user_agent);
+        if !delay.is_zero() {
+            let start = {
+                let mut next = site.next_start.lock().unwrap_or_else(|e| e.into_inner());
+                let start = (*next).max(Instant::now());
+                *next = start + delay;
+                start
+            };
+            tokio::time::sleep_until(start.into()).await;
+        }
+        Turn { _permit: permit }
+    }
+
+    fn crawl_delay(&self, site: &Site, user_agent: &str) -> Duration {
+        let configured = Duration::from_secs_f64(self.settings.crawl_delay_secs.max(0.0));
+        let robots = site
+            .robots
+            .get()
+            .and_then(|robots| robots.crawl_delay(user_agent))
+            .map_or(Duration::ZERO, |delay| delay.min(MAX_CRAWL_DELAY));
+        configured.max(robots)
+    }
+}
+
+/// `urls` reordered so that consecutive ones come from different sites
+/// where possible, which keeps a per-site limit from idling the fetchers.
+pub fn interleave(urls: Vec<String>) -> Vec<String> {
+    let mut by_site: Vec<(String, std::collections::VecDeque<String>)> = Vec::new();
+    for url in urls {
+        let key = site_key(&url);
+        match by_site.iter_mut().find(|(site, _)| *site == key) {
+            Some((_, queue)) => queue.push_back(url),
+            None => by_site.push((key, [url].into())),
+        }
+    }
+    let mut interleaved = Vec::new();
+    while !by_site.is_empty() {
+        for (_, queue) in &mut by_site {
+            interleaved.extend(queue.pop_front());
+        }
+        by_site.retain(|(_, queue)| !queue.is_empty());
+    }
+    interleaved
+}
+
+/// The host of `url` without `www.`, so both count as one site.
+fn site_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .map(|host| host.trim_start_matches("www.").to_string())
+        .unwrap_or_default()
+}
+
+/// The robots.txt of `url`'s site. A missing or unreadable one allows
+/// everything.
+async fn fetch_robots(client: &Client, url: &url::Url) -> Robots {
+    let Ok(robots_url) = url.join("/robots.txt") else {
+        return Robots::default();
+    };
+    let response = match client
+        .get(robots_url.as_str())
+        .timeout(ROBOTS_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            tracing::debug!("{}: {}", robots_url, response.status());
+            return Robots::default();
+        }
+        Err(e) => {
+            tracing::debug!("{}: {}", robots_url, e);
+            return Robots::default();
+        }
+    };
+    match crate::net::read_body_capped(response, MAX_ROBOTS_BYTES).await {
+        Ok(body) => Robots::parse(&String::from_utf8_lossy(&body)),
+        Err(e) => {
+            tracing::debug!("{}: {}", robots_url, e);
+            Robots::default()
+        }
+    }
+}
+
+/// The parts of a robots.txt the extractor follows.
+#[derive(Debug, Default)]
+struct Robots {
+    groups: Vec<Group>,
+}
+
+#[derive(Debug, Default)]
+struct Group {
+    /// Lowercased `User-agent` names
+    agents: Vec<String>,
+    /// `(allow, path pattern)`
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<f64>,
+}
+
+impl Robots {
+    fn parse(text: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        // A User-agent line after rules starts a new group; consecutive
+        // ones share the group that follows
+        let mut in_rules = true;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        groups.push(Group::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // An empty Disallow allows everything
+                    if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                        group.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    if let Some(group) = groups.last_mut() {
+                        group.crawl_delay = value.parse().ok().filter(|d: &f64| *d >= 0.0);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self { groups }
+    }
+
+    /// The groups for `user_agent`: those naming it, or else the `*` ones.
+    fn groups_for(&self, user_agent: &str) -> Vec<&Group> {
+        let user_agent = user_agent.to_ascii_lowercase();
+        let named: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|group| {
+                group
+                    .agents
+                    .iter()
+                    .any(|agent| agent != "*" && user_agent.contains(agent.as_str()))
+            })
+            .collect();
+        if !named.is_empty() {
+            return named;
+        }
+        self.groups
+            .iter()
+            .filter(|group| group.agents.iter().any(|agent| agent == "*"))
+            .collect()
+    }
+
+    /// The longest matching rule decides; on a tie, Allow wins.
+    fn allows(&self, user_agent: &str, path: &str) -> bool {
+        self.groups_for(user_agent)
+            .iter()
+            .flat_map(|group| &group.rules)
+            .filter(|(_, pattern)| matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+
+    fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        self.groups_for(user_agent)
+            .iter()
+            .filter_map(|group| group.crawl_delay)
+            .reduce(f64::max)
+            .map(Duration::from_secs_f64)
+    }
+}
+
+/// Whether the robots.txt path `pattern` matches `path`: a prefix match,
+/// with `*` for any run of characters and a trailing `$` for the end.
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if anchored && i + 1 == parts.len() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockServer, Response};
+
+    const ROBOTS: &str = "\
+        # Illustrative
+        User-agent: *
+        Disallow: /private/
+        Disallow: /*.pdf$
+        Allow: /private/press/
+        Crawl-delay: 2
+
+        User-agent: Googlebot
+        User-agent: Bingbot
+        Disallow:
+    ";
+
+    #[test]
+    fn test_robots_rules() {
+        let robots = Robots::parse(ROBOTS);
+        let browser = crate::net::BROWSER_USER_AGENT;
+        assert!(robots.allows(browser, "/2026/02/story"));
+        assert!(!robots.allows(browser, "/private/notes"));
+        assert!(robots.allows(browser, "/private/press/release"));
+        assert!(!robots.allows(browser, "/reports/q4.pdf"));
+        assert!(robots.allows(browser, "/reports/q4.pdf?page=2"));
+        assert!(robots.allows("Googlebot/2.1", "/private/notes"));
+        assert_eq!(robots.crawl_delay(browser), Some(Duration::from_secs(2)));
+        assert_eq!(robots.crawl_delay("Googlebot/2.1"), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("/", "/anything"));
+        assert!(matches("/news/*/amp", "/news/2026/story/amp/"));
+        assert!(!matches("/news/*/amp$", "/news/2026/story/amp/"));
+        assert!(matches("/*?print=", "/story?print=1"));
+        assert!(!matches("/private", "/public"));
+    }
+
+    #[test]
+    fn test_interleave() {
+        let urls = [
+            "https://www.example.com/1",
+            "https://example.com/2",
+            "https://other.example/1",
+            "https://example.com/3",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            interleave(urls),
+            [
+                "https://www.example.com/1",
+                "https://other.example/1",
+                "https://example.com/2",
+                "https://example.com/3",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_allows_reads_robots_once() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/robots.txt" => Response::html("User-agent: *\nDisallow: /members/\n"),
+            _ => Response::status(404),
+        });
+        let client = Client::new();
+        let politeness = Politeness::new(FetchSettings {
+            robots_txt: true,
+            ..Default::default()
+        });
+        let ua = crate::net::BROWSER_USER_AGENT;
+        let site = server.url();
+        assert!(
+            !politeness
+                .allows(&client, &format!("{}/members/a", site), ua)
+                .await
+        );
+        assert!(
+            politeness
+                .allows(&client, &format!("{}/news/b", site), ua)
+                .await
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        // Off by default: robots.txt isn't even fetched
+        let politeness = Politeness::default();
+        assert!(
+            politeness
+                .allows(&client, &format!("{}/members/a", site), ua)
+                .await
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_turn_spaces_out_requests() {
+        let politeness = Politeness::new(FetchSettings {
+            per_domain: 1,
+            crawl_delay_secs: 0.05,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        for _ in 0..3 {
+            let _turn = politeness.turn("https://example.com/a", "test").await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // Other sites don't wait
+        let start = Instant::now();
+        let _turn = politeness.turn("https://other.example/a", "test").await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
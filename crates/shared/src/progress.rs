@@ -0,0 +1,377 @@
+//! Progress events from the extractor, summarizer and clusterer.
+//!
+//! Each stage reports what it is doing through a [`ProgressReporter`].
+//! [`ConsoleProgress`] reproduces the familiar dots on stderr; wrappers and
+//! UIs can use [`JsonLinesProgress`] instead (e.g. `collect-stories
+//! --progress-json FILE`) and compute their own bars and ETAs from
+//! `stage_started.total` and the `*_done` events.
+//!
+//! While the rate limiter holds requests off, a [`PauseWatch`] reports a
+//! `paused` event every second with the time left and the number of items
+//! still queued, so a long stall shows as a countdown rather than a hang.
+
+use serde::Serialize;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::rate_limit::RateLimiter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Extract,
+    Summarize,
+    Cluster,
+    Edit,
+    Connect,
+    Followup,
+    Translate,
+    Entities,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// A stage began working through `total` items
+    StageStarted {
+        stage: Stage,
+        total: usize,
+    },
+    StageFinished {
+        stage: Stage,
+    },
+    FetchStarted {
+        url: String,
+    },
+    /// `outcome` is one of success, paywalled, skipped or failed
+    FetchDone {
+        url: String,
+        outcome: &'static str,
+    },
+    SummaryDone {
+        url: String,
+        ok: bool,
+    },
+    /// Message Batches job status after a poll
+    BatchProgress {
+        done: u64,
+        total: usize,
+    },
+    RateLimited {
+        stage: Stage,
+        attempt: u32,
+        wait_secs: u64,
+    },
+    /// A request failed for some other reason and will be retried
+    Retrying {
+        stage: Stage,
+        attempt: u32,
+        wait_secs: u64,
+        error: String,
+    },
+    /// The rate limiter is holding requests off for `resume_secs` more
+    /// seconds, with `queued` items still to do. Sent every second during
+    /// the pause, and once with `resume_secs` 0 when it ends.
+    Paused {
+        stage: Stage,
+        resume_secs: u64,
+        queued: usize,
+    },
+}
+
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, event: &ProgressEvent);
+}
+
+/// Shared handle passed to every stage.
+pub type Progress = Arc<dyn ProgressReporter>;
+
+/// Dots on stderr, one per fetched article or summary, plus retry notices.
+#[derive(Default)]
+pub struct ConsoleProgress {
+    /// A line of dots is open and needs a newline before the next message
+    dots: AtomicBool,
+    /// The open line is a pause countdown that gets rewritten in place
+    countdown: AtomicBool,
+}
+
+impl ConsoleProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn end_line(&self) {
+        self.countdown.store(false, Ordering::Relaxed);
+        if self.dots.swap(false, Ordering::Relaxed) {
+            eprintln!();
+        }
+    }
+}
+
+impl ProgressReporter for ConsoleProgress {
+    fn report(&self, event: &ProgressEvent) {
+        match event {
+            ProgressEvent::FetchDone { .. } | ProgressEvent::SummaryDone { .. } => {
+                if self.countdown.load(Ordering::Relaxed) {
+                    self.end_line();
+                }
+                eprint!(".");
+                let _ = std::io::stderr().flush();
+                self.dots.store(true, Ordering::Relaxed);
+            }
+            ProgressEvent::BatchProgress { done, total } => {
+                eprint!("\r  Batch: {}/{} done", done, total);
+                let _ = std::io::stderr().flush();
+                self.dots.store(true, Ordering::Relaxed);
+            }
+            ProgressEvent::StageFinished { .. } => self.end_line(),
+            ProgressEvent::Paused { resume_secs: 0, .. } => self.end_line(),
+            ProgressEvent::Paused {
+                resume_secs,
+                queued,
+                ..
+            } => {
+                if !self.countdown.load(Ordering::Relaxed) {
+                    self.end_line();
+                }
+                eprint!("\r  {}   ", pause_message(*resume_secs, *queued));
+                let _ = std::io::stderr().flush();
+                self.dots.store(true, Ordering::Relaxed);
+                self.countdown.store(true, Ordering::Relaxed);
+            }
+            ProgressEvent::RateLimited {
+                stage,
+                attempt,
+                wait_secs,
+            } => {
+                self.end_line();
+                eprintln!(
+                    "Rate limit hit during {}, waiting {}s before retry {} of 5...",
+                    stage.name(),
+                    wait_secs,
+                    attempt + 1
+                );
+            }
+            // Summaries retry quietly; clustering is a single request, so
+            // its retries are worth mentioning.
+            ProgressEvent::Retrying {
+                stage: Stage::Cluster,
+                attempt,
+                wait_secs,
+                error,
+            } => {
+                self.end_line();
+                eprintln!(
+                    "Clustering error (attempt {} of 5): {}, retrying after {}s...",
+                    attempt, error, wait_secs
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `"Rate-limited, resuming in 12s, 4 stories queued"`
+fn pause_message(resume_secs: u64, queued: usize) -> String {
+    let mut message = format!("Rate-limited, resuming in {}s", resume_secs);
+    match queued {
+        0 => {}
+        1 => message.push_str(", 1 story queued"),
+        n => message.push_str(&format!(", {} stories queued", n)),
+    }
+    message
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Extract => "extraction",
+            Stage::Summarize => "summarization",
+            Stage::Cluster => "clustering",
+            Stage::Edit => "editing",
+            Stage::Connect => "connections",
+            Stage::Followup => "follow-up recaps",
+            Stage::Translate => "translation",
+            Stage::Entities => "entity tagging",
+        }
+    }
+}
+
+/// One JSON object per event, each with an RFC 3339 `ts` field.
+pub struct JsonLinesProgress<W: Write + Send> {
+    out: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesProgress<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out: Mutex::new(out),
+        }
+    }
+}
+
+impl<W: Write + Send> ProgressReporter for JsonLinesProgress<W> {
+    fn report(&self, event: &ProgressEvent) {
+        let Ok(serde_json::Value::Object(mut line)) = serde_json::to_value(event) else {
+            return;
+        };
+        line.insert("ts".to_string(), chrono::Utc::now().to_rfc3339().into());
+        if let Ok(mut out) = self.out.lock() {
+            let _ = writeln!(out, "{}", serde_json::Value::Object(line));
+            let _ = out.flush();
+        }
+    }
+}
+
+/// Reports nothing.
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&self, _event: &ProgressEvent) {}
+}
+
+/// Reports [`ProgressEvent::Paused`] while a rate limiter pause lasts.
+/// Stops when dropped.
+pub struct PauseWatch {
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl PauseWatch {
+    /// Check `limiter` every second for the rest of `stage`; `queued` is
+    /// the number of items not yet done, kept up to date by the caller.
+    pub fn start(
+        limiter: Arc<RateLimiter>,
+        progress: Progress,
+        stage: Stage,
+        queued: Arc<AtomicUsize>,
+    ) -> Self {
+        let task = tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(Duration::from_secs(1));
+            let mut paused = false;
+            loop {
+                ticks.tick().await;
+                let pause = limiter.remaining_pause();
+                if pause.is_zero() && !paused {
+                    continue;
+                }
+                paused = !pause.is_zero();
+                progress.report(&ProgressEvent::Paused {
+                    stage,
+                    // Round up, so the countdown ends at 1s rather than 0s
+                    resume_secs: pause.as_millis().div_ceil(1000) as u64,
+                    queued: queued.load(Ordering::Relaxed),
+                });
+            }
+        });
+        Self { task }
+    }
+}
+
+impl Drop for PauseWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The default reporter for interactive runs.
+pub fn console() -> Progress {
+    Arc::new(ConsoleProgress::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pause_message() {
+        assert_eq!(
+            pause_message(12, 4),
+            "Rate-limited, resuming in 12s, 4 stories queued"
+        );
+        assert_eq!(
+            pause_message(3, 1),
+            "Rate-limited, resuming in 3s, 1 story queued"
+        );
+        assert_eq!(pause_message(5, 0), "Rate-limited, resuming in 5s");
+    }
+
+    #[tokio::test]
+    async fn test_pause_watch_reports_countdown() {
+        #[derive(Default)]
+        struct Events(Mutex<Vec<ProgressEvent>>);
+        impl ProgressReporter for Events {
+            fn report(&self, event: &ProgressEvent) {
+                self.0.lock().unwrap().push(event.clone());
+            }
+        }
+
+        let limiter = RateLimiter::new();
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("retry-after", "1".parse().unwrap());
+        limiter.record(429, &headers);
+
+        let events = Arc::new(Events::default());
+        let watch = PauseWatch::start(
+            limiter,
+            events.clone(),
+            Stage::Summarize,
+            Arc::new(AtomicUsize::new(4)),
+        );
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        drop(watch);
+
+        let events = events.0.lock().unwrap();
+        let countdown: Vec<(u64, usize)> = events
+            .iter()
+            .map(|e| match e {
+                ProgressEvent::Paused {
+                    resume_secs,
+                    queued,
+                    ..
+                } => (*resume_secs, *queued),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(countdown, [(1, 4), (0, 4)]);
+    }
+
+    #[test]
+    fn test_json_lines_progress() {
+        let buffer = Buffer::default();
+        let progress = JsonLinesProgress::new(buffer.clone());
+        progress.report(&ProgressEvent::StageStarted {
+            stage: Stage::Summarize,
+            total: 3,
+        });
+        progress.report(&ProgressEvent::FetchDone {
+            url: "https://example.com".to_string(),
+            outcome: "paywalled",
+        });
+
+        let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "stage_started");
+        assert_eq!(lines[0]["stage"], "summarize");
+        assert_eq!(lines[0]["total"], 3);
+        assert_eq!(lines[1]["outcome"], "paywalled");
+        assert!(lines[1]["ts"].is_string());
+    }
+}
@@ -0,0 +1,521 @@
+//! Prompt templates for the summarizer and clusterer.
+//!
+//! The built-in prompts live in `assets/prompts/` and can be customized by
+//! exporting them (`briefing export-assets`) and editing the copies in the
+//! config dir. A show can get its own wording by placing a template under
+//! `prompts/{slug}/`, which takes precedence over the shared one.
+//!
+//! Templates use `{{ name }}` placeholders. `show`, `show_slug`, `audience`
+//! and `format` are available everywhere; each prompt adds its own per-request
+//! variables (see [`PromptKind::request_vars`]). Unknown names are rejected
+//! when the template is loaded, so a typo fails the run up front instead of
+//! reaching the model verbatim.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::ShowInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    Summarize,
+    Cluster,
+    /// The optional editorial pass over each topic's summaries
+    Edit,
+    /// "How these connect" paragraphs for topics of several stories
+    Connect,
+    /// One-line recaps of a follow-up story's earlier coverage
+    Followup,
+    /// Non-English articles into English, before they're summarized
+    Translate,
+    /// Subtopics for a topic over `[clustering] max_topic_size`
+    Split,
+    /// Companies, products and people each story names
+    Entities,
+}
+
+impl PromptKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            PromptKind::Summarize => "summarize",
+            PromptKind::Cluster => "cluster",
+            PromptKind::Edit => "edit",
+            PromptKind::Connect => "connect",
+            PromptKind::Followup => "followup",
+            PromptKind::Translate => "translate",
+            PromptKind::Split => "split",
+            PromptKind::Entities => "entities",
+        }
+    }
+
+    /// Variables filled in for each request rather than once per run.
+    pub fn request_vars(self) -> &'static [&'static str] {
+        match self {
+            PromptKind::Summarize => &["article"],
+            PromptKind::Cluster => &["articles", "last_index"],
+            PromptKind::Edit | PromptKind::Connect => &["topic", "stories"],
+            PromptKind::Followup => &["story", "earlier"],
+            PromptKind::Entities => &["story"],
+            PromptKind::Translate => &["language", "article"],
+            PromptKind::Split => &["topic", "articles", "last_index", "max"],
+        }
+    }
+
+    fn asset_path(self) -> String {
+        format!("prompts/{}.txt", self.name())
+    }
+
+    fn show_asset_path(self, slug: &str) -> String {
+        format!("prompts/{}/{}.txt", slug, self.name())
+    }
+}
+
+/// `[prompts]` section of config.toml. `audience` and `format` feed the
+/// template variables of the same name, and `summary_length` and
+/// `audience_level` the `length` and `level` instructions;
+/// `[prompts.shows.<slug>]` overrides them for one show.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PromptSettings {
+    pub audience: String,
+    pub format: String,
+    pub summary_length: SummaryLength,
+    pub audience_level: AudienceLevel,
+    pub shows: HashMap<String, ShowPromptSettings>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShowPromptSettings {
+    pub audience: Option<String>,
+    pub format: Option<String>,
+    pub summary_length: Option<SummaryLength>,
+    pub audience_level: Option<AudienceLevel>,
+}
+
+/// How long summaries are. Besides the prompt's instructions, it sets the
+/// reply's token limit and the word limits of the summary checks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SummaryLength {
+    /// A one-sentence lede and a one-sentence nutgraf
+    OneLiner,
+    #[default]
+    Standard,
+    /// A longer nutgraf with background and what comes next
+    DeepDive,
+}
+
+impl SummaryLength {
+    pub const ALL: [SummaryLength; 3] = [Self::OneLiner, Self::Standard, Self::DeepDive];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OneLiner => "one-liner",
+            Self::Standard => "standard",
+            Self::DeepDive => "deep-dive",
+        }
+    }
+
+    /// The `{{ length }}` instruction.
+    fn instruction(self) -> &'static str {
+        match self {
+            Self::OneLiner => "Keep it tight: a LEDE under 25 words and a NUTGRAF of a single sentence.",
+            Self::Standard => "Keep the LEDE to one sentence and the NUTGRAF to 2-4 sentences.",
+            Self::DeepDive => "Go deeper: the NUTGRAF may run 4-6 sentences covering the background, the stakes and what comes next.",
+        }
+    }
+
+    /// Most tokens a summary reply may use.
+    pub fn max_tokens(self) -> u32 {
+        match self {
+            Self::OneLiner => 512,
+            Self::Standard => 1024,
+            Self::DeepDive => 2048,
+        }
+    }
+
+    /// Longest acceptable lede and other fields, in words.
+    pub fn word_limits(self) -> (usize, usize) {
+        match self {
+            Self::OneLiner => (30, 50),
+            Self::Standard => (45, 120),
+            Self::DeepDive => (60, 220),
+        }
+    }
+}
+
+impl std::str::FromStr for SummaryLength {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('_', "-");
+        Self::ALL
+            .into_iter()
+            .find(|l| l.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown summary length '{}' (use one-liner, standard or deep-dive)",
+                    s
+                )
+            })
+    }
+}
+
+/// How much the listeners already know, which decides what the summaries
+/// explain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AudienceLevel {
+    /// No technical background
+    General,
+    /// Follows tech news
+    #[default]
+    Informed,
+    /// Works in the field
+    Expert,
+}
+
+impl AudienceLevel {
+    pub const ALL: [AudienceLevel; 3] = [Self::General, Self::Informed, Self::Expert];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::Informed => "informed",
+            Self::Expert => "expert",
+        }
+    }
+
+    /// The `{{ level }}` instruction.
+    fn instruction(self) -> &'static str {
+        match self {
+            Self::General => {
+                "Assume no technical background: explain jargon and acronyms in plain words."
+            }
+            Self::Informed => {
+                "Assume listeners follow tech news: common terms need no explanation."
+            }
+            Self::Expert => {
+                "Assume expert listeners: use precise technical terms and skip the basics."
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for AudienceLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|l| l.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown audience level '{}' (use general, informed or expert)",
+                    s
+                )
+            })
+    }
+}
+
+impl Default for PromptSettings {
+    fn default() -> Self {
+        Self {
+            audience: "a tech-savvy general audience".to_string(),
+            format: "weekly tech podcast".to_string(),
+            summary_length: SummaryLength::default(),
+            audience_level: AudienceLevel::default(),
+            shows: HashMap::new(),
+        }
+    }
+}
+
+impl PromptSettings {
+    /// The summary length for `show`.
+    pub fn summary_length(&self, show: &ShowInfo) -> SummaryLength {
+        self.shows
+            .get(&show.slug)
+            .and_then(|o| o.summary_length)
+            .unwrap_or(self.summary_length)
+    }
+
+    /// The audience level for `show`.
+    pub fn audience_level(&self, show: &ShowInfo) -> AudienceLevel {
+        self.shows
+            .get(&show.slug)
+            .and_then(|o| o.audience_level)
+            .unwrap_or(self.audience_level)
+    }
+
+    /// Use `length` and `level`, where given, for every show, whatever the
+    /// config says (`--summary-length`, `--audience-level`).
+    pub fn set_summary_style(
+        &mut self,
+        length: Option<SummaryLength>,
+        level: Option<AudienceLevel>,
+    ) {
+        for overrides in self.shows.values_mut() {
+            if length.is_some() {
+                overrides.summary_length = None;
+            }
+            if level.is_some() {
+                overrides.audience_level = None;
+            }
+        }
+        self.summary_length = length.unwrap_or(self.summary_length);
+        self.audience_level = level.unwrap_or(self.audience_level);
+    }
+
+    fn vars_for(&self, show: &ShowInfo) -> BTreeMap<String, String> {
+        let overrides = self.shows.get(&show.slug);
+        let audience = overrides
+            .and_then(|o| o.audience.clone())
+            .unwrap_or_else(|| self.audience.clone());
+        let format = overrides
+            .and_then(|o| o.format.clone())
+            .unwrap_or_else(|| self.format.clone());
+
+        BTreeMap::from([
+            ("show".to_string(), show.name.clone()),
+            ("show_slug".to_string(), show.slug.clone()),
+            ("audience".to_string(), audience),
+            ("format".to_string(), format),
+            (
+                "length".to_string(),
+                self.summary_length(show).instruction().to_string(),
+            ),
+            (
+                "level".to_string(),
+                self.audience_level(show).instruction().to_string(),
+            ),
+        ])
+    }
+}
+
+/// A loaded template with its per-run variables already bound.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    kind: PromptKind,
+    template: String,
+    vars: BTreeMap<String, String>,
+}
+
+impl Prompt {
+    /// Load the template for `kind`, preferring the show-specific copy, then
+    /// the customized shared copy, then the built-in.
+    pub fn load(kind: PromptKind, show: &ShowInfo, settings: &PromptSettings) -> Result<Self> {
+        let template = crate::assets::load(&kind.show_asset_path(&show.slug))
+            .or_else(|| crate::assets::load(&kind.asset_path()))
+            .with_context(|| format!("No {} prompt template", kind.name()))?;
+        Self::from_template(kind, &template, settings.vars_for(show))
+            .with_context(|| format!("Invalid {} prompt template", kind.name()))
+    }
+
+    /// The built-in template with default settings, for callers that don't
+    /// know which show they're working for.
+    pub fn builtin(kind: PromptKind) -> Self {
+        let template = crate::assets::get(&kind.asset_path()).unwrap_or_default();
+        let vars = PromptSettings::default().vars_for(&ShowInfo::new("the show", "", ""));
+        Self::from_template(kind, &template, vars).expect("built-in prompt templates are valid")
+    }
+
+    fn from_template(
+        kind: PromptKind,
+        template: &str,
+        vars: BTreeMap<String, String>,
+    ) -> Result<Self> {
+        for name in placeholders(template)? {
+            if !vars.contains_key(name) && !kind.request_vars().contains(&name) {
+                let mut known: Vec<&str> = vars.keys().map(String::as_str).collect();
+                known.extend(kind.request_vars());
+                bail!(
+                    "unknown variable `{}` (available: {})",
+                    name,
+                    known.join(", ")
+                );
+            }
+        }
+        Ok(Self {
+            kind,
+            template: template.trim_end().to_string(),
+            vars,
+        })
+    }
+
+    pub fn kind(&self) -> PromptKind {
+        self.kind
+    }
+
+    /// Fill in the template. `request` supplies the per-request variables.
+    pub fn render(&self, request: &[(&str, &str)]) -> String {
+        let mut out = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            // `from_template` has already checked every placeholder closes.
+            let end = rest[start..].find("}}").map_or(rest.len(), |e| start + e);
+            let name = rest[start + 2..end].trim();
+            let value = request
+                .iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| *v)
+                .or_else(|| self.vars.get(name).map(String::as_str))
+                .unwrap_or_default();
+            out.push_str(value);
+            rest = rest.get(end + 2..).unwrap_or_default();
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Names of every `{{ name }}` placeholder in `template`.
+pub(crate) fn placeholders(template: &str) -> Result<Vec<&str>> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").context("unclosed `{{` placeholder")?;
+        let name = after[..end].trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("invalid placeholder `{{{{{}}}}}`", &after[..end]);
+        }
+        names.push(name);
+        rest = &after[end + 2..];
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show() -> ShowInfo {
+        ShowInfo::new("MacBreak Weekly", "mbw", "#mbw")
+    }
+
+    #[test]
+    fn test_builtin_prompts_render() {
+        let prompt = Prompt::builtin(PromptKind::Summarize);
+        let text = prompt.render(&[("article", "Apple shipped a thing.")]);
+        assert!(text.contains("FORMAT: EDITORIAL"));
+        assert!(text.ends_with("Article:\nApple shipped a thing."));
+        assert!(!text.contains("{{"));
+
+        let prompt = Prompt::builtin(PromptKind::Cluster);
+        let text = prompt.render(&[("articles", "0: A - a"), ("last_index", "0")]);
+        assert!(text.contains("\"article_indices\": [0, 3, 7]"));
+        assert!(text.contains("from 0 to 0 must"));
+
+        let prompt = Prompt::builtin(PromptKind::Edit);
+        let text = prompt.render(&[("topic", "Apple"), ("stories", "STORY 1")]);
+        assert!(text.contains("under the topic \"Apple\""));
+        assert!(text.ends_with("Stories:\nSTORY 1"));
+
+        let prompt = Prompt::builtin(PromptKind::Followup);
+        let text = prompt.render(&[("story", "NOW"), ("earlier", "THEN")]);
+        assert!(text.contains("Earlier coverage:\nTHEN"));
+        assert!(text.ends_with("This week's story:\nNOW"));
+
+        let prompt = Prompt::builtin(PromptKind::Translate);
+        let text = prompt.render(&[("language", "German"), ("article", "Hallo")]);
+        assert!(text.contains("Translate the German article"));
+        assert!(text.ends_with("Article:\nHallo"));
+
+        let prompt = Prompt::builtin(PromptKind::Split);
+        let text = prompt.render(&[
+            ("topic", "Apple"),
+            ("articles", "0: A - a"),
+            ("last_index", "0"),
+            ("max", "6"),
+        ]);
+        assert!(text.contains("\"title\": \"Apple: Hardware\""));
+        assert!(text.contains("at most 6 articles"));
+
+        let prompt = Prompt::builtin(PromptKind::Entities);
+        let text = prompt.render(&[("story", "Apple cuts prices")]);
+        assert!(text.contains("\"companies\": [\"Apple\"]"));
+        assert!(text.ends_with("Story:\nApple cuts prices"));
+    }
+
+    #[test]
+    fn test_show_overrides_audience() {
+        let mut settings = PromptSettings::default();
+        settings.shows.insert(
+            "mbw".to_string(),
+            ShowPromptSettings {
+                audience: Some("Apple enthusiasts".to_string()),
+                ..Default::default()
+            },
+        );
+        let prompt = Prompt::from_template(
+            PromptKind::Summarize,
+            "{{show}} for {{ audience }} ({{format}}): {{article}}",
+            settings.vars_for(&show()),
+        )
+        .unwrap();
+        assert_eq!(
+            prompt.render(&[("article", "text")]),
+            "MacBreak Weekly for Apple enthusiasts (weekly tech podcast): text"
+        );
+    }
+
+    #[test]
+    fn test_unknown_variable_is_rejected() {
+        let vars = PromptSettings::default().vars_for(&show());
+        let err = Prompt::from_template(PromptKind::Cluster, "{{ artcles }}", vars.clone())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("artcles"));
+        // Request variables belong to their own prompt only.
+        assert!(Prompt::from_template(PromptKind::Cluster, "{{ article }}", vars.clone()).is_err());
+        assert!(Prompt::from_template(PromptKind::Summarize, "{{ oops", vars).is_err());
+    }
+
+    #[test]
+    fn test_settings_parse_show_section() {
+        let settings: PromptSettings = toml::from_str(
+            r#"
+            audience = "listeners"
+            [shows.im]
+            format = "AI talk show"
+            "#,
+        )
+        .unwrap();
+        let vars = settings.vars_for(&ShowInfo::new("Intelligent Machines", "im", "#im"));
+        assert_eq!(vars["audience"], "listeners");
+        assert_eq!(vars["format"], "AI talk show");
+    }
+
+    #[test]
+    fn test_summary_length_and_level() {
+        let mut settings: PromptSettings = toml::from_str(
+            r#"
+            audience_level = "expert"
+            [shows.twit]
+            summary_length = "one-liner"
+            audience_level = "general"
+            "#,
+        )
+        .unwrap();
+        let vars = settings.vars_for(&show());
+        assert_eq!(settings.summary_length(&show()), SummaryLength::Standard);
+        assert!(vars["level"].starts_with("Assume expert listeners"));
+        let twit = ShowInfo::new("This Week in Tech", "twit", "TWiT");
+        let vars = settings.vars_for(&twit);
+        assert_eq!(settings.summary_length(&twit), SummaryLength::OneLiner);
+        assert!(vars["length"].starts_with("Keep it tight"));
+        assert!(vars["level"].starts_with("Assume no technical background"));
+        assert_eq!(settings.audience_level(&twit), AudienceLevel::General);
+
+        // The command line wins over every show's settings
+        settings.set_summary_style(Some(SummaryLength::DeepDive), None);
+        assert_eq!(settings.summary_length(&twit), SummaryLength::DeepDive);
+        assert!(settings.vars_for(&twit)["level"].starts_with("Assume no technical"));
+        assert_eq!("Deep_Dive".parse(), Ok(SummaryLength::DeepDive));
+        assert!("short".parse::<AudienceLevel>().is_err());
+    }
+}
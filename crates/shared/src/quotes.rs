@@ -0,0 +1,209 @@
+//! Quotes and who said them.
+//!
+//! The model writes a quote as `"text" -- Speaker Name`, but it also uses
+//! curly quotes, dashes and `Name, Role` or `Name (Role)` attributions, and
+//! hand-edited org drafts can hold anything. [`Quote::parse`] reads all of
+//! these into the quote's text, speaker and role, and writes them back in
+//! one format: `"text" -- Speaker Name, Role`. A quote it can't find a
+//! speaker in is kept word for word as free-form text. Story files written
+//! before quotes had a speaker hold the whole line, which is read the same
+//! way, and those from before a story could have several hold one quote or
+//! `null`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct Quote {
+    /// The words quoted, without quote marks, or the whole line of a
+    /// free-form quote
+    pub text: String,
+    /// Who said it; `None` for a free-form quote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// The speaker's title or affiliation, e.g. "CEO of Apple"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// Quote marks a quote may open with, and the mark that closes each.
+const MARKS: &[(char, char)] = &[('"', '"'), ('“', '”'), ('«', '»')];
+
+/// What may come between the closing mark and the speaker.
+const SEPARATORS: &[&str] = &["--", "—", "–", "-", ","];
+
+impl Quote {
+    /// Read a quote line. Without a speaker after the quote marks (or after
+    /// ` -- ` when there are none), the line is kept as free-form text.
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let free_form = || Quote {
+            text: line.to_string(),
+            speaker: None,
+            role: None,
+        };
+        let Some((text, attribution)) = split_marked(line).or_else(|| split_unmarked(line)) else {
+            return free_form();
+        };
+        let attribution = attribution.trim().trim_end_matches('.').trim();
+        let (speaker, role) = match attribution
+            .strip_suffix(')')
+            .and_then(|a| a.split_once(" ("))
+            .or_else(|| attribution.split_once(", "))
+        {
+            Some((speaker, role)) => (speaker.trim(), Some(role.trim())),
+            None => (attribution, None),
+        };
+        if text.is_empty() || speaker.is_empty() {
+            return free_form();
+        }
+        Quote {
+            text: text.to_string(),
+            speaker: Some(speaker.to_string()),
+            role: role.filter(|r| !r.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+/// `"text" -- attribution`, split at the last closing mark.
+fn split_marked(line: &str) -> Option<(&str, &str)> {
+    let open = line.chars().next()?;
+    let &(_, close) = MARKS.iter().find(|(o, _)| *o == open)?;
+    let body = &line[open.len_utf8()..];
+    let end = body.rfind(close)?;
+    let rest = body[end + close.len_utf8()..].trim_start();
+    let attribution = SEPARATORS.iter().find_map(|sep| rest.strip_prefix(sep))?;
+    Some((body[..end].trim(), attribution))
+}
+
+/// `text -- attribution` without quote marks.
+fn split_unmarked(line: &str) -> Option<(&str, &str)> {
+    let (text, attribution) = line
+        .rsplit_once(" -- ")
+        .or_else(|| line.rsplit_once(" — "))?;
+    Some((text.trim(), attribution))
+}
+
+impl fmt::Display for Quote {
+    /// `"text" -- Speaker, Role`, or a free-form quote as it was written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(speaker) = &self.speaker else {
+            return f.write_str(&self.text);
+        };
+        write!(f, "\"{}\" -- {}", self.text, speaker)?;
+        if let Some(role) = &self.role {
+            write!(f, ", {}", role)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for Quote {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            /// A story file from before quotes had a speaker
+            Line(String),
+            Parts {
+                text: String,
+                #[serde(default)]
+                speaker: Option<String>,
+                #[serde(default)]
+                role: Option<String>,
+            },
+        }
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Line(line) => Quote::parse(&line),
+            Stored::Parts {
+                text,
+                speaker,
+                role,
+            } => Quote {
+                text,
+                speaker,
+                role,
+            },
+        })
+    }
+}
+
+/// A summary's quotes: a list, or from older story files, one quote or
+/// `null`.
+pub(crate) fn deserialize_quotes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Quote>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Many(Vec<Quote>),
+        One(Quote),
+    }
+    Ok(match Option::<Stored>::deserialize(deserializer)? {
+        Some(Stored::Many(quotes)) => quotes,
+        Some(Stored::One(quote)) => vec![quote],
+        None => Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(text: &str, speaker: &str, role: Option<&str>) -> Quote {
+        Quote {
+            text: text.to_string(),
+            speaker: Some(speaker.to_string()),
+            role: role.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_attributions() {
+        assert_eq!(
+            Quote::parse("\"This is huge\" -- Tim Cook"),
+            quote("This is huge", "Tim Cook", None)
+        );
+        assert_eq!(
+            Quote::parse("“We’re not done” — Tim Cook, CEO of Apple."),
+            quote("We’re not done", "Tim Cook", Some("CEO of Apple"))
+        );
+        assert_eq!(
+            Quote::parse("Ship it -- Lisa Su (AMD)"),
+            quote("Ship it", "Lisa Su", Some("AMD"))
+        );
+    }
+
+    #[test]
+    fn test_free_form_is_kept() {
+        for line in [
+            "Cook called it \"huge\" on the call",
+            "\"No speaker here\"",
+            "\"Dangling\" --",
+        ] {
+            let parsed = Quote::parse(line);
+            assert_eq!(parsed.speaker, None);
+            assert_eq!(parsed.to_string(), line);
+        }
+    }
+
+    #[test]
+    fn test_normalized_format_round_trips() {
+        let parsed = Quote::parse("“We’re not done” – Tim Cook (CEO of Apple)");
+        assert_eq!(
+            parsed.to_string(),
+            "\"We’re not done\" -- Tim Cook, CEO of Apple"
+        );
+        assert_eq!(Quote::parse(&parsed.to_string()), parsed);
+    }
+
+    #[test]
+    fn test_deserialize_old_and_new() {
+        let old: Quote = serde_json::from_str(r#""\"Wow\" -- Tim""#).unwrap();
+        assert_eq!(old, quote("Wow", "Tim", None));
+        let json = serde_json::to_string(&old).unwrap();
+        assert_eq!(json, r#"{"text":"Wow","speaker":"Tim"}"#);
+        assert_eq!(serde_json::from_str::<Quote>(&json).unwrap(), old);
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::NaiveDate;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bookmark {
@@ -18,14 +19,80 @@ pub struct Bookmark {
 #[derive(Debug, Deserialize)]
 struct RaindropResponse {
     items: Vec<Bookmark>,
+    /// Total matches across all pages
     #[serde(default)]
-    #[allow(dead_code)]
     count: usize,
 }
 
+/// Collection ID Raindrop uses for "all bookmarks except Trash".
+pub const ALL_COLLECTIONS: i64 = 0;
+
+/// Largest page size the API allows.
+const PER_PAGE: usize = 50;
+
+/// Stop paging after this many pages even if the API keeps answering, so a
+/// misreported `count` can't keep a run going forever.
+const MAX_PAGES: usize = 100;
+
+/// Tag that stars a bookmark unless `star_tag` says otherwise.
+pub const DEFAULT_STAR_TAG: &str = "star";
+
+/// The `[raindrop]` section of `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RaindropSettings {
+    /// Collection to search instead of all bookmarks
+    pub collection: Option<i64>,
+    /// Per-show collections by show slug, e.g. `{ mbw = 12345 }`
+    pub collections: HashMap<String, i64>,
+    /// Tag added to bookmarks used in a briefing; `{show}` and `{date}` are
+    /// replaced, e.g. `"briefed-{show}-{date}"`
+    pub briefed_tag: Option<String>,
+    /// Collection to move bookmarks to once they've been used
+    pub briefed_collection: Option<i64>,
+    /// Tag marking a bookmark as a must-cover story; `star` when unset
+    pub star_tag: Option<String>,
+}
+
+impl RaindropSettings {
+    /// The collection to search for `show_slug`'s bookmarks.
+    pub fn collection_for(&self, show_slug: &str) -> i64 {
+        self.collections
+            .get(show_slug)
+            .copied()
+            .or(self.collection)
+            .unwrap_or(ALL_COLLECTIONS)
+    }
+
+    /// Whether used bookmarks should be tagged or moved at all.
+    pub fn writes_back(&self) -> bool {
+        self.briefed_tag.is_some() || self.briefed_collection.is_some()
+    }
+
+    /// Whether `bookmark` carries the star tag (in any case).
+    pub fn is_starred(&self, bookmark: &Bookmark) -> bool {
+        let star = self.star_tag.as_deref().unwrap_or(DEFAULT_STAR_TAG);
+        bookmark.tags.iter().any(|t| t.eq_ignore_ascii_case(star))
+    }
+
+    /// The tag for bookmarks used in `show_slug`'s briefing for `date`.
+    pub fn briefed_tag_for(&self, show_slug: &str, date: NaiveDate) -> Option<String> {
+        self.briefed_tag.as_ref().map(|template| {
+            template
+                .replace("{show}", show_slug)
+                .replace("{date}", &date.format("%Y-%m-%d").to_string())
+        })
+    }
+}
+
+/// The REST API, overridable via RAINDROP_API_URL for testing against a
+/// mock server.
+pub const DEFAULT_API_URL: &str = "https://api.raindrop.io/rest/v1";
+
 pub struct RaindropClient {
     client: Client,
     api_token: String,
+    api_url: String,
 }
 
 impl RaindropClient {
@@ -35,88 +102,251 @@ impl RaindropClient {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client, api_token })
+        let api_url = std::env::var("RAINDROP_API_URL")
+            .unwrap_or_else(|_| DEFAULT_API_URL.to_string())
+            .trim_end_matches('/')
+            .to_string();
+        Ok(Self {
+            client,
+            api_token,
+            api_url,
+        })
     }
 
-    pub async fn fetch_bookmarks(&self, tag: &str, since: DateTime<Utc>) -> Result<Vec<Bookmark>> {
-        use std::collections::HashSet;
-
-        let date_str = since.format("%Y-%m-%d").to_string();
-
-        // Search for multiple case variations to handle uppercase/lowercase tags
-        // Common variations: lowercase, UPPERCASE, Titlecase
-        let tag_variations = vec![
-            tag.to_lowercase(),
-            tag.to_uppercase(),
-            // Titlecase (first char upper, rest lower)
-            {
-                let mut chars = tag.chars();
-                match chars.next() {
-                    None => String::new(),
-                    Some(first) => first
-                        .to_uppercase()
-                        .chain(chars.as_str().to_lowercase().chars())
-                        .collect(),
-                }
-            },
-        ];
-
-        let mut all_bookmarks = Vec::new();
+    /// Bookmarks in `collection` tagged `tag` (in any letter case) created
+    /// after `since`.
+    pub async fn fetch_bookmarks(
+        &self,
+        collection: i64,
+        tag: &str,
+        since: NaiveDate,
+    ) -> Result<Vec<Bookmark>> {
+        let search_query = search_query(tag, since);
+        let mut all_bookmarks: Vec<Bookmark> = Vec::new();
         let mut seen_ids = HashSet::new();
+        let mut page = 0;
 
-        // Search for each tag variation
-        for tag_variant in &tag_variations {
-            let search_query = format!("{} created:>{}", tag_variant, date_str);
-            let mut page = 0;
-            let per_page = 50;
-
-            loop {
-                let url = format!(
-                    "https://api.raindrop.io/rest/v1/raindrops/0?perpage={}&page={}&search={}",
-                    per_page,
-                    page,
-                    urlencoding::encode(&search_query)
-                );
+        loop {
+            let url = format!(
+                "{}/raindrops/{}?perpage={}&page={}&search={}",
+                self.api_url,
+                collection,
+                PER_PAGE,
+                page,
+                urlencoding::encode(&search_query)
+            );
+            tracing::debug!("GET {}", url);
 
-                let response = self
-                    .client
-                    .get(&url)
-                    .header("Authorization", format!("Bearer {}", self.api_token))
-                    .send()
-                    .await
-                    .context("Failed to fetch bookmarks from Raindrop.io")?;
-
-                let status = response.status();
-                if !status.is_success() {
-                    let error_text = response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| String::from("unknown error"));
-                    anyhow::bail!("Raindrop API returned error: {} - {}", status, error_text);
-                }
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .send()
+                .await
+                .context("Failed to fetch bookmarks from Raindrop.io")?;
 
-                let raindrop_response = response
-                    .json::<RaindropResponse>()
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response
+                    .text()
                     .await
-                    .context("Failed to parse Raindrop API response")?;
+                    .unwrap_or_else(|_| String::from("unknown error"));
+                anyhow::bail!("Raindrop API returned error: {} - {}", status, error_text);
+            }
 
-                if raindrop_response.items.is_empty() {
-                    break;
-                }
+            let raindrop_response = response
+                .json::<RaindropResponse>()
+                .await
+                .context("Failed to parse Raindrop API response")?;
+            let received = raindrop_response.items.len();
+            let count = raindrop_response.count;
 
-                // Deduplicate by bookmark ID
-                for bookmark in raindrop_response.items {
-                    if seen_ids.insert(bookmark.id) {
-                        all_bookmarks.push(bookmark);
-                    }
+            // Items can shift between pages while bookmarks are being added
+            for bookmark in raindrop_response.items {
+                if seen_ids.insert(bookmark.id) {
+                    all_bookmarks.push(bookmark);
                 }
+            }
 
-                page += 1;
-
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            page += 1;
+            if !has_more(page, received, count) {
+                break;
             }
+            if page >= MAX_PAGES {
+                tracing::warn!(
+                    "Stopped after {} pages of Raindrop results ({} of {} bookmarks)",
+                    MAX_PAGES,
+                    all_bookmarks.len(),
+                    count
+                );
+                break;
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         }
 
         Ok(all_bookmarks)
     }
+
+    /// Record that `bookmarks` were used on air: add `tag` to each (keeping
+    /// its other tags) and/or move it to `collection`. Returns how many were
+    /// updated; bookmarks that already carry `tag` are skipped.
+    pub async fn mark_briefed(
+        &self,
+        bookmarks: &[Bookmark],
+        tag: Option<&str>,
+        collection: Option<i64>,
+    ) -> Result<usize> {
+        let mut updated = 0;
+        for bookmark in bookmarks {
+            let Some(body) = briefed_update(bookmark, tag, collection) else {
+                continue;
+            };
+            let url = format!("{}/raindrop/{}", self.api_url, bookmark.id);
+            tracing::debug!("PUT {}", url);
+
+            let response = self
+                .client
+                .put(&url)
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .json(&body)
+                .send()
+                .await
+                .context("Failed to update bookmark on Raindrop.io")?;
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("unknown error"));
+                anyhow::bail!(
+                    "Raindrop API returned error updating {}: {} - {}",
+                    bookmark.link,
+                    status,
+                    error_text
+                );
+            }
+            updated += 1;
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+        Ok(updated)
+    }
+}
+
+/// The update body for marking `bookmark` briefed, or `None` if there's
+/// nothing to change.
+fn briefed_update(
+    bookmark: &Bookmark,
+    tag: Option<&str>,
+    collection: Option<i64>,
+) -> Option<serde_json::Value> {
+    let mut body = serde_json::Map::new();
+    if let Some(tag) = tag {
+        if !bookmark.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            let mut tags = bookmark.tags.clone();
+            tags.push(tag.to_string());
+            body.insert("tags".to_string(), serde_json::json!(tags));
+        }
+    }
+    // Moving is only done together with a new tag when both are set, so a
+    // rerun doesn't move bookmarks back out of wherever they were filed.
+    if let Some(id) = collection {
+        if tag.is_none() || body.contains_key("tags") {
+            body.insert("collection".to_string(), serde_json::json!({ "$id": id }));
+        }
+    }
+    (!body.is_empty()).then_some(serde_json::Value::Object(body))
+}
+
+/// Raindrop search for a tag and a creation date. The `#` operator matches
+/// tags regardless of case; quoting keeps multi-word tags together.
+fn search_query(tag: &str, since: NaiveDate) -> String {
+    let tag = tag.trim_start_matches('#');
+    let tag = if tag.contains(' ') {
+        format!("#\"{}\"", tag)
+    } else {
+        format!("#{}", tag)
+    };
+    format!("{} created:>{}", tag, since.format("%Y-%m-%d"))
+}
+
+/// Whether another page is needed after `pages` pages, the last of which
+/// had `received` items, out of `count` in total.
+fn has_more(pages: usize, received: usize, count: usize) -> bool {
+    received == PER_PAGE && pages * PER_PAGE < count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_query_uses_tag_operator() {
+        let since = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        assert_eq!(search_query("TWiT", since), "#TWiT created:>2026-02-01");
+        assert_eq!(
+            search_query("#mac break", since),
+            "#\"mac break\" created:>2026-02-01"
+        );
+    }
+
+    #[test]
+    fn test_has_more_follows_count() {
+        assert!(has_more(1, 50, 120));
+        assert!(has_more(2, 50, 120));
+        assert!(!has_more(3, 20, 120));
+        // Exactly one full page
+        assert!(!has_more(1, 50, 50));
+        // A short page ends paging even if `count` says otherwise
+        assert!(!has_more(1, 10, 500));
+        assert!(!has_more(1, 0, 0));
+    }
+
+    #[test]
+    fn test_briefed_update_appends_tag_once() {
+        let bookmark = Bookmark {
+            id: 1,
+            title: "Story".to_string(),
+            link: "https://example.com".to_string(),
+            excerpt: None,
+            note: None,
+            tags: vec!["#twit".to_string()],
+            created: "2026-02-01T00:00:00Z".to_string(),
+        };
+        let body = briefed_update(&bookmark, Some("briefed-twit-2026-02-01"), Some(9)).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "tags": ["#twit", "briefed-twit-2026-02-01"],
+                "collection": { "$id": 9 },
+            })
+        );
+
+        let mut tagged = bookmark.clone();
+        tagged.tags.push("briefed-twit-2026-02-01".to_string());
+        assert!(briefed_update(&tagged, Some("briefed-twit-2026-02-01"), Some(9)).is_none());
+        assert!(briefed_update(&bookmark, None, Some(9)).is_some());
+
+        let settings: RaindropSettings =
+            toml::from_str("briefed_tag = \"briefed-{show}-{date}\"").unwrap();
+        assert_eq!(
+            settings
+                .briefed_tag_for("mbw", NaiveDate::from_ymd_opt(2026, 2, 3).unwrap())
+                .as_deref(),
+            Some("briefed-mbw-2026-02-03")
+        );
+    }
+
+    #[test]
+    fn test_collection_for_show() {
+        let settings: RaindropSettings =
+            toml::from_str("collection = 7\ncollections = { mbw = 42 }").unwrap();
+        assert_eq!(settings.collection_for("mbw"), 42);
+        assert_eq!(settings.collection_for("twit"), 7);
+        assert_eq!(
+            RaindropSettings::default().collection_for("twit"),
+            ALL_COLLECTIONS
+        );
+    }
 }
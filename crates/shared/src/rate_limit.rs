@@ -0,0 +1,287 @@
+//! Rate limiting shared by every LLM request in the process.
+//!
+//! The summarizer and clusterer send their requests through one
+//! [`RateLimiter`], which decides how many may be in flight and when to hold
+//! off. It learns from each response:
+//! - a 429 (or 529 overloaded) halves the concurrency and pauses every caller
+//!   for the `retry-after` the server asked for
+//! - Anthropic's `anthropic-ratelimit-*` headers pause callers until the
+//!   reset time when the minute's token or request budget is nearly spent
+//! - a run of successes raises the concurrency again, one step at a time
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Requests in flight when starting out, and the most ever allowed.
+const INITIAL_CONCURRENCY: usize = 2;
+pub const MAX_CONCURRENCY: usize = 4;
+
+/// Pause after a 429 that doesn't say how long to wait.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(15);
+
+/// Hold off when fewer tokens than this are left in the current minute,
+/// about what one summary request uses.
+const LOW_TOKENS: u64 = 4_000;
+
+/// What a response said about the rate limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Signals {
+    pub retry_after: Option<Duration>,
+    pub tokens_remaining: Option<u64>,
+    pub tokens_reset: Option<Duration>,
+    pub requests_remaining: Option<u64>,
+    pub requests_reset: Option<Duration>,
+}
+
+impl Signals {
+    /// Read the `retry-after` and `anthropic-ratelimit-*` headers. Reset
+    /// times are RFC 3339 and are turned into durations from `now`.
+    pub fn from_headers(headers: &HeaderMap, now: DateTime<Utc>) -> Self {
+        let text = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let number = |name: &str| text(name).and_then(|v| v.trim().parse::<u64>().ok());
+        let reset = |name: &str| {
+            let at = DateTime::parse_from_rfc3339(text(name)?.trim()).ok()?;
+            (at.with_timezone(&Utc) - now).to_std().ok()
+        };
+        Self {
+            retry_after: number("retry-after").map(Duration::from_secs),
+            tokens_remaining: number("anthropic-ratelimit-tokens-remaining"),
+            tokens_reset: reset("anthropic-ratelimit-tokens-reset"),
+            requests_remaining: number("anthropic-ratelimit-requests-remaining"),
+            requests_reset: reset("anthropic-ratelimit-requests-reset"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    limit: usize,
+    in_flight: usize,
+    paused_until: Option<Instant>,
+    /// Successes since the limit last changed
+    successes: usize,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            limit: INITIAL_CONCURRENCY,
+            in_flight: 0,
+            paused_until: None,
+            successes: 0,
+        }
+    }
+
+    fn pause_until(&mut self, until: Instant) {
+        if self.paused_until.is_none_or(|current| until > current) {
+            self.paused_until = Some(until);
+        }
+    }
+
+    fn record(&mut self, status: u16, signals: &Signals, now: Instant) {
+        if status == 429 || status == 529 {
+            self.limit = (self.limit / 2).max(1);
+            self.successes = 0;
+            self.pause_until(now + signals.retry_after.unwrap_or(DEFAULT_RETRY_AFTER));
+            return;
+        }
+
+        if let (Some(remaining), Some(reset)) = (signals.tokens_remaining, signals.tokens_reset) {
+            if remaining < LOW_TOKENS {
+                self.pause_until(now + reset);
+            }
+        }
+        if let (Some(0), Some(reset)) = (signals.requests_remaining, signals.requests_reset) {
+            self.pause_until(now + reset);
+        }
+
+        if (200..300).contains(&status) {
+            self.successes += 1;
+            if self.successes >= self.limit && self.limit < MAX_CONCURRENCY {
+                self.limit += 1;
+                self.successes = 0;
+            }
+        }
+    }
+
+    fn remaining_pause(&self, now: Instant) -> Duration {
+        self.paused_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(now))
+    }
+}
+
+pub struct RateLimiter {
+    state: Mutex<State>,
+    released: Notify,
+}
+
+/// Held while a request is in flight; dropping it lets the next one start.
+pub struct Permit {
+    limiter: Arc<RateLimiter>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.limiter.lock().in_flight -= 1;
+        self.limiter.released.notify_waiters();
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State::new()),
+            released: Notify::new(),
+        })
+    }
+
+    /// The limiter every [`crate::llm::LlmClient`] uses by default.
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+        SHARED.get_or_init(RateLimiter::new).clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for any pause to end and for a free slot.
+    pub async fn acquire(self: &Arc<Self>) -> Permit {
+        loop {
+            // Registered before checking, so a release in between isn't missed
+            let released = self.released.notified();
+            let pause = {
+                let mut state = self.lock();
+                let pause = state.remaining_pause(Instant::now());
+                if pause.is_zero() && state.in_flight < state.limit {
+                    state.in_flight += 1;
+                    return Permit {
+                        limiter: self.clone(),
+                    };
+                }
+                pause
+            };
+            if pause.is_zero() {
+                released.await;
+            } else {
+                tokio::time::sleep(pause).await;
+            }
+        }
+    }
+
+    /// Learn from a response's status and headers.
+    pub fn record(&self, status: u16, headers: &HeaderMap) {
+        let signals = Signals::from_headers(headers, Utc::now());
+        let mut state = self.lock();
+        state.record(status, &signals, Instant::now());
+        tracing::debug!(
+            status,
+            limit = state.limit,
+            pause_secs = state.remaining_pause(Instant::now()).as_secs(),
+            "rate limiter updated"
+        );
+        drop(state);
+        // The limit may have grown
+        self.released.notify_waiters();
+    }
+
+    /// How long callers are currently being held off, e.g. after a 429.
+    pub fn remaining_pause(&self) -> Duration {
+        self.lock().remaining_pause(Instant::now())
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.lock().limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_signals_from_headers() {
+        let now = DateTime::parse_from_rfc3339("2026-02-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("20"));
+        headers.insert(
+            "anthropic-ratelimit-tokens-remaining",
+            HeaderValue::from_static("1200"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-tokens-reset",
+            HeaderValue::from_static("2026-02-01T12:00:30Z"),
+        );
+
+        let signals = Signals::from_headers(&headers, now);
+        assert_eq!(signals.retry_after, Some(Duration::from_secs(20)));
+        assert_eq!(signals.tokens_remaining, Some(1200));
+        assert_eq!(signals.tokens_reset, Some(Duration::from_secs(30)));
+        assert_eq!(signals.requests_remaining, None);
+    }
+
+    #[test]
+    fn test_state_backs_off_and_recovers() {
+        let now = Instant::now();
+        let mut state = State::new();
+
+        state.record(
+            429,
+            &Signals {
+                retry_after: Some(Duration::from_secs(20)),
+                ..Default::default()
+            },
+            now,
+        );
+        assert_eq!(state.limit, 1);
+        assert_eq!(state.remaining_pause(now), Duration::from_secs(20));
+        // A shorter later pause doesn't cut the first one short
+        state.record(429, &Signals::default(), now);
+        assert_eq!(state.remaining_pause(now), Duration::from_secs(20));
+
+        for _ in 0..8 {
+            state.record(200, &Signals::default(), now);
+        }
+        assert_eq!(state.limit, MAX_CONCURRENCY);
+
+        let mut later = State::new();
+        later.record(
+            200,
+            &Signals {
+                tokens_remaining: Some(100),
+                tokens_reset: Some(Duration::from_secs(40)),
+                ..Default::default()
+            },
+            now,
+        );
+        assert_eq!(later.remaining_pause(now), Duration::from_secs(40));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_limits_in_flight() {
+        let limiter = RateLimiter::new();
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move {
+                let _third = limiter.acquire().await;
+            }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
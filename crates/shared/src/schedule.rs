@@ -0,0 +1,361 @@
+//! When each show records, from its `[[shows]]` table: the date of the next
+//! episode, which names the files and heads the briefing, and when the last
+//! one ended, which starts collect's bookmark window.
+//!
+//! A show records every week on its `day` and is over at its `ends` hour,
+//! in its `timezone` (this machine's if unset). The briefing date moves on
+//! to the next week at its `rollover` hour, `ends` unless it says otherwise. Weeks that differ are listed
+//! as exceptions, and `[schedule] holidays` skips every show's episode on
+//! those dates:
+//!
+//! ```toml
+//! [schedule]
+//! holidays = ["2026-12-25"]
+//!
+//! [[shows]]
+//! slug = "weekly"
+//! name = "The Weekly Tech Show"
+//! day = "sunday"
+//! ends = 18
+//! timezone = "America/Los_Angeles"
+//! exceptions = [
+//!   { date = "2026-11-29", moved_to = "2026-11-28" },
+//!   { date = "2027-01-03" },
+//! ]
+//! ```
+//!
+//! A skipped week's bookmarks roll into the next episode's briefing.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
+
+use crate::shows::ShowSettings;
+use crate::timezone::{Tz, ZoneExt};
+
+/// How far either side of today to look for episodes, so a long run of
+/// skipped weeks still finds one.
+const SEARCH_WEEKS: i64 = 53;
+
+/// The `[schedule]` section of `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScheduleSettings {
+    /// Dates no show records on
+    #[serde(deserialize_with = "dates")]
+    pub holidays: Vec<NaiveDate>,
+}
+
+/// One `exceptions` entry of a `[[shows]]` table.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Exception {
+    /// The regular episode's date
+    #[serde(deserialize_with = "date")]
+    pub date: NaiveDate,
+    /// When it records instead; without this the episode is skipped
+    #[serde(default, deserialize_with = "optional_date")]
+    pub moved_to: Option<NaiveDate>,
+}
+
+/// A show's recording schedule. Its times are in the show's zone.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    day: Weekday,
+    ends: u32,
+    rollover: u32,
+    zone: Tz,
+    /// Regular date to the date it records, `None` if skipped
+    exceptions: HashMap<NaiveDate, Option<NaiveDate>>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl Schedule {
+    /// Fails when the show's `timezone` can't be found.
+    pub fn new(show: &ShowSettings, settings: &ScheduleSettings) -> Result<Self> {
+        let zone = match &show.timezone {
+            Some(name) => crate::timezone::named(name)
+                .with_context(|| format!("In the [[shows]] table '{}'", show.slug))?,
+            None => crate::timezone::local(),
+        };
+        Ok(Self {
+            day: show.day,
+            ends: show.ends,
+            rollover: show.rollover.unwrap_or(show.ends),
+            zone,
+            exceptions: show
+                .exceptions
+                .iter()
+                .map(|e| (e.date, e.moved_to))
+                .collect(),
+            holidays: settings.holidays.iter().copied().collect(),
+        })
+    }
+
+    pub fn zone(&self) -> &Tz {
+        &self.zone
+    }
+
+    /// The time in the show's zone now.
+    pub fn now(&self) -> DateTime<Tz> {
+        self.zone.now()
+    }
+
+    /// When the most recent past episode ended, at the show's end hour.
+    pub fn previous_end(&self, now: DateTime<Tz>) -> DateTime<Tz> {
+        let today = now.date_naive();
+        self.episodes(today)
+            .into_iter()
+            .map(|date| self.hour_of(date, self.ends))
+            .rfind(|end| *end <= now)
+            .unwrap_or_else(|| self.hour_of(today - Duration::weeks(1), self.ends))
+    }
+
+    /// The date of the next episode, at midnight: today on show day until
+    /// the rollover hour, then the following one.
+    pub fn next_date(&self, now: DateTime<Tz>) -> DateTime<Tz> {
+        let date = self
+            .episodes(now.date_naive())
+            .into_iter()
+            .find(|date| self.hour_of(*date, self.rollover) > now)
+            .unwrap_or(now.date_naive());
+        self.zone.at(date.and_time(NaiveTime::MIN))
+    }
+
+    fn hour_of(&self, date: NaiveDate, hour: u32) -> DateTime<Tz> {
+        self.zone
+            .at(date.and_hms_opt(hour, 0, 0).expect("valid hour of the day"))
+    }
+
+    /// The recording dates within [`SEARCH_WEEKS`] of `around`, in order.
+    fn episodes(&self, around: NaiveDate) -> Vec<NaiveDate> {
+        let start = around - Duration::weeks(SEARCH_WEEKS);
+        let offset =
+            (self.day.num_days_from_monday() + 7 - start.weekday().num_days_from_monday()) % 7;
+        let first = start + Duration::days(offset.into());
+        let mut dates: Vec<NaiveDate> = (0..=2 * SEARCH_WEEKS)
+            .map(|week| first + Duration::weeks(week))
+            .filter_map(|regular| self.recorded(regular))
+            .collect();
+        // A moved episode can land past its neighbors
+        dates.sort_unstable();
+        dates
+    }
+
+    /// The date the episode due on `regular` records, if it does.
+    fn recorded(&self, regular: NaiveDate) -> Option<NaiveDate> {
+        let date = match self.exceptions.get(&regular) {
+            Some(moved) => (*moved)?,
+            None => regular,
+        };
+        (!self.holidays.contains(&date)).then_some(date)
+    }
+}
+
+fn parse_date<E: serde::de::Error>(date: &str) -> Result<NaiveDate, E> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| E::custom(format!("invalid date: {} (use YYYY-MM-DD)", date)))
+}
+
+fn date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    parse_date(&String::deserialize(deserializer)?)
+}
+
+fn optional_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    date(deserializer).map(Some)
+}
+
+fn dates<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<NaiveDate>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|d| parse_date(d))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+
+    fn show(day: Weekday, ends: u32) -> ShowSettings {
+        ShowSettings {
+            slug: "show".to_string(),
+            name: "The Show".to_string(),
+            tag: None,
+            day,
+            ends,
+            rollover: None,
+            locale: None,
+            topic_order: None,
+            timezone: None,
+            exceptions: Vec::new(),
+        }
+    }
+
+    fn schedule(day: Weekday, ends: u32) -> Schedule {
+        Schedule::new(&show(day, ends), &ScheduleSettings::default()).unwrap()
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Tz> {
+        crate::timezone::local().at(NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap())
+    }
+
+    #[test]
+    fn test_previous_end() {
+        let sunday = schedule(Weekday::Sun, 17);
+        // After the end on show day: today
+        assert_eq!(
+            sunday.previous_end(at(2026, 3, 22, 18)),
+            at(2026, 3, 22, 17)
+        );
+        // Before the end on show day: last week
+        assert_eq!(
+            sunday.previous_end(at(2026, 3, 22, 15)),
+            at(2026, 3, 15, 17)
+        );
+        // Monday: yesterday
+        assert_eq!(
+            sunday.previous_end(at(2026, 3, 23, 10)),
+            at(2026, 3, 22, 17)
+        );
+        // Saturday: six days back
+        assert_eq!(
+            sunday.previous_end(at(2026, 3, 21, 14)),
+            at(2026, 3, 15, 17)
+        );
+
+        let tuesday = schedule(Weekday::Tue, 14);
+        assert_eq!(
+            tuesday.previous_end(at(2026, 3, 24, 15)),
+            at(2026, 3, 24, 14)
+        );
+        assert_eq!(
+            tuesday.previous_end(at(2026, 3, 24, 8)),
+            at(2026, 3, 17, 14)
+        );
+
+        let wednesday = schedule(Weekday::Wed, 17);
+        // At exactly the end hour the episode is over
+        assert_eq!(
+            wednesday.previous_end(at(2026, 3, 25, 17)),
+            at(2026, 3, 25, 17)
+        );
+        assert_eq!(
+            wednesday.previous_end(at(2026, 3, 25, 10)),
+            at(2026, 3, 18, 17)
+        );
+    }
+
+    #[test]
+    fn test_next_date() {
+        let date = |schedule: &Schedule, from| schedule.next_date(from).date_naive().to_string();
+
+        let sunday = schedule(Weekday::Sun, 18);
+        assert_eq!(date(&sunday, at(2026, 2, 1, 17)), "2026-02-01");
+        assert_eq!(date(&sunday, at(2026, 2, 1, 19)), "2026-02-08");
+
+        let tuesday = schedule(Weekday::Tue, 14);
+        // Sunday evening: this Tuesday
+        assert_eq!(date(&tuesday, at(2026, 2, 1, 21)), "2026-02-03");
+        assert_eq!(date(&tuesday, at(2026, 2, 3, 13)), "2026-02-03");
+        assert_eq!(date(&tuesday, at(2026, 2, 3, 15)), "2026-02-10");
+
+        let wednesday = schedule(Weekday::Wed, 18);
+        assert_eq!(date(&wednesday, at(2026, 2, 1, 21)), "2026-02-04");
+        assert_eq!(date(&wednesday, at(2026, 2, 4, 19)), "2026-02-11");
+    }
+
+    #[test]
+    fn test_rollover_after_the_end() {
+        // Over at 5pm, but dated for today until 6pm
+        let mut sunday = show(Weekday::Sun, 17);
+        sunday.rollover = Some(18);
+        let sunday = Schedule::new(&sunday, &ScheduleSettings::default()).unwrap();
+        let date = |from| sunday.next_date(from).date_naive().to_string();
+        assert_eq!(date(at(2026, 2, 1, 17)), "2026-02-01");
+        assert_eq!(date(at(2026, 2, 1, 18)), "2026-02-08");
+        assert_eq!(sunday.previous_end(at(2026, 2, 1, 17)), at(2026, 2, 1, 17));
+    }
+
+    #[test]
+    fn test_exceptions_and_holidays() {
+        let settings = Settings::from_toml(
+            r#"
+            [schedule]
+            holidays = ["2026-12-27"]
+
+            [[shows]]
+            slug = "weekly"
+            name = "The Weekly Show"
+            day = "sunday"
+            ends = 18
+            exceptions = [
+              { date = "2026-11-29", moved_to = "2026-11-28" },
+              { date = "2027-01-03" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let weekly = Schedule::new(&settings.shows[0], &settings.schedule).unwrap();
+        let next = |now| weekly.next_date(now).date_naive().to_string();
+
+        // Moved a day early: Saturday's the show, and the window before
+        // the following Sunday starts then
+        assert_eq!(next(at(2026, 11, 25, 12)), "2026-11-28");
+        assert_eq!(next(at(2026, 11, 28, 19)), "2026-12-06");
+        assert_eq!(
+            weekly.previous_end(at(2026, 12, 1, 12)),
+            at(2026, 11, 28, 18)
+        );
+        // The holiday and the skipped week: three weeks' bookmarks for
+        // 10 January
+        assert_eq!(next(at(2026, 12, 21, 12)), "2027-01-10");
+        assert_eq!(
+            weekly.previous_end(at(2027, 1, 9, 12)),
+            at(2026, 12, 20, 18)
+        );
+
+        assert!(Settings::from_toml(
+            "[[shows]]\nslug = \"x\"\nname = \"X\"\nday = \"sun\"\nends = 18\n\
+             exceptions = [{ date = \"2026-11-29\", moved = \"2026-11-28\" }]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_across_clock_change() {
+        let mut weekly = schedule(Weekday::Sun, 18);
+        weekly.zone = crate::timezone::named("America/Los_Angeles").unwrap();
+        let at = |day, hour| {
+            weekly.zone.at(NaiveDate::from_ymd_opt(2026, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap())
+        };
+        // The Sunday the clocks go forward, 6pm is still 6pm
+        let end = weekly.previous_end(at(8, 19));
+        assert_eq!(end.naive_local(), at(8, 18).naive_local());
+        assert_eq!(end.offset().to_string(), "PDT");
+        // and the week before it was an hour shorter
+        let before = weekly.previous_end(at(8, 17));
+        assert_eq!((end - before).num_hours(), 7 * 24 - 1);
+        assert_eq!(
+            before.format("%-d %B %-l%P %Z").to_string(),
+            "1 March 6pm PST"
+        );
+    }
+
+    #[test]
+    fn test_unknown_timezone() {
+        let mut show = show(Weekday::Sun, 18);
+        show.timezone = Some("Mars/Olympus_Mons".to_string());
+        let err = Schedule::new(&show, &ScheduleSettings::default()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Unknown time zone 'Mars/Olympus_Mons'"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Stories that need careful framing on air.
+//!
+//! A story about layoffs, a lawsuit, a security breach or a death reads
+//! differently from the gadget news around it, and a host who finds out
+//! mid-sentence has to change tone on the fly. Such stories get a discreet
+//! ◆ marker beside their headline in the briefing, with the reasons on
+//! hover, and a `SENSITIVE:` line in the org draft. The check is a word
+//! list, matched as whole words ignoring case against the title and
+//! summary, so it costs nothing and runs again on every briefing.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::clustering::Story;
+use crate::disclosures::{mentions, story_text};
+
+/// The built-in reasons and the words that give them away.
+const BUILTIN: &[(&str, &[&str])] = &[
+    (
+        "layoffs",
+        &[
+            "layoff",
+            "layoffs",
+            "laid off",
+            "lays off",
+            "job cuts",
+            "cuts jobs",
+            "cutting jobs",
+            "redundancies",
+            "furloughs",
+        ],
+    ),
+    (
+        "lawsuit",
+        &[
+            "lawsuit",
+            "lawsuits",
+            "sued",
+            "sues",
+            "suing",
+            "class action",
+            "indicted",
+            "indictment",
+        ],
+    ),
+    (
+        "security breach",
+        &[
+            "breach",
+            "breaches",
+            "data breach",
+            "hacked",
+            "ransomware",
+            "cyberattack",
+            "stolen data",
+            "leaked data",
+        ],
+    ),
+    (
+        "death",
+        &[
+            "died",
+            "dies",
+            "death",
+            "deaths",
+            "killed",
+            "passed away",
+            "obituary",
+            "fatal",
+        ],
+    ),
+];
+
+/// The `[briefing.sensitive]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SensitiveSettings {
+    /// Mark stories that need careful framing
+    pub enabled: bool,
+    /// More words by reason, e.g. `layoffs = ["restructuring"]`; a new
+    /// reason adds to the built-in ones
+    pub words: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for SensitiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            words: BTreeMap::new(),
+        }
+    }
+}
+
+impl SensitiveSettings {
+    /// Why `story` needs careful framing, the built-in reasons first, or
+    /// nothing if it doesn't.
+    pub fn reasons(&self, story: &Story) -> Vec<String> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let text = story_text(story).to_lowercase();
+        let extra = |reason: &str| self.words.get(reason).into_iter().flatten();
+        let builtin = BUILTIN.iter().filter_map(|(reason, words)| {
+            let found = words.iter().any(|w| mentions(&text, w))
+                || extra(reason).any(|w| mentions(&text, w));
+            found.then(|| reason.to_string())
+        });
+        let added = self
+            .words
+            .iter()
+            .filter(|(reason, _)| !BUILTIN.iter().any(|(r, _)| r == reason))
+            .filter(|(_, words)| words.iter().any(|w| mentions(&text, w)))
+            .map(|(reason, _)| reason.clone());
+        builtin.chain(added).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    #[test]
+    fn test_reasons() {
+        let settings = SensitiveSettings::default();
+        assert_eq!(
+            settings.reasons(
+                &story("Intel lays off 15,000")
+                    .with_lede("Shareholders sued the company last week.", "It matters.")
+                    .build()
+            ),
+            ["layoffs", "lawsuit"]
+        );
+        // Whole words only
+        assert!(settings
+            .reasons(
+                &story("Fatalities app wins an award")
+                    .with_lede("A new SDK ships.", "It matters.")
+                    .build()
+            )
+            .is_empty());
+
+        let custom = SensitiveSettings {
+            words: BTreeMap::from([
+                ("layoffs".to_string(), vec!["restructuring".to_string()]),
+                ("politics".to_string(), vec!["election".to_string()]),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            custom.reasons(
+                &story("Restructuring at Meta")
+                    .with_lede("Before the election.", "It matters.")
+                    .build()
+            ),
+            ["layoffs", "politics"]
+        );
+
+        let off = SensitiveSettings {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(off
+            .reasons(&story("Founder dies").with_lede("", "It matters.").build())
+            .is_empty());
+    }
+}
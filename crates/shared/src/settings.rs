@@ -0,0 +1,214 @@
+//! User settings from `~/.config/podcast-briefing/config.toml`.
+//!
+//! Every section is optional and defaults to "off" (or, for `[budget]` and
+//! `[fetch]`, to the built-in limits), so an empty or missing file keeps
+//! the tools' built-in behavior. Without `[[shows]]`, [`Settings::load`]
+//! falls back to the shows that were built in before there was a
+//! `[[shows]]` (see [`crate::shows::legacy`]).
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::briefing::BriefingSettings;
+use crate::budget::BudgetSettings;
+use crate::clustering::ClusterSettings;
+use crate::filter::Filters;
+use crate::hooks::Hooks;
+use crate::logging::LogSettings;
+use crate::notify::NotifySettings;
+use crate::output::OutputSettings;
+use crate::politeness::FetchSettings;
+use crate::prompts::PromptSettings;
+use crate::raindrop::RaindropSettings;
+use crate::schedule::ScheduleSettings;
+use crate::shows::ShowSettings;
+use crate::upload::UploadSettings;
+use crate::usage::UsageSettings;
+
+pub const SETTINGS_FILE: &str = "config.toml";
+
+/// Set by `briefing --config`.
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub hooks: Hooks,
+    pub filter: Filters,
+    pub usage: UsageSettings,
+    pub prompts: PromptSettings,
+    pub log: LogSettings,
+    pub briefing: BriefingSettings,
+    pub raindrop: RaindropSettings,
+    pub budget: BudgetSettings,
+    pub fetch: FetchSettings,
+    pub clustering: ClusterSettings,
+    /// `[[shows]]` tables, in menu order
+    pub shows: Vec<ShowSettings>,
+    pub schedule: ScheduleSettings,
+    pub upload: UploadSettings,
+    pub notify: NotifySettings,
+    pub output: OutputSettings,
+}
+
+impl Settings {
+    /// Load settings from the config dir. A file that fails to parse is an
+    /// error: running on the defaults would drop the shows, the upload
+    /// settings and any `pre_upload` hooks that are meant to stop a bad
+    /// briefing going out.
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        let settings =
+            Self::from_file(&path).with_context(|| format!("Failed to load {}", path.display()))?;
+        Ok(settings.with_legacy_shows())
+    }
+
+    /// The [legacy shows](crate::shows::legacy) if there are no
+    /// `[[shows]]` tables.
+    pub fn with_legacy_shows(mut self) -> Self {
+        if self.shows.is_empty() {
+            tracing::warn!(
+                "No [[shows]] in {}; using the old built-in twit, mbw and im shows",
+                Self::path().display()
+            );
+            self.shows = crate::shows::legacy();
+        }
+        self
+    }
+
+    /// The config.toml in use: the one given to [`use_file`](Self::use_file),
+    /// or the one in the config dir.
+    pub fn path() -> PathBuf {
+        PATH_OVERRIDE
+            .get()
+            .cloned()
+            .unwrap_or_else(|| crate::config::config_dir().join(SETTINGS_FILE))
+    }
+
+    /// Read settings from `path` instead of the config dir from now on. Only
+    /// the first call counts.
+    pub fn use_file(path: PathBuf) {
+        let _ = PATH_OVERRIDE.set(path);
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Invalid settings")
+    }
+
+    /// Apply the overrides in the `[[shows]]` table for `slug`, if any.
+    pub fn apply_show(&mut self, slug: &str) {
+        let Some(show) = self.shows.iter().find(|s| s.slug == slug) else {
+            return;
+        };
+        if let Some(locale) = show.locale {
+            self.briefing.locale = locale;
+        }
+        if let Some(order) = &show.topic_order {
+            self.briefing.topic_order = order.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::Locale;
+
+    #[test]
+    fn test_empty_settings_use_defaults() {
+        let settings = Settings::from_toml("").unwrap();
+        assert!(settings.hooks.post_collect.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_shows_without_shows_tables() {
+        let settings = Settings::from_toml("").unwrap().with_legacy_shows();
+        let slugs: Vec<&str> = settings.shows.iter().map(|s| s.slug.as_str()).collect();
+        assert_eq!(slugs, ["twit", "mbw", "im"]);
+
+        let settings = Settings::from_toml(
+            "[[shows]]\nslug = \"weekly\"\nname = \"Weekly\"\nday = \"sun\"\nends = 18",
+        )
+        .unwrap()
+        .with_legacy_shows();
+        assert_eq!(settings.shows.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SETTINGS_FILE);
+        std::fs::write(&path, "[[hooks.pre_upload]\ncommand = \"check\"\n").unwrap();
+        assert!(Settings::from_file(&path).is_err());
+        assert!(Settings::from_file(&dir.path().join("missing.toml")).is_ok());
+    }
+
+    #[test]
+    fn test_parse_hooks_section() {
+        let settings = Settings::from_toml(
+            r#"
+            [hooks]
+            post_upload = ["~/bin/post-to-cms", "~/bin/ping-studio"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.hooks.post_upload.len(), 2);
+        assert!(settings.hooks.pre_upload.is_empty());
+    }
+
+    #[test]
+    fn test_apply_show_locale() {
+        let mut settings = Settings::from_toml(
+            r#"
+            [briefing]
+            locale = "fr"
+
+            [[shows]]
+            slug = "wochenschau"
+            name = "Die Wochenschau"
+            day = "sunday"
+            ends = 18
+            locale = "de-AT"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.briefing.locale, Locale::Fr);
+        settings.apply_show("other");
+        assert_eq!(settings.briefing.locale, Locale::Fr);
+        settings.apply_show("wochenschau");
+        assert_eq!(settings.briefing.locale, Locale::De);
+    }
+
+    #[test]
+    fn test_apply_show_topic_order() {
+        let mut settings = Settings::from_toml(
+            r#"
+            [[shows]]
+            slug = "mbw"
+            name = "MacBreak Weekly"
+            day = "tuesday"
+            ends = 14
+
+            [shows.topic_order]
+            first = ["Apple"]
+            last = ["In Other News"]
+            "#,
+        )
+        .unwrap();
+        assert!(settings.briefing.topic_order.is_default());
+        settings.apply_show("mbw");
+        assert_eq!(settings.briefing.topic_order.first, ["Apple"]);
+        assert_eq!(settings.briefing.topic_order.last, ["In Other News"]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Short links for hosts who read story URLs on air.
+//!
+//! Links are made through a self-hosted Shlink or YOURLS instance, or any
+//! service with a "GET this URL, get the short link back as text" API.
+//! Short links are cached in the data dir, so rerunning `prepare-briefing`
+//! reuses them instead of minting new ones.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Environment variable holding the shortener's API key.
+pub const API_KEY_ENV_VAR: &str = "SHORTENER_API_KEY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Service {
+    Shlink,
+    Yourls,
+    /// `url` is a template with `{url}` (and optionally `{key}`) replaced;
+    /// the response body is the short link
+    Custom,
+}
+
+/// The `[briefing.shortener]` section of `config.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShortenerSettings {
+    /// Off when unset
+    pub service: Option<Service>,
+    /// Base URL of the Shlink or YOURLS server, or the custom template
+    pub url: String,
+}
+
+impl ShortenerSettings {
+    pub fn enabled(&self) -> bool {
+        self.service.is_some()
+    }
+}
+
+fn cache_path() -> PathBuf {
+    crate::config::data_dir().join("short-links.json")
+}
+
+fn load_cache() -> HashMap<String, String> {
+    std::fs::read_to_string(cache_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &HashMap<String, String>) {
+    let path = cache_path();
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| {
+            std::fs::write(
+                &path,
+                serde_json::to_string_pretty(cache).unwrap_or_default(),
+            )
+        });
+    if let Err(e) = result {
+        tracing::warn!("Could not save {}: {}", path.display(), e);
+    }
+}
+
+/// Short links for `urls`, keyed by the original URL. URLs that couldn't be
+/// shortened are left out with a warning; the briefing shows the full link.
+pub async fn shorten_all(settings: &ShortenerSettings, urls: &[String]) -> HashMap<String, String> {
+    let Some(service) = settings.service else {
+        return HashMap::new();
+    };
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Skipping short links: {}", e);
+            return HashMap::new();
+        }
+    };
+    let key = std::env::var(API_KEY_ENV_VAR).unwrap_or_default();
+
+    let mut cache = load_cache();
+    let mut links = HashMap::new();
+    let mut created = 0;
+    for url in urls {
+        if let Some(short) = cache.get(url) {
+            links.insert(url.clone(), short.clone());
+            continue;
+        }
+        match shorten(&client, service, &settings.url, &key, url).await {
+            Ok(short) => {
+                cache.insert(url.clone(), short.clone());
+                links.insert(url.clone(), short);
+                created += 1;
+            }
+            Err(e) => tracing::warn!("Could not shorten {}: {:#}", url, e),
+        }
+    }
+    if created > 0 {
+        save_cache(&cache);
+    }
+    links
+}
+
+async fn shorten(
+    client: &reqwest::Client,
+    service: Service,
+    base: &str,
+    key: &str,
+    url: &str,
+) -> Result<String> {
+    let base = base.trim_end_matches('/');
+    let short = match service {
+        Service::Shlink => {
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct Created {
+                short_url: String,
+            }
+            client
+                .post(format!("{}/rest/v3/short-urls", base))
+                .header("X-Api-Key", key)
+                .json(&serde_json::json!({ "longUrl": url, "findIfExists": true }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Created>()
+                .await
+                .context("Unexpected Shlink response")?
+                .short_url
+        }
+        Service::Yourls => {
+            #[derive(Deserialize)]
+            struct Created {
+                shorturl: Option<String>,
+                message: Option<String>,
+            }
+            // YOURLS answers an already-shortened URL with an error status
+            // but still includes its short link
+            let created = client
+                .get(format!(
+                    "{}/yourls-api.php?signature={}&action=shorturl&format=json&url={}",
+                    base,
+                    urlencoding::encode(key),
+                    urlencoding::encode(url)
+                ))
+                .send()
+                .await?
+                .json::<Created>()
+                .await
+                .context("Unexpected YOURLS response")?;
+            match created.shorturl {
+                Some(short) => short,
+                None => bail!(created
+                    .message
+                    .unwrap_or_else(|| "no short link".to_string())),
+            }
+        }
+        Service::Custom => {
+            let request = custom_request(base, key, url);
+            client
+                .get(&request)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?
+                .trim()
+                .to_string()
+        }
+    };
+    if !short.starts_with("http") {
+        bail!("shortener returned `{}`", short);
+    }
+    Ok(short)
+}
+
+fn custom_request(template: &str, key: &str, url: &str) -> String {
+    template
+        .replace("{url}", &urlencoding::encode(url))
+        .replace("{key}", &urlencoding::encode(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_and_custom_template() {
+        let settings: ShortenerSettings = toml::from_str(
+            "service = \"custom\"\nurl = \"https://is.gd/create.php?format=simple&url={url}\"",
+        )
+        .unwrap();
+        assert_eq!(settings.service, Some(Service::Custom));
+        assert!(!ShortenerSettings::default().enabled());
+
+        assert_eq!(
+            custom_request(&settings.url, "", "https://example.com/a?b=c"),
+            "https://is.gd/create.php?format=simple&url=https%3A%2F%2Fexample.com%2Fa%3Fb%3Dc"
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! Shows, from the `[[shows]]` tables in config.toml.
+//!
+//! Each table gives its slug, name, Raindrop tag and the weekday and hour
+//! the episode is over; from that [`Schedule`](crate::schedule::Schedule)
+//! works out which bookmarks are new since the last episode and the date
+//! the briefing is for. A config.toml from before `[[shows]]` gets the
+//! three shows that used to be built in, from [`legacy`].
+//!
+//! ```toml
+//! [[shows]]
+//! slug = "weekly"
+//! name = "The Weekly Tech Show"
+//! day = "sunday"
+//! ends = 18
+//! timezone = "America/Los_Angeles"
+//! ```
+
+use anyhow::Result;
+use chrono::Weekday;
+use serde::{Deserialize, Deserializer};
+
+use crate::dates::Locale;
+use crate::models::ShowInfo;
+use crate::schedule::Exception;
+use crate::topic_order::TopicOrder;
+
+/// One `[[shows]]` table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShowSettings {
+    /// Used for `--show`, file names and per-show settings, e.g. `weekly`
+    pub slug: String,
+    /// Display name, e.g. "The Weekly Tech Show"
+    pub name: String,
+    /// Raindrop tag on the show's bookmarks; `#{slug}` if unset
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Weekday the show records, e.g. `"sunday"` or `"sun"`
+    #[serde(deserialize_with = "weekday")]
+    pub day: Weekday,
+    /// Hour (0-23) the episode is over: bookmarks from before it belong to
+    /// this episode
+    #[serde(deserialize_with = "hour")]
+    pub ends: u32,
+    /// Hour (0-23) on show day the briefing date moves on to next week's
+    /// episode; `ends` if unset
+    #[serde(default, deserialize_with = "optional_hour")]
+    pub rollover: Option<u32>,
+    /// Language for this show's dates, overriding `[briefing] locale`
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// Order of this show's topics, overriding `[briefing] topic_order`
+    #[serde(default)]
+    pub topic_order: Option<TopicOrder>,
+    /// Time zone the show records in, e.g. `"America/New_York"`; this
+    /// machine's if unset
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Weeks the show is skipped or moved
+    #[serde(default)]
+    pub exceptions: Vec<Exception>,
+}
+
+impl ShowSettings {
+    pub fn info(&self) -> ShowInfo {
+        let tag = self
+            .tag
+            .clone()
+            .unwrap_or_else(|| format!("#{}", self.slug));
+        ShowInfo::new(&self.name, &self.slug, tag)
+    }
+}
+
+/// The shows that were built in before `[[shows]]`, used when config.toml
+/// has no `[[shows]]` tables so an older install keeps running. Their
+/// bookmark windows start at 5pm (2pm for MacBreak Weekly), but the
+/// briefing date only moves on at 6pm, as it always did.
+pub fn legacy() -> Vec<ShowSettings> {
+    let show = |slug: &str, name: &str, day, ends, rollover| ShowSettings {
+        slug: slug.to_string(),
+        name: name.to_string(),
+        tag: None,
+        day,
+        ends,
+        rollover: Some(rollover),
+        locale: None,
+        topic_order: None,
+        timezone: None,
+        exceptions: Vec::new(),
+    };
+    vec![
+        show("twit", "This Week in Tech", Weekday::Sun, 17, 18),
+        show("mbw", "MacBreak Weekly", Weekday::Tue, 14, 14),
+        show("im", "Intelligent Machines", Weekday::Wed, 17, 18),
+    ]
+}
+
+/// All the shows, or an error if none are set up.
+pub fn configured(shows: &[ShowSettings]) -> Result<&[ShowSettings]> {
+    if shows.is_empty() {
+        anyhow::bail!(
+            "No shows are set up. Add a [[shows]] table for each show to {}",
+            crate::settings::Settings::path().display()
+        );
+    }
+    Ok(shows)
+}
+
+/// The show with `slug`, or an error naming the configured ones.
+pub fn find<'a>(shows: &'a [ShowSettings], slug: &str) -> Result<&'a ShowSettings> {
+    configured(shows)?
+        .iter()
+        .find(|s| s.slug == slug)
+        .ok_or_else(|| {
+            let slugs: Vec<String> = shows.iter().map(|s| format!("'{}'", s.slug)).collect();
+            anyhow::anyhow!("Invalid show: {}. Use {}", slug, slugs.join(", "))
+        })
+}
+
+fn weekday<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Weekday, D::Error> {
+    let day = String::deserialize(deserializer)?;
+    day.parse()
+        .map_err(|_| serde::de::Error::custom(format!("invalid weekday: {}", day)))
+}
+
+fn hour<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let hour = u32::deserialize(deserializer)?;
+    if hour > 23 {
+        return Err(serde::de::Error::custom(format!(
+            "invalid hour: {} (use 0-23)",
+            hour
+        )));
+    }
+    Ok(hour)
+}
+
+fn optional_hour<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    hour(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{Schedule, ScheduleSettings};
+    use crate::settings::Settings;
+    use crate::timezone::{Tz, ZoneExt};
+    use chrono::{DateTime, NaiveDate};
+
+    fn show(day: Weekday, ends: u32) -> ShowSettings {
+        ShowSettings {
+            slug: "show".to_string(),
+            name: "The Show".to_string(),
+            tag: None,
+            day,
+            ends,
+            rollover: None,
+            locale: None,
+            topic_order: None,
+            timezone: None,
+            exceptions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_shows() {
+        let settings = Settings::from_toml(
+            r#"
+            [[shows]]
+            slug = "twit"
+            name = "This Week in Tech"
+            day = "sunday"
+            ends = 18
+
+            [[shows]]
+            slug = "mbw"
+            name = "MacBreak Weekly"
+            tag = "macbreak"
+            day = "Tue"
+            ends = 14
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.shows.len(), 2);
+        assert_eq!(settings.shows[0].day, Weekday::Sun);
+        assert_eq!(settings.shows[0].info().tag, "#twit");
+        assert_eq!(settings.shows[1].day, Weekday::Tue);
+        assert_eq!(settings.shows[1].info().tag, "macbreak");
+
+        assert!(Settings::from_toml(
+            "[[shows]]\nslug = \"x\"\nname = \"X\"\nday = \"someday\"\nends = 18"
+        )
+        .is_err());
+        assert!(Settings::from_toml(
+            "[[shows]]\nslug = \"x\"\nname = \"X\"\nday = \"sun\"\nends = 24"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let shows = vec![show(Weekday::Sun, 18)];
+        assert_eq!(find(&shows, "show").unwrap().name, "The Show");
+        let err = find(&shows, "Show").unwrap_err().to_string();
+        assert_eq!(err, "Invalid show: Show. Use 'show'");
+        let err = find(&[], "show").unwrap_err().to_string();
+        assert!(err.starts_with("No shows are set up"), "{}", err);
+    }
+
+    #[test]
+    fn test_rollover() {
+        let settings = Settings::from_toml(
+            "[[shows]]\nslug = \"x\"\nname = \"X\"\nday = \"sun\"\nends = 17\nrollover = 18",
+        )
+        .unwrap();
+        assert_eq!(settings.shows[0].ends, 17);
+        assert_eq!(settings.shows[0].rollover, Some(18));
+        assert!(Settings::from_toml(
+            "[[shows]]\nslug = \"x\"\nname = \"X\"\nday = \"sun\"\nends = 17\nrollover = 25"
+        )
+        .is_err());
+    }
+
+    // ==================== The legacy shows ====================
+    //
+    // One test per case from when the three shows were built in, with the
+    // hours they had: bookmark windows start at Sun 17:00, Tue 14:00 and
+    // Wed 17:00, and the briefing date rolls over at Sun 18:00, Tue 14:00
+    // and Wed 18:00.
+
+    fn legacy_schedule(slug: &str) -> Schedule {
+        Schedule::new(find(&legacy(), slug).unwrap(), &ScheduleSettings::default()).unwrap()
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Tz> {
+        crate::timezone::local().at(NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap())
+    }
+
+    fn next_show_date(slug: &str, now: DateTime<Tz>) -> String {
+        legacy_schedule(slug)
+            .next_date(now)
+            .format("%a, %-d %B %Y")
+            .to_string()
+    }
+
+    #[test]
+    fn test_show_from_slug_twit() {
+        assert!(find(&legacy(), "twit").is_ok());
+    }
+
+    #[test]
+    fn test_show_from_slug_mbw() {
+        assert!(find(&legacy(), "mbw").is_ok());
+    }
+
+    #[test]
+    fn test_show_from_slug_im() {
+        assert!(find(&legacy(), "im").is_ok());
+    }
+
+    #[test]
+    fn test_show_from_slug_invalid() {
+        assert!(find(&legacy(), "invalid").is_err());
+    }
+
+    #[test]
+    fn test_show_from_slug_case_sensitive() {
+        assert!(find(&legacy(), "TWiT").is_err());
+        assert!(find(&legacy(), "MBW").is_err());
+    }
+
+    #[test]
+    fn test_show_info_twit() {
+        let info = find(&legacy(), "twit").unwrap().info();
+        assert_eq!(info.slug, "twit");
+        assert!(info.name.contains("Week in Tech"));
+        assert_eq!(info.tag, "#twit");
+    }
+
+    #[test]
+    fn test_show_info_mbw() {
+        let info = find(&legacy(), "mbw").unwrap().info();
+        assert_eq!(info.slug, "mbw");
+        assert!(info.name.contains("MacBreak"));
+        assert_eq!(info.tag, "#mbw");
+    }
+
+    #[test]
+    fn test_show_info_im() {
+        let info = find(&legacy(), "im").unwrap().info();
+        assert_eq!(info.slug, "im");
+        assert!(info.name.contains("Intelligent"));
+        assert_eq!(info.tag, "#im");
+    }
+
+    #[test]
+    fn test_previous_show_end_twit_sunday_after_cutoff() {
+        // Sunday 6pm (hour >= 17) → same day, anchored at 5pm
+        let end = legacy_schedule("twit").previous_end(at(2026, 3, 22, 18));
+        assert_eq!(end, at(2026, 3, 22, 17));
+    }
+
+    #[test]
+    fn test_previous_show_end_twit_sunday_before_cutoff() {
+        // Sunday 3pm (hour < 17) → previous Sunday 5pm
+        let end = legacy_schedule("twit").previous_end(at(2026, 3, 22, 15));
+        assert_eq!(end, at(2026, 3, 15, 17));
+    }
+
+    #[test]
+    fn test_previous_show_end_twit_monday() {
+        // Monday → previous day (Sunday) at 5pm
+        let end = legacy_schedule("twit").previous_end(at(2026, 3, 23, 10));
+        assert_eq!(end, at(2026, 3, 22, 17));
+    }
+
+    #[test]
+    fn test_previous_show_end_twit_saturday() {
+        // Saturday → previous Sunday 5pm (6 days back)
+        let end = legacy_schedule("twit").previous_end(at(2026, 3, 21, 14));
+        assert_eq!(end, at(2026, 3, 15, 17));
+    }
+
+    #[test]
+    fn test_previous_show_end_mbw_tuesday_after_cutoff() {
+        // Tuesday 3pm (hour >= 14) → same day, anchored at 2pm
+        let end = legacy_schedule("mbw").previous_end(at(2026, 3, 24, 15));
+        assert_eq!(end, at(2026, 3, 24, 14));
+    }
+
+    #[test]
+    fn test_previous_show_end_mbw_tuesday_before_cutoff() {
+        // Tuesday 8am (hour < 14) → previous Tuesday 2pm
+        let end = legacy_schedule("mbw").previous_end(at(2026, 3, 24, 8));
+        assert_eq!(end, at(2026, 3, 17, 14));
+    }
+
+    #[test]
+    fn test_previous_show_end_im_wednesday_after_cutoff() {
+        // Wednesday 5pm (hour >= 17) → same day, anchored at 5pm
+        let end = legacy_schedule("im").previous_end(at(2026, 3, 25, 17));
+        assert_eq!(end, at(2026, 3, 25, 17));
+    }
+
+    #[test]
+    fn test_previous_show_end_im_wednesday_before_cutoff() {
+        // Wednesday 10am (hour < 17) → previous Wednesday 5pm
+        let end = legacy_schedule("im").previous_end(at(2026, 3, 25, 10));
+        assert_eq!(end, at(2026, 3, 18, 17));
+    }
+
+    #[test]
+    fn test_mbw_from_sunday_evening() {
+        // Sunday Feb 1, 2026 at 9:25 PM -> next MBW is Tuesday Feb 3
+        let now = at(2026, 2, 1, 21) + chrono::Duration::minutes(25);
+        assert_eq!(next_show_date("mbw", now), "Tue, 3 February 2026");
+    }
+
+    #[test]
+    fn test_twit_from_sunday_after_cutoff() {
+        // Sunday Feb 1, 2026 at 7 PM (after 6 PM cutoff) -> next TWiT is Feb 8
+        assert_eq!(
+            next_show_date("twit", at(2026, 2, 1, 19)),
+            "Sun, 8 February 2026"
+        );
+    }
+
+    #[test]
+    fn test_twit_from_sunday_before_cutoff() {
+        // Sunday Feb 1, 2026 at 5 PM (before 6 PM cutoff) -> TWiT is today
+        assert_eq!(
+            next_show_date("twit", at(2026, 2, 1, 17)),
+            "Sun, 1 February 2026"
+        );
+    }
+
+    #[test]
+    fn test_mbw_from_tuesday_after_cutoff() {
+        // Tuesday Feb 3, 2026 at 3 PM (after 2 PM cutoff) -> next MBW is Feb 10
+        assert_eq!(
+            next_show_date("mbw", at(2026, 2, 3, 15)),
+            "Tue, 10 February 2026"
+        );
+    }
+
+    #[test]
+    fn test_mbw_from_tuesday_before_cutoff() {
+        // Tuesday Feb 3, 2026 at 1 PM (before 2 PM cutoff) -> MBW is today
+        assert_eq!(
+            next_show_date("mbw", at(2026, 2, 3, 13)),
+            "Tue, 3 February 2026"
+        );
+    }
+
+    #[test]
+    fn test_im_from_wednesday_after_cutoff() {
+        // Wednesday Feb 4, 2026 at 7 PM (after 6 PM cutoff) -> next IM is Feb 11
+        assert_eq!(
+            next_show_date("im", at(2026, 2, 4, 19)),
+            "Wed, 11 February 2026"
+        );
+    }
+
+    #[test]
+    fn test_im_from_sunday() {
+        // Sunday Feb 1, 2026 -> next IM is Wednesday Feb 4
+        let now = at(2026, 2, 1, 21) + chrono::Duration::minutes(25);
+        assert_eq!(next_show_date("im", now), "Wed, 4 February 2026");
+    }
+}
@@ -0,0 +1,176 @@
+//! Social share snippets: a ready-to-post line for each topic's lead story.
+//!
+//! Each post is the story's headline, its link and the show hashtag, cut to
+//! fit the character limit. Links are canonicalized (no tracking parameters
+//! or AMP markers) unless the story has a short link. The posts are written
+//! one per line to a text file the social team can schedule from.
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::clustering::{Story, Topic};
+use crate::dedup::canonicalize_url;
+use crate::timezone::Tz;
+
+/// The `[briefing.social]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SocialSettings {
+    /// Write the posts file with each briefing
+    pub enabled: bool,
+    /// Maximum characters per post, including link and hashtag
+    pub limit: usize,
+    /// Hashtag by show slug; `#` followed by the slug when unset
+    pub hashtags: HashMap<String, String>,
+}
+
+impl Default for SocialSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            limit: 280,
+            hashtags: HashMap::new(),
+        }
+    }
+}
+
+impl SocialSettings {
+    pub fn hashtag(&self, show_slug: &str) -> String {
+        self.hashtags
+            .get(show_slug)
+            .cloned()
+            .unwrap_or_else(|| format!("#{}", show_slug))
+    }
+}
+
+/// One post per topic, for the topic's first story, separated by newlines.
+pub fn generate(
+    topics: &[Topic],
+    hashtag: &str,
+    limit: usize,
+    short_links: &HashMap<String, String>,
+) -> String {
+    let mut posts = String::new();
+    for story in topics.iter().filter_map(|t| t.stories.first()) {
+        if story.url.is_empty() {
+            continue;
+        }
+        let link = short_links
+            .get(&story.url)
+            .cloned()
+            .unwrap_or_else(|| canonicalize_url(&story.url));
+        posts.push_str(&post(story, &link, hashtag, limit));
+        posts.push('\n');
+    }
+    posts
+}
+
+/// `"{headline} {link} {hashtag}"`, shortening the headline at a word
+/// boundary if needed. The link and hashtag are never cut.
+fn post(story: &Story, link: &str, hashtag: &str, limit: usize) -> String {
+    let tail = if hashtag.is_empty() {
+        format!(" {}", link)
+    } else {
+        format!(" {} {}", link, hashtag)
+    };
+    let room = limit.saturating_sub(tail.chars().count());
+    let headline = truncate(story.title.trim(), room);
+    format!("{}{}", headline, tail).trim_start().to_string()
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    // Leave room for the ellipsis, and don't end mid-word
+    let cut: String = text.chars().take(max - 1).collect();
+    let whole_word = text[cut.len()..].starts_with(char::is_whitespace);
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > 0 && !whole_word => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!(
+        "{}…",
+        cut.trim_end_matches(|c: char| c.is_whitespace() || c == ',' || c == ':')
+    )
+}
+
+pub fn save(content: &str, show_slug: &str, date: DateTime<Tz>) -> Result<PathBuf> {
+    let filepath = crate::output::settings().path(
+        crate::output::Artifact::Html,
+        show_slug,
+        date.date_naive(),
+        "-SOCIAL",
+        "txt",
+    )?;
+
+    crate::io::save_file(&filepath, content).context("Failed to write social posts file")?;
+
+    Ok(filepath)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    #[test]
+    fn test_generate_posts() {
+        let topics = vec![
+            Topic {
+                title: "Apple".to_string(),
+                stories: vec![
+                    story("Apple unveils new MacBook Pro")
+                        .with_url("https://www.example.com/macbook?utm_source=rss")
+                        .build(),
+                    story("Not the lead story")
+                        .with_url("https://example.com/other")
+                        .build(),
+                ],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+            Topic {
+                title: "Google".to_string(),
+                stories: vec![story("Google releases a very long headline about Gemini")
+                    .with_url("https://example.com/gemini")
+                    .build()],
+                connections: None,
+                notes: None,
+                rationale: None,
+            },
+        ];
+        let short_links: HashMap<String, String> = [(
+            "https://example.com/gemini".to_string(),
+            "https://twit.to/g".to_string(),
+        )]
+        .into();
+
+        let posts = generate(&topics, "#TWiT", 48, &short_links);
+        let lines: Vec<&str> = posts.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "Apple unveils… https://example.com/macbook #TWiT",
+                "Google releases a very… https://twit.to/g #TWiT",
+            ]
+        );
+        assert!(lines.iter().all(|l| l.chars().count() <= 48));
+    }
+
+    #[test]
+    fn test_hashtag_defaults_to_slug() {
+        let settings: SocialSettings =
+            toml::from_str("enabled = true\n[hashtags]\ntwit = \"#TWiT\"").unwrap();
+        assert_eq!(settings.limit, 280);
+        assert_eq!(settings.hashtag("twit"), "#TWiT");
+        assert_eq!(settings.hashtag("mbw"), "#mbw");
+    }
+}
@@ -0,0 +1,219 @@
+//! How many stories each outlet supplied.
+//!
+//! A rundown drawn mostly from one site gives that site's take on the week.
+//! After clustering, collect-stories prints the stories per site and warns
+//! about any site over `max_share` percent of them. The same breakdown is
+//! written as a comment block at the top of the org draft, which
+//! `parse_org_mode` skips, so it is rewritten whenever the draft is.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::clustering::{source_domain, Topic};
+
+/// Fewer stories than this are too few to call unbalanced.
+const MIN_STORIES: usize = 5;
+
+/// The `[briefing.sources]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SourceSettings {
+    /// Report stories per site after collection and in the org draft
+    pub enabled: bool,
+    /// Warn when one site supplies more than this percentage of stories
+    pub max_share: u32,
+}
+
+impl Default for SourceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_share: 40,
+        }
+    }
+}
+
+/// Stories from one site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceCount {
+    /// e.g. `theverge.com`, or `(no site)` for stories without a web link
+    pub site: String,
+    pub stories: usize,
+}
+
+/// The stories of a briefing by site, most first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceReport {
+    pub total: usize,
+    pub sources: Vec<SourceCount>,
+}
+
+impl SourceReport {
+    pub fn new(topics: &[Topic]) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for story in topics.iter().flat_map(|t| &t.stories) {
+            let site = source_domain(&story.url).unwrap_or_else(|| "(no site)".to_string());
+            *counts.entry(site.to_lowercase()).or_default() += 1;
+        }
+        let mut sources: Vec<SourceCount> = counts
+            .into_iter()
+            .map(|(site, stories)| SourceCount { site, stories })
+            .collect();
+        sources.sort_by(|a, b| b.stories.cmp(&a.stories).then_with(|| a.site.cmp(&b.site)));
+        Self {
+            total: sources.iter().map(|s| s.stories).sum(),
+            sources,
+        }
+    }
+
+    /// `source`'s share of the stories, rounded to a whole percent.
+    pub fn share(&self, source: &SourceCount) -> u32 {
+        if self.total == 0 {
+            return 0;
+        }
+        ((source.stories * 100 + self.total / 2) / self.total) as u32
+    }
+
+    /// `Sources: 12 stories from 7 sites`
+    pub fn heading(&self) -> String {
+        let plural =
+            |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+        format!(
+            "Sources: {} from {}",
+            plural(self.total, "story", "stories"),
+            plural(self.sources.len(), "site", "sites")
+        )
+    }
+
+    /// One line per site, e.g. `theverge.com: 5 (42%)`.
+    pub fn lines(&self) -> Vec<String> {
+        self.sources
+            .iter()
+            .map(|s| format!("{}: {} ({}%)", s.site, s.stories, self.share(s)))
+            .collect()
+    }
+}
+
+impl SourceSettings {
+    /// The breakdown for `topics`, or `None` when it's turned off or there
+    /// are no stories.
+    pub fn report(&self, topics: &[Topic]) -> Option<SourceReport> {
+        let report = SourceReport::new(topics);
+        (self.enabled && report.total > 0).then_some(report)
+    }
+
+    /// A warning for each site over `max_share`.
+    pub fn warnings(&self, report: &SourceReport) -> Vec<String> {
+        if report.total < MIN_STORIES {
+            return Vec::new();
+        }
+        report
+            .sources
+            .iter()
+            .filter(|s| s.stories * 100 > self.max_share as usize * report.total)
+            .map(|s| {
+                format!(
+                    "{} supplied {} of {} stories ({}%), over the {}% limit",
+                    s.site,
+                    s.stories,
+                    report.total,
+                    report.share(s),
+                    self.max_share
+                )
+            })
+            .collect()
+    }
+
+    /// The breakdown and warnings as an org comment block, or nothing.
+    pub fn org_block(&self, topics: &[Topic]) -> String {
+        let Some(report) = self.report(topics) else {
+            return String::new();
+        };
+        let mut block = format!("#+BEGIN_COMMENT\n{}\n", report.heading());
+        for line in report.lines() {
+            block.push_str(&format!("- {}\n", line));
+        }
+        for warning in self.warnings(&report) {
+            block.push_str(&format!("⚠ {}\n", warning));
+        }
+        block.push_str("#+END_COMMENT\n\n");
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::Story;
+    use crate::summarizer::Summary;
+
+    fn topic(urls: &[&str]) -> Topic {
+        Topic {
+            title: "News".to_string(),
+            stories: urls
+                .iter()
+                .map(|url| Story {
+                    title: "A story".to_string(),
+                    url: url.to_string(),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                })
+                .collect(),
+            connections: None,
+            notes: None,
+            rationale: None,
+        }
+    }
+
+    #[test]
+    fn test_report_and_warnings() {
+        let topics = [
+            topic(&[
+                "https://www.theverge.com/a",
+                "https://theverge.com/b",
+                "https://arstechnica.com/c",
+            ]),
+            topic(&["https://theverge.com/d", "https://9to5mac.com/e", ""]),
+        ];
+        let settings = SourceSettings::default();
+        let report = settings.report(&topics).unwrap();
+        assert_eq!(report.heading(), "Sources: 6 stories from 4 sites");
+        assert_eq!(
+            report.lines(),
+            [
+                "theverge.com: 3 (50%)",
+                "(no site): 1 (17%)",
+                "9to5mac.com: 1 (17%)",
+                "arstechnica.com: 1 (17%)"
+            ]
+        );
+        assert_eq!(
+            settings.warnings(&report),
+            ["theverge.com supplied 3 of 6 stories (50%), over the 40% limit"]
+        );
+
+        let lenient = SourceSettings {
+            max_share: 50,
+            ..Default::default()
+        };
+        assert!(lenient.warnings(&report).is_empty());
+        // Too few stories to judge
+        let few = SourceReport::new(&[topic(&["https://theverge.com/a"])]);
+        assert!(settings.warnings(&few).is_empty());
+    }
+
+    #[test]
+    fn test_org_block() {
+        let topics = [topic(&["https://theverge.com/a", "https://ft.com/b"])];
+        assert_eq!(
+            SourceSettings::default().org_block(&topics),
+            "#+BEGIN_COMMENT\nSources: 2 stories from 2 sites\n- ft.com: 1 (50%)\n- theverge.com: 1 (50%)\n#+END_COMMENT\n\n"
+        );
+        let off = SourceSettings {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(off.org_block(&topics), "");
+        assert_eq!(SourceSettings::default().org_block(&[]), "");
+    }
+}
@@ -0,0 +1,133 @@
+//! Stories that were old news by the show date.
+//!
+//! Bookmarks can sit in Raindrop for weeks, and an article's date is easy
+//! to miss in a long rundown. A story published more than
+//! `[briefing.staleness] days` before the show (10 by default) gets a
+//! "⚠ 12 days old" badge in the briefing and a `STALE:` line in the org
+//! draft. The age is counted from the story's date, which is the article's
+//! published date when the page gave one and the bookmark's otherwise.
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::clustering::Story;
+use crate::dates::{self, ParsedDate};
+
+/// The `[briefing.staleness]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StalenessSettings {
+    /// Flag stories older than this many days on the show date; 0 turns
+    /// the flags off
+    pub days: u32,
+}
+
+impl Default for StalenessSettings {
+    fn default() -> Self {
+        Self { days: 10 }
+    }
+}
+
+impl StalenessSettings {
+    /// How many days old `story` is on `show_date`, if that's past the
+    /// limit. Stories without a readable date are never flagged.
+    pub fn stale_days(&self, story: &Story, show_date: NaiveDate) -> Option<i64> {
+        if self.days == 0 {
+            return None;
+        }
+        let age = age_days(&story.created, show_date)?;
+        (age > i64::from(self.days)).then_some(age)
+    }
+}
+
+/// Whole days from the date in `created` to `show_date`.
+pub fn age_days(created: &str, show_date: NaiveDate) -> Option<i64> {
+    let published = match dates::parse(created)? {
+        ParsedDate::At(at) => at.date_naive(),
+        ParsedDate::On(date) => date,
+    };
+    Some((show_date - published).num_days())
+}
+
+/// The flag's text, e.g. "12 days old".
+pub fn label(days: i64) -> String {
+    format!("{} days old", days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summarizer::Summary;
+    use crate::testing::story;
+
+    #[test]
+    fn test_stale_days() {
+        let show = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        let settings = StalenessSettings::default();
+
+        assert_eq!(
+            settings.stale_days(
+                &story("Old news")
+                    .with_created("2026-01-20T23:30:00-08:00")
+                    .with_summary(Summary::Failed("none".to_string()))
+                    .build(),
+                show
+            ),
+            Some(12)
+        );
+        // Exactly at the limit is still fresh
+        assert_eq!(
+            settings.stale_days(
+                &story("Old news")
+                    .with_created("Thu, 22 Jan 2026")
+                    .with_summary(Summary::Failed("none".to_string()))
+                    .build(),
+                show
+            ),
+            None
+        );
+        assert_eq!(
+            settings.stale_days(
+                &story("Old news")
+                    .with_created("2026-01-21")
+                    .with_summary(Summary::Failed("none".to_string()))
+                    .build(),
+                show
+            ),
+            Some(11)
+        );
+        assert_eq!(
+            settings.stale_days(
+                &story("Old news")
+                    .with_created("2026-01-30")
+                    .with_summary(Summary::Failed("none".to_string()))
+                    .build(),
+                show
+            ),
+            None
+        );
+        assert_eq!(
+            settings.stale_days(
+                &story("Old news")
+                    .with_created("last week")
+                    .with_summary(Summary::Failed("none".to_string()))
+                    .build(),
+                show
+            ),
+            None
+        );
+
+        let off = StalenessSettings { days: 0 };
+        assert_eq!(
+            off.stale_days(
+                &story("Old news")
+                    .with_created("2025-01-01")
+                    .with_summary(Summary::Failed("none".to_string()))
+                    .build(),
+                show
+            ),
+            None
+        );
+        assert_eq!(label(12), "12 days old");
+    }
+}
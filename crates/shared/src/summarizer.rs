@@ -1,104 +1,468 @@
-use anyhow::{Context, Result};
-use futures::stream::{self, StreamExt};
-use reqwest::Client;
+use anyhow::Result;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use std::io::Write;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Semaphore;
+use std::time::{Duration, Instant};
+
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind, SummaryLength};
+use crate::quotes::Quote;
+use crate::usage::UsageTracker;
 
-const GLM_MODEL: &str = "glm-5.2";
-const ZAI_API_URL: &str = "https://api.z.ai/api/anthropic/v1/messages";
 const SUMMARIZE_TIMEOUT: Duration = Duration::from_secs(90);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub enum Summary {
     Editorial {
         lede: String,
         nutgraf: String,
-        quote: Option<String>,
+        /// Up to [`MAX_QUOTES`], in the order the model gave them
+        #[serde(
+            default,
+            alias = "quote",
+            deserialize_with = "crate::quotes::deserialize_quotes",
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        quotes: Vec<Quote>,
+        /// What commenters made of it, for links to a discussion thread
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reaction: Option<String>,
+        /// The article's key figures in one line, e.g. "$3B acquisition,
+        /// 12,000 layoffs, 40% YoY"
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        by_the_numbers: Option<String>,
     },
     Product {
         the_product: String,
         cost: String,
         availability: String,
         platforms: String,
-        quote: Option<String>,
+        #[serde(
+            default,
+            alias = "quote",
+            deserialize_with = "crate::quotes::deserialize_quotes",
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        quotes: Vec<Quote>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reaction: Option<String>,
     },
+    /// Not enough text to summarize, and the default for a new story
+    #[default]
     Insufficient,
     Failed(String),
 }
 
-const SUMMARIZER_SYSTEM_PROMPT: &str = r#"You are a journalist summarizing articles using the nut graph structure. Summarize the article below using the appropriate format.
+/// Quotes kept from a summary reply.
+pub const MAX_QUOTES: usize = 3;
 
-First, determine: Is this article primarily about a specific PRODUCT (hardware, software, app, device) or is it EDITORIAL (news, policy, analysis, industry event)?
+/// Marker used as the nutgraf of pseudo-summaries made without an LLM.
+pub const AI_DISABLED_NOTE: &str = "[AI disabled: opening of the article, not a summary]";
 
-RULES:
-1. Use ONLY information from the article - no external knowledge
-2. If the article has insufficient content, respond with: "Insufficient content for summary"
-3. QUOTE must be copied VERBATIM from the article — the exact words as they appear, with clear speaker attribution. Do not paraphrase or alter the quote in any way.
+/// Stand-in summary when no LLM is configured: the article's opening
+/// sentences as the lede, flagged with [`AI_DISABLED_NOTE`]. Short lines
+/// (navigation, bylines, captions) before the first real paragraph are
+/// skipped.
+pub fn extractive_summary(text: &str) -> Summary {
+    const MIN_PARAGRAPH: usize = 80;
+    const MAX_LEDE: usize = 300;
 
-If EDITORIAL, respond in this exact format:
-FORMAT: EDITORIAL
-QUOTE: "exact verbatim quote from the article" -- Speaker Name
-LEDE: One strong sentence identifying WHO is involved and WHAT happened or was announced.
-NUTGRAF: A paragraph (2-4 sentences) explaining WHY this matters. Contextualize the most important facts and give the reader a clear understanding of the central issue or topic.
+    let Some(paragraph) = text
+        .lines()
+        .map(str::trim)
+        .find(|line| line.chars().count() >= MIN_PARAGRAPH)
+    else {
+        return Summary::Insufficient;
+    };
 
-If PRODUCT, respond in this exact format:
-FORMAT: PRODUCT
-THE_PRODUCT: What the product is and what it does (1-2 sentences).
-COST: Pricing details. Omit this line if pricing is not mentioned.
-AVAILABILITY: When and where it is available. Omit this line if not mentioned.
-PLATFORMS: What platforms or operating systems it runs on. Omit this line for hardware-only products or if not mentioned.
-QUOTE: "exact verbatim quote from the article" -- Speaker Name
+    let mut lede = String::new();
+    for sentence in paragraph.split_inclusive(". ") {
+        if !lede.is_empty() && lede.chars().count() + sentence.chars().count() > MAX_LEDE {
+            break;
+        }
+        lede.push_str(sentence);
+    }
+    if lede.chars().count() > MAX_LEDE {
+        lede = lede.chars().take(MAX_LEDE).collect::<String>() + "…";
+    }
+
+    Summary::Editorial {
+        lede: lede.trim().to_string(),
+        nutgraf: AI_DISABLED_NOTE.to_string(),
+        quotes: Vec::new(),
+        reaction: None,
+        by_the_numbers: None,
+    }
+}
+
+/// Marker on summaries built from a bookmark's Raindrop excerpt because the
+/// article itself couldn't be fetched.
+pub const EXCERPT_NOTE: &str = "[Based on the Raindrop excerpt; the article could not be fetched]";
+
+/// Excerpts shorter than this are shown as-is rather than summarized.
+pub const MIN_EXCERPT_FOR_SUMMARY: usize = 150;
+
+/// The excerpt itself as the description, marked with [`EXCERPT_NOTE`].
+pub fn excerpt_summary(excerpt: &str) -> Summary {
+    Summary::Editorial {
+        lede: excerpt.trim().to_string(),
+        nutgraf: EXCERPT_NOTE.to_string(),
+        quotes: Vec::new(),
+        reaction: None,
+        by_the_numbers: None,
+    }
+}
+
+/// Summarizer input for an unfetchable article: its title and excerpt, or
+/// `None` if the excerpt is too short to be worth summarizing.
+pub fn excerpt_input(title: &str, excerpt: &str) -> Option<String> {
+    let excerpt = excerpt.trim();
+    (excerpt.chars().count() >= MIN_EXCERPT_FOR_SUMMARY)
+        .then(|| format!("{}\n\n{}", title, excerpt))
+}
+
+/// Flag a summary generated from an excerpt so it isn't mistaken for one of
+/// the full article.
+pub fn mark_excerpt_based(summary: Summary) -> Summary {
+    match summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        } => Summary::Editorial {
+            lede,
+            nutgraf: format!("{} {}", nutgraf, EXCERPT_NOTE).trim().to_string(),
+            quotes,
+            reaction,
+            by_the_numbers,
+        },
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        } => Summary::Product {
+            the_product: format!("{} {}", the_product, EXCERPT_NOTE),
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        },
+        other => other,
+    }
+}
+
+/// What's wrong with `summary`, phrased as instructions for the model. An
+/// empty list means the summary passes. `headline` is the story's title, if
+/// known.
+pub fn problems(summary: &Summary, headline: Option<&str>) -> Vec<String> {
+    problems_at(summary, headline, SummaryLength::Standard)
+}
+
+/// [`problems`] with the word limits of `length`.
+pub fn problems_at(
+    summary: &Summary,
+    headline: Option<&str>,
+    length: SummaryLength,
+) -> Vec<String> {
+    let (max_lede_words, max_paragraph_words) = length.word_limits();
+    let mut problems = Vec::new();
+    let (opening, fields): (&str, Vec<(&str, &String)>) = match summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        } => {
+            let mut fields = vec![("LEDE", lede), ("NUTGRAF", nutgraf)];
+            fields.extend(quotes.iter().map(|q| ("QUOTE", &q.text)));
+            fields.extend(reaction.iter().map(|r| ("REACTION", r)));
+            fields.extend(by_the_numbers.iter().map(|n| ("BY_THE_NUMBERS", n)));
+            (lede, fields)
+        }
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        } => {
+            let mut fields = vec![
+                ("THE_PRODUCT", the_product),
+                ("COST", cost),
+                ("AVAILABILITY", availability),
+                ("PLATFORMS", platforms),
+            ];
+            fields.extend(quotes.iter().map(|q| ("QUOTE", &q.text)));
+            fields.extend(reaction.iter().map(|r| ("REACTION", r)));
+            (the_product, fields)
+        }
+        Summary::Insufficient => return problems,
+        Summary::Failed(reason) => {
+            problems.push(format!(
+                "{}. Include every required field of the format.",
+                reason
+            ));
+            return problems;
+        }
+    };
+
+    for (name, text) in &fields {
+        let words = text.split_whitespace().count();
+        let max = if *name == "LEDE" {
+            max_lede_words
+        } else {
+            max_paragraph_words
+        };
+        if *name != "QUOTE" && words > max {
+            problems.push(format!(
+                "{} is {} words long; keep it under {} words.",
+                name, words, max
+            ));
+        }
+    }
+    if let Some(headline) = headline.map(normalize).filter(|h| !h.is_empty()) {
+        if normalize(opening).starts_with(&headline) {
+            problems.push(format!(
+                "{} repeats the headline word for word; say what happened in your own words.",
+                fields[0].0
+            ));
+        }
+    }
+    // Quotes are verbatim, so they may say it, and commenters talk about it
+    if fields
+        .iter()
+        .filter(|(name, _)| !matches!(*name, "QUOTE" | "REACTION"))
+        .any(|(_, text)| text.to_lowercase().contains("the article"))
+    {
+        problems.push("Don't refer to \"the article\"; state the facts directly.".to_string());
+    }
+    problems
+}
+
+/// Lowercase words only, so punctuation and spacing don't hide a repeat.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The original request plus the rejected reply and what to fix.
+fn correction_prompt(prompt: &str, reply: &str, problems: &[String]) -> String {
+    let mut out = format!(
+        "{}\n\nYour previous reply was:\n\n{}\n\nIt has these problems:\n",
+        prompt,
+        reply.trim()
+    );
+    for problem in problems {
+        out.push_str(&format!("- {}\n", problem));
+    }
+    out.push_str("\nRespond again in the same format, fixing every problem.");
+    out
+}
 
-Omit the QUOTE line if there are no direct quotes with clear speaker attribution in the article."#;
+/// Read a reply in the prompt's `FIELD: value` format. Missing required
+/// fields give [`Summary::Failed`].
+pub(crate) fn parse_fields(text: &str) -> Summary {
+    let mut format_type = None;
+    let mut quotes = Vec::new();
+    let mut lede = String::new();
+    let mut nutgraf = String::new();
+    let mut the_product = String::new();
+    let mut cost = String::new();
+    let mut availability = String::new();
+    let mut platforms = String::new();
+    let mut reaction = None;
+    let mut by_the_numbers = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(fmt) = trimmed.strip_prefix("FORMAT:") {
+            format_type = Some(fmt.trim().to_uppercase());
+        } else if let Some(val) = trimmed.strip_prefix("LEDE:") {
+            lede = val.trim().to_string();
+        } else if let Some(val) = trimmed.strip_prefix("NUTGRAF:") {
+            nutgraf = val.trim().to_string();
+        } else if let Some(val) = trimmed.strip_prefix("THE_PRODUCT:") {
+            the_product = val.trim().to_string();
+        } else if let Some(val) = trimmed.strip_prefix("COST:") {
+            cost = val.trim().to_string();
+        } else if let Some(val) = trimmed.strip_prefix("AVAILABILITY:") {
+            availability = val.trim().to_string();
+        } else if let Some(val) = trimmed.strip_prefix("PLATFORMS:") {
+            platforms = val.trim().to_string();
+        } else if let Some(val) = trimmed.strip_prefix("REACTION:") {
+            let val = val.trim();
+            if !val.is_empty() {
+                reaction = Some(val.to_string());
+            }
+        } else if let Some(val) = trimmed.strip_prefix("BY_THE_NUMBERS:") {
+            let val = val.trim();
+            if !val.is_empty() {
+                by_the_numbers = Some(val.to_string());
+            }
+        } else if let Some(val) = trimmed.strip_prefix("QUOTE:") {
+            let val = val.trim();
+            if !val.is_empty() && quotes.len() < MAX_QUOTES {
+                quotes.push(Quote::parse(val));
+            }
+        }
+    }
+
+    // Auto-detect format from content if FORMAT: line is missing
+    let is_product = match format_type.as_deref() {
+        Some("PRODUCT") => true,
+        Some("EDITORIAL") => false,
+        _ => !the_product.is_empty(),
+    };
+
+    if is_product {
+        if the_product.is_empty() {
+            return Summary::Failed("Product format missing THE_PRODUCT field".to_string());
+        }
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        }
+    } else {
+        if lede.is_empty() || nutgraf.is_empty() {
+            return Summary::Failed("Editorial format missing required fields".to_string());
+        }
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        }
+    }
+}
 
 pub struct ClaudeSummarizer {
-    client: Client,
-    api_key: String,
-    semaphore: Arc<Semaphore>,
+    llm: LlmClient,
+    prompt: Prompt,
+    progress: Progress,
+    headlines: HashMap<String, String>,
+    deadline: Option<Instant>,
+    length: SummaryLength,
 }
 
 impl ClaudeSummarizer {
     pub fn new() -> Result<Self> {
-        // The API key lives in the env var named by BRIEFING_LLM_KEY_ENV
-        // (default ZAI_API_KEY), so a run can target a different backend —
-        // e.g. Anthropic via ANTHROPIC_API_KEY — without code changes.
-        let key_var = std::env::var("BRIEFING_LLM_KEY_ENV")
-            .unwrap_or_else(|_| "ZAI_API_KEY".to_string());
-        let api_key = std::env::var(&key_var)
-            .with_context(|| format!("{key_var} not set"))?;
-        let client = Client::builder()
-            .timeout(SUMMARIZE_TIMEOUT)
-            .build()
-            .context("Failed to build HTTP client")?;
         Ok(ClaudeSummarizer {
-            client,
-            api_key,
-            semaphore: Arc::new(Semaphore::new(2)),
+            llm: LlmClient::from_env(SUMMARIZE_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Summarize),
+            progress: crate::progress::console(),
+            headlines: HashMap::new(),
+            deadline: None,
+            length: SummaryLength::default(),
         })
     }
 
+    /// Record token usage for every summary request into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Report summaries, batch status and retries to `progress`.
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Story titles by URL, so the batch methods can catch ledes that just
+    /// repeat the headline.
+    pub fn with_headlines(mut self, headlines: HashMap<String, String>) -> Self {
+        self.headlines = headlines;
+        self
+    }
+
+    /// After `deadline`, `summarize_articles_parallel` starts no new
+    /// summaries and failed requests aren't retried.
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Give replies the token limit of `length` and check them against its
+    /// word limits. The prompt's instructions come from the settings it was
+    /// loaded with, so pass the same length.
+    pub fn with_length(mut self, length: SummaryLength) -> Self {
+        self.length = length;
+        self
+    }
+
     pub async fn summarize_article(&self, content: &str) -> Result<Summary> {
-        let _permit = self.semaphore.acquire().await?;
+        self.summarize(content, None).await
+    }
 
+    /// [`summarize_article`](Self::summarize_article) for a story whose
+    /// title is known.
+    pub async fn summarize_story(&self, headline: &str, content: &str) -> Result<Summary> {
+        self.summarize(content, Some(headline)).await
+    }
+
+    async fn summarize(&self, content: &str, headline: Option<&str>) -> Result<Summary> {
         for attempt in 0..5 {
-            match self.try_summarize(content).await {
-                Ok(summary) => {
-                    // Small delay after successful request to spread load
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                    return Ok(summary);
-                }
+            match self.try_summarize(content, headline).await {
+                Ok(summary) => return Ok(summary),
                 Err(e) => {
-                    if attempt == 4 {
-                        eprintln!("Failed to summarize: {}", e);
+                    if attempt == 4 || crate::budget::expired(self.deadline) {
+                        tracing::warn!("Failed to summarize: {}", e);
                         return Ok(Summary::Failed(e.to_string()));
                     }
 
-                    let backoff =
-                        Duration::from_millis(1000 * (2_u64.pow(attempt as u32)));
+                    let backoff = self.llm.retry_delay(attempt);
+                    let error_msg = e.to_string();
+                    let event = if error_msg.contains("rate_limit") || error_msg.contains("429") {
+                        ProgressEvent::RateLimited {
+                            stage: Stage::Summarize,
+                            attempt: attempt + 1,
+                            wait_secs: backoff.as_secs(),
+                        }
+                    } else {
+                        ProgressEvent::Retrying {
+                            stage: Stage::Summarize,
+                            attempt: attempt + 1,
+                            wait_secs: backoff.as_secs(),
+                            error: error_msg,
+                        }
+                    };
+                    tracing::debug!(
+                        attempt = attempt + 1,
+                        wait_secs = backoff.as_secs(),
+                        "summary request failed: {}",
+                        e
+                    );
+                    self.progress.report(&event);
                     tokio::time::sleep(backoff).await;
                 }
             }
@@ -107,7 +471,49 @@ impl ClaudeSummarizer {
         Ok(Summary::Failed("Max retries reached".to_string()))
     }
 
-    async fn try_summarize(&self, content: &str) -> Result<Summary> {
+    async fn try_summarize(&self, content: &str, headline: Option<&str>) -> Result<Summary> {
+        let prompt = self.build_prompt(content);
+        let summary_text = self
+            .llm
+            .complete("summarize", &prompt, self.length.max_tokens())
+            .await?;
+        self.validated(&prompt, &summary_text, headline).await
+    }
+
+    /// Interpret `reply` to `prompt`. A summary that fails [`problems`] is
+    /// sent back once with corrective instructions; if the second reply
+    /// fails too, the story is marked Failed.
+    async fn validated(
+        &self,
+        prompt: &str,
+        reply: &str,
+        headline: Option<&str>,
+    ) -> Result<Summary> {
+        let summary = self.interpret(reply)?;
+        let found = problems_at(&summary, headline, self.length);
+        if found.is_empty() {
+            return Ok(summary);
+        }
+        tracing::debug!("re-prompting summary: {}", found.join(" "));
+
+        let retry = correction_prompt(prompt, reply, &found);
+        let reply = self
+            .llm
+            .complete("summarize-retry", &retry, self.length.max_tokens())
+            .await?;
+        let summary = self.interpret(&reply)?;
+        let found = problems_at(&summary, headline, self.length);
+        if found.is_empty() {
+            Ok(summary)
+        } else {
+            Ok(Summary::Failed(format!(
+                "Summary failed validation: {}",
+                found.join(" ")
+            )))
+        }
+    }
+
+    fn build_prompt(&self, content: &str) -> String {
         // Truncate content to 10000 chars, respecting UTF-8 boundaries
         let truncated_content = if content.len() > 10000 {
             let mut end = 10000;
@@ -119,44 +525,10 @@ impl ClaudeSummarizer {
             content
         };
 
-        let prompt = format!("{}\n\nArticle:\n{}", SUMMARIZER_SYSTEM_PROMPT, truncated_content);
-
-        // Endpoint/model are env-overridable for testing alternate backends
-        // (e.g. a local llama.cpp /v1/messages server). Defaults to z.ai GLM.
-        let model = std::env::var("BRIEFING_LLM_MODEL").unwrap_or_else(|_| GLM_MODEL.to_string());
-        let url = std::env::var("BRIEFING_LLM_URL").unwrap_or_else(|_| ZAI_API_URL.to_string());
-
-        let body = json!({
-            "model": model,
-            "max_tokens": 1024,
-            "messages": [{"role": "user", "content": prompt}]
-        });
+        self.prompt.render(&[("article", truncated_content)])
+    }
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", &self.api_key))
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .context("API request failed")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            anyhow::bail!("API error {}: {}", status, text);
-        }
-
-        let data: Value = response
-            .json()
-            .await
-            .context("Failed to parse API response")?;
-
-        let summary_text = data["content"][0]["text"]
-            .as_str()
-            .context("No text in API response")?;
+    fn interpret(&self, summary_text: &str) -> Result<Summary> {
         let summary_text = summary_text.trim();
 
         if summary_text.contains("Insufficient content for summary") {
@@ -167,95 +539,112 @@ impl ClaudeSummarizer {
     }
 
     fn parse_smart_brevity(&self, text: &str) -> Result<Summary> {
-        let mut format_type = None;
-        let mut quote = None;
-        let mut lede = String::new();
-        let mut nutgraf = String::new();
-        let mut the_product = String::new();
-        let mut cost = String::new();
-        let mut availability = String::new();
-        let mut platforms = String::new();
-
-        for line in text.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-
-            if let Some(fmt) = trimmed.strip_prefix("FORMAT:") {
-                format_type = Some(fmt.trim().to_uppercase());
-            } else if let Some(val) = trimmed.strip_prefix("LEDE:") {
-                lede = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("NUTGRAF:") {
-                nutgraf = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("THE_PRODUCT:") {
-                the_product = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("COST:") {
-                cost = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("AVAILABILITY:") {
-                availability = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("PLATFORMS:") {
-                platforms = val.trim().to_string();
-            } else if let Some(val) = trimmed.strip_prefix("QUOTE:") {
-                let val = val.trim();
-                if !val.is_empty() {
-                    quote = Some(val.to_string());
-                }
-            }
-        }
+        Ok(parse_fields(text))
+    }
 
-        // Auto-detect format from content if FORMAT: line is missing
-        let is_product = match format_type.as_deref() {
-            Some("PRODUCT") => true,
-            Some("EDITORIAL") => false,
-            _ => !the_product.is_empty(),
-        };
+    /// Summarize every article in a single Message Batches job, polling until
+    /// it finishes. Articles whose batch request fails are retried one at a
+    /// time; if the batch can't be submitted at all (e.g. the backend has no
+    /// batch endpoint) the error is returned so the caller can fall back to
+    /// `summarize_articles_parallel`.
+    pub async fn summarize_articles_batch(
+        &self,
+        articles: Vec<(String, String)>,
+    ) -> Result<Vec<(String, Summary)>> {
+        let prompts: Vec<(String, String)> = articles
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, content))| (idx.to_string(), self.build_prompt(content)))
+            .collect();
+        let total = prompts.len();
+        let stage = Stage::Summarize;
 
-        if is_product {
-            if the_product.is_empty() {
-                return Ok(Summary::Failed(
-                    "Product format missing THE_PRODUCT field".to_string(),
-                ));
-            }
-            Ok(Summary::Product {
-                the_product,
-                cost,
-                availability,
-                platforms,
-                quote,
-            })
-        } else {
-            if lede.is_empty() || nutgraf.is_empty() {
-                return Ok(Summary::Failed(
-                    "Editorial format missing required fields".to_string(),
-                ));
-            }
-            Ok(Summary::Editorial {
-                lede,
-                nutgraf,
-                quote,
+        self.progress
+            .report(&ProgressEvent::StageStarted { stage, total });
+        let replies = self
+            .llm
+            .complete_batch("summarize", &prompts, self.length.max_tokens(), |batch| {
+                self.progress.report(&ProgressEvent::BatchProgress {
+                    done: batch.done(),
+                    total,
+                });
             })
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+        let mut replies = replies?;
+
+        let mut results = Vec::with_capacity(articles.len());
+        for (idx, (url, content)) in articles.into_iter().enumerate() {
+            let headline = self.headlines.get(&url).map(String::as_str);
+            let summary = match replies.remove(&idx.to_string()) {
+                Some(Ok(text)) => self
+                    .validated(&prompts[idx].1, &text, headline)
+                    .await
+                    .unwrap_or_else(|e| Summary::Failed(e.to_string())),
+                _ => match self.summarize(&content, headline).await {
+                    Ok(summary) => summary,
+                    Err(e) => Summary::Failed(e.to_string()),
+                },
+            };
+            results.push((url, summary));
         }
+
+        Ok(results)
     }
 
     pub async fn summarize_articles_parallel(
         &self,
         articles: Vec<(String, String)>,
     ) -> Result<Vec<(String, Summary)>> {
-        let results: Vec<(String, Summary)> = stream::iter(articles)
-            .map(|(url, content)| async move {
-                let summary = match self.summarize_article(&content).await {
-                    Ok(summary) => summary,
-                    Err(e) => Summary::Failed(e.to_string()),
-                };
-                eprint!(".");
-                let _ = std::io::stderr().flush();
-                (url, summary)
-            })
-            .buffer_unordered(2)
-            .collect()
-            .await;
-        eprintln!();
+        let stage = Stage::Summarize;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: articles.len(),
+        });
+        let queued = Arc::new(AtomicUsize::new(articles.len()));
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            queued.clone(),
+        );
+        let (mut results, out_of_time) = crate::budget::run_until(
+            articles,
+            // The rate limiter decides how many actually run at once
+            crate::rate_limit::MAX_CONCURRENCY,
+            self.deadline,
+            |(url, content)| {
+                let queued = queued.clone();
+                async move {
+                    let headline = self.headlines.get(&url).map(String::as_str);
+                    let summary = match self.summarize(&content, headline).await {
+                        Ok(summary) => summary,
+                        Err(e) => Summary::Failed(e.to_string()),
+                    };
+                    queued.fetch_sub(1, Ordering::Relaxed);
+                    self.progress.report(&ProgressEvent::SummaryDone {
+                        url: url.clone(),
+                        ok: !matches!(summary, Summary::Failed(_)),
+                    });
+                    (url, summary)
+                }
+            },
+        )
+        .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+        if !out_of_time.is_empty() {
+            tracing::warn!(
+                "Summarize time budget ran out; {} article(s) not summarized",
+                out_of_time.len()
+            );
+        }
+        results.extend(
+            out_of_time
+                .into_iter()
+                .map(|(url, _)| (url, Summary::Failed(crate::budget::OUT_OF_TIME.to_string()))),
+        );
 
         Ok(results)
     }
@@ -264,12 +653,100 @@ impl ClaudeSummarizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
 
     fn summarizer() -> ClaudeSummarizer {
         ClaudeSummarizer {
-            client: Client::new(),
-            api_key: "test".to_string(),
-            semaphore: Arc::new(Semaphore::new(2)),
+            llm: LlmClient::new(
+                "test".to_string(),
+                crate::llm::DEFAULT_MODEL.to_string(),
+                crate::llm::DEFAULT_API_URL.to_string(),
+                SUMMARIZE_TIMEOUT,
+            )
+            .unwrap(),
+            prompt: Prompt::builtin(PromptKind::Summarize),
+            progress: Arc::new(crate::progress::NoProgress),
+            headlines: HashMap::new(),
+            deadline: None,
+            length: SummaryLength::default(),
+        }
+    }
+
+    // ==================== excerpt fallback ====================
+
+    #[test]
+    fn test_excerpt_input_requires_some_length() {
+        assert_eq!(excerpt_input("Title", "  Short.  "), None);
+        let long = "x".repeat(MIN_EXCERPT_FOR_SUMMARY);
+        assert_eq!(
+            excerpt_input("Title", &long),
+            Some(format!("Title\n\n{}", long))
+        );
+    }
+
+    #[test]
+    fn test_mark_excerpt_based() {
+        let marked = mark_excerpt_based(Summary::Editorial {
+            lede: "Lede".to_string(),
+            nutgraf: "Why it matters.".to_string(),
+            quotes: Vec::new(),
+            reaction: None,
+            by_the_numbers: None,
+        });
+        match marked {
+            Summary::Editorial { nutgraf, .. } => {
+                assert_eq!(nutgraf, format!("Why it matters. {}", EXCERPT_NOTE))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            mark_excerpt_based(Summary::Insufficient),
+            Summary::Insufficient
+        ));
+        match excerpt_summary(" An excerpt. ") {
+            Summary::Editorial { lede, nutgraf, .. } => {
+                assert_eq!(lede, "An excerpt.");
+                assert_eq!(nutgraf, EXCERPT_NOTE);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    // ==================== extractive_summary ====================
+
+    #[test]
+    fn test_extractive_summary_skips_short_lines() {
+        let text = "\
+Home | News | Tech
+By Jane Doe
+Apple on Tuesday announced a new chip that it says doubles battery life. The company will ship it in every Mac this year. Analysts were surprised by the timing.
+More text follows.";
+        match extractive_summary(text) {
+            Summary::Editorial { lede, nutgraf, .. } => {
+                assert!(lede.starts_with("Apple on Tuesday announced"));
+                assert!(lede.ends_with("Analysts were surprised by the timing."));
+                assert_eq!(nutgraf, AI_DISABLED_NOTE);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            extractive_summary("Too short"),
+            Summary::Insufficient
+        ));
+    }
+
+    #[test]
+    fn test_extractive_summary_caps_length() {
+        let sentence =
+            "This sentence is exactly long enough to count as a real paragraph of text. ";
+        match extractive_summary(&sentence.repeat(10)) {
+            Summary::Editorial { lede, .. } => assert!(lede.chars().count() <= 301),
+            other => panic!("unexpected {:?}", other),
+        }
+        let run_on = "word ".repeat(200);
+        match extractive_summary(&run_on) {
+            Summary::Editorial { lede, .. } => assert!(lede.ends_with('…')),
+            other => panic!("unexpected {:?}", other),
         }
     }
 
@@ -286,15 +763,102 @@ NUTGRAF: This matters because performance gains change the industry.";
 
         let result = s.parse_smart_brevity(text).unwrap();
         match result {
-            Summary::Editorial { lede, nutgraf, quote } => {
+            Summary::Editorial {
+                lede,
+                nutgraf,
+                quotes,
+                reaction,
+                by_the_numbers,
+            } => {
                 assert_eq!(lede, "Apple announced a new chip.");
                 assert!(nutgraf.contains("performance gains"));
-                assert!(quote.unwrap().contains("This is huge"));
+                assert_eq!(quotes.len(), 1);
+                assert_eq!(quotes[0].text, "This is huge");
+                assert_eq!(quotes[0].speaker.as_deref(), Some("John Doe"));
+                assert!(reaction.is_none());
+                assert!(by_the_numbers.is_none());
             }
             _ => panic!("Expected Editorial"),
         }
     }
 
+    #[test]
+    fn test_parse_reaction() {
+        let s = summarizer();
+        let text = "\
+FORMAT: EDITORIAL
+LEDE: Apple announced a new chip.
+NUTGRAF: This matters because performance gains change the industry.
+REACTION: Commenters doubt the benchmarks.";
+
+        match s.parse_smart_brevity(text).unwrap() {
+            Summary::Editorial { reaction, .. } => {
+                assert_eq!(
+                    reaction.as_deref(),
+                    Some("Commenters doubt the benchmarks.")
+                );
+            }
+            _ => panic!("Expected Editorial"),
+        }
+    }
+
+    #[test]
+    fn test_parse_by_the_numbers() {
+        let s = summarizer();
+        let text = "\
+FORMAT: EDITORIAL
+LEDE: Intel agreed to buy a chip designer.
+NUTGRAF: The deal doubles its GPU team.
+BY_THE_NUMBERS: $3B acquisition, 12,000 layoffs, 40% YoY";
+
+        match s.parse_smart_brevity(text).unwrap() {
+            Summary::Editorial { by_the_numbers, .. } => {
+                assert_eq!(
+                    by_the_numbers.as_deref(),
+                    Some("$3B acquisition, 12,000 layoffs, 40% YoY")
+                );
+            }
+            _ => panic!("Expected Editorial"),
+        }
+    }
+
+    #[test]
+    fn test_parse_several_quotes() {
+        let text = "\
+FORMAT: EDITORIAL
+QUOTE: \"One\" -- A
+QUOTE: \"Two\" -- B
+QUOTE: \"Three\" -- C
+QUOTE: \"Four\" -- D
+LEDE: Something happened.
+NUTGRAF: It matters for reasons.";
+
+        match parse_fields(text) {
+            Summary::Editorial { quotes, .. } => {
+                let texts: Vec<&str> = quotes.iter().map(|q| q.text.as_str()).collect();
+                assert_eq!(texts, ["One", "Two", "Three"]);
+            }
+            _ => panic!("Expected Editorial"),
+        }
+    }
+
+    #[test]
+    fn test_old_story_files_have_one_quote() {
+        let old: Summary = serde_json::from_str(
+            r#"{"Editorial": {"lede": "A", "nutgraf": "B", "quote": "\"Wow\" -- Tim"}}"#,
+        )
+        .unwrap();
+        match &old {
+            Summary::Editorial { quotes, .. } => {
+                assert_eq!(quotes.len(), 1);
+                assert_eq!(quotes[0].speaker.as_deref(), Some("Tim"));
+            }
+            _ => panic!("Expected Editorial"),
+        }
+        let json = serde_json::to_string(&old).unwrap();
+        assert!(json.contains(r#""quotes":[{"text":"Wow","speaker":"Tim"}]"#));
+    }
+
     #[test]
     fn test_parse_editorial_without_quote() {
         let s = summarizer();
@@ -305,7 +869,7 @@ NUTGRAF: It matters for reasons.";
 
         let result = s.parse_smart_brevity(text).unwrap();
         match result {
-            Summary::Editorial { quote, .. } => assert!(quote.is_none()),
+            Summary::Editorial { quotes, .. } => assert!(quotes.is_empty()),
             _ => panic!("Expected Editorial"),
         }
     }
@@ -347,12 +911,19 @@ QUOTE: \"Best watch ever\" -- Tim Cook";
 
         let result = s.parse_smart_brevity(text).unwrap();
         match result {
-            Summary::Product { the_product, cost, availability, platforms, quote } => {
+            Summary::Product {
+                the_product,
+                cost,
+                availability,
+                platforms,
+                quotes,
+                ..
+            } => {
                 assert!(the_product.contains("smartwatch"));
                 assert_eq!(cost, "$399.");
                 assert!(availability.contains("March"));
                 assert!(platforms.contains("iOS"));
-                assert!(quote.unwrap().contains("Best watch ever"));
+                assert!(quotes[0].text.contains("Best watch ever"));
             }
             _ => panic!("Expected Product"),
         }
@@ -367,11 +938,17 @@ THE_PRODUCT: A new app for task management.";
 
         let result = s.parse_smart_brevity(text).unwrap();
         match result {
-            Summary::Product { cost, availability, platforms, quote, .. } => {
+            Summary::Product {
+                cost,
+                availability,
+                platforms,
+                quotes,
+                ..
+            } => {
                 assert!(cost.is_empty());
                 assert!(availability.is_empty());
                 assert!(platforms.is_empty());
-                assert!(quote.is_none());
+                assert!(quotes.is_empty());
             }
             _ => panic!("Expected Product"),
         }
@@ -432,4 +1009,75 @@ NUTGRAF: Revenue beat expectations.";
         let result = s.parse_smart_brevity(text).unwrap();
         assert!(matches!(result, Summary::Failed(_)));
     }
+
+    // ==================== validation ====================
+
+    fn editorial(lede: &str, nutgraf: &str) -> Summary {
+        Summary::Editorial {
+            lede: lede.to_string(),
+            nutgraf: nutgraf.to_string(),
+            quotes: vec![Quote::parse("\"Read the article\" -- Tim Cook")],
+            reaction: None,
+            by_the_numbers: None,
+        }
+    }
+
+    #[test]
+    fn test_problems_passes_good_summary() {
+        let summary = editorial(
+            "Apple reported record iPhone revenue for the holiday quarter.",
+            "Growth in China offset a weaker Mac lineup.",
+        );
+        assert!(problems(&summary, Some("Apple Q1 earnings beat estimates")).is_empty());
+        assert!(problems(&Summary::Insufficient, None).is_empty());
+    }
+
+    #[test]
+    fn test_problems_flags_each_rule() {
+        let (max_lede_words, _) = SummaryLength::Standard.word_limits();
+        let long = vec!["word"; max_lede_words + 1].join(" ");
+        let found = problems(&editorial(&long, "Fine."), None);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].starts_with("LEDE is 46 words long"));
+
+        let found = problems(
+            &editorial("Apple Q1 earnings beat estimates: the company...", "Fine."),
+            Some("Apple Q1 Earnings Beat Estimates"),
+        );
+        assert!(found[0].contains("repeats the headline"));
+
+        let found = problems(
+            &editorial("Apple made money.", "The article says it matters."),
+            None,
+        );
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("\"the article\""));
+
+        let found = problems(
+            &Summary::Failed("Editorial format missing required fields".to_string()),
+            None,
+        );
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn test_word_limits_follow_length() {
+        let nutgraf = vec!["word"; 80].join(" ");
+        let summary = editorial("Apple made money.", &nutgraf);
+        assert!(problems(&summary, None).is_empty());
+        assert!(problems_at(&summary, None, SummaryLength::DeepDive).is_empty());
+        let found = problems_at(&summary, None, SummaryLength::OneLiner);
+        assert_eq!(found, ["NUTGRAF is 80 words long; keep it under 50 words."]);
+    }
+
+    #[test]
+    fn test_correction_prompt_lists_problems() {
+        let prompt = correction_prompt(
+            "Summarize this.",
+            "LEDE: x\n",
+            &["LEDE is too long.".to_string()],
+        );
+        assert!(prompt.starts_with("Summarize this.\n\nYour previous reply was:\n\nLEDE: x\n"));
+        assert!(prompt.contains("- LEDE is too long.\n"));
+    }
 }
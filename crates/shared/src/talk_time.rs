@@ -0,0 +1,146 @@
+//! Talk-time estimates, to see whether a rundown fits the show.
+//!
+//! Each story gets a flat `minutes_per_story` when one is set, and
+//! otherwise a minute for every `words_per_minute` words of its summary
+//! (at least one minute). The briefing's contents list gives each topic's
+//! subtotal and the grand total, compared with `show_minutes` when the
+//! show's length is known.
+
+use serde::Deserialize;
+
+use crate::clustering::{Story, Topic};
+use crate::summarizer::Summary;
+
+/// The `[briefing.talk_time]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TalkTimeSettings {
+    /// Show the estimates in the briefing
+    pub enabled: bool,
+    /// Minutes for every story; estimated from the summary when unset
+    pub minutes_per_story: Option<u32>,
+    /// Summary words that take a minute to talk through
+    pub words_per_minute: u32,
+    /// Length of the show, to say how far over or under the total is
+    pub show_minutes: Option<u32>,
+}
+
+impl Default for TalkTimeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            minutes_per_story: None,
+            words_per_minute: 20,
+            show_minutes: None,
+        }
+    }
+}
+
+impl TalkTimeSettings {
+    /// Estimated minutes of discussion for `story`.
+    pub fn story_minutes(&self, story: &Story) -> u32 {
+        if let Some(minutes) = self.minutes_per_story {
+            return minutes;
+        }
+        let words = summary_words(&story.summary) as u32;
+        words.div_ceil(self.words_per_minute.max(1)).max(1)
+    }
+
+    pub fn topic_minutes(&self, topic: &Topic) -> u32 {
+        topic.stories.iter().map(|s| self.story_minutes(s)).sum()
+    }
+
+    /// The grand total line, e.g. "2 h 10 min, 10 min over the 2 h show".
+    pub fn total(&self, topics: &[Topic]) -> String {
+        let total: u32 = topics.iter().map(|t| self.topic_minutes(t)).sum();
+        let Some(show) = self.show_minutes else {
+            return duration(total);
+        };
+        let fit = match total.cmp(&show) {
+            std::cmp::Ordering::Greater => format!("{} over", duration(total - show)),
+            std::cmp::Ordering::Less => format!("{} under", duration(show - total)),
+            std::cmp::Ordering::Equal => "exactly".to_string(),
+        };
+        format!("{}, {} the {} show", duration(total), fit, duration(show))
+    }
+}
+
+/// Words in the parts of a summary the hosts talk through.
+fn summary_words(summary: &Summary) -> usize {
+    let parts: Vec<&str> = match summary {
+        Summary::Editorial {
+            lede,
+            nutgraf,
+            quotes,
+            reaction,
+            by_the_numbers,
+        } => [lede.as_str(), nutgraf.as_str()]
+            .into_iter()
+            .chain(quotes.iter().map(|q| q.text.as_str()))
+            .chain(reaction.as_deref())
+            .chain(by_the_numbers.as_deref())
+            .collect(),
+        Summary::Product {
+            the_product,
+            cost,
+            availability,
+            platforms,
+            quotes,
+            reaction,
+        } => [
+            the_product.as_str(),
+            cost.as_str(),
+            availability.as_str(),
+            platforms.as_str(),
+        ]
+        .into_iter()
+        .chain(quotes.iter().map(|q| q.text.as_str()))
+        .chain(reaction.as_deref())
+        .collect(),
+        Summary::Insufficient | Summary::Failed(_) => Vec::new(),
+    };
+    parts.iter().map(|p| p.split_whitespace().count()).sum()
+}
+
+/// Minutes as "45 min", "2 h" or "1 h 5 min".
+pub fn duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::story;
+
+    #[test]
+    fn test_talk_time_estimates() {
+        let topic = Topic {
+            title: "Apple".to_string(),
+            stories: vec![
+                story("A story").with_lede(&"word ".repeat(45), "").build(),
+                story("A story").with_lede("Short.", "").build(),
+            ],
+            connections: None,
+            notes: None,
+            rationale: None,
+        };
+        let mut settings = TalkTimeSettings::default();
+
+        // 45 words take three minutes, and no story takes less than one
+        assert_eq!(settings.topic_minutes(&topic), 4);
+        assert_eq!(settings.total(std::slice::from_ref(&topic)), "4 min");
+
+        settings.minutes_per_story = Some(65);
+        settings.show_minutes = Some(120);
+        assert_eq!(settings.topic_minutes(&topic), 130);
+        assert_eq!(
+            settings.total(&[topic]),
+            "2 h 10 min, 10 min over the 2 h show"
+        );
+        assert_eq!(duration(45), "45 min");
+    }
+}
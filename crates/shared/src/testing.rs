@@ -0,0 +1,359 @@
+//! A small HTTP server for the end-to-end tests, standing in for Raindrop,
+//! the LLM API, WebDAV and the article sites.
+//!
+//! Each connection gets one response from the handler and is then closed.
+//! Every request is recorded so a test can check what the tools sent.
+//! [`Sandbox`] runs the binaries with a throwaway home directory, so they
+//! read its config.toml and write their files there. [`story`] builds the
+//! stories unit tests feed the briefing code.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::clustering::Story;
+use crate::summarizer::Summary;
+
+/// Start a test story called `title`, at `https://example.com/{title}`
+/// with no summary. The `with_*` methods set the rest.
+pub fn story(title: &str) -> StoryBuilder {
+    StoryBuilder(Story {
+        title: title.to_string(),
+        url: format!("https://example.com/{}", title),
+        ..Default::default()
+    })
+}
+
+/// A [`Story`] being put together for a test, from [`story`].
+pub struct StoryBuilder(Story);
+
+impl StoryBuilder {
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.0.url = url.to_string();
+        self
+    }
+
+    pub fn with_created(mut self, created: &str) -> Self {
+        self.0.created = created.to_string();
+        self
+    }
+
+    pub fn with_summary(mut self, summary: Summary) -> Self {
+        self.0.summary = summary;
+        self
+    }
+
+    /// An editorial summary of `lede` and `nutgraf`.
+    pub fn with_lede(self, lede: &str, nutgraf: &str) -> Self {
+        self.with_summary(Summary::Editorial {
+            lede: lede.to_string(),
+            nutgraf: nutgraf.to_string(),
+            quotes: Vec::new(),
+            reaction: None,
+            by_the_numbers: None,
+        })
+    }
+
+    pub fn with_hosts(mut self, hosts: &[&str]) -> Self {
+        self.0.hosts = hosts.iter().map(|h| h.to_string()).collect();
+        self
+    }
+
+    pub fn starred(mut self) -> Self {
+        self.0.starred = true;
+        self
+    }
+
+    pub fn build(self) -> Story {
+        self.0
+    }
+}
+
+/// A temporary home directory with a config.toml and a Documents folder.
+pub struct Sandbox {
+    home: tempfile::TempDir,
+}
+
+impl Sandbox {
+    pub fn new(config_toml: &str) -> Self {
+        let home = tempfile::tempdir().expect("temp home");
+        let config = home.path().join(".config");
+        fs::create_dir_all(config.join("podcast-briefing")).unwrap();
+        fs::create_dir_all(home.path().join("Documents")).unwrap();
+        fs::write(
+            config.join("user-dirs.dirs"),
+            "XDG_DOCUMENTS_DIR=\"$HOME/Documents\"\n",
+        )
+        .unwrap();
+        fs::write(
+            config
+                .join("podcast-briefing")
+                .join(crate::settings::SETTINGS_FILE),
+            config_toml,
+        )
+        .unwrap();
+        Self { home }
+    }
+
+    pub fn home(&self) -> &Path {
+        self.home.path()
+    }
+
+    /// Where the org drafts and finished briefings are written.
+    pub fn documents(&self) -> PathBuf {
+        self.home().join("Documents")
+    }
+
+    /// The files in Documents with `extension`, sorted.
+    pub fn documents_with(&self, extension: &str) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(self.documents())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().and_then(OsStr::to_str) == Some(extension))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// `program` with nothing from the test's environment but `PATH`, run
+    /// in the sandbox home on UTC.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut command = Command::new(program);
+        command
+            .env_clear()
+            .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+            .env("HOME", self.home())
+            .env("TZ", "UTC")
+            .current_dir(self.home());
+        command
+    }
+}
+
+/// A request as the mock server received it.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// Path and query, e.g. `/raindrops/0?page=0`
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// The first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn body_text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: String,
+    pub body: Vec<u8>,
+    /// Sent after `Content-Type`
+    pub headers: Vec<(String, String)>,
+}
+
+impl Response {
+    pub fn json(value: &serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json".to_string(),
+            body: value.to_string().into_bytes(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn html(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8".to_string(),
+            body: body.as_bytes().to_vec(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// An empty response with `status`, e.g. 201 for a WebDAV upload.
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/plain".to_string(),
+            body: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// This response with the extra header `name: value`.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+type Handler = dyn Fn(&Request) -> Response + Send + Sync;
+
+/// A server on a free local port, running until the test process exits.
+pub struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    pub fn start(handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let url = format!("http://{}", listener.local_addr().expect("local address"));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let recorded = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let handler = handler.clone();
+                let recorded = recorded.clone();
+                thread::spawn(move || serve(stream, &*handler, &recorded));
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// A WebDAV folder in memory: PUT stores a file that GET and HEAD then
+    /// serve, with its SHA-256 as the ETag.
+    pub fn webdav() -> Self {
+        let files: Mutex<HashMap<String, Vec<u8>>> = Mutex::new(HashMap::new());
+        Self::start(move |request| {
+            let mut files = files.lock().unwrap();
+            match request.method.as_str() {
+                "PUT" => {
+                    files.insert(request.path.clone(), request.body.clone());
+                    Response::status(201)
+                }
+                "GET" | "HEAD" => match files.get(&request.path) {
+                    Some(body) => Response {
+                        status: 200,
+                        content_type: "application/octet-stream".to_string(),
+                        body: body.clone(),
+                        headers: vec![(
+                            "ETag".to_string(),
+                            format!("\"{}\"", crate::manifest::sha256(body)),
+                        )],
+                    },
+                    None => Response::status(404),
+                },
+                _ => Response::status(405),
+            }
+        })
+    }
+
+    /// Base URL without a trailing slash, e.g. `http://127.0.0.1:40000`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Every request so far, in the order they arrived.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The requests with `method`, e.g. the uploads to a WebDAV server.
+    pub fn requests_for(&self, method: &str) -> Vec<Request> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.method == method)
+            .collect()
+    }
+}
+
+fn serve(stream: TcpStream, handler: &Handler, recorded: &Mutex<Vec<Request>>) {
+    let Some(request) = read_request(&stream) else {
+        return;
+    };
+    let response = handler(&request);
+    let head_only = request.method == "HEAD";
+    recorded.lock().unwrap().push(request);
+
+    let mut stream = stream;
+    let extra: String = response
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        extra,
+        response.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    // A HEAD response has the length of the body it leaves out
+    if !head_only {
+        let _ = stream.write_all(&response.body);
+    }
+    let _ = stream.flush();
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        304 => "Not Modified",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Status",
+    }
+}
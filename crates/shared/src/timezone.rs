@@ -0,0 +1,150 @@
+//! Time zones by IANA name (`America/Los_Angeles`), for shows that record
+//! somewhere other than where the tools run.
+//!
+//! Zones are chrono-tz's [`Tz`], built from the tz database it bundles, so
+//! they don't depend on the system's zoneinfo files. Its times show the
+//! zone's abbreviation, e.g. `PDT`, with `Display` and `%Z`.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, LocalResult, NaiveDateTime, Offset, TimeDelta, TimeZone, Utc};
+pub use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+/// The zone called `name`.
+pub fn named(name: &str) -> Result<Tz> {
+    name.parse().map_err(|_| {
+        anyhow!(
+            "Unknown time zone '{}'; use a name like America/Los_Angeles",
+            name
+        )
+    })
+}
+
+/// This machine's zone: `TZ` if it names one, otherwise the system's
+/// setting. UTC, with a warning, if neither can be made out.
+pub fn local() -> Tz {
+    static LOCAL: OnceLock<Tz> = OnceLock::new();
+    *LOCAL.get_or_init(|| {
+        let from_env = std::env::var("TZ")
+            .ok()
+            .and_then(|tz| named(tz_name(&tz)).ok());
+        let from_system = || {
+            iana_time_zone::get_timezone()
+                .ok()
+                .and_then(|name| named(&name).ok())
+        };
+        from_env.or_else(from_system).unwrap_or_else(|| {
+            tracing::warn!("Can't tell this machine's time zone; using UTC");
+            Tz::UTC
+        })
+    })
+}
+
+/// The zone name in a `TZ` value such as `:America/New_York` or
+/// `/usr/share/zoneinfo/America/New_York`.
+fn tz_name(tz: &str) -> &str {
+    let tz = tz.strip_prefix(':').unwrap_or(tz);
+    tz.split_once("zoneinfo/").map_or(tz, |(_, name)| name)
+}
+
+/// Reading the clock in a zone.
+pub trait ZoneExt {
+    /// The time here now.
+    fn now(self) -> DateTime<Tz>;
+
+    /// The wall-clock time `local` here. When the clocks go back the
+    /// earlier of the two readings wins; an hour they skip is read with the
+    /// offset from before, so it lands after the change.
+    fn at(self, local: NaiveDateTime) -> DateTime<Tz>;
+}
+
+impl ZoneExt for Tz {
+    fn now(self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self)
+    }
+
+    fn at(self, local: NaiveDateTime) -> DateTime<Tz> {
+        match self.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time,
+            LocalResult::None => {
+                let before = self
+                    .offset_from_utc_datetime(&(local - TimeDelta::days(1)))
+                    .fix();
+                self.from_utc_datetime(&(local - before))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_named_zone() {
+        let zone = named("America/Los_Angeles").unwrap();
+        assert_eq!(zone.name(), "America/Los_Angeles");
+
+        // 6pm Sunday 1 February in California is 2am Monday UTC
+        let end = zone.with_ymd_and_hms(2026, 2, 1, 18, 0, 0).unwrap();
+        assert_eq!(end.with_timezone(&Utc), utc(2026, 2, 2, 2));
+        assert_eq!(end.format("%-l%P %Z").to_string(), "6pm PST");
+        let summer = zone.with_ymd_and_hms(2026, 7, 5, 18, 0, 0).unwrap();
+        assert_eq!(summer.with_timezone(&Utc), utc(2026, 7, 6, 1));
+        assert_eq!(summer.offset().to_string(), "PDT");
+
+        let sydney = named("Australia/Sydney").unwrap();
+        assert_eq!(
+            utc(2026, 1, 15, 0)
+                .with_timezone(&sydney)
+                .offset()
+                .fix()
+                .local_minus_utc(),
+            11 * 3600
+        );
+
+        assert!(named("../etc/passwd").is_err());
+        assert!(named("America/Nowhere").is_err());
+    }
+
+    #[test]
+    fn test_clock_changes() {
+        let zone = named("America/Los_Angeles").unwrap();
+        let local = |day, hour| {
+            NaiveDate::from_ymd_opt(2026, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 30, 0)
+                .unwrap()
+        };
+        // 2:30 on 8 March never happens; it's read as 3:30 PDT
+        assert!(zone.from_local_datetime(&local(8, 2)).single().is_none());
+        assert_eq!(zone.at(local(8, 2)).naive_local(), local(8, 3));
+        // A day's 24 hours of wall clock span 23 real ones
+        let hours = zone.at(local(9, 0)) - zone.at(local(8, 0));
+        assert_eq!(hours.num_hours(), 23);
+
+        // 1:30 on 1 November happens twice; PDT's comes first
+        let twice = NaiveDate::from_ymd_opt(2026, 11, 1)
+            .unwrap()
+            .and_hms_opt(1, 30, 0)
+            .unwrap();
+        let LocalResult::Ambiguous(first, second) = zone.from_local_datetime(&twice) else {
+            panic!("1:30 should be ambiguous");
+        };
+        assert_eq!(first.offset().to_string(), "PDT");
+        assert_eq!(second.offset().to_string(), "PST");
+        assert_eq!(zone.at(twice), first);
+    }
+
+    #[test]
+    fn test_tz_name() {
+        assert_eq!(tz_name("America/New_York"), "America/New_York");
+        assert_eq!(tz_name(":Europe/London"), "Europe/London");
+        assert_eq!(tz_name("/usr/share/zoneinfo/Asia/Kolkata"), "Asia/Kolkata");
+    }
+}
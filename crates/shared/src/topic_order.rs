@@ -0,0 +1,190 @@
+//! The order of topics after clustering.
+//!
+//! The model returns topics in no particular order. A show can pin topics
+//! to the top or the bottom by title and choose how the rest are sorted:
+//!
+//! ```toml
+//! [[shows]]
+//! slug = "mbw"
+//! # ...
+//! topic_order = { first = ["Apple"], last = ["In Other News"], rest = "size" }
+//! ```
+//!
+//! A title matches a pinned name that it equals or contains as whole words,
+//! ignoring case, so `Apple` pins "Apple's Vision Pro" but not "Pineapple
+//! Phones". The org draft is written in this order, and the HTML briefing
+//! keeps the draft's order.
+
+use serde::Deserialize;
+
+use crate::clustering::Topic;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TopicOrder {
+    /// Topics to put first, in this order
+    pub first: Vec<String>,
+    /// Topics to put last, in this order
+    pub last: Vec<String>,
+    /// How the topics in between are sorted
+    pub rest: RestOrder,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestOrder {
+    /// As clustering returned them
+    #[default]
+    Clustered,
+    /// By title
+    Alphabetical,
+    /// Most stories first
+    Size,
+}
+
+impl TopicOrder {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Sort `topics` by these rules. Topics that tie keep their order.
+    pub fn apply(&self, topics: &mut [Topic]) {
+        if self.is_default() {
+            return;
+        }
+        // Pinned-first by list position, then the rest, then pinned-last
+        let rank = |topic: &Topic| -> (u8, usize) {
+            if let Some(i) = position(&self.first, &topic.title) {
+                (0, i)
+            } else if let Some(i) = position(&self.last, &topic.title) {
+                (2, i)
+            } else {
+                (1, 0)
+            }
+        };
+        match self.rest {
+            RestOrder::Clustered => topics.sort_by_key(rank),
+            RestOrder::Alphabetical => {
+                topics.sort_by_cached_key(|t| (rank(t), t.title.to_lowercase()))
+            }
+            RestOrder::Size => {
+                topics.sort_by_key(|t| (rank(t), std::cmp::Reverse(t.stories.len())))
+            }
+        }
+    }
+}
+
+/// The first of `names` that `title` matches.
+fn position(names: &[String], title: &str) -> Option<usize> {
+    let words = words_of(title);
+    names.iter().position(|name| {
+        let name = words_of(name);
+        !name.is_empty() && words.windows(name.len()).any(|w| w == name.as_slice())
+    })
+}
+
+/// Lowercase words of `text`, with `'s` dropped so "Apple's" is "apple".
+fn words_of(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’')
+        .map(|w| {
+            let w = w.to_lowercase();
+            let w = w.trim_matches(['\'', '’']);
+            w.strip_suffix("'s")
+                .or_else(|| w.strip_suffix("’s"))
+                .unwrap_or(w)
+                .to_string()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics(titles: &[(&str, usize)]) -> Vec<Topic> {
+        titles
+            .iter()
+            .map(|(title, size)| Topic {
+                title: title.to_string(),
+                stories: (0..*size)
+                    .map(|i| crate::clustering::Story {
+                        title: format!("Story {}", i),
+                        url: String::new(),
+                        summary: crate::Summary::Insufficient,
+                        ..Default::default()
+                    })
+                    .collect(),
+                connections: None,
+                notes: None,
+                rationale: None,
+            })
+            .collect()
+    }
+
+    fn titles(topics: &[Topic]) -> Vec<&str> {
+        topics.iter().map(|t| t.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_pinned_first_and_last() {
+        let order = TopicOrder {
+            first: vec!["Apple".to_string()],
+            last: vec!["Other News".to_string()],
+            rest: RestOrder::Clustered,
+        };
+        let mut topics = topics(&[
+            ("In Other News", 2),
+            ("AI Everywhere", 1),
+            ("Pineapple Phones", 1),
+            ("Apple's Vision Pro", 3),
+            ("Security", 2),
+        ]);
+        order.apply(&mut topics);
+        assert_eq!(
+            titles(&topics),
+            [
+                "Apple's Vision Pro",
+                "AI Everywhere",
+                "Pineapple Phones",
+                "Security",
+                "In Other News"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rest_by_size_or_title() {
+        let list = [("Security", 1), ("AI", 3), ("Apple", 2), ("Chips", 3)];
+        let mut by_size = topics(&list);
+        TopicOrder {
+            rest: RestOrder::Size,
+            ..Default::default()
+        }
+        .apply(&mut by_size);
+        assert_eq!(titles(&by_size), ["AI", "Chips", "Apple", "Security"]);
+
+        let mut by_title = topics(&list);
+        TopicOrder {
+            first: vec!["security".to_string()],
+            rest: RestOrder::Alphabetical,
+            ..Default::default()
+        }
+        .apply(&mut by_title);
+        assert_eq!(titles(&by_title), ["Security", "AI", "Apple", "Chips"]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let order: TopicOrder = toml::from_str(
+            r#"
+            first = ["Apple"]
+            last = ["In Other News"]
+            rest = "alphabetical"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(order.rest, RestOrder::Alphabetical);
+        assert_eq!(order.last, ["In Other News"]);
+    }
+}
@@ -0,0 +1,190 @@
+//! English translations of non-English articles (`[briefing] translate =
+//! true`).
+//!
+//! collect-stories runs this between extraction and summarization: each
+//! article that [`crate::language`] found not to be English is translated
+//! by the model, and the translation replaces its text, so the summary,
+//! quote and clustering all work from English. The article keeps its
+//! original language and is marked as translated, which the briefing shows
+//! as "translated from German" beside the headline.
+
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::extractor::ArticleContent;
+use crate::llm::LlmClient;
+use crate::progress::{PauseWatch, Progress, ProgressEvent, Stage};
+use crate::prompts::{Prompt, PromptKind};
+use crate::usage::UsageTracker;
+
+/// The summarizer reads no more than this, so there's no point translating
+/// the rest.
+const MAX_CHARS: usize = 10_000;
+
+const TRANSLATE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Attempts per article before summarizing it untranslated.
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct Translator {
+    llm: LlmClient,
+    prompt: Prompt,
+    progress: Progress,
+}
+
+impl Translator {
+    pub fn new() -> Result<Self> {
+        Ok(Translator {
+            llm: LlmClient::from_env(TRANSLATE_TIMEOUT)?,
+            prompt: Prompt::builtin(PromptKind::Translate),
+            progress: crate::progress::console(),
+        })
+    }
+
+    /// Record token usage for these requests into `usage`.
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.llm = self.llm.with_usage(usage);
+        self
+    }
+
+    /// Use `prompt` (usually loaded for the current show) instead of the
+    /// built-in template.
+    pub fn with_prompt(mut self, prompt: Prompt) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Translate every article in `articles` that [`needs_translation`].
+    /// Returns the URLs of the ones translated.
+    pub async fn translate_all<'a>(
+        &self,
+        articles: impl IntoIterator<Item = (&'a String, &'a mut ArticleContent)>,
+    ) -> Vec<String> {
+        let wanted: Vec<(&String, &mut ArticleContent)> = articles
+            .into_iter()
+            .filter(|(_, article)| needs_translation(article))
+            .collect();
+        if wanted.is_empty() {
+            return Vec::new();
+        }
+
+        let stage = Stage::Translate;
+        self.progress.report(&ProgressEvent::StageStarted {
+            stage,
+            total: wanted.len(),
+        });
+        let queued = Arc::new(AtomicUsize::new(wanted.len()));
+        let _watch = PauseWatch::start(
+            self.llm.rate_limiter().clone(),
+            self.progress.clone(),
+            stage,
+            queued.clone(),
+        );
+        let translated: Vec<Option<String>> = stream::iter(wanted)
+            .map(|(url, article)| {
+                let queued = queued.clone();
+                async move {
+                    let translation = self.translate(url, article).await;
+                    queued.fetch_sub(1, Ordering::Relaxed);
+                    let text = translation?;
+                    article.text = text;
+                    article.translated = true;
+                    Some(url.clone())
+                }
+            })
+            .buffer_unordered(crate::rate_limit::MAX_CONCURRENCY)
+            .collect()
+            .await;
+        self.progress
+            .report(&ProgressEvent::StageFinished { stage });
+        translated.into_iter().flatten().collect()
+    }
+
+    async fn translate(&self, url: &str, article: &ArticleContent) -> Option<String> {
+        let language = crate::language::name(article.language.as_deref()?);
+        let prompt = self.prompt.render(&[
+            ("language", language.as_str()),
+            ("article", truncate(&article.text, MAX_CHARS)),
+        ]);
+        for attempt in 0..MAX_ATTEMPTS {
+            match self.llm.complete("translate", &prompt, 4096).await {
+                Ok(reply) => {
+                    let text = reply.trim();
+                    if text.is_empty() {
+                        tracing::warn!("Empty translation of {}", url);
+                        return None;
+                    }
+                    return Some(text.to_string());
+                }
+                Err(e) if attempt + 1 < MAX_ATTEMPTS => {
+                    let backoff = self.llm.retry_delay(attempt);
+                    self.progress.report(&ProgressEvent::Retrying {
+                        stage: Stage::Translate,
+                        attempt: attempt + 1,
+                        wait_secs: backoff.as_secs(),
+                        error: e.to_string(),
+                    });
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    tracing::warn!("No translation of {}: {}", url, e);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Whether `article` is in a language other than English and hasn't been
+/// translated yet.
+pub fn needs_translation(article: &ArticleContent) -> bool {
+    !article.translated && !crate::language::is_english(article.language.as_deref())
+}
+
+/// The first `max` characters of `text`.
+fn truncate(text: &str, max: usize) -> &str {
+    text.char_indices()
+        .nth(max)
+        .map_or(text, |(end, _)| &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(language: Option<&str>, translated: bool) -> ArticleContent {
+        ArticleContent {
+            text: "Apple ruft die Armbänder zurück.".to_string(),
+            published_date: None,
+            final_url: "https://heise.de/a".to_string(),
+            author: None,
+            word_count: 5,
+            image: None,
+            language: language.map(str::to_string),
+            translated,
+            title: None,
+        }
+    }
+
+    #[test]
+    fn test_needs_translation() {
+        assert!(needs_translation(&article(Some("de"), false)));
+        assert!(!needs_translation(&article(Some("de"), true)));
+        assert!(!needs_translation(&article(Some("en"), false)));
+        assert!(!needs_translation(&article(None, false)));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Größe", 3), "Grö");
+        assert_eq!(truncate("Größe", 10), "Größe");
+    }
+}
@@ -0,0 +1,192 @@
+//! Trends: topics that keep coming back week after week.
+//!
+//! Each of this week's topics is compared with the topics of the show's
+//! recent archived briefings. A topic that has appeared for three or more
+//! consecutive weeks, or two weeks running with more stories each time, is
+//! reported, e.g. "Third consecutive week of AI Chip Exports stories". Topic
+//! titles come from clustering and vary a little from week to week, so they
+//! are matched by their words rather than exactly.
+
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+
+use crate::clustering::Topic;
+use crate::models::BriefingData;
+
+/// Briefings further apart than this don't count as consecutive weeks.
+const MAX_GAP_DAYS: i64 = 10;
+
+/// Words ignored when comparing topic titles.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "the", "of", "in", "on", "for", "to", "news",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trend {
+    /// This week's topic title
+    pub topic: String,
+    /// Story counts for each week of the streak, oldest first, ending with
+    /// this week
+    pub counts: Vec<usize>,
+}
+
+impl Trend {
+    pub fn weeks(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Whether every week had more stories than the one before.
+    pub fn rising(&self) -> bool {
+        self.counts.windows(2).all(|w| w[1] > w[0])
+    }
+
+    /// One-line note for the briefing.
+    pub fn note(&self) -> String {
+        let counts: Vec<String> = self.counts.iter().map(|c| c.to_string()).collect();
+        format!(
+            "{} consecutive week of {} stories ({})",
+            ordinal(self.weeks()),
+            self.topic,
+            counts.join(" → ")
+        )
+    }
+}
+
+/// Trends among `topics` given `history`: the show's earlier briefings with
+/// their dates, newest first, starting before `date`.
+pub fn detect(
+    topics: &[Topic],
+    date: NaiveDate,
+    history: &[(NaiveDate, BriefingData)],
+) -> Vec<Trend> {
+    let mut trends = Vec::new();
+    for topic in topics {
+        let words = title_words(&topic.title);
+        let mut counts = vec![topic.stories.len()];
+        let mut last = date;
+        for (past_date, data) in history {
+            if (last - *past_date).num_days() > MAX_GAP_DAYS {
+                break;
+            }
+            let count: usize = data
+                .topics
+                .iter()
+                .filter(|t| similar(&words, &title_words(&t.title)))
+                .map(|t| t.stories.len())
+                .sum();
+            if count == 0 {
+                break;
+            }
+            counts.push(count);
+            last = *past_date;
+        }
+        counts.reverse();
+
+        let trend = Trend {
+            topic: topic.title.clone(),
+            counts,
+        };
+        if trend.weeks() >= 3 || (trend.weeks() == 2 && trend.rising()) {
+            trends.push(trend);
+        }
+    }
+    // Longest streaks first
+    trends.sort_by_key(|t| std::cmp::Reverse(t.weeks()));
+    trends
+}
+
+fn title_words(title: &str) -> BTreeSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(&w.as_str()))
+        // Crude singular so "Export" matches "Exports"
+        .map(|w| match w.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 => stem.to_string(),
+            _ => w,
+        })
+        .collect()
+}
+
+/// Titles are similar when at least half their combined words are shared.
+fn similar(a: &BTreeSet<String>, b: &BTreeSet<String>) -> bool {
+    let shared = a.intersection(b).count();
+    let total = a.union(b).count();
+    total > 0 && shared * 2 >= total
+}
+
+fn ordinal(n: usize) -> String {
+    const WORDS: &[&str] = &[
+        "First", "Second", "Third", "Fourth", "Fifth", "Sixth", "Seventh", "Eighth", "Ninth",
+        "Tenth",
+    ];
+    WORDS
+        .get(n.wrapping_sub(1))
+        .map(|w| w.to_string())
+        .unwrap_or_else(|| format!("{}th", n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::Story;
+    use crate::models::ShowInfo;
+    use crate::summarizer::Summary;
+
+    fn topic(title: &str, stories: usize) -> Topic {
+        Topic {
+            title: title.to_string(),
+            stories: (0..stories)
+                .map(|i| Story {
+                    title: format!("{} {}", title, i),
+                    url: format!("https://example.com/{}", i),
+                    summary: Summary::Insufficient,
+                    ..Default::default()
+                })
+                .collect(),
+            connections: None,
+            notes: None,
+            rationale: None,
+        }
+    }
+
+    fn week(date: &str, topics: Vec<Topic>) -> (NaiveDate, BriefingData) {
+        (
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            BriefingData::new(ShowInfo::new("TWiT", "twit", "#twit"), topics),
+        )
+    }
+
+    #[test]
+    fn test_detects_streaks_and_rising_topics() {
+        let history = vec![
+            week(
+                "2026-01-25",
+                vec![topic("AI Chip Export Controls", 2), topic("Apple", 1)],
+            ),
+            week("2026-01-18", vec![topic("AI chip exports", 1)]),
+            week("2026-01-11", vec![topic("Apple", 4)]),
+        ];
+        let current = vec![
+            topic("AI Chip Exports", 3),
+            topic("Apple", 3),
+            topic("Space", 1),
+        ];
+        let date = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+
+        let trends = detect(&current, date, &history);
+        assert_eq!(trends.len(), 2);
+        assert_eq!(trends[0].counts, [1, 2, 3]);
+        assert_eq!(
+            trends[0].note(),
+            "Third consecutive week of AI Chip Exports stories (1 → 2 → 3)"
+        );
+        // Two weeks running, with more stories this week
+        assert_eq!(trends[1].topic, "Apple");
+        assert!(trends[1].rising());
+
+        // A skipped week breaks the streak
+        let gap = vec![week("2026-01-11", vec![topic("AI Chip Exports", 5)])];
+        assert!(detect(&current, date, &gap).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Articles collect-stories couldn't fetch.
+//!
+//! At the end of a collection every URL that failed or hit a paywall is
+//! listed with the status the site answered, the error and whether cookies
+//! were sent. A 403 despite cookies usually means a login has expired; a
+//! timeout is worth another try later. The list is also saved under the
+//! story file's name in `failed-fetches/` in the data dir, where `briefing
+//! retry-failed` picks it up to fetch just those URLs again and patch the
+//! story file.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::extractor::FetchFailure;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedFetch {
+    pub url: String,
+    /// The bookmark's title
+    pub title: String,
+    /// HTTP status, if the site answered
+    pub status: Option<u16>,
+    pub error: String,
+    /// Cookies went with the request
+    pub cookies: bool,
+}
+
+impl FailedFetch {
+    pub fn new(url: &str, title: &str, failure: FetchFailure) -> Self {
+        Self {
+            url: url.to_string(),
+            title: title.to_string(),
+            status: failure.status,
+            error: failure.error,
+            cookies: failure.cookies,
+        }
+    }
+}
+
+/// The failures of one collection.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryFile {
+    /// The story file holding the failed stories
+    pub story_file: PathBuf,
+    pub failures: Vec<FailedFetch>,
+}
+
+impl RetryFile {
+    /// Where the retry file for `story_file` goes.
+    pub fn path_for(story_file: &Path) -> PathBuf {
+        crate::config::data_dir()
+            .join("failed-fetches")
+            .join(story_file.file_name().unwrap_or_default())
+    }
+
+    /// The retry file for `story_file`, if its collection had failures.
+    pub fn load(story_file: &Path) -> Result<Option<Self>> {
+        let path = Self::path_for(story_file);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let retry = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(retry))
+    }
+
+    /// Write the file, or delete it when nothing failed.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path_for(&self.story_file);
+        if self.failures.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            return Ok(());
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize failures")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// `failures` as a list for the console, two lines each.
+pub fn report(failures: &[FailedFetch]) -> String {
+    let mut out = String::new();
+    for failure in failures {
+        let status = failure
+            .status
+            .map_or_else(|| "no response".to_string(), |status| status.to_string());
+        let cookies = if failure.cookies {
+            "cookies sent"
+        } else {
+            "no cookies"
+        };
+        out.push_str(&format!(
+            "  ✗ {} · {} · {}\n    {}\n",
+            status, cookies, failure.url, failure.error
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let failures = [
+            FailedFetch {
+                url: "https://news.example/paywalled".to_string(),
+                title: "Paywalled".to_string(),
+                status: Some(403),
+                error: "Access forbidden (403 Forbidden)".to_string(),
+                cookies: true,
+            },
+            FailedFetch {
+                url: "https://slow.example/story".to_string(),
+                title: "Slow".to_string(),
+                status: None,
+                error: "Failed to send HTTP request".to_string(),
+                cookies: false,
+            },
+        ];
+        assert_eq!(
+            report(&failures),
+            "  ✗ 403 · cookies sent · https://news.example/paywalled\n    \
+             Access forbidden (403 Forbidden)\n  \
+             ✗ no response · no cookies · https://slow.example/story\n    \
+             Failed to send HTTP request\n"
+        );
+    }
+}
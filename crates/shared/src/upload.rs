@@ -0,0 +1,560 @@
+//! The `[upload]` section of `config.toml`: the WebDAV folder
+//! prepare-briefing uploads finished briefings to, or the folder in JMAP
+//! file storage at the same path with `method = "jmap"`.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::jmap::Jmap;
+use crate::manifest::RemoteFile;
+
+/// WebDAV login, read from the environment like the API keys.
+pub const USER_ENV_VAR: &str = "WEBDAV_USER";
+pub const PASSWORD_ENV_VAR: &str = "WEBDAV_PASSWORD";
+
+/// What the login variables were called when uploads went to Fastmail only.
+/// They are still read, with a warning, when the new ones aren't set.
+pub const OLD_USER_ENV_VAR: &str = "FASTMAIL_USER";
+pub const OLD_PASSWORD_ENV_VAR: &str = "FASTMAIL_PASSWORD";
+
+/// Tries per request before giving up on a flaky connection.
+const ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry, doubled after each one.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest a request may take, so a stalled upload is retried rather than
+/// waited on forever.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadMethod {
+    /// PUT with the WebDAV login
+    #[default]
+    WebDav,
+    /// JMAP file storage with an API token
+    Jmap,
+}
+
+impl UploadMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            UploadMethod::WebDav => "WebDAV",
+            UploadMethod::Jmap => "JMAP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UploadSettings {
+    /// Base folder URL; each show's files go in `{url}/{slug}/`. Nothing is
+    /// uploaded without it.
+    pub url: Option<String>,
+    pub method: UploadMethod,
+    /// JMAP session URL (default: Fastmail's)
+    pub session: Option<String>,
+}
+
+impl UploadSettings {
+    /// `{url}/{slug}/{file}`, or `None` if uploads are off.
+    pub fn file_url(&self, show_slug: &str, file: &str) -> Option<String> {
+        let base = self.url.as_deref()?.trim_end_matches('/');
+        Some(format!("{}/{}/{}", base, show_slug, file))
+    }
+
+    /// `{url}/{file}`, outside every show's folder, or `None` if uploads
+    /// are off.
+    pub fn root_url(&self, file: &str) -> Option<String> {
+        let base = self.url.as_deref()?.trim_end_matches('/');
+        Some(format!("{}/{}", base, file))
+    }
+}
+
+/// `name` from `env`, or `old`, the name it had before, with a warning to
+/// rename it.
+fn login_var(name: &str, old: &str, env: impl Fn(&str) -> Option<String>) -> Result<String> {
+    if let Some(value) = env(name) {
+        return Ok(value);
+    }
+    match env(old) {
+        Some(value) => {
+            tracing::warn!("{} is deprecated; rename it to {}", old, name);
+            Ok(value)
+        }
+        None => bail!("{} not set in environment", name),
+    }
+}
+
+/// A WebDAV login from [`USER_ENV_VAR`] and [`PASSWORD_ENV_VAR`].
+///
+/// Requests that time out, can't connect or get a 5xx back are retried a
+/// few times with a growing wait, as hotel Wi-Fi tends to need. Folders are
+/// created with MKCOL before the first file goes in them, so a new show's
+/// folder needn't be made by hand.
+pub struct WebDav {
+    client: reqwest::Client,
+    user: String,
+    password: String,
+}
+
+impl WebDav {
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str, old: &str| login_var(name, old, |v| std::env::var(v).ok());
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                .build()
+                .context("Failed to create HTTP client")?,
+            user: var(USER_ENV_VAR, OLD_USER_ENV_VAR)?,
+            password: var(PASSWORD_ENV_VAR, OLD_PASSWORD_ENV_VAR)?,
+        })
+    }
+
+    /// Upload `body` to `url`. Folders are only made when the server says
+    /// the one it goes in is missing, since some servers refuse MKCOL on
+    /// folders that are there already. WebDAV says 409 for that; Fastmail
+    /// says 404.
+    pub async fn put(&self, url: &str, body: Vec<u8>) -> Result<()> {
+        let put = || async {
+            self.send(|| self.client.put(url).body(body.clone()))
+                .await
+                .with_context(|| format!("Failed to upload {}", url))
+        };
+        let mut response = put().await?;
+        if is_missing_folder(response.status()) {
+            self.make_folders(url).await?;
+            response = put().await?;
+        }
+        if !response.status().is_success() {
+            bail!("Upload of {} failed: HTTP {}", url, response.status());
+        }
+        Ok(())
+    }
+
+    /// [`put`](Self::put), then check the server has every byte of `body`,
+    /// returning what it says about its copy.
+    pub async fn upload(&self, url: &str, body: Vec<u8>) -> Result<RemoteFile> {
+        let expected = body.len() as u64;
+        self.put(url, body).await?;
+        check_upload(url, expected, self.stat(url).await?)
+    }
+
+    /// The size of the file at `url`, or `None` if there isn't one.
+    pub async fn size(&self, url: &str) -> Result<Option<u64>> {
+        Ok(self.stat(url).await?.map(|file| file.size))
+    }
+
+    /// The size and ETag of the file at `url`, or `None` if there isn't one.
+    /// Uses the HEAD Content-Length, downloading the file if the server
+    /// leaves it out.
+    pub async fn stat(&self, url: &str) -> Result<Option<RemoteFile>> {
+        let etag = |response: &reqwest::Response| {
+            response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let response = self
+            .send(|| self.client.head(url))
+            .await
+            .with_context(|| format!("Failed to reach {}", url))?;
+        match response.status() {
+            status if status.is_success() => {}
+            reqwest::StatusCode::NOT_FOUND => return Ok(None),
+            status => bail!("Checking {} failed: HTTP {}", url, status),
+        }
+        let length = response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok()?.parse().ok());
+        if let Some(size) = length {
+            return Ok(Some(RemoteFile {
+                size,
+                version: etag(&response),
+            }));
+        }
+        let response = self
+            .send(|| self.client.get(url))
+            .await
+            .with_context(|| format!("Failed to download {}", url))?;
+        if !response.status().is_success() {
+            bail!("Checking {} failed: HTTP {}", url, response.status());
+        }
+        let version = etag(&response);
+        let body = response
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to download {}", url))?;
+        Ok(Some(RemoteFile {
+            size: body.len() as u64,
+            version,
+        }))
+    }
+
+    /// Whether the server has a file at `url`.
+    pub async fn exists(&self, url: &str) -> Result<bool> {
+        let response = self
+            .send(|| self.client.head(url))
+            .await
+            .with_context(|| format!("Failed to reach {}", url))?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => bail!("Checking {} failed: HTTP {}", url, status),
+        }
+    }
+
+    /// Create the folder holding `url` with MKCOL, and the folders above it
+    /// that are missing too. The server answers 405 for one that's there
+    /// already.
+    async fn make_folders(&self, url: &str) -> Result<()> {
+        let Some(parent) = parent_url(url) else {
+            return Ok(());
+        };
+        let mkcol = reqwest::Method::from_bytes(b"MKCOL").expect("valid method");
+        let make = |folder: String| {
+            let mkcol = mkcol.clone();
+            async move {
+                let response = self
+                    .send(|| self.client.request(mkcol.clone(), &folder))
+                    .await
+                    .with_context(|| format!("Failed to create {}", folder))?;
+                anyhow::Ok((folder, response.status()))
+            }
+        };
+        // Go up until a folder can be made (or already exists), then back
+        // down through the ones that couldn't for want of a parent
+        let mut missing = Vec::new();
+        let mut folder = Some(parent);
+        while let Some(url) = folder {
+            let (url, status) = make(url).await?;
+            match status {
+                status if is_missing_folder(status) => {
+                    folder = parent_url(&url);
+                    missing.push(url);
+                }
+                // 405: it's there already
+                status if status.is_success() || status.as_u16() == 405 => break,
+                status => bail!("Creating {} failed: HTTP {}", url, status),
+            }
+        }
+        for url in missing.into_iter().rev() {
+            let (url, status) = make(url).await?;
+            // Another upload may have just made it
+            if !(status.is_success() || status.as_u16() == 405) {
+                bail!("Creating {} failed: HTTP {}", url, status);
+            }
+        }
+        Ok(())
+    }
+
+    /// Send the request `build` makes with the login, retrying timeouts,
+    /// connection errors and 5xx responses. The last response is returned
+    /// whatever its status.
+    async fn send(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut delay = RETRY_DELAY;
+        for _ in 1..ATTEMPTS {
+            match build()
+                .basic_auth(&self.user, Some(&self.password))
+                .send()
+                .await
+            {
+                Ok(response) if !response.status().is_server_error() => return Ok(response),
+                Err(e) if !(e.is_timeout() || e.is_connect()) => return Err(e),
+                _ => {}
+            }
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+        build()
+            .basic_auth(&self.user, Some(&self.password))
+            .send()
+            .await
+    }
+}
+
+/// Whether `status` says the folder a request went to isn't there.
+fn is_missing_folder(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::CONFLICT || status == reqwest::StatusCode::NOT_FOUND
+}
+
+/// The folder holding `url`, if it's below the server's root.
+fn parent_url(url: &str) -> Option<String> {
+    let (parent, _) = url.trim_end_matches('/').rsplit_once('/')?;
+    let (_, path) = parent.split_once("://")?;
+    path.contains('/').then(|| format!("{}/", parent))
+}
+
+/// Where `[upload]` sends the files: a WebDAV login or a JMAP session.
+pub enum Uploader {
+    WebDav(WebDav),
+    Jmap(Jmap),
+}
+
+impl Uploader {
+    /// Log in the way `settings` says, with the credentials from the
+    /// environment.
+    pub async fn connect(settings: &UploadSettings) -> Result<Self> {
+        match settings.method {
+            UploadMethod::WebDav => Ok(Uploader::WebDav(WebDav::from_env()?)),
+            UploadMethod::Jmap => Ok(Uploader::Jmap(
+                Jmap::connect(
+                    settings
+                        .session
+                        .as_deref()
+                        .unwrap_or(crate::jmap::DEFAULT_SESSION_URL),
+                )
+                .await?,
+            )),
+        }
+    }
+
+    pub async fn put(&self, url: &str, body: Vec<u8>) -> Result<()> {
+        match self {
+            Uploader::WebDav(dav) => dav.put(url, body).await,
+            Uploader::Jmap(jmap) => jmap.put(&path_of(url)?, body, content_type(url)).await,
+        }
+    }
+
+    /// [`put`](Self::put), then check the server has every byte of `body`,
+    /// returning what it says about its copy.
+    pub async fn upload(&self, url: &str, body: Vec<u8>) -> Result<RemoteFile> {
+        match self {
+            Uploader::WebDav(dav) => dav.upload(url, body).await,
+            Uploader::Jmap(jmap) => {
+                let expected = body.len() as u64;
+                let path = path_of(url)?;
+                jmap.put(&path, body, content_type(url)).await?;
+                check_upload(url, expected, jmap.stat(&path).await?)
+            }
+        }
+    }
+
+    /// The size and version of the file at `url`, or `None` if there isn't
+    /// one.
+    pub async fn stat(&self, url: &str) -> Result<Option<RemoteFile>> {
+        match self {
+            Uploader::WebDav(dav) => dav.stat(url).await,
+            Uploader::Jmap(jmap) => jmap.stat(&path_of(url)?).await,
+        }
+    }
+
+    /// Whether the server has a file at `url`.
+    pub async fn exists(&self, url: &str) -> Result<bool> {
+        match self {
+            Uploader::WebDav(dav) => dav.exists(url).await,
+            Uploader::Jmap(jmap) => Ok(jmap.size(&path_of(url)?).await?.is_some()),
+        }
+    }
+}
+
+/// The server's copy of `url` after an upload of `expected` bytes, as long
+/// as it has all of them.
+fn check_upload(url: &str, expected: u64, remote: Option<RemoteFile>) -> Result<RemoteFile> {
+    match remote {
+        Some(file) if file.size == expected => Ok(file),
+        Some(file) => bail!(
+            "Upload of {} is incomplete: the server has {} of {} bytes",
+            url,
+            file.size,
+            expected
+        ),
+        None => bail!("Upload of {} is missing from the server", url),
+    }
+}
+
+/// The file path in `url`, e.g. `/Briefings/twit/index.html`.
+fn path_of(url: &str) -> Result<String> {
+    let url = url::Url::parse(url).with_context(|| format!("Invalid upload URL {}", url))?;
+    Ok(urlencoding::decode(url.path())
+        .map(|path| path.into_owned())
+        .unwrap_or_else(|_| url.path().to_string()))
+}
+
+fn content_type(url: &str) -> &'static str {
+    match url.rsplit('.').next() {
+        Some("html") => "text/html; charset=utf-8",
+        Some("csv") => "text/csv; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockServer, Response};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn webdav() -> WebDav {
+        WebDav {
+            client: reqwest::Client::new(),
+            user: "leo".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_login_var_falls_back_to_old_name() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let both = env(&[("WEBDAV_USER", "new"), ("FASTMAIL_USER", "old")]);
+        assert_eq!(
+            login_var(USER_ENV_VAR, OLD_USER_ENV_VAR, both).unwrap(),
+            "new"
+        );
+        let old = env(&[("FASTMAIL_USER", "old")]);
+        assert_eq!(
+            login_var(USER_ENV_VAR, OLD_USER_ENV_VAR, old).unwrap(),
+            "old"
+        );
+        let err = login_var(USER_ENV_VAR, OLD_USER_ENV_VAR, env(&[])).unwrap_err();
+        assert_eq!(err.to_string(), "WEBDAV_USER not set in environment");
+    }
+
+    #[tokio::test]
+    async fn test_upload_retries_server_errors() {
+        let puts = AtomicUsize::new(0);
+        let server = MockServer::start(move |request| match request.method.as_str() {
+            "PUT" if puts.fetch_add(1, Ordering::SeqCst) == 0 => Response::status(503),
+            "PUT" => Response::status(201),
+            _ => Response::html("<html>"),
+        });
+        let url = format!("{}/show/index.html", server.url());
+        webdav().upload(&url, b"<html>".to_vec()).await.unwrap();
+        let methods: Vec<String> = server.requests().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, ["PUT", "PUT", "HEAD"]);
+    }
+
+    #[tokio::test]
+    async fn test_upload_checks_the_size() {
+        let server = MockServer::webdav();
+        let url = format!("{}/show/links.csv", server.url());
+        let file = webdav().upload(&url, b"a,b\n".to_vec()).await.unwrap();
+        assert_eq!(file.size, 4);
+        assert!(file.version.is_some());
+        assert_eq!(webdav().size(&url).await.unwrap(), Some(4));
+
+        // A server that kept only part of the file
+        let server = MockServer::start(|request| match request.method.as_str() {
+            "PUT" => Response::status(201),
+            _ => Response::html("a,"),
+        });
+        let url = format!("{}/show/links.csv", server.url());
+        let error = webdav().upload(&url, b"a,b\n".to_vec()).await.unwrap_err();
+        assert!(error.to_string().contains("has 2 of 4 bytes"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_put_creates_missing_folders() {
+        // WebDAV's answer for a missing folder, then Fastmail's
+        for missing in [409, 404] {
+            put_creates_missing_folders(missing).await;
+        }
+    }
+
+    async fn put_creates_missing_folders(missing: u16) {
+        let folders = Mutex::new(vec!["/Briefings/".to_string()]);
+        let server = MockServer::start(move |request| {
+            let mut folders = folders.lock().unwrap();
+            let parent = parent_url(&format!("http://dav{}", request.path)).unwrap_or_default();
+            let parent = parent.trim_start_matches("http://dav");
+            match request.method.as_str() {
+                _ if folders.iter().any(|f| f == &request.path) => Response::status(405),
+                _ if !folders.iter().any(|f| f == parent) => Response::status(missing),
+                "MKCOL" => {
+                    folders.push(request.path.clone());
+                    Response::status(201)
+                }
+                _ => Response::status(201),
+            }
+        });
+        let dav = webdav();
+        let url = format!("{}/Briefings/twit/2026/index.html", server.url());
+        dav.put(&url, b"<html>".to_vec()).await.unwrap();
+        // Once it's there, files just go in
+        let url = format!("{}/Briefings/twit/2026/links.csv", server.url());
+        dav.put(&url, b"a,b".to_vec()).await.unwrap();
+        let requests: Vec<String> = server
+            .requests()
+            .into_iter()
+            .map(|r| format!("{} {}", r.method, r.path))
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                "PUT /Briefings/twit/2026/index.html",
+                "MKCOL /Briefings/twit/2026/",
+                "MKCOL /Briefings/twit/",
+                "MKCOL /Briefings/twit/2026/",
+                "PUT /Briefings/twit/2026/index.html",
+                "PUT /Briefings/twit/2026/links.csv",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_into_existing_folder_skips_mkcol() {
+        // A server that refuses MKCOL outright, as some do for folders the
+        // login can write to but not create
+        let server = MockServer::start(|request| match request.method.as_str() {
+            "MKCOL" => Response::status(403),
+            _ => Response::status(201),
+        });
+        let url = format!("{}/Briefings/twit/index.html", server.url());
+        webdav().put(&url, b"<html>".to_vec()).await.unwrap();
+        let methods: Vec<String> = server.requests().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, ["PUT"]);
+    }
+
+    #[test]
+    fn test_parent_and_path() {
+        assert_eq!(
+            parent_url("https://dav.example.com/Briefings/twit/index.html").as_deref(),
+            Some("https://dav.example.com/Briefings/twit/")
+        );
+        assert_eq!(parent_url("https://dav.example.com/index.html"), None);
+        assert_eq!(
+            path_of("https://myfiles.fastmail.com/My%20Briefings/twit/index.html").unwrap(),
+            "/My Briefings/twit/index.html"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start(|_| Response::status(401));
+        let url = format!("{}/show/index.html", server.url());
+        assert!(webdav().put(&url, Vec::new()).await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_file_url() {
+        let settings: UploadSettings =
+            toml::from_str("url = \"https://dav.example.com/Briefings/\"").unwrap();
+        assert_eq!(
+            settings.file_url("show", "index.html").as_deref(),
+            Some("https://dav.example.com/Briefings/show/index.html")
+        );
+        assert_eq!(
+            UploadSettings::default().file_url("show", "index.html"),
+            None
+        );
+        assert_eq!(
+            settings.root_url("index.html").as_deref(),
+            Some("https://dav.example.com/Briefings/index.html")
+        );
+    }
+}
@@ -0,0 +1,315 @@
+//! Token usage accounting.
+//!
+//! A `UsageTracker` is shared by every `LlmClient` in a run. Each API reply's
+//! `usage` block is recorded with the request's purpose, so the end of a run
+//! can print what it cost and append the run to a cumulative JSON-lines log.
+//! Prices and the runaway-prompt threshold come from the `[usage]` section of
+//! `config.toml`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const USAGE_LOG_FILE: &str = "usage.jsonl";
+
+/// The `[usage]` section of `config.toml`. Prices are USD per million tokens.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct UsageSettings {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    /// Requests with more input tokens than this are called out in the report
+    pub warn_input_tokens: u64,
+    /// Append each run's totals to `usage.jsonl` in the data dir
+    pub log: bool,
+}
+
+impl Default for UsageSettings {
+    fn default() -> Self {
+        Self {
+            input_per_mtok: 1.0,
+            output_per_mtok: 5.0,
+            warn_input_tokens: 20_000,
+            log: true,
+        }
+    }
+}
+
+impl UsageSettings {
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_mtok + output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub purpose: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenTotals {
+    pub requests: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// One line of the cumulative usage log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageLogEntry {
+    pub timestamp: String,
+    pub label: String,
+    pub by_purpose: BTreeMap<String, TokenTotals>,
+    pub cost_usd: f64,
+}
+
+/// Cheap to clone; all clones share the same records.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    records: Arc<Mutex<Vec<UsageRecord>>>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, purpose: &str, model: &str, input_tokens: u64, output_tokens: u64) {
+        if let Ok(mut records) = self.records.lock() {
+            records.push(UsageRecord {
+                purpose: purpose.to_string(),
+                model: model.to_string(),
+                input_tokens,
+                output_tokens,
+            });
+        }
+    }
+
+    /// Record the `usage` block of a messages API response, if it has one.
+    pub fn record_response(&self, purpose: &str, model: &str, response: &serde_json::Value) {
+        let usage = &response["usage"];
+        let input = usage["input_tokens"].as_u64();
+        let output = usage["output_tokens"].as_u64();
+        if input.is_some() || output.is_some() {
+            self.record(purpose, model, input.unwrap_or(0), output.unwrap_or(0));
+        }
+    }
+
+    pub fn records(&self) -> Vec<UsageRecord> {
+        self.records.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    pub fn by_purpose(&self) -> BTreeMap<String, TokenTotals> {
+        let mut totals: BTreeMap<String, TokenTotals> = BTreeMap::new();
+        for record in self.records() {
+            let entry = totals.entry(record.purpose).or_default();
+            entry.requests += 1;
+            entry.input_tokens += record.input_tokens;
+            entry.output_tokens += record.output_tokens;
+        }
+        totals
+    }
+
+    pub fn total(&self) -> TokenTotals {
+        self.by_purpose()
+            .values()
+            .fold(TokenTotals::default(), |acc, t| TokenTotals {
+                requests: acc.requests + t.requests,
+                input_tokens: acc.input_tokens + t.input_tokens,
+                output_tokens: acc.output_tokens + t.output_tokens,
+            })
+    }
+
+    /// Human-readable per-run summary, or `None` if nothing was recorded.
+    pub fn report(&self, settings: &UsageSettings) -> Option<String> {
+        let by_purpose = self.by_purpose();
+        if by_purpose.is_empty() {
+            return None;
+        }
+
+        let mut out = String::new();
+        for (purpose, t) in &by_purpose {
+            let _ = writeln!(
+                out,
+                "  {:<12} {:>4} request(s) {:>9} in {:>8} out  ${:.4}",
+                purpose,
+                t.requests,
+                t.input_tokens,
+                t.output_tokens,
+                settings.cost(t.input_tokens, t.output_tokens)
+            );
+        }
+        let total = self.total();
+        let _ = write!(
+            out,
+            "  {:<12} {:>4} request(s) {:>9} in {:>8} out  ${:.4}",
+            "total",
+            total.requests,
+            total.input_tokens,
+            total.output_tokens,
+            settings.cost(total.input_tokens, total.output_tokens)
+        );
+
+        let runaway: Vec<_> = self
+            .records()
+            .into_iter()
+            .filter(|r| r.input_tokens > settings.warn_input_tokens)
+            .collect();
+        if !runaway.is_empty() {
+            let _ = write!(
+                out,
+                "\n  ⚠ {} request(s) over {} input tokens (largest: {} for {})",
+                runaway.len(),
+                settings.warn_input_tokens,
+                runaway.iter().map(|r| r.input_tokens).max().unwrap_or(0),
+                runaway
+                    .iter()
+                    .max_by_key(|r| r.input_tokens)
+                    .map(|r| r.purpose.as_str())
+                    .unwrap_or("")
+            );
+        }
+
+        Some(out)
+    }
+
+    /// Append this run's totals to the cumulative log at `path`.
+    pub fn append_to_log(&self, path: &Path, label: &str, settings: &UsageSettings) -> Result<()> {
+        let total = self.total();
+        let entry = UsageLogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            label: label.to_string(),
+            by_purpose: self.by_purpose(),
+            cost_usd: settings.cost(total.input_tokens, total.output_tokens),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Average cost of one story (its summary plus a share of clustering) over
+/// every run in the usage log at `path`, or `None` without any history.
+pub fn cost_per_story(path: &Path) -> Option<f64> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let (cost, stories) = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<UsageLogEntry>(line).ok())
+        .fold((0.0, 0), |(cost, stories), entry| {
+            let summarized = entry.by_purpose.get("summarize").map_or(0, |t| t.requests);
+            (cost + entry.cost_usd, stories + summarized)
+        });
+    (stories > 0).then(|| cost / stories as f64)
+}
+
+/// Default location of the cumulative usage log.
+pub fn default_log_path() -> PathBuf {
+    crate::config::data_dir().join(USAGE_LOG_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_response_reads_usage_block() {
+        let tracker = UsageTracker::new();
+        tracker.record_response(
+            "summarize",
+            "glm-5.2",
+            &json!({"usage": {"input_tokens": 1200, "output_tokens": 150}}),
+        );
+        tracker.record_response("summarize", "glm-5.2", &json!({"content": []}));
+
+        let records = tracker.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].input_tokens, 1200);
+    }
+
+    #[test]
+    fn test_totals_and_cost() {
+        let tracker = UsageTracker::new();
+        let clone = tracker.clone();
+        tracker.record("summarize", "m", 1_000_000, 0);
+        clone.record("summarize", "m", 0, 100_000);
+        clone.record("cluster", "m", 500_000, 10_000);
+
+        let by_purpose = tracker.by_purpose();
+        assert_eq!(by_purpose["summarize"].requests, 2);
+        assert_eq!(tracker.total().input_tokens, 1_500_000);
+
+        let settings = UsageSettings::default();
+        let total = tracker.total();
+        let cost = settings.cost(total.input_tokens, total.output_tokens);
+        assert!((cost - (1.5 + 0.55)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_report_flags_runaway_prompts() {
+        let tracker = UsageTracker::new();
+        assert!(tracker.report(&UsageSettings::default()).is_none());
+
+        tracker.record("summarize", "m", 50_000, 100);
+        let report = tracker.report(&UsageSettings::default()).unwrap();
+        assert!(report.contains("total"));
+        assert!(report.contains("over 20000 input tokens"));
+    }
+
+    #[test]
+    fn test_append_to_log() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("usage.jsonl");
+        let tracker = UsageTracker::new();
+        tracker.record("cluster", "m", 10, 5);
+
+        let settings = UsageSettings::default();
+        tracker.append_to_log(&path, "twit", &settings).unwrap();
+        tracker.append_to_log(&path, "twit", &settings).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let entry: UsageLogEntry = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry.label, "twit");
+        assert_eq!(entry.by_purpose["cluster"].output_tokens, 5);
+    }
+
+    #[test]
+    fn test_cost_per_story_averages_log() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("usage.jsonl");
+        assert_eq!(cost_per_story(&path), None);
+
+        let settings = UsageSettings {
+            input_per_mtok: 1_000_000.0,
+            output_per_mtok: 0.0,
+            ..UsageSettings::default()
+        };
+        let tracker = UsageTracker::new();
+        tracker.record("summarize", "m", 1, 0);
+        tracker.record("summarize", "m", 1, 0);
+        tracker.record("cluster", "m", 2, 0);
+        tracker.append_to_log(&path, "twit", &settings).unwrap();
+
+        assert_eq!(cost_per_story(&path), Some(2.0));
+    }
+}